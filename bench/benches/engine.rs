@@ -0,0 +1,137 @@
+// the engine's hot paths, on boards built from fixed seeds or from
+// `from_bombs` so that every run measures the same work. Where an old and a
+// new implementation of something are both kept while one replaces the
+// other, each is benchmarked in the same group under its own name, so that
+// criterion compares them side by side
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use minesweeper::board::{
+    Board, CellPosition, CellState, Layout, MineDistribution, Mines, Stencil,
+};
+use minesweeper::odds::enumerate;
+use minesweeper::solver::{solves, CellOrder};
+use minesweeper::view::BoardView;
+
+const SEED: u64 = 1;
+
+// an expert board that can be cleared from its corner without guessing
+const EXPERT_SEED: u64 = 4;
+
+const CORNER: CellPosition = CellPosition {
+    row_index: 0,
+    col_index: 0,
+};
+
+// squares of eight cells, alternately marked, to weight the mines with
+fn checkers(size: usize) -> Stencil {
+    let rows: Vec<String> = (0..size)
+        .map(|row| {
+            (0..size)
+                .map(|col| if (row / 8 + col / 8).is_multiple_of(2) { '#' } else { '.' })
+                .collect()
+        })
+        .collect();
+    Stencil::parse(&rows.join("\n"))
+}
+
+fn generation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generate");
+    group.sample_size(10);
+    for size in [16, 100, 1000] {
+        let mines = Mines::Count(size * size / 5);
+        group.bench_with_input(BenchmarkId::new("count", size), &size, |b, &size| {
+            b.iter(|| Board::from_seed(SEED, size, size, mines))
+        });
+
+        let mut layout = Layout::new(mines);
+        layout.distribution = MineDistribution::Stencil(checkers(size));
+        group.bench_with_input(BenchmarkId::new("weighted", size), &size, |b, &size| {
+            b.iter(|| Board::generate(SEED, size, size, &layout))
+        });
+    }
+    group.finish();
+}
+
+// a board without mines, which one clear opens all of
+fn cascade(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cascade");
+    group.sample_size(10);
+    for size in [16, 100, 1000] {
+        let board = Board::from_bombs(vec![vec![false; size]; size]);
+        group.bench_with_input(BenchmarkId::new("clear", size), &board, |b, board| {
+            b.iter_batched(
+                || board.clone(),
+                |mut board| board.clear(CORNER),
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("preview", size), &board, |b, board| {
+            b.iter(|| board.clear_preview(CORNER))
+        });
+    }
+    group.finish();
+}
+
+// the worst case for the check, where the only closed cell is the last one
+fn is_won(c: &mut Criterion) {
+    let size = 100;
+    let mut bombs = vec![vec![false; size]; size];
+    bombs[size - 1][size - 1] = true;
+    let mut board = Board::from_bombs(bombs);
+    let _ = board.clear(CORNER);
+
+    c.bench_function("is_won/100", |b| b.iter(|| black_box(&board).is_won()));
+}
+
+fn rendering(c: &mut Criterion) {
+    let board = Board::from_seed(SEED, 1000, 1000, Mines::Count(200_000));
+    let mut group = c.benchmark_group("render");
+    group.sample_size(10);
+    group.bench_function("display/1000", |b| b.iter(|| black_box(&board).to_string()));
+    group.bench_function("view/1000", |b| {
+        b.iter(|| BoardView::of(black_box(&board)).to_string())
+    });
+    group.finish();
+}
+
+fn solver(c: &mut Criterion) {
+    let board = Board::from_seed(EXPERT_SEED, 16, 30, Mines::Count(99));
+    assert!(solves(&board, CORNER), "The expert board should be solvable from its corner");
+
+    c.bench_function("solves/expert", |b| b.iter(|| solves(black_box(&board), CORNER)));
+}
+
+// an expert board with every ninth safe cell open, as far into a game as
+// the dense guessing part, numbers everywhere and a frontier in a few parts
+fn scattered(seed: u64) -> Board {
+    let mut board = Board::from_seed(seed, 16, 30, Mines::Count(99));
+    let safe: Vec<CellPosition> = board
+        .rows()
+        .flatten()
+        .filter(|cell| matches!(cell.state, CellState::Safe { .. }))
+        .map(|cell| cell.position)
+        .collect();
+    for at in safe.into_iter().step_by(9) {
+        let _ = board.clear(at);
+    }
+    board
+}
+
+// the search for frontier layouts, deciding the cells in each order, over
+// the whole frontier at once and a part at a time
+fn frontier(c: &mut Criterion) {
+    let boards: Vec<Board> = (0..4).map(scattered).collect();
+    let mut group = c.benchmark_group("frontier");
+    group.sample_size(10);
+    for order in [CellOrder::Declaration, CellOrder::MostConstrained, CellOrder::Breadth] {
+        for (name, split) in [("whole", false), ("parts", true)] {
+            let id = BenchmarkId::new(format!("{order:?}"), name);
+            group.bench_with_input(id, &boards, |b, boards| {
+                b.iter(|| boards.iter().map(|board| enumerate(board, order, split, None)).count())
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, generation, cascade, is_won, rendering, solver, frontier);
+criterion_main!(benches);
@@ -0,0 +1,14 @@
+// drives the functions a browser frontend would call, from the terminal:
+// `cargo run --example wasm --no-default-features --features wasm`
+use minesweeper::wasm::new_game;
+
+fn main() {
+    let mut game = new_game(42);
+    for command in [
+        r#"{"command": "c 4 4", "now_ms": 0}"#,
+        r#"{"command": "f 0 0", "now_ms": 1500}"#,
+        r#"{"command": "nonsense"}"#,
+    ] {
+        println!("> {command}\n{}", game.apply(command));
+    }
+}
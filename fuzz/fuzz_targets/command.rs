@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// the checks are shared with the tests that replay what this has found
+#[path = "../../tests/fuzz/mod.rs"]
+mod checks;
+
+fuzz_target!(|data: &[u8]| checks::check_command(data));
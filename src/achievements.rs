@@ -0,0 +1,203 @@
+use crate::board::FlagChange;
+use crate::game::{GameOutcome, Move, MoveOutcome, Stats};
+use crate::i18n::{fill, text, Msg};
+use crate::stats::StatsFile;
+
+pub struct Achievement {
+    // how it is kept in the stats file, so it must never change
+    pub id: &'static str,
+    pub name: Msg,
+    pub description: Msg,
+    // whether a finished game earns it
+    pub earned: fn(GameOutcome, &[Move], &Stats) -> bool,
+}
+
+fn won(outcome: GameOutcome) -> bool {
+    matches!(outcome, GameOutcome::Won(_))
+}
+
+// new ones go at the end, so that the list keeps its order
+pub const ACHIEVEMENTS: &[Achievement] = &[
+    Achievement {
+        id: "first_win",
+        name: Msg::AchievementFirstWin,
+        description: Msg::AchievementFirstWinInfo,
+        earned: |outcome, _, _| won(outcome),
+    },
+    Achievement {
+        id: "no_flags",
+        name: Msg::AchievementNoFlags,
+        description: Msg::AchievementNoFlagsInfo,
+        earned: |outcome, moves, _| {
+            won(outcome)
+                && !moves
+                    .iter()
+                    .any(|made| matches!(made.mark(), Some((FlagChange::FlagPlaced, _))))
+        },
+    },
+    Achievement {
+        id: "ninety_nine",
+        name: Msg::AchievementNinetyNine,
+        description: Msg::AchievementNinetyNineInfo,
+        earned: |outcome, _, stats| won(outcome) && stats.mines >= 99,
+    },
+    Achievement {
+        id: "cascade_50",
+        name: Msg::AchievementCascade,
+        description: Msg::AchievementCascadeInfo,
+        earned: |_, moves, _| {
+            moves
+                .iter()
+                .any(|made| matches!(made.outcome, MoveOutcome::Opened(count) if count >= 50))
+        },
+    },
+    Achievement {
+        id: "under_a_minute",
+        name: Msg::AchievementQuick,
+        description: Msg::AchievementQuickInfo,
+        earned: |outcome, _, stats| won(outcome) && stats.elapsed.as_secs() < 60,
+    },
+];
+
+// the achievements a finished game earns for the first time, which are
+// added to `stats`; an aborted or resigned game earns nothing
+pub fn award(
+    stats: &mut StatsFile,
+    outcome: GameOutcome,
+    moves: &[Move],
+) -> Vec<&'static Achievement> {
+    let game_stats = match outcome {
+        GameOutcome::Won(game_stats)
+        | GameOutcome::Lost {
+            stats: game_stats, ..
+        } => game_stats,
+        GameOutcome::Resigned { .. } | GameOutcome::Aborted => return vec![],
+    };
+
+    let mut new = vec![];
+    for achievement in ACHIEVEMENTS {
+        if !stats.achievements.iter().any(|id| id == achievement.id)
+            && (achievement.earned)(outcome, moves, &game_stats)
+        {
+            stats.achievements.push(achievement.id.to_string());
+            new.push(achievement);
+        }
+    }
+    new
+}
+
+pub fn announce(achievement: &Achievement) -> String {
+    fill(
+        Msg::AchievementEarned,
+        &[&text(achievement.name), &text(achievement.description)],
+    )
+}
+
+// every achievement, earned or not, for the `achievements` command
+pub fn list(stats: &StatsFile) -> String {
+    ACHIEVEMENTS
+        .iter()
+        .map(|achievement| {
+            let earned = stats.achievements.iter().any(|id| id == achievement.id);
+            format!(
+                "[{}] {}: {}",
+                if earned { "x" } else { " " },
+                text(achievement.name),
+                text(achievement.description)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{Board, CellPosition};
+    use crate::command::Command;
+    use crate::game::Game;
+    use crate::stats::tests::SetByHand;
+    use std::sync::Arc;
+
+    // `commands` played on `layout`, each `seconds` after the one before
+    fn played(layout: &str, seconds: u64, commands: &[Command]) -> Game {
+        let board: Board = layout.parse().expect("The layout should parse");
+        let clock = Arc::new(SetByHand::default());
+        let mut game = Game::with_clock(board, 1, clock.clone());
+        for (index, command) in commands.iter().enumerate() {
+            clock.set(index as u64 * seconds);
+            game.apply(0, command.clone())
+                .expect("The move should be played");
+        }
+        game
+    }
+
+    fn clear(row: usize, col: usize) -> Command {
+        Command::Clear(CellPosition::new(row, col))
+    }
+
+    fn earned(stats: &mut StatsFile, game: &Game) -> Vec<&'static str> {
+        let outcome = game.outcome().unwrap_or(GameOutcome::Aborted);
+        award(stats, outcome, game.moves())
+            .iter()
+            .map(|achievement| achievement.id)
+            .collect()
+    }
+
+    #[test]
+    fn a_quick_win_without_flags_earns_three_and_only_the_first_time() {
+        let mut stats = StatsFile::default();
+        let game = played("*.\n..", 10, &[clear(1, 1), clear(0, 1), clear(1, 0)]);
+        assert_eq!(
+            earned(&mut stats, &game),
+            ["first_win", "no_flags", "under_a_minute"]
+        );
+        assert_eq!(
+            stats.achievements,
+            ["first_win", "no_flags", "under_a_minute"]
+        );
+
+        // the same again is announced no more
+        assert_eq!(earned(&mut stats, &game), [] as [&str; 0]);
+        assert_eq!(stats.achievements.len(), 3);
+    }
+
+    #[test]
+    fn a_flag_or_a_slow_win_leaves_those_out() {
+        let flag = Command::Flag(CellPosition::new(0, 0));
+        let game = played("*.\n..", 40, &[flag, clear(1, 1), clear(0, 1), clear(1, 0)]);
+        assert_eq!(earned(&mut StatsFile::default(), &game), ["first_win"]);
+    }
+
+    #[test]
+    fn a_big_cascade_counts_even_in_a_lost_game() {
+        // every cell but the two mines and the one between them, from the
+        // far corner
+        let mut rows = vec!["..........".to_string(); 10];
+        rows[0] = "*.*.......".to_string();
+        let game = played(&rows.join("\n"), 1, &[clear(9, 9), clear(0, 0)]);
+        assert!(matches!(game.outcome(), Some(GameOutcome::Lost { .. })));
+        assert!(matches!(game.moves()[0].outcome, MoveOutcome::Opened(97)));
+        assert_eq!(earned(&mut StatsFile::default(), &game), ["cascade_50"]);
+
+        // and nothing for a game given up
+        let mut stats = StatsFile::default();
+        let outcome = GameOutcome::Resigned {
+            progress: 99,
+            stats: game.stats(),
+        };
+        assert!(award(&mut stats, outcome, game.moves()).is_empty());
+        assert!(stats.achievements.is_empty());
+    }
+
+    #[test]
+    fn ninety_nine_mines_won_earns_the_minefield() {
+        let mut layout = vec!["*".repeat(10); 10];
+        layout[9] = "*********.".to_string();
+        let mut stats = StatsFile::default();
+        let game = played(&layout.join("\n"), 1, &[clear(9, 9)]);
+        assert!(earned(&mut stats, &game).contains(&"ninety_nine"));
+        assert!(list(&stats).contains("[x] Minefield"));
+        assert!(list(&stats).contains("[ ] Avalanche"));
+    }
+}
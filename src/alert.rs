@@ -0,0 +1,152 @@
+use crate::config::Config;
+use crate::game::{Game, GameEvent};
+use std::io::{stdout, Write};
+use std::time::Duration;
+
+const BELL: &str = "\x07";
+
+// how many times the mine that ended the game blinks, and how fast
+const FLASHES: usize = 3;
+const FLASH_INTERVAL: Duration = Duration::from_millis(150);
+
+// whether the latest move deserves the bell: a mine going off always does,
+// a win only if asked for
+pub fn wants_bell(events: &[GameEvent], config: &Config) -> bool {
+    config.bell
+        && events.iter().any(|event| match event {
+            GameEvent::MineHit(_) => true,
+            GameEvent::GameWon => config.bell_on_win,
+            _ => false,
+        })
+}
+
+// rings the terminal bell for the latest move, if it deserves it and there
+// is a terminal to hear it
+pub fn ring_bell(game: &Game, config: &Config) {
+    if config.output.bell && wants_bell(game.events(), config) {
+        print!("{BELL}");
+        let _ = stdout().flush();
+    }
+}
+
+// one step of a flash: whether the cell is highlighted, and for how long
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frame {
+    pub highlighted: bool,
+    pub hold: Duration,
+}
+
+// highlighted and plain in turn, ending plain
+pub fn flash_frames(flashes: usize, interval: Duration) -> Vec<Frame> {
+    (0..flashes * 2)
+        .map(|index| Frame {
+            highlighted: index % 2 == 0,
+            hold: interval,
+        })
+        .collect()
+}
+
+// draws each frame and waits it out; the waiting is passed in so that
+// nothing has to really sleep to run a flash
+pub fn play_frames(frames: &[Frame], mut draw: impl FnMut(bool), mut wait: impl FnMut(Duration)) {
+    for frame in frames {
+        draw(frame.highlighted);
+        wait(frame.hold);
+    }
+}
+
+pub fn default_flash() -> Vec<Frame> {
+    flash_frames(FLASHES, FLASH_INTERVAL)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::CellPosition;
+    use crate::output::OutputCapabilities;
+
+    const HIT: GameEvent = GameEvent::MineHit(CellPosition {
+        row_index: 0,
+        col_index: 0,
+    });
+
+    fn config(file: &str) -> Config {
+        let mut config = Config::default();
+        config.apply_file(file).expect("The config should be read");
+        config
+    }
+
+    #[test]
+    fn the_bell_is_rung_for_what_the_config_asks_for() {
+        let defaults = config("");
+        assert!(wants_bell(&[HIT], &defaults));
+        assert!(!wants_bell(&[GameEvent::GameWon], &defaults));
+        assert!(!wants_bell(&[], &defaults));
+
+        let on_win = config("bell_on_win = true\n");
+        assert!(wants_bell(&[GameEvent::GameWon], &on_win));
+        assert!(wants_bell(&[HIT], &on_win));
+
+        // and, turned off, for nothing
+        let off = config("bell = false\nbell_on_win = true\n");
+        assert!(!wants_bell(&[HIT], &off));
+        assert!(!wants_bell(&[GameEvent::GameWon], &off));
+    }
+
+    #[test]
+    fn bells_and_flashes_go_with_a_terminal() {
+        let config = config("flash = false\n");
+        assert!(!config.flash);
+
+        let terminal = OutputCapabilities::of(&config, false, true);
+        assert!(terminal.bell && terminal.clear);
+        // piped, there is no bell, and no redrawing output to flash in
+        let piped = OutputCapabilities::of(&config, false, false);
+        assert!(!piped.bell && !piped.clear);
+        let plain = OutputCapabilities::of(
+            &Config {
+                plain: true,
+                ..config
+            },
+            false,
+            true,
+        );
+        assert!(!plain.bell && !plain.clear);
+    }
+
+    #[test]
+    fn a_flash_alternates_and_ends_plain() {
+        let interval = Duration::from_millis(10);
+        let frames = flash_frames(2, interval);
+        let highlighted: Vec<bool> = frames.iter().map(|frame| frame.highlighted).collect();
+        assert_eq!(highlighted, [true, false, true, false]);
+        assert!(frames.iter().all(|frame| frame.hold == interval));
+        assert_eq!(flash_frames(0, interval), []);
+
+        let frames = default_flash();
+        assert_eq!(frames.len(), FLASHES * 2);
+        assert_eq!(frames.last().map(|frame| frame.highlighted), Some(false));
+    }
+
+    #[test]
+    fn playing_frames_draws_then_waits_for_each() {
+        let mut steps = vec![];
+        let frames = [
+            Frame {
+                highlighted: true,
+                hold: Duration::from_millis(5),
+            },
+            Frame {
+                highlighted: false,
+                hold: Duration::from_millis(7),
+            },
+        ];
+        let log = std::cell::RefCell::new(&mut steps);
+        play_frames(
+            &frames,
+            |highlighted| log.borrow_mut().push(format!("draw {highlighted}")),
+            |hold| log.borrow_mut().push(format!("wait {}", hold.as_millis())),
+        );
+        assert_eq!(steps, ["draw true", "wait 5", "draw false", "wait 7"]);
+    }
+}
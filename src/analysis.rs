@@ -0,0 +1,699 @@
+use crate::board::{Board, Cell, CellPosition, CellState, ClearError, FlagChange, Mines};
+use crate::command::Command;
+use crate::game::{Game, MoveOutcome};
+use crate::i18n::{fill, text, Msg};
+use crate::layout::CellLayout;
+use crate::solver::{deduce, provable, reasoning, Proof};
+use std::time::Duration;
+
+fn is_safe(cell: &Cell) -> bool {
+    matches!(cell.state, CellState::Safe { .. })
+}
+
+fn is_zero(cell: &Cell) -> bool {
+    is_safe(cell) && cell.bombs_around == 0
+}
+
+// the openings (connected patches of zeros) on the board, and which cells
+// clicking them would open: the zeros and the border around them
+fn openings(board: &Board) -> (usize, Vec<Vec<bool>>) {
+    let mut reached = vec![vec![false; board.width()]; board.height()];
+    let mut openings = 0;
+
+    for cell in board.rows().flatten() {
+        let (row, col) = (cell.position.row_index, cell.position.col_index);
+        if !is_zero(cell) || reached[row][col] {
+            continue;
+        }
+
+        openings += 1;
+        reached[row][col] = true;
+        let mut zeros = vec![cell.position];
+        while let Some(zero) = zeros.pop() {
+            for around in board.cells_around(zero) {
+                let at = around.position;
+                if reached[at.row_index][at.col_index] {
+                    continue;
+                }
+                reached[at.row_index][at.col_index] = true;
+                if is_zero(around) {
+                    zeros.push(at);
+                }
+            }
+        }
+    }
+
+    (openings, reached)
+}
+
+// the fewest clicks that clear the board without flagging: one for each
+// opening, which opens its border along with it, plus one for every other
+// safe cell
+pub fn three_bv(board: &Board) -> usize {
+    let (openings, reached) = openings(board);
+    let unreached = |cell: &&Cell| !reached[cell.position.row_index][cell.position.col_index];
+    openings
+        + board
+            .rows()
+            .flatten()
+            .filter(|cell| is_safe(cell))
+            .filter(unreached)
+            .count()
+}
+
+// what a board is like overall, without saying where anything is
+#[derive(Debug, PartialEq)]
+pub struct BoardInfo {
+    pub height: usize,
+    pub width: usize,
+    pub mines: usize,
+    pub zeros: usize,
+    pub openings: usize,
+    pub three_bv: usize,
+}
+
+impl BoardInfo {
+    pub fn of(board: &Board) -> BoardInfo {
+        BoardInfo {
+            height: board.height(),
+            width: board.width(),
+            mines: board.count_bombs(),
+            zeros: board.rows().flatten().filter(|cell| is_zero(cell)).count(),
+            openings: openings(board).0,
+            three_bv: three_bv(board),
+        }
+    }
+
+    pub fn density(&self) -> f64 {
+        self.mines as f64 / (self.height * self.width) as f64
+    }
+}
+
+// `requested` is what the board was generated from, when that is known
+pub fn render_board_info(info: &BoardInfo, requested: Option<Mines>) -> String {
+    let requested = match requested {
+        Some(Mines::Density(density)) => fill(
+            Msg::BoardInfoRequestedDensity,
+            &[&format!("{:.1}", density * 100.0)],
+        ),
+        Some(Mines::Count(count)) => fill(Msg::BoardInfoRequestedCount, &[&count]),
+        None => String::new(),
+    };
+
+    [
+        fill(Msg::BoardInfoSize, &[&info.height, &info.width]),
+        fill(
+            Msg::BoardInfoMines,
+            &[
+                &info.mines,
+                &format!("{:.1}", info.density() * 100.0),
+                &requested,
+            ],
+        ),
+        fill(Msg::BoardInfoZeros, &[&info.zeros, &info.openings]),
+        fill(Msg::BoardInfoThreeBv, &[&info.three_bv]),
+    ]
+    .join("\n")
+}
+
+// how flags count towards a game's clicks, which players differ on
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FlagScoring {
+    // not at all
+    #[default]
+    Ignore,
+    // each flag put on
+    Placements,
+    // each flag put on or taken off, so that changing one's mind costs
+    Toggles,
+}
+
+impl FlagScoring {
+    // as it is written in the config file and the history
+    pub fn name(self) -> &'static str {
+        match self {
+            FlagScoring::Ignore => "ignore",
+            FlagScoring::Placements => "placements",
+            FlagScoring::Toggles => "toggles",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<FlagScoring> {
+        match name {
+            "ignore" => Some(FlagScoring::Ignore),
+            "placements" => Some(FlagScoring::Placements),
+            "toggles" => Some(FlagScoring::Toggles),
+            _ => None,
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            FlagScoring::Ignore => text(Msg::FlagsIgnored),
+            FlagScoring::Placements => text(Msg::FlagsPlacedCounted),
+            FlagScoring::Toggles => text(Msg::FlagTogglesCounted),
+        }
+    }
+}
+
+// how few clicks a game took for its board, counted the way competitive
+// players count them: a clear or chord is a click if it opened anything,
+// however much it opened, and flags count as `FlagScoring` says
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Efficiency {
+    pub three_bv: usize,
+    // the clears and chords that opened at least one cell, undone or not
+    pub clicks: usize,
+    // the flags put on
+    pub flags: usize,
+    // the flags put on and taken off, each time
+    pub toggles: usize,
+    // clears of cells already open, which count as neither
+    pub wasted: usize,
+}
+
+impl Efficiency {
+    pub fn of(game: &Game) -> Self {
+        let (mut clicks, mut flags, mut toggles) = (0, 0, 0);
+        for logged in game.log() {
+            match (&logged.game_move.command, logged.game_move.outcome) {
+                (Command::Clear(_) | Command::Chord(_), MoveOutcome::Opened(opened))
+                | (Command::ClearArea(_), MoveOutcome::AreaOpened { opened, .. })
+                    if opened > 0 =>
+                {
+                    clicks += 1
+                }
+                (Command::Flag(_), MoveOutcome::Marked(FlagChange::FlagPlaced)) => {
+                    flags += 1;
+                    toggles += 1;
+                }
+                (Command::Flag(_), MoveOutcome::Marked(FlagChange::FlagRemoved)) => toggles += 1,
+                _ => (),
+            }
+        }
+
+        Efficiency {
+            three_bv: game.stats().three_bv,
+            clicks,
+            flags,
+            toggles,
+            wasted: game.wasted_clicks(),
+        }
+    }
+
+    pub fn total_clicks(&self, scoring: FlagScoring) -> usize {
+        match scoring {
+            FlagScoring::Ignore => self.clicks,
+            FlagScoring::Placements => self.clicks + self.flags,
+            FlagScoring::Toggles => self.clicks + self.toggles,
+        }
+    }
+
+    // 3BV over clicks, as a percentage; None before anything was opened
+    pub fn percent(&self, scoring: FlagScoring) -> Option<f64> {
+        let clicks = self.total_clicks(scoring);
+        (clicks > 0).then(|| self.three_bv as f64 * 100.0 / clicks as f64)
+    }
+
+    // None for a game over before any time passed
+    pub fn per_second(&self, elapsed: Duration) -> Option<f64> {
+        let seconds = elapsed.as_secs_f64();
+        (seconds > 0.0).then(|| self.three_bv as f64 / seconds)
+    }
+}
+
+// naming how the flags were counted, since the numbers mean different
+// things depending on it
+pub fn render_efficiency(
+    efficiency: &Efficiency,
+    elapsed: Duration,
+    scoring: FlagScoring,
+) -> String {
+    let two_places = |value: Option<f64>| value.map_or(String::from("-"), |v| format!("{v:.2}"));
+    let percent = efficiency
+        .percent(scoring)
+        .map_or(String::from("-"), |v| format!("{v:.0}"));
+    fill(
+        Msg::EfficiencyLine,
+        &[
+            &efficiency.three_bv,
+            &efficiency.total_clicks(scoring),
+            &efficiency.wasted,
+            &percent,
+            &two_places(efficiency.per_second(elapsed)),
+            &scoring.describe(),
+        ],
+    )
+}
+
+// how many closed cells the numbers prove safe, and how many cells they
+// prove to be mines that aren't flagged yet, without saying which; cheap
+// enough for every turn, as it only deduces and never weighs up odds
+#[derive(Debug, PartialEq)]
+pub struct Remaining {
+    pub safe: usize,
+    pub mines: usize,
+}
+
+impl Remaining {
+    pub fn of(board: &Board) -> Remaining {
+        let proven = provable(board);
+        let unflagged = |&&position: &&CellPosition| {
+            board
+                .get_cell(position)
+                .is_some_and(|cell| !matches!(cell.state, CellState::Bomb { flagged: true, .. }))
+        };
+        Remaining {
+            safe: proven.safe.len(),
+            mines: proven.mines.iter().filter(unflagged).count(),
+        }
+    }
+}
+
+pub fn render_remaining(remaining: &Remaining) -> String {
+    fill(Msg::Remaining, &[&remaining.safe, &remaining.mines])
+}
+
+fn cell_list(cells: &[CellPosition]) -> String {
+    cells
+        .iter()
+        .map(CellPosition::to_string)
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+// one step of the solver's reasoning in words: what the numbers it comes
+// from say, and what follows; `proofs` all come from the same numbers
+pub fn render_proof(proofs: &[Proof]) -> String {
+    let Some(proof) = proofs.first() else {
+        return String::new();
+    };
+    let deduction = &proof.deduction;
+    let premise = match &proof.constraints[..] {
+        [number] if deduction.mine && number.known.is_empty() => fill(
+            Msg::TraceOnlyRoom,
+            &[&number.at, &number.shows, &cell_list(&number.cells)],
+        ),
+        [number] if deduction.mine => fill(
+            Msg::TraceOnlyRoomKnown,
+            &[
+                &number.at,
+                &number.shows,
+                &cell_list(&number.known),
+                &number.known.len(),
+                &cell_list(&number.cells),
+            ],
+        ),
+        [number] if number.known.is_empty() => fill(Msg::TraceShowsZero, &[&number.at]),
+        [number] => fill(
+            Msg::TraceAccounted,
+            &[&number.at, &number.shows, &cell_list(&number.known)],
+        ),
+        [smaller, larger] => {
+            let rest: Vec<CellPosition> = larger
+                .cells
+                .iter()
+                .copied()
+                .filter(|cell| !smaller.cells.contains(cell))
+                .collect();
+            fill(
+                Msg::TraceSubset,
+                &[
+                    &smaller.at,
+                    &smaller.mines,
+                    &cell_list(&smaller.cells),
+                    &larger.at,
+                    &larger.mines,
+                    &cell_list(&larger.cells),
+                    &(larger.mines - smaller.mines),
+                    &cell_list(&rest),
+                ],
+            )
+        }
+        _ => panic!("A proof should come from one number or from two"),
+    };
+    let cells: Vec<CellPosition> = proofs
+        .iter()
+        .map(|proof| proof.deduction.position)
+        .collect();
+    let conclusion = match (deduction.mine, &cells[..]) {
+        (true, [cell]) => fill(Msg::TraceMine, &[cell]),
+        (false, [cell]) => fill(Msg::TraceSafe, &[cell]),
+        (true, _) => fill(Msg::TraceMines, &[&cell_list(&cells)]),
+        (false, _) => fill(Msg::TraceSafeCells, &[&cell_list(&cells)]),
+    };
+    format!("{premise}; {conclusion}")
+}
+
+// the cell the solver would see to next, and with `why` each step of how
+// it got there
+pub fn render_hint(board: &Board, why: bool) -> String {
+    let Some((next, proofs)) = reasoning(board) else {
+        return text(Msg::NoHint).to_string();
+    };
+    let position = next.deduction.position;
+    let mut out = match next.deduction.mine {
+        true => fill(Msg::HintMine, &[&position]),
+        false => fill(Msg::HintSafe, &[&position]),
+    };
+    if why {
+        // the cells one number settles at once go on one line
+        for proofs in proofs.chunk_by(|first, next| first.constraints == next.constraints) {
+            out += &format!("\n  {}", render_proof(proofs));
+        }
+    }
+    out
+}
+
+fn is_closed(cell: &Cell) -> bool {
+    matches!(
+        cell.state,
+        CellState::Bomb {
+            flagged: false,
+            exploded: false
+        } | CellState::Safe {
+            flagged: false,
+            open: false
+        }
+    )
+}
+
+// an open number, with how many more mines it needs once the flags and
+// exploded mines around it are counted
+fn open_number(board: &Board, cell: &Cell) -> Option<usize> {
+    if !matches!(cell.state, CellState::Safe { open: true, .. }) || cell.bombs_around == 0 {
+        return None;
+    }
+    let marked = board
+        .cells_around(cell.position)
+        .filter(|around| {
+            matches!(
+                around.state,
+                CellState::Bomb { flagged: true, .. }
+                    | CellState::Bomb { exploded: true, .. }
+                    | CellState::Safe { flagged: true, .. }
+            )
+        })
+        .count();
+    Some(usize::from(cell.bombs_around).saturating_sub(marked))
+}
+
+// a connected patch of closed, unflagged cells, and what the open numbers
+// around it say about it
+#[derive(Debug, PartialEq)]
+pub struct Region {
+    pub cells: Vec<CellPosition>,
+    // how many of the cells are next to an open number
+    pub frontier: usize,
+    // the open numbers next to the region
+    pub numbers: usize,
+    // what those numbers still ask for, added up; a number that also
+    // touches another region counts towards both
+    pub mines_needed: usize,
+}
+
+// the closed cells split into regions, joined wherever two cells touch,
+// diagonals included, in the order their first cells appear on the board
+pub fn closed_regions(board: &Board) -> Vec<Region> {
+    let mut region_of = vec![vec![None; board.width()]; board.height()];
+    let mut regions = vec![];
+
+    for cell in board.rows().flatten() {
+        let (row, col) = (cell.position.row_index, cell.position.col_index);
+        if !is_closed(cell) || region_of[row][col].is_some() {
+            continue;
+        }
+
+        let index = regions.len();
+        region_of[row][col] = Some(index);
+        let mut cells = vec![];
+        let mut pending = vec![cell.position];
+        while let Some(position) = pending.pop() {
+            cells.push(position);
+            for around in board.cells_around(position) {
+                let at = around.position;
+                if is_closed(around) && region_of[at.row_index][at.col_index].is_none() {
+                    region_of[at.row_index][at.col_index] = Some(index);
+                    pending.push(at);
+                }
+            }
+        }
+        cells.sort_by_key(|position| (position.row_index, position.col_index));
+
+        let mut numbers: Vec<CellPosition> = vec![];
+        let mut frontier = 0;
+        for &position in &cells {
+            let mut next_to_number = false;
+            for around in board.cells_around(position) {
+                if open_number(board, around).is_some() {
+                    next_to_number = true;
+                    if !numbers.contains(&around.position) {
+                        numbers.push(around.position);
+                    }
+                }
+            }
+            frontier += usize::from(next_to_number);
+        }
+        let mines_needed = numbers
+            .iter()
+            .filter_map(|&position| open_number(board, board.get_cell(position)?))
+            .sum();
+
+        regions.push(Region {
+            cells,
+            frontier,
+            numbers: numbers.len(),
+            mines_needed,
+        });
+    }
+
+    regions
+}
+
+// how the star rating weighs what makes a board hard: the stars each forced
+// guess adds, the stars each cell of the largest frontier adds, and the
+// stars a 3BV of one click per safe cell would add; the total, starting from
+// one star, is rounded and kept within 1 to 5
+const GUESS_STARS: f64 = 1.0;
+const FRONTIER_STARS: f64 = 0.02;
+const THREE_BV_STARS: f64 = 2.0;
+const MAX_STARS: u8 = 5;
+
+// how hard a board is to clear, as found by playing it through: from the
+// first cell of its largest opening, deducing all that the numbers allow,
+// and guessing right whenever nothing follows from them
+#[derive(Debug, PartialEq)]
+pub struct DifficultyReport {
+    pub three_bv: usize,
+    // how many times the player had to guess; the first click isn't counted
+    pub guesses: usize,
+    // the most cells next to open numbers that any one region had at once
+    pub largest_frontier: usize,
+    pub stars: u8,
+}
+
+fn rating(three_bv: usize, guesses: usize, largest_frontier: usize, safe: usize) -> u8 {
+    let stars = 1.0
+        + GUESS_STARS * guesses as f64
+        + FRONTIER_STARS * largest_frontier as f64
+        + THREE_BV_STARS * three_bv as f64 / safe.max(1) as f64;
+    (stars.round() as u8).clamp(1, MAX_STARS)
+}
+
+fn is_closed_safe(cell: &Cell) -> bool {
+    matches!(cell.state, CellState::Safe { open: false, .. })
+}
+
+// where a player who has to guess would do best to: a zero, then a cell
+// next to what is open, then any safe cell at all
+fn best_guess(board: &Board) -> Option<CellPosition> {
+    let safe = || board.rows().flatten().filter(|cell| is_closed_safe(cell));
+    let next_to_open = |cell: &&Cell| {
+        board
+            .cells_around(cell.position)
+            .any(|around| matches!(around.state, CellState::Safe { open: true, .. }))
+    };
+    safe()
+        .find(|cell| cell.bombs_around == 0)
+        .or_else(|| safe().find(next_to_open))
+        .or_else(|| safe().next())
+        .map(|cell| cell.position)
+}
+
+// the first zero of each opening, with how many cells clicking it opens
+fn opening_sizes(board: &Board) -> Vec<(CellPosition, usize)> {
+    let mut seen = vec![vec![false; board.width()]; board.height()];
+    let mut sizes = vec![];
+
+    for cell in board.rows().flatten() {
+        let (row, col) = (cell.position.row_index, cell.position.col_index);
+        if !is_zero(cell) || seen[row][col] {
+            continue;
+        }
+
+        let mut opened = vec![vec![false; board.width()]; board.height()];
+        opened[row][col] = true;
+        seen[row][col] = true;
+        let mut size = 1;
+        let mut zeros = vec![cell.position];
+        while let Some(zero) = zeros.pop() {
+            for around in board.cells_around(zero) {
+                let at = around.position;
+                if opened[at.row_index][at.col_index] {
+                    continue;
+                }
+                opened[at.row_index][at.col_index] = true;
+                size += 1;
+                if is_zero(around) {
+                    seen[at.row_index][at.col_index] = true;
+                    zeros.push(at);
+                }
+            }
+        }
+        sizes.push((cell.position, size));
+    }
+    sizes
+}
+
+// the first cell of the biggest opening, or the best guess without one
+fn best_start(board: &Board) -> Option<CellPosition> {
+    let mut biggest: Option<(CellPosition, usize)> = None;
+    for (position, size) in opening_sizes(board) {
+        if biggest.is_none_or(|(_, most)| size > most) {
+            biggest = Some((position, size));
+        }
+    }
+    biggest
+        .map(|(position, _)| position)
+        .or_else(|| best_guess(board))
+}
+
+fn flagged(board: &Board) -> Vec<CellPosition> {
+    board
+        .rows()
+        .flatten()
+        .filter(|cell| matches!(cell.state, CellState::Bomb { flagged: true, .. }))
+        .map(|cell| cell.position)
+        .collect()
+}
+
+impl Board {
+    pub fn difficulty_report(&self) -> DifficultyReport {
+        let three_bv = three_bv(self);
+        let safe = self.rows().flatten().filter(|cell| is_safe(cell)).count();
+
+        let mut board = self.clone();
+        let mut guesses = 0;
+        let mut largest_frontier = 0;
+        let mut next = best_start(&board);
+        while let Some(position) = next {
+            match board.clear(position) {
+                Ok(()) | Err(ClearError::AlreadyCleared) => (),
+                Err(e) => panic!("Only safe cells should be cleared: {e}"),
+            }
+            if board.is_won() {
+                break;
+            }
+
+            // what the player has to reason about, before the mines they
+            // work out are flagged and drop out of it
+            largest_frontier = closed_regions(&board)
+                .iter()
+                .map(|region| region.frontier)
+                .fold(largest_frontier, usize::max);
+
+            let deductions = loop {
+                let deductions = deduce(&board, &flagged(&board));
+                if deductions.mines.is_empty() {
+                    break deductions;
+                }
+                for mine in deductions.mines {
+                    let _ = board.toggle_flag(mine);
+                }
+            };
+
+            next = match deductions.safe.first() {
+                Some(&safe) => Some(safe),
+                None => {
+                    guesses += 1;
+                    best_guess(&board)
+                }
+            };
+        }
+
+        DifficultyReport {
+            three_bv,
+            guesses,
+            largest_frontier,
+            stars: rating(three_bv, guesses, largest_frontier, safe),
+        }
+    }
+}
+
+pub fn render_difficulty(report: &DifficultyReport) -> String {
+    let stars = MAX_STARS.saturating_sub(report.stars);
+    let stars = "★".repeat(usize::from(report.stars)) + &"☆".repeat(usize::from(stars));
+    [
+        fill(Msg::DifficultyStars, &[&stars, &report.stars, &MAX_STARS]),
+        fill(
+            Msg::DifficultyDetails,
+            &[&report.three_bv, &report.guesses, &report.largest_frontier],
+        ),
+    ]
+    .join("\n")
+}
+
+// A to Z, then a to z, then + for any more than that
+fn region_label(index: usize) -> char {
+    match index {
+        0..=25 => char::from(b'A' + index as u8),
+        26..=51 => char::from(b'a' + (index - 26) as u8),
+        _ => '+',
+    }
+}
+
+// the board in plain text with each closed cell showing its region's
+// label, followed by a line on each region
+pub fn render_analysis(board: &Board) -> String {
+    let regions = closed_regions(board);
+    let closed: usize = regions.iter().map(|region| region.cells.len()).sum();
+
+    let mut labels = vec![vec![None; board.width()]; board.height()];
+    for (index, region) in regions.iter().enumerate() {
+        for position in &region.cells {
+            labels[position.row_index][position.col_index] = Some(region_label(index));
+        }
+    }
+
+    let mut out = fill(Msg::AnalysisClosed, &[&closed, &board.mines_left()]);
+    for row in board.rows() {
+        let cells: Vec<String> = row
+            .iter()
+            .map(
+                |cell| match labels[cell.position.row_index][cell.position.col_index] {
+                    Some(label) => label.to_string(),
+                    None => cell.to_string(),
+                },
+            )
+            .collect();
+        out += &format!("\n{}", CellLayout::text().row(cells));
+    }
+
+    for (index, region) in regions.iter().enumerate() {
+        let label = region_label(index);
+        out += "\n";
+        out += &match region.numbers {
+            0 => fill(Msg::AnalysisIsolatedRegion, &[&label, &region.cells.len()]),
+            _ => fill(
+                Msg::AnalysisRegion,
+                &[
+                    &label,
+                    &region.cells.len(),
+                    &region.frontier,
+                    &region.mines_needed,
+                ],
+            ),
+        };
+    }
+    out
+}
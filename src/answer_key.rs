@@ -0,0 +1,32 @@
+use crate::config::Config;
+use crate::game::Game;
+use crate::i18n::{fill, Msg};
+use crate::output::OutputCapabilities;
+use crate::render::render_key;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// the board a game starts on with everything on it shown, for someone
+// streaming the game to put up once it is over; in the player's theme, but
+// without colour, since it is for a file
+pub fn render_answer_key(game: &Game, code: &str, config: &Config) -> String {
+    let plain = Config {
+        output: OutputCapabilities {
+            color: false,
+            ..config.output
+        },
+        ..config.clone()
+    };
+    let board = game.initial_board();
+    let size = format!("{}x{}", board.height(), board.width());
+    format!(
+        "{}\n{}\n",
+        fill(Msg::AnswerKeyTitle, &[&code, &size, &board.count_bombs()]),
+        render_key(board, &plain)
+    )
+}
+
+pub fn write_answer_key(game: &Game, code: &str, config: &Config, path: &Path) -> io::Result<()> {
+    fs::write(path, render_answer_key(game, code, config))
+}
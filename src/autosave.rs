@@ -0,0 +1,215 @@
+use crate::dirs::data_dir;
+use crate::dump::debug_dump;
+use crate::game::Game;
+use crate::i18n::{fill, text, Msg};
+use crate::save::{deserialize, serialize, Mode, SavedGame};
+use std::fs;
+use std::io::{self, stdin, ErrorKind};
+use std::panic::{self, PanicHookInfo};
+use std::path::PathBuf;
+use std::process;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const AUTOSAVE_FILE: &str = "autosave.txt";
+
+// the exit code of a crash, the same as an uncaught panic's
+const CRASH_EXIT_CODE: i32 = 101;
+
+struct Pending {
+    mode: Mode,
+    code: String,
+    game: Game,
+}
+
+// the game in progress, kept so that an interrupt can write it out with an
+// up to date timer instead of the one from the last move, and a crash can
+// write it out at all
+static PENDING: Mutex<Option<Pending>> = Mutex::new(None);
+
+fn autosave_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join(AUTOSAVE_FILE))
+}
+
+fn write(contents: &str) -> io::Result<()> {
+    let path =
+        autosave_path().ok_or_else(|| io::Error::new(ErrorKind::NotFound, text(Msg::NoDataDir)))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // write to a temporary file first so that a crash mid-write can't leave
+    // a half-written autosave behind
+    let temp = path.with_extension("tmp");
+    fs::write(&temp, contents)?;
+    fs::rename(temp, path)
+}
+
+// called after every move
+pub fn save(mode: Mode, code: &str, game: &Game) {
+    if let Err(e) = write(&serialize(mode, code, game)) {
+        println!("{}", fill(Msg::CouldntAutosave, &[&e]));
+    }
+
+    if let Ok(mut pending) = PENDING.lock() {
+        *pending = Some(Pending {
+            mode,
+            code: code.to_string(),
+            game: game.clone(),
+        });
+    }
+}
+
+// called when a game ends cleanly, so that it isn't offered for resuming
+pub fn discard() {
+    if let Ok(mut pending) = PENDING.lock() {
+        *pending = None;
+    }
+
+    if let Some(path) = autosave_path() {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != ErrorKind::NotFound => {
+                println!("{}", fill(Msg::CouldntRemoveAutosave, &[&e]))
+            }
+            _ => (),
+        }
+    }
+}
+
+fn flush() {
+    let contents = match PENDING.lock() {
+        Ok(pending) => pending
+            .as_ref()
+            .map(|Pending { mode, code, game }| serialize(*mode, code, game)),
+        Err(_) => None,
+    };
+
+    if let Some(contents) = contents {
+        match write(&contents) {
+            Ok(_) => println!("\n{}", text(Msg::GameSaved)),
+            Err(e) => println!("\n{}", fill(Msg::CouldntSave, &[&e])),
+        }
+    }
+}
+
+// looks for a game that was interrupted last time and asks whether to pick it up
+pub fn offer_resume() -> Option<SavedGame> {
+    let contents = fs::read_to_string(autosave_path()?).ok()?;
+
+    let saved = match deserialize(&contents) {
+        Ok(saved) => saved,
+        Err(e) => {
+            println!("{}", fill(Msg::IgnoringAutosave, &[&e]));
+            discard();
+            return None;
+        }
+    };
+
+    println!(
+        "{}",
+        fill(
+            Msg::ResumePrompt,
+            &[&saved.mode, &saved.code, &saved.game.moves().len()]
+        )
+    );
+
+    let mut answer = String::new();
+    if stdin().read_line(&mut answer).is_err() {
+        answer.clear();
+    }
+    // "y" is understood whatever the language
+    let answer = answer.trim();
+    if answer.eq_ignore_ascii_case("y") || answer.eq_ignore_ascii_case(text(Msg::YesAnswer)) {
+        Some(saved)
+    } else {
+        discard();
+        None
+    }
+}
+
+// on ctrl-c, writes out the game in progress before exiting
+pub fn install_interrupt_handler() {
+    // there is no handler to install anywhere else, so say what ctrl-c costs
+    if cfg!(not(unix)) {
+        println!("{}", text(Msg::InterruptNotCaught));
+    }
+    on_interrupt(|| {
+        flush();
+        std::process::exit(130);
+    });
+}
+
+// the panic, where it happened and a debug dump of the game, written next to
+// the autosave with the time in its name, so that one crash can't overwrite
+// the report of another
+fn write_report(info: &PanicHookInfo, pending: &Pending) -> io::Result<PathBuf> {
+    let dir =
+        data_dir().ok_or_else(|| io::Error::new(ErrorKind::NotFound, text(Msg::NoDataDir)))?;
+    fs::create_dir_all(&dir)?;
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    let path = dir.join(format!("crash-{secs}.txt"));
+
+    let report = format!("{info}\n\n{}\n", debug_dump(&pending.game, &pending.code));
+    fs::write(&path, report)?;
+    Ok(path)
+}
+
+// on a panic during a game, saves the game and writes a crash report instead
+// of losing it to a backtrace, then exits; a panic with no game in progress
+// is left to the default hook
+pub fn install_panic_handler() {
+    let default = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        // the panic could have happened while the lock was held
+        let pending = match PENDING.try_lock() {
+            Ok(mut pending) => pending.take(),
+            Err(_) => None,
+        };
+        let Some(pending) = pending else {
+            return default(info);
+        };
+
+        if let Err(e) = write(&serialize(pending.mode, &pending.code, &pending.game)) {
+            eprintln!("{}", fill(Msg::CouldntSave, &[&e]));
+        }
+        match write_report(info, &pending) {
+            Ok(path) => eprintln!("\n{}", fill(Msg::CrashReportWritten, &[&path.display()])),
+            Err(e) => {
+                eprintln!("\n{}", fill(Msg::CouldntWriteCrashReport, &[&e]));
+                default(info);
+            }
+        }
+        process::exit(CRASH_EXIT_CODE);
+    }));
+}
+
+// runs `handler` on ctrl-c, which is then up to it to exit; this must run
+// before any other thread is spawned so that every thread inherits the
+// blocked signal, leaving the dedicated thread below as the only one to see it
+#[cfg(unix)]
+pub fn on_interrupt(handler: impl FnOnce() + Send + 'static) {
+    use std::{mem, ptr, thread};
+
+    // SAFETY: the signal set is initialized by sigemptyset before use, and
+    // the pointers passed are valid for the duration of each call
+    let set = unsafe {
+        let mut set: libc::sigset_t = mem::zeroed();
+        libc::sigemptyset(&mut set);
+        libc::sigaddset(&mut set, libc::SIGINT);
+        libc::pthread_sigmask(libc::SIG_BLOCK, &set, ptr::null_mut());
+        set
+    };
+
+    thread::spawn(move || {
+        let mut signal = 0;
+        // SAFETY: both pointers are valid, and SIGINT is blocked in this thread
+        if unsafe { libc::sigwait(&set, &mut signal) } == 0 {
+            handler();
+        }
+    });
+}
+
+#[cfg(not(unix))]
+pub fn on_interrupt(_handler: impl FnOnce() + Send + 'static) {}
@@ -0,0 +1,151 @@
+use crate::config::{BannerStyle, Config, Theme};
+use crate::game::{Game, GameState};
+use crate::layout::display_width;
+use crate::render::{cell_layout, render_revealed};
+
+const WIN_BLOCK: &str = include_str!("../assets/banners/win_block.txt");
+const BOOM_BLOCK: &str = include_str!("../assets/banners/boom_block.txt");
+const WIN_OUTLINE: &str = include_str!("../assets/banners/win_outline.txt");
+const BOOM_OUTLINE: &str = include_str!("../assets/banners/boom_outline.txt");
+
+// the art for a finished game, or None for no banner at all
+pub fn banner(state: GameState, style: BannerStyle) -> Option<&'static str> {
+    match (style, state) {
+        // nothing went off, and nothing was won
+        (BannerStyle::None, _) | (_, GameState::Playing | GameState::Resigned) => None,
+        (BannerStyle::Block, GameState::Won) => Some(WIN_BLOCK),
+        (BannerStyle::Block, GameState::Lost) => Some(BOOM_BLOCK),
+        (BannerStyle::Outline, GameState::Won) => Some(WIN_OUTLINE),
+        (BannerStyle::Outline, GameState::Lost) => Some(BOOM_OUTLINE),
+    }
+}
+
+// how many columns a rendered board row takes up
+fn board_columns(width: usize, theme: Theme) -> usize {
+    cell_layout(theme).row_width(width)
+}
+
+// centres every line of the art over `columns`, keeping the lines lined up
+// with each other; art wider than that is left as it is
+pub fn center(art: &str, columns: usize) -> String {
+    let art_width = art.lines().map(display_width).max().unwrap_or(0);
+    let padding = " ".repeat(columns.saturating_sub(art_width) / 2);
+
+    art.lines()
+        .map(|line| format!("{padding}{line}").trim_end().to_string())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// the revealed board, under a banner when there is someone at a terminal to
+// see it; piped output stays easy to read back in
+pub fn render_ending(game: &Game, config: &Config) -> String {
+    let revealed = render_revealed(game.board(), config);
+
+    match banner(game.state(), config.output.banner) {
+        Some(art) => {
+            let columns = board_columns(game.board().width(), config.output.theme);
+            format!("{}\n\n{revealed}", center(art, columns))
+        }
+        None => revealed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{Board, CellPosition};
+    use crate::command::Command;
+    use crate::output::OutputCapabilities;
+
+    const STYLES: [BannerStyle; 3] = [BannerStyle::None, BannerStyle::Block, BannerStyle::Outline];
+
+    // a one-row game finished by clearing (0,0), safe or not
+    fn finished(layout: &str) -> Game {
+        let board: Board = layout.parse().expect("The layout should parse");
+        let mut game = Game::new(board, 1);
+        game.apply(0, Command::Clear(CellPosition::new(0, 0)))
+            .expect("The clear should be played");
+        game
+    }
+
+    fn config(plain: bool, terminal: bool) -> Config {
+        let mut config = Config {
+            plain,
+            banner: BannerStyle::Block,
+            ..Config::default()
+        };
+        config.output = OutputCapabilities::of(&config, false, terminal);
+        config
+    }
+
+    #[test]
+    fn each_style_has_its_own_art_for_a_win_and_a_loss() {
+        assert_eq!(banner(GameState::Won, BannerStyle::Block), Some(WIN_BLOCK));
+        assert_eq!(
+            banner(GameState::Lost, BannerStyle::Block),
+            Some(BOOM_BLOCK)
+        );
+        assert_eq!(
+            banner(GameState::Won, BannerStyle::Outline),
+            Some(WIN_OUTLINE)
+        );
+        assert_eq!(
+            banner(GameState::Lost, BannerStyle::Outline),
+            Some(BOOM_OUTLINE)
+        );
+        let arts = [WIN_BLOCK, BOOM_BLOCK, WIN_OUTLINE, BOOM_OUTLINE];
+        for (index, art) in arts.iter().enumerate() {
+            assert!(art.is_ascii() && !art.trim().is_empty());
+            assert!(!arts[index + 1..].contains(art));
+        }
+
+        // and none for no style, or for a game that wasn't won or lost
+        for style in STYLES {
+            assert_eq!(banner(GameState::Playing, style), None);
+            assert_eq!(banner(GameState::Resigned, style), None);
+        }
+        assert_eq!(banner(GameState::Won, BannerStyle::None), None);
+        assert_eq!(banner(GameState::Lost, BannerStyle::None), None);
+    }
+
+    #[test]
+    fn the_art_is_centred_as_one_block() {
+        assert_eq!(center("ab\nabcd", 8), "  ab\n  abcd");
+        assert_eq!(center("ab \nabcd", 9), "  ab\n  abcd");
+        // wider than the board, it is left where it is
+        assert_eq!(center("abcd", 2), "abcd");
+    }
+
+    #[test]
+    fn the_banner_is_drawn_only_at_a_terminal_and_never_in_plain_mode() {
+        let won = finished(".*");
+        let lost = finished("*.");
+        let art = |game: &Game, config: &Config| {
+            render_ending(game, config)
+                .lines()
+                .filter(|line| line.contains('#'))
+                .count()
+        };
+
+        let terminal = config(false, true);
+        assert!(render_ending(&won, &terminal)
+            .starts_with(&center(WIN_BLOCK, board_columns(2, terminal.output.theme))));
+        assert!(art(&lost, &terminal) >= BOOM_BLOCK.lines().count());
+
+        // piped, or plain even at a terminal, it is the revealed board alone
+        for config in [
+            config(false, false),
+            config(true, true),
+            config(true, false),
+        ] {
+            assert_eq!(config.output.banner, BannerStyle::None);
+            for game in [&won, &lost] {
+                assert_eq!(
+                    render_ending(game, &config),
+                    render_revealed(game.board(), &config)
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,312 @@
+use crate::cast::write_cast;
+use crate::command::{self, Command, Export};
+use crate::config::Config;
+use crate::driver::Degenerate;
+use crate::game::{Game, GameOutcome, GameState, Move, MoveOutcome};
+use crate::i18n::{fill, text, Msg};
+use crate::json::Value;
+use crate::mbf::write_mbf;
+use crate::queries;
+use crate::render::{render_game, render_last_move, render_status};
+use crate::save::Mode;
+use crate::saves::save_as;
+use crate::transcript::write_transcript;
+use crate::view::{change_name, GameView};
+use std::fmt::Display;
+use std::io::{self, stdin, stdout, Write};
+
+// the line of output for an applied command
+fn move_json(view: &GameView, command: &Command, state: GameState) -> Value {
+    let last = view.last_move();
+
+    let mut fields = vec![
+        (String::from("command"), Value::from(command.to_string())),
+        (String::from("state"), Value::from(state.name())),
+    ];
+    if let Some(&MoveOutcome::AreaOpened { opened, skipped }) = last.map(|last| &last.outcome) {
+        fields.push((String::from("opened"), Value::from(opened)));
+        fields.push((String::from("skipped"), Value::from(skipped)));
+    }
+    if let Some((change, _)) = last.and_then(Move::mark) {
+        fields.push((String::from("change"), Value::from(change_name(change))));
+        fields.push((
+            String::from("mines_left"),
+            Value::from(view.counters().mines_left as f64),
+        ));
+    }
+    fields.push((String::from("events"), view.events_json()));
+    fields.push((String::from("board"), view.board_json()));
+
+    Value::Object(fields)
+}
+
+// a move's line with `--diff`: each cell it changed as [row, col, how it
+// looks now], or the whole board if the events can't say which changed
+fn diff_json(view: &GameView, command: &Command, state: GameState) -> Value {
+    let mut fields = vec![
+        (String::from("command"), Value::from(command.to_string())),
+        (String::from("state"), Value::from(state.name())),
+    ];
+    match view.changes_json() {
+        Some(changes) => fields.push((String::from("changes"), changes)),
+        None => fields.push((String::from("board"), view.board_json())),
+    }
+    fields.push((String::from("counters"), view.counters_json()));
+
+    Value::Object(fields)
+}
+
+// the answer to `full`, for a client that wants the whole board again
+fn full_json(view: &GameView) -> Value {
+    Value::object([
+        ("state", Value::from(view.state().name())),
+        ("counters", view.counters_json()),
+        ("board", view.board_json()),
+    ])
+}
+
+// a line of output, to stdout or a client; a client that has gone away is
+// noticed when the next line can't be read from it
+macro_rules! emit {
+    ($out:expr, $($arg:tt)*) => {{
+        let _ = writeln!($out, $($arg)*);
+    }};
+}
+
+fn report_error(out: &mut dyn Write, e: impl Display, json: bool) {
+    if json {
+        emit!(
+            out,
+            "{}",
+            Value::object([("error", Value::from(e.to_string()))])
+        );
+    } else {
+        emit!(out, "{}", fill(Msg::ErrorLine, &[&e]));
+    }
+}
+
+// a non-interactive single-player game for scripts: a line of commands at a
+// time from stdin, no prompts, and the board printed after every accepted
+// command (as text, or with `json` as one object per line)
+pub fn play(mut game: Game, code: String, config: &Config, json: bool, diff: bool) -> GameOutcome {
+    let protocol = Protocol {
+        config,
+        json,
+        diff,
+        files: true,
+    };
+    protocol.opening(&game, &mut stdout());
+    protocol.run(&mut game, &code, stdin().lines(), &mut stdout())
+}
+
+// how commands are answered: `diff` is whether a move is answered with only
+// the cells it changed (in json), and `files` whether exports can write
+// files, which they can't for a client on the network
+pub struct Protocol<'a> {
+    pub config: &'a Config,
+    pub json: bool,
+    pub diff: bool,
+    pub files: bool,
+}
+
+impl Protocol<'_> {
+    // the whole of the game as it stands, as the `full` command gives it
+    pub fn show(&self, game: &Game, out: &mut dyn Write) {
+        if self.json {
+            emit!(out, "{}", full_json(&GameView::of(game)));
+        } else {
+            let (status, board) = (
+                render_status(game, self.config),
+                render_game(game, self.config),
+            );
+            emit!(out, "{status}\n{board}\n------");
+        }
+    }
+
+    // what is said before the first command, as run_game says it at the
+    // terminal: that there is nothing to play, or only a guess to make
+    pub fn opening(&self, game: &Game, out: &mut dyn Write) {
+        match Degenerate::of(game) {
+            Some(degenerate @ Degenerate::NoSafeCells) => report_error(out, degenerate, self.json),
+            Some(degenerate) if self.json => emit!(
+                out,
+                "{}",
+                Value::object([("warning", Value::from(degenerate.to_string()))])
+            ),
+            Some(degenerate) => emit!(out, "{degenerate}"),
+            None => (),
+        }
+    }
+
+    // plays the game on from its current position with commands from
+    // `lines`, answering each on `out`, until it is over or the lines run out
+    pub fn run(
+        &self,
+        game: &mut Game,
+        code: &str,
+        lines: impl Iterator<Item = io::Result<String>>,
+        out: &mut dyn Write,
+    ) -> GameOutcome {
+        self.run_with(game, code, lines, out, |_| ())
+    }
+
+    // as `run`, calling `after_move` with the game after each applied command
+    pub fn run_with(
+        &self,
+        game: &mut Game,
+        code: &str,
+        lines: impl Iterator<Item = io::Result<String>>,
+        out: &mut dyn Write,
+        mut after_move: impl FnMut(&Game),
+    ) -> GameOutcome {
+        let Protocol {
+            config,
+            json,
+            diff,
+            files,
+        } = *self;
+        // and not taken as won
+        if Degenerate::of(game) == Some(Degenerate::NoSafeCells) {
+            return GameOutcome::Aborted;
+        }
+        if let Some(outcome) = game.outcome() {
+            return outcome;
+        }
+
+        let view = GameView::of(game);
+        let (height, width) = (view.board().height(), view.board().width());
+        for line in lines {
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => {
+                    report_error(out, e, json);
+                    break;
+                }
+            };
+
+            if line.trim().is_empty() {
+                continue;
+            }
+            // not a command of the game's, but of the protocol's
+            if line.trim() == "full" {
+                self.show(game, out);
+                continue;
+            }
+
+            let commands = match command::parse_line_within(&line, height, width) {
+                Ok(commands) => commands,
+                Err(e) if json => {
+                    let error = Value::object([
+                        ("error", Value::from(e.to_string())),
+                        ("code", Value::from(e.code())),
+                    ]);
+                    emit!(out, "{error}");
+                    continue;
+                }
+                Err(e) => {
+                    report_error(out, e, json);
+                    continue;
+                }
+            };
+            let several = commands.len() > 1;
+
+            // the rest of the line is skipped after an error
+            for command in commands {
+                if let Some(answer) = queries::answer(config, game, code, &command, json) {
+                    match answer {
+                        Ok(line) => emit!(out, "{line}"),
+                        Err(e) => report_error(out, e, json),
+                    }
+                    continue;
+                }
+                match command {
+                    // the game ends with its last move, before the board could
+                    // be given away
+                    Command::Export(Export::Discord) => {
+                        report_error(out, text(Msg::ExportsAfterGame), json);
+                        break;
+                    }
+                    Command::Watch => {
+                        report_error(out, text(Msg::WatchNotHere), json);
+                        break;
+                    }
+                    Command::SaveAs(_) if !files => {
+                        report_error(out, text(Msg::NoSavesOverNetwork), json);
+                        break;
+                    }
+                    Command::SaveAs(name) => match save_as(&name, Mode::Single, code, game) {
+                        Ok(_) if json => emit!(
+                            out,
+                            "{}",
+                            Value::object([("saved", Value::from(name.as_str()))])
+                        ),
+                        Ok(_) => emit!(out, "{}", fill(Msg::SavedAs, &[&name])),
+                        Err(e) => {
+                            report_error(out, fill(Msg::CouldntSaveAs, &[&e]), json);
+                            break;
+                        }
+                    },
+                    Command::Export(_) if !files => {
+                        report_error(out, text(Msg::NoExportsOverNetwork), json);
+                        break;
+                    }
+                    Command::Export(Export::Transcript(path)) => {
+                        if let Err(e) = write_transcript(game, code, &path) {
+                            report_error(out, e, json);
+                            break;
+                        }
+                    }
+                    Command::Export(Export::Cast(path)) => {
+                        if let Err(e) = write_cast(game, code, config, &path) {
+                            report_error(out, e, json);
+                            break;
+                        }
+                    }
+                    Command::Export(Export::Mbf(path)) => {
+                        if let Err(e) = write_mbf(game.board(), &path) {
+                            report_error(out, e, json);
+                            break;
+                        }
+                    }
+                    command => {
+                        let state = match game.apply(0, command.clone()) {
+                            Ok(state) => state,
+                            Err(e) => {
+                                report_error(out, e, json);
+                                break;
+                            }
+                        };
+
+                        after_move(game);
+                        if json && diff {
+                            emit!(out, "{}", diff_json(&GameView::of(game), &command, state));
+                        } else if json {
+                            emit!(out, "{}", move_json(&GameView::of(game), &command, state));
+                        } else {
+                            // a resignation isn't a move of its own to describe
+                            let feedback = render_last_move(game, several)
+                                .filter(|_| command != Command::Resign);
+                            if let Some(feedback) = feedback {
+                                emit!(out, "{feedback}");
+                            }
+                            emit!(
+                                out,
+                                "{}\n{}\n------",
+                                render_status(game, config),
+                                render_game(game, config)
+                            );
+                        }
+
+                        if state != GameState::Playing {
+                            return game
+                                .outcome()
+                                .expect("A finished game should have an outcome");
+                        }
+                    }
+                }
+            }
+        }
+
+        GameOutcome::Aborted
+    }
+}
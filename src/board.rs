@@ -0,0 +1,1695 @@
+use crate::grid::{ArrayGrid, CellGrid, VecGrid};
+use crate::i18n::{fill, text, Msg};
+use crate::layout::CellLayout;
+use crate::topology::{cascade, Reach};
+use rand::rngs::StdRng;
+use rand::seq::index::sample;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::cmp::Reverse;
+use std::fmt::Display;
+use std::ops::Range;
+use std::str::FromStr;
+
+#[derive(Debug, Clone)]
+pub enum CellState {
+    Bomb { flagged: bool, exploded: bool },
+    Safe { flagged: bool, open: bool },
+    // an obstacle: not a mine, and never opened, marked or cascaded through
+    Rock,
+}
+
+// WARNING: there are no checks to ensure this has valid indeces;
+// it is only intended as a convenient abstraction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CellPosition {
+    pub row_index: usize,
+    pub col_index: usize,
+}
+
+impl CellPosition {
+    pub fn new(row_index: usize, col_index: usize) -> Self {
+        CellPosition {
+            row_index,
+            col_index,
+        }
+    }
+
+    // `rows` down and `cols` across from here, if that is still on the board
+    pub fn checked_offset(self, rows: isize, cols: isize, dims: Dims) -> Option<CellPosition> {
+        let at = CellPosition {
+            row_index: self.row_index.checked_add_signed(rows)?,
+            col_index: self.col_index.checked_add_signed(cols)?,
+        };
+        dims.contains(at).then_some(at)
+    }
+
+    // the positions on the board at most N rows and N columns away, row by
+    // row, not counting this one
+    pub fn neighbors<const N: usize>(self, dims: Dims) -> impl Iterator<Item = CellPosition> {
+        let (rows, cols) = dims.clamp_range(self, N);
+        rows.flat_map(move |row_index| {
+            cols.clone().map(move |col_index| CellPosition {
+                row_index,
+                col_index,
+            })
+        })
+        .filter(move |&at| at != self)
+    }
+}
+
+// (row,col), as positions are shown in messages
+impl Display for CellPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "({},{})", self.row_index, self.col_index)
+    }
+}
+
+// how many rows and columns a board has
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dims {
+    pub height: usize,
+    pub width: usize,
+}
+
+impl Dims {
+    // the rows are expected to be the same length
+    pub fn of<T>(board: &[Vec<T>]) -> Self {
+        Dims {
+            height: board.len(),
+            width: board.first().map_or(0, Vec::len),
+        }
+    }
+
+    pub fn contains(self, position: CellPosition) -> bool {
+        position.row_index < self.height && position.col_index < self.width
+    }
+
+    // the rows and the columns of the board at most `radius` from `center`,
+    // which are both empty for a centre off the board
+    pub fn clamp_range(self, center: CellPosition, radius: usize) -> (Range<usize>, Range<usize>) {
+        if !self.contains(center) {
+            return (0..0, 0..0);
+        }
+        let span = |index: usize, length: usize| {
+            index.saturating_sub(radius)..index.saturating_add(radius).saturating_add(1).min(length)
+        };
+        (
+            span(center.row_index, self.height),
+            span(center.col_index, self.width),
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Cell {
+    pub bombs_around: u8,
+    pub state: CellState,
+    pub position: CellPosition,
+    // a note to self about a closed cell; unlike a flag it doesn't count
+    // towards the mines or get in the way of clearing
+    pub question: bool,
+    // the player used a peek on it, so whether it is a mine is known
+    pub peeked: bool,
+    // a mine set off while a shield was up, which then costs nothing
+    pub defused: bool,
+}
+
+impl Cell {
+    fn new(row_index: usize, col_index: usize, bombs: &[Vec<bool>], is_bomb: bool) -> Self {
+        let position = CellPosition {
+            row_index,
+            col_index,
+        };
+
+        Cell {
+            bombs_around: get_bombs_around(bombs, position),
+            state: if is_bomb {
+                CellState::Bomb {
+                    flagged: false,
+                    exploded: false,
+                }
+            } else {
+                CellState::Safe {
+                    flagged: false,
+                    open: false,
+                }
+            },
+            position,
+            question: false,
+            peeked: false,
+            defused: false,
+        }
+    }
+
+    // what a cell can't be whatever the board around it, and so what
+    // anything drawing one has to be ready for: flagged and open at once,
+    // or showing more mines than it has neighbours. Nothing the game does
+    // leaves a cell like that, but a board set by hand can
+    pub fn check(&self) -> Result<(), InvariantViolation> {
+        let at = self.position;
+        match self.state {
+            CellState::Safe {
+                flagged: true,
+                open: true,
+            } => Err(InvariantViolation::FlaggedAndOpen { at }),
+            CellState::Safe { open: true, .. } if self.bombs_around > 8 => {
+                Err(InvariantViolation::TooManyAround {
+                    at,
+                    shown: self.bombs_around,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+// what the player can see: closed cells look the same whether or not they
+// hide a bomb
+impl Display for Cell {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let out = match self.state {
+            CellState::Bomb { exploded: true, .. } if self.defused => "+",
+            CellState::Bomb { exploded: true, .. } => "*",
+            CellState::Bomb { flagged: true, .. } => "^",
+            CellState::Bomb { flagged: false, .. } if self.peeked => "!",
+            CellState::Bomb { flagged: false, .. } if self.question => "?",
+            CellState::Bomb { flagged: false, .. } => "#",
+            // a cell that can't be, drawn so that the board around it
+            // still can be
+            CellState::Safe {
+                flagged: true,
+                open: true,
+            } => "E",
+            CellState::Safe {
+                flagged: true,
+                open: false,
+            } => "^",
+            CellState::Safe {
+                flagged: false,
+                open: true,
+            } if self.bombs_around <= 8 => return write!(f, "{}", self.bombs_around),
+            CellState::Safe {
+                flagged: false,
+                open: true,
+            } => "E",
+            CellState::Safe {
+                flagged: false,
+                open: false,
+            } if self.peeked => "~",
+            CellState::Safe {
+                flagged: false,
+                open: false,
+            } if self.question => "?",
+            CellState::Safe {
+                flagged: false,
+                open: false,
+            } => "#",
+            CellState::Rock => "%",
+        };
+
+        write!(f, "{out}")
+    }
+}
+
+impl PartialEq for Cell {
+    fn eq(&self, other: &Self) -> bool {
+        self.position == other.position
+    }
+}
+
+#[derive(Debug)]
+pub enum ClearError {
+    ClearedBomb,
+    CellNotFound,
+    AlreadyCleared,
+    Rock,
+    // the board contradicts itself where the cascade reached `position`,
+    // which is a bug in whatever made it
+    Inconsistent {
+        position: CellPosition,
+        detail: String,
+    },
+}
+
+impl Display for ClearError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let out = match self {
+            ClearError::ClearedBomb => text(Msg::ClearedBomb),
+            ClearError::CellNotFound => text(Msg::InvalidCellPosition),
+            ClearError::AlreadyCleared => text(Msg::CellAlreadyCleared),
+            ClearError::Rock => text(Msg::CellIsRock),
+            ClearError::Inconsistent { position, detail } => {
+                return write!(f, "{}", fill(Msg::BoardInconsistent, &[position, detail]))
+            }
+        };
+
+        write!(f, "{out}")
+    }
+}
+
+// what clearing a cell would do: the cells it would open, in the order the
+// cascade reaches them, whether it is a mine, and how many cells would be
+// open afterwards
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClearOutcome {
+    pub opened: Vec<CellPosition>,
+    pub mine: bool,
+    pub open_count: usize,
+}
+
+// what chording a cell would do, from what the player can see of it: the
+// cells it would clear, and the flags (and mines set off) around it against
+// its number; nothing in it depends on which closed cells are mines
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChordPreview {
+    pub targets: Vec<CellPosition>,
+    pub flags: usize,
+    pub bombs_around: u8,
+}
+
+impl ChordPreview {
+    // whether the chord would clear its targets, rather than do nothing
+    pub fn satisfied(&self) -> bool {
+        self.flags == usize::from(self.bombs_around)
+    }
+}
+
+// what marking a cell did to it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FlagChange {
+    FlagPlaced,
+    FlagRemoved,
+    QuestionPlaced,
+    QuestionRemoved,
+}
+
+#[derive(Debug)]
+pub enum FlagError {
+    CellNotFound,
+    AlreadyCleared,
+    Rock,
+}
+
+impl Display for FlagError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let out = match self {
+            FlagError::CellNotFound => text(Msg::InvalidCellPosition),
+            FlagError::AlreadyCleared => text(Msg::CellAlreadyCleared),
+            FlagError::Rock => text(Msg::CellIsRock),
+        };
+
+        write!(f, "{out}")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ChordError {
+    CellNotFound,
+    NotOpen,
+    // the flags around the cell don't account for its number yet
+    FlagsDontMatch { flags: usize, bombs_around: u8 },
+    // every cell around it is open or flagged already
+    NothingToOpen,
+}
+
+impl Display for ChordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let out = match self {
+            ChordError::CellNotFound => String::from(text(Msg::InvalidCellPosition)),
+            ChordError::NotOpen => String::from(text(Msg::ChordNotOpen)),
+            ChordError::FlagsDontMatch {
+                flags,
+                bombs_around,
+            } => fill(Msg::ChordFlagsDontMatch, &[flags, bombs_around]),
+            ChordError::NothingToOpen => String::from(text(Msg::ChordNothingToOpen)),
+        };
+
+        write!(f, "{out}")
+    }
+}
+
+// something about a board that can't happen if it was only ever changed
+// through its methods
+#[derive(Debug, PartialEq)]
+pub enum InvariantViolation {
+    // a row with a different number of cells from the first
+    RaggedRows {
+        row: usize,
+    },
+    // the cell found at `at` thinks it is somewhere else
+    WrongPosition {
+        at: CellPosition,
+    },
+    // an open cell that doesn't show the mines around it
+    WrongCount {
+        at: CellPosition,
+        shown: u8,
+        actual: u8,
+    },
+    // more mines than any cell has around it
+    TooManyAround {
+        at: CellPosition,
+        shown: u8,
+    },
+    FlaggedAndOpen {
+        at: CellPosition,
+    },
+    // a question mark on an open cell, which has nothing left to ask
+    QuestionOnOpen {
+        at: CellPosition,
+    },
+    // a mine that is flagged has been stepped on
+    FlaggedAndExploded {
+        at: CellPosition,
+    },
+    // only a mine that went off can have been defused
+    DefusedButNotExploded {
+        at: CellPosition,
+    },
+}
+
+impl Display for InvariantViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let out = match self {
+            InvariantViolation::RaggedRows { row } => fill(Msg::InvariantRaggedRows, &[row]),
+            InvariantViolation::WrongPosition { at } => fill(Msg::InvariantWrongPosition, &[at]),
+            InvariantViolation::WrongCount { at, shown, actual } => {
+                fill(Msg::InvariantWrongCount, &[at, shown, actual])
+            }
+            InvariantViolation::TooManyAround { at, shown } => {
+                fill(Msg::InvariantTooManyAround, &[at, shown])
+            }
+            InvariantViolation::FlaggedAndOpen { at } => fill(Msg::InvariantFlaggedAndOpen, &[at]),
+            InvariantViolation::QuestionOnOpen { at } => fill(Msg::InvariantQuestionOnOpen, &[at]),
+            InvariantViolation::FlaggedAndExploded { at } => {
+                fill(Msg::InvariantFlaggedAndExploded, &[at])
+            }
+            InvariantViolation::DefusedButNotExploded { at } => {
+                fill(Msg::InvariantDefusedButNotExploded, &[at])
+            }
+        };
+
+        write!(f, "{out}")
+    }
+}
+
+// why some text isn't a layout
+#[derive(Debug, PartialEq)]
+pub enum LayoutError {
+    Empty,
+    // a row with a different number of cells from the first
+    RaggedRow {
+        row: usize,
+    },
+    UnexpectedSymbol {
+        symbol: char,
+        row: usize,
+        col: usize,
+    },
+    // every cell a mine or a rock, which would be won before it started
+    NoSafeCells,
+}
+
+impl Display for LayoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let out = match self {
+            LayoutError::Empty => text(Msg::LayoutEmpty).to_string(),
+            LayoutError::RaggedRow { row } => fill(Msg::LayoutRaggedRow, &[row]),
+            LayoutError::UnexpectedSymbol { symbol, row, col } => {
+                fill(Msg::LayoutUnexpectedSymbol, &[symbol, row, col])
+            }
+            LayoutError::NoSafeCells => text(Msg::LayoutNoSafeCells).to_string(),
+        };
+
+        write!(f, "{out}")
+    }
+}
+
+// how many bombs to place when generating a board
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mines {
+    // exactly this many bombs
+    Count(usize),
+    // each cell is a bomb with this probability
+    Density(f64),
+}
+
+impl Mines {
+    // the number of mines a board of `cells` cells is to have; a density is
+    // taken as the number it gives on average
+    pub fn count_on(self, cells: usize) -> usize {
+        match self {
+            Mines::Count(count) => count,
+            Mines::Density(density) => (density * cells as f64).round() as usize,
+        }
+    }
+}
+
+// bounds on how many mines each row and each column of a generated board
+// has; a maximum of None is the length of the line
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct LineLimits {
+    pub min_per_row: usize,
+    pub max_per_row: Option<usize>,
+    pub min_per_column: usize,
+    pub max_per_column: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Line {
+    Row,
+    Column,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum LimitError {
+    MinAboveMax {
+        line: Line,
+        min: usize,
+        max: usize,
+    },
+    MinAboveLength {
+        line: Line,
+        min: usize,
+        length: usize,
+    },
+    TooFewMines {
+        line: Line,
+        min: usize,
+        needed: usize,
+        mines: usize,
+    },
+    TooManyMines {
+        line: Line,
+        max: usize,
+        allowed: usize,
+        mines: usize,
+    },
+}
+
+impl Display for LimitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = |line: &Line| match line {
+            Line::Row => text(Msg::LimitRow),
+            Line::Column => text(Msg::LimitColumn),
+        };
+        let out = match self {
+            LimitError::MinAboveMax { line, min, max } => {
+                fill(Msg::LimitMinAboveMax, &[min, &name(line), max])
+            }
+            LimitError::MinAboveLength { line, min, length } => {
+                fill(Msg::LimitMinAboveLength, &[min, &name(line), length])
+            }
+            LimitError::TooFewMines {
+                line,
+                min,
+                needed,
+                mines,
+            } => fill(Msg::LimitTooFewMines, &[min, &name(line), needed, mines]),
+            LimitError::TooManyMines {
+                line,
+                max,
+                allowed,
+                mines,
+            } => fill(Msg::LimitTooManyMines, &[max, &name(line), allowed, mines]),
+        };
+
+        write!(f, "{out}")
+    }
+}
+
+// how mines are scattered over a generated board
+#[derive(Debug, Clone, PartialEq)]
+pub enum MineDistribution {
+    // every cell is as likely as any other
+    Uniform,
+    // in a few dense pockets, leaving open plains between them
+    Clustered,
+    // kept apart from each other, as far as the count allows
+    Sparse,
+    // more often where the stencil is marked, so that the mines draw it
+    Stencil(Stencil),
+}
+
+// a picture the size of the board, with # where a mine is preferred and
+// anything else where it isn't; short lines are taken as unmarked after
+// their end
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stencil {
+    marks: Vec<Vec<bool>>,
+}
+
+impl Stencil {
+    pub fn parse(text: &str) -> Self {
+        let mut lines: Vec<&str> = text.lines().collect();
+        while lines.last().is_some_and(|line| line.trim().is_empty()) {
+            lines.pop();
+        }
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let marks = lines
+            .iter()
+            .map(|line| {
+                let mut row: Vec<bool> = line.chars().map(|symbol| symbol == '#').collect();
+                row.resize(width, false);
+                row
+            })
+            .collect();
+        Stencil { marks }
+    }
+
+    pub fn height(&self) -> usize {
+        self.marks.len()
+    }
+
+    pub fn width(&self) -> usize {
+        self.marks.first().map_or(0, Vec::len)
+    }
+}
+
+// everything that shapes a generated board besides its size; the symmetry,
+// the line limits and the distribution are expected not to be combined,
+// and the limits to pass their check
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layout {
+    pub mines: Mines,
+    pub symmetry: Symmetry,
+    pub limits: LineLimits,
+    pub distribution: MineDistribution,
+    // up to this many of the safe cells are turned into rocks
+    pub rocks: usize,
+}
+
+impl Layout {
+    pub fn new(mines: Mines) -> Self {
+        Layout {
+            mines,
+            symmetry: Symmetry::None,
+            limits: LineLimits::default(),
+            distribution: MineDistribution::Uniform,
+            rocks: 0,
+        }
+    }
+}
+
+impl LineLimits {
+    pub fn is_none(&self) -> bool {
+        *self == LineLimits::default()
+    }
+
+    // the bounds for one row (each `width` long), or one column
+    fn bounds(&self, line: Line, length: usize) -> (usize, usize) {
+        let (min, max) = match line {
+            Line::Row => (self.min_per_row, self.max_per_row),
+            Line::Column => (self.min_per_column, self.max_per_column),
+        };
+        (min, max.unwrap_or(length).min(length))
+    }
+
+    // whether `mines` mines can be laid out on the board within the limits;
+    // for bounds that are the same for every row and every column, it is
+    // enough that the rows and the columns can each take that many
+    pub fn check(&self, height: usize, width: usize, mines: usize) -> Result<(), LimitError> {
+        for (line, length, lines) in [(Line::Row, width, height), (Line::Column, height, width)] {
+            let (min, max) = self.bounds(line, length);
+            if min > length {
+                return Err(LimitError::MinAboveLength { line, min, length });
+            }
+            if min > max {
+                return Err(LimitError::MinAboveMax { line, min, max });
+            }
+            if min * lines > mines {
+                let needed = min * lines;
+                return Err(LimitError::TooFewMines {
+                    line,
+                    min,
+                    needed,
+                    mines,
+                });
+            }
+            if max * lines < mines {
+                let allowed = max * lines;
+                return Err(LimitError::TooManyMines {
+                    line,
+                    max,
+                    allowed,
+                    mines,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+// how the mines of a generated board mirror each other
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Symmetry {
+    None,
+    // the right half is the left half in a mirror
+    Horizontal,
+    // the bottom half is the top half in a mirror
+    Vertical,
+    // the board looks the same turned upside down
+    Rotational,
+}
+
+impl Symmetry {
+    // where a cell ends up when the board is mirrored or turned
+    fn image(self, height: usize, width: usize, position: CellPosition) -> CellPosition {
+        let CellPosition {
+            row_index,
+            col_index,
+        } = position;
+        let (flipped_row, flipped_col) = (height - 1 - row_index, width - 1 - col_index);
+        let (row_index, col_index) = match self {
+            Symmetry::None => (row_index, col_index),
+            Symmetry::Horizontal => (row_index, flipped_col),
+            Symmetry::Vertical => (flipped_row, col_index),
+            Symmetry::Rotational => (flipped_row, flipped_col),
+        };
+        CellPosition {
+            row_index,
+            col_index,
+        }
+    }
+
+    // whether some cells are their own image, on the axis or at the centre;
+    // without any, a symmetric board can only have an even number of mines
+    pub fn has_axis_cells(self, height: usize, width: usize) -> bool {
+        match self {
+            Symmetry::None => true,
+            Symmetry::Horizontal => width % 2 == 1,
+            Symmetry::Vertical => height % 2 == 1,
+            Symmetry::Rotational => height % 2 == 1 && width % 2 == 1,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Board<G: CellGrid = VecGrid> {
+    board: G,
+}
+
+// a board kept in a fixed array, e.g. `ArrayBoard<9>` for a beginner's 9x9
+pub type ArrayBoard<const N: usize> = Board<ArrayGrid<N>>;
+
+impl Board {
+    // the same seed always produces the same layout, which lets several
+    // players (or several games) share a board
+    pub fn from_seed(seed: u64, height: usize, width: usize, mines: Mines) -> Self {
+        Self::generate(seed, height, width, &Layout::new(mines))
+    }
+
+    // like `from_seed`, but laid out as `layout` asks; with nothing else
+    // asked of it, the layout is the one `from_seed` gives
+    pub fn generate(seed: u64, height: usize, width: usize, layout: &Layout) -> Self {
+        Self::generate_with(&mut StdRng::seed_from_u64(seed), height, width, layout)
+    }
+
+    // for a caller with a generator of its own
+    pub fn generate_with(rng: &mut impl Rng, height: usize, width: usize, layout: &Layout) -> Self {
+        let mut bombs = generate_bombs(rng, height, width, layout);
+        // a density can come out as every cell, which leaves nothing to open
+        if height * width > 0 && bombs.iter().flatten().all(|&is_bomb| is_bomb) {
+            let index = rng.gen_range(0..height * width);
+            bombs[index / width][index % width] = false;
+        }
+        let rocks = generate_rocks(rng, &bombs, layout.rocks);
+        Self::from_bombs(bombs).with_rocks(&rocks)
+    }
+
+    // every row of bombs is expected to have the same length; a board of
+    // only mines is made as asked, and it is up to the caller to turn it down
+    pub fn from_bombs(bombs: Vec<Vec<bool>>) -> Self {
+        Self::with_bombs(bombs).expect("A VecGrid should hold a board of any size")
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[Cell]> {
+        self.board.rows().iter().map(|row| row.as_slice())
+    }
+
+    // that the board is consistent with itself, for tests and fuzzing;
+    // moving mines leaves the open numbers behind on purpose, so a board
+    // they have moved on doesn't pass
+    pub fn check_invariants(&self) -> Result<(), InvariantViolation> {
+        let bombs: Vec<Vec<bool>> = self
+            .board
+            .rows()
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| matches!(cell.state, CellState::Bomb { .. }))
+                    .collect()
+            })
+            .collect();
+
+        for (row_index, row) in self.board.rows().iter().enumerate() {
+            if row.len() != self.width() {
+                return Err(InvariantViolation::RaggedRows { row: row_index });
+            }
+            for (col_index, cell) in row.iter().enumerate() {
+                let at = CellPosition {
+                    row_index,
+                    col_index,
+                };
+                if cell.position != at {
+                    return Err(InvariantViolation::WrongPosition { at });
+                }
+
+                cell.check()?;
+                let open = matches!(cell.state, CellState::Safe { open: true, .. });
+                if let CellState::Bomb {
+                    flagged: true,
+                    exploded: true,
+                } = cell.state
+                {
+                    return Err(InvariantViolation::FlaggedAndExploded { at });
+                }
+                if open && cell.question {
+                    return Err(InvariantViolation::QuestionOnOpen { at });
+                }
+                if cell.defused && !matches!(cell.state, CellState::Bomb { exploded: true, .. }) {
+                    return Err(InvariantViolation::DefusedButNotExploded { at });
+                }
+
+                let actual = get_bombs_around(&bombs, at);
+                if open && cell.bombs_around != actual {
+                    return Err(InvariantViolation::WrongCount {
+                        at,
+                        shown: cell.bombs_around,
+                        actual,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<G: CellGrid> Board<G> {
+    // like `from_bombs`, in whichever grid; none if it can't hold a board
+    // that size
+    pub fn with_bombs(bombs: Vec<Vec<bool>>) -> Option<Self> {
+        let cells = bombs
+            .iter()
+            .enumerate()
+            .map(|(row_index, row)| {
+                row.iter()
+                    .enumerate()
+                    .map(|(col_index, &is_bomb)| Cell::new(row_index, col_index, &bombs, is_bomb))
+                    .collect()
+            })
+            .collect();
+
+        G::from_rows(cells).map(|board| Board { board })
+    }
+
+    // the same board, cells and all, in another grid; none if it doesn't fit
+    pub fn regrid<H: CellGrid>(&self) -> Option<Board<H>> {
+        let Dims { height, width } = self.dims();
+        let rows = (0..height)
+            .map(|row_index| {
+                (0..width)
+                    .map(|col_index| {
+                        self.get_cell(CellPosition {
+                            row_index,
+                            col_index,
+                        })
+                        .cloned()
+                    })
+                    .collect::<Option<Vec<Cell>>>()
+            })
+            .collect::<Option<Vec<Vec<Cell>>>>()?;
+
+        H::from_rows(rows).map(|board| Board { board })
+    }
+
+    // counts the mines around each cell in `bombs` instead of on the board,
+    // as `from_bombs` does, which leaves the board contradicting itself
+    // unless they are the same mines; for testing what a wrong board does
+    pub fn with_counts_from(mut self, bombs: &[Vec<bool>]) -> Self {
+        for cell in self.board.iter_mut() {
+            cell.bombs_around = get_bombs_around(bombs, cell.position);
+        }
+        self
+    }
+
+    // turns the given safe cells into rocks; the numbers around them stay
+    // as they are, since they only count mines
+    pub fn with_rocks(mut self, rocks: &[CellPosition]) -> Self {
+        for &position in rocks {
+            if let Some(cell) = self.get_cell_mut(position) {
+                if matches!(cell.state, CellState::Safe { .. }) {
+                    cell.state = CellState::Rock;
+                }
+            }
+        }
+        self
+    }
+
+    pub fn height(&self) -> usize {
+        self.dims().height
+    }
+
+    pub fn dims(&self) -> Dims {
+        self.board.dims()
+    }
+
+    pub fn width(&self) -> usize {
+        self.dims().width
+    }
+
+    pub fn get_cell(&self, position: CellPosition) -> Option<&Cell> {
+        self.board.get(position)
+    }
+
+    // the up to eight cells touching `position`, and the cell itself
+    pub fn cells_around(&self, position: CellPosition) -> impl Iterator<Item = &Cell> {
+        let (rows, cols) = self.dims().clamp_range(position, 1);
+        rows.flat_map(move |row_index| {
+            cols.clone().map(move |col_index| CellPosition {
+                row_index,
+                col_index,
+            })
+        })
+        .filter_map(|around| self.board.get(around))
+    }
+
+    // the cell itself, to be set however the caller likes: for editors, and
+    // for tests of what a board that contradicts itself does. Nothing keeps
+    // the board consistent after, which `check_invariants` can be asked
+    pub fn get_cell_mut(&mut self, position: CellPosition) -> Option<&mut Cell> {
+        self.board.get_mut(position)
+    }
+
+    pub fn is_won(&self) -> bool {
+        // check if there is any cell that is closed and safe
+        !self
+            .board
+            .iter()
+            .any(|cell| matches!(cell.state, CellState::Safe { open: false, .. }))
+    }
+
+    // every mine flagged, or set off, and no safe cell flagged; a board
+    // without any mines to flag doesn't count
+    pub fn all_mines_flagged(&self) -> bool {
+        self.count_flags() > 0
+            && self.board.iter().all(|cell| match cell.state {
+                CellState::Bomb { flagged, exploded } => flagged || exploded,
+                CellState::Safe { flagged, .. } => !flagged,
+                CellState::Rock => true,
+            })
+    }
+
+    pub fn count_bombs(&self) -> usize {
+        self.board
+            .iter()
+            .filter(|cell| matches!(cell.state, CellState::Bomb { .. }))
+            .count()
+    }
+
+    pub fn count_flags(&self) -> usize {
+        self.board
+            .iter()
+            .filter(|cell| {
+                matches!(
+                    cell.state,
+                    CellState::Bomb { flagged: true, .. } | CellState::Safe { flagged: true, .. }
+                )
+            })
+            .count()
+    }
+
+    pub fn count_exploded(&self) -> usize {
+        self.board
+            .iter()
+            .filter(|cell| matches!(cell.state, CellState::Bomb { exploded: true, .. }))
+            .count()
+    }
+
+    // mines that have gone off are as good as flagged, they can't be hit
+    // again; negative when there are more flags than mines
+    pub fn mines_left(&self) -> isize {
+        self.count_bombs() as isize - (self.count_flags() + self.count_exploded()) as isize
+    }
+
+    // open or not; a board without any has nothing to play
+    pub fn count_safe(&self) -> usize {
+        self.board
+            .iter()
+            .filter(|cell| matches!(cell.state, CellState::Safe { .. }))
+            .count()
+    }
+
+    pub fn count_rocks(&self) -> usize {
+        self.board
+            .iter()
+            .filter(|cell| matches!(cell.state, CellState::Rock))
+            .count()
+    }
+
+    pub fn count_open(&self) -> usize {
+        self.board
+            .iter()
+            .filter(|cell| matches!(cell.state, CellState::Safe { open: true, .. }))
+            .count()
+    }
+
+    // flags a closed cell (replacing a question mark), or removes the flag
+    // if it is already flagged
+    pub fn toggle_flag(&mut self, position: CellPosition) -> Result<FlagChange, FlagError> {
+        let cell = self.get_cell_mut(position).ok_or(FlagError::CellNotFound)?;
+
+        match &mut cell.state {
+            CellState::Bomb { exploded: true, .. } | CellState::Safe { open: true, .. } => {
+                Err(FlagError::AlreadyCleared)
+            }
+            CellState::Rock => Err(FlagError::Rock),
+            CellState::Bomb { flagged, .. } | CellState::Safe { flagged, .. } => {
+                *flagged = !*flagged;
+                cell.question = false;
+                Ok(if *flagged {
+                    FlagChange::FlagPlaced
+                } else {
+                    FlagChange::FlagRemoved
+                })
+            }
+        }
+    }
+
+    // puts a question mark on a closed cell (replacing a flag), or removes it
+    pub fn toggle_question(&mut self, position: CellPosition) -> Result<FlagChange, FlagError> {
+        let cell = self.get_cell_mut(position).ok_or(FlagError::CellNotFound)?;
+
+        match &mut cell.state {
+            CellState::Bomb { exploded: true, .. } | CellState::Safe { open: true, .. } => {
+                Err(FlagError::AlreadyCleared)
+            }
+            CellState::Rock => Err(FlagError::Rock),
+            CellState::Bomb { flagged, .. } | CellState::Safe { flagged, .. } => {
+                *flagged = false;
+                cell.question = !cell.question;
+                Ok(if cell.question {
+                    FlagChange::QuestionPlaced
+                } else {
+                    FlagChange::QuestionRemoved
+                })
+            }
+        }
+    }
+
+    // whether a closed cell is a mine, which the cell then shows
+    pub fn peek(&mut self, position: CellPosition) -> Result<bool, FlagError> {
+        let cell = self.get_cell_mut(position).ok_or(FlagError::CellNotFound)?;
+
+        match cell.state {
+            CellState::Bomb { exploded: true, .. } | CellState::Safe { open: true, .. } => {
+                Err(FlagError::AlreadyCleared)
+            }
+            CellState::Rock => Err(FlagError::Rock),
+            CellState::Bomb { .. } => {
+                cell.peeked = true;
+                Ok(true)
+            }
+            CellState::Safe { .. } => {
+                cell.peeked = true;
+                Ok(false)
+            }
+        }
+    }
+
+    // the closed, unflagged neighbours that chording an open cell would
+    // clear, once the flags around it (and mines already set off) match
+    // its number
+    pub fn chord_targets(&self, position: CellPosition) -> Result<Vec<CellPosition>, ChordError> {
+        let preview = self.chord_preview(position)?;
+        if !preview.satisfied() {
+            return Err(ChordError::FlagsDontMatch {
+                flags: preview.flags,
+                bombs_around: preview.bombs_around,
+            });
+        }
+        // so that a chord that would do nothing isn't a move
+        if preview.targets.is_empty() {
+            return Err(ChordError::NothingToOpen);
+        }
+        Ok(preview.targets)
+    }
+
+    // what chording an open cell would do, without doing it; a flag on a
+    // safe cell counts as any other flag, as it does to the player
+    pub fn chord_preview(&self, position: CellPosition) -> Result<ChordPreview, ChordError> {
+        let cell = self.get_cell(position).ok_or(ChordError::CellNotFound)?;
+        if !matches!(cell.state, CellState::Safe { open: true, .. }) {
+            return Err(ChordError::NotOpen);
+        }
+
+        let flags = self
+            .cells_around(position)
+            .filter(|cell| {
+                matches!(
+                    cell.state,
+                    CellState::Bomb { flagged: true, .. }
+                        | CellState::Bomb { exploded: true, .. }
+                        | CellState::Safe { flagged: true, .. }
+                )
+            })
+            .count();
+        let targets = self
+            .cells_around(position)
+            .filter(|cell| {
+                matches!(
+                    cell.state,
+                    CellState::Bomb {
+                        flagged: false,
+                        exploded: false
+                    } | CellState::Safe {
+                        flagged: false,
+                        open: false
+                    }
+                )
+            })
+            .map(|cell| cell.position)
+            .collect();
+
+        Ok(ChordPreview {
+            targets,
+            flags,
+            bombs_around: cell.bombs_around,
+        })
+    }
+
+    // marks a bomb as set off without ending the game, for modes where
+    // hitting a bomb only costs a life; does nothing to safe cells
+    pub fn explode(&mut self, position: CellPosition) {
+        if let Some(Cell {
+            state: CellState::Bomb { flagged, exploded },
+            ..
+        }) = self.get_cell_mut(position)
+        {
+            *flagged = false;
+            *exploded = true;
+        }
+    }
+
+    // like `explode`, for a mine that a shield took the blast of
+    pub fn defuse(&mut self, position: CellPosition) {
+        self.explode(position);
+        if let Some(cell) = self.get_cell_mut(position) {
+            cell.defused = matches!(cell.state, CellState::Bomb { .. });
+        }
+    }
+
+    // where the mines (*) and rocks (o) are, a line per row, whatever has
+    // been opened or marked
+    pub fn layout(&self) -> String {
+        let symbols: Vec<char> = self
+            .board
+            .iter()
+            .map(|cell| match cell.state {
+                CellState::Bomb { .. } => '*',
+                CellState::Safe { .. } => '.',
+                CellState::Rock => 'o',
+            })
+            .collect();
+        symbols
+            .chunks(self.width().max(1))
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    // moves the mine at `from` to the closed safe cell at `to` and recounts
+    // the closed cells around both; open cells keep the numbers they showed
+    // when they were opened. False, changing nothing, unless both are
+    // closed and unflagged
+    pub fn move_mine(&mut self, from: CellPosition, to: CellPosition) -> bool {
+        let movable = matches!(
+            self.get_cell(from).map(|cell| &cell.state),
+            Some(CellState::Bomb {
+                flagged: false,
+                exploded: false
+            })
+        ) && matches!(
+            self.get_cell(to).map(|cell| &cell.state),
+            Some(CellState::Safe {
+                flagged: false,
+                open: false
+            })
+        );
+        if !movable {
+            return false;
+        }
+
+        if let Some(cell) = self.get_cell_mut(from) {
+            cell.state = CellState::Safe {
+                flagged: false,
+                open: false,
+            };
+        }
+        if let Some(cell) = self.get_cell_mut(to) {
+            cell.state = CellState::Bomb {
+                flagged: false,
+                exploded: false,
+            };
+        }
+        self.recount_around(from);
+        self.recount_around(to);
+        true
+    }
+
+    // the mines around each closed cell touching `position`, itself included
+    fn recount_around(&mut self, position: CellPosition) {
+        let counts: Vec<(CellPosition, u8)> = self
+            .cells_around(position)
+            .filter(|cell| !matches!(cell.state, CellState::Safe { open: true, .. }))
+            .map(|cell| {
+                let bombs = self
+                    .cells_around(cell.position)
+                    .filter(|around| matches!(around.state, CellState::Bomb { .. }))
+                    .count();
+                (cell.position, bombs as u8)
+            })
+            .collect();
+
+        for (position, bombs_around) in counts {
+            if let Some(cell) = self.get_cell_mut(position) {
+                cell.bombs_around = bombs_around;
+            }
+        }
+    }
+
+    // what clearing `position` would do, worked out from the cells' states
+    // without changing anything on the board
+    pub fn clear_preview(&self, position: CellPosition) -> Result<ClearOutcome, ClearError> {
+        let cell = self.get_cell(position).ok_or(ClearError::CellNotFound)?;
+        match cell.state {
+            CellState::Bomb { exploded: true, .. } => return Err(ClearError::AlreadyCleared),
+            CellState::Bomb { .. } => {
+                return Ok(ClearOutcome {
+                    opened: vec![],
+                    mine: true,
+                    open_count: self.count_open(),
+                })
+            }
+            CellState::Rock => return Err(ClearError::Rock),
+            CellState::Safe { open: true, .. } => return Err(ClearError::AlreadyCleared),
+            CellState::Safe { open: false, .. } => (),
+        }
+
+        let opened = cascade(&self.dims(), position, |position| {
+            let Some(cell) = self.get_cell(position) else {
+                return Ok(Reach::Stop);
+            };
+            match cell.state {
+                // cascades stop at rocks, and at cells already open; a mine
+                // is only reached from a zero, which it shouldn't be next to
+                CellState::Rock | CellState::Safe { open: true, .. } | CellState::Bomb { .. } => {
+                    return Ok(Reach::Stop)
+                }
+                CellState::Safe { open: false, .. } if cell.bombs_around != 0 => {
+                    return Ok(Reach::Open)
+                }
+                CellState::Safe { open: false, .. } => (),
+            }
+            let mine = self.cells_around(position).find(|around| {
+                matches!(
+                    around.state,
+                    CellState::Bomb {
+                        exploded: false,
+                        ..
+                    }
+                )
+            });
+            match mine {
+                Some(mine) => {
+                    let error = ClearError::Inconsistent {
+                        position,
+                        detail: fill(Msg::ZeroBesideMine, &[&mine.position]),
+                    };
+                    // fuzzing wants the panic, to stop on the first board
+                    // that goes wrong
+                    if cfg!(feature = "debug-checks") {
+                        panic!("{error}");
+                    }
+                    Err(error)
+                }
+                None => Ok(Reach::Spread),
+            }
+        })?;
+
+        Ok(ClearOutcome {
+            open_count: self.count_open() + opened.len(),
+            opened,
+            mine: false,
+        })
+    }
+
+    // opens the cells of an outcome `clear_preview` worked out for the board
+    // as it is now; one that hit a mine changes nothing
+    pub fn apply_clear(&mut self, outcome: &ClearOutcome) {
+        for &position in &outcome.opened {
+            if let Some(cell) = self.get_cell_mut(position) {
+                cell.state = CellState::Safe {
+                    open: true,
+                    flagged: false,
+                };
+                cell.question = false;
+            }
+        }
+    }
+
+    pub fn clear(&mut self, position: CellPosition) -> Result<(), ClearError> {
+        let outcome = self.clear_preview(position)?;
+        if outcome.mine {
+            return Err(ClearError::ClearedBomb);
+        }
+        self.apply_clear(&outcome);
+        Ok(())
+    }
+}
+
+// a layout as `Board::layout` writes it, with nothing opened or marked
+impl FromStr for Board {
+    type Err = LayoutError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut bombs: Vec<Vec<bool>> = vec![];
+        let mut rocks = vec![];
+        for (row_index, line) in s.lines().enumerate() {
+            let mut row = vec![];
+            for (col_index, symbol) in line.chars().enumerate() {
+                row.push(match symbol {
+                    '*' => true,
+                    '.' => false,
+                    'o' => {
+                        rocks.push(CellPosition {
+                            row_index,
+                            col_index,
+                        });
+                        false
+                    }
+                    _ => {
+                        return Err(LayoutError::UnexpectedSymbol {
+                            symbol,
+                            row: row_index,
+                            col: col_index,
+                        })
+                    }
+                });
+            }
+            if bombs.first().is_some_and(|first| first.len() != row.len()) {
+                return Err(LayoutError::RaggedRow { row: row_index });
+            }
+            bombs.push(row);
+        }
+
+        if bombs.first().is_none_or(|first| first.is_empty()) {
+            return Err(LayoutError::Empty);
+        }
+        let board = Board::from_bombs(bombs).with_rocks(&rocks);
+        match board.count_safe() {
+            0 => Err(LayoutError::NoSafeCells),
+            _ => Ok(board),
+        }
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let layout = CellLayout::text();
+        let out = self
+            .rows()
+            .map(|row| layout.row(row.iter().map(Cell::to_string)))
+            .collect::<Vec<String>>()
+            .join("\n");
+        write!(f, "{out}")
+    }
+}
+
+// the cells at most `radius` rows and `radius` columns from `position`, it
+// included, row by row with where each one is; the parts of the square off
+// the board are left out, and so is all of it for a position off the board
+pub fn cells_within<T>(
+    board: &[Vec<T>],
+    position: CellPosition,
+    radius: usize,
+) -> impl Iterator<Item = (CellPosition, &T)> {
+    let (rows, cols) = Dims::of(board).clamp_range(position, radius);
+    rows.flat_map(move |row_index| {
+        let row = &board[row_index];
+        cols.clone().map(move |col_index| {
+            let at = CellPosition {
+                row_index,
+                col_index,
+            };
+            (at, &row[col_index])
+        })
+    })
+}
+
+// the cell and the up to eight around it
+fn get_cells_around<T>(board: &[Vec<T>], position: CellPosition) -> impl Iterator<Item = &T> {
+    cells_within(board, position, 1).map(|(_, cell)| cell)
+}
+
+fn get_bombs_around(board: &[Vec<bool>], position: CellPosition) -> u8 {
+    let cells_around = get_cells_around(board, position);
+    let num_bombs_around = cells_around.filter(|&&is_bomb| is_bomb).count();
+    num_bombs_around as u8
+}
+
+// drawn from the cells without a bomb, after the bombs so that adding rocks
+// doesn't move them
+fn generate_rocks(rng: &mut impl Rng, bombs: &[Vec<bool>], rocks: usize) -> Vec<CellPosition> {
+    let safe: Vec<CellPosition> = bombs
+        .iter()
+        .enumerate()
+        .flat_map(|(row_index, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, &is_bomb)| !is_bomb)
+                .map(move |(col_index, _)| CellPosition {
+                    row_index,
+                    col_index,
+                })
+        })
+        .collect();
+
+    // with one safe cell left over to open
+    let rocks = rocks.min(safe.len().saturating_sub(1));
+    sample(rng, safe.len(), rocks)
+        .iter()
+        .map(|index| safe[index])
+        .collect()
+}
+
+fn generate_bombs(
+    rng: &mut impl Rng,
+    height: usize,
+    width: usize,
+    layout: &Layout,
+) -> Vec<Vec<bool>> {
+    let count = layout.mines.count_on(height * width);
+    if !layout.limits.is_none() {
+        return generate_limited_bombs(rng, height, width, count, layout.limits);
+    }
+    if layout.symmetry != Symmetry::None {
+        return generate_symmetric_bombs(rng, height, width, layout.mines, layout.symmetry);
+    }
+    match &layout.distribution {
+        MineDistribution::Uniform => (),
+        MineDistribution::Clustered => return generate_clustered_bombs(rng, height, width, count),
+        MineDistribution::Sparse => return generate_sparse_bombs(rng, height, width, count),
+        MineDistribution::Stencil(stencil) => {
+            return generate_stencil_bombs(rng, height, width, count, stencil)
+        }
+    }
+
+    match layout.mines {
+        Mines::Density(density) => (0..height)
+            .map(|_| (0..width).map(|_| rng.gen_bool(density)).collect())
+            .collect(),
+        Mines::Count(count) => {
+            let mut bombs = vec![vec![false; width]; height];
+            for index in sample(rng, height * width, count) {
+                bombs[index / width][index % width] = true;
+            }
+            bombs
+        }
+    }
+}
+
+// about one pocket for this many cells, each spread over about an eighth of
+// the board's side
+const CELLS_PER_CLUSTER: usize = 80;
+
+// each cell is picked with a weight that is highest near the centres of a
+// few pockets placed at random, with a little left over everywhere so that
+// the plains between them aren't entirely empty
+fn generate_clustered_bombs(
+    rng: &mut impl Rng,
+    height: usize,
+    width: usize,
+    count: usize,
+) -> Vec<Vec<bool>> {
+    let clusters = (height * width / CELLS_PER_CLUSTER).max(1);
+    let centres: Vec<(f64, f64)> = (0..clusters)
+        .map(|_| {
+            (
+                rng.gen_range(0.0..height as f64),
+                rng.gen_range(0.0..width as f64),
+            )
+        })
+        .collect();
+    let spread = (height.min(width) as f64 / 8.0).max(1.0);
+
+    let weight = |row: usize, col: usize| {
+        let pull: f64 = centres
+            .iter()
+            .map(|&(centre_row, centre_col)| {
+                let distance =
+                    (row as f64 - centre_row).powi(2) + (col as f64 - centre_col).powi(2);
+                (-distance / (2.0 * spread * spread)).exp()
+            })
+            .sum();
+        pull + 0.02
+    };
+    choose_weighted(rng, height, width, count, weight)
+}
+
+// a marked cell of a stencil is this many times as likely to get a mine as
+// an unmarked one
+const STENCIL_WEIGHT: f64 = 10.0;
+
+// the stencil is expected to be the size of the board
+fn generate_stencil_bombs(
+    rng: &mut impl Rng,
+    height: usize,
+    width: usize,
+    count: usize,
+    stencil: &Stencil,
+) -> Vec<Vec<bool>> {
+    choose_weighted(rng, height, width, count, |row, col| {
+        match stencil.marks.get(row).and_then(|marks| marks.get(col)) {
+            Some(true) => STENCIL_WEIGHT,
+            _ => 1.0,
+        }
+    })
+}
+
+// exactly `count` cells, each picked with a chance in proportion to its
+// weight among the cells not picked yet: every cell gets a random key that
+// grows with its weight, and the cells with the biggest keys are picked
+fn choose_weighted(
+    rng: &mut impl Rng,
+    height: usize,
+    width: usize,
+    count: usize,
+    weight: impl Fn(usize, usize) -> f64,
+) -> Vec<Vec<bool>> {
+    let cells: Vec<(usize, usize)> = (0..height)
+        .flat_map(|row| (0..width).map(move |col| (row, col)))
+        .collect();
+
+    let mut keyed: Vec<(f64, (usize, usize))> = cells
+        .into_iter()
+        .map(|(row, col)| (rng.gen::<f64>().powf(1.0 / weight(row, col)), (row, col)))
+        .collect();
+    keyed.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let mut bombs = vec![vec![false; width]; height];
+    for (_, (row, col)) in keyed.into_iter().take(count) {
+        bombs[row][col] = true;
+    }
+    bombs
+}
+
+// mines go down in a random order of the cells, each only if no mine is
+// within `spacing` rows and columns of it; the spacing starts at what an
+// even spread would give and shrinks until every mine fits, which it
+// always does at 0
+fn generate_sparse_bombs(
+    rng: &mut impl Rng,
+    height: usize,
+    width: usize,
+    count: usize,
+) -> Vec<Vec<bool>> {
+    let mut cells: Vec<(usize, usize)> = (0..height)
+        .flat_map(|row| (0..width).map(move |col| (row, col)))
+        .collect();
+    cells.shuffle(rng);
+
+    let even = ((height * width) as f64 / count.max(1) as f64).sqrt() as usize;
+    for spacing in (0..even.max(1)).rev() {
+        let mut bombs = vec![vec![false; width]; height];
+        let mut placed = 0;
+        for &(row, col) in &cells {
+            if placed == count {
+                break;
+            }
+            let position = CellPosition {
+                row_index: row,
+                col_index: col,
+            };
+            let crowded = cells_within(&bombs, position, spacing).any(|(_, &is_bomb)| is_bomb);
+            if !crowded {
+                bombs[row][col] = true;
+                placed += 1;
+            }
+        }
+        if placed == count || spacing == 0 {
+            return bombs;
+        }
+    }
+    unreachable!("A spacing of 0 fits every mine")
+}
+
+// how often a random share of the mines between the columns is tried
+// before falling back on the most even one
+const LIMIT_ATTEMPTS: usize = 20;
+
+// `total` shared out at random between `lines` lines, each getting between
+// `min` and `max`
+fn spread(rng: &mut impl Rng, total: usize, lines: usize, min: usize, max: usize) -> Vec<usize> {
+    let mut counts = vec![min; lines];
+    let mut open: Vec<usize> = (0..lines).filter(|_| min < max).collect();
+    for _ in 0..total.saturating_sub(min * lines) {
+        let Some(&line) = open.choose(rng) else {
+            break;
+        };
+        counts[line] += 1;
+        if counts[line] == max {
+            open.retain(|&open| open != line);
+        }
+    }
+    counts
+}
+
+// the mines of each row put in the columns with the most still to get
+// (ties broken at random), which lays out the counts whenever any layout
+// can; None if this share between the columns can't be
+fn fill_lines(
+    rng: &mut impl Rng,
+    rows: &[usize],
+    mut columns: Vec<usize>,
+) -> Option<Vec<Vec<bool>>> {
+    let width = columns.len();
+    let mut bombs = vec![vec![false; width]; rows.len()];
+    for (row_index, &count) in rows.iter().enumerate() {
+        let mut order: Vec<usize> = (0..width).collect();
+        order.shuffle(rng);
+        order.sort_by_key(|&col_index| Reverse(columns[col_index]));
+        for &col_index in &order[..count] {
+            if columns[col_index] == 0 {
+                return None;
+            }
+            columns[col_index] -= 1;
+            bombs[row_index][col_index] = true;
+        }
+    }
+    Some(bombs)
+}
+
+// exactly `mines` mines, with every row and column within `limits`; the
+// rows get a random share, and so do the columns unless no layout fits,
+// in which case they get the most even share, which always does
+fn generate_limited_bombs(
+    rng: &mut impl Rng,
+    height: usize,
+    width: usize,
+    mines: usize,
+    limits: LineLimits,
+) -> Vec<Vec<bool>> {
+    let (row_min, row_max) = limits.bounds(Line::Row, width);
+    let (col_min, col_max) = limits.bounds(Line::Column, height);
+    let rows = spread(rng, mines, height, row_min, row_max);
+
+    for _ in 0..LIMIT_ATTEMPTS {
+        let columns = spread(rng, mines, width, col_min, col_max);
+        if let Some(bombs) = fill_lines(rng, &rows, columns) {
+            return bombs;
+        }
+    }
+
+    let mut columns = vec![mines / width; width];
+    for index in sample(rng, width, mines % width) {
+        columns[index] += 1;
+    }
+    fill_lines(rng, &rows, columns).expect("The most even share of mines should always fit")
+}
+
+// mines are chosen among pairs of a cell and its image, and among the cells
+// that are their own image; an odd count needs one of the latter, and
+// without any the count is one short
+fn generate_symmetric_bombs(
+    rng: &mut impl Rng,
+    height: usize,
+    width: usize,
+    mines: Mines,
+    symmetry: Symmetry,
+) -> Vec<Vec<bool>> {
+    let mut pairs = vec![];
+    let mut axis = vec![];
+    for row_index in 0..height {
+        for col_index in 0..width {
+            let position = CellPosition {
+                row_index,
+                col_index,
+            };
+            let image = symmetry.image(height, width, position);
+            if image == position {
+                axis.push(position);
+            } else if (row_index, col_index) < (image.row_index, image.col_index) {
+                pairs.push(position);
+            }
+        }
+    }
+
+    let chosen: Vec<CellPosition> = match mines {
+        Mines::Density(density) => pairs
+            .iter()
+            .chain(&axis)
+            .copied()
+            .filter(|_| rng.gen_bool(density))
+            .collect(),
+        Mines::Count(count) => {
+            // as many on the axis as it would get by chance, give or take
+            // one to match the parity of the count
+            let cells = height * width;
+            let mut on_axis = (count * axis.len()).div_ceil(cells.max(1));
+            if on_axis % 2 != count % 2 {
+                on_axis = if on_axis < axis.len() {
+                    on_axis + 1
+                } else {
+                    on_axis.saturating_sub(1)
+                };
+            }
+            let on_axis = on_axis
+                .max(count.saturating_sub(2 * pairs.len()))
+                .min(axis.len())
+                .min(count);
+            let paired = ((count - on_axis) / 2).min(pairs.len());
+
+            let from_pairs = sample(rng, pairs.len(), paired)
+                .into_iter()
+                .map(|index| pairs[index]);
+            let from_axis = sample(rng, axis.len(), on_axis)
+                .into_iter()
+                .map(|index| axis[index]);
+            from_pairs.chain(from_axis).collect()
+        }
+    };
+
+    let mut bombs = vec![vec![false; width]; height];
+    for position in chosen {
+        let image = symmetry.image(height, width, position);
+        bombs[position.row_index][position.col_index] = true;
+        bombs[image.row_index][image.col_index] = true;
+    }
+    bombs
+}
@@ -0,0 +1,335 @@
+use crate::config::Config;
+use crate::driver::{GameOutput, Screen};
+use crate::i18n::{fill, text, Msg};
+use crate::json::Value;
+use crate::render::render_status;
+use crate::view::GameView;
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+
+// frames waiting for a spectator who is slow to read them; more than this
+// and the oldest are dropped for them, rather than holding up the player,
+// so that they still end up with the board as it is now
+const QUEUE: usize = 16;
+
+#[derive(Default)]
+struct Queue {
+    // the frames, and whether the spectator has gone away
+    frames: Mutex<(VecDeque<String>, bool)>,
+    ready: Condvar,
+}
+
+impl Queue {
+    // false once there is nobody to send to
+    fn push(&self, frame: String) -> bool {
+        let Ok(mut frames) = self.frames.lock() else {
+            return false;
+        };
+        let (queued, gone) = &mut *frames;
+        if *gone {
+            return false;
+        }
+        if queued.len() == QUEUE {
+            queued.pop_front();
+        }
+        queued.push_back(frame);
+        self.ready.notify_one();
+        true
+    }
+
+    fn pop(&self) -> Option<String> {
+        let frames = self.frames.lock().ok()?;
+        let mut frames = self
+            .ready
+            .wait_while(frames, |(queued, _)| queued.is_empty())
+            .ok()?;
+        frames.0.pop_front()
+    }
+
+    fn close(&self) {
+        if let Ok(mut frames) = self.frames.lock() {
+            frames.1 = true;
+        }
+    }
+}
+
+#[derive(Default)]
+struct Spectators {
+    queues: Vec<Arc<Queue>>,
+    // for whoever connects next, so that they don't wait for a move to see
+    // the board
+    latest: Option<String>,
+}
+
+// sends the game to spectators on localhost as it is played; they are never
+// sent anything the player can't see, and can't send anything back
+pub struct Broadcast {
+    json: bool,
+    spectators: Arc<Mutex<Spectators>>,
+}
+
+// each spectator's frames are written on a thread of their own
+fn watch(mut stream: TcpStream, queue: Arc<Queue>) {
+    thread::spawn(move || {
+        while let Some(frame) = queue.pop() {
+            if writeln!(stream, "{frame}").is_err() {
+                break;
+            }
+        }
+        queue.close();
+    });
+}
+
+impl Broadcast {
+    pub fn start(port: u16, json: bool) -> io::Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+        println!(
+            "{}",
+            fill(Msg::BroadcastListening, &[&listener.local_addr()?])
+        );
+        Ok(Self::on(listener, json))
+    }
+
+    // as `start`, on a listener already bound
+    pub fn on(listener: TcpListener, json: bool) -> Self {
+        let spectators: Arc<Mutex<Spectators>> = Arc::default();
+        let joining = spectators.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let queue = Arc::new(Queue::default());
+                let Ok(mut spectators) = joining.lock() else {
+                    break;
+                };
+                if let Some(latest) = &spectators.latest {
+                    queue.push(latest.clone());
+                }
+                spectators.queues.push(queue.clone());
+                watch(stream, queue);
+            }
+        });
+
+        Broadcast { json, spectators }
+    }
+
+    // never waits on a spectator, and forgets those who have gone away
+    fn send(&self, frame: String) {
+        let Ok(mut spectators) = self.spectators.lock() else {
+            return;
+        };
+        spectators.queues.retain(|queue| queue.push(frame.clone()));
+        spectators.latest = Some(frame);
+    }
+
+    // `status` is the line the player sees over the board
+    fn frame(&self, view: &GameView, status: &str) -> String {
+        if self.json {
+            Value::object([
+                ("state", Value::from(view.state().name())),
+                ("mines_left", Value::from(view.counters().mines_left as f64)),
+                ("moves", Value::from(view.counters().moves)),
+                ("board", view.board_json()),
+            ])
+            .to_string()
+        } else {
+            format!("{status}\n{}\n------", view.board())
+        }
+    }
+}
+
+// shows the game to the player with `output`, and to the spectators of
+// `broadcast`
+pub struct Broadcasting<'a> {
+    pub output: &'a mut dyn GameOutput,
+    pub broadcast: &'a Broadcast,
+    pub config: &'a Config,
+}
+
+impl GameOutput for Broadcasting<'_> {
+    fn show(&mut self, screen: &Screen) {
+        match screen {
+            // the mines shown at the end stay with the player, like the rest
+            // of what is hidden
+            Screen::Board(game) | Screen::Revealed(game) => {
+                let status = render_status(game, self.config);
+                let frame = self.broadcast.frame(&GameView::of(game), &status);
+                self.broadcast.send(frame)
+            }
+            Screen::Paused if self.broadcast.json => self
+                .broadcast
+                .send(Value::object([("state", Value::from("paused"))]).to_string()),
+            Screen::Paused => self.broadcast.send(text(Msg::Paused).to_string()),
+            _ => (),
+        }
+        self.output.show(screen);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::driver::{run_game, GameConfig, GameInput, Hooks, Io};
+    use crate::game::Game;
+    use crate::json::parse;
+    use std::collections::VecDeque;
+    use std::io::{BufRead, BufReader, Lines};
+    use std::net::SocketAddr;
+    use std::time::{Duration, Instant};
+
+    // walled off, so that each clear opens the one cell
+    const LAYOUT: &str = "..*\n*.*\n**.";
+
+    struct Script(VecDeque<&'static str>);
+
+    impl GameInput for Script {
+        fn read_command(&mut self) -> io::Result<Option<String>> {
+            Ok(self.0.pop_front().map(String::from))
+        }
+    }
+
+    // how many boards the player was shown
+    #[derive(Default)]
+    struct Counted(usize);
+
+    impl GameOutput for Counted {
+        fn show(&mut self, screen: &Screen) {
+            if matches!(screen, Screen::Board(_) | Screen::Revealed(_)) {
+                self.0 += 1;
+            }
+        }
+    }
+
+    fn start(json: bool) -> (Broadcast, SocketAddr) {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).expect("A port should be free");
+        let address = listener.local_addr().expect("The listener has an address");
+        (Broadcast::on(listener, json), address)
+    }
+
+    // a spectator, once the broadcast has taken them on
+    fn spectate(broadcast: &Broadcast, address: SocketAddr) -> Lines<BufReader<TcpStream>> {
+        let before = spectators(broadcast);
+        let stream = TcpStream::connect(address).expect("The broadcast should accept");
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .expect("The timeout should be set");
+        while spectators(broadcast) == before {
+            thread::sleep(Duration::from_millis(5));
+        }
+        BufReader::new(stream).lines()
+    }
+
+    fn spectators(broadcast: &Broadcast) -> usize {
+        broadcast
+            .spectators
+            .lock()
+            .map_or(0, |spectators| spectators.queues.len())
+    }
+
+    // the game played to a win, with the boards the player was shown
+    fn play(broadcast: &Broadcast) -> usize {
+        let config = Config::default();
+        let mut input = Script(VecDeque::from(["c 0 0", "c 0 1", "c 1 1", "c 2 2"]));
+        let mut shown = Counted::default();
+        let mut output = Broadcasting {
+            output: &mut shown,
+            broadcast,
+            config: &config,
+        };
+        let io = Io {
+            input: &mut input,
+            output: &mut output,
+        };
+        let game = GameConfig {
+            game: Game::new(LAYOUT.parse().expect("The layout should parse"), 1),
+            retries: Some(3),
+            idle_limit: None,
+            prompt: Msg::EnterRowOrCommand,
+            players: 1,
+        };
+        run_game(game, io, Hooks::default());
+        shown.0
+    }
+
+    #[test]
+    fn a_spectator_gets_a_frame_for_every_board_the_player_sees() {
+        let (broadcast, address) = start(false);
+        let mut lines = spectate(&broadcast, address);
+        let shown = play(&broadcast);
+
+        let mut statuses = vec![];
+        for _ in 0..shown {
+            let frame: Vec<String> = lines
+                .by_ref()
+                .map(|line| line.expect("The frame should be read"))
+                .take_while(|line| line != "------")
+                .collect();
+            assert_eq!(frame.len(), 1 + 3, "{frame:?}");
+            statuses.push(frame[0].clone());
+        }
+        // the board before the first move, after each of the three that
+        // don't win, and at the end after the fourth
+        assert_eq!(shown, 5);
+        for (status, moves) in statuses.iter().zip([0, 1, 2, 3, 4]) {
+            assert!(status.ends_with(&format!("Moves: {moves}")), "{status}");
+        }
+    }
+
+    #[test]
+    fn json_frames_are_one_line_a_move() {
+        let (broadcast, address) = start(true);
+        let mut lines = spectate(&broadcast, address);
+        let shown = play(&broadcast);
+
+        let moves: Vec<u64> = lines
+            .by_ref()
+            .take(shown)
+            .map(|line| {
+                let frame = parse(&line.expect("The frame should be read"))
+                    .expect("The frame should be JSON");
+                frame.get("moves").and_then(Value::as_u64).unwrap_or(99)
+            })
+            .collect();
+        assert_eq!(moves, [0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn a_spectator_who_stops_reading_doesnt_hold_up_the_game() {
+        let (broadcast, address) = start(false);
+        // connected, and never read from
+        let _stalled = spectate(&broadcast, address);
+        let mut reading = spectate(&broadcast, address);
+
+        // far more than the connection can hold unread
+        let frame = "#".repeat(64 * 1024);
+        let started = Instant::now();
+        for index in 0..500 {
+            broadcast.send(format!("{index} {frame}"));
+        }
+        assert!(started.elapsed() < Duration::from_secs(2));
+
+        // the stalled spectator's frames are dropped past the queue's
+        // length, and the reading one still gets to the last frame
+        let queued = broadcast
+            .spectators
+            .lock()
+            .map(|spectators| {
+                spectators
+                    .queues
+                    .iter()
+                    .map(|queue| queue.frames.lock().map_or(0, |frames| frames.0.len()))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .unwrap_or(0);
+        assert!(queued <= QUEUE, "{queued}");
+        let last = reading
+            .by_ref()
+            .map(|line| line.expect("The frame should be read"))
+            .find(|line| line.starts_with("499 "));
+        assert!(last.is_some());
+        // and with the game over, the player goes on as before
+        assert_eq!(play(&broadcast), 5);
+    }
+}
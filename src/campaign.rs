@@ -0,0 +1,277 @@
+use crate::board::{Board, CellPosition};
+use crate::command::Command;
+use crate::config::Config;
+use crate::game::{Game, GameOutcome};
+use crate::i18n::{fill, text, Msg};
+use crate::input;
+use crate::output::StdoutOutput;
+use crate::run::{run_game, GameOptions};
+use crate::stats::StatsFile;
+use crate::timer::format_duration;
+use std::time::Duration;
+
+// each is a `name` line, a `start` line with the cell the level opens with,
+// then the layout, with * for a mine; they get harder as they go, and every
+// one can be cleared from its start without guessing
+const LEVEL_FILES: [&str; 12] = [
+    include_str!("../assets/campaign/01.txt"),
+    include_str!("../assets/campaign/02.txt"),
+    include_str!("../assets/campaign/03.txt"),
+    include_str!("../assets/campaign/04.txt"),
+    include_str!("../assets/campaign/05.txt"),
+    include_str!("../assets/campaign/06.txt"),
+    include_str!("../assets/campaign/07.txt"),
+    include_str!("../assets/campaign/08.txt"),
+    include_str!("../assets/campaign/09.txt"),
+    include_str!("../assets/campaign/10.txt"),
+    include_str!("../assets/campaign/11.txt"),
+    include_str!("../assets/campaign/12.txt"),
+];
+
+pub struct Level {
+    pub name: String,
+    pub start: CellPosition,
+    pub board: Board,
+}
+
+pub fn parse_level(text: &str) -> Result<Level, String> {
+    let mut lines = text.lines();
+    let name = lines
+        .next()
+        .and_then(|line| line.strip_prefix("name "))
+        .ok_or("expected name")?
+        .to_string();
+    let start = lines
+        .next()
+        .and_then(|line| line.strip_prefix("start "))
+        .and_then(|start| start.split_once(' '))
+        .and_then(|(row, col)| Some((row.parse().ok()?, col.parse().ok()?)))
+        .map(|(row_index, col_index)| CellPosition {
+            row_index,
+            col_index,
+        })
+        .ok_or("expected start")?;
+
+    let mut bombs = vec![];
+    for line in lines.filter(|line| !line.is_empty()) {
+        let row = line
+            .chars()
+            .map(|symbol| match symbol {
+                '*' => Ok(true),
+                '.' => Ok(false),
+                _ => Err(format!("unexpected {symbol} in layout")),
+            })
+            .collect::<Result<Vec<bool>, String>>()?;
+        if bombs
+            .first()
+            .is_some_and(|first: &Vec<bool>| first.len() != row.len())
+        {
+            return Err(String::from("layout rows have different lengths"));
+        }
+        bombs.push(row);
+    }
+    if bombs.is_empty() || start.row_index >= bombs.len() || start.col_index >= bombs[0].len() {
+        return Err(String::from("start is off the board"));
+    }
+
+    Ok(Level {
+        name,
+        start,
+        board: Board::from_bombs(bombs),
+    })
+}
+
+// the bundled levels, in order; the tests check that every one parses and
+// can be solved from its start, so that a broken level can't slip in
+pub fn levels() -> Vec<Level> {
+    LEVEL_FILES
+        .iter()
+        .enumerate()
+        .map(|(index, file)| {
+            parse_level(file)
+                .unwrap_or_else(|e| panic!("Campaign level {} should parse: {e}", index + 1))
+        })
+        .collect()
+}
+
+// levels are numbered from 1; each unlocks once the one before it is won
+fn best(stats: &StatsFile, number: usize) -> Option<Duration> {
+    stats
+        .campaign
+        .iter()
+        .find(|&&(level, _)| level == number)
+        .map(|&(_, best)| best)
+}
+
+pub fn render_progress(levels: &[Level], stats: &StatsFile) -> String {
+    let mut unlocked = true;
+    levels
+        .iter()
+        .enumerate()
+        .map(|(index, level)| {
+            let number = index + 1;
+            let status = match best(stats, number) {
+                Some(best) => fill(Msg::CampaignDone, &[&format_duration(best)]),
+                None if unlocked => {
+                    unlocked = false;
+                    text(Msg::CampaignNext).to_string()
+                }
+                None => text(Msg::CampaignLocked).to_string(),
+            };
+            format!("{number:>2}. {:<20}{status}", level.name)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// keeps the better of `elapsed` and any earlier time for the level
+pub fn record(stats: &mut StatsFile, number: usize, elapsed: Duration) {
+    match stats
+        .campaign
+        .iter_mut()
+        .find(|(level, _)| *level == number)
+    {
+        Some((_, best)) => *best = (*best).min(elapsed),
+        None => stats.campaign.push((number, elapsed)),
+    }
+}
+
+// shows how far the player has got, then plays the first level not yet won
+pub fn run(config: &Config) -> String {
+    let levels = levels();
+    let mut stats = StatsFile::load();
+    println!("{}", render_progress(&levels, &stats));
+
+    let Some(index) = (0..levels.len()).find(|&index| best(&stats, index + 1).is_none()) else {
+        return text(Msg::CampaignComplete).to_string();
+    };
+    let level = &levels[index];
+    let number = index + 1;
+    let code = fill(Msg::CampaignLevel, &[&number, &level.name]);
+    println!("{code}");
+
+    let mut game = Game::new(level.board.clone(), 1);
+    game.apply(0, Command::Clear(level.start))
+        .expect("A level's start should be a safe cell on its board");
+
+    let options = GameOptions {
+        game,
+        code,
+        // a level is played from the start each time, and its best time is
+        // kept apart from the stats of ordinary games
+        autosave: false,
+        stats: false,
+        idle_limit: config.idle_limit(),
+        requested: None,
+        config,
+    };
+    let mut input = input::stdin_input(config);
+    let outcome = run_game(&mut *input, &mut StdoutOutput { config }, options);
+
+    match outcome {
+        GameOutcome::Won(game_stats) => {
+            record(&mut stats, number, game_stats.elapsed);
+            stats.save();
+            match levels.get(number) {
+                Some(next) => fill(Msg::CampaignUnlocked, &[&number, &(number + 1), &next.name]),
+                None => text(Msg::CampaignComplete).to_string(),
+            }
+        }
+        outcome => outcome.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::CellState;
+    use crate::solver::solves;
+
+    fn secs(seconds: u64) -> Duration {
+        Duration::from_secs(seconds)
+    }
+
+    #[test]
+    fn every_bundled_level_parses_and_solves_from_its_start() {
+        let levels = levels();
+        assert!((10..=15).contains(&levels.len()));
+
+        for (index, level) in levels.iter().enumerate() {
+            let number = index + 1;
+            assert!(!level.name.is_empty(), "level {number}");
+            let start = level
+                .board
+                .get_cell(level.start)
+                .unwrap_or_else(|| panic!("Level {number} should start on its board"));
+            assert!(
+                matches!(start.state, CellState::Safe { .. }),
+                "Level {number} should start on a safe cell"
+            );
+            assert!(level.board.count_bombs() > 0, "level {number}");
+            assert!(
+                solves(&level.board, level.start),
+                "Level {number} should be solvable without guessing"
+            );
+        }
+
+        // and no two share a name, and none is smaller than the one before
+        for (index, pair) in levels.windows(2).enumerate() {
+            let cells = |level: &Level| level.board.height() * level.board.width();
+            assert_ne!(pair[0].name, pair[1].name);
+            assert!(cells(&pair[1]) >= cells(&pair[0]), "level {}", index + 2);
+        }
+    }
+
+    #[test]
+    fn a_level_must_be_well_formed() {
+        let level = parse_level("name Test\nstart 1 0\n.*\n..\n").expect("The level should parse");
+        assert_eq!(
+            (level.name.as_str(), level.start),
+            ("Test", CellPosition::new(1, 0))
+        );
+        assert_eq!(level.board.count_bombs(), 1);
+
+        let broken = [
+            ("start 1 0\n..\n", "expected name"),
+            ("name Test\n..\n", "expected start"),
+            ("name Test\nstart 0 0\n.x\n", "unexpected x in layout"),
+            (
+                "name Test\nstart 0 0\n..\n.\n",
+                "layout rows have different lengths",
+            ),
+            ("name Test\nstart 2 0\n..\n..\n", "start is off the board"),
+            ("name Test\nstart 0 0\n", "start is off the board"),
+        ];
+        for (text, error) in broken {
+            assert_eq!(parse_level(text).err().as_deref(), Some(error), "{text}");
+        }
+    }
+
+    #[test]
+    fn winning_a_level_unlocks_the_next() {
+        let levels = levels();
+        let status = |stats: &StatsFile| -> Vec<String> {
+            render_progress(&levels, stats)
+                .lines()
+                .map(|line| line[24..].to_string())
+                .collect()
+        };
+
+        let mut stats = StatsFile::default();
+        let fresh = status(&stats);
+        assert_eq!(fresh[0], "next");
+        assert!(fresh[1..].iter().all(|status| status == "locked"));
+
+        record(&mut stats, 1, secs(41));
+        let after = status(&stats);
+        assert_eq!(after[..3], ["won, best 00:41", "next", "locked"]);
+
+        // a slower win keeps the best, a faster one replaces it
+        record(&mut stats, 1, secs(50));
+        assert_eq!(stats.campaign, [(1, secs(41))]);
+        record(&mut stats, 1, secs(30));
+        assert_eq!(stats.campaign, [(1, secs(30))]);
+        assert_eq!(best(&stats, 1), Some(secs(30)));
+        assert_eq!(best(&stats, 2), None);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::config::Config;
+use crate::game::Game;
+use crate::json::Value;
+use crate::screen::{terminal_for, RedrawOutput};
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+// the time between moves when the game has none recorded, as when it was
+// loaded from a save
+pub const INTERVAL: Duration = Duration::from_secs(1);
+
+// wide enough for the status line on a narrow board
+const MIN_COLUMNS: usize = 80;
+
+// one event of a cast: the seconds since it started, and what was written
+fn output(at: Duration, data: &str) -> String {
+    // a terminal turns a newline into a carriage return and a newline, and
+    // the cast has to hold what it would have shown
+    let data = data.replace('\n', "\r\n");
+    Value::Array(vec![
+        Value::from(at.as_secs_f64()),
+        Value::from("o"),
+        Value::from(data),
+    ])
+    .to_string()
+}
+
+// the game as an asciinema (v2) recording of redraw mode: a header line with
+// the size of the terminal, then the screen as it was drawn after each move,
+// as long after the one before as the player took to make it, and the
+// revealed board once the game is over
+pub fn render_cast(game: &Game, code: &str, config: &Config) -> String {
+    let mut replayed = game.restart();
+    let (columns, rows) = terminal_for(replayed.board(), config.output.theme);
+    let terminal = (columns.max(MIN_COLUMNS), rows);
+
+    let header = Value::object([
+        ("version", Value::from(2_usize)),
+        ("width", Value::from(terminal.0)),
+        ("height", Value::from(terminal.1)),
+        ("title", Value::from(format!("Minesweeper {code}"))),
+    ]);
+    let timed = game
+        .moves()
+        .iter()
+        .any(|game_move| !game_move.thinking.is_zero());
+
+    let mut screen = RedrawOutput::new(config);
+    let mut at = Duration::ZERO;
+    let mut events = vec![
+        header.to_string(),
+        output(at, &screen.frame(&replayed, terminal)),
+    ];
+    for game_move in game.moves() {
+        replayed
+            .apply(game_move.player, game_move.command.clone())
+            .expect("Moves in the log should be valid when replayed in order");
+        at += if timed { game_move.thinking } else { INTERVAL };
+        events.push(output(at, &screen.frame(&replayed, terminal)));
+    }
+    if replayed.outcome().is_some() {
+        events.push(output(at + INTERVAL, &screen.ending(&replayed)));
+    }
+
+    events.join("\n") + "\n"
+}
+
+pub fn write_cast(game: &Game, code: &str, config: &Config, path: &Path) -> io::Result<()> {
+    fs::write(path, render_cast(game, code, config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{Board, CellPosition};
+    use crate::command::Command;
+    use crate::json::{parse, Value};
+    use crate::stats::tests::SetByHand;
+    use std::sync::Arc;
+
+    // four safe cells walled off from each other by mines
+    const LAYOUT: &str = "..*\n*.*\n**.";
+
+    // each line of the cast as JSON, the header first
+    fn lines(cast: &str) -> Vec<Value> {
+        assert!(cast.ends_with('\n'));
+        cast.lines()
+            .map(|line| parse(line).expect("Every line should be JSON"))
+            .collect()
+    }
+
+    // the seconds and the data of an output event
+    fn event(line: &Value) -> (f64, &str) {
+        let Value::Array(fields) = line else {
+            panic!("An event should be an array: {line}");
+        };
+        assert_eq!(fields.len(), 3, "{line}");
+        assert_eq!(fields[1].as_str(), Some("o"));
+        let at = fields[0]
+            .as_f64()
+            .expect("An event should start with its time");
+        let data = fields[2]
+            .as_str()
+            .expect("An event should end with its output");
+        (at, data)
+    }
+
+    fn clears(game: &mut Game, clock: &SetByHand, thinking: &[u64]) {
+        let cells = [(0, 0), (0, 1), (1, 1), (2, 2)];
+        let mut now = 0;
+        for (&(row, col), &seconds) in cells.iter().zip(thinking) {
+            clock.set(now);
+            game.prompted();
+            now += seconds;
+            clock.set(now);
+            game.answered(None);
+            game.apply(0, Command::Clear(CellPosition::new(row, col)))
+                .expect("The cell should open");
+        }
+    }
+
+    #[test]
+    fn the_header_gives_the_format_the_terminal_and_the_title() {
+        let game = Game::new(LAYOUT.parse().expect("The layout should parse"), 1);
+        let config = Config::default();
+        let cast = render_cast(&game, "#7", &config);
+        let header = &lines(&cast)[0];
+
+        let (_, rows) = terminal_for(game.board(), config.output.theme);
+        assert_eq!(header.get("version").and_then(Value::as_u64), Some(2));
+        assert_eq!(header.get("width").and_then(Value::as_u64), Some(80));
+        assert_eq!(
+            header.get("height").and_then(Value::as_u64),
+            Some(rows as u64)
+        );
+        assert_eq!(
+            header.get("title").and_then(Value::as_str),
+            Some("Minesweeper #7")
+        );
+    }
+
+    #[test]
+    fn a_won_game_has_a_frame_per_move_at_its_recorded_times_and_an_ending() {
+        let board: Board = LAYOUT.parse().expect("The layout should parse");
+        let clock = Arc::new(SetByHand::default());
+        let mut game = Game::with_clock(board, 1, clock.clone());
+        clears(&mut game, &clock, &[2, 3, 5, 1]);
+        assert!(game.outcome().is_some());
+
+        let cast = render_cast(&game, "#7", &Config::default());
+        let lines = lines(&cast);
+        let events: Vec<(f64, &str)> = lines[1..].iter().map(event).collect();
+        // the board before the first move, one after each, and the ending
+        assert_eq!(events.len(), 1 + 4 + 1);
+        let times: Vec<f64> = events.iter().map(|&(at, _)| at).collect();
+        assert_eq!(times, [0.0, 2.0, 5.0, 10.0, 11.0, 12.0]);
+        // every newline is written as the terminal would have shown it
+        for (_, data) in &events {
+            assert!(!data.replace("\r\n", "").contains('\n'), "{data:?}");
+        }
+        assert!(events[0].1.starts_with("\u{1b}[H\u{1b}[2J"));
+    }
+
+    #[test]
+    fn moves_without_timings_are_a_second_apart() {
+        let mut game = Game::new(LAYOUT.parse().expect("The layout should parse"), 1);
+        for col in [0, 1] {
+            game.apply(0, Command::Clear(CellPosition::new(0, col)))
+                .expect("The cell should open");
+        }
+
+        let cast = render_cast(&game, "#7", &Config::default());
+        let times: Vec<f64> = lines(&cast)[1..].iter().map(|line| event(line).0).collect();
+        // and with the game still going, there is no ending
+        assert_eq!(times, [0.0, 1.0, 2.0]);
+    }
+}
@@ -0,0 +1,330 @@
+use crate::history::HistoryQuery;
+use crate::i18n::{fill, text, Lang, Msg};
+use std::fmt::Display;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ModeArg {
+    Single,
+    Race,
+    Coop,
+    Daily,
+    Weekly,
+    Tutorial,
+    Campaign,
+    Gauntlet,
+    Endless,
+}
+
+#[derive(Debug)]
+pub enum Action {
+    Play(ModeArg),
+    ConfigInit,
+    Achievements,
+    History(HistoryQuery),
+    // `load` on its own
+    ListSaves,
+    DeleteSave(String),
+    Help,
+    // `--help protocol`
+    ProtocolHelp,
+    PrintReference,
+}
+
+#[derive(Debug)]
+pub struct Cli {
+    pub action: Action,
+    // config options to override, in the order they were given
+    pub settings: Vec<(&'static str, String)>,
+    pub seed: Option<u64>,
+    pub batch: bool,
+    pub json: bool,
+    // with `json`, each move answered with only the cells it changed
+    pub diff: bool,
+    pub summary: bool,
+    pub load: Option<PathBuf>,
+    // a named save, by its number in `load`'s list or its name
+    pub named_save: Option<String>,
+    pub stencil: Option<PathBuf>,
+    // where each new game's board is written out, mines and all
+    pub answer_key: Option<PathBuf>,
+    pub import_mbf: Option<PathBuf>,
+    pub serve: Option<u16>,
+    pub broadcast: Option<u16>,
+    pub lang: Option<Lang>,
+}
+
+#[derive(Debug)]
+pub enum CliError {
+    UnknownArgument(String),
+    MissingValue(&'static str),
+    InvalidValue { option: &'static str, expected: Msg },
+    // two options that each set the same thing their own way
+    Conflicting(&'static str, &'static str),
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CliError::UnknownArgument(argument) => {
+                write!(f, "{}", fill(Msg::UnknownArgument, &[argument]))
+            }
+            CliError::MissingValue(option) => write!(f, "{}", fill(Msg::MissingValue, &[option])),
+            CliError::InvalidValue { option, expected } => write!(
+                f,
+                "{}",
+                fill(Msg::OptionMustBe, &[option, &text(*expected)])
+            ),
+            CliError::Conflicting(first, second) => {
+                write!(f, "{}", fill(Msg::OptionsConflict, &[first, second]))
+            }
+        }
+    }
+}
+
+// height, width and mines
+fn preset(name: &str) -> Option<(usize, usize, usize)> {
+    match name {
+        "beginner" => Some((9, 9, 10)),
+        "intermediate" => Some((16, 16, 40)),
+        "expert" => Some((16, 30, 99)),
+        _ => None,
+    }
+}
+
+// the options that only make sense for `history`
+fn history_query<'a>(
+    action: &'a mut Action,
+    option: &'static str,
+) -> Result<&'a mut HistoryQuery, CliError> {
+    match action {
+        Action::History(query) => Ok(query),
+        _ => Err(CliError::UnknownArgument(option.to_string())),
+    }
+}
+
+// the language the environment asks for, in the usual order of precedence
+pub fn env_lang() -> Lang {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| Lang::from_code(&value))
+        .unwrap_or(Lang::English)
+}
+
+pub fn parse_args(args: &[String]) -> Result<Cli, CliError> {
+    let mut cli = Cli {
+        action: Action::Play(ModeArg::Single),
+        settings: vec![],
+        seed: None,
+        batch: false,
+        json: false,
+        diff: false,
+        summary: true,
+        load: None,
+        named_save: None,
+        stencil: None,
+        answer_key: None,
+        import_mbf: None,
+        serve: None,
+        broadcast: None,
+        lang: None,
+    };
+
+    let mut args = args.iter().map(String::as_str).peekable();
+
+    match args.peek() {
+        Some(&"race") => cli.action = Action::Play(ModeArg::Race),
+        Some(&"coop") => cli.action = Action::Play(ModeArg::Coop),
+        Some(&"daily") => cli.action = Action::Play(ModeArg::Daily),
+        Some(&"weekly") => cli.action = Action::Play(ModeArg::Weekly),
+        Some(&"tutorial") => cli.action = Action::Play(ModeArg::Tutorial),
+        Some(&"campaign") => cli.action = Action::Play(ModeArg::Campaign),
+        Some(&"gauntlet") => cli.action = Action::Play(ModeArg::Gauntlet),
+        Some(&"endless") => cli.action = Action::Play(ModeArg::Endless),
+        Some(&"achievements") => cli.action = Action::Achievements,
+        Some(&"history") => cli.action = Action::History(HistoryQuery::default()),
+        Some(&"load") => {
+            args.next();
+            match args.next_if(|argument| !argument.starts_with("--")) {
+                Some(save) => cli.named_save = Some(save.to_string()),
+                None => cli.action = Action::ListSaves,
+            }
+        }
+        Some(&"save") => {
+            args.next();
+            match (args.next(), args.next()) {
+                (Some("delete"), Some(save)) => cli.action = Action::DeleteSave(save.to_string()),
+                _ => return Err(CliError::UnknownArgument(String::from("save"))),
+            }
+        }
+        Some(&"config") => {
+            args.next();
+            match args.peek() {
+                Some(&"init") => cli.action = Action::ConfigInit,
+                _ => return Err(CliError::UnknownArgument(String::from("config"))),
+            }
+        }
+        _ => (),
+    }
+    // `load` and `save` have already had all of their words
+    if !matches!(
+        cli.action,
+        Action::Play(ModeArg::Single) | Action::ListSaves | Action::DeleteSave(_)
+    ) {
+        args.next();
+    }
+
+    // which of the two ways of saying how many mines were used
+    let (mut count, mut density) = (false, false);
+    while let Some(argument) = args.next() {
+        let mut value = |option: &'static str| args.next().ok_or(CliError::MissingValue(option));
+
+        match argument {
+            "-h" | "--help" => cli.action = Action::Help,
+            "--print-reference" => cli.action = Action::PrintReference,
+            "protocol" if matches!(cli.action, Action::Help) => cli.action = Action::ProtocolHelp,
+            "heatmap" => history_query(&mut cli.action, "heatmap")?.heatmap = true,
+            "openings" => history_query(&mut cli.action, "openings")?.openings = true,
+            "weekly" => history_query(&mut cli.action, "weekly")?.weekly = true,
+            "--size" => cli.settings.push(("size", value("size")?.to_string())),
+            "--width" => cli.settings.push(("width", value("width")?.to_string())),
+            "--height" => cli.settings.push(("height", value("height")?.to_string())),
+            "--mines" => {
+                count = true;
+                cli.settings.push(("mines", value("mines")?.to_string()))
+            }
+            "--density" => {
+                density = true;
+                cli.settings
+                    .push(("density", value("density")?.to_string()))
+            }
+            "--preset" => {
+                let size = preset(value("preset")?).ok_or(CliError::InvalidValue {
+                    option: "preset",
+                    expected: Msg::ExpectPreset,
+                })?;
+                // for `history` a preset picks the games to list instead
+                if let Action::History(query) = &mut cli.action {
+                    query.size = Some(size);
+                } else {
+                    let (height, width, mines) = size;
+                    cli.settings.extend([
+                        ("height", height.to_string()),
+                        ("width", width.to_string()),
+                        ("mines", mines.to_string()),
+                    ]);
+                }
+            }
+            "--last" => {
+                let last = value("last")?.parse().map_err(|_| CliError::InvalidValue {
+                    option: "last",
+                    expected: Msg::ExpectWholeNumber,
+                })?;
+                history_query(&mut cli.action, "--last")?.last = last;
+            }
+            "--result" => {
+                let (won, resigned) = match value("result")? {
+                    "won" => (true, None),
+                    "lost" => (false, None),
+                    "resigned" => (false, Some(true)),
+                    _ => {
+                        return Err(CliError::InvalidValue {
+                            option: "result",
+                            expected: Msg::ExpectResult,
+                        })
+                    }
+                };
+                let query = history_query(&mut cli.action, "--result")?;
+                query.won = Some(won);
+                query.resigned = resigned.or(query.resigned);
+            }
+            "--no-resigned" => {
+                history_query(&mut cli.action, "--no-resigned")?.resigned = Some(false)
+            }
+            "--seed" => {
+                cli.seed = Some(value("seed")?.parse().map_err(|_| CliError::InvalidValue {
+                    option: "seed",
+                    expected: Msg::ExpectWholeNumber,
+                })?)
+            }
+            "--serve" => {
+                cli.serve = Some(
+                    value("serve")?
+                        .parse()
+                        .map_err(|_| CliError::InvalidValue {
+                            option: "serve",
+                            expected: Msg::ExpectPort,
+                        })?,
+                )
+            }
+            "--broadcast" => {
+                cli.broadcast =
+                    Some(
+                        value("broadcast")?
+                            .parse()
+                            .map_err(|_| CliError::InvalidValue {
+                                option: "broadcast",
+                                expected: Msg::ExpectPort,
+                            })?,
+                    )
+            }
+            "--no-color" => cli.settings.push(("color", String::from("false"))),
+            "--peeks" => cli.settings.push(("peeks", value("peeks")?.to_string())),
+            "--shields" => cli.settings.push(("shields", String::from("true"))),
+            "--flag-win" => cli.settings.push(("win", String::from("flags"))),
+            "--symmetry" => cli
+                .settings
+                .push(("symmetry", value("symmetry")?.to_string())),
+            "--distribution" => cli
+                .settings
+                .push(("distribution", value("distribution")?.to_string())),
+            "--min-per-row" => cli
+                .settings
+                .push(("min_per_row", value("min-per-row")?.to_string())),
+            "--max-per-row" => cli
+                .settings
+                .push(("max_per_row", value("max-per-row")?.to_string())),
+            "--min-per-column" => cli
+                .settings
+                .push(("min_per_column", value("min-per-column")?.to_string())),
+            "--max-per-column" => cli
+                .settings
+                .push(("max_per_column", value("max-per-column")?.to_string())),
+            "--rocks" => cli.settings.push(("rocks", value("rocks")?.to_string())),
+            "--minutes" => cli
+                .settings
+                .push(("gauntlet_minutes", value("minutes")?.to_string())),
+            "--moving-mines" => cli
+                .settings
+                .push(("moving_mines", value("moving-mines")?.to_string())),
+            "--redraw" => cli.settings.push(("redraw", String::from("true"))),
+            "--difficulty" => cli.settings.push(("difficulty", String::from("true"))),
+            "--debug-dump" => cli.settings.push(("debug_dump", String::from("true"))),
+            "--plain" => cli.settings.push(("plain", String::from("true"))),
+            "--no-summary" => cli.summary = false,
+            "--batch" => cli.batch = true,
+            "--json" => cli.json = true,
+            "--diff" => cli.diff = true,
+            "--load" => cli.load = Some(PathBuf::from(value("load")?)),
+            "--import-mbf" => cli.import_mbf = Some(PathBuf::from(value("import-mbf")?)),
+            "--stencil" => cli.stencil = Some(PathBuf::from(value("stencil")?)),
+            "--answer-key" => cli.answer_key = Some(PathBuf::from(value("answer-key")?)),
+            "--lang" => {
+                cli.lang = Some(
+                    Lang::from_code(value("lang")?).ok_or(CliError::InvalidValue {
+                        option: "lang",
+                        expected: Msg::ExpectLang,
+                    })?,
+                )
+            }
+            other => return Err(CliError::UnknownArgument(other.to_string())),
+        }
+    }
+    if count && density {
+        return Err(CliError::Conflicting("mines", "density"));
+    }
+
+    Ok(cli)
+}
@@ -0,0 +1,838 @@
+use crate::board::CellPosition;
+use crate::i18n::{fill, text, Msg};
+use std::fmt::Display;
+use std::iter::Peekable;
+use std::path::PathBuf;
+use std::str::{FromStr, SplitWhitespace};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Export {
+    Transcript(PathBuf),
+    // the mines of the board, as an .mbf file
+    Mbf(PathBuf),
+    // the finished board, printed for pasting into Discord
+    Discord,
+    // a replay of the game, as an asciinema recording
+    Cast(PathBuf),
+}
+
+// columns as letters, as a spreadsheet names them: A to Z, then AA to AZ,
+// BA and so on
+pub fn column_letters(col_index: usize) -> String {
+    let mut letters = vec![];
+    let mut rest = col_index;
+    loop {
+        letters.push(char::from(b'A' + (rest % 26) as u8));
+        match rest / 26 {
+            0 => break,
+            more => rest = more - 1,
+        }
+    }
+    letters.iter().rev().collect()
+}
+
+// the other way, in either case; None for anything but letters, or for
+// more of them than an index can hold
+pub fn parse_column_letters(letters: &str) -> Option<usize> {
+    if letters.is_empty() {
+        return None;
+    }
+    // one more than the index, which for the last column doesn't fit in one
+    let mut number: u128 = 0;
+    for letter in letters.chars() {
+        if !letter.is_ascii_alphabetic() {
+            return None;
+        }
+        let digit = u128::from(letter.to_ascii_uppercase() as u8 - b'A') + 1;
+        number = number.checked_mul(26)?.checked_add(digit)?;
+    }
+    usize::try_from(number - 1).ok()
+}
+
+// a cell as its column's letters and its row counted from 1, e.g. E4 for
+// (3,4)
+pub fn cell_name(position: CellPosition) -> String {
+    format!(
+        "{}{}",
+        column_letters(position.col_index),
+        position.row_index + 1
+    )
+}
+
+pub fn parse_cell_name(name: &str) -> Option<CellPosition> {
+    let digits = name.find(|c: char| c.is_ascii_digit())?;
+    let (letters, row) = name.split_at(digits);
+    let row: usize = row.parse().ok()?;
+    Some(CellPosition {
+        row_index: row.checked_sub(1)?,
+        col_index: parse_column_letters(letters)?,
+    })
+}
+
+// a block of cells to clear in one go; corners are inclusive
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Area {
+    Row(usize),
+    Rect {
+        top: usize,
+        left: usize,
+        bottom: usize,
+        right: usize,
+    },
+}
+
+impl Area {
+    // every position in the area, row by row, or None if any of it is off
+    // the board
+    pub fn positions(&self, height: usize, width: usize) -> Option<Vec<CellPosition>> {
+        let (top, left, bottom, right) = match *self {
+            Area::Row(row) => (row, 0, row, width.checked_sub(1)?),
+            Area::Rect {
+                top,
+                left,
+                bottom,
+                right,
+            } => (top, left, bottom, right),
+        };
+
+        if bottom >= height || right >= width {
+            return None;
+        }
+
+        Some(
+            (top..=bottom)
+                .flat_map(|row_index| {
+                    (left..=right).map(move |col_index| CellPosition {
+                        row_index,
+                        col_index,
+                    })
+                })
+                .collect(),
+        )
+    }
+}
+
+// in the same syntax the parser accepts after "c"
+impl Display for Area {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Area::Row(row) => write!(f, "row {row}"),
+            Area::Rect {
+                top,
+                left,
+                bottom,
+                right,
+            } => write!(f, "rect {top} {left} {bottom} {right}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Clear(CellPosition),
+    // clears every closed, unflagged cell in the area
+    ClearArea(Area),
+    Flag(CellPosition),
+    // clears every unflagged neighbour of an open cell whose mines are all flagged
+    Chord(CellPosition),
+    // says what `Chord` would do there, without doing it
+    ChordCheck(CellPosition),
+    Question(CellPosition),
+    // puts a one-letter note on a closed cell, or with None takes it off;
+    // notes are for the player only, and no rule looks at them
+    Note(CellPosition, Option<char>),
+    // tells whether a closed cell is a mine, using up one of the game's peeks
+    Peek(CellPosition),
+    Undo,
+    // takes the game back to how it was after that many of its moves
+    Rewind(usize),
+    // stops the timer and hides the board until `Resume`
+    Pause,
+    Resume,
+    Share,
+    // the size, mine count and 3BV of the board, which give nothing away
+    BoardInfo,
+    // the closed cells split into regions, with what the numbers say of each
+    Analyze,
+    // how many cells the numbers prove safe and how many mines, but not
+    // which
+    Remaining,
+    // the chance of clearing the board from here without guessing, as
+    // estimated by playing the solver through layouts that fit the numbers
+    WinProb,
+    // the cell the solver would see to next, and with `why` how it works
+    // that out
+    Hint { why: bool },
+    // the last few moves, or with `all` every one
+    Moves { all: bool },
+    // everything about the game, the mines included, as a debug dump; only
+    // parsed in a build with the `debug` feature
+    Dump,
+    Export(Export),
+    // keeps the game under a name of the player's, beside the autosave
+    SaveAs(String),
+    // the solver plays on from here while the player watches, who then
+    // keeps its moves or takes them all back
+    Watch,
+    // gives the game up as lost, without setting off a mine
+    Resign,
+    // lists the commands, from COMMANDS
+    Help,
+}
+
+impl Command {
+    // whether every cell the command names is on a board of `height` by
+    // `width`; a command that names none always is
+    pub fn check_within(&self, height: usize, width: usize) -> Result<(), CoordinateError> {
+        let (rows, cols) = match self {
+            &Command::Clear(position)
+            | &Command::Flag(position)
+            | &Command::Chord(position)
+            | &Command::ChordCheck(position)
+            | &Command::Question(position)
+            | &Command::Note(position, _)
+            | &Command::Peek(position) => (position.row_index, position.col_index),
+            Command::ClearArea(Area::Row(row)) => (*row, 0),
+            &Command::ClearArea(Area::Rect { bottom, right, .. }) => (bottom, right),
+            _ => return Ok(()),
+        };
+        check_coordinate(rows, Field::Row, height)?;
+        check_coordinate(cols, Field::Column, width)?;
+        Ok(())
+    }
+}
+
+// renders a command in the same syntax the parser accepts
+impl Display for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Command::Clear(position) => {
+                write!(f, "c {} {}", position.row_index, position.col_index)
+            }
+            Command::ClearArea(area) => write!(f, "c {area}"),
+            Command::Flag(position) => {
+                write!(f, "f {} {}", position.row_index, position.col_index)
+            }
+            Command::Chord(position) => {
+                write!(f, "ch {} {}", position.row_index, position.col_index)
+            }
+            Command::ChordCheck(position) => {
+                write!(f, "ch? {} {}", position.row_index, position.col_index)
+            }
+            Command::Question(position) => {
+                write!(f, "q {} {}", position.row_index, position.col_index)
+            }
+            Command::Note(position, note) => write!(
+                f,
+                "note {} {} {}",
+                position.row_index,
+                position.col_index,
+                note.unwrap_or('-')
+            ),
+            Command::Peek(position) => {
+                write!(f, "peek {} {}", position.row_index, position.col_index)
+            }
+            Command::Undo => write!(f, "u"),
+            Command::Rewind(moves) => write!(f, "rewind {moves}"),
+            Command::Pause => write!(f, "pause"),
+            Command::Resume => write!(f, "resume"),
+            Command::Share => write!(f, "share"),
+            Command::BoardInfo => write!(f, "boardinfo"),
+            Command::Analyze => write!(f, "analyze"),
+            Command::Remaining => write!(f, "remaining"),
+            Command::WinProb => write!(f, "winprob"),
+            Command::Hint { why: false } => write!(f, "hint"),
+            Command::Hint { why: true } => write!(f, "hint why"),
+            Command::Watch => write!(f, "watch"),
+            Command::Resign => write!(f, "resign"),
+            Command::Help => write!(f, "help"),
+            Command::Moves { all: false } => write!(f, "moves"),
+            Command::Moves { all: true } => write!(f, "moves all"),
+            Command::Dump => write!(f, "dump"),
+            Command::Export(Export::Transcript(path)) => {
+                write!(f, "export transcript {}", path.display())
+            }
+            Command::Export(Export::Mbf(path)) => write!(f, "export mbf {}", path.display()),
+            Command::Export(Export::Discord) => write!(f, "export discord"),
+            Command::Export(Export::Cast(path)) => write!(f, "export cast {}", path.display()),
+            Command::SaveAs(name) => write!(f, "save as {name}"),
+        }
+    }
+}
+
+// which half of a cell's position a coordinate is
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    Row,
+    Column,
+}
+
+// why a row or a column couldn't be read
+#[derive(Debug, Clone, PartialEq)]
+pub enum CoordinateError {
+    // anything but the digits 0 to 9: a sign, a space or a null inside it,
+    // or the digits of another script
+    NotANumber(String),
+    // digits, but more of them than any board has rows or columns for
+    TooLarge(String),
+    // a number, but past the edge of this board, whose last row or column
+    // is `max`
+    OutOfRange {
+        field: Field,
+        index: usize,
+        max: usize,
+    },
+}
+
+impl CoordinateError {
+    // for the JSON protocol, which answers with these rather than with the
+    // message
+    pub fn code(&self) -> &'static str {
+        match self {
+            CoordinateError::NotANumber(_) => "not_a_number",
+            CoordinateError::TooLarge(_) => "too_large",
+            CoordinateError::OutOfRange { .. } => "out_of_range",
+        }
+    }
+}
+
+impl Display for CoordinateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let out = match self {
+            CoordinateError::NotANumber(word) => fill(Msg::CoordinateNotANumber, &[word]),
+            CoordinateError::TooLarge(word) => fill(Msg::CoordinateTooLarge, &[word]),
+            CoordinateError::OutOfRange {
+                field: Field::Row,
+                index,
+                max,
+            } => fill(Msg::RowOffBoard, &[index, max]),
+            CoordinateError::OutOfRange {
+                field: Field::Column,
+                index,
+                max,
+            } => fill(Msg::ColumnOffBoard, &[index, max]),
+        };
+
+        write!(f, "{out}")
+    }
+}
+
+// a row or a column, written in the digits 0 to 9 and nothing else; Rust's
+// own parsing also takes a leading +
+pub fn parse_coordinate(word: &str) -> Result<usize, CoordinateError> {
+    if word.is_empty() || !word.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(CoordinateError::NotANumber(word.to_string()));
+    }
+    // nothing but digits is left to fail on but their number
+    word.parse()
+        .map_err(|_| CoordinateError::TooLarge(word.to_string()))
+}
+
+// likewise, for the endless board, where a - can go before the digits
+pub fn parse_signed_coordinate(word: &str) -> Result<i64, CoordinateError> {
+    let digits = word.strip_prefix('-').unwrap_or(word);
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(CoordinateError::NotANumber(word.to_string()));
+    }
+    word.parse()
+        .map_err(|_| CoordinateError::TooLarge(word.to_string()))
+}
+
+// an index into `length` rows or columns
+pub fn check_coordinate(
+    index: usize,
+    field: Field,
+    length: usize,
+) -> Result<usize, CoordinateError> {
+    match index < length {
+        true => Ok(index),
+        false => Err(CoordinateError::OutOfRange {
+            field,
+            index,
+            max: length.saturating_sub(1),
+        }),
+    }
+}
+
+#[derive(Debug)]
+pub enum CommandError {
+    Empty,
+    Unknown(String),
+    MissingCoordinate,
+    MissingPath,
+    MissingSaveName,
+    MissingMoveNumber,
+    InvalidMoveNumber(String),
+    // a single argument that isn't a cell name such as E4
+    InvalidCellName(String),
+    // anything but a single letter or -
+    InvalidNote(String),
+    UnknownExport(String),
+    Coordinate(CoordinateError),
+    // a rectangle whose second corner is above or left of its first
+    InvertedRect,
+    TrailingInput,
+}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let out = match self {
+            CommandError::Empty => String::from(text(Msg::NoCommand)),
+            CommandError::Unknown(name) => fill(Msg::UnknownCommand, &[name]),
+            CommandError::MissingCoordinate => String::from(text(Msg::MissingCoordinate)),
+            CommandError::MissingPath => String::from(text(Msg::MissingPath)),
+            CommandError::MissingSaveName => String::from(text(Msg::MissingSaveName)),
+            CommandError::MissingMoveNumber => String::from(text(Msg::MissingMoveNumber)),
+            CommandError::InvalidMoveNumber(moves) => fill(Msg::InvalidMoveNumber, &[moves]),
+            CommandError::InvalidCellName(name) => fill(Msg::InvalidCellName, &[name]),
+            CommandError::InvalidNote(note) => fill(Msg::InvalidNote, &[note]),
+            CommandError::UnknownExport(format) => fill(Msg::UnknownExport, &[format]),
+            CommandError::Coordinate(e) => e.to_string(),
+            CommandError::InvertedRect => String::from(text(Msg::InvertedRect)),
+            CommandError::TrailingInput => String::from(text(Msg::TooManyArguments)),
+        };
+
+        write!(f, "{out}")
+    }
+}
+
+impl CommandError {
+    // for the JSON protocol, one per kind of mistake
+    pub fn code(&self) -> &'static str {
+        match self {
+            CommandError::Empty => "empty",
+            CommandError::Unknown(_) => "unknown_command",
+            CommandError::MissingCoordinate => "missing_coordinate",
+            CommandError::MissingPath => "missing_path",
+            CommandError::MissingSaveName => "missing_save_name",
+            CommandError::MissingMoveNumber => "missing_move_number",
+            CommandError::InvalidMoveNumber(_) => "invalid_move_number",
+            CommandError::InvalidCellName(_) => "invalid_cell_name",
+            CommandError::InvalidNote(_) => "invalid_note",
+            CommandError::UnknownExport(_) => "unknown_export",
+            CommandError::Coordinate(e) => e.code(),
+            CommandError::InvertedRect => "inverted_rect",
+            CommandError::TrailingInput => "trailing_input",
+        }
+    }
+}
+
+fn next_index<'a>(args: &mut impl Iterator<Item = &'a str>) -> Result<usize, CommandError> {
+    parse_coordinate(args.next().ok_or(CommandError::MissingCoordinate)?)
+        .map_err(CommandError::Coordinate)
+}
+
+fn expect_end<'a>(mut args: impl Iterator<Item = &'a str>) -> Result<(), CommandError> {
+    match args.next() {
+        Some(_) => Err(CommandError::TrailingInput),
+        None => Ok(()),
+    }
+}
+
+// "<row> <col>", or the cell's name, e.g. "E4"
+fn parse_position<'a>(args: impl Iterator<Item = &'a str>) -> Result<CellPosition, CommandError> {
+    let mut args = args.peekable();
+    if let Some(name) = args.next_if(|arg| arg.starts_with(|c: char| c.is_ascii_alphabetic())) {
+        let position =
+            parse_cell_name(name).ok_or_else(|| CommandError::InvalidCellName(name.to_string()))?;
+        expect_end(args)?;
+        return Ok(position);
+    }
+
+    let row_index = next_index(&mut args)?;
+    let col_index = next_index(&mut args)?;
+    expect_end(args)?;
+
+    Ok(CellPosition {
+        row_index,
+        col_index,
+    })
+}
+
+// "<position> <letter>", or "<position> -" to take the note off
+fn parse_note<'a>(
+    args: impl Iterator<Item = &'a str>,
+) -> Result<(CellPosition, Option<char>), CommandError> {
+    let args: Vec<&str> = args.collect();
+    let (&note, position) = args.split_last().ok_or(CommandError::MissingCoordinate)?;
+    let mut chars = note.chars();
+    let note = match (chars.next(), chars.next()) {
+        (Some('-'), None) => None,
+        (Some(letter), None) if letter.is_alphabetic() => Some(letter),
+        _ => return Err(CommandError::InvalidNote(note.to_string())),
+    };
+
+    Ok((parse_position(position.iter().copied())?, note))
+}
+
+// "row <row>" or "rect <top> <left> <bottom> <right>"
+fn parse_area<'a>(
+    kind: &str,
+    mut args: impl Iterator<Item = &'a str>,
+) -> Result<Area, CommandError> {
+    let area = if kind == "row" {
+        Area::Row(next_index(&mut args)?)
+    } else {
+        let (top, left) = (next_index(&mut args)?, next_index(&mut args)?);
+        let (bottom, right) = (next_index(&mut args)?, next_index(&mut args)?);
+        if bottom < top || right < left {
+            return Err(CommandError::InvertedRect);
+        }
+        Area::Rect {
+            top,
+            left,
+            bottom,
+            right,
+        }
+    };
+    expect_end(args)?;
+
+    Ok(area)
+}
+
+// the words of a command after its name
+pub type Words<'a> = Peekable<SplitWhitespace<'a>>;
+
+// one command as it is written: its name, the other names it goes by, how
+// its arguments are written and what it does. The parser is built from
+// COMMANDS, and so is every list of them the player is shown, so that the
+// two can't drift apart
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    // as help writes them, e.g. "<cell> [why]"; empty for none
+    pub args: &'static str,
+    pub description: Msg,
+    // a line it parses, as the reference shows it
+    pub example: &'static str,
+    // only parsed in a build with the `debug` feature
+    pub debug: bool,
+    // the command from the words after its name
+    parse: fn(&mut Words) -> Result<Command, CommandError>,
+}
+
+impl CommandSpec {
+    // the name and the aliases, in that order
+    pub fn names(&self) -> impl Iterator<Item = &'static str> {
+        std::iter::once(self.name).chain(self.aliases.iter().copied())
+    }
+}
+
+// a command that takes no arguments
+const fn bare(
+    name: &'static str,
+    description: Msg,
+    parse: fn(&mut Words) -> Result<Command, CommandError>,
+) -> CommandSpec {
+    CommandSpec {
+        name,
+        aliases: &[],
+        args: "",
+        description,
+        example: name,
+        debug: false,
+        parse,
+    }
+}
+
+// in the order help lists them
+pub const COMMANDS: [CommandSpec; 24] = [
+    CommandSpec {
+        name: "c",
+        aliases: &["clear"],
+        args: "<cell> | row <row> | rect <top> <left> <bottom> <right>",
+        description: Msg::CommandClear,
+        example: "c 3 4",
+        debug: false,
+        parse: |words| match words.peek() {
+            Some(&kind @ ("row" | "rect")) => {
+                words.next();
+                Ok(Command::ClearArea(parse_area(kind, words)?))
+            }
+            _ => Ok(Command::Clear(parse_position(words)?)),
+        },
+    },
+    CommandSpec {
+        name: "f",
+        aliases: &["flag"],
+        args: "<cell>",
+        description: Msg::CommandFlag,
+        example: "f 3 4",
+        debug: false,
+        parse: |words| Ok(Command::Flag(parse_position(words)?)),
+    },
+    CommandSpec {
+        name: "ch",
+        aliases: &["chord"],
+        args: "[--check] <cell>",
+        description: Msg::CommandChord,
+        example: "ch 3 4",
+        debug: false,
+        parse: |words| match words.next_if_eq(&"--check") {
+            Some(_) => Ok(Command::ChordCheck(parse_position(words)?)),
+            None => Ok(Command::Chord(parse_position(words)?)),
+        },
+    },
+    CommandSpec {
+        name: "ch?",
+        aliases: &["chord?"],
+        args: "<cell>",
+        description: Msg::CommandChordCheck,
+        example: "ch? 3 4",
+        debug: false,
+        parse: |words| Ok(Command::ChordCheck(parse_position(words)?)),
+    },
+    CommandSpec {
+        name: "q",
+        aliases: &["question"],
+        args: "<cell>",
+        description: Msg::CommandQuestion,
+        example: "q 3 4",
+        debug: false,
+        parse: |words| Ok(Command::Question(parse_position(words)?)),
+    },
+    CommandSpec {
+        name: "note",
+        aliases: &[],
+        args: "<cell> <letter>|-",
+        description: Msg::CommandNote,
+        example: "note 3 4 A",
+        debug: false,
+        parse: |words| {
+            let (position, note) = parse_note(words)?;
+            Ok(Command::Note(position, note))
+        },
+    },
+    CommandSpec {
+        name: "peek",
+        aliases: &[],
+        args: "<cell>",
+        description: Msg::CommandPeek,
+        example: "peek 3 4",
+        debug: false,
+        parse: |words| Ok(Command::Peek(parse_position(words)?)),
+    },
+    CommandSpec {
+        aliases: &["undo"],
+        ..bare("u", Msg::CommandUndo, |words| {
+            expect_end(words).map(|_| Command::Undo)
+        })
+    },
+    CommandSpec {
+        name: "rewind",
+        aliases: &[],
+        args: "<moves>",
+        description: Msg::CommandRewind,
+        example: "rewind 12",
+        debug: false,
+        parse: |words| {
+            let moves = words.next().ok_or(CommandError::MissingMoveNumber)?;
+            let moves = moves
+                .parse()
+                .map_err(|_| CommandError::InvalidMoveNumber(moves.to_string()))?;
+            expect_end(words).map(|_| Command::Rewind(moves))
+        },
+    },
+    bare("pause", Msg::CommandPause, |words| {
+        expect_end(words).map(|_| Command::Pause)
+    }),
+    bare("resume", Msg::CommandResume, |words| {
+        expect_end(words).map(|_| Command::Resume)
+    }),
+    bare("share", Msg::CommandShare, |words| {
+        expect_end(words).map(|_| Command::Share)
+    }),
+    bare("boardinfo", Msg::CommandBoardInfo, |words| {
+        expect_end(words).map(|_| Command::BoardInfo)
+    }),
+    bare("analyze", Msg::CommandAnalyze, |words| {
+        expect_end(words).map(|_| Command::Analyze)
+    }),
+    bare("remaining", Msg::CommandRemaining, |words| {
+        expect_end(words).map(|_| Command::Remaining)
+    }),
+    bare("winprob", Msg::CommandWinProb, |words| {
+        expect_end(words).map(|_| Command::WinProb)
+    }),
+    CommandSpec {
+        args: "[why]",
+        ..bare("hint", Msg::CommandHint, |words| {
+            let why = words.next_if_eq(&"why").is_some();
+            expect_end(words).map(|_| Command::Hint { why })
+        })
+    },
+    CommandSpec {
+        args: "[all]",
+        ..bare("moves", Msg::CommandMoves, |words| {
+            let all = words.next_if_eq(&"all").is_some();
+            expect_end(words).map(|_| Command::Moves { all })
+        })
+    },
+    bare("watch", Msg::CommandWatch, |words| {
+        expect_end(words).map(|_| Command::Watch)
+    }),
+    bare("resign", Msg::CommandResign, |words| {
+        expect_end(words).map(|_| Command::Resign)
+    }),
+    CommandSpec {
+        name: "export",
+        aliases: &[],
+        args: "discord | transcript <file> | mbf <file> | cast <file>",
+        description: Msg::CommandExport,
+        example: "export transcript game.txt",
+        debug: false,
+        parse: |words| match words.next() {
+            Some("discord") => expect_end(words).map(|_| Command::Export(Export::Discord)),
+            Some(format @ ("transcript" | "mbf" | "cast")) => {
+                let path = PathBuf::from(words.next().ok_or(CommandError::MissingPath)?);
+                expect_end(words)?;
+                Ok(Command::Export(match format {
+                    "transcript" => Export::Transcript(path),
+                    "mbf" => Export::Mbf(path),
+                    _ => Export::Cast(path),
+                }))
+            }
+            Some(other) => Err(CommandError::UnknownExport(other.to_string())),
+            None => Err(CommandError::UnknownExport(String::new())),
+        },
+    },
+    // the rest of the line, in quotes or not
+    CommandSpec {
+        name: "save",
+        aliases: &[],
+        args: "as <name>",
+        description: Msg::CommandSaveAs,
+        example: "save as before the guess",
+        debug: false,
+        parse: |words| {
+            if words.next_if_eq(&"as").is_none() {
+                return Err(CommandError::Unknown(String::from("save")));
+            }
+            let name = words.collect::<Vec<&str>>().join(" ");
+            let name = match name
+                .strip_prefix('"')
+                .and_then(|name| name.strip_suffix('"'))
+            {
+                Some(quoted) => quoted.trim(),
+                None => &name,
+            };
+            match name {
+                "" => Err(CommandError::MissingSaveName),
+                name => Ok(Command::SaveAs(name.to_string())),
+            }
+        },
+    },
+    bare("help", Msg::CommandHelp, |words| {
+        expect_end(words).map(|_| Command::Help)
+    }),
+    CommandSpec {
+        debug: true,
+        ..bare("dump", Msg::CommandDump, |words| {
+            expect_end(words).map(|_| Command::Dump)
+        })
+    },
+];
+
+// the commands this build parses
+pub fn commands() -> impl Iterator<Item = &'static CommandSpec> {
+    COMMANDS
+        .iter()
+        .filter(|spec| !spec.debug || cfg!(feature = "debug"))
+}
+
+// the command going by `name`, as its name or one of its aliases
+pub fn find_command(name: &str) -> Option<&'static CommandSpec> {
+    commands().find(|spec| spec.names().any(|known| known == name))
+}
+
+// commands are a name followed by whitespace-separated arguments,
+// e.g. "c 3 4" clears the cell at row 3, column 4
+impl FromStr for Command {
+    type Err = CommandError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace().peekable();
+        let name = words.next().ok_or(CommandError::Empty)?;
+        let spec = find_command(name).ok_or_else(|| CommandError::Unknown(name.to_string()))?;
+        (spec.parse)(&mut words)
+    }
+}
+
+// several commands on one line are separated by semicolons, e.g.
+// "f 0 1; f 0 2; c 3 3"; empty segments are ignored, and nothing is
+// returned unless every segment parses
+pub fn parse_line(line: &str) -> Result<Vec<Command>, CommandError> {
+    let commands = line
+        .split(';')
+        .filter(|segment| !segment.trim().is_empty())
+        .map(str::parse)
+        .collect::<Result<Vec<Command>, CommandError>>()?;
+
+    if commands.is_empty() {
+        return Err(CommandError::Empty);
+    }
+
+    Ok(commands)
+}
+
+// likewise, turning down a line that names a cell off a board of `height`
+// by `width`, with the row or column that is
+pub fn parse_line_within(
+    line: &str,
+    height: usize,
+    width: usize,
+) -> Result<Vec<Command>, CommandError> {
+    let commands = parse_line(line)?;
+    for command in &commands {
+        command
+            .check_within(height, width)
+            .map_err(CommandError::Coordinate)?;
+    }
+    Ok(commands)
+}
+
+// a cell of the endless board, where rows and columns go on in every
+// direction from (0,0)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Point {
+    pub row: i64,
+    pub col: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EndlessCommand {
+    Clear(Point),
+    Flag(Point),
+    Chord(Point),
+    // moves the view so that it is centred on the cell
+    Go(Point),
+}
+
+// like parse_position, but either coordinate can be negative
+pub fn parse_point<'a>(mut args: impl Iterator<Item = &'a str>) -> Result<Point, CommandError> {
+    let mut next = || {
+        parse_signed_coordinate(args.next().ok_or(CommandError::MissingCoordinate)?)
+            .map_err(CommandError::Coordinate)
+    };
+    let (row, col) = (next()?, next()?);
+    expect_end(args)?;
+
+    Ok(Point { row, col })
+}
+
+impl FromStr for EndlessCommand {
+    type Err = CommandError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace();
+        let name = words.next().ok_or(CommandError::Empty)?;
+
+        match name {
+            "c" | "clear" => Ok(EndlessCommand::Clear(parse_point(words)?)),
+            "f" | "flag" => Ok(EndlessCommand::Flag(parse_point(words)?)),
+            "ch" | "chord" => Ok(EndlessCommand::Chord(parse_point(words)?)),
+            "go" => Ok(EndlessCommand::Go(parse_point(words)?)),
+            other => Err(CommandError::Unknown(other.to_string())),
+        }
+    }
+}
@@ -0,0 +1,620 @@
+use crate::analysis::FlagScoring;
+use crate::board::{Board, Layout, LineLimits, MineDistribution, Mines, Symmetry};
+use crate::dirs::config_dir;
+use crate::game::WinCondition;
+use crate::i18n::{fill, text, Msg};
+use crate::output::OutputCapabilities;
+use crate::rules::{Ruleset, RulesetBuilder, RulesetError, MAX_CELLS};
+use std::fmt::Display;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::PathBuf;
+use std::time::Duration;
+
+const CONFIG_FILE: &str = "config.toml";
+
+const DEFAULT_CONFIG: &str = r#"# minesweeper-cli configuration
+#
+# Every setting is optional; anything left out uses the built-in default.
+# Command-line options take precedence over this file.
+
+# board dimensions: `size` sets both, `width`/`height` set one each
+size = 10
+# width = 10
+# height = 10
+
+# either an exact number of mines, or the chance (0 to 1) of each cell being a mine
+# mines = 16
+density = 0.1667
+
+# "ascii" or "emoji"
+theme = "ascii"
+
+# colour the numbers, flags and mines
+color = false
+
+# nothing but lines of ASCII: the ascii theme, no colour, banner or bell,
+# and the screen never cleared (as with --plain); colour is also left off
+# when NO_COLOR is set, and escapes, banners and the bell when the output
+# isn't a terminal
+plain = false
+
+# "numeric" (rows and columns are numbers) or "letters" (columns are letters)
+coordinates = "numeric"
+
+# say which cell each move acts on, as a row and a column and by its name,
+# e.g. "Clearing (3,4) = E4"
+echo_moves = false
+
+# ask before clearing a cell next to a flag
+confirm_near_flags = false
+
+# "none", "hints" or "full"; with either assist, the status line counts the
+# cells the numbers prove safe and the mines they prove, without saying which
+assist = "none"
+
+# the art shown over the board when a game ends: "block", "outline" or "none"
+banner = "block"
+
+# ring the terminal bell when a mine goes off, and also on a win
+bell = true
+bell_on_win = false
+
+# in redraw mode, blink the mine that ended the game before revealing the board
+flash = true
+
+# show the elapsed time while playing
+timer = true
+
+# seconds of waiting for a move after which the timer stops counting, so that
+# a break isn't timed; 0 counts every wait in full
+idle_after = 0
+
+# milliseconds between the solver's moves while you `watch` it play
+watch_delay_ms = 400
+
+# how many times a game lets you peek at a closed cell to learn whether it is a
+# mine; a game that uses any is marked as assisted in the history
+peeks = 0
+
+# how a game is won: "open" (every safe cell opened) or "flags" (every mine
+# flagged and no safe cell flagged, or every safe cell opened)
+win = "open"
+
+# opening 15 or more cells in one move earns a shield (up to 3 at a time),
+# which takes the blast of the next mine instead of a life
+shields = false
+
+# lay the mines out symmetrically: "none", "horizontal" (the right half mirrors
+# the left), "vertical" (the bottom half mirrors the top) or "rotational" (the
+# board looks the same upside down)
+symmetry = "none"
+
+# how the mines are scattered: "uniform", "clustered" (in a few dense pockets,
+# with open plains between them) or "sparse" (kept apart where they can be);
+# the last two always place exactly the number of mines a density gives on
+# average
+distribution = "uniform"
+
+# how many mines each row and each column has at least and at most; a maximum
+# of 0 is no limit. With any of these, the board gets exactly the number of
+# mines a density gives on average
+min_per_row = 0
+max_per_row = 0
+min_per_column = 0
+max_per_column = 0
+
+# how many of the safe cells are rocks, which are never opened and stop a
+# cascade of zeros, making the board more of a maze
+rocks = 0
+
+# the most cells a board may have; bigger sizes are turned down rather than
+# laid out
+max_cells = 1000000
+
+# how long a gauntlet lasts
+gauntlet_minutes = 5
+
+# the moving-mines variant: every this many moves, moving_fraction of the mines
+# (those not flagged or peeked at) move to other closed cells, while the numbers
+# already open stay as they were; 0 plays by the usual rules
+moving_mines = 0
+moving_fraction = 0.2
+
+# at a terminal, keep the board in place and repaint only the cells that
+# change, instead of printing it again after every move
+redraw = false
+
+# before a new game, rate how hard its board is, counting the guesses it can't
+# be cleared without
+difficulty = false
+
+# how flags count as clicks in the efficiency shown when a game is won:
+# "ignore", "placements" (each flag put on) or "toggles" (each flag put on or
+# taken off)
+flag_scoring = "ignore"
+
+# count resigned games as losses in the summary at the end of a session,
+# rather than leaving them out
+count_resigned = true
+
+# race the fastest earlier win at the same board, shown in the status line as
+# how many moves it had made by now
+ghost = true
+
+# when a game ends, print a debug dump of it, mines included, to paste into a
+# bug report
+debug_dump = false
+"#;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Theme {
+    Ascii,
+    Emoji,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BannerStyle {
+    Block,
+    Outline,
+    None,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CoordinateStyle {
+    Numeric,
+    Letters,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AssistLevel {
+    None,
+    Hints,
+    Full,
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub height: usize,
+    pub width: usize,
+    pub mines: Mines,
+    pub theme: Theme,
+    pub color: bool,
+    pub plain: bool,
+    // what the output can show of the settings above, worked out from them
+    // once they are all in
+    pub output: OutputCapabilities,
+    pub banner: BannerStyle,
+    pub bell: bool,
+    pub bell_on_win: bool,
+    pub flash: bool,
+    // not consumed yet; parsed and validated so that config files written
+    // today keep working once coordinate styles land
+    #[allow(dead_code)]
+    pub coordinates: CoordinateStyle,
+    pub assist: AssistLevel,
+    pub timer: bool,
+    pub idle_after: u64,
+    pub watch_delay_ms: u64,
+    pub peeks: u32,
+    pub shields: bool,
+    pub symmetry: Symmetry,
+    pub limits: LineLimits,
+    pub distribution: MineDistribution,
+    pub rocks: usize,
+    // the most cells a board may have
+    pub max_cells: usize,
+    pub gauntlet_minutes: u64,
+    pub moving_mines: usize,
+    pub moving_fraction: f64,
+    pub redraw: bool,
+    pub difficulty: bool,
+    pub flag_scoring: FlagScoring,
+    pub count_resigned: bool,
+    pub ghost: bool,
+    pub echo_moves: bool,
+    pub confirm_near_flags: bool,
+    pub win: WinCondition,
+    pub debug_dump: bool,
+}
+
+impl Config {
+    pub fn board(&self, seed: u64) -> Board {
+        let layout = Layout {
+            mines: self.mines,
+            symmetry: self.symmetry,
+            limits: self.limits,
+            distribution: self.distribution.clone(),
+            rocks: self.rocks,
+        };
+        Board::generate(seed, self.height, self.width, &layout)
+    }
+
+    // the rules the settings make, to change before building them
+    pub fn rules(&self) -> RulesetBuilder {
+        Ruleset::builder()
+            .size(self.height, self.width)
+            .mines(self.mines)
+            .symmetry(self.symmetry)
+            .limits(self.limits)
+            .distribution(self.distribution.clone())
+            .rocks(self.rocks)
+            .max_cells(self.max_cells)
+            .peeks(self.peeks)
+            .shields(self.shields)
+            .win(self.win)
+            .moving_mines(self.moving_mines, self.moving_fraction)
+    }
+
+    pub fn ruleset(&self) -> Result<Ruleset, RulesetError> {
+        self.rules().build()
+    }
+
+    // how long a wait for a move counts in full, if not forever
+    pub fn idle_limit(&self) -> Option<Duration> {
+        (self.idle_after > 0).then(|| Duration::from_secs(self.idle_after))
+    }
+
+    pub fn watch_delay(&self) -> Duration {
+        Duration::from_millis(self.watch_delay_ms)
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            height: 10,
+            width: 10,
+            // 1 bomb for every 5 safe tiles
+            mines: Mines::Density(1.0 / 6.0),
+            theme: Theme::Ascii,
+            color: false,
+            plain: false,
+            output: OutputCapabilities::plain(),
+            banner: BannerStyle::Block,
+            bell: true,
+            bell_on_win: false,
+            flash: true,
+            coordinates: CoordinateStyle::Numeric,
+            assist: AssistLevel::None,
+            timer: true,
+            idle_after: 0,
+            watch_delay_ms: 400,
+            peeks: 0,
+            shields: false,
+            symmetry: Symmetry::None,
+            limits: LineLimits::default(),
+            distribution: MineDistribution::Uniform,
+            rocks: 0,
+            max_cells: MAX_CELLS,
+            gauntlet_minutes: 5,
+            moving_mines: 0,
+            moving_fraction: 0.2,
+            redraw: false,
+            difficulty: false,
+            flag_scoring: FlagScoring::Ignore,
+            count_resigned: true,
+            ghost: true,
+            echo_moves: false,
+            confirm_near_flags: false,
+            win: WinCondition::OpenAll,
+            debug_dump: false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(PathBuf, io::Error),
+    Syntax {
+        line: usize,
+    },
+    UnknownKey {
+        line: usize,
+        key: String,
+    },
+    InvalidValue {
+        line: usize,
+        key: String,
+        expected: Msg,
+    },
+    // settings that don't make a game together
+    Rules(RulesetError),
+    AlreadyExists(PathBuf),
+    NoConfigDir,
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let out = match self {
+            ConfigError::Io(path, e) => fill(Msg::ConfigCouldntAccess, &[&path.display(), e]),
+            ConfigError::Syntax { line } => fill(Msg::ConfigSyntax, &[line]),
+            ConfigError::UnknownKey { line, key } => fill(Msg::ConfigUnknownKey, &[line, key]),
+            ConfigError::InvalidValue {
+                line,
+                key,
+                expected,
+            } => fill(Msg::ConfigInvalidValue, &[line, key, &text(*expected)]),
+            ConfigError::Rules(e) => e.to_string(),
+            ConfigError::AlreadyExists(path) => fill(Msg::ConfigAlreadyExists, &[&path.display()]),
+            ConfigError::NoConfigDir => String::from(text(Msg::NoConfigDir)),
+        };
+
+        write!(f, "{out}")
+    }
+}
+
+// why a single option couldn't be set, without knowing where it came from
+#[derive(Debug)]
+pub enum SetError {
+    UnknownKey,
+    // describes what the key expects
+    InvalidValue(Msg),
+}
+
+fn parse_bool(value: &str) -> Result<bool, SetError> {
+    match value {
+        "true" | "on" | "yes" => Ok(true),
+        "false" | "off" | "no" => Ok(false),
+        _ => Err(SetError::InvalidValue(Msg::ExpectTrueOrFalse)),
+    }
+}
+
+fn parse_size(value: &str) -> Result<usize, SetError> {
+    value
+        .parse()
+        .ok()
+        .filter(|&size| size >= 1)
+        .ok_or(SetError::InvalidValue(Msg::ExpectSize))
+}
+
+fn parse_whole(value: &str) -> Result<usize, SetError> {
+    value
+        .parse()
+        .map_err(|_| SetError::InvalidValue(Msg::ExpectWholeNumber))
+}
+
+// 0 is no limit
+fn parse_limit(value: &str) -> Result<Option<usize>, SetError> {
+    parse_whole(value).map(|limit| (limit > 0).then_some(limit))
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join(CONFIG_FILE))
+    }
+
+    // the built-in defaults, overridden by the config file if there is one
+    pub fn load() -> Result<Self, ConfigError> {
+        let mut config = Config::default();
+
+        let Some(path) = Self::path() else {
+            return Ok(config);
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(text) => config.apply_file(&text)?,
+            Err(e) if e.kind() == ErrorKind::NotFound => (),
+            Err(e) => return Err(ConfigError::Io(path, e)),
+        }
+
+        Ok(config)
+    }
+
+    // checks the options that depend on each other, once they are all set
+    // (by the file and then the command line)
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        self.ruleset().map(drop).map_err(ConfigError::Rules)
+    }
+
+    // writes a commented copy of the defaults, for `config init`
+    pub fn init() -> Result<PathBuf, ConfigError> {
+        let path = Self::path().ok_or(ConfigError::NoConfigDir)?;
+        if path.exists() {
+            return Err(ConfigError::AlreadyExists(path));
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| ConfigError::Io(parent.to_path_buf(), e))?;
+        }
+        fs::write(&path, DEFAULT_CONFIG).map_err(|e| ConfigError::Io(path.clone(), e))?;
+
+        Ok(path)
+    }
+
+    // accepts the flat `key = value` subset of TOML, with `#` comments
+    pub fn apply_file(&mut self, text: &str) -> Result<(), ConfigError> {
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = match line.split_once('#') {
+                // a # inside a quoted value isn't a comment
+                Some((before, _)) if before.matches('"').count() % 2 == 0 => before,
+                _ => line,
+            }
+            .trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or(ConfigError::Syntax { line: line_number })?;
+            let key = key.trim();
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .unwrap_or(value);
+
+            self.set(key, value).map_err(|e| match e {
+                SetError::UnknownKey => ConfigError::UnknownKey {
+                    line: line_number,
+                    key: key.to_string(),
+                },
+                SetError::InvalidValue(expected) => ConfigError::InvalidValue {
+                    line: line_number,
+                    key: key.to_string(),
+                    expected,
+                },
+            })?;
+        }
+
+        Ok(())
+    }
+
+    // sets one option from its textual value
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), SetError> {
+        match key {
+            "size" => {
+                let size = parse_size(value)?;
+                self.height = size;
+                self.width = size;
+            }
+            "width" => self.width = parse_size(value)?,
+            "height" => self.height = parse_size(value)?,
+            "mines" => {
+                self.mines = Mines::Count(
+                    value
+                        .parse()
+                        .map_err(|_| SetError::InvalidValue(Msg::ExpectWholeNumber))?,
+                );
+            }
+            "density" => {
+                self.mines = Mines::Density(
+                    value
+                        .parse()
+                        .ok()
+                        .filter(|density| (0.0..=1.0).contains(density))
+                        .ok_or(SetError::InvalidValue(Msg::ExpectDensity))?,
+                );
+            }
+            "theme" => {
+                self.theme = match value {
+                    "ascii" => Theme::Ascii,
+                    "emoji" => Theme::Emoji,
+                    _ => return Err(SetError::InvalidValue(Msg::ExpectTheme)),
+                }
+            }
+            "color" | "colour" => self.color = parse_bool(value)?,
+            "plain" => self.plain = parse_bool(value)?,
+            "banner" => {
+                self.banner = match value {
+                    "block" => BannerStyle::Block,
+                    "outline" => BannerStyle::Outline,
+                    "none" => BannerStyle::None,
+                    _ => return Err(SetError::InvalidValue(Msg::ExpectBanner)),
+                }
+            }
+            "coordinates" => {
+                self.coordinates = match value {
+                    "numeric" => CoordinateStyle::Numeric,
+                    "letters" => CoordinateStyle::Letters,
+                    _ => return Err(SetError::InvalidValue(Msg::ExpectCoordinates)),
+                }
+            }
+            "assist" => {
+                self.assist = match value {
+                    "none" => AssistLevel::None,
+                    "hints" => AssistLevel::Hints,
+                    "full" => AssistLevel::Full,
+                    _ => return Err(SetError::InvalidValue(Msg::ExpectAssist)),
+                }
+            }
+            "timer" => self.timer = parse_bool(value)?,
+            "idle_after" => {
+                self.idle_after = value
+                    .parse()
+                    .map_err(|_| SetError::InvalidValue(Msg::ExpectWholeNumber))?
+            }
+            "watch_delay_ms" => {
+                self.watch_delay_ms = value
+                    .parse()
+                    .map_err(|_| SetError::InvalidValue(Msg::ExpectWholeNumber))?
+            }
+            "peeks" => {
+                self.peeks = value
+                    .parse()
+                    .map_err(|_| SetError::InvalidValue(Msg::ExpectWholeNumber))?
+            }
+            "shields" => self.shields = parse_bool(value)?,
+            "win" => {
+                self.win = match value {
+                    "open" => WinCondition::OpenAll,
+                    "flags" => WinCondition::FlagAll,
+                    _ => return Err(SetError::InvalidValue(Msg::ExpectWin)),
+                }
+            }
+            "symmetry" => {
+                self.symmetry = match value {
+                    "none" => Symmetry::None,
+                    "horizontal" => Symmetry::Horizontal,
+                    "vertical" => Symmetry::Vertical,
+                    "rotational" => Symmetry::Rotational,
+                    _ => return Err(SetError::InvalidValue(Msg::ExpectSymmetry)),
+                }
+            }
+            "distribution" => {
+                self.distribution = match value {
+                    "uniform" => MineDistribution::Uniform,
+                    "clustered" => MineDistribution::Clustered,
+                    "sparse" => MineDistribution::Sparse,
+                    _ => return Err(SetError::InvalidValue(Msg::ExpectDistribution)),
+                }
+            }
+            "min_per_row" => self.limits.min_per_row = parse_whole(value)?,
+            "max_per_row" => self.limits.max_per_row = parse_limit(value)?,
+            "min_per_column" => self.limits.min_per_column = parse_whole(value)?,
+            "max_per_column" => self.limits.max_per_column = parse_limit(value)?,
+            "rocks" => {
+                self.rocks = value
+                    .parse()
+                    .map_err(|_| SetError::InvalidValue(Msg::ExpectWholeNumber))?
+            }
+            "max_cells" => self.max_cells = parse_whole(value)?,
+            "gauntlet_minutes" => {
+                self.gauntlet_minutes = value
+                    .parse()
+                    .ok()
+                    .filter(|&minutes| minutes > 0)
+                    .ok_or(SetError::InvalidValue(Msg::ExpectPositiveNumber))?
+            }
+            "moving_mines" => {
+                self.moving_mines = value
+                    .parse()
+                    .map_err(|_| SetError::InvalidValue(Msg::ExpectWholeNumber))?
+            }
+            "moving_fraction" => {
+                self.moving_fraction = value
+                    .parse()
+                    .ok()
+                    .filter(|fraction| (0.0..=1.0).contains(fraction))
+                    .ok_or(SetError::InvalidValue(Msg::ExpectDensity))?
+            }
+            "redraw" => self.redraw = parse_bool(value)?,
+            "difficulty" => self.difficulty = parse_bool(value)?,
+            "flag_scoring" => {
+                self.flag_scoring = FlagScoring::from_name(value)
+                    .ok_or(SetError::InvalidValue(Msg::ExpectFlagScoring))?
+            }
+            // the setting before there was a choice of how to count them
+            "efficiency_flags" => {
+                self.flag_scoring = match parse_bool(value)? {
+                    true => FlagScoring::Placements,
+                    false => FlagScoring::Ignore,
+                }
+            }
+            "count_resigned" => self.count_resigned = parse_bool(value)?,
+            "ghost" => self.ghost = parse_bool(value)?,
+            "echo_moves" => self.echo_moves = parse_bool(value)?,
+            "confirm_near_flags" => self.confirm_near_flags = parse_bool(value)?,
+            "debug_dump" => self.debug_dump = parse_bool(value)?,
+            "bell" => self.bell = parse_bool(value)?,
+            "bell_on_win" => self.bell_on_win = parse_bool(value)?,
+            "flash" => self.flash = parse_bool(value)?,
+            _ => return Err(SetError::UnknownKey),
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+use crate::autosave;
+use crate::board::{FlagChange, Mines};
+use crate::command::Command;
+use crate::config::Config;
+use crate::driver::{self, GameConfig, GameInput, Hooks, Io, Screen};
+use crate::game::{Game, GameOutcome, GameState, MoveOutcome};
+use crate::i18n::{fill, text, Msg};
+use crate::input;
+use crate::output::StdoutOutput;
+use crate::postgame;
+use crate::render::{render_mark, render_note};
+use crate::run::{carry_out, check_target};
+use crate::save::Mode;
+use rand::random;
+
+const PLAYERS: usize = 2;
+const SHARED_LIVES: u32 = 3;
+
+#[derive(Default)]
+struct PlayerStats {
+    opened: usize,
+    flags: usize,
+    mistakes: usize,
+}
+
+// two players alternate moves on one board and share a pool of lives;
+// turn order follows the move log, so undoing a move also hands the
+// turn back to whoever made it
+pub fn new_game(config: &Config, seed: Option<u64>) -> (String, Game) {
+    let seed = seed.unwrap_or_else(random);
+    (
+        format!("Co-op #{seed}"),
+        Game::from_ruleset(
+            &config
+                .rules()
+                .lives(SHARED_LIVES)
+                .build()
+                .expect("The config should be checked before a game starts"),
+            seed,
+        ),
+    )
+}
+
+pub fn play(
+    game: Game,
+    code: String,
+    config: &Config,
+    input: &mut dyn GameInput,
+    requested: Option<Mines>,
+) -> GameOutcome {
+    let code = code.as_str();
+    let turn = |game: &Game| fill(Msg::PlayerTurn, &[&(game.moves().len() % PLAYERS + 1)]);
+    println!("{}", turn(&game));
+
+    // each move is told with who made it, and then whose turn it is now
+    let after_move = |game: &Game, command: &Command, _: bool, io: &mut Io| {
+        autosave::save(Mode::Coop, code, game);
+        let feedback = match (command, game.moves().last()) {
+            (Command::Undo, _) => Some(text(Msg::MoveUndone).to_string()),
+            (Command::Rewind(moves), _) => Some(fill(Msg::Rewound, &[moves])),
+            (&Command::Note(position, note), _) => Some(render_note(position, note)),
+            (_, Some(last)) => {
+                let description = match last.mark() {
+                    Some((change, position)) => render_mark(change, position, game.board()),
+                    None => last.to_string(),
+                };
+                Some(fill(Msg::PlayerMove, &[&(last.player + 1), &description]))
+            }
+            (_, None) => None,
+        };
+        if let Some(feedback) = feedback {
+            io.output.show(&Screen::Message(feedback));
+        }
+        if let Some(MoveOutcome::HitBomb(_)) = game.moves().last().map(|last| last.outcome) {
+            io.output
+                .show(&Screen::Message(fill(Msg::LivesLeft, &[&game.lives()])));
+        }
+        if game.state() == GameState::Playing {
+            io.output.show(&Screen::Message(turn(game)));
+        }
+    };
+    let on_command = |game: &mut Game, command: &Command, io: &mut Io| {
+        carry_out(game, command, io, Mode::Coop, code, config, requested)
+    };
+    // an aborted game stays autosaved so it can be resumed
+    let on_game_end = |game: &Game, outcome: GameOutcome, io: &mut Io| {
+        if outcome == GameOutcome::Aborted {
+            return;
+        }
+        autosave::discard();
+
+        let mut stats: [PlayerStats; PLAYERS] = Default::default();
+        for game_move in game.moves() {
+            let player_stats = &mut stats[game_move.player];
+            match game_move.outcome {
+                MoveOutcome::Opened(count) | MoveOutcome::AreaOpened { opened: count, .. } => {
+                    player_stats.opened += count
+                }
+                MoveOutcome::Marked(FlagChange::FlagPlaced) => player_stats.flags += 1,
+                MoveOutcome::HitBomb(_) | MoveOutcome::Defused(_) => player_stats.mistakes += 1,
+                MoveOutcome::Marked(_) | MoveOutcome::Peeked(_) => (),
+            }
+        }
+        for (player, player_stats) in stats.iter().enumerate() {
+            let line = fill(
+                Msg::PlayerStats,
+                &[
+                    &(player + 1),
+                    &player_stats.opened,
+                    &player_stats.flags,
+                    &player_stats.mistakes,
+                ],
+            );
+            io.output.show(&Screen::Message(line));
+        }
+
+        postgame::run(game, code, config);
+    };
+
+    let hooks = Hooks {
+        on_move: Some(Box::new(|game: &Game, command: &Command, io: &mut Io| {
+            check_target(game, command, io, config)
+        })),
+        after_move: Some(Box::new(after_move)),
+        on_command: Some(Box::new(on_command)),
+        on_board_render: None,
+        on_game_end: Some(Box::new(on_game_end)),
+    };
+    let setup = GameConfig {
+        game,
+        retries: input::piped_retries(),
+        idle_limit: config.idle_limit(),
+        prompt: Msg::CoopPrompt,
+        players: PLAYERS,
+    };
+    let output = &mut StdoutOutput { config };
+    driver::run_game(setup, Io { input, output }, hooks)
+}
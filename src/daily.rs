@@ -0,0 +1,142 @@
+use crate::analysis::{render_efficiency, Efficiency};
+use crate::autosave;
+use crate::board::{Board, Mines};
+use crate::calendar::{civil_from_days, iso_week};
+use crate::command::Command;
+use crate::config::Config;
+use crate::driver::{self, GameConfig, GameInput, Hooks, Io, Screen, Verdict};
+use crate::game::{Game, GameOutcome};
+use crate::history::{self, Record};
+use crate::i18n::{fill, text, Msg};
+use crate::input;
+use crate::output::StdoutOutput;
+use crate::postgame;
+use crate::render::render_last_move;
+use crate::run::{carry_out, check_target};
+use crate::save::Mode;
+use crate::share::share_block;
+use crate::weekly::{self, Modifier};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn days_since_epoch() -> i64 {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    (seconds / 86_400) as i64
+}
+
+pub const MINES: Mines = Mines::Density(1.0 / 6.0);
+
+// everyone playing on the same (UTC) day gets the same board
+pub fn new_game() -> (String, Game) {
+    let days = days_since_epoch();
+    let (year, month, day) = civil_from_days(days);
+
+    (
+        format!("Daily {year:04}-{month:02}-{day:02}"),
+        // the size is fixed rather than configurable so that everyone
+        // really does get the same board
+        Game::new(Board::from_seed(days as u64, 10, 10, MINES), 1),
+    )
+}
+
+// what the week's code starts with, before its tag
+const WEEKLY: &str = "Weekly";
+
+// everyone playing in the same (UTC) ISO week gets the same board, by the
+// same twist on the rules
+pub fn new_weekly() -> (String, Game, &'static Modifier) {
+    let (year, week) = iso_week(days_since_epoch());
+    (
+        format!("{WEEKLY} {}", weekly::tag(year, week)),
+        weekly::new_game(year, week),
+        weekly::modifier(week),
+    )
+}
+
+pub fn render_modifier(modifier: &Modifier) -> String {
+    fill(
+        Msg::WeeklyModifier,
+        &[&modifier.name, &text(modifier.description)],
+    )
+}
+
+// the daily or the weekly challenge, which are played the same way and kept
+// in the history, a week's games with the week they were played in
+pub fn play(
+    mode: Mode,
+    game: Game,
+    code: String,
+    config: &Config,
+    input: &mut dyn GameInput,
+    requested: Option<Mines>,
+) -> GameOutcome {
+    println!("{code}");
+    let code = code.as_str();
+
+    // nobody else gets to take a move back
+    let on_move = |game: &Game, command: &Command, io: &mut Io| {
+        let refused = match (command, mode) {
+            (Command::Undo, Mode::Weekly) => Msg::UndoNotInWeekly,
+            (Command::Rewind(_), Mode::Weekly) => Msg::RewindNotInWeekly,
+            (Command::Undo, _) => Msg::UndoNotInDaily,
+            (Command::Rewind(_), _) => Msg::RewindNotInDaily,
+            _ => return check_target(game, command, io, config),
+        };
+        io.output.show(&Screen::Message(text(refused).to_string()));
+        Verdict::Veto
+    };
+    let after_move = |game: &Game, _: &Command, several: bool, io: &mut Io| {
+        autosave::save(mode, code, game);
+        if let Some(feedback) = render_last_move(game, several) {
+            io.output.show(&Screen::Message(feedback));
+        }
+    };
+    let on_command = |game: &mut Game, command: &Command, io: &mut Io| {
+        carry_out(game, command, io, mode, code, config, requested)
+    };
+    // an aborted game stays autosaved so it can be resumed
+    let on_game_end = |game: &Game, outcome: GameOutcome, io: &mut Io| {
+        if outcome == GameOutcome::Aborted {
+            return;
+        }
+        autosave::discard();
+
+        let week = code.strip_prefix(WEEKLY).map(str::trim_start);
+        let record = Record::new(game, outcome, code, config.flag_scoring).map(|record| Record {
+            week: week.filter(|_| mode == Mode::Weekly).map(str::to_string),
+            ..record
+        });
+        if let Some(Err(e)) = record.map(|record| history::append(&record)) {
+            let e = fill(Msg::CouldntWriteHistory, &[&e]);
+            io.output.show(&Screen::Message(e));
+        }
+
+        if let GameOutcome::Won(won) = outcome {
+            let efficiency = Efficiency::of(game);
+            let line = render_efficiency(&efficiency, won.elapsed, config.flag_scoring);
+            io.output.show(&Screen::Message(line));
+        }
+        io.output.show(&Screen::Message(share_block(game, code)));
+
+        postgame::run(game, code, config);
+    };
+
+    let hooks = Hooks {
+        on_move: Some(Box::new(on_move)),
+        after_move: Some(Box::new(after_move)),
+        on_command: Some(Box::new(on_command)),
+        on_board_render: None,
+        on_game_end: Some(Box::new(on_game_end)),
+    };
+    let setup = GameConfig {
+        game,
+        retries: input::piped_retries(),
+        idle_limit: config.idle_limit(),
+        prompt: Msg::DailyPrompt,
+        players: 1,
+    };
+    let output = &mut StdoutOutput { config };
+    driver::run_game(setup, Io { input, output }, hooks)
+}
@@ -0,0 +1,544 @@
+// the interactive game loop, for the binary's ways of playing and for anything
+// else that embeds the game: lines come in through a GameInput and screens go
+// out through a GameOutput, and hooks get a say at each step in between
+use crate::analysis::{render_analysis, render_board_info, render_remaining, BoardInfo, Remaining};
+use crate::board::{CellPosition, ClearError};
+use crate::command::{self, check_coordinate, parse_coordinate, Command, CoordinateError, Field};
+use crate::game::{Game, GameError, GameOutcome, GameState};
+use crate::i18n::{fill, text, Msg};
+use crate::odds::{render_estimate, WinEstimate};
+use crate::reference::render_commands;
+use crate::timer::format_duration;
+use std::fmt::Display;
+use std::io;
+use std::str::FromStr;
+use std::time::Duration;
+
+// where the interactive game loops read their lines from
+pub trait GameInput {
+    // the next line without its line ending, or None at the end of input;
+    // a line the player cancelled is an Interrupted error
+    fn read_command(&mut self) -> io::Result<Option<String>>;
+}
+
+// something for a game loop to show the player
+pub enum Screen<'a> {
+    // the status line and the board as the player sees it
+    Board(&'a Game),
+    // every mine, once the game is over
+    Revealed(&'a Game),
+    Prompt(Msg),
+    // feedback on a move, an error, or anything else that is a line of text
+    Message(String),
+    // between one turn and the next
+    Divider,
+    // nothing of the game may show while it is paused
+    Paused,
+}
+
+// where the game loops send what they show
+pub trait GameOutput {
+    fn show(&mut self, screen: &Screen);
+}
+
+// "y" is understood whatever the language
+pub fn is_yes(answer: &str) -> bool {
+    let answer = answer.trim();
+    answer.eq_ignore_ascii_case("y") || answer.eq_ignore_ascii_case(text(Msg::YesAnswer))
+}
+
+// a row or a column typed on its own line, into `length` of them
+pub fn parse_index(line: &str, field: Field, length: usize) -> Result<usize, CoordinateError> {
+    check_coordinate(parse_coordinate(line.trim())?, field, length)
+}
+
+#[derive(Debug)]
+pub enum PromptError {
+    // the input ended before an answer that parsed
+    Eof,
+    // how many answers in a row didn't parse before the retries ran out
+    TooManyRetries(u32),
+    Io(io::Error),
+}
+
+impl Display for PromptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let out = match self {
+            PromptError::Eof => text(Msg::PromptEof).to_string(),
+            PromptError::TooManyRetries(answers) => fill(Msg::PromptGaveUp, &[answers]),
+            PromptError::Io(e) => e.to_string(),
+        };
+
+        write!(f, "{out}")
+    }
+}
+
+// asks with `msg` until an answer parses, saying what was wrong with each
+// one that didn't; with `max_retries`, gives up after that many more tries
+// rather than asking forever, for input nobody is typing
+pub fn prompt_parse<T: FromStr>(
+    input: &mut dyn GameInput,
+    output: &mut dyn GameOutput,
+    msg: Msg,
+    max_retries: Option<u32>,
+) -> Result<T, PromptError>
+where
+    T::Err: Display,
+{
+    prompt_with(input, output, msg, max_retries, |line| line.trim().parse())
+}
+
+// likewise, with `parse` deciding what a valid answer is
+pub fn prompt_with<T, E: Display>(
+    input: &mut dyn GameInput,
+    output: &mut dyn GameOutput,
+    msg: Msg,
+    max_retries: Option<u32>,
+    parse: impl Fn(&str) -> Result<T, E>,
+) -> Result<T, PromptError> {
+    let mut invalid = 0;
+    loop {
+        output.show(&Screen::Prompt(msg));
+        let line = input
+            .read_command()
+            .map_err(PromptError::Io)?
+            .ok_or(PromptError::Eof)?;
+        match parse(&line) {
+            Ok(answer) => return Ok(answer),
+            Err(e) => output.show(&Screen::Message(e.to_string())),
+        }
+
+        invalid += 1;
+        if max_retries.is_some_and(|max_retries| invalid > max_retries) {
+            return Err(PromptError::TooManyRetries(invalid));
+        }
+    }
+}
+
+// the player's next line, with any wait for it past `idle_limit` left off
+// the timer, and the player told so
+pub fn read_move(
+    game: &mut Game,
+    input: &mut dyn GameInput,
+    output: &mut dyn GameOutput,
+    idle_limit: Option<Duration>,
+) -> io::Result<Option<String>> {
+    game.prompted();
+    let line = input.read_command()?;
+    if let Some(idle) = game.answered(idle_limit) {
+        output.show(&Screen::Message(fill(
+            Msg::IdleNotCounted,
+            &[&format_duration(idle)],
+        )));
+    }
+
+    Ok(line)
+}
+
+// hides the game until the player types `resume`, or gives it up with
+// `resign`; only `help` is answered meanwhile, since it shows nothing of the
+// board. False if the input ends first
+pub fn wait_for_resume(
+    game: &mut Game,
+    input: &mut dyn GameInput,
+    output: &mut dyn GameOutput,
+) -> bool {
+    output.show(&Screen::Paused);
+    let resign = loop {
+        let line = match input.read_command() {
+            Ok(None) => return false,
+            Ok(Some(line)) => line,
+            Err(_) => continue,
+        };
+        match line.parse() {
+            Ok(Command::Resume) => break false,
+            Ok(Command::Help) => output.show(&Screen::Message(render_commands())),
+            Ok(Command::Resign) if confirm_resign(input, output) => break true,
+            _ => (),
+        }
+    };
+
+    game.apply(0, Command::Resume)
+        .expect("A paused game should be able to resume");
+    if resign {
+        game.apply(0, Command::Resign)
+            .expect("A game still being played should be able to resign");
+    }
+    true
+}
+
+// a resigned game can't be taken back, so the player is asked first;
+// false if they didn't say yes
+pub fn confirm_resign(input: &mut dyn GameInput, output: &mut dyn GameOutput) -> bool {
+    output.show(&Screen::Prompt(Msg::ConfirmResign));
+    let confirmed = matches!(input.read_command(), Ok(Some(answer)) if is_yes(&answer));
+    if !confirmed {
+        output.show(&Screen::Message(text(Msg::MoveLeftUndone).to_string()));
+    }
+    confirmed
+}
+
+// a move by `player`, with a board that contradicts itself reported as the
+// bug it is
+pub fn play(game: &mut Game, player: usize, command: Command) -> Result<GameState, String> {
+    game.apply(player, command).map_err(|e| match e {
+        GameError::Clear(ClearError::Inconsistent { .. }) => {
+            format!("{e}\n{}", text(Msg::ReportInconsistent))
+        }
+        e => e.to_string(),
+    })
+}
+
+// where a game is read from and shown, together, for the hooks to ask and
+// tell the player through as well
+pub struct Io<'a> {
+    pub input: &'a mut dyn GameInput,
+    pub output: &'a mut dyn GameOutput,
+}
+
+pub struct GameConfig {
+    pub game: Game,
+    // how many more tries the column prompt gives a wrong answer; None to
+    // keep asking
+    pub retries: Option<u32>,
+    // see `read_move`
+    pub idle_limit: Option<Duration>,
+    // what the player is asked for each turn
+    pub prompt: Msg,
+    // how many take turns at the board, a command a turn when there are
+    // several; whose turn it is follows the move log, so an undo hands the
+    // turn back to whoever made the move
+    pub players: usize,
+}
+
+// whether a move goes ahead
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Verdict {
+    Allow,
+    Veto,
+    // the game is left where it is, unfinished, as if the input had ended
+    Stop,
+}
+
+pub type OnMove<'a> = Box<dyn FnMut(&Game, &Command, &mut Io) -> Verdict + 'a>;
+pub type AfterMove<'a> = Box<dyn FnMut(&Game, &Command, bool, &mut Io) + 'a>;
+pub type OnCommand<'a> = Box<dyn FnMut(&mut Game, &Command, &mut Io) -> Result<bool, String> + 'a>;
+pub type OnBoardRender<'a> = Box<dyn FnMut(&Game) -> Option<String> + 'a>;
+pub type OnGameEnd<'a> = Box<dyn FnMut(&Game, GameOutcome, &mut Io) + 'a>;
+
+// what the embedder does at each step of a game, all of it optional
+#[derive(Default)]
+pub struct Hooks<'a> {
+    // asked before each move (a clear, a flag, an undo and the like) is
+    // made; a veto leaves it unmade and goes on to the rest of the line
+    pub on_move: Option<OnMove<'a>>,
+    // told of each move once it is made, with whether others came with it
+    // on the same line
+    pub after_move: Option<AfterMove<'a>>,
+    // offered each command that isn't a move, pausing and resigning too,
+    // before the loop deals with it: true if the hook did, and an error
+    // abandons the rest of the line
+    pub on_command: Option<OnCommand<'a>>,
+    // more to show under the board, each time it is shown
+    pub on_board_render: Option<OnBoardRender<'a>>,
+    // once the game is over or left, after its mines are shown
+    pub on_game_end: Option<OnGameEnd<'a>>,
+}
+
+// the commands that change the board or the moves, which go by `on_move`
+fn is_move(command: &Command) -> bool {
+    matches!(
+        command,
+        Command::Clear(_)
+            | Command::ClearArea(..)
+            | Command::Flag(_)
+            | Command::Chord(_)
+            | Command::Question(_)
+            | Command::Note(..)
+            | Command::Peek(_)
+            | Command::Undo
+            | Command::Rewind(_)
+    )
+}
+
+// what the loop shows for a command no hook dealt with
+fn answer(game: &Game, command: &Command) -> String {
+    match command {
+        Command::Help => render_commands(),
+        Command::BoardInfo => render_board_info(&BoardInfo::of(game.board()), None),
+        Command::Analyze => render_analysis(game.board()),
+        Command::Remaining => render_remaining(&Remaining::of(game.board())),
+        Command::WinProb => render_estimate(WinEstimate::of(game.board(), &*game.clock()).as_ref()),
+        command => fill(Msg::CommandNotHere, &[command]),
+    }
+}
+
+// how one command on a line went
+enum Step {
+    Next,
+    // the rest of the line is left
+    Abandon,
+    Over(GameOutcome),
+}
+
+fn step(
+    game: &mut Game,
+    command: Command,
+    several: bool,
+    io: &mut Io,
+    hooks: &mut Hooks,
+    players: usize,
+) -> Step {
+    let player = game.moves().len() % players;
+    if is_move(&command) {
+        match hooks
+            .on_move
+            .as_mut()
+            .map(|on_move| on_move(game, &command, io))
+        {
+            Some(Verdict::Veto) => return Step::Next,
+            Some(Verdict::Stop) => return Step::Over(GameOutcome::Aborted),
+            Some(Verdict::Allow) | None => (),
+        }
+        if let Err(e) = play(game, player, command.clone()) {
+            io.output.show(&Screen::Message(e));
+            return Step::Abandon;
+        }
+        if let Some(after_move) = &mut hooks.after_move {
+            after_move(game, &command, several, io);
+        }
+    } else {
+        let handled = match &mut hooks.on_command {
+            Some(on_command) => on_command(game, &command, io),
+            None => Ok(false),
+        };
+        match handled {
+            Ok(true) => (),
+            Ok(false) => {
+                if let Some(step) = answer_command(game, player, command, io) {
+                    return step;
+                }
+            }
+            Err(e) => {
+                io.output.show(&Screen::Message(e));
+                return Step::Abandon;
+            }
+        }
+    }
+
+    match game.outcome() {
+        Some(outcome) => Step::Over(outcome),
+        None => Step::Next,
+    }
+}
+
+// a command that isn't a move and that no hook dealt with; a step if it
+// ends the line there
+fn answer_command(game: &mut Game, player: usize, command: Command, io: &mut Io) -> Option<Step> {
+    match command {
+        Command::Pause => {
+            if let Err(e) = game.apply(player, Command::Pause) {
+                io.output.show(&Screen::Message(e.to_string()));
+                return Some(Step::Abandon);
+            }
+            if !wait_for_resume(game, io.input, io.output) {
+                return Some(Step::Over(GameOutcome::Aborted));
+            }
+        }
+        // there is nothing to resume from, since a pause waits for it
+        Command::Resume => {
+            if let Err(e) = play(game, player, Command::Resume) {
+                io.output.show(&Screen::Message(e));
+                return Some(Step::Abandon);
+            }
+        }
+        Command::Resign => {
+            if !confirm_resign(io.input, io.output) {
+                return Some(Step::Next);
+            }
+            if let Err(e) = play(game, player, Command::Resign) {
+                io.output.show(&Screen::Message(e));
+                return Some(Step::Abandon);
+            }
+        }
+        command => io.output.show(&Screen::Message(answer(game, &command))),
+    }
+    None
+}
+
+// turns until the game ends or the input does: a row and then a column,
+// or a line of commands
+fn turns(
+    game: &mut Game,
+    io: &mut Io,
+    hooks: &mut Hooks,
+    retries: Option<u32>,
+    idle_limit: Option<Duration>,
+    prompt: Msg,
+    players: usize,
+) -> GameOutcome {
+    loop {
+        io.output.show(&Screen::Board(game));
+        if let Some(overlay) = hooks
+            .on_board_render
+            .as_mut()
+            .and_then(|render| render(game))
+        {
+            io.output.show(&Screen::Message(overlay));
+        }
+
+        io.output.show(&Screen::Prompt(prompt));
+        let line = match read_move(game, io.input, io.output, idle_limit) {
+            Ok(Some(line)) => line,
+            Ok(None) => return GameOutcome::Aborted,
+            Err(e) => {
+                io.output.show(&Screen::Message(e.to_string()));
+                continue;
+            }
+        };
+
+        // anything that isn't a row index is a line of commands, e.g. "f 3 4"
+        // or "f 0 1; c 3 3"; digits too many for any row are still a row
+        let row_index = parse_coordinate(line.trim());
+        if let Err(e @ CoordinateError::TooLarge(_)) = &row_index {
+            io.output.show(&Screen::Message(e.to_string()));
+            continue;
+        }
+        let Ok(row_index) = row_index else {
+            let (height, width) = (game.board().height(), game.board().width());
+            let commands = match command::parse_line_within(&line, height, width) {
+                Ok(commands) => commands,
+                Err(e) => {
+                    io.output.show(&Screen::Message(e.to_string()));
+                    continue;
+                }
+            };
+            let several = commands.len() > 1;
+            // a line of several would be several turns at once
+            if several && players > 1 {
+                io.output
+                    .show(&Screen::Message(text(Msg::OneCommandPerTurn).to_string()));
+                continue;
+            }
+            for command in commands {
+                match step(game, command, several, io, hooks, players) {
+                    Step::Next => (),
+                    Step::Abandon => break,
+                    Step::Over(outcome) => return outcome,
+                }
+            }
+            io.output.show(&Screen::Divider);
+            continue;
+        };
+
+        let row_index = match check_coordinate(row_index, Field::Row, game.board().height()) {
+            Ok(row_index) => row_index,
+            Err(e) => {
+                io.output.show(&Screen::Message(e.to_string()));
+                continue;
+            }
+        };
+
+        // a mistake in the column asks for the column again, keeping the row
+        let width = game.board().width();
+        game.prompted();
+        let col_index = prompt_with(io.input, io.output, Msg::EnterColIndex, retries, |line| {
+            parse_index(line, Field::Column, width)
+        });
+        if let Some(idle) = game.answered(idle_limit) {
+            let idle = fill(Msg::IdleNotCounted, &[&format_duration(idle)]);
+            io.output.show(&Screen::Message(idle));
+        }
+        let col_index = match col_index {
+            Ok(col_index) => col_index,
+            Err(PromptError::Eof) => return GameOutcome::Aborted,
+            Err(e @ PromptError::TooManyRetries(_)) => {
+                io.output.show(&Screen::Message(e.to_string()));
+                return GameOutcome::Aborted;
+            }
+            Err(e) => {
+                io.output.show(&Screen::Message(e.to_string()));
+                continue;
+            }
+        };
+
+        let position = CellPosition {
+            row_index,
+            col_index,
+        };
+        match step(game, Command::Clear(position), false, io, hooks, players) {
+            Step::Over(outcome) => return outcome,
+            // the error was shown, and there is nothing else on the line
+            Step::Abandon => continue,
+            Step::Next => io.output.show(&Screen::Divider),
+        }
+    }
+}
+
+// a board that is no game at all, or only a guess, which every way of
+// playing says before the first move
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Degenerate {
+    // won before it starts; a layout or a ruleset can't make such a board,
+    // but a caller's own board can
+    NoSafeCells,
+    // nothing to work out, only a guess to make, on a board of this many
+    // cells
+    OneSafeCell(usize),
+}
+
+impl Degenerate {
+    // only a game yet to be played is a guess
+    pub fn of(game: &Game) -> Option<Self> {
+        let board = game.board();
+        match board.count_safe() {
+            0 => Some(Degenerate::NoSafeCells),
+            1 if game.moves().is_empty() => {
+                Some(Degenerate::OneSafeCell(board.height() * board.width()))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Display for Degenerate {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Degenerate::NoSafeCells => write!(f, "{}", text(Msg::NoSafeCells)),
+            Degenerate::OneSafeCell(cells) => write!(f, "{}", fill(Msg::OneSafeCell, &[cells])),
+        }
+    }
+}
+
+// plays `config.game` out, to its end or the input's; every mine is shown
+// unless the game was left unfinished, so that it can still be resumed
+pub fn run_game(config: GameConfig, mut io: Io, mut hooks: Hooks) -> GameOutcome {
+    let GameConfig {
+        mut game,
+        retries,
+        idle_limit,
+        prompt,
+        players,
+    } = config;
+
+    match Degenerate::of(&game) {
+        Some(degenerate @ Degenerate::NoSafeCells) => {
+            io.output.show(&Screen::Message(degenerate.to_string()));
+            return GameOutcome::Aborted;
+        }
+        Some(degenerate) => io.output.show(&Screen::Message(degenerate.to_string())),
+        None => (),
+    }
+
+    let outcome = turns(
+        &mut game, &mut io, &mut hooks, retries, idle_limit, prompt, players,
+    );
+    if outcome != GameOutcome::Aborted {
+        io.output.show(&Screen::Revealed(&game));
+    }
+    if let Some(on_game_end) = &mut hooks.on_game_end {
+        on_game_end(&game, outcome, &mut io);
+    }
+
+    outcome
+}
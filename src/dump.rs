@@ -0,0 +1,244 @@
+use crate::board::{Board, CellState, LayoutError};
+use crate::command::Command;
+use crate::game::{Game, MovingMines, WinCondition};
+use crate::i18n::{fill, text, Msg};
+use crate::timer::Clock;
+use std::fmt::Display;
+use std::sync::Arc;
+
+// the lines a dump starts and ends with, so that it can't pass for anything
+// the game showed while being played, and can be picked out of a pasted
+// bug report
+pub const DUMP_BEGIN: &str = "===== MINESWEEPER DEBUG DUMP: NOT GAME OUTPUT, MINES SHOWN =====";
+pub const DUMP_END: &str = "===== END OF DEBUG DUMP =====";
+
+#[derive(Debug)]
+pub enum DumpError {
+    // no line between DUMP_BEGIN and DUMP_END
+    Missing,
+    Malformed(String),
+    // replaying the dump's moves on its board doesn't give the board it shows
+    Mismatch,
+}
+
+impl Display for DumpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let out = match self {
+            DumpError::Missing => text(Msg::DumpMissing).to_string(),
+            DumpError::Malformed(detail) => fill(Msg::DumpMalformed, &[detail]),
+            DumpError::Mismatch => text(Msg::DumpMismatch).to_string(),
+        };
+
+        write!(f, "{out}")
+    }
+}
+
+// the game rebuilt from a dump, with the code it was played under
+pub struct DebugDump {
+    pub code: String,
+    pub game: Game,
+}
+
+// what each cell is now: # closed, ^ flagged, ? question mark, . open, ! a
+// mine that went off, d one a shield took, o a rock
+fn state(board: &Board) -> String {
+    board
+        .rows()
+        .map(|row| {
+            row.iter()
+                .map(|cell| match cell.state {
+                    CellState::Rock => 'o',
+                    CellState::Bomb { exploded: true, .. } if cell.defused => 'd',
+                    CellState::Bomb { exploded: true, .. } => '!',
+                    CellState::Bomb { flagged: true, .. }
+                    | CellState::Safe { flagged: true, .. } => '^',
+                    CellState::Safe { open: true, .. } => '.',
+                    _ if cell.question => '?',
+                    _ => '#',
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// the number every cell holds, open or not, with * for a mine
+fn numbers(board: &Board) -> String {
+    board
+        .rows()
+        .map(|row| {
+            row.iter()
+                .map(|cell| match cell.state {
+                    CellState::Bomb { .. } => '*',
+                    CellState::Rock => 'o',
+                    CellState::Safe { .. } => char::from(b'0' + cell.bombs_around.min(8)),
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// everything about a game, the mines included, for a bug report; `code` is
+// what it was started from, which for a generated board is its seed
+pub fn debug_dump(game: &Game, code: &str) -> String {
+    let board = game.board();
+    let initial = game.initial_board();
+    let moving = match game.moving_mines() {
+        Some(MovingMines {
+            every,
+            fraction,
+            seed,
+        }) => format!("{every} {fraction} {seed}"),
+        None => String::from("none"),
+    };
+    let win = match game.win_condition() {
+        WinCondition::OpenAll => "open",
+        WinCondition::FlagAll => "flags",
+    };
+    let moves = game
+        .moves()
+        .iter()
+        .map(|game_move| format!("{} {}\n", game_move.player, game_move.command))
+        .collect::<String>();
+
+    [
+        DUMP_BEGIN.to_string(),
+        format!("code {code}"),
+        format!("size {}x{}", initial.height(), initial.width()),
+        format!("lives {} of {}", game.lives(), game.initial_lives()),
+        format!("peeks {} of {}", game.peeks_left(), game.initial_peeks()),
+        format!("shields {}", if game.has_shields() { "on" } else { "off" }),
+        format!("moving_mines {moving}"),
+        format!("win {win}"),
+        format!("state {}", game.state().name()),
+        format!(
+            "counts mines={} flags={} open={} exploded={} mines_left={} moves={}",
+            board.count_bombs(),
+            board.count_flags(),
+            board.count_open(),
+            board.count_exploded(),
+            board.mines_left(),
+            game.moves().len()
+        ),
+        String::from("layout"),
+        initial.layout(),
+        String::from("numbers"),
+        numbers(board),
+        String::from("cells"),
+        state(board),
+        String::from("moves"),
+        format!("{moves}{DUMP_END}"),
+    ]
+    .join("\n")
+}
+
+fn malformed(detail: &str) -> DumpError {
+    DumpError::Malformed(detail.to_string())
+}
+
+fn field<'a>(lines: &mut impl Iterator<Item = &'a str>, name: &str) -> Result<&'a str, DumpError> {
+    lines
+        .next()
+        .and_then(|line| line.strip_prefix(name))
+        .and_then(|rest| rest.strip_prefix(' '))
+        .ok_or_else(|| DumpError::Malformed(format!("expected {name}")))
+}
+
+// "<now> of <at the start>", of which only the start is needed to replay
+fn initial<'a>(lines: &mut impl Iterator<Item = &'a str>, name: &str) -> Result<u32, DumpError> {
+    field(lines, name)?
+        .split_once(" of ")
+        .and_then(|(_, initial)| initial.parse().ok())
+        .ok_or_else(|| DumpError::Malformed(format!("invalid {name}")))
+}
+
+// the game in a dump, found anywhere in `text`, rebuilt by replaying its
+// moves on its board, timed by `clock`; the rebuilt game has to dump exactly
+// as it was dumped, or the dump doesn't describe a game this build would play
+pub fn parse_dump(text: &str, clock: Arc<dyn Clock>) -> Result<DebugDump, DumpError> {
+    let dump: Vec<&str> = text
+        .lines()
+        .map(str::trim_end)
+        .skip_while(|&line| line != DUMP_BEGIN)
+        .skip(1)
+        .take_while(|&line| line != DUMP_END)
+        .collect();
+    if dump.is_empty() {
+        return Err(DumpError::Missing);
+    }
+    let mut lines = dump.iter().copied();
+
+    let code = field(&mut lines, "code")?.to_string();
+    let height: usize = field(&mut lines, "size")?
+        .split_once('x')
+        .and_then(|(height, _)| height.parse().ok())
+        .ok_or_else(|| malformed("invalid size"))?;
+    let lives = initial(&mut lines, "lives")?;
+    let peeks = initial(&mut lines, "peeks")?;
+    let shielded = match field(&mut lines, "shields")? {
+        "on" => true,
+        "off" => false,
+        _ => return Err(malformed("invalid shields")),
+    };
+    let moving = match field(&mut lines, "moving_mines")? {
+        "none" => None,
+        moving => {
+            let fields: Vec<&str> = moving.split(' ').collect();
+            let [every, fraction, seed] = fields[..] else {
+                return Err(malformed("invalid moving_mines"));
+            };
+            let invalid = |_| malformed("invalid moving_mines");
+            Some(MovingMines {
+                every: every.parse().map_err(invalid)?,
+                fraction: fraction
+                    .parse()
+                    .map_err(|_| malformed("invalid moving_mines"))?,
+                seed: seed.parse().map_err(invalid)?,
+            })
+        }
+    };
+    let win_condition = match field(&mut lines, "win")? {
+        "open" => WinCondition::OpenAll,
+        "flags" => WinCondition::FlagAll,
+        _ => return Err(malformed("invalid win")),
+    };
+    // worked out again by the replay, and checked with the rest
+    field(&mut lines, "state")?;
+    field(&mut lines, "counts")?;
+
+    if lines.next() != Some("layout") {
+        return Err(malformed("expected layout"));
+    }
+    let layout: Vec<&str> = lines.by_ref().take(height).collect();
+    let board: Board = layout
+        .join("\n")
+        .parse()
+        .map_err(|e: LayoutError| DumpError::Malformed(e.to_string()))?;
+
+    let mut game = Game::with_clock(board, lives, clock)
+        .with_peeks(peeks)
+        .shielded(shielded)
+        .with_moving_mines(moving)
+        .with_win_condition(win_condition);
+    let moves = lines.skip_while(|&line| line != "moves").skip(1);
+    for line in moves {
+        let (player, command) = line
+            .split_once(' ')
+            .ok_or_else(|| DumpError::Malformed(format!("invalid move {line}")))?;
+        let player: usize = player
+            .parse()
+            .map_err(|_| DumpError::Malformed(format!("invalid move {line}")))?;
+        let command: Command = command
+            .parse()
+            .map_err(|e| DumpError::Malformed(format!("invalid move {line}: {e}")))?;
+        game.apply(player, command)
+            .map_err(|e| DumpError::Malformed(format!("move {line} can't be replayed: {e}")))?;
+    }
+
+    let expected = [&[DUMP_BEGIN], &dump[..], &[DUMP_END]].concat().join("\n");
+    if debug_dump(&game, &code) != expected {
+        return Err(DumpError::Mismatch);
+    }
+    Ok(DebugDump { code, game })
+}
@@ -0,0 +1,442 @@
+use crate::board::{ClearError, FlagChange, FlagError, Mines};
+use crate::command::{EndlessCommand, Point};
+use crate::config::Config;
+use crate::driver::{prompt_parse, GameInput, PromptError};
+use crate::i18n::{fill, text, Msg};
+use crate::input;
+use crate::layout::CellLayout;
+use crate::output::StdoutOutput;
+use crate::topology::{cascade, Plane, Reach, Topology};
+use rand::rngs::StdRng;
+use rand::{random, Rng, SeedableRng};
+use std::collections::HashMap;
+
+// the board is made of square chunks this many cells across, each generated
+// the first time anything needs to know what is in it
+pub const CHUNK: i64 = 16;
+
+// a cascade is cut short after this many cells, since on a board without
+// edges a very sparse patch could otherwise go on for a long time
+const MAX_CASCADE: usize = 100_000;
+
+// which chunk a cell is in, and which chunk is to the right or below of
+// which, counting from the one holding (0,0)
+type ChunkCoord = (i64, i64);
+
+struct Chunk {
+    // row by row, CHUNK * CHUNK of each
+    mines: Vec<bool>,
+    open: Vec<bool>,
+    flagged: Vec<bool>,
+}
+
+// the chunk a cell is in, and its index within it
+fn locate(point: Point) -> (ChunkCoord, usize) {
+    let coord = (point.row.div_euclid(CHUNK), point.col.div_euclid(CHUNK));
+    let index = point.row.rem_euclid(CHUNK) * CHUNK + point.col.rem_euclid(CHUNK);
+    (coord, index as usize)
+}
+
+fn neighbours(point: Point) -> impl Iterator<Item = Point> {
+    Plane.neighbours(point).into_iter()
+}
+
+// the same master seed always gives the same chunk at the same place,
+// whatever order the chunks are reached in; the cells around (0,0) are
+// never mines, so that the first clear there is safe and opens some space
+fn generate_chunk(seed: u64, density: f64, coord: ChunkCoord) -> Chunk {
+    let mixed = seed
+        ^ (coord.0 as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (coord.1 as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+    let rng = &mut StdRng::seed_from_u64(mixed);
+
+    let mines = (0..CHUNK * CHUNK)
+        .map(|index| {
+            let row = coord.0 * CHUNK + index / CHUNK;
+            let col = coord.1 * CHUNK + index % CHUNK;
+            let start = row.abs() <= 1 && col.abs() <= 1;
+            rng.gen_bool(density) && !start
+        })
+        .collect();
+
+    let cells = (CHUNK * CHUNK) as usize;
+    Chunk {
+        mines,
+        open: vec![false; cells],
+        flagged: vec![false; cells],
+    }
+}
+
+// a board without edges, whose chunks are kept once they have been generated
+pub struct EndlessBoard {
+    seed: u64,
+    density: f64,
+    chunks: HashMap<ChunkCoord, Chunk>,
+}
+
+impl EndlessBoard {
+    pub fn new(seed: u64, density: f64) -> Self {
+        EndlessBoard {
+            seed,
+            density,
+            chunks: HashMap::new(),
+        }
+    }
+
+    fn chunk(&mut self, coord: ChunkCoord) -> &mut Chunk {
+        let (seed, density) = (self.seed, self.density);
+        self.chunks
+            .entry(coord)
+            .or_insert_with(|| generate_chunk(seed, density, coord))
+    }
+
+    pub fn is_mine(&mut self, point: Point) -> bool {
+        let (coord, index) = locate(point);
+        self.chunk(coord).mines[index]
+    }
+
+    // a chunk that hasn't been generated has nothing open or flagged yet
+    pub fn is_open(&self, point: Point) -> bool {
+        let (coord, index) = locate(point);
+        self.chunks
+            .get(&coord)
+            .is_some_and(|chunk| chunk.open[index])
+    }
+
+    pub fn is_flagged(&self, point: Point) -> bool {
+        let (coord, index) = locate(point);
+        self.chunks
+            .get(&coord)
+            .is_some_and(|chunk| chunk.flagged[index])
+    }
+
+    // generates whichever neighbouring chunks it has to, so that numbers at
+    // the edge of a chunk count the mines across it
+    pub fn bombs_around(&mut self, point: Point) -> u8 {
+        neighbours(point)
+            .filter(|&around| self.is_mine(around))
+            .count() as u8
+    }
+
+    pub fn count_open(&self) -> usize {
+        self.chunks
+            .values()
+            .map(|chunk| chunk.open.iter().filter(|&&open| open).count())
+            .sum()
+    }
+
+    pub fn count_flags(&self) -> usize {
+        self.chunks
+            .values()
+            .map(|chunk| chunk.flagged.iter().filter(|&&flagged| flagged).count())
+            .sum()
+    }
+
+    pub fn count_chunks(&self) -> usize {
+        self.chunks.len()
+    }
+
+    // opens a cell, and the cells around any zero it reaches; returns how
+    // many were opened
+    pub fn clear(&mut self, point: Point) -> Result<usize, ClearError> {
+        if self.is_open(point) || self.is_flagged(point) {
+            return Err(ClearError::AlreadyCleared);
+        }
+        if self.is_mine(point) {
+            return Err(ClearError::ClearedBomb);
+        }
+
+        let mut opened = 0;
+        let reached = cascade(&Plane, point, |point| {
+            if self.is_open(point) || self.is_flagged(point) || self.is_mine(point) {
+                return Ok::<_, ClearError>(Reach::Stop);
+            }
+            let (coord, index) = locate(point);
+            self.chunk(coord).open[index] = true;
+            opened += 1;
+
+            Ok(
+                match opened < MAX_CASCADE && self.bombs_around(point) == 0 {
+                    true => Reach::Spread,
+                    false => Reach::Open,
+                },
+            )
+        })?;
+        Ok(reached.len())
+    }
+
+    pub fn toggle_flag(&mut self, point: Point) -> Result<FlagChange, FlagError> {
+        if self.is_open(point) {
+            return Err(FlagError::AlreadyCleared);
+        }
+        let (coord, index) = locate(point);
+        let flagged = &mut self.chunk(coord).flagged[index];
+        *flagged = !*flagged;
+        Ok(if *flagged {
+            FlagChange::FlagPlaced
+        } else {
+            FlagChange::FlagRemoved
+        })
+    }
+
+    // the closed, unflagged neighbours of an open number whose mines are all
+    // flagged
+    pub fn chord_targets(&mut self, point: Point) -> Option<Vec<Point>> {
+        if !self.is_open(point) {
+            return None;
+        }
+        let flags = neighbours(point)
+            .filter(|&around| self.is_flagged(around))
+            .count();
+        (flags == usize::from(self.bombs_around(point))).then(|| {
+            neighbours(point)
+                .filter(|&around| !self.is_open(around) && !self.is_flagged(around))
+                .collect()
+        })
+    }
+}
+
+// the part of the board around `centre`, `height` by `width` cells, with
+// the coordinates of its edges; `hit` is the mine that ended the game, and
+// once there is one every mine in view is shown
+pub fn render_view(
+    board: &mut EndlessBoard,
+    centre: Point,
+    height: i64,
+    width: i64,
+    hit: Option<Point>,
+) -> String {
+    let top = centre.row - height / 2;
+    let left = centre.col - width / 2;
+
+    let mut rows = vec![fill(
+        Msg::EndlessView,
+        &[&top, &(top + height - 1), &left, &(left + width - 1)],
+    )];
+    for row in top..top + height {
+        let cells: Vec<String> = (left..left + width)
+            .map(|col| {
+                let point = Point { row, col };
+                if Some(point) == hit {
+                    String::from("*")
+                } else if board.is_open(point) {
+                    board.bombs_around(point).to_string()
+                } else if board.is_flagged(point) {
+                    String::from("^")
+                } else if hit.is_some() && board.is_mine(point) {
+                    String::from("@")
+                } else {
+                    String::from("#")
+                }
+            })
+            .collect();
+        rows.push(CellLayout::text().row(cells));
+    }
+    rows.join("\n")
+}
+
+// mines are placed by chance cell by cell, so a fixed count becomes the
+// density it would have on the configured board
+fn density(config: &Config) -> f64 {
+    match config.mines {
+        Mines::Density(density) => density,
+        Mines::Count(count) => count as f64 / (config.height * config.width) as f64,
+    }
+}
+
+// survives for as long as the player can: there is nothing to clear, so the
+// score is how many cells were opened before a mine went off or the input
+// ended
+pub fn play(config: &Config, input: &mut dyn GameInput, seed: u64) -> String {
+    let mut board = EndlessBoard::new(seed, density(config));
+    let (height, width) = (config.height as i64, config.width as i64);
+    let mut centre = Point { row: 0, col: 0 };
+
+    let hit = loop {
+        println!(
+            "{}",
+            fill(
+                Msg::EndlessStatus,
+                &[
+                    &board.count_open(),
+                    &board.count_flags(),
+                    &board.count_chunks()
+                ]
+            )
+        );
+        println!(
+            "{}\n------",
+            render_view(&mut board, centre, height, width, None)
+        );
+
+        let mut output = StdoutOutput { config };
+        let command: EndlessCommand =
+            match prompt_parse(input, &mut output, Msg::EndlessPrompt, None) {
+                Ok(command) => command,
+                Err(PromptError::Eof) => break None,
+                Err(e) => {
+                    println!("{e}");
+                    continue;
+                }
+            };
+
+        let targets = match command {
+            EndlessCommand::Go(point) => {
+                centre = point;
+                continue;
+            }
+            EndlessCommand::Flag(point) => {
+                unwrap_or_continue!(board.toggle_flag(point));
+                continue;
+            }
+            EndlessCommand::Clear(point) => vec![point],
+            EndlessCommand::Chord(point) => match board.chord_targets(point) {
+                Some(targets) => targets,
+                None => {
+                    println!("{}", text(Msg::EndlessCantChord));
+                    continue;
+                }
+            },
+        };
+
+        let mut hit = None;
+        for &target in &targets {
+            match board.clear(target) {
+                Ok(_) | Err(ClearError::AlreadyCleared) if targets.len() > 1 => (),
+                Ok(_) => (),
+                Err(ClearError::ClearedBomb) => {
+                    hit = Some(target);
+                    break;
+                }
+                Err(e) => println!("{e}"),
+            }
+        }
+        // the view follows moves that go out of it
+        if let Some(&target) = targets.first() {
+            if (target.row - centre.row).abs() > height / 2
+                || (target.col - centre.col).abs() > width / 2
+            {
+                centre = target;
+            }
+        }
+        if let Some(hit) = hit {
+            break Some(hit);
+        }
+    };
+
+    let opened = board.count_open();
+    match hit {
+        Some(hit) => {
+            println!("{}", render_view(&mut board, hit, height, width, Some(hit)));
+            fill(Msg::EndlessOver, &[&hit.row, &hit.col, &opened])
+        }
+        None => fill(Msg::EndlessStopped, &[&opened]),
+    }
+}
+
+pub fn run(config: &Config, seed: Option<u64>) -> String {
+    play(
+        config,
+        &mut *input::stdin_input(config),
+        seed.unwrap_or_else(random),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(row: i64, col: i64) -> Point {
+        Point { row, col }
+    }
+
+    // the mines of each chunk in `coords`, generated in that order
+    fn mines_of(seed: u64, coords: &[ChunkCoord]) -> Vec<Vec<bool>> {
+        let mut board = EndlessBoard::new(seed, 0.2);
+        coords
+            .iter()
+            .map(|&coord| board.chunk(coord).mines.clone())
+            .collect()
+    }
+
+    // a board with no mines but the ones given
+    fn with_mines(mines: &[Point]) -> EndlessBoard {
+        let mut board = EndlessBoard::new(0, 0.0);
+        for &mine in mines {
+            let (coord, index) = locate(mine);
+            board.chunk(coord).mines[index] = true;
+        }
+        board
+    }
+
+    #[test]
+    fn chunks_are_the_same_whatever_order_they_are_reached_in() {
+        let coords = [(0, 0), (0, 1), (-1, 0), (3, -7), (-2, -2)];
+        let reversed: Vec<_> = coords.iter().rev().copied().collect();
+
+        let forwards = mines_of(9, &coords);
+        let mut backwards = mines_of(9, &reversed);
+        backwards.reverse();
+        assert_eq!(forwards, backwards);
+
+        // and another seed, or another place, gives other mines
+        assert_ne!(forwards, mines_of(10, &coords));
+        assert_ne!(forwards[1], forwards[2]);
+    }
+
+    #[test]
+    fn the_cells_around_the_start_are_never_mines() {
+        for seed in 0..20 {
+            let mut board = EndlessBoard::new(seed, 0.9);
+            for row in -1..=1 {
+                for col in -1..=1 {
+                    assert!(!board.is_mine(point(row, col)), "{seed}: ({row},{col})");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn numbers_count_mines_in_the_chunks_next_door() {
+        // one mine just over each edge of the chunk holding (0,0), and one
+        // over its top-left corner
+        let mut board = with_mines(&[point(0, 16), point(16, 5), point(-1, -1)]);
+
+        // right, in chunk (0,1), from (0,15) and (1,15)
+        assert_eq!(board.bombs_around(point(0, 15)), 1);
+        assert_eq!(board.bombs_around(point(1, 15)), 1);
+        // and from (-1,15) in chunk (-1,0), diagonally over both edges
+        assert_eq!(board.bombs_around(point(-1, 15)), 1);
+        // below, in chunk (1,0)
+        assert_eq!(board.bombs_around(point(15, 4)), 1);
+        assert_eq!(board.bombs_around(point(15, 5)), 1);
+        assert_eq!(board.bombs_around(point(15, 7)), 0);
+        // the corner, in chunk (-1,-1)
+        assert_eq!(board.bombs_around(point(0, 0)), 1);
+        assert_eq!(board.bombs_around(point(-1, 0)), 1);
+        assert_eq!(board.bombs_around(point(0, -1)), 1);
+        assert_eq!(board.bombs_around(point(-2, -2)), 1);
+    }
+
+    #[test]
+    fn generated_numbers_at_each_border_match_the_mines_around() {
+        let mut board = EndlessBoard::new(3, 0.3);
+        // every cell on either side of the edges of the chunk holding (0,0),
+        // which reaches all eight chunks around it
+        let borders = (0..CHUNK).flat_map(|along| {
+            [-1, 0, CHUNK - 1, CHUNK]
+                .into_iter()
+                .flat_map(move |across| [point(along, across), point(across, along)])
+        });
+        for at in borders.collect::<Vec<_>>() {
+            let mut mines = 0;
+            for row in at.row - 1..=at.row + 1 {
+                for col in at.col - 1..=at.col + 1 {
+                    mines += usize::from(point(row, col) != at && board.is_mine(point(row, col)));
+                }
+            }
+            assert_eq!(usize::from(board.bombs_around(at)), mines, "{at:?}");
+        }
+        assert_eq!(board.count_chunks(), 9);
+    }
+}
@@ -0,0 +1,140 @@
+use crate::board::{CellPosition, ClearError, Mines};
+use crate::command::Command;
+use crate::game::{Game, GameError, GameState};
+use crate::rules::Ruleset;
+use crate::view::BoardView;
+use std::ffi::c_char;
+use std::sync::Mutex;
+
+// what the functions return: zero and up is the state of the game after a
+// move, below zero is why nothing happened
+pub const MS_PLAYING: i32 = 0;
+pub const MS_WON: i32 = 1;
+pub const MS_LOST: i32 = 2;
+// a null pointer, or a game that was never made or has been freed
+pub const MS_NO_GAME: i32 = -1;
+pub const MS_OFF_BOARD: i32 = -2;
+// the cell is already open; a flag doesn't stop a clear, so a flagged mine
+// is MS_LOST and a flagged safe cell opens
+pub const MS_ALREADY_OPEN: i32 = -3;
+pub const MS_ROCK: i32 = -4;
+// the game has been won or lost, and the board must stay as it ended
+pub const MS_GAME_OVER: i32 = -5;
+pub const MS_BUFFER_TOO_SMALL: i32 = -6;
+// a bug: the board contradicts itself, and the move was turned down
+pub const MS_INCONSISTENT: i32 = -7;
+
+// every game handed out and not yet freed, by the address it was handed out
+// as; a pointer is only ever looked up here, never followed, so a stale or
+// made-up one is turned down instead of being read
+static GAMES: Mutex<Vec<(usize, Box<Game>)>> = Mutex::new(Vec::new());
+
+fn with_game(game: *const Game, f: impl FnOnce(&mut Game) -> i32) -> i32 {
+    let Ok(mut games) = GAMES.lock() else {
+        return MS_NO_GAME;
+    };
+    match games
+        .iter_mut()
+        .find(|(address, _)| *address == game as usize)
+    {
+        Some((_, game)) => f(game),
+        None => MS_NO_GAME,
+    }
+}
+
+fn status(state: GameState) -> i32 {
+    match state {
+        GameState::Playing => MS_PLAYING,
+        GameState::Won => MS_WON,
+        // a game can't be resigned through here, but it is lost if it was
+        GameState::Lost | GameState::Resigned => MS_LOST,
+    }
+}
+
+// a new game on a board from `seed`, or null if the ruleset would turn the
+// numbers down: no board, nothing on it to open, or more than MAX_CELLS
+// cells; free it with ms_free
+#[no_mangle]
+pub extern "C" fn ms_new(width: u32, height: u32, mines: u32, seed: u64) -> *mut Game {
+    let rules = Ruleset::builder()
+        .size(height as usize, width as usize)
+        .mines(Mines::Count(mines as usize))
+        .build();
+    let Ok(rules) = rules else {
+        return std::ptr::null_mut();
+    };
+
+    let mut game = Box::new(Game::from_ruleset(&rules, seed));
+    let pointer: *mut Game = &mut *game;
+    match GAMES.lock() {
+        Ok(mut games) => {
+            games.push((pointer as usize, game));
+            pointer
+        }
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+// MS_PLAYING, MS_WON or MS_LOST after the move, or why it couldn't be made
+#[no_mangle]
+pub extern "C" fn ms_clear(game: *mut Game, row: u32, col: u32) -> i32 {
+    let position = CellPosition {
+        row_index: row as usize,
+        col_index: col as usize,
+    };
+    with_game(game, |game| match game.apply(0, Command::Clear(position)) {
+        Ok(state) => status(state),
+        Err(GameError::Clear(ClearError::CellNotFound)) => MS_OFF_BOARD,
+        Err(GameError::Clear(ClearError::AlreadyCleared)) => MS_ALREADY_OPEN,
+        Err(GameError::Clear(ClearError::Rock)) => MS_ROCK,
+        Err(GameError::Clear(ClearError::ClearedBomb)) => MS_LOST,
+        Err(GameError::Clear(ClearError::Inconsistent { .. })) => MS_INCONSISTENT,
+        // a clear can't be turned down for any other reason
+        Err(_) => MS_GAME_OVER,
+    })
+}
+
+/// The board as the player sees it, a line per row and a space between
+/// cells, written to `buf` with a NUL after it. Returns the length without
+/// the NUL, or MS_BUFFER_TOO_SMALL, in which case nothing is written; twice
+/// the number of cells is always enough.
+///
+/// # Safety
+///
+/// `buf` must be null or point to at least `len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn ms_render_visible(game: *const Game, buf: *mut c_char, len: usize) -> i32 {
+    if buf.is_null() {
+        return MS_NO_GAME;
+    }
+    with_game(game, |game| {
+        let rendered = BoardView::of(game.board()).to_string();
+        if rendered.len() >= len {
+            return MS_BUFFER_TOO_SMALL;
+        }
+        // SAFETY: the caller promises `len` bytes at `buf`, and at most
+        // `len - 1` of them and the NUL are written
+        let out = unsafe { std::slice::from_raw_parts_mut(buf.cast::<u8>(), len) };
+        out[..rendered.len()].copy_from_slice(rendered.as_bytes());
+        out[rendered.len()] = 0;
+        i32::try_from(rendered.len()).unwrap_or(MS_BUFFER_TOO_SMALL)
+    })
+}
+
+// MS_NO_GAME for null, or for a game already freed, which is left alone
+#[no_mangle]
+pub extern "C" fn ms_free(game: *mut Game) -> i32 {
+    let Ok(mut games) = GAMES.lock() else {
+        return MS_NO_GAME;
+    };
+    match games
+        .iter()
+        .position(|(address, _)| *address == game as usize)
+    {
+        Some(index) => {
+            games.swap_remove(index);
+            MS_PLAYING
+        }
+        None => MS_NO_GAME,
+    }
+}
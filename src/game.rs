@@ -0,0 +1,1312 @@
+use crate::analysis::three_bv;
+use crate::board::{
+    Board, Cell, CellPosition, CellState, ChordError, ClearError, FlagChange, FlagError,
+};
+use crate::command::Command;
+use crate::ghost::Ghost;
+use crate::i18n::{fill, text, Msg};
+use crate::json::Value;
+use crate::rules::Ruleset;
+#[cfg(feature = "clock")]
+use crate::timer::SystemClock;
+use crate::timer::{Clock, Timer};
+use rand::rngs::StdRng;
+use rand::seq::index::sample;
+use rand::SeedableRng;
+use std::fmt::Display;
+use std::sync::Arc;
+use std::time::Duration;
+
+// in the shield variant, a move that opens at least this many cells at once
+// earns a shield, which takes the blast of the next mine set off instead of
+// a life; no more than SHIELD_CAP can be held at a time
+pub const SHIELD_CASCADE: usize = 15;
+pub const SHIELD_CAP: u32 = 3;
+
+// the most board cells the snapshots that undo and rewind go back to can
+// hold between them; past that only every SNAPSHOT_EVERY-th move's is kept,
+// then fewer still, and going back to a move without one replays the move
+// log from the start instead. A beginner's board fits over 10,000 moves
+const SNAPSHOT_CELLS: usize = 1 << 20;
+const SNAPSHOT_EVERY: usize = 10;
+// however big the board, the last few moves can always be undone at once
+const RECENT_SNAPSHOTS: usize = 2;
+
+// the moving-mines variant: every `every` moves, `fraction` of the mines
+// move to other closed cells; flagged and peeked cells are left alone, and
+// the numbers already open aren't updated, so they become hints about where
+// the mines used to be. Where they go is decided by `seed` and the move
+// count, so that replaying the moves, even after an undo, moves them the same
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MovingMines {
+    pub every: usize,
+    pub fraction: f64,
+    pub seed: u64,
+}
+
+// what it takes to win: opening every safe cell, or, as some variants play,
+// flagging every mine without flagging anything safe, which also wins by
+// opening every safe cell
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum WinCondition {
+    #[default]
+    OpenAll,
+    FlagAll,
+}
+
+// how far through the safe cells a split is taken
+pub const SPLIT_PERCENTS: [usize; 4] = [25, 50, 75, 100];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameState {
+    Playing,
+    Won,
+    Lost,
+    // given up by the player, which is lost without a mine going off
+    Resigned,
+}
+
+impl GameState {
+    // as it is written in JSON
+    pub fn name(self) -> &'static str {
+        match self {
+            GameState::Playing => "playing",
+            GameState::Won => "won",
+            GameState::Lost => "lost",
+            GameState::Resigned => "resigned",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum MoveOutcome {
+    Opened(usize),
+    // skipped cells were flagged, rocks or already open when the area was
+    // cleared
+    AreaOpened { opened: usize, skipped: usize },
+    // where the mine was, which for a chord isn't the cell that was chosen
+    HitBomb(CellPosition),
+    // like HitBomb, but a shield was used up instead of a life
+    Defused(CellPosition),
+    Marked(FlagChange),
+    // whether the peeked cell is a mine
+    Peeked(bool),
+}
+
+// what a move changed, for frontends that update what they show instead of
+// redrawing the whole board
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameEvent {
+    CellsOpened(Vec<CellPosition>),
+    // whether the cell is flagged now
+    FlagToggled(CellPosition, bool),
+    QuestionToggled(CellPosition, bool),
+    // the note the cell has now, if any
+    NoteChanged(CellPosition, Option<char>),
+    // whether the cell is a mine
+    Peeked(CellPosition, bool),
+    MineHit(CellPosition),
+    MineDefused(CellPosition),
+    // the number of shields held now
+    ShieldEarned(u32),
+    // how many mines the moving-mines variant just moved
+    MinesMoved(usize),
+    GameWon,
+    // the new count of mines not yet flagged or set off
+    MineCountChanged(isize),
+    // the last move was taken back, which can change anything
+    Undone,
+}
+
+// what the cells of a `CellsOpened` event were: how many, how many separate
+// regions of zeros among them, and how many numbers, which are on the
+// frontier between what is open and what isn't
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CascadeSummary {
+    pub opened: usize,
+    pub regions: usize,
+    pub numbered: usize,
+}
+
+impl CascadeSummary {
+    pub fn of(cells: &[CellPosition], board: &Board) -> Self {
+        let is_zero = |position: CellPosition| {
+            board
+                .get_cell(position)
+                .is_some_and(|cell| cell.bombs_around == 0)
+        };
+        let dims = board.dims();
+        let mut zeros = vec![vec![false; dims.width]; dims.height];
+        for &cell in cells.iter().filter(|&&cell| is_zero(cell)) {
+            zeros[cell.row_index][cell.col_index] = true;
+        }
+
+        // each region is taken off the zeros whole, along with every zero
+        // joined to it
+        let mut regions = 0;
+        let mut numbered = 0;
+        for &cell in cells {
+            if !is_zero(cell) {
+                numbered += 1;
+            }
+            if !std::mem::take(&mut zeros[cell.row_index][cell.col_index]) {
+                continue;
+            }
+            regions += 1;
+            let mut pending = vec![cell];
+            while let Some(position) = pending.pop() {
+                for around in position.neighbors::<1>(dims) {
+                    if std::mem::take(&mut zeros[around.row_index][around.col_index]) {
+                        pending.push(around);
+                    }
+                }
+            }
+        }
+
+        CascadeSummary {
+            opened: cells.len(),
+            regions,
+            numbered,
+        }
+    }
+}
+
+// one accepted action, attributed to the player who made it
+#[derive(Debug, Clone)]
+pub struct Move {
+    pub player: usize,
+    pub command: Command,
+    pub outcome: MoveOutcome,
+    // how long the player took to decide on it, less any idle time; zero for
+    // the later commands of a line, and for moves replayed from a save
+    pub thinking: Duration,
+    // when it was made, by the game's timer; about zero for moves replayed
+    // from a save
+    pub at: Duration,
+}
+
+impl Move {
+    // the mark this move put on or took off a cell, and where
+    pub fn mark(&self) -> Option<(FlagChange, CellPosition)> {
+        match (self.outcome, &self.command) {
+            (MoveOutcome::Marked(change), &Command::Flag(position))
+            | (MoveOutcome::Marked(change), &Command::Question(position)) => {
+                Some((change, position))
+            }
+            _ => None,
+        }
+    }
+}
+
+// an entry of the move log, numbered from 1 in the order the moves were
+// made; an undone move stays in the log under its number, marked as undone
+#[derive(Debug, Clone)]
+pub struct LoggedMove {
+    pub number: usize,
+    pub game_move: Move,
+    pub undone: bool,
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let (action, at, position) = match &self.command {
+            &Command::Clear(position) => {
+                (text(Msg::ActionClear), position.to_string(), Some(position))
+            }
+            Command::ClearArea(area) => (text(Msg::ActionClear), area.to_string(), None),
+            &Command::Chord(position) => {
+                (text(Msg::ActionChord), position.to_string(), Some(position))
+            }
+            &Command::Flag(position) => {
+                (text(Msg::ActionFlag), position.to_string(), Some(position))
+            }
+            &Command::Question(position) => (
+                text(Msg::ActionQuestion),
+                position.to_string(),
+                Some(position),
+            ),
+            &Command::Peek(position) => {
+                (text(Msg::ActionPeek), position.to_string(), Some(position))
+            }
+            // only board actions end up in the move log
+            Command::Undo
+            | Command::Rewind(_)
+            | Command::Share
+            | Command::BoardInfo
+            | Command::Analyze
+            | Command::Remaining
+            | Command::ChordCheck(_)
+            | Command::WinProb
+            | Command::Hint { .. }
+            | Command::Moves { .. }
+            | Command::Dump
+            | Command::Export(_)
+            | Command::SaveAs(_)
+            | Command::Watch
+            | Command::Resign
+            | Command::Help
+            | Command::Note(..)
+            | Command::Pause
+            | Command::Resume => return Ok(()),
+        };
+
+        let out = match self.outcome {
+            MoveOutcome::Opened(1) => fill(Msg::MoveOpenedOne, &[&action, &at]),
+            MoveOutcome::Opened(count) => fill(Msg::MoveOpened, &[&action, &at, &count]),
+            MoveOutcome::AreaOpened { opened, skipped } => {
+                fill(Msg::MoveAreaOpened, &[&action, &at, &opened, &skipped])
+            }
+            MoveOutcome::HitBomb(hit) if Some(hit) == position => {
+                fill(Msg::MoveHitBomb, &[&action, &at])
+            }
+            MoveOutcome::HitBomb(hit) => fill(Msg::MoveHitBombAt, &[&action, &at, &hit]),
+            MoveOutcome::Defused(hit) if Some(hit) == position => {
+                fill(Msg::MoveDefused, &[&action, &at])
+            }
+            MoveOutcome::Defused(hit) => fill(Msg::MoveDefusedAt, &[&action, &at, &hit]),
+            MoveOutcome::Marked(FlagChange::FlagPlaced | FlagChange::QuestionPlaced) => {
+                fill(Msg::MoveMarkPlaced, &[&action, &at])
+            }
+            MoveOutcome::Marked(FlagChange::FlagRemoved | FlagChange::QuestionRemoved) => {
+                fill(Msg::MoveMarkRemoved, &[&action, &at])
+            }
+            MoveOutcome::Peeked(true) => fill(Msg::MovePeekedMine, &[&action, &at]),
+            MoveOutcome::Peeked(false) => fill(Msg::MovePeekedSafe, &[&action, &at]),
+        };
+
+        write!(f, "{out}")
+    }
+}
+
+#[derive(Debug)]
+pub enum GameError {
+    Clear(ClearError),
+    Flag(FlagError),
+    Chord(ChordError),
+    NothingToUndo,
+    // there have only been `made` moves to go back to
+    RewindTooFar { moves: usize, made: usize },
+    NoPeeksLeft,
+    AlreadyPeeked,
+    Paused,
+    NotPaused,
+    // the game has been won or lost, and the board must stay as it ended
+    GameOver,
+}
+
+impl Display for GameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            GameError::Clear(e) => write!(f, "{e}"),
+            GameError::Flag(e) => write!(f, "{e}"),
+            GameError::Chord(e) => write!(f, "{e}"),
+            GameError::NothingToUndo => write!(f, "{}", text(Msg::NothingToUndo)),
+            GameError::RewindTooFar { moves, made } => {
+                write!(f, "{}", fill(Msg::RewindTooFar, &[moves, made]))
+            }
+            GameError::NoPeeksLeft => write!(f, "{}", text(Msg::NoPeeksLeft)),
+            GameError::AlreadyPeeked => write!(f, "{}", text(Msg::AlreadyPeeked)),
+            GameError::Paused => write!(f, "{}", text(Msg::GameIsPaused)),
+            GameError::NotPaused => write!(f, "{}", text(Msg::GameIsntPaused)),
+            GameError::GameOver => write!(f, "{}", text(Msg::GameIsOver)),
+        }
+    }
+}
+
+// a summary of a finished (or abandoned) game
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub moves: usize,
+    pub opened: usize,
+    pub elapsed: Duration,
+    // of the board as dealt, see `analysis::three_bv`
+    pub three_bv: usize,
+    pub mines: usize,
+    // whether any peeks were used
+    pub assisted: bool,
+    // which way a game that could be won either way was won; None for the
+    // usual rules, and for a game not won
+    pub won_by: Option<WinCondition>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameOutcome {
+    Won(Stats),
+    // `hit` is the mine that ended the game
+    Lost { hit: CellPosition, stats: Stats },
+    // given up, `progress` percent of the way to opening every safe cell
+    Resigned { progress: usize, stats: Stats },
+    // the player stopped before the game was decided, e.g. stdin was closed
+    Aborted,
+}
+
+impl GameOutcome {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            GameOutcome::Won(_) => 0,
+            GameOutcome::Lost { .. } | GameOutcome::Resigned { .. } => 1,
+            GameOutcome::Aborted => 130,
+        }
+    }
+
+    pub fn to_json(self) -> Value {
+        let stats = |stats: Stats| {
+            [
+                ("moves", Value::from(stats.moves)),
+                ("opened", Value::from(stats.opened)),
+                ("elapsed_ms", Value::from(stats.elapsed.as_millis() as u64)),
+                ("three_bv", Value::from(stats.three_bv)),
+            ]
+        };
+
+        match self {
+            GameOutcome::Won(won) => {
+                let [moves, opened, elapsed, three_bv] = stats(won);
+                Value::object([
+                    ("outcome", Value::from("won")),
+                    moves,
+                    opened,
+                    elapsed,
+                    three_bv,
+                ])
+            }
+            GameOutcome::Lost { hit, stats: lost } => {
+                let [moves, opened, elapsed, three_bv] = stats(lost);
+                Value::object([
+                    ("outcome", Value::from("lost")),
+                    (
+                        "hit",
+                        Value::object([
+                            ("row", Value::from(hit.row_index)),
+                            ("col", Value::from(hit.col_index)),
+                        ]),
+                    ),
+                    moves,
+                    opened,
+                    elapsed,
+                    three_bv,
+                ])
+            }
+            GameOutcome::Resigned {
+                progress,
+                stats: resigned,
+            } => {
+                let [moves, opened, elapsed, three_bv] = stats(resigned);
+                Value::object([
+                    ("outcome", Value::from("resigned")),
+                    ("progress", Value::from(progress)),
+                    moves,
+                    opened,
+                    elapsed,
+                    three_bv,
+                ])
+            }
+            GameOutcome::Aborted => Value::object([("outcome", Value::from("aborted"))]),
+        }
+    }
+}
+
+impl Display for GameOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let out = match self {
+            GameOutcome::Won(stats) => String::from(text(match stats.won_by {
+                Some(WinCondition::FlagAll) => Msg::GameWonByFlags,
+                Some(WinCondition::OpenAll) => Msg::GameWonByOpening,
+                None => Msg::GameWon,
+            })),
+            GameOutcome::Lost { hit, .. } => fill(Msg::GameLostAt, &[hit]),
+            GameOutcome::Resigned { progress, .. } => fill(Msg::GameResigned, &[progress]),
+            GameOutcome::Aborted => String::from(text(Msg::GameAborted)),
+        };
+
+        write!(f, "{out}")
+    }
+}
+
+// everything a move can change, so that it can be undone
+#[derive(Clone)]
+struct Snapshot {
+    board: Board,
+    lives: u32,
+    shields: u32,
+}
+
+// wraps a board with the rules layered on top of it: lives, the move log, and undo
+#[derive(Clone)]
+pub struct Game {
+    initial: Snapshot,
+    board: Board,
+    lives: u32,
+    // None unless playing the shield variant
+    shields: Option<u32>,
+    // decided by `apply` as each move lands, rather than rescanned by callers
+    state: GameState,
+    moves: Vec<Move>,
+    // how things were before each of `moves`, where one is kept (see
+    // SNAPSHOT_CELLS)
+    snapshots: Vec<Option<Snapshot>>,
+    // what the latest call to `apply` changed
+    events: Vec<GameEvent>,
+    // starts with the first move and stops once the game is over
+    timer: Timer,
+    // while paused the timer is stopped and only resuming is allowed
+    paused: bool,
+    // the time each of SPLIT_PERCENTS was first reached, in order
+    splits: Vec<(usize, Duration)>,
+    // how many peeks the game started with
+    peeks: u32,
+    moving_mines: Option<MovingMines>,
+    win_condition: WinCondition,
+    // one-letter notes on closed cells, kept apart from the board since no
+    // rule looks at them; opening a cell takes its note off
+    notes: Vec<(CellPosition, char)>,
+    // every move made, including the ones since undone
+    log: Vec<LoggedMove>,
+    // the thinking time for the next move, adding up every prompt answered
+    // since the last one, including lines that failed
+    thinking: Option<Duration>,
+    // the player kept moves the solver made for them
+    solver_played: bool,
+    // the game was rewound at least once
+    rewound: bool,
+    // clears of cells already open, which aren't moves but were clicks all
+    // the same
+    wasted: usize,
+    // an earlier run at the same board to race against
+    ghost: Option<Ghost>,
+}
+
+impl Game {
+    // a game on the OS's clock; without the `clock` feature, make it
+    // with_clock instead
+    #[cfg(feature = "clock")]
+    pub fn new(board: Board, lives: u32) -> Self {
+        Self::with_clock(board, lives, Arc::new(SystemClock))
+    }
+
+    // a game whose timer reads `clock`, which needn't be the real time
+    pub fn with_clock(board: Board, lives: u32, clock: Arc<dyn Clock>) -> Self {
+        Game {
+            initial: Snapshot {
+                board: board.clone(),
+                lives,
+                shields: 0,
+            },
+            state: decide(&board, lives, WinCondition::OpenAll),
+            board,
+            lives,
+            shields: None,
+            moves: vec![],
+            snapshots: vec![],
+            events: vec![],
+            timer: Timer::new(clock),
+            paused: false,
+            splits: vec![],
+            thinking: None,
+            peeks: 0,
+            moving_mines: None,
+            win_condition: WinCondition::OpenAll,
+            notes: vec![],
+            log: vec![],
+            solver_played: false,
+            rewound: false,
+            wasted: 0,
+            ghost: None,
+        }
+    }
+
+    // a game played by `ruleset` on the board it lays out from `seed`, which
+    // also decides where any moving mines go
+    #[cfg(feature = "clock")]
+    pub fn from_ruleset(ruleset: &Ruleset, seed: u64) -> Self {
+        Self::from_ruleset_with_clock(ruleset, seed, Arc::new(SystemClock))
+    }
+
+    pub fn from_ruleset_with_clock(ruleset: &Ruleset, seed: u64, clock: Arc<dyn Clock>) -> Self {
+        Game::with_clock(ruleset.board(seed), ruleset.lives(), clock)
+            .with_peeks(ruleset.peeks())
+            .shielded(ruleset.shields())
+            .with_moving_mines(ruleset.moving_mines(seed))
+            .with_win_condition(ruleset.win())
+    }
+
+    pub fn ruleset(&self) -> Ruleset {
+        Ruleset::of(self)
+    }
+
+    // a game that lets the player peek at `peeks` cells
+    pub fn with_peeks(mut self, peeks: u32) -> Self {
+        self.peeks = peeks;
+        self
+    }
+
+    pub fn with_ghost(mut self, ghost: Ghost) -> Self {
+        self.ghost = Some(ghost);
+        self
+    }
+
+    pub fn ghost(&self) -> Option<&Ghost> {
+        self.ghost.as_ref()
+    }
+
+    // a game of the shield variant if `shielded`, starting without any
+    pub fn shielded(mut self, shielded: bool) -> Self {
+        self.shields = shielded.then_some(0);
+        self
+    }
+
+    pub fn with_moving_mines(mut self, moving_mines: Option<MovingMines>) -> Self {
+        self.moving_mines = moving_mines;
+        self
+    }
+
+    pub fn moving_mines(&self) -> Option<MovingMines> {
+        self.moving_mines
+    }
+
+    pub fn with_win_condition(mut self, win_condition: WinCondition) -> Self {
+        self.win_condition = win_condition;
+        self.state = decide(&self.board, self.lives, win_condition);
+        self
+    }
+
+    pub fn win_condition(&self) -> WinCondition {
+        self.win_condition
+    }
+
+    pub fn notes(&self) -> &[(CellPosition, char)] {
+        &self.notes
+    }
+
+    pub fn note(&self, position: CellPosition) -> Option<char> {
+        self.notes
+            .iter()
+            .find(|&&(noted, _)| noted == position)
+            .map(|&(_, note)| note)
+    }
+
+    // replaces any note already on the cell; unlike a move, it isn't logged
+    // and can't be undone
+    pub fn annotate(
+        &mut self,
+        position: CellPosition,
+        note: Option<char>,
+    ) -> Result<(), FlagError> {
+        let cell = self
+            .board
+            .get_cell(position)
+            .ok_or(FlagError::CellNotFound)?;
+        match cell.state {
+            CellState::Rock => return Err(FlagError::Rock),
+            CellState::Safe { open: true, .. } | CellState::Bomb { exploded: true, .. } => {
+                return Err(FlagError::AlreadyCleared)
+            }
+            _ => (),
+        }
+
+        self.notes.retain(|&(noted, _)| noted != position);
+        if let Some(note) = note {
+            self.notes.push((position, note));
+        }
+        Ok(())
+    }
+
+    fn won_by(&self) -> Option<WinCondition> {
+        if self.state != GameState::Won || self.win_condition == WinCondition::OpenAll {
+            None
+        } else if self.board.is_won() {
+            Some(WinCondition::OpenAll)
+        } else {
+            Some(WinCondition::FlagAll)
+        }
+    }
+
+    // a fresh game from the same starting position, used to replay the move log
+    pub fn restart(&self) -> Self {
+        let game = Self::with_clock(
+            self.initial.board.clone(),
+            self.initial.lives,
+            self.timer.clock(),
+        )
+        .with_peeks(self.peeks)
+        .with_moving_mines(self.moving_mines)
+        .with_win_condition(self.win_condition);
+        game.shielded(self.has_shields())
+    }
+
+    pub fn has_shields(&self) -> bool {
+        self.shields.is_some()
+    }
+
+    pub fn shields(&self) -> u32 {
+        self.shields.unwrap_or(0)
+    }
+
+    pub fn initial_peeks(&self) -> u32 {
+        self.peeks
+    }
+
+    fn peeks_used(&self) -> usize {
+        self.moves
+            .iter()
+            .filter(|made| matches!(made.outcome, MoveOutcome::Peeked(_)))
+            .count()
+    }
+
+    pub fn peeks_left(&self) -> u32 {
+        self.peeks.saturating_sub(self.peeks_used() as u32)
+    }
+
+    pub fn assisted(&self) -> bool {
+        self.peeks_used() > 0 || self.solver_played || self.rewound
+    }
+
+    // for a game the solver has played moves in, which counts as assisted
+    pub fn mark_solver_played(&mut self) {
+        self.solver_played = true;
+    }
+
+    pub fn solver_played(&self) -> bool {
+        self.solver_played
+    }
+
+    // for a game that has been rewound, which counts as assisted
+    pub fn mark_rewound(&mut self) {
+        self.rewound = true;
+    }
+
+    pub fn rewound(&self) -> bool {
+        self.rewound
+    }
+
+    pub fn initial_board(&self) -> &Board {
+        &self.initial.board
+    }
+
+    pub fn initial_lives(&self) -> u32 {
+        self.initial.lives
+    }
+
+    // carries the elapsed time over from a saved game; the splits taken while
+    // replaying its moves are meaningless, so they are dropped
+    pub fn restore_elapsed(&mut self, elapsed: Duration) {
+        self.splits.clear();
+        if self.state() != GameState::Playing {
+            self.timer.restore(elapsed, true);
+        } else if !self.moves.is_empty() {
+            self.timer.restore(elapsed, false);
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn lives(&self) -> u32 {
+        self.lives
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    pub fn log(&self) -> &[LoggedMove] {
+        &self.log
+    }
+
+    // how many times a cell already open was cleared again
+    pub fn wasted_clicks(&self) -> usize {
+        self.wasted
+    }
+
+    // empty after a command that failed or changed nothing
+    pub fn events(&self) -> &[GameEvent] {
+        &self.events
+    }
+
+    // not counting any time spent paused
+    pub fn elapsed(&self) -> Duration {
+        self.timer.elapsed()
+    }
+
+    // what the game keeps time by, for anything else timed alongside it
+    pub fn clock(&self) -> Arc<dyn Clock> {
+        self.timer.clock()
+    }
+
+    pub fn splits(&self) -> &[(usize, Duration)] {
+        &self.splits
+    }
+
+    // marks when the player is asked for a move, so that a long wait for the
+    // answer can be left off the timer
+    pub fn prompted(&mut self) {
+        self.timer.prompted();
+    }
+
+    // leaves off anything past `idle_limit` since the last prompt, if there
+    // is a limit; returns the time left off
+    pub fn answered(&mut self, idle_limit: Option<Duration>) -> Option<Duration> {
+        let waited = self.timer.since_prompt();
+        let idle = idle_limit.and_then(|limit| self.timer.answered(limit));
+        if let Some(waited) = waited {
+            let thinking = self.thinking.unwrap_or_default();
+            self.thinking = Some(thinking + waited.saturating_sub(idle.unwrap_or_default()));
+        }
+        idle
+    }
+
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    pub fn stats(&self) -> Stats {
+        Stats {
+            moves: self.moves.len(),
+            opened: self.board.count_open(),
+            elapsed: self.elapsed(),
+            three_bv: three_bv(&self.initial.board),
+            mines: self.initial.board.count_bombs(),
+            assisted: self.assisted(),
+            won_by: self.won_by(),
+        }
+    }
+
+    // None while the game is still being played
+    pub fn outcome(&self) -> Option<GameOutcome> {
+        match self.state() {
+            GameState::Playing => None,
+            GameState::Won => Some(GameOutcome::Won(self.stats())),
+            GameState::Lost => {
+                let hit = self
+                    .moves
+                    .iter()
+                    .rev()
+                    .find_map(|game_move| match game_move.outcome {
+                        MoveOutcome::HitBomb(hit) => Some(hit),
+                        _ => None,
+                    });
+
+                // lost without a mine going off, as a game made with no
+                // lives is: nothing was played, so it was abandoned
+                Some(match hit {
+                    Some(hit) => GameOutcome::Lost {
+                        hit,
+                        stats: self.stats(),
+                    },
+                    None => GameOutcome::Aborted,
+                })
+            }
+            GameState::Resigned => Some(GameOutcome::Resigned {
+                progress: self.progress(),
+                stats: self.stats(),
+            }),
+        }
+    }
+
+    // how much of the way to opening every safe cell the game has got, as
+    // a percentage rounded down
+    pub fn progress(&self) -> usize {
+        let safe = self
+            .board
+            .rows()
+            .flatten()
+            .filter(|cell| matches!(cell.state, CellState::Safe { .. }))
+            .count();
+        match safe {
+            0 => 100,
+            safe => self.board.count_open() * 100 / safe,
+        }
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            board: self.board.clone(),
+            lives: self.lives,
+            shields: self.shields(),
+        }
+    }
+
+    // lets go of snapshots until they fit in SNAPSHOT_CELLS: first those
+    // between every SNAPSHOT_EVERY-th move, oldest first, and then the
+    // oldest of the rest, but never the last RECENT_SNAPSHOTS
+    fn thin_snapshots(&mut self) {
+        let cells = self.board.height() * self.board.width();
+        let most = (SNAPSHOT_CELLS / cells.max(1)).max(RECENT_SNAPSHOTS);
+        let recent = self.snapshots.len().saturating_sub(RECENT_SNAPSHOTS);
+
+        let mut kept = self.snapshots.iter().flatten().count();
+        while kept > most {
+            let kept_at = |index: usize| self.snapshots[index].is_some();
+            let Some(index) = (0..recent)
+                .find(|&index| kept_at(index) && index % SNAPSHOT_EVERY != 0)
+                .or_else(|| (0..recent).find(|&index| kept_at(index)))
+            else {
+                return;
+            };
+            self.snapshots[index] = None;
+            kept -= 1;
+        }
+    }
+
+    // how things were after the first `moves` moves, from the snapshot
+    // before the next one if there is one, or else by replaying them from
+    // the start
+    fn snapshot_after(&self, moves: usize) -> Snapshot {
+        if let Some(Some(snapshot)) = self.snapshots.get(moves) {
+            return snapshot.clone();
+        }
+        let mut replayed = self.restart();
+        for made in &self.moves[..moves] {
+            replayed
+                .apply(made.player, made.command.clone())
+                .expect("A move that was made should replay");
+        }
+        replayed.snapshot()
+    }
+
+    // takes the game back to how it was after its first `moves` moves, with
+    // the ones after them marked as undone in the log
+    fn go_back_to(&mut self, moves: usize) {
+        let Snapshot {
+            board,
+            lives,
+            shields,
+        } = self.snapshot_after(moves);
+        self.events = vec![GameEvent::Undone];
+        if board.mines_left() != self.board.mines_left() {
+            self.events
+                .push(GameEvent::MineCountChanged(board.mines_left()));
+        }
+        self.board = board;
+        self.lives = lives;
+        if let Some(held) = &mut self.shields {
+            *held = shields;
+        }
+        self.state = decide(&self.board, self.lives, self.win_condition);
+
+        let undone = self.moves.len() - moves;
+        self.moves.truncate(moves);
+        self.snapshots.truncate(moves);
+        for logged in self
+            .log
+            .iter_mut()
+            .rev()
+            .filter(|logged| !logged.undone)
+            .take(undone)
+        {
+            logged.undone = true;
+        }
+    }
+
+    // sets off a mine, costing a shield if there is one and a life if not
+    fn hit(&mut self, position: CellPosition) -> MoveOutcome {
+        if let Some(shields @ 1..) = &mut self.shields {
+            *shields -= 1;
+            self.board.defuse(position);
+            return MoveOutcome::Defused(position);
+        }
+        self.lives = self.lives.saturating_sub(1);
+        self.board.explode(position);
+        MoveOutcome::HitBomb(position)
+    }
+
+    pub fn apply(&mut self, player: usize, command: Command) -> Result<GameState, GameError> {
+        self.events.clear();
+
+        // a finished board can still be shared, but not changed
+        if self.state != GameState::Playing
+            && !matches!(
+                command,
+                Command::Share
+                    | Command::BoardInfo
+                    | Command::Analyze
+                    | Command::Remaining
+                    | Command::Moves { .. }
+                    | Command::Dump
+                    | Command::Export(_)
+                    | Command::Help
+            )
+        {
+            return Err(GameError::GameOver);
+        }
+        // everything waits until the game is resumed
+        if self.paused && command != Command::Resume {
+            return Err(GameError::Paused);
+        }
+
+        let snapshot = self.snapshot();
+
+        let outcome = match &command {
+            &Command::Clear(position) => {
+                let open_before = self.board.count_open();
+
+                match self.board.clear(position) {
+                    Ok(_) => MoveOutcome::Opened(self.board.count_open() - open_before),
+                    Err(ClearError::ClearedBomb) => self.hit(position),
+                    Err(e) => {
+                        if matches!(e, ClearError::AlreadyCleared) {
+                            self.wasted += 1;
+                        }
+                        return Err(GameError::Clear(e));
+                    }
+                }
+            }
+            Command::ClearArea(area) => {
+                let positions = area
+                    .positions(self.board.height(), self.board.width())
+                    .ok_or(GameError::Clear(ClearError::CellNotFound))?;
+                let open_before = self.board.count_open();
+
+                // decided up front, so that cells opened by an earlier
+                // cell's cascade don't count as skipped
+                let (targets, skipped): (Vec<CellPosition>, Vec<CellPosition>) =
+                    positions.into_iter().partition(|&position| {
+                        matches!(
+                            self.board.get_cell(position).map(|cell| &cell.state),
+                            Some(CellState::Safe {
+                                open: false,
+                                flagged: false
+                            }) | Some(CellState::Bomb {
+                                flagged: false,
+                                exploded: false
+                            })
+                        )
+                    });
+                let skipped = skipped.len();
+
+                let mut hit = None;
+                for position in targets {
+                    match self.board.clear(position) {
+                        // already opened by an earlier cell's cascade
+                        Ok(_) | Err(ClearError::AlreadyCleared | ClearError::Rock) => (),
+                        Err(ClearError::ClearedBomb) => {
+                            hit = Some(position);
+                            break;
+                        }
+                        Err(ClearError::CellNotFound) => {
+                            panic!("Area positions should be on the board")
+                        }
+                        // the cells opened before it are closed again, so
+                        // that the move fails as a whole
+                        Err(e @ ClearError::Inconsistent { .. }) => {
+                            self.board = snapshot.board;
+                            return Err(GameError::Clear(e));
+                        }
+                    }
+                }
+
+                match hit {
+                    Some(hit) => self.hit(hit),
+                    None => MoveOutcome::AreaOpened {
+                        opened: self.board.count_open() - open_before,
+                        skipped,
+                    },
+                }
+            }
+            &Command::Chord(position) => {
+                let targets = self
+                    .board
+                    .chord_targets(position)
+                    .map_err(GameError::Chord)?;
+                let open_before = self.board.count_open();
+
+                // stops at the first mine, behind a wrong flag
+                let mut hit = None;
+                for target in targets {
+                    match self.board.clear(target) {
+                        // already opened by an earlier target's cascade
+                        Ok(_) | Err(ClearError::AlreadyCleared | ClearError::Rock) => (),
+                        Err(ClearError::ClearedBomb) => {
+                            hit = Some(target);
+                            break;
+                        }
+                        Err(ClearError::CellNotFound) => {
+                            panic!("Chord targets should be on the board")
+                        }
+                        Err(e @ ClearError::Inconsistent { .. }) => {
+                            self.board = snapshot.board;
+                            return Err(GameError::Clear(e));
+                        }
+                    }
+                }
+
+                match hit {
+                    Some(hit) => self.hit(hit),
+                    None => MoveOutcome::Opened(self.board.count_open() - open_before),
+                }
+            }
+            &Command::Flag(position) => {
+                MoveOutcome::Marked(self.board.toggle_flag(position).map_err(GameError::Flag)?)
+            }
+            &Command::Peek(position) => {
+                if self.peeks_left() == 0 {
+                    return Err(GameError::NoPeeksLeft);
+                }
+                if self
+                    .board
+                    .get_cell(position)
+                    .is_some_and(|cell| cell.peeked)
+                {
+                    return Err(GameError::AlreadyPeeked);
+                }
+                MoveOutcome::Peeked(self.board.peek(position).map_err(GameError::Flag)?)
+            }
+            &Command::Question(position) => MoveOutcome::Marked(
+                self.board
+                    .toggle_question(position)
+                    .map_err(GameError::Flag)?,
+            ),
+            // purely informational, there is nothing to apply
+            Command::Share
+            | Command::BoardInfo
+            | Command::Analyze
+            | Command::Remaining
+            | Command::ChordCheck(_)
+            | Command::WinProb
+            | Command::Hint { .. }
+            | Command::Moves { .. }
+            | Command::Dump
+            | Command::Export(_)
+            | Command::SaveAs(_)
+            | Command::Watch
+            | Command::Help => return Ok(self.state()),
+            &Command::Note(position, note) => {
+                self.annotate(position, note).map_err(GameError::Flag)?;
+                self.events = vec![GameEvent::NoteChanged(position, note)];
+                return Ok(self.state);
+            }
+            // the board stays as it was, to be shown with its mines
+            Command::Resign => {
+                self.state = GameState::Resigned;
+                self.timer.stop();
+                return Ok(self.state);
+            }
+            Command::Pause => {
+                self.paused = true;
+                self.timer.pause();
+                return Ok(self.state);
+            }
+            Command::Resume => {
+                if !self.paused {
+                    return Err(GameError::NotPaused);
+                }
+                self.paused = false;
+                self.timer.resume();
+                return Ok(self.state);
+            }
+            Command::Undo => {
+                let moves = self
+                    .moves
+                    .len()
+                    .checked_sub(1)
+                    .ok_or(GameError::NothingToUndo)?;
+                self.go_back_to(moves);
+                return Ok(self.state);
+            }
+            &Command::Rewind(moves) => {
+                let made = self.moves.len();
+                if moves >= made {
+                    return Err(GameError::RewindTooFar { moves, made });
+                }
+                self.go_back_to(moves);
+                self.rewound = true;
+                return Ok(self.state);
+            }
+        };
+
+        self.timer.start();
+        self.state = decide(&self.board, self.lives, self.win_condition);
+        self.events = changes(&snapshot.board, &self.board, outcome, self.state);
+        let board = &self.board;
+        self.notes.retain(|&(position, _)| {
+            board.get_cell(position).is_some_and(|cell| {
+                !is_open(cell) && !matches!(cell.state, CellState::Bomb { exploded: true, .. })
+            })
+        });
+        self.earn_shield();
+        self.snapshots.push(Some(snapshot));
+        self.thin_snapshots();
+        self.moves.push(Move {
+            player,
+            command,
+            outcome,
+            thinking: self.thinking.take().unwrap_or_default(),
+            at: self.timer.elapsed(),
+        });
+        self.log.push(LoggedMove {
+            number: self.log.len() + 1,
+            game_move: self.moves[self.moves.len() - 1].clone(),
+            undone: false,
+        });
+
+        self.take_splits();
+        if self.state != GameState::Playing {
+            self.timer.stop();
+        } else {
+            self.move_mines();
+        }
+
+        // moving mines leave the open numbers as they were on purpose
+        #[cfg(feature = "debug-checks")]
+        if self.moving_mines.is_none() {
+            if let Err(e) = self.board.check_invariants() {
+                panic!(
+                    "The board should be consistent after {:?}: {e}",
+                    self.moves.last()
+                );
+            }
+        }
+
+        Ok(self.state)
+    }
+
+    // the moving-mines variant's turn, if it is due
+    fn move_mines(&mut self) {
+        let Some(MovingMines {
+            every,
+            fraction,
+            seed,
+        }) = self.moving_mines
+        else {
+            return;
+        };
+        if every == 0 || !self.moves.len().is_multiple_of(every) {
+            return;
+        }
+
+        let untouched = |cell: &&Cell| !cell.peeked && !is_flagged(cell);
+        let cells = || self.board.rows().flatten().filter(untouched);
+        let sources: Vec<CellPosition> = cells()
+            .filter(|cell| {
+                matches!(
+                    cell.state,
+                    CellState::Bomb {
+                        exploded: false,
+                        ..
+                    }
+                )
+            })
+            .map(|cell| cell.position)
+            .collect();
+        let destinations: Vec<CellPosition> = cells()
+            .filter(|cell| matches!(cell.state, CellState::Safe { open: false, .. }))
+            .map(|cell| cell.position)
+            .collect();
+
+        // rounded, but at least one while there is any to move
+        let count = ((sources.len() as f64 * fraction).round() as usize)
+            .max(usize::from(fraction > 0.0))
+            .min(sources.len())
+            .min(destinations.len());
+        if count == 0 {
+            return;
+        }
+
+        let rng = &mut StdRng::seed_from_u64(seed.wrapping_add(self.moves.len() as u64));
+        let from = sample(rng, sources.len(), count);
+        let to = sample(rng, destinations.len(), count);
+        for (from, to) in from.iter().zip(to.iter()) {
+            self.board.move_mine(sources[from], destinations[to]);
+        }
+        self.events.push(GameEvent::MinesMoved(count));
+    }
+
+    // going by the cells the move just opened
+    fn earn_shield(&mut self) {
+        let Some(shields) = &mut self.shields else {
+            return;
+        };
+        let opened = self.events.iter().find_map(|event| match event {
+            GameEvent::CellsOpened(cells) => Some(cells.len()),
+            _ => None,
+        });
+        if opened.unwrap_or(0) >= SHIELD_CASCADE && *shields < SHIELD_CAP {
+            *shields += 1;
+            self.events.push(GameEvent::ShieldEarned(*shields));
+        }
+    }
+
+    // only the first time each milestone is reached counts, even if an undo
+    // goes back below it
+    fn take_splits(&mut self) {
+        let safe = self.board.count_safe();
+        let open = self.board.count_open();
+
+        for percent in SPLIT_PERCENTS {
+            if open * 100 >= safe * percent && !self.splits.iter().any(|&(p, _)| p == percent) {
+                self.splits.push((percent, self.timer.elapsed()));
+            }
+        }
+    }
+}
+
+// a game is won once every safe cell is open, whatever has been flagged, and
+// mines that were defused or set off count as dealt with; with FlagAll,
+// flagging every mine and nothing else also wins
+fn decide(board: &Board, lives: u32, win_condition: WinCondition) -> GameState {
+    if lives == 0 {
+        GameState::Lost
+    } else if board.is_won() || win_condition == WinCondition::FlagAll && board.all_mines_flagged()
+    {
+        GameState::Won
+    } else {
+        GameState::Playing
+    }
+}
+
+fn is_open(cell: &Cell) -> bool {
+    matches!(cell.state, CellState::Safe { open: true, .. })
+}
+
+fn is_flagged(cell: &Cell) -> bool {
+    matches!(
+        cell.state,
+        CellState::Bomb { flagged: true, .. } | CellState::Safe { flagged: true, .. }
+    )
+}
+
+// the events for a move, found by comparing the board before and after it
+fn changes(
+    before: &Board,
+    after: &Board,
+    outcome: MoveOutcome,
+    state: GameState,
+) -> Vec<GameEvent> {
+    let mut opened = vec![];
+    let mut flags = vec![];
+    for (old, new) in before.rows().flatten().zip(after.rows().flatten()) {
+        if !is_open(old) && is_open(new) {
+            opened.push(new.position);
+        }
+        if is_flagged(old) != is_flagged(new) {
+            flags.push(GameEvent::FlagToggled(new.position, is_flagged(new)));
+        }
+        if old.question != new.question && !is_open(new) {
+            flags.push(GameEvent::QuestionToggled(new.position, new.question));
+        }
+        if !old.peeked && new.peeked {
+            let mine = matches!(new.state, CellState::Bomb { .. });
+            flags.push(GameEvent::Peeked(new.position, mine));
+        }
+    }
+
+    let mut events = vec![];
+    if !opened.is_empty() {
+        events.push(GameEvent::CellsOpened(opened));
+    }
+    events.extend(flags);
+    match outcome {
+        MoveOutcome::HitBomb(hit) => events.push(GameEvent::MineHit(hit)),
+        MoveOutcome::Defused(hit) => events.push(GameEvent::MineDefused(hit)),
+        _ => (),
+    }
+    if before.mines_left() != after.mines_left() {
+        events.push(GameEvent::MineCountChanged(after.mines_left()));
+    }
+    if state == GameState::Won {
+        events.push(GameEvent::GameWon);
+    }
+
+    events
+}
@@ -0,0 +1,322 @@
+use crate::board::{Board, Mines};
+use crate::command::Command;
+use crate::config::Config;
+use crate::driver::{self, GameConfig, GameInput, Hooks, Io, Screen, Verdict};
+use crate::game::{Game, GameOutcome};
+use crate::i18n::{fill, text, Msg};
+use crate::input;
+use crate::output::StdoutOutput;
+use crate::render::render_last_move;
+use crate::stats::StatsFile;
+use crate::timer::{format_duration, Clock, SystemClock};
+use rand::random;
+use std::cell::Cell;
+use std::sync::Arc;
+use std::time::Duration;
+
+// every board of a gauntlet is this small, so that a good run clears a lot
+// of them
+const SIZE: usize = 8;
+const MINES: Mines = Mines::Count(10);
+
+// taken off the time left for each board lost
+pub const PENALTY: Duration = Duration::from_secs(15);
+
+// boards cleared, then the time that was left when the last of them was,
+// so that more is better for both
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Score {
+    pub cleared: usize,
+    pub remaining: Duration,
+}
+
+// one run against a fixed budget of time, which starts with the first board
+// and keeps going through every board and prompt
+struct Run {
+    clock: Arc<dyn Clock>,
+    started: Duration,
+    budget: Duration,
+    penalties: Cell<Duration>,
+}
+
+impl Run {
+    fn remaining(&self) -> Duration {
+        let spent = self.clock.now().saturating_sub(self.started) + self.penalties.get();
+        self.budget.saturating_sub(spent)
+    }
+
+    fn expired(&self) -> bool {
+        self.remaining().is_zero()
+    }
+}
+
+// plays boards from `seed` onwards until `budget` runs out; a lost board is
+// skipped, costing PENALTY. None if the input ends first
+pub fn play(
+    config: &Config,
+    input: &mut dyn GameInput,
+    clock: Arc<dyn Clock>,
+    seed: u64,
+    budget: Duration,
+) -> Option<Score> {
+    let run = Run {
+        started: clock.now(),
+        clock,
+        budget,
+        penalties: Cell::new(Duration::ZERO),
+    };
+    let mut score = Score {
+        cleared: 0,
+        remaining: Duration::ZERO,
+    };
+
+    for board in 0.. {
+        let game = Game::with_clock(
+            Board::from_seed(seed.wrapping_add(board), SIZE, SIZE, MINES),
+            1,
+            run.clock.clone(),
+        );
+        println!(
+            "{}",
+            fill(Msg::GauntletBoard, &[&(board + 1), &score.cleared])
+        );
+
+        // a move made after the time is up doesn't count
+        let on_move = |_: &Game, _: &Command, _: &mut Io| match run.expired() {
+            true => Verdict::Stop,
+            false => Verdict::Allow,
+        };
+        let after_move = |game: &Game, _: &Command, several: bool, io: &mut Io| {
+            if let Some(feedback) = render_last_move(game, several) {
+                io.output.show(&Screen::Message(feedback));
+            }
+        };
+        let on_command = |_: &mut Game, command: &Command, _: &mut Io| match command {
+            Command::Pause => Err(text(Msg::NoPauseInGauntlet).to_string()),
+            _ => Ok(false),
+        };
+        let on_board_render = |_: &Game| {
+            Some(fill(
+                Msg::GauntletLeft,
+                &[&format_duration(run.remaining())],
+            ))
+        };
+        // on to the next board, won or not
+        let on_game_end = |_: &Game, outcome: GameOutcome, io: &mut Io| match outcome {
+            GameOutcome::Won(_) => {
+                score.cleared += 1;
+                score.remaining = run.remaining();
+            }
+            // giving a board up costs the same as losing it
+            GameOutcome::Lost { .. } | GameOutcome::Resigned { .. } => {
+                run.penalties.set(run.penalties.get() + PENALTY);
+                let penalty = fill(Msg::GauntletPenalty, &[&PENALTY.as_secs()]);
+                io.output.show(&Screen::Message(penalty));
+            }
+            GameOutcome::Aborted => (),
+        };
+
+        let hooks = Hooks {
+            on_move: Some(Box::new(on_move)),
+            after_move: Some(Box::new(after_move)),
+            on_command: Some(Box::new(on_command)),
+            on_board_render: Some(Box::new(on_board_render)),
+            on_game_end: Some(Box::new(on_game_end)),
+        };
+        let setup = GameConfig {
+            game,
+            retries: input::piped_retries(),
+            idle_limit: config.idle_limit(),
+            prompt: Msg::GauntletPrompt,
+            players: 1,
+        };
+        let output = &mut StdoutOutput { config };
+        let outcome = driver::run_game(
+            setup,
+            Io {
+                input: &mut *input,
+                output,
+            },
+            hooks,
+        );
+        // the input ended, unless the time ran out first
+        if outcome == GameOutcome::Aborted {
+            if !run.expired() {
+                return None;
+            }
+            break;
+        }
+    }
+
+    Some(score)
+}
+
+// a gauntlet at the terminal, for the `gauntlet` command, keeping the best
+// score for its budget in the stats file
+pub fn run(config: &Config, seed: Option<u64>) -> String {
+    let budget = Duration::from_secs(config.gauntlet_minutes * 60);
+    let Some(score) = play(
+        config,
+        &mut *input::stdin_input(config),
+        Arc::new(SystemClock),
+        seed.unwrap_or_else(random),
+        budget,
+    ) else {
+        return text(Msg::GameAborted).to_string();
+    };
+
+    let mut stats = StatsFile::load();
+    let best = stats.gauntlet_best(config.gauntlet_minutes);
+    let mut report = fill(
+        Msg::GauntletOver,
+        &[&score.cleared, &format_duration(score.remaining)],
+    );
+    if best.is_none_or(|best| score > best) {
+        stats.record_gauntlet(config.gauntlet_minutes, score);
+        stats.save();
+        report += &format!("\n{}", text(Msg::GauntletNewBest));
+    } else if let Some(best) = best {
+        report += &format!(
+            "\n{}",
+            fill(
+                Msg::GauntletBest,
+                &[&best.cleared, &format_duration(best.remaining)]
+            )
+        );
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{CellPosition, CellState};
+    use crate::game::GameState;
+    use crate::stats::tests::SetByHand;
+    use std::collections::VecDeque;
+    use std::io;
+
+    const SEED: u64 = 5;
+    const BUDGET: Duration = Duration::from_secs(60);
+
+    // each line read at the second it is paired with
+    struct Timed {
+        clock: Arc<SetByHand>,
+        lines: VecDeque<(u64, String)>,
+    }
+
+    impl GameInput for Timed {
+        fn read_command(&mut self) -> io::Result<Option<String>> {
+            Ok(self.lines.pop_front().map(|(second, line)| {
+                self.clock.set(second);
+                line
+            }))
+        }
+    }
+
+    fn board(index: u64) -> Board {
+        Board::from_seed(SEED + index, SIZE, SIZE, MINES)
+    }
+
+    fn cells(board: &Board, mine: bool) -> impl Iterator<Item = CellPosition> + '_ {
+        board
+            .rows()
+            .flatten()
+            .filter(move |cell| matches!(cell.state, CellState::Bomb { .. }) == mine)
+            .map(|cell| cell.position)
+    }
+
+    // the clears that win the `index`th board, each of a cell still closed
+    fn win(index: u64, second: u64) -> Vec<(u64, String)> {
+        let mut game = Game::new(board(index), 1);
+        let mut lines = vec![];
+        for at in cells(&board(index), false) {
+            let open = game
+                .board()
+                .get_cell(at)
+                .is_some_and(|cell| matches!(cell.state, CellState::Safe { open: true, .. }));
+            if !open && game.state() == GameState::Playing {
+                game.apply(0, Command::Clear(at))
+                    .expect("The cell should open");
+                lines.push((second, format!("c {} {}", at.row_index, at.col_index)));
+            }
+        }
+        lines
+    }
+
+    fn lose(index: u64, second: u64) -> (u64, String) {
+        let mine = cells(&board(index), true)
+            .next()
+            .expect("There should be a mine");
+        (second, format!("c {} {}", mine.row_index, mine.col_index))
+    }
+
+    fn flag(second: u64) -> (u64, String) {
+        (second, String::from("f 0 0"))
+    }
+
+    fn played(lines: Vec<(u64, String)>) -> (Option<Score>, usize) {
+        let clock = Arc::new(SetByHand::default());
+        let mut input = Timed {
+            clock: clock.clone(),
+            lines: lines.into(),
+        };
+        let score = play(&Config::default(), &mut input, clock, SEED, BUDGET);
+        (score, input.lines.len())
+    }
+
+    #[test]
+    fn boards_won_score_and_boards_lost_cost_the_penalty() {
+        // won at 10, lost at 12, won at 20, then a flag at 44 that still
+        // counts: 60 - 44 - 15 is a second left, and 45 is the end of it
+        let mut lines = win(0, 10);
+        lines.push(lose(1, 12));
+        lines.extend(win(2, 20));
+        lines.extend([flag(44), flag(45), flag(46)]);
+
+        let (score, unread) = played(lines);
+        assert_eq!(
+            score,
+            Some(Score {
+                cleared: 2,
+                // 60 less the 20 seconds to the second win and one penalty
+                remaining: Duration::from_secs(25),
+            })
+        );
+        assert_eq!(unread, 1);
+    }
+
+    #[test]
+    fn the_run_stops_at_the_first_move_after_the_budget() {
+        // nothing won, and the move at 60 is past it
+        let (score, unread) = played(vec![flag(30), flag(59), flag(60), flag(61)]);
+        assert_eq!(
+            score,
+            Some(Score {
+                cleared: 0,
+                remaining: Duration::ZERO,
+            })
+        );
+        assert_eq!(unread, 1);
+
+        // four losses use the budget up by themselves
+        let lines: Vec<_> = (0..5).map(|index| lose(index, 0)).collect();
+        let (score, unread) = played(lines);
+        assert_eq!(score.map(|score| score.cleared), Some(0));
+        assert_eq!(unread, 0);
+    }
+
+    #[test]
+    fn the_end_of_input_abandons_the_run() {
+        assert_eq!(played(win(0, 10)).0, None);
+        assert!(
+            Score {
+                cleared: 2,
+                remaining: Duration::ZERO
+            } > Score {
+                cleared: 1,
+                remaining: BUDGET
+            }
+        );
+    }
+}
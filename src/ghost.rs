@@ -0,0 +1,34 @@
+// an earlier run at the same board, raced against by the clock: how many
+// moves it had made by the time the game in play has been going
+use crate::game::Game;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ghost {
+    // when each of its moves was made, by its own timer, in order
+    times: Vec<Duration>,
+}
+
+impl Ghost {
+    pub fn new(times: Vec<Duration>) -> Ghost {
+        Ghost { times }
+    }
+
+    // the moves `game` kept, undone ones left out
+    pub fn of(game: &Game) -> Ghost {
+        Ghost::new(game.moves().iter().map(|made| made.at).collect())
+    }
+
+    pub fn times(&self) -> &[Duration] {
+        &self.times
+    }
+
+    pub fn moves(&self) -> usize {
+        self.times.len()
+    }
+
+    // how many of its moves it had made `elapsed` into its game
+    pub fn moves_by(&self, elapsed: Duration) -> usize {
+        self.times.iter().filter(|&&at| at <= elapsed).count()
+    }
+}
@@ -0,0 +1,110 @@
+use crate::board::{Cell, CellPosition, Dims};
+
+// where a board keeps its cells; the rules are all in `Board`, which only
+// reaches the cells through this, so that a board can be stored however
+// suits its size
+pub trait CellGrid: Sized {
+    // the cells a row at a time, or none if they don't fit in this grid
+    fn from_rows(rows: Vec<Vec<Cell>>) -> Option<Self>;
+
+    fn dims(&self) -> Dims;
+
+    fn get(&self, position: CellPosition) -> Option<&Cell>;
+
+    fn get_mut(&mut self, position: CellPosition) -> Option<&mut Cell>;
+
+    // every cell, a row at a time
+    fn iter(&self) -> impl Iterator<Item = &Cell>;
+
+    fn iter_mut(&mut self) -> impl Iterator<Item = &mut Cell>;
+}
+
+// a board of any size, on the heap; the one every board uses unless told
+// otherwise
+#[derive(Clone)]
+pub struct VecGrid {
+    rows: Vec<Vec<Cell>>,
+}
+
+impl VecGrid {
+    pub fn rows(&self) -> &[Vec<Cell>] {
+        &self.rows
+    }
+}
+
+impl CellGrid for VecGrid {
+    // the rows are expected to be the same length, which is left to
+    // `Board::check_invariants` to check
+    fn from_rows(rows: Vec<Vec<Cell>>) -> Option<Self> {
+        Some(VecGrid { rows })
+    }
+
+    fn dims(&self) -> Dims {
+        Dims::of(&self.rows)
+    }
+
+    fn get(&self, position: CellPosition) -> Option<&Cell> {
+        self.rows
+            .get(position.row_index)
+            .and_then(|row| row.get(position.col_index))
+    }
+
+    fn get_mut(&mut self, position: CellPosition) -> Option<&mut Cell> {
+        self.rows
+            .get_mut(position.row_index)
+            .and_then(|row| row.get_mut(position.col_index))
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Cell> {
+        self.rows.iter().flatten()
+    }
+
+    fn iter_mut(&mut self) -> impl Iterator<Item = &mut Cell> {
+        self.rows.iter_mut().flatten()
+    }
+}
+
+// an N by N board in a fixed array, with no allocation for its cells
+#[derive(Clone)]
+pub struct ArrayGrid<const N: usize> {
+    cells: [[Cell; N]; N],
+}
+
+impl<const N: usize> CellGrid for ArrayGrid<N> {
+    fn from_rows(rows: Vec<Vec<Cell>>) -> Option<Self> {
+        let rows: Vec<[Cell; N]> = rows
+            .into_iter()
+            .map(|row| row.try_into().ok())
+            .collect::<Option<_>>()?;
+        Some(ArrayGrid {
+            cells: rows.try_into().ok()?,
+        })
+    }
+
+    fn dims(&self) -> Dims {
+        Dims {
+            height: N,
+            width: N,
+        }
+    }
+
+    fn get(&self, position: CellPosition) -> Option<&Cell> {
+        self.cells
+            .get(position.row_index)
+            .and_then(|row| row.get(position.col_index))
+    }
+
+    fn get_mut(&mut self, position: CellPosition) -> Option<&mut Cell> {
+        self.cells
+            .get_mut(position.row_index)
+            .and_then(|row| row.get_mut(position.col_index))
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &Cell> {
+        self.cells.iter().flatten()
+    }
+
+    fn iter_mut(&mut self) -> impl Iterator<Item = &mut Cell> {
+        self.cells.iter_mut().flatten()
+    }
+}
@@ -0,0 +1,792 @@
+use crate::analysis::{Efficiency, FlagScoring};
+use crate::board::CellPosition;
+use crate::calendar::civil_from_days;
+use crate::command::Command;
+use crate::dirs::data_dir;
+use crate::game::{Game, GameOutcome, MoveOutcome};
+use crate::ghost::Ghost;
+use crate::i18n::{fill, text, Msg};
+use crate::json::{self, Value};
+use crate::timer::format_duration;
+use crate::versions::{upgrade, Format, VersionError};
+use std::fs::{self, OpenOptions};
+use std::io::{self, ErrorKind, Write};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// one JSON object per line, oldest first
+const HISTORY_FILE: &str = "history.jsonl";
+
+// written into each record, since a history is appended to by whichever
+// release played the game
+pub const VERSION: u32 = 2;
+
+// each from the version before to the next, applied to a record's JSON
+const MIGRATIONS: [fn(Value) -> Value; VERSION as usize - 1] = [toggles_from_flags];
+
+// 2 began with the version field, and keeps the flags taken off as well as
+// put on; a record from before kept only the ones put on, so they stand in
+fn toggles_from_flags(mut record: Value) -> Value {
+    if let (Some(flags), None) = (record.get("flags").cloned(), record.get("toggles")) {
+        if let Value::Object(fields) = &mut record {
+            fields.push(("toggles".to_string(), flags));
+        }
+    }
+    record
+}
+
+// what the first cell a game opened did at once
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Opening {
+    Mine,
+    // a blank, and everything around it
+    Cascade,
+    Number,
+}
+
+impl Opening {
+    fn name(self) -> &'static str {
+        match self {
+            Opening::Mine => "mine",
+            Opening::Cascade => "cascade",
+            Opening::Number => "number",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Opening> {
+        match name {
+            "mine" => Some(Opening::Mine),
+            "cascade" => Some(Opening::Cascade),
+            "number" => Some(Opening::Number),
+            _ => None,
+        }
+    }
+}
+
+// where a game was first clicked, and what came of it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FirstMove {
+    pub position: CellPosition,
+    pub opening: Opening,
+}
+
+impl FirstMove {
+    // the first move that opened a cell or hit a mine
+    fn of(game: &Game) -> Option<FirstMove> {
+        game.moves().iter().find_map(|made| {
+            let position = match made.command {
+                Command::Clear(position) | Command::Chord(position) => position,
+                _ => return None,
+            };
+            let opening = match made.outcome {
+                MoveOutcome::HitBomb(_) | MoveOutcome::Defused(_) => Opening::Mine,
+                MoveOutcome::Opened(1) => Opening::Number,
+                MoveOutcome::Opened(0) => return None,
+                MoveOutcome::Opened(_) => Opening::Cascade,
+                _ => return None,
+            };
+            Some(FirstMove { position, opening })
+        })
+    }
+}
+
+// a finished game, as kept in the history
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    // seconds since the unix epoch
+    pub timestamp: u64,
+    pub code: String,
+    pub height: usize,
+    pub width: usize,
+    pub mines: usize,
+    pub won: bool,
+    // given up rather than lost to a mine, which is still not won
+    pub resigned: bool,
+    pub elapsed: Duration,
+    pub three_bv: usize,
+    pub moves: usize,
+    // the mine that ended a lost game
+    pub hit: Option<CellPosition>,
+    // whether any peeks were used
+    pub assisted: bool,
+    // the rules besides the size and mines, as `Ruleset::key` gives them;
+    // None for records older than the rules being kept
+    pub rules: Option<String>,
+    // None for records older than first moves being kept, and for a game
+    // that never opened a cell
+    pub first: Option<FirstMove>,
+    // the clicks the game took, see `analysis::Efficiency`; None for records
+    // older than clicks being kept
+    pub efficiency: Option<Efficiency>,
+    // how flags were counted in the efficiency shown when the game ended;
+    // None for records older than the choice
+    pub flag_scoring: Option<FlagScoring>,
+    // the ISO week of a weekly challenge, "2026-W07"; None for every other game
+    pub week: Option<String>,
+    // when each move kept was made, by the game's timer, for racing against
+    // later; None for records older than the times being kept
+    pub times: Option<Vec<Duration>>,
+}
+
+impl Record {
+    // None for a game that was abandoned rather than won or lost
+    pub fn new(
+        game: &Game,
+        outcome: GameOutcome,
+        code: &str,
+        scoring: FlagScoring,
+    ) -> Option<Record> {
+        let (won, stats, hit) = match outcome {
+            GameOutcome::Won(stats) => (true, stats, None),
+            GameOutcome::Lost { hit, stats } => (false, stats, Some(hit)),
+            GameOutcome::Resigned { stats, .. } => (false, stats, None),
+            GameOutcome::Aborted => return None,
+        };
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+
+        Some(Record {
+            timestamp,
+            code: code.to_string(),
+            height: game.board().height(),
+            width: game.board().width(),
+            mines: stats.mines,
+            won,
+            resigned: matches!(outcome, GameOutcome::Resigned { .. }),
+            elapsed: stats.elapsed,
+            three_bv: stats.three_bv,
+            moves: stats.moves,
+            hit,
+            assisted: stats.assisted,
+            rules: Some(game.ruleset().key()),
+            first: FirstMove::of(game),
+            efficiency: Some(Efficiency::of(game)),
+            flag_scoring: Some(scoring),
+            week: None,
+            times: Some(Ghost::of(game).times().to_vec()),
+        })
+    }
+
+    fn result(&self) -> &'static str {
+        match (self.won, self.resigned) {
+            (true, _) => "won",
+            (false, true) => "resigned",
+            (false, false) => "lost",
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let mut value = Value::object([
+            ("version", Value::from(VERSION)),
+            ("timestamp", Value::from(self.timestamp)),
+            ("code", Value::from(self.code.as_str())),
+            ("height", Value::from(self.height)),
+            ("width", Value::from(self.width)),
+            ("mines", Value::from(self.mines)),
+            ("result", Value::from(self.result())),
+            ("elapsed_ms", Value::from(self.elapsed.as_millis() as u64)),
+            ("three_bv", Value::from(self.three_bv)),
+            ("moves", Value::from(self.moves)),
+            ("assisted", Value::from(self.assisted)),
+        ]);
+        if let (Some(rules), Value::Object(fields)) = (&self.rules, &mut value) {
+            fields.push(("rules".to_string(), Value::from(rules.as_str())));
+        }
+        if let (Some(hit), Value::Object(fields)) = (self.hit, &mut value) {
+            let hit = Value::object([
+                ("row", Value::from(hit.row_index)),
+                ("col", Value::from(hit.col_index)),
+            ]);
+            fields.push(("hit".to_string(), hit));
+        }
+        if let (Some(first), Value::Object(fields)) = (self.first, &mut value) {
+            let first = Value::object([
+                ("row", Value::from(first.position.row_index)),
+                ("col", Value::from(first.position.col_index)),
+                ("opened", Value::from(first.opening.name())),
+            ]);
+            fields.push(("first".to_string(), first));
+        }
+        if let (Some(efficiency), Value::Object(fields)) = (self.efficiency, &mut value) {
+            fields.push(("clicks".to_string(), Value::from(efficiency.clicks)));
+            fields.push(("flags".to_string(), Value::from(efficiency.flags)));
+            fields.push(("toggles".to_string(), Value::from(efficiency.toggles)));
+            fields.push(("wasted".to_string(), Value::from(efficiency.wasted)));
+        }
+        if let (Some(scoring), Value::Object(fields)) = (self.flag_scoring, &mut value) {
+            fields.push(("flag_scoring".to_string(), Value::from(scoring.name())));
+        }
+        if let (Some(week), Value::Object(fields)) = (&self.week, &mut value) {
+            fields.push(("week".to_string(), Value::from(week.as_str())));
+        }
+        if let (Some(times), Value::Object(fields)) = (&self.times, &mut value) {
+            let times = times
+                .iter()
+                .map(|at| Value::from(at.as_millis() as u64))
+                .collect();
+            fields.push(("times".to_string(), Value::Array(times)));
+        }
+        value
+    }
+
+    fn from_json(value: &Value) -> Result<Record, HistoryError> {
+        // a record from before versions were written is version 1
+        let version = match value.get("version") {
+            Some(version) => version
+                .as_u64()
+                .filter(|&version| version > 0)
+                .ok_or(HistoryError::InvalidField("version"))? as u32,
+            None => 1,
+        };
+        let value = &upgrade(Format::History, version, value.clone(), &MIGRATIONS)
+            .map_err(HistoryError::Version)?;
+        let field = |key: &'static str| value.get(key).ok_or(HistoryError::MissingField(key));
+        let number =
+            |key: &'static str| field(key)?.as_u64().ok_or(HistoryError::InvalidField(key));
+        let count = |key: &'static str| number(key).map(|value| value as usize);
+
+        Ok(Record {
+            timestamp: number("timestamp")?,
+            code: field("code")?
+                .as_str()
+                .ok_or(HistoryError::InvalidField("code"))?
+                .to_string(),
+            height: count("height")?,
+            width: count("width")?,
+            mines: count("mines")?,
+            resigned: field("result")?.as_str() == Some("resigned"),
+            won: match field("result")?.as_str() {
+                Some("won") => true,
+                Some("resigned") => false,
+                Some("lost") => false,
+                _ => return Err(HistoryError::InvalidField("result")),
+            },
+            elapsed: Duration::from_millis(number("elapsed_ms")?),
+            three_bv: count("three_bv")?,
+            moves: count("moves")?,
+            // older records don't have it
+            hit: match value.get("hit") {
+                Some(hit) => {
+                    let at = |key| hit.get(key).and_then(Value::as_u64);
+                    match (at("row"), at("col")) {
+                        (Some(row), Some(col)) => Some(CellPosition {
+                            row_index: row as usize,
+                            col_index: col as usize,
+                        }),
+                        _ => return Err(HistoryError::InvalidField("hit")),
+                    }
+                }
+                None => None,
+            },
+            assisted: value.get("assisted") == Some(&Value::Bool(true)),
+            rules: value
+                .get("rules")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            first: match value.get("first") {
+                Some(first) => {
+                    let at = |key| first.get(key).and_then(Value::as_u64);
+                    let opening = first
+                        .get("opened")
+                        .and_then(Value::as_str)
+                        .and_then(Opening::from_name);
+                    match (at("row"), at("col"), opening) {
+                        (Some(row), Some(col), Some(opening)) => Some(FirstMove {
+                            position: CellPosition {
+                                row_index: row as usize,
+                                col_index: col as usize,
+                            },
+                            opening,
+                        }),
+                        _ => return Err(HistoryError::InvalidField("first")),
+                    }
+                }
+                None => None,
+            },
+            efficiency: match (value.get("clicks"), value.get("flags"), value.get("wasted")) {
+                (Some(clicks), Some(flags), Some(wasted)) => {
+                    let at =
+                        |value: &Value, key| value.as_u64().ok_or(HistoryError::InvalidField(key));
+                    Some(Efficiency {
+                        three_bv: count("three_bv")?,
+                        clicks: at(clicks, "clicks")? as usize,
+                        flags: at(flags, "flags")? as usize,
+                        toggles: count("toggles")?,
+                        wasted: at(wasted, "wasted")? as usize,
+                    })
+                }
+                _ => None,
+            },
+            flag_scoring: match value.get("flag_scoring") {
+                Some(scoring) => Some(
+                    scoring
+                        .as_str()
+                        .and_then(FlagScoring::from_name)
+                        .ok_or(HistoryError::InvalidField("flag_scoring"))?,
+                ),
+                None => None,
+            },
+            week: value
+                .get("week")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            times: match value.get("times") {
+                Some(Value::Array(times)) => Some(
+                    times
+                        .iter()
+                        .map(|at| at.as_u64().map(Duration::from_millis))
+                        .collect::<Option<_>>()
+                        .ok_or(HistoryError::InvalidField("times"))?,
+                ),
+                Some(_) => return Err(HistoryError::InvalidField("times")),
+                None => None,
+            },
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum HistoryError {
+    Json(json::JsonError),
+    MissingField(&'static str),
+    InvalidField(&'static str),
+    Version(VersionError),
+}
+
+impl std::fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            HistoryError::Json(e) => write!(f, "{e}"),
+            HistoryError::MissingField(key) => write!(f, "{}", fill(Msg::MissingField, &[key])),
+            HistoryError::InvalidField(key) => write!(f, "{}", fill(Msg::InvalidField, &[key])),
+            HistoryError::Version(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+fn history_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join(HISTORY_FILE))
+}
+
+pub fn append(record: &Record) -> io::Result<()> {
+    let path =
+        history_path().ok_or_else(|| io::Error::new(ErrorKind::NotFound, text(Msg::NoDataDir)))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", record.to_json())
+}
+
+// every record that can be read, oldest first; a line that can't be is
+// skipped with a warning, so that one bad line doesn't hide the rest
+pub fn parse_history(contents: &str) -> Vec<Record> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter_map(|(index, line)| {
+            let record = json::parse(line)
+                .map_err(HistoryError::Json)
+                .and_then(|value| Record::from_json(&value));
+            match record {
+                Ok(record) => Some(record),
+                Err(e) => {
+                    println!("{}", fill(Msg::SkippingHistoryLine, &[&(index + 1), &e]));
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+// a missing history is an empty one
+pub fn load() -> io::Result<Vec<Record>> {
+    let Some(path) = history_path() else {
+        return Ok(vec![]);
+    };
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(parse_history(&contents)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(vec![]),
+        Err(e) => Err(e),
+    }
+}
+
+// the fastest earlier win at `game`'s board, the same code with the same
+// size and rules, that kept the times of its moves
+pub fn best_run(records: &[Record], code: &str, game: &Game) -> Option<Ghost> {
+    let rules = game.ruleset().key();
+    let size = (game.board().height(), game.board().width());
+    records
+        .iter()
+        .filter(|record| record.won && record.code == code)
+        .filter(|record| (record.height, record.width) == size)
+        .filter(|record| record.rules.as_deref() == Some(rules.as_str()))
+        .filter_map(|record| Some((record.elapsed, record.times.as_ref()?)))
+        .min_by_key(|&(elapsed, _)| elapsed)
+        .map(|(_, times)| Ghost::new(times.clone()))
+}
+
+// which records `history` lists
+#[derive(Debug, PartialEq)]
+pub struct HistoryQuery {
+    pub last: usize,
+    pub won: Option<bool>,
+    // only the resigned games, or none of them; a resigned game is also lost
+    pub resigned: Option<bool>,
+    // height, width and mines
+    pub size: Option<(usize, usize, usize)>,
+    // show where the matching games were lost instead of listing them
+    pub heatmap: bool,
+    // or where they were first clicked, and how that went
+    pub openings: bool,
+    // only the weekly challenges
+    pub weekly: bool,
+}
+
+impl Default for HistoryQuery {
+    fn default() -> Self {
+        HistoryQuery {
+            last: 10,
+            won: None,
+            resigned: None,
+            size: None,
+            heatmap: false,
+            openings: false,
+            weekly: false,
+        }
+    }
+}
+
+impl HistoryQuery {
+    fn matches(&self, record: &Record) -> bool {
+        self.won.is_none_or(|won| record.won == won)
+            && self
+                .resigned
+                .is_none_or(|resigned| record.resigned == resigned)
+            && (!self.weekly || record.week.is_some())
+            && self
+                .size
+                .is_none_or(|size| (record.height, record.width, record.mines) == size)
+    }
+}
+
+// UTC, to the minute
+pub fn format_timestamp(timestamp: u64) -> String {
+    let (year, month, day) = civil_from_days((timestamp / 86_400) as i64);
+    let minutes = timestamp % 86_400 / 60;
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}",
+        minutes / 60,
+        minutes % 60
+    )
+}
+
+fn history_row([date, board, result, time, bv, moves, code]: [&str; 7]) -> String {
+    let row = format!("{date:<18}{board:<11}{result:<11}{time:>7}{bv:>6}{moves:>9}  {code}");
+    row.trim_end().to_string()
+}
+
+// the last `query.last` records that match, newest last
+pub fn render_history(records: &[Record], query: &HistoryQuery) -> String {
+    let matching: Vec<&Record> = records
+        .iter()
+        .filter(|record| query.matches(record))
+        .collect();
+    if matching.is_empty() {
+        return String::from(text(Msg::NoHistory));
+    }
+
+    let mut table = history_row([
+        text(Msg::HistoryDate),
+        text(Msg::HistoryBoard),
+        text(Msg::HistoryResult),
+        text(Msg::HistoryTime),
+        "3BV",
+        text(Msg::HistoryMoves),
+        text(Msg::HistoryCode),
+    ]);
+
+    // an asterisk on the result marks a game that used peeks
+    for record in &matching[matching.len().saturating_sub(query.last)..] {
+        table += "\n";
+        table += &history_row([
+            &format_timestamp(record.timestamp),
+            &format!("{}x{}/{}", record.height, record.width, record.mines),
+            &format!(
+                "{}{}",
+                text(match (record.won, record.resigned) {
+                    (true, _) => Msg::HistoryWon,
+                    (false, true) => Msg::HistoryResigned,
+                    (false, false) => Msg::HistoryLost,
+                }),
+                if record.assisted { "*" } else { "" }
+            ),
+            &format_duration(record.elapsed),
+            &record.three_bv.to_string(),
+            &record.moves.to_string(),
+            &record.code,
+        ]);
+    }
+    table
+}
+
+// the heatmap's size, whatever the size of the boards
+const HEATMAP_SIZE: usize = 10;
+
+// the openings report's, which is small enough to tell corners, edges and
+// the middle apart
+const OPENINGS_SIZE: usize = 3;
+
+// which of `size` x `size` parts of its record's board `position` falls in,
+// so that boards of every size can be put together; None off the board
+fn scale(position: CellPosition, record: &Record, size: usize) -> Option<(usize, usize)> {
+    if position.row_index >= record.height || position.col_index >= record.width {
+        return None;
+    }
+    Some((
+        position.row_index * size / record.height,
+        position.col_index * size / record.width,
+    ))
+}
+
+// the records in each part of the board, by the cell `position` picks out of
+// each, with every board scaled to `size` x `size`; a record without one is
+// left out
+fn bin<'a>(
+    records: &[&'a Record],
+    size: usize,
+    position: impl Fn(&Record) -> Option<CellPosition>,
+) -> Vec<Vec<Vec<&'a Record>>> {
+    let mut bins = vec![vec![vec![]; size]; size];
+    for &record in records {
+        if let Some((row, col)) = position(record).and_then(|at| scale(at, record, size)) {
+            bins[row][col].push(record);
+        }
+    }
+    bins
+}
+
+// how many losses fell in each part of the board
+fn bin_hits(records: &[&Record]) -> Vec<Vec<usize>> {
+    bin(records, HEATMAP_SIZE, |record| record.hit)
+        .into_iter()
+        .map(|row| row.iter().map(Vec::len).collect())
+        .collect()
+}
+
+// darker for more of the losses, relative to the part of the board where
+// the most were
+fn shade(count: usize, most: usize) -> char {
+    match count * 4 {
+        0 => '·',
+        quarters if quarters <= most => '░',
+        quarters if quarters <= most * 2 => '▒',
+        quarters if quarters <= most * 3 => '▓',
+        _ => '█',
+    }
+}
+
+pub fn render_heatmap(records: &[Record], query: &HistoryQuery) -> String {
+    let matching: Vec<&Record> = records
+        .iter()
+        .filter(|record| query.matches(record))
+        .collect();
+    let bins = bin_hits(&matching);
+    let losses: usize = bins.iter().flatten().sum();
+    if losses == 0 {
+        return String::from(text(Msg::NoLossesInHistory));
+    }
+
+    let most = bins.iter().flatten().copied().max().unwrap_or(0);
+    let mut map = fill(Msg::HeatmapHeading, &[&losses]);
+    for row in bins {
+        map += "\n";
+        // two characters a cell, so that the map comes out roughly square
+        map += &row
+            .iter()
+            .map(|&count| shade(count, most).to_string().repeat(2))
+            .collect::<String>();
+    }
+    map
+}
+
+// the games first clicked in one part of the board
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct OpeningStats {
+    pub games: usize,
+    pub won: usize,
+    pub mines: usize,
+    pub cascades: usize,
+    pub numbers: usize,
+}
+
+// the matching games by where they were first clicked, in OPENINGS_SIZE x
+// OPENINGS_SIZE parts of the board
+pub fn openings(records: &[&Record]) -> Vec<Vec<OpeningStats>> {
+    let bins = bin(records, OPENINGS_SIZE, |record| {
+        record.first.map(|first| first.position)
+    });
+    bins.into_iter()
+        .map(|row| {
+            row.iter()
+                .map(|part| {
+                    let mut stats = OpeningStats::default();
+                    for record in part {
+                        stats.games += 1;
+                        stats.won += usize::from(record.won);
+                        match record.first.map(|first| first.opening) {
+                            Some(Opening::Mine) => stats.mines += 1,
+                            Some(Opening::Cascade) => stats.cascades += 1,
+                            Some(Opening::Number) => stats.numbers += 1,
+                            None => (),
+                        }
+                    }
+                    stats
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// each part of the board as "games won% mines/cascades/numbers", laid out
+// as the board is
+pub fn render_openings(records: &[Record], query: &HistoryQuery) -> String {
+    let matching: Vec<&Record> = records
+        .iter()
+        .filter(|record| query.matches(record))
+        .collect();
+    let parts = openings(&matching);
+    let games: usize = parts.iter().flatten().map(|part| part.games).sum();
+    if games == 0 {
+        return String::from(text(Msg::NoOpeningsInHistory));
+    }
+
+    let mut grid = format!(
+        "{}\n{}",
+        fill(Msg::OpeningsHeading, &[&games]),
+        text(Msg::OpeningsKey)
+    );
+    for row in parts {
+        grid += "\n";
+        let row = row
+            .iter()
+            .map(|part| match part.games {
+                0 => format!("{:<16}", "·"),
+                games => format!(
+                    "{:<16}",
+                    format!(
+                        "{games} {}% {}/{}/{}",
+                        part.won * 100 / games,
+                        part.mines,
+                        part.cascades,
+                        part.numbers
+                    )
+                ),
+            })
+            .collect::<String>();
+        grid += row.trim_end();
+    }
+    grid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a record of a game on a `height` x `width` board, lost at (row, col)
+    // if given one
+    fn record(height: usize, width: usize, hit: Option<(usize, usize)>) -> Record {
+        let (result, hit) = match hit {
+            Some((row, col)) => ("lost", format!(",\"hit\":{{\"row\":{row},\"col\":{col}}}")),
+            None => ("won", String::new()),
+        };
+        let line = format!(
+            "{{\"timestamp\":0,\"code\":\"1\",\"height\":{height},\"width\":{width},\"mines\":10,\
+             \"result\":\"{result}\",\"elapsed_ms\":1000,\"three_bv\":10,\"moves\":5,\
+             \"assisted\":false{hit}}}"
+        );
+        parse_history(&line)
+            .pop()
+            .expect("The record should be read")
+    }
+
+    #[test]
+    fn positions_are_scaled_to_the_same_grid_whatever_the_board() {
+        let at = |row_index, col_index| CellPosition {
+            row_index,
+            col_index,
+        };
+        let beginner = record(9, 9, None);
+        let expert = record(16, 30, None);
+        assert_eq!(scale(at(0, 0), &beginner, 10), Some((0, 0)));
+        assert_eq!(scale(at(4, 4), &beginner, 10), Some((4, 4)));
+        assert_eq!(scale(at(8, 8), &beginner, 10), Some((8, 8)));
+        assert_eq!(scale(at(15, 29), &expert, 10), Some((9, 9)));
+        assert_eq!(scale(at(8, 3), &expert, 10), Some((5, 1)));
+        assert_eq!(scale(at(8, 3), &expert, 3), Some((1, 0)));
+        // and off the board, nowhere
+        assert_eq!(scale(at(9, 0), &beginner, 10), None);
+        assert_eq!(scale(at(0, 30), &expert, 10), None);
+    }
+
+    #[test]
+    fn losses_are_counted_in_their_part_of_the_board() {
+        let records = [
+            record(9, 9, Some((0, 0))),
+            record(16, 30, Some((1, 2))),
+            record(9, 9, Some((8, 8))),
+            record(16, 30, Some((15, 29))),
+            record(16, 30, Some((15, 27))),
+            // won, so nowhere
+            record(9, 9, None),
+        ];
+        let records: Vec<&Record> = records.iter().collect();
+        let bins = bin_hits(&records);
+
+        assert_eq!(bins.len(), HEATMAP_SIZE);
+        assert!(bins.iter().all(|row| row.len() == HEATMAP_SIZE));
+        assert_eq!(bins[0][0], 2);
+        assert_eq!(bins[8][8], 1);
+        assert_eq!(bins[9][9], 2);
+        assert_eq!(bins.iter().flatten().sum::<usize>(), 5);
+    }
+
+    #[test]
+    fn shades_darken_by_quarters_of_the_most() {
+        let shades = |most| {
+            (0..=most)
+                .map(|count| shade(count, most))
+                .collect::<String>()
+        };
+        assert_eq!(shades(4), "·░▒▓█");
+        assert_eq!(shades(8), "·░░▒▒▓▓██");
+        assert_eq!(shades(1), "·█");
+        assert_eq!(shades(2), "·▒█");
+    }
+
+    #[test]
+    fn the_heatmap_draws_each_part_twice_as_wide() {
+        let records = [
+            record(10, 10, Some((0, 0))),
+            record(10, 10, Some((0, 0))),
+            record(10, 10, Some((9, 0))),
+            record(10, 10, Some((0, 9))),
+        ];
+        let map = render_heatmap(&records, &HistoryQuery::default());
+        let lines: Vec<&str> = map.lines().collect();
+        assert_eq!(lines[0], "Where the last mine was hit, over 4 lost games:");
+        assert_eq!(lines[1], format!("██{}▒▒", "··".repeat(8)));
+        assert_eq!(lines[10], format!("▒▒{}", "··".repeat(9)));
+        assert!(lines[2..10].iter().all(|line| *line == "··".repeat(10)));
+
+        let won = [record(9, 9, None)];
+        assert_eq!(
+            render_heatmap(&won, &HistoryQuery::default()),
+            text(Msg::NoLossesInHistory)
+        );
+    }
+}
@@ -0,0 +1,1798 @@
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Lang {
+    English,
+    Spanish,
+}
+
+impl Lang {
+    // accepts a bare code ("es") or a locale ("es_ES.UTF-8", "es-MX")
+    pub fn from_code(code: &str) -> Option<Lang> {
+        let language = code.split(['_', '-', '.', '@']).next().unwrap_or(code);
+        match language.to_ascii_lowercase().as_str() {
+            "en" | "c" | "posix" => Some(Lang::English),
+            "es" => Some(Lang::Spanish),
+            _ => None,
+        }
+    }
+}
+
+// set once at startup, but read from Display impls that have no other way
+// of finding out which language to use
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+pub fn set_lang(lang: Lang) {
+    CURRENT.store(lang as u8, Ordering::Relaxed);
+}
+
+pub fn lang() -> Lang {
+    match CURRENT.load(Ordering::Relaxed) {
+        1 => Lang::Spanish,
+        _ => Lang::English,
+    }
+}
+
+// declares Msg along with a list of every key, so that the tables below can
+// be checked against it
+macro_rules! messages {
+    ($($name:ident,)*) => {
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum Msg {
+            $($name,)*
+        }
+
+        impl Msg {
+            pub const ALL: &'static [Msg] = &[$(Msg::$name,)*];
+        }
+    };
+}
+
+// every user-facing string; templates take positional arguments as {0}, {1}...
+messages! {
+    // results
+    GameWon,
+    GameLostAt,
+    GameAborted,
+    PlayerWins,
+
+    // prompts
+    EnterRowIndex,
+    EnterRowOrCommand,
+    EnterColIndex,
+    EnterCommand,
+    CoopPrompt,
+    DailyPrompt,
+    PostgamePrompt,
+    ResumePrompt,
+    YesAnswer,
+    PlayAgainPrompt,
+    SummaryHeading,
+    SummaryPlayed,
+    SummaryWinRate,
+    SummaryTotalTime,
+    SummaryBestTime,
+    SummaryBvPerSecond,
+    SummaryStreak,
+    JsonExpected,
+    JsonTrailing,
+    MissingField,
+    InvalidField,
+    SkippingHistoryLine,
+    CouldntReadHistory,
+    CouldntWriteHistory,
+    NoHistory,
+    HistoryDate,
+    HistoryBoard,
+    HistoryResult,
+    HistoryTime,
+    HistoryMoves,
+    HistoryCode,
+    HistoryWon,
+    HistoryLost,
+    ExpectResult,
+    NoLossesInHistory,
+    HeatmapHeading,
+    BoardInfoSize,
+    BoardInfoMines,
+    BoardInfoRequestedDensity,
+    BoardInfoRequestedCount,
+    BoardInfoZeros,
+    BoardInfoThreeBv,
+    AnalysisClosed,
+    AnalysisRegion,
+    AnalysisIsolatedRegion,
+    NoPeeksLeft,
+    AlreadyPeeked,
+    ActionPeek,
+    MovePeekedMine,
+    MovePeekedSafe,
+    PeeksLeft,
+    StatusShields,
+    MoveDefused,
+    MoveDefusedAt,
+    ShieldEarned,
+    MinesMoved,
+    StatusMovingMines,
+    CampaignDone,
+    CampaignNext,
+    CampaignLocked,
+    CampaignLevel,
+    CampaignUnlocked,
+    CampaignComplete,
+    ExpectPositiveNumber,
+    GauntletBoard,
+    GauntletLeft,
+    GauntletPrompt,
+    NoPauseInGauntlet,
+    GauntletPenalty,
+    GauntletOver,
+    GauntletNewBest,
+    GauntletBest,
+    CellIsRock,
+    EndlessView,
+    EndlessStatus,
+    EndlessPrompt,
+    EndlessCantChord,
+    EndlessOver,
+    EndlessStopped,
+    ExpectSymmetry,
+    ConfigUnevenSymmetry,
+    LimitRow,
+    LimitColumn,
+    LimitMinAboveMax,
+    LimitMinAboveLength,
+    LimitTooFewMines,
+    LimitTooManyMines,
+    ExpectDistribution,
+    ConfigConflicting,
+    ConfigStencilSize,
+    CouldntReadStencil,
+    MbfTruncated,
+    MbfEmptyBoard,
+    MbfOutOfRange,
+    MbfDuplicateMine,
+    MbfNoSafeCells,
+    MbfTooLarge,
+    MbfWritten,
+    CouldntImport,
+    LoadOrImport,
+    CastWritten,
+    NoExportsOverNetwork,
+    ServeListening,
+    ServeBusy,
+    ServeToken,
+    ServeNoSuchGame,
+    ServeParked,
+    ServeStopping,
+    CouldntServe,
+    ExpectPort,
+    NetRaceWaiting,
+    NetRaceStarted,
+    NetRaceProgress,
+    NetRaceLeft,
+    NetRaceBack,
+    NetRaceWon,
+    NetRaceLost,
+    BroadcastListening,
+    BroadcastSingleOnly,
+    CouldntBroadcast,
+    InvariantRaggedRows,
+    InvariantWrongPosition,
+    InvariantWrongCount,
+    InvariantFlaggedAndOpen,
+    InvariantQuestionOnOpen,
+    InvariantFlaggedAndExploded,
+    InvariantDefusedButNotExploded,
+    LayoutEmpty,
+    LayoutRaggedRow,
+    LayoutUnexpectedSymbol,
+    DifficultyStars,
+    DifficultyDetails,
+    InvalidCellName,
+    EchoClearing,
+    EchoFlagging,
+    EchoChording,
+    EchoQuestioning,
+    EchoPeeking,
+    ConfirmNearFlag,
+    MoveLeftUndone,
+    CascadeOpened,
+    CascadeOneRegion,
+    CascadeRegions,
+    CascadeOneNumber,
+    CascadeNumbers,
+    ExpectWin,
+    StatusFlagWin,
+    GameWonByFlags,
+    GameWonByOpening,
+    InvalidNote,
+    NotePlacedAt,
+    NoteRemovedFrom,
+    LoggedMove,
+    LoggedMoveUndone,
+    NoMovesYet,
+    MoreMoves,
+    DumpMissing,
+    DumpMalformed,
+    DumpMismatch,
+    CrashReportWritten,
+    CouldntWriteCrashReport,
+    BoardInconsistent,
+    ZeroBesideMine,
+    ReportInconsistent,
+    RulesNoCells,
+    RulesNoLives,
+    RulesInvalidFraction,
+    Remaining,
+    StatusRemaining,
+    PromptEof,
+    PromptGaveUp,
+    RaceAborted,
+    MissingSaveName,
+    NoSuchSave,
+    NoSaves,
+    SavesName,
+    SavesSize,
+    SavesProgress,
+    SavesSaved,
+    SkippingSaveIndexLine,
+    SavedAs,
+    SaveReplaced,
+    CouldntSaveAs,
+    ConfirmDeleteSave,
+    SaveDeleted,
+    SaveKept,
+    NoSavesOverNetwork,
+    ReasonSatisfied,
+    ReasonAllMines,
+    ReasonSubset,
+    SolverMove,
+    SolverFinished,
+    SolverNeedsGuess,
+    SolverNothingProven,
+    KeepSolverMoves,
+    SolverMovesKept,
+    SolverMovesUndone,
+    WatchNotHere,
+    NoOpeningsInHistory,
+    OpeningsHeading,
+    OpeningsKey,
+    MissingMoveNumber,
+    InvalidMoveNumber,
+    RewindTooFar,
+    Rewound,
+    RewindNotInDaily,
+    WinProbExact,
+    WinProbSampled,
+    WinProbCoarse,
+    WinProbCoarseWarning,
+    WinProbNone,
+    WinProbInconsistent,
+    HintSafe,
+    HintMine,
+    NoHint,
+    HintsOff,
+    TraceShowsZero,
+    TraceAccounted,
+    TraceOnlyRoom,
+    TraceOnlyRoomKnown,
+    TraceSubset,
+    TraceSafe,
+    TraceMine,
+    TraceMines,
+    TraceSafeCells,
+    DiffNeedsJson,
+    RulesTooBig,
+    ChordPreviewUnsatisfied,
+    ChordPreviewNothing,
+    ChordPreviewClears,
+    WeeklyLives,
+    WeeklyRocks,
+    WeeklyMirror,
+    WeeklyClusters,
+    WeeklyMoving,
+    WeeklyFlags,
+    WeeklySparse,
+    WeeklyPeeks,
+    WeeklyModifier,
+    UndoNotInWeekly,
+    RewindNotInWeekly,
+    EfficiencyLine,
+    GameResigned,
+    ResultResigned,
+    ConfirmResign,
+    HistoryResigned,
+    SummaryResigned,
+    StatusGhost,
+    CommandClear,
+    CommandFlag,
+    CommandChord,
+    CommandChordCheck,
+    CommandQuestion,
+    CommandNote,
+    CommandPeek,
+    CommandUndo,
+    CommandRewind,
+    CommandPause,
+    CommandResume,
+    CommandShare,
+    CommandBoardInfo,
+    CommandAnalyze,
+    CommandRemaining,
+    CommandWinProb,
+    CommandHint,
+    CommandMoves,
+    CommandWatch,
+    CommandResign,
+    CommandExport,
+    CommandSaveAs,
+    CommandHelp,
+    CommandDump,
+    CommandsNote,
+    ReferenceExample,
+    Reference,
+    CoordinateNotANumber,
+    CoordinateTooLarge,
+    RowOffBoard,
+    ColumnOffBoard,
+    CommandNotHere,
+    InvariantTooManyAround,
+    CellDrawnBroken,
+    AnswerKeyTitle,
+    AnswerKeyWritten,
+    CouldntWriteAnswerKey,
+    AnswerKeyWithDebugDump,
+    FlagsIgnored,
+    FlagsPlacedCounted,
+    FlagTogglesCounted,
+    ExpectFlagScoring,
+    LayoutNoSafeCells,
+    NoSafeCells,
+    OneSafeCell,
+    FormatSave,
+    FormatHistory,
+    FormatStats,
+    InvalidFormatVersion,
+    NewerFormatVersion,
+    StatsLeftAlone,
+    SaveUnknownMode,
+    SaveMissingHeader,
+    SaveExpectedField,
+    SaveInvalidField,
+    SaveInvalidNote,
+    SaveLayoutTooShort,
+    SaveLayoutWrongLength,
+    SaveInvalidMove,
+    SaveInvalidMoveBecause,
+    SaveMoveNotReplayed,
+    SaveNoteNotKept,
+    InterruptNotCaught,
+    ServeIdle,
+    AnswerKeyOneGame,
+    ShareHeading,
+    AchievementEarned,
+    AchievementFirstWin,
+    AchievementFirstWinInfo,
+    AchievementNoFlags,
+    AchievementNoFlagsInfo,
+    AchievementNinetyNine,
+    AchievementNinetyNineInfo,
+    AchievementCascade,
+    AchievementCascadeInfo,
+    AchievementQuick,
+    AchievementQuickInfo,
+
+    // board and game errors
+    InvalidCellPosition,
+    CellAlreadyCleared,
+    ClearedBomb,
+    NothingToUndo,
+    GameIsPaused,
+    Paused,
+    IdleNotCounted,
+    SplitsHeading,
+    SplitsTime,
+    SplitsBest,
+    SlowestMoves,
+    GameIsntPaused,
+    ChordNotOpen,
+    ChordNothingToOpen,
+    ChordFlagsDontMatch,
+
+    // command errors
+    NoCommand,
+    UnknownCommand,
+    MissingCoordinate,
+    MissingPath,
+    UnknownExport,
+    TooManyArguments,
+    InvertedRect,
+
+    // move log
+    ActionClear,
+    ActionChord,
+    ActionFlag,
+    ActionQuestion,
+    MoveOpenedOne,
+    MoveOpened,
+    MoveAreaOpened,
+    MoveHitBomb,
+    MoveHitBombAt,
+    MoveMarkPlaced,
+    MoveMarkRemoved,
+    FlagPlacedAt,
+    FlagRemovedFrom,
+    QuestionPlacedAt,
+    QuestionRemovedFrom,
+
+    // status line
+    StatusMines,
+    StatusFlags,
+    StatusLives,
+    StatusTime,
+    StatusMoves,
+    StatusView,
+    TerminalTooSmall,
+
+    // share blocks and transcripts
+    ResultWon,
+    ResultLost,
+    ResultInProgress,
+    ShareSummary,
+    TranscriptStart,
+    TranscriptMove,
+    TranscriptResult,
+    TranscriptWritten,
+
+    // in-game notices
+    MoveUndone,
+    ExportsAfterGame,
+    UndoNotInDaily,
+    GameIsOver,
+    LivesLeft,
+    PlayerName,
+    PlayerTurn,
+    PlayerMove,
+    OneCommandPerTurn,
+    PlayerStats,
+    ErrorLine,
+
+    // race
+    RaceBoard,
+    RaceStartTurn,
+    RaceHandOver,
+    RaceHitBomb,
+    RaceStats,
+    RaceSeed,
+
+    // autosave
+    CouldntAutosave,
+    CouldntSaveStats,
+    LineCancelled,
+    CouldntRemoveAutosave,
+    NoDataDir,
+    GameSaved,
+    CouldntSave,
+    IgnoringAutosave,
+    MalformedSave,
+
+    // command line and config
+    Help,
+    ProtocolHelp,
+    WroteConfig,
+    CouldntRead,
+    BatchSingleOnly,
+    JsonNeedsBatch,
+    UnknownArgument,
+    MissingValue,
+    OptionMustBe,
+    OptionsConflict,
+    ConfigCouldntAccess,
+    ConfigSyntax,
+    ConfigUnknownKey,
+    ConfigInvalidValue,
+    ConfigTooManyMines,
+    ConfigTooManyRocks,
+    ConfigAlreadyExists,
+    NoConfigDir,
+
+    // descriptions of what an option expects
+    ExpectTrueOrFalse,
+    ExpectSize,
+    ExpectWholeNumber,
+    ExpectDensity,
+    ExpectTheme,
+    ExpectBanner,
+    ExpectCoordinates,
+    ExpectAssist,
+    ExpectPreset,
+    ExpectLang,
+
+    // tutorial
+    TutorialWelcome,
+    TutorialFirstClear,
+    TutorialNumbers,
+    TutorialCorner,
+    TutorialCascade,
+    TutorialFlagFirst,
+    TutorialFlagSecond,
+    TutorialThirdMine,
+    TutorialFlagThird,
+    TutorialAllFlagged,
+    TutorialLastCell,
+    TutorialFreePlay,
+    TutorialOnlyClearAndFlag,
+    TutorialNotQuite,
+    TutorialWon,
+    TutorialLost,
+    TutorialStopped,
+    TutorialComplete,
+}
+
+// the fallback for every other language, so it must cover every message
+fn english(msg: Msg) -> &'static str {
+    match msg {
+        Msg::GameWon => "Game won",
+        Msg::GameLostAt => "Game lost: hit a mine at {0}",
+        Msg::GameAborted => "Game aborted",
+        Msg::PlayerWins => "{0} wins",
+
+        Msg::EnterRowIndex => "Enter row index:",
+        Msg::EnterRowOrCommand => {
+            "Enter row index (or a command: f <row> <col> to flag, q <row> <col> for a question mark, ch <row> <col> to chord, u to undo):"
+        }
+        Msg::EnterColIndex => "Enter col index:",
+        Msg::EnterCommand => "Enter command:",
+        Msg::CoopPrompt => {
+            "Enter command (c <row> <col> to clear, f <row> <col> to flag, ch <row> <col> to chord, u to undo, share):"
+        }
+        Msg::DailyPrompt => {
+            "Enter command (c <row> <col> to clear, f <row> <col> to flag, ch <row> <col> to chord, share):"
+        }
+        Msg::PostgamePrompt => "Enter share, export transcript <path>, export cast <path>, export mbf <path>, export discord, or nothing to exit:",
+        Msg::ResumePrompt => {
+            "Found an interrupted {0} game ({1}, {2} moves made). Resume it? [y/n]"
+        }
+        Msg::YesAnswer => "y",
+        Msg::PlayAgainPrompt => "Play another game? [y/n]",
+        Msg::SummaryHeading => "This session:",
+        Msg::SummaryPlayed => "  Games played: {0}",
+        Msg::SummaryWinRate => "  Win rate: {0}% ({1} of {2})",
+        Msg::SummaryTotalTime => "  Total time: {0}",
+        Msg::SummaryBestTime => "  Best time: {0}",
+        Msg::SummaryBvPerSecond => "  Average 3BV/s: {0}",
+        Msg::SummaryStreak => "  Longest win streak: {0}",
+        Msg::JsonExpected => "Expected {0} at byte {1}",
+        Msg::JsonTrailing => "Unexpected characters at byte {0}",
+        Msg::MissingField => "Missing field {0}",
+        Msg::InvalidField => "Invalid value for {0}",
+        Msg::SkippingHistoryLine => "Skipping line {0} of the history: {1}",
+        Msg::CouldntReadHistory => "Couldn't read the history: {0}",
+        Msg::CouldntWriteHistory => "Couldn't add the game to the history: {0}",
+        Msg::NoHistory => "No matching games in the history",
+        Msg::HistoryDate => "Date",
+        Msg::HistoryBoard => "Board",
+        Msg::HistoryResult => "Result",
+        Msg::HistoryTime => "Time",
+        Msg::HistoryMoves => "Moves",
+        Msg::HistoryCode => "Code",
+        Msg::HistoryWon => "won",
+        Msg::HistoryLost => "lost",
+        Msg::ExpectResult => "won, lost or resigned",
+        Msg::NoLossesInHistory => "No lost games in the history",
+        Msg::HeatmapHeading => "Where the last mine was hit, over {0} lost games:",
+        Msg::BoardInfoSize => "Board: {0}x{1}",
+        Msg::BoardInfoMines => "Mines: {0} ({1}% of the cells{2})",
+        Msg::BoardInfoRequestedDensity => "; {0}% was asked for",
+        Msg::BoardInfoRequestedCount => "; {0} were asked for",
+        Msg::BoardInfoZeros => "Zeros: {0}, in {1} openings",
+        Msg::BoardInfoThreeBv => "3BV: {0}",
+        Msg::AnalysisClosed => "Closed cells: {0}, mines not yet flagged: {1}",
+        Msg::AnalysisRegion => "{0}: {1} cells, {2} of them next to numbers that ask for {3} more mines between them",
+        Msg::AnalysisIsolatedRegion => "{0}: {1} cells, none of them next to a number",
+        Msg::NoPeeksLeft => "No peeks left",
+        Msg::AlreadyPeeked => "That cell has already been peeked at",
+        Msg::ActionPeek => "peek",
+        Msg::MovePeekedMine => "{0} {1}: a mine",
+        Msg::MovePeekedSafe => "{0} {1}: safe",
+        Msg::PeeksLeft => "Peeks: {0}",
+        Msg::StatusShields => "Shields: {0}",
+        Msg::MoveDefused => "{0} {1} hit a mine, but a shield took the blast",
+        Msg::MoveDefusedAt => "{0} {1} hit a mine at {2}, but a shield took the blast",
+        Msg::ShieldEarned => "Shield earned! Shields: {0}",
+        Msg::MinesMoved => "{0} mines moved!",
+        Msg::StatusMovingMines => "Moving mines: every {0}",
+        Msg::CampaignDone => "won, best {0}",
+        Msg::CampaignNext => "next",
+        Msg::CampaignLocked => "locked",
+        Msg::CampaignLevel => "Level {0}: {1}",
+        Msg::CampaignUnlocked => "Level {0} complete! Level {1} ({2}) is unlocked",
+        Msg::CampaignComplete => "Every level of the campaign is complete",
+        Msg::ExpectPositiveNumber => "a whole number above 0",
+        Msg::GauntletBoard => "Board {0} ({1} cleared so far)",
+        Msg::GauntletLeft => "Left: {0}",
+        Msg::GauntletPrompt => "Enter a command (c <row> <col> to clear, f <row> <col> to flag, ch <row> <col> to chord):",
+        Msg::NoPauseInGauntlet => "A gauntlet can't be paused",
+        Msg::GauntletPenalty => "Board lost: {0} seconds off the clock",
+        Msg::GauntletOver => "Time's up! Boards cleared: {0}, the last with {1} left",
+        Msg::GauntletNewBest => "A new best!",
+        Msg::GauntletBest => "Best: {0} boards, the last with {1} left",
+        Msg::CellIsRock => "That cell is a rock",
+        Msg::EndlessView => "Rows {0} to {1}, columns {2} to {3}",
+        Msg::EndlessStatus => "Opened: {0}  Flags: {1}  Chunks: {2}",
+        Msg::EndlessPrompt => "Enter a command (c/f/ch <row> <col>, or go <row> <col> to move the view; coordinates can be negative):",
+        Msg::EndlessCantChord => "That cell is not an open number with all its mines flagged",
+        Msg::EndlessOver => "Hit a mine at ({0}, {1}) after opening {2} cells",
+        Msg::EndlessStopped => "Stopped after opening {0} cells",
+        Msg::ExpectSymmetry => "\"none\", \"horizontal\", \"vertical\" or \"rotational\"",
+        Msg::ConfigUnevenSymmetry => "A symmetric layout of this size can only have an even number of mines, not {0}",
+        Msg::LimitRow => "row",
+        Msg::LimitColumn => "column",
+        Msg::LimitMinAboveMax => "At least {0} mines per {1} is more than the most allowed, {2}",
+        Msg::LimitMinAboveLength => "At least {0} mines per {1} can't fit in {2} cells",
+        Msg::LimitTooFewMines => "At least {0} mines per {1} needs {2} mines, but the board has {3}",
+        Msg::LimitTooManyMines => "At most {0} mines per {1} leaves room for {2} mines, but the board has {3}",
+        Msg::ExpectDistribution => "\"uniform\", \"clustered\" or \"sparse\"",
+        Msg::ConfigConflicting => "`{0}` and `{1}` each lay the mines out their own way, so only one can be set",
+        Msg::ConfigStencilSize => "The stencil is {0}x{1}, but the board is {2}x{3}",
+        Msg::CouldntReadStencil => "Couldn't read the stencil {0}: {1}",
+        Msg::MbfTruncated => "The file ends early: it should have at least {0} bytes, but has {1}",
+        Msg::MbfEmptyBoard => "The board in the file has no cells",
+        Msg::MbfOutOfRange => "The mine at ({0},{1}) is off the board",
+        Msg::MbfDuplicateMine => "The mine at ({0},{1}) is listed twice",
+        Msg::MbfNoSafeCells => "Every cell of the board is a mine",
+        Msg::MbfTooLarge => "A {0}x{1} board is too large for an .mbf file, which holds up to 255x255",
+        Msg::MbfWritten => "Board written to {0}",
+        Msg::CouldntImport => "Couldn't import {0}: {1}",
+        Msg::LoadOrImport => "Only one of --load, --import-mbf and load <SAVE> can be used at a time",
+        Msg::CastWritten => "Recording written to {0}",
+        Msg::NoExportsOverNetwork => "Exports to files aren't available over the network",
+        Msg::ServeListening => "Listening on {0}",
+        Msg::ServeBusy => "Another client is playing; try again once its game is over",
+        Msg::ServeToken => "Playing {0}; to continue after disconnecting, send resume {1}",
+        Msg::ServeNoSuchGame => "No game is waiting with that token",
+        Msg::ServeParked => "The client left {0}; it can be resumed with {1}",
+        Msg::ServeStopping => "The server is stopping",
+        Msg::CouldntServe => "Couldn't serve: {0}",
+        Msg::ExpectPort => "a port number, 0 to 65535",
+        Msg::NetRaceWaiting => "Waiting for an opponent to connect",
+        Msg::NetRaceStarted => "The race is on: both players have the same board",
+        Msg::NetRaceProgress => "Your opponent has opened {0}% of their board",
+        Msg::NetRaceLeft => "Your opponent disconnected; they forfeit unless they are back within {0} seconds",
+        Msg::NetRaceBack => "Your opponent is back",
+        Msg::NetRaceWon => "You won the race",
+        Msg::NetRaceLost => "Your opponent won the race",
+        Msg::BroadcastListening => "Spectators can watch on {0}",
+        Msg::BroadcastSingleOnly => "--broadcast only works for single-player games at the terminal",
+        Msg::CouldntBroadcast => "Couldn't broadcast: {0}",
+        Msg::InvariantRaggedRows => "Row {0} has a different number of cells from the first",
+        Msg::InvariantWrongPosition => "The cell at {0} has the wrong position",
+        Msg::InvariantWrongCount => "The open cell at {0} shows {1} but has {2} mines around it",
+        Msg::InvariantFlaggedAndOpen => "The cell at {0} is both flagged and open",
+        Msg::InvariantQuestionOnOpen => "The open cell at {0} has a question mark",
+        Msg::InvariantFlaggedAndExploded => "The mine at {0} is both flagged and set off",
+        Msg::InvariantDefusedButNotExploded => "The cell at {0} is defused but wasn't set off",
+        Msg::LayoutEmpty => "The layout has no cells",
+        Msg::LayoutRaggedRow => "Row {0} of the layout has a different length from the first",
+        Msg::LayoutUnexpectedSymbol => "Unexpected {0} in the layout at ({1},{2})",
+        Msg::DifficultyStars => "Difficulty: {0} ({1} of {2})",
+        Msg::DifficultyDetails => "3BV {0}, {1} unavoidable guesses, largest frontier {2} cells",
+        Msg::InvalidCellName => "Not a cell: {0} (expected a row and a column, or a name such as E4)",
+        Msg::EchoClearing => "Clearing {0} = {1}",
+        Msg::EchoFlagging => "Flagging {0} = {1}",
+        Msg::EchoChording => "Chording {0} = {1}",
+        Msg::EchoQuestioning => "Questioning {0} = {1}",
+        Msg::EchoPeeking => "Peeking at {0} = {1}",
+        Msg::ConfirmNearFlag => "{0} is next to a flag. Clear it anyway? [y/n]",
+        Msg::MoveLeftUndone => "Left as it was",
+        Msg::CascadeOpened => "Opened {0} cells ({1}), {2} exposed",
+        Msg::CascadeOneRegion => "1 new region",
+        Msg::CascadeRegions => "{0} new regions",
+        Msg::CascadeOneNumber => "1 numbered frontier cell",
+        Msg::CascadeNumbers => "{0} numbered frontier cells",
+        Msg::ExpectWin => "\"open\" or \"flags\"",
+        Msg::StatusFlagWin => "Win: flag every mine",
+        Msg::GameWonByFlags => "Game won: every mine flagged",
+        Msg::GameWonByOpening => "Game won: every safe cell open",
+        Msg::InvalidNote => "A note is a single letter, or - to clear it, not {0}",
+        Msg::NotePlacedAt => "Note {0} put on {1}",
+        Msg::NoteRemovedFrom => "Note taken off {0}",
+        Msg::LoggedMove => "#{0} {1}",
+        Msg::LoggedMoveUndone => "#{0} {1} (undone)",
+        Msg::NoMovesYet => "No moves yet",
+        Msg::MoreMoves => "-- Enter for more, q to stop --",
+        Msg::DumpMissing => "No debug dump found",
+        Msg::DumpMalformed => "Invalid debug dump: {0}",
+        Msg::DumpMismatch => "The debug dump does not match the game its moves replay to",
+        Msg::CrashReportWritten => "Something went wrong. The game was saved, to be resumed next time, and a crash report written to {0}",
+        Msg::CouldntWriteCrashReport => "Couldn't write a crash report: {0}",
+        Msg::BoardInconsistent => "Bug: the board contradicts itself at {0}: {1}",
+        Msg::ZeroBesideMine => "it shows no mines around it, but {0} is one",
+        Msg::ReportInconsistent => "This is a bug in the game, and the move was not made. Please report it with the output of --debug-dump",
+        Msg::RulesNoCells => "The board needs at least one cell",
+        Msg::RulesNoLives => "A game needs at least one life",
+        Msg::RulesInvalidFraction => "The fraction of mines that move has to be between 0 and 1, not {0}",
+        Msg::Remaining => "The numbers prove {0} closed cells safe and {1} unflagged cells mines",
+        Msg::StatusRemaining => "Provable: {0} safe, {1} mines",
+        Msg::PromptEof => "The input ended before an answer was given",
+        Msg::PromptGaveUp => "Giving up after {0} invalid answers in a row",
+        Msg::RaceAborted => "The race ended before anyone won",
+        Msg::MissingSaveName => "A save needs a name, e.g. save as friday game",
+        Msg::NoSuchSave => "There is no save {0}; `load` lists them",
+        Msg::NoSaves => "There are no saves yet; `save as <name>` during a game makes one",
+        Msg::SavesName => "Name",
+        Msg::SavesSize => "Size",
+        Msg::SavesProgress => "Open",
+        Msg::SavesSaved => "Saved",
+        Msg::SkippingSaveIndexLine => "Skipping line {0} of the save index, which can't be read",
+        Msg::SavedAs => "Saved as \"{0}\"; `minesweeper load` lists the saves",
+        Msg::SaveReplaced => "Replaced the save \"{0}\"",
+        Msg::CouldntSaveAs => "Couldn't save the game: {0}",
+        Msg::ConfirmDeleteSave => "Delete the save \"{0}\" ({1}, {2}% open)? [y/N]",
+        Msg::SaveDeleted => "Deleted the save \"{0}\"",
+        Msg::SaveKept => "The save was kept",
+        Msg::NoSavesOverNetwork => "Games can't be saved over the network",
+        Msg::ReasonSatisfied => "single-point: {0} satisfied",
+        Msg::ReasonAllMines => "single-point: {0} needs them all",
+        Msg::ReasonSubset => "subset: {0} within {1}",
+        Msg::SolverMove => "Solver: {0}  [{1}]",
+        Msg::SolverFinished => "The solver finished the game in {0} moves",
+        Msg::SolverNeedsGuess => "The solver made {0} moves; the next one would be a guess",
+        Msg::SolverNothingProven => "The solver can't prove any move from here; the next one would be a guess",
+        Msg::KeepSolverMoves => "Keep the solver's moves? The game would count as assisted [y/n]",
+        Msg::SolverMovesKept => "Kept the solver's moves",
+        Msg::SolverMovesUndone => "Back to where you were before watching",
+        Msg::WatchNotHere => "The solver can only be watched in a single-player game",
+        Msg::NoOpeningsInHistory => "No first moves in the history",
+        Msg::OpeningsHeading => "Where the first move was, over {0} games:",
+        Msg::OpeningsKey => "(games, how many won, and how many first moves hit a mine/opened a blank/opened a number)",
+        Msg::MissingMoveNumber => "Expected how many moves to rewind to, e.g. rewind 3",
+        Msg::InvalidMoveNumber => "{0} is not a number of moves",
+        Msg::RewindTooFar => "Can't rewind to move {0}: only {1} moves have been made",
+        Msg::Rewound => "Rewound to move {0}; the game now counts as assisted",
+        Msg::RewindNotInDaily => "Rewind is not available in the daily challenge",
+        Msg::WinProbExact => "Winnable without guessing: {0}% (exact, over all {1} layouts of the mines that fit the numbers)",
+        Msg::WinProbSampled => "Winnable without guessing: about {0}% (±{1}% at 95% confidence, from {2} sampled layouts of the mines)",
+        Msg::WinProbCoarse => "Winnable without guessing: roughly {0}% (±{1}%, from {2} layouts of the mines)",
+        Msg::WinProbCoarseWarning => "Warning: too much of the board is out of reach of the numbers, or too many layouts fit them, to weigh them properly, so this is only a coarse estimate",
+        Msg::WinProbNone => "No layout of the mines that fits the numbers was found in time, so there is no estimate",
+        Msg::WinProbInconsistent => "No layout of the mines fits both the numbers and the flags; one of the flags must be wrong",
+        Msg::HintSafe => "Hint: {0} is safe",
+        Msg::HintMine => "Hint: {0} is a mine",
+        Msg::NoHint => "The numbers don't prove anything more; the next move would be a guess",
+        Msg::HintsOff => "Hints are off; set assist = \"hints\" in the config file to get them",
+        Msg::TraceShowsZero => "{0} shows 0",
+        Msg::TraceAccounted => "{0} shows {1}, and all of its mines are accounted for by {2}",
+        Msg::TraceOnlyRoom => "{0} shows {1}, with room for its mines only at {2}",
+        Msg::TraceOnlyRoomKnown => "{0} shows {1}; {2} account for {3} of its mines, with room for the rest only at {4}",
+        Msg::TraceSubset => "{0} has {1} left to find among {2}, all of which are next to {3} too, which has {4} left among {5}; so {6} are left for {7}",
+        Msg::TraceSafe => "therefore {0} is safe",
+        Msg::TraceMine => "therefore {0} is a mine",
+        Msg::TraceMines => "therefore {0} are mines",
+        Msg::TraceSafeCells => "therefore {0} are safe",
+        Msg::DiffNeedsJson => "--diff needs --json, with --batch or --serve",
+        Msg::RulesTooBig => "a {0}x{1} board has more than the {2} cells a board can have (see max_cells)",
+        Msg::ChordPreviewUnsatisfied => "Chording {0} would do nothing yet: it shows {1} and has {2} flags around it",
+        Msg::ChordPreviewNothing => "Chording {0} would clear nothing: every cell around it is open or flagged",
+        Msg::ChordPreviewClears => "Chording {0} would clear {1} cells, if its flags are right: {2}",
+        Msg::WeeklyLives => "two lives on a board as crowded as expert",
+        Msg::WeeklyRocks => "rocks that can never be opened, scattered between the mines",
+        Msg::WeeklyMirror => "mines laid out in a mirror, the right half the left",
+        Msg::WeeklyClusters => "mines bunched into pockets, with open plains between",
+        Msg::WeeklyMoving => "a fifth of the mines move every ten moves",
+        Msg::WeeklyFlags => "won by flagging every mine rather than opening every cell",
+        Msg::WeeklySparse => "mines kept as far apart as they can be",
+        Msg::WeeklyPeeks => "three peeks and a shield on a big, crowded board",
+        Msg::WeeklyModifier => "This week ({0}): {1}",
+        Msg::UndoNotInWeekly => "Undo is not available in the weekly challenge",
+        Msg::RewindNotInWeekly => "Rewind is not available in the weekly challenge",
+        Msg::EfficiencyLine => "3BV {0} in {1} clicks ({5}), {2} wasted: {3}% efficient, {4} 3BV/s",
+        Msg::GameResigned => "Game resigned at {0}% complete",
+        Msg::ResultResigned => "Resigned",
+        Msg::ConfirmResign => "Resign this game? It will count as a loss [y/n]",
+        Msg::HistoryResigned => "resigned",
+        Msg::SummaryResigned => "  Resigned: {0}",
+        Msg::StatusGhost => "Ghost: move {0} of {1}",
+        Msg::CommandClear => "Open a cell, or every closed, unflagged cell in a row or a rectangle (top-left and bottom-right corners, inclusive)",
+        Msg::CommandFlag => "Put a flag on a closed cell, or take it off",
+        Msg::CommandChord => "Chord: clear every unflagged neighbour of a number whose mines are all flagged; with --check, say what it would do instead",
+        Msg::CommandChordCheck => "Say what chording there would do, without doing it",
+        Msg::CommandQuestion => "Put a question mark on a closed cell, or take it off",
+        Msg::CommandNote => "Put a one-letter note on a closed cell, or take it off with -",
+        Msg::CommandPeek => "Use up a peek to see whether a closed cell is a mine",
+        Msg::CommandUndo => "Take back the last move",
+        Msg::CommandRewind => "Go back to how the game stood after that many of its moves",
+        Msg::CommandPause => "Stop the timer and hide the board until resume",
+        Msg::CommandResume => "Go on with a paused game",
+        Msg::CommandShare => "Show the game as a block to share, without its mines",
+        Msg::CommandBoardInfo => "The board's size, mines, openings and 3BV, which give nothing away",
+        Msg::CommandAnalyze => "The closed cells split into regions, with what the numbers ask of each",
+        Msg::CommandRemaining => "How many cells the numbers prove safe and how many mines, but not which",
+        Msg::CommandWinProb => "Estimate the chance of clearing the board without another guess",
+        Msg::CommandHint => "The cell the solver would see to next; with why, how it gets there",
+        Msg::CommandMoves => "The last five moves, or with all every one",
+        Msg::CommandWatch => "Let the solver play on while you watch, then keep its moves or take them back",
+        Msg::CommandResign => "Give the game up as lost, once you confirm",
+        Msg::CommandExport => "Write the game out, as a Discord block, a transcript, an .mbf file or an asciinema recording",
+        Msg::CommandSaveAs => "Keep the game under a name, to load later",
+        Msg::CommandHelp => "List these commands",
+        Msg::CommandDump => "Print the game, mines included, as a debug dump",
+        Msg::CommandsNote => "A <cell> is a row and a column, as in c 3 4, or a cell's name, its column letter and its row counted from 1, as in c E4. Several commands can go on one line, separated by semicolons: f 0 1; f 0 2; c 3 3",
+        Msg::ReferenceExample => "Example: {0}",
+        Msg::Reference => REFERENCE_EN,
+        Msg::CoordinateNotANumber => "Not a row or a column: {0} (they are written with the digits 0 to 9 alone)",
+        Msg::CoordinateTooLarge => "{0} is too large to be a row or a column of any board",
+        Msg::RowOffBoard => "Row {0} is off the board, whose rows go from 0 to {1}",
+        Msg::ColumnOffBoard => "Column {0} is off the board, whose columns go from 0 to {1}",
+        Msg::CommandNotHere => "`{0}` isn't available in this game",
+        Msg::InvariantTooManyAround => "The open cell at {0} shows {1}, more mines than a cell has neighbours",
+        Msg::CellDrawnBroken => "Warning: {0}, so it is drawn as an error mark",
+        Msg::AnswerKeyTitle => "Minesweeper {0}, {1} with {2} mines: the answer key",
+        Msg::AnswerKeyWritten => "The answer key is in {0}",
+        Msg::CouldntWriteAnswerKey => "Couldn't write the answer key to {0}: {1}",
+        Msg::AnswerKeyWithDebugDump => "--answer-key can't be used with --debug-dump, which prints the mines at the terminal",
+        Msg::FlagsIgnored => "flags not counted",
+        Msg::FlagsPlacedCounted => "counting flags put on",
+        Msg::FlagTogglesCounted => "counting every flag put on or taken off",
+        Msg::ExpectFlagScoring => "\"ignore\", \"placements\" or \"toggles\"",
+        Msg::LayoutNoSafeCells => "The layout has no safe cells to open",
+        Msg::NoSafeCells => "Every cell on this board is a mine, so there is nothing to play",
+        Msg::OneSafeCell => "Only one of this board's {0} cells is safe, so the first clear wins or loses the game",
+        Msg::FormatSave => "save",
+        Msg::FormatHistory => "history record",
+        Msg::FormatStats => "stats file",
+        Msg::InvalidFormatVersion => "Invalid format version {0}",
+        Msg::NewerFormatVersion => "This {0} was written by a newer version of minesweeper-cli (format version {1}; this one reads up to {2})",
+        Msg::StatsLeftAlone => "{0}, so it is left as it is and nothing is kept this session",
+        Msg::SaveUnknownMode => "unknown mode {0}",
+        Msg::SaveMissingHeader => "missing header",
+        Msg::SaveExpectedField => "expected {0}",
+        Msg::SaveInvalidField => "invalid {0}",
+        Msg::SaveInvalidNote => "invalid note {0}",
+        Msg::SaveLayoutTooShort => "layout is too short",
+        Msg::SaveLayoutWrongLength => "layout row has the wrong length",
+        Msg::SaveInvalidMove => "invalid move {0}",
+        Msg::SaveInvalidMoveBecause => "invalid move {0}: {1}",
+        Msg::SaveMoveNotReplayed => "move {0} can't be replayed: {1}",
+        Msg::SaveNoteNotKept => "note on {0} can't be kept: {1}",
+        Msg::InterruptNotCaught => "Ctrl-C can't be caught on this platform: the game is autosaved after every move, but the time since the last one is lost",
+        Msg::ServeIdle => "Nothing was sent for too long, so the game is put aside for resume",
+        Msg::AnswerKeyOneGame => "--answer-key writes the board of a game played at the terminal, so it only goes with a single, coop, daily or weekly game, and not with --batch or --serve",
+        Msg::ShareHeading => "Minesweeper {0}",
+        Msg::AchievementEarned => "Achievement earned: {0} ({1})",
+        Msg::AchievementFirstWin => "First win",
+        Msg::AchievementFirstWinInfo => "Win a game",
+        Msg::AchievementNoFlags => "No flags needed",
+        Msg::AchievementNoFlagsInfo => "Win without placing a single flag",
+        Msg::AchievementNinetyNine => "Minefield",
+        Msg::AchievementNinetyNineInfo => "Win a board with 99 mines or more",
+        Msg::AchievementCascade => "Avalanche",
+        Msg::AchievementCascadeInfo => "Open 50 cells or more with a single click",
+        Msg::AchievementQuick => "Against the clock",
+        Msg::AchievementQuickInfo => "Win in under a minute",
+
+        Msg::InvalidCellPosition => "Invalid cell position",
+        Msg::CellAlreadyCleared => "Cell already cleared",
+        Msg::ClearedBomb => "Cleared a bomb",
+        Msg::NothingToUndo => "Nothing to undo",
+        Msg::GameIsPaused => "The game is paused; type resume to carry on",
+        Msg::GameIsntPaused => "The game isn't paused",
+        Msg::Paused => "Paused",
+        Msg::IdleNotCounted => "(idle for {0}; not counted)",
+        Msg::SplitsHeading => "Split",
+        Msg::SplitsTime => "Time",
+        Msg::SplitsBest => "Best",
+        Msg::SlowestMoves => "Slowest moves:",
+        Msg::ChordNotOpen => "Only an open cell can be chorded",
+        Msg::ChordNothingToOpen => "There is nothing around it left to open",
+        Msg::ChordFlagsDontMatch => "There are {0} flags around this cell, but it needs {1}",
+
+        Msg::NoCommand => "No command entered",
+        Msg::UnknownCommand => "Unknown command: {0}",
+        Msg::MissingCoordinate => "Expected a row and a column",
+        Msg::MissingPath => "Expected a file path",
+        Msg::UnknownExport => "Unknown export format: {0}",
+        Msg::TooManyArguments => "Too many arguments",
+        Msg::InvertedRect => "The second corner of a rectangle must be below and right of the first",
+
+        Msg::ActionClear => "clear",
+        Msg::ActionChord => "chord",
+        Msg::ActionFlag => "flag",
+        Msg::ActionQuestion => "question",
+        Msg::MoveOpenedOne => "{0} {1} opened 1 cell",
+        Msg::MoveOpened => "{0} {1} opened {2} cells",
+        Msg::MoveAreaOpened => "{0} {1} opened {2} cells and skipped {3}",
+        Msg::MoveHitBomb => "{0} {1} hit a bomb",
+        Msg::MoveHitBombAt => "{0} {1} hit a bomb at {2}",
+        Msg::MoveMarkPlaced => "{0} {1} placed",
+        Msg::MoveMarkRemoved => "{0} {1} removed",
+        Msg::FlagPlacedAt => "Flag placed at {0}; {1} mines unaccounted for",
+        Msg::FlagRemovedFrom => "Flag removed from {0}; {1} mines unaccounted for",
+        Msg::QuestionPlacedAt => "Question mark placed at {0}",
+        Msg::QuestionRemovedFrom => "Question mark removed from {0}",
+
+        Msg::StatusMines => "Mines: {0}",
+        Msg::StatusFlags => "Flags: {0}",
+        Msg::StatusLives => "Lives: {0}",
+        Msg::StatusTime => "Time: {0}",
+        Msg::StatusMoves => "Moves: {0}",
+        Msg::StatusView => "Showing rows {0}-{1} of {2}, columns {3}-{4} of {5}",
+        Msg::TerminalTooSmall => "Terminal too small (need at least {0}x{1})",
+
+        Msg::ResultWon => "won",
+        Msg::ResultLost => "lost",
+        Msg::ResultInProgress => "in progress",
+        Msg::ShareSummary => "{0}x{1}, {2} mines: {3} in {4}",
+        Msg::TranscriptStart => "start",
+        Msg::TranscriptMove => "move {0}: {1}",
+        Msg::TranscriptResult => "result: {0} in {1}",
+        Msg::TranscriptWritten => "Transcript written to {0}",
+
+        Msg::MoveUndone => "Move undone",
+        Msg::ExportsAfterGame => "Exports are available once the game is over",
+        Msg::UndoNotInDaily => "Undo is not available in the daily challenge",
+        Msg::GameIsOver => "The game is over",
+        Msg::LivesLeft => "{0} lives left",
+        Msg::PlayerName => "Player {0}",
+        Msg::PlayerTurn => "Player {0}'s turn",
+        Msg::PlayerMove => "Player {0}: {1}",
+        Msg::OneCommandPerTurn => "One command per turn, please",
+        Msg::PlayerStats => "Player {0}: {1} cells opened, {2} flags placed, {3} mistakes",
+        Msg::ErrorLine => "error: {0}",
+
+        Msg::RaceBoard => "{0}'s board:",
+        Msg::RaceStartTurn => "{0}, press enter to start your turn",
+        Msg::RaceHandOver => "Press enter and hand over to the other player",
+        Msg::RaceHitBomb => "{0} hit a bomb",
+        Msg::RaceStats => "{0}: {1} moves, {2} cells opened",
+        Msg::RaceSeed => "Seed: {0}",
+
+        Msg::CouldntAutosave => "Couldn't autosave: {0}",
+        Msg::CouldntSaveStats => "Couldn't save stats: {0}",
+        Msg::LineCancelled => "(cancelled)",
+        Msg::CouldntRemoveAutosave => "Couldn't remove autosave: {0}",
+        Msg::NoDataDir => "No data directory available",
+        Msg::GameSaved => "Game saved, start minesweeper again to resume it",
+        Msg::CouldntSave => "Couldn't save the game: {0}",
+        Msg::IgnoringAutosave => "Ignoring unreadable autosave ({0})",
+        Msg::MalformedSave => "Malformed save: {0}",
+
+        Msg::Help => HELP_EN,
+        Msg::ProtocolHelp => PROTOCOL_HELP_EN,
+        Msg::WroteConfig => "Wrote the default config to {0}",
+        Msg::CouldntRead => "Couldn't read {0}: {1}",
+        Msg::BatchSingleOnly => "--batch only supports single-player games",
+        Msg::JsonNeedsBatch => "--json only works together with --batch, --serve or --broadcast",
+        Msg::UnknownArgument => "Unknown argument `{0}`, see --help",
+        Msg::MissingValue => "--{0} needs a value",
+        Msg::OptionMustBe => "--{0} must be {1}",
+        Msg::OptionsConflict => "--{0} and --{1} each say how many mines to lay, so only one can be given",
+        Msg::ConfigCouldntAccess => "Couldn't access {0}: {1}",
+        Msg::ConfigSyntax => "Config line {0}: expected `key = value`",
+        Msg::ConfigUnknownKey => "Config line {0}: unknown key `{1}`",
+        Msg::ConfigInvalidValue => "Config line {0}: `{1}` must be {2}",
+        Msg::ConfigTooManyMines => {
+            "There must be fewer mines than cells, but there are {0} mines for {1} cells"
+        }
+        Msg::ConfigTooManyRocks => {
+            "There must be fewer rocks than cells without a mine, but there are {0} rocks for {1}"
+        }
+        Msg::ConfigAlreadyExists => "{0} already exists, not overwriting it",
+        Msg::NoConfigDir => "No config directory available",
+
+        Msg::ExpectTrueOrFalse => "true or false",
+        Msg::ExpectSize => "a whole number of at least 1",
+        Msg::ExpectWholeNumber => "a whole number",
+        Msg::ExpectDensity => "a number between 0 and 1",
+        Msg::ExpectTheme => "\"ascii\" or \"emoji\"",
+        Msg::ExpectBanner => "\"block\", \"outline\" or \"none\"",
+        Msg::ExpectCoordinates => "\"numeric\" or \"letters\"",
+        Msg::ExpectAssist => "\"none\", \"hints\" or \"full\"",
+        Msg::ExpectPreset => "beginner, intermediate or expert",
+        Msg::ExpectLang => "en or es",
+
+        Msg::TutorialWelcome => {
+            "Welcome to minesweeper! The goal is to open every cell that doesn't hide a mine.\n\
+             Closed cells are shown as #. Here is a small board:"
+        }
+        Msg::TutorialFirstClear => {
+            "Cells are chosen by row, then column, both counting from 0.\n\
+             Try clearing row 2, column 2 by typing: c 2 2"
+        }
+        Msg::TutorialNumbers => {
+            "The 1 means exactly one of the 8 cells around it is a mine.\n\
+             Cells with no mines around them are even better: opening one opens its neighbours too."
+        }
+        Msg::TutorialCorner => "The top-left corner is far from the 1. Clear it with: c 0 0",
+        Msg::TutorialCascade => {
+            "That opened a whole area at once, stopping at the numbered cells around its edge.\n\
+             Look at the 2 at row 2, column 4: only two closed cells touch it, so both are mines.\n\
+             Mark mines with flags so you don't clear them by accident; flags show as ^."
+        }
+        Msg::TutorialFlagFirst => "Flag the cell at row 3, column 3 with: f 3 3",
+        Msg::TutorialFlagSecond => "Now flag the other one, at row 3, column 4, with: f 3 4",
+        Msg::TutorialThirdMine => {
+            "The 2 at row 4, column 2 touches two closed cells as well: the flag at (3,3) and (4,3).\n\
+             So (4,3) is the third mine."
+        }
+        Msg::TutorialFlagThird => "Flag it with: f 4 3",
+        Msg::TutorialAllFlagged => "Every mine is flagged, so the last closed cell must be safe.",
+        Msg::TutorialLastCell => "Clear it with: c 4 4",
+        Msg::TutorialFreePlay => {
+            "Well done, that's the whole game! Now try a small board on your own.\n\
+             Use c <row> <col> to clear and f <row> <col> to flag or unflag."
+        }
+        Msg::TutorialOnlyClearAndFlag => "Only c and f are used in the tutorial",
+        Msg::TutorialNotQuite => "That's not quite it. {0}",
+        Msg::TutorialWon => "You won your first game!",
+        Msg::TutorialLost => "Boom! That one was a mine.",
+        Msg::TutorialStopped => "Tutorial stopped",
+        Msg::TutorialComplete => "Tutorial complete",
+    }
+}
+
+// the lesson scripts haven't been translated yet, so they fall back to English
+fn spanish(msg: Msg) -> Option<&'static str> {
+    let text = match msg {
+        Msg::GameWon => "Partida ganada",
+        Msg::GameLostAt => "Partida perdida: mina en {0}",
+        Msg::GameAborted => "Partida abandonada",
+        Msg::PlayerWins => "{0} gana",
+
+        Msg::EnterRowIndex => "Introduce la fila:",
+        Msg::EnterRowOrCommand => {
+            "Introduce la fila (o una orden: f <fila> <col> para marcar, q <fila> <col> para una interrogación, ch <fila> <col> para un acorde, u para deshacer):"
+        }
+        Msg::EnterColIndex => "Introduce la columna:",
+        Msg::EnterCommand => "Introduce una orden:",
+        Msg::CoopPrompt => {
+            "Introduce una orden (c <fila> <col> para despejar, f <fila> <col> para marcar, ch <fila> <col> para un acorde, u para deshacer, share):"
+        }
+        Msg::DailyPrompt => {
+            "Introduce una orden (c <fila> <col> para despejar, f <fila> <col> para marcar, ch <fila> <col> para un acorde, share):"
+        }
+        Msg::PostgamePrompt => "Escribe share, export transcript <ruta>, export cast <ruta>, export mbf <ruta>, export discord, o nada para salir:",
+        Msg::ResumePrompt => {
+            "Hay una partida {0} interrumpida ({1}, {2} jugadas). ¿Continuarla? [s/n]"
+        }
+        Msg::YesAnswer => "s",
+        Msg::PlayAgainPrompt => "¿Jugar otra partida? [s/n]",
+        Msg::SummaryHeading => "En esta sesión:",
+        Msg::SummaryPlayed => "  Partidas jugadas: {0}",
+        Msg::SummaryWinRate => "  Victorias: {0}% ({1} de {2})",
+        Msg::SummaryTotalTime => "  Tiempo total: {0}",
+        Msg::SummaryBestTime => "  Mejor tiempo: {0}",
+        Msg::SummaryBvPerSecond => "  3BV/s medio: {0}",
+        Msg::SummaryStreak => "  Racha de victorias más larga: {0}",
+        Msg::JsonExpected => "Se esperaba {0} en el byte {1}",
+        Msg::JsonTrailing => "Caracteres inesperados en el byte {0}",
+        Msg::MissingField => "Falta el campo {0}",
+        Msg::InvalidField => "Valor no válido para {0}",
+        Msg::SkippingHistoryLine => "Se omite la línea {0} del historial: {1}",
+        Msg::CouldntReadHistory => "No se pudo leer el historial: {0}",
+        Msg::CouldntWriteHistory => "No se pudo añadir la partida al historial: {0}",
+        Msg::NoHistory => "No hay partidas así en el historial",
+        Msg::HistoryDate => "Fecha",
+        Msg::HistoryBoard => "Tablero",
+        Msg::HistoryResult => "Resultado",
+        Msg::HistoryTime => "Tiempo",
+        Msg::HistoryMoves => "Jugadas",
+        Msg::HistoryCode => "Código",
+        Msg::HistoryWon => "ganada",
+        Msg::HistoryLost => "perdida",
+        Msg::ExpectResult => "won, lost o resigned",
+        Msg::NoLossesInHistory => "No hay partidas perdidas en el historial",
+        Msg::HeatmapHeading => "Dónde se pisó la última mina, en {0} partidas perdidas:",
+        Msg::BoardInfoSize => "Tablero: {0}x{1}",
+        Msg::BoardInfoMines => "Minas: {0} (el {1}% de las celdas{2})",
+        Msg::BoardInfoRequestedDensity => "; se pidió un {0}%",
+        Msg::BoardInfoRequestedCount => "; se pidieron {0}",
+        Msg::BoardInfoZeros => "Ceros: {0}, en {1} aberturas",
+        Msg::BoardInfoThreeBv => "3BV: {0}",
+        Msg::AnalysisClosed => "Celdas cerradas: {0}, minas sin marcar: {1}",
+        Msg::AnalysisRegion => "{0}: {1} celdas, {2} junto a números que piden {3} minas más entre todos",
+        Msg::AnalysisIsolatedRegion => "{0}: {1} celdas, ninguna junto a un número",
+        Msg::NoPeeksLeft => "No quedan vistazos",
+        Msg::AlreadyPeeked => "Ya se ha echado un vistazo a esa celda",
+        Msg::ActionPeek => "vistazo",
+        Msg::MovePeekedMine => "{0} {1}: una mina",
+        Msg::MovePeekedSafe => "{0} {1}: segura",
+        Msg::PeeksLeft => "Vistazos: {0}",
+        Msg::StatusShields => "Escudos: {0}",
+        Msg::MoveDefused => "{0} {1} pisó una mina, pero un escudo aguantó la explosión",
+        Msg::MoveDefusedAt => "{0} {1} pisó una mina en {2}, pero un escudo aguantó la explosión",
+        Msg::ShieldEarned => "¡Escudo conseguido! Escudos: {0}",
+        Msg::MinesMoved => "¡Se han movido {0} minas!",
+        Msg::StatusMovingMines => "Minas móviles: cada {0}",
+        Msg::CampaignDone => "ganado, mejor {0}",
+        Msg::CampaignNext => "siguiente",
+        Msg::CampaignLocked => "bloqueado",
+        Msg::CampaignLevel => "Nivel {0}: {1}",
+        Msg::CampaignUnlocked => "¡Nivel {0} superado! Se ha desbloqueado el nivel {1} ({2})",
+        Msg::CampaignComplete => "Has superado todos los niveles de la campaña",
+        Msg::ExpectPositiveNumber => "un número entero mayor que 0",
+        Msg::GauntletBoard => "Tablero {0} ({1} despejados hasta ahora)",
+        Msg::GauntletLeft => "Quedan: {0}",
+        Msg::GauntletPrompt => "Escribe una orden (c <fila> <col> para despejar, f <fila> <col> para marcar, ch <fila> <col> para acorde):",
+        Msg::NoPauseInGauntlet => "Un gauntlet no se puede pausar",
+        Msg::GauntletPenalty => "Tablero perdido: {0} segundos menos",
+        Msg::GauntletOver => "¡Se acabó el tiempo! Tableros despejados: {0}, el último con {1} restantes",
+        Msg::GauntletNewBest => "¡Nuevo récord!",
+        Msg::GauntletBest => "Récord: {0} tableros, el último con {1} restantes",
+        Msg::CellIsRock => "Esa celda es una roca",
+        Msg::EndlessView => "Filas {0} a {1}, columnas {2} a {3}",
+        Msg::EndlessStatus => "Abiertas: {0}  Banderas: {1}  Bloques: {2}",
+        Msg::EndlessPrompt => "Introduce un comando (c/f/ch <fila> <col>, o go <fila> <col> para mover la vista; las coordenadas pueden ser negativas):",
+        Msg::EndlessCantChord => "Esa celda no es un número abierto con todas sus minas marcadas",
+        Msg::EndlessOver => "Has pisado una mina en ({0}, {1}) tras abrir {2} celdas",
+        Msg::EndlessStopped => "Terminado tras abrir {0} celdas",
+        Msg::ExpectSymmetry => "\"none\", \"horizontal\", \"vertical\" o \"rotational\"",
+        Msg::ConfigUnevenSymmetry => "Una disposición simétrica de este tamaño solo puede tener un número par de minas, no {0}",
+        Msg::LimitRow => "fila",
+        Msg::LimitColumn => "columna",
+        Msg::LimitMinAboveMax => "Al menos {0} minas por {1} es más que el máximo permitido, {2}",
+        Msg::LimitMinAboveLength => "Al menos {0} minas por {1} no caben en {2} celdas",
+        Msg::LimitTooFewMines => "Al menos {0} minas por {1} necesita {2} minas, pero el tablero tiene {3}",
+        Msg::LimitTooManyMines => "Como mucho {0} minas por {1} deja sitio para {2} minas, pero el tablero tiene {3}",
+        Msg::ExpectDistribution => "\"uniform\", \"clustered\" o \"sparse\"",
+        Msg::ConfigConflicting => "`{0}` y `{1}` colocan las minas cada uno a su manera, así que solo se puede usar uno",
+        Msg::ConfigStencilSize => "La plantilla mide {0}x{1}, pero el tablero mide {2}x{3}",
+        Msg::CouldntReadStencil => "No se pudo leer la plantilla {0}: {1}",
+        Msg::MbfTruncated => "El archivo termina antes de tiempo: debería tener al menos {0} bytes, pero tiene {1}",
+        Msg::MbfEmptyBoard => "El tablero del archivo no tiene celdas",
+        Msg::MbfOutOfRange => "La mina en ({0},{1}) está fuera del tablero",
+        Msg::MbfDuplicateMine => "La mina en ({0},{1}) aparece dos veces",
+        Msg::MbfNoSafeCells => "Todas las celdas del tablero son minas",
+        Msg::MbfTooLarge => "Un tablero de {0}x{1} es demasiado grande para un archivo .mbf, que admite hasta 255x255",
+        Msg::MbfWritten => "Tablero escrito en {0}",
+        Msg::CouldntImport => "No se pudo importar {0}: {1}",
+        Msg::LoadOrImport => "Solo se puede usar uno de --load, --import-mbf y load <PARTIDA> a la vez",
+        Msg::CastWritten => "Grabación escrita en {0}",
+        Msg::NoExportsOverNetwork => "Las exportaciones a archivos no están disponibles por la red",
+        Msg::ServeListening => "Escuchando en {0}",
+        Msg::ServeBusy => "Otro cliente está jugando; vuelve a intentarlo cuando termine su partida",
+        Msg::ServeToken => "Jugando {0}; para continuar tras desconectarte, envía resume {1}",
+        Msg::ServeNoSuchGame => "No hay ninguna partida esperando con ese código",
+        Msg::ServeParked => "El cliente dejó {0}; se puede continuar con {1}",
+        Msg::ServeStopping => "El servidor se está deteniendo",
+        Msg::CouldntServe => "No se pudo servir: {0}",
+        Msg::ExpectPort => "un número de puerto, de 0 a 65535",
+        Msg::NetRaceWaiting => "Esperando a que se conecte un rival",
+        Msg::NetRaceStarted => "Empieza la carrera: los dos jugadores tienen el mismo tablero",
+        Msg::NetRaceProgress => "Tu rival ha abierto el {0}% de su tablero",
+        Msg::NetRaceLeft => "Tu rival se ha desconectado; pierde si no vuelve en {0} segundos",
+        Msg::NetRaceBack => "Tu rival ha vuelto",
+        Msg::NetRaceWon => "Has ganado la carrera",
+        Msg::NetRaceLost => "Tu rival ha ganado la carrera",
+        Msg::BroadcastListening => "Los espectadores pueden mirar en {0}",
+        Msg::BroadcastSingleOnly => "--broadcast solo funciona en partidas de un jugador en la terminal",
+        Msg::CouldntBroadcast => "No se pudo retransmitir: {0}",
+        Msg::InvariantRaggedRows => "La fila {0} tiene un número de casillas distinto de la primera",
+        Msg::InvariantWrongPosition => "La casilla en {0} tiene la posición equivocada",
+        Msg::InvariantWrongCount => "La casilla abierta en {0} muestra {1} pero tiene {2} minas alrededor",
+        Msg::InvariantFlaggedAndOpen => "La casilla en {0} está marcada y abierta a la vez",
+        Msg::InvariantQuestionOnOpen => "La casilla abierta en {0} tiene un signo de interrogación",
+        Msg::InvariantFlaggedAndExploded => "La mina en {0} está marcada y explotada a la vez",
+        Msg::InvariantDefusedButNotExploded => "La casilla en {0} está desactivada pero no explotó",
+        Msg::LayoutEmpty => "La disposición no tiene casillas",
+        Msg::LayoutRaggedRow => "La fila {0} de la disposición tiene una longitud distinta de la primera",
+        Msg::LayoutUnexpectedSymbol => "{0} inesperado en la disposición en ({1},{2})",
+        Msg::DifficultyStars => "Dificultad: {0} ({1} de {2})",
+        Msg::DifficultyDetails => "3BV {0}, {1} conjeturas inevitables, frontera más grande de {2} casillas",
+        Msg::InvalidCellName => "No es una casilla: {0} (se esperaba una fila y una columna, o un nombre como E4)",
+        Msg::EchoClearing => "Despejando {0} = {1}",
+        Msg::EchoFlagging => "Marcando {0} = {1}",
+        Msg::EchoChording => "Despejando alrededor de {0} = {1}",
+        Msg::EchoQuestioning => "Poniendo una interrogación en {0} = {1}",
+        Msg::EchoPeeking => "Mirando {0} = {1}",
+        Msg::ConfirmNearFlag => "{0} está junto a una bandera. ¿Despejarla de todos modos? [s/n]",
+        Msg::MoveLeftUndone => "Se ha dejado como estaba",
+        Msg::CascadeOpened => "Se abrieron {0} casillas ({1}), {2} a la vista",
+        Msg::CascadeOneRegion => "1 región nueva",
+        Msg::CascadeRegions => "{0} regiones nuevas",
+        Msg::CascadeOneNumber => "1 casilla con número en la frontera",
+        Msg::CascadeNumbers => "{0} casillas con número en la frontera",
+        Msg::ExpectWin => "\"open\" o \"flags\"",
+        Msg::StatusFlagWin => "Victoria: marcar cada mina",
+        Msg::GameWonByFlags => "Partida ganada: todas las minas marcadas",
+        Msg::GameWonByOpening => "Partida ganada: todas las casillas seguras abiertas",
+        Msg::InvalidNote => "Una nota es una sola letra, o - para borrarla, no {0}",
+        Msg::NotePlacedAt => "Nota {0} puesta en {1}",
+        Msg::NoteRemovedFrom => "Nota quitada de {0}",
+        Msg::LoggedMove => "#{0} {1}",
+        Msg::LoggedMoveUndone => "#{0} {1} (deshecho)",
+        Msg::NoMovesYet => "Todavía no hay jugadas",
+        Msg::MoreMoves => "-- Intro para ver más, q para parar --",
+        Msg::DumpMissing => "No se encontró ningún volcado de depuración",
+        Msg::DumpMalformed => "Volcado de depuración no válido: {0}",
+        Msg::DumpMismatch => "El volcado de depuración no coincide con la partida que reproducen sus jugadas",
+        Msg::CrashReportWritten => "Algo salió mal. La partida se guardó, para reanudarla la próxima vez, y se escribió un informe del fallo en {0}",
+        Msg::CouldntWriteCrashReport => "No se pudo escribir un informe del fallo: {0}",
+        Msg::BoardInconsistent => "Error del programa: el tablero se contradice en {0}: {1}",
+        Msg::ZeroBesideMine => "no muestra minas alrededor, pero {0} es una",
+        Msg::ReportInconsistent => "Es un error del juego, y la jugada no se hizo. Informa de él con la salida de --debug-dump",
+        Msg::RulesNoCells => "El tablero necesita al menos una casilla",
+        Msg::RulesNoLives => "Una partida necesita al menos una vida",
+        Msg::RulesInvalidFraction => "La fracción de minas que se mueven tiene que estar entre 0 y 1, no {0}",
+        Msg::Remaining => "Los números demuestran que {0} celdas cerradas son seguras y {1} sin bandera son minas",
+        Msg::StatusRemaining => "Demostrables: {0} seguras, {1} minas",
+        Msg::PromptEof => "La entrada terminó antes de recibir una respuesta",
+        Msg::PromptGaveUp => "Se abandona tras {0} respuestas no válidas seguidas",
+        Msg::RaceAborted => "La carrera terminó antes de que nadie ganara",
+        Msg::MissingSaveName => "Una partida guardada necesita un nombre, p. ej. save as partida del viernes",
+        Msg::NoSuchSave => "No hay ninguna partida guardada {0}; `load` las lista",
+        Msg::NoSaves => "Aún no hay partidas guardadas; `save as <nombre>` durante una partida guarda una",
+        Msg::SavesName => "Nombre",
+        Msg::SavesSize => "Tamaño",
+        Msg::SavesProgress => "Abierto",
+        Msg::SavesSaved => "Guardada",
+        Msg::SkippingSaveIndexLine => "Se omite la línea {0} del índice de partidas guardadas, que no se puede leer",
+        Msg::SavedAs => "Guardada como \"{0}\"; `minesweeper load` lista las partidas guardadas",
+        Msg::SaveReplaced => "Reemplazada la partida guardada \"{0}\"",
+        Msg::CouldntSaveAs => "No se pudo guardar la partida: {0}",
+        Msg::ConfirmDeleteSave => "¿Borrar la partida guardada \"{0}\" ({1}, {2}% abierto)? [s/N]",
+        Msg::SaveDeleted => "Borrada la partida guardada \"{0}\"",
+        Msg::SaveKept => "Se conservó la partida guardada",
+        Msg::NoSavesOverNetwork => "Las partidas no se pueden guardar por la red",
+        Msg::ReasonSatisfied => "un punto: {0} satisfecho",
+        Msg::ReasonAllMines => "un punto: {0} las necesita todas",
+        Msg::ReasonSubset => "subconjunto: {0} dentro de {1}",
+        Msg::SolverMove => "Resolutor: {0}  [{1}]",
+        Msg::SolverFinished => "El resolutor terminó la partida en {0} jugadas",
+        Msg::SolverNeedsGuess => "El resolutor hizo {0} jugadas; la siguiente sería adivinar",
+        Msg::SolverNothingProven => "El resolutor no puede demostrar ninguna jugada desde aquí; la siguiente sería adivinar",
+        Msg::KeepSolverMoves => "¿Conservar las jugadas del resolutor? La partida contaría como asistida [s/n]",
+        Msg::SolverMovesKept => "Se conservan las jugadas del resolutor",
+        Msg::SolverMovesUndone => "De vuelta a donde estabas antes de mirar",
+        Msg::WatchNotHere => "Solo se puede mirar al resolutor en una partida de un jugador",
+        Msg::NoOpeningsInHistory => "No hay primeras jugadas en el historial",
+        Msg::OpeningsHeading => "Dónde fue la primera jugada, en {0} partidas:",
+        Msg::OpeningsKey => "(partidas, cuántas se ganaron, y cuántas primeras jugadas pisaron una mina/abrieron un hueco/abrieron un número)",
+        Msg::MissingMoveNumber => "Se esperaba a cuántas jugadas retroceder, p. ej. rewind 3",
+        Msg::InvalidMoveNumber => "{0} no es un número de jugadas",
+        Msg::RewindTooFar => "No se puede retroceder a la jugada {0}: solo se han hecho {1} jugadas",
+        Msg::Rewound => "Retrocedido a la jugada {0}; la partida cuenta ahora como asistida",
+        Msg::RewindNotInDaily => "No se puede retroceder en el reto diario",
+        Msg::WinProbExact => "Se puede ganar sin adivinar: {0}% (exacto, sobre las {1} disposiciones de las minas que encajan con los números)",
+        Msg::WinProbSampled => "Se puede ganar sin adivinar: alrededor del {0}% (±{1}% con un 95% de confianza, de {2} disposiciones de las minas al azar)",
+        Msg::WinProbCoarse => "Se puede ganar sin adivinar: aproximadamente el {0}% (±{1}%, de {2} disposiciones de las minas)",
+        Msg::WinProbCoarseWarning => "Aviso: demasiada parte del tablero queda fuera del alcance de los números, o demasiadas disposiciones encajan con ellos, para sopesarlas bien, así que esto es solo una estimación aproximada",
+        Msg::WinProbNone => "No se encontró a tiempo ninguna disposición de las minas que encaje con los números, así que no hay estimación",
+        Msg::WinProbInconsistent => "Ninguna disposición de las minas encaja a la vez con los números y las banderas; alguna bandera debe de estar mal",
+        Msg::HintSafe => "Pista: {0} es segura",
+        Msg::HintMine => "Pista: {0} es una mina",
+        Msg::NoHint => "Los números no demuestran nada más; la siguiente jugada sería adivinar",
+        Msg::HintsOff => "Las pistas están desactivadas; pon assist = \"hints\" en el archivo de configuración para tenerlas",
+        Msg::TraceShowsZero => "{0} muestra 0",
+        Msg::TraceAccounted => "{0} muestra {1}, y todas sus minas las explican {2}",
+        Msg::TraceOnlyRoom => "{0} muestra {1}, y solo caben sus minas en {2}",
+        Msg::TraceOnlyRoomKnown => "{0} muestra {1}; {2} explican {3} de sus minas, y el resto solo cabe en {4}",
+        Msg::TraceSubset => "a {0} le quedan {1} por encontrar entre {2}, todas también junto a {3}, al que le quedan {4} entre {5}; así que quedan {6} para {7}",
+        Msg::TraceSafe => "por tanto {0} es segura",
+        Msg::TraceMine => "por tanto {0} es una mina",
+        Msg::TraceMines => "por tanto {0} son minas",
+        Msg::TraceSafeCells => "por tanto {0} son seguras",
+        Msg::DiffNeedsJson => "--diff necesita --json, con --batch o --serve",
+        Msg::RulesTooBig => "un tablero de {0}x{1} tiene más de las {2} celdas que puede tener un tablero (ver max_cells)",
+        Msg::ChordPreviewUnsatisfied => "Hacer un acorde en {0} no haría nada todavía: muestra {1} y tiene {2} banderas alrededor",
+        Msg::ChordPreviewNothing => "Hacer un acorde en {0} no despejaría nada: todas las celdas alrededor están abiertas o marcadas",
+        Msg::ChordPreviewClears => "Hacer un acorde en {0} despejaría {1} celdas, si sus banderas son correctas: {2}",
+        Msg::WeeklyLives => "dos vidas en un tablero tan lleno como el experto",
+        Msg::WeeklyRocks => "rocas que nunca se abren, repartidas entre las minas",
+        Msg::WeeklyMirror => "minas en espejo: la mitad derecha es la izquierda",
+        Msg::WeeklyClusters => "minas agrupadas en bolsas, con llanuras abiertas entre ellas",
+        Msg::WeeklyMoving => "una quinta parte de las minas se mueve cada diez jugadas",
+        Msg::WeeklyFlags => "se gana marcando todas las minas en vez de abrir todas las casillas",
+        Msg::WeeklySparse => "minas tan separadas como se pueda",
+        Msg::WeeklyPeeks => "tres vistazos y un escudo en un tablero grande y lleno",
+        Msg::WeeklyModifier => "Esta semana ({0}): {1}",
+        Msg::UndoNotInWeekly => "No se puede deshacer en el reto semanal",
+        Msg::RewindNotInWeekly => "No se puede retroceder en el reto semanal",
+        Msg::EfficiencyLine => "3BV {0} en {1} clics ({5}), {2} en vano: {3}% de eficiencia, {4} 3BV/s",
+        Msg::GameResigned => "Partida abandonada al {0}% completada",
+        Msg::ResultResigned => "Abandonada",
+        Msg::ConfirmResign => "¿Abandonar esta partida? Contará como derrota [s/n]",
+        Msg::HistoryResigned => "abandonada",
+        Msg::SummaryResigned => "  Abandonadas: {0}",
+        Msg::StatusGhost => "Fantasma: jugada {0} de {1}",
+        Msg::CommandClear => "Abre una celda, o todas las celdas cerradas y sin bandera de una fila o de un rectángulo (esquinas superior izquierda e inferior derecha, incluidas)",
+        Msg::CommandFlag => "Pone una bandera en una celda cerrada, o la quita",
+        Msg::CommandChord => "Acorde: despeja los vecinos sin bandera de un número cuyas minas están todas marcadas; con --check, dice lo que haría en su lugar",
+        Msg::CommandChordCheck => "Dice lo que haría un acorde ahí, sin hacerlo",
+        Msg::CommandQuestion => "Pone un signo de interrogación en una celda cerrada, o lo quita",
+        Msg::CommandNote => "Pone una nota de una letra en una celda cerrada, o la quita con -",
+        Msg::CommandPeek => "Gasta un vistazo para ver si una celda cerrada es una mina",
+        Msg::CommandUndo => "Deshace la última jugada",
+        Msg::CommandRewind => "Vuelve a como estaba la partida tras ese número de jugadas",
+        Msg::CommandPause => "Detiene el reloj y oculta el tablero hasta resume",
+        Msg::CommandResume => "Continúa una partida en pausa",
+        Msg::CommandShare => "Muestra la partida como un bloque para compartir, sin sus minas",
+        Msg::CommandBoardInfo => "El tamaño, las minas, las aperturas y el 3BV del tablero, que no revelan nada",
+        Msg::CommandAnalyze => "Las celdas cerradas en regiones, con lo que piden los números de cada una",
+        Msg::CommandRemaining => "Cuántas celdas demuestran los números seguras y cuántas minas, pero no cuáles",
+        Msg::CommandWinProb => "Estima la probabilidad de despejar el tablero sin volver a adivinar",
+        Msg::CommandHint => "La celda que el solucionador resolvería después; con why, cómo llega a ella",
+        Msg::CommandMoves => "Las últimas cinco jugadas, o con all todas",
+        Msg::CommandWatch => "Deja que el solucionador siga mientras miras, y luego conserva sus jugadas o las deshace",
+        Msg::CommandResign => "Abandona la partida como perdida, cuando lo confirmes",
+        Msg::CommandExport => "Escribe la partida, como bloque de Discord, transcripción, archivo .mbf o grabación de asciinema",
+        Msg::CommandSaveAs => "Guarda la partida con un nombre, para cargarla después",
+        Msg::CommandHelp => "Muestra estas órdenes",
+        Msg::CommandDump => "Imprime la partida, minas incluidas, como volcado de depuración",
+        Msg::CommandsNote => "Una <cell> es una fila y una columna, como en c 3 4, o el nombre de la celda, la letra de su columna y su fila contando desde 1, como en c E4. Varias órdenes pueden ir en una línea, separadas por punto y coma: f 0 1; f 0 2; c 3 3",
+        Msg::ReferenceExample => "Ejemplo: {0}",
+        Msg::Reference => REFERENCE_ES,
+        Msg::CoordinateNotANumber => "No es una fila ni una columna: {0} (se escriben solo con los dígitos del 0 al 9)",
+        Msg::CoordinateTooLarge => "{0} es demasiado grande para ser una fila o una columna de cualquier tablero",
+        Msg::RowOffBoard => "La fila {0} está fuera del tablero, cuyas filas van de 0 a {1}",
+        Msg::ColumnOffBoard => "La columna {0} está fuera del tablero, cuyas columnas van de 0 a {1}",
+        Msg::CommandNotHere => "`{0}` no está disponible en esta partida",
+        Msg::InvariantTooManyAround => "La casilla abierta en {0} muestra {1}, más minas de las que caben alrededor de una casilla",
+        Msg::CellDrawnBroken => "Aviso: {0}, así que se dibuja con una marca de error",
+        Msg::AnswerKeyTitle => "Buscaminas {0}, {1} con {2} minas: la solución",
+        Msg::AnswerKeyWritten => "La solución está en {0}",
+        Msg::CouldntWriteAnswerKey => "No se pudo escribir la solución en {0}: {1}",
+        Msg::AnswerKeyWithDebugDump => "--answer-key no se puede usar con --debug-dump, que muestra las minas en la terminal",
+        Msg::FlagsIgnored => "sin contar banderas",
+        Msg::FlagsPlacedCounted => "contando las banderas puestas",
+        Msg::FlagTogglesCounted => "contando cada bandera puesta o quitada",
+        Msg::ExpectFlagScoring => "\"ignore\", \"placements\" o \"toggles\"",
+        Msg::LayoutNoSafeCells => "La disposición no tiene casillas seguras que abrir",
+        Msg::NoSafeCells => "Todas las casillas de este tablero son minas, así que no hay nada que jugar",
+        Msg::OneSafeCell => "Solo una de las {0} casillas de este tablero es segura, así que el primer despeje gana o pierde la partida",
+        Msg::FormatSave => "partida guardada",
+        Msg::FormatHistory => "registro del historial",
+        Msg::FormatStats => "archivo de estadísticas",
+        Msg::InvalidFormatVersion => "Versión de formato no válida: {0}",
+        Msg::NewerFormatVersion => "Una versión más nueva de minesweeper-cli escribió esto ({0}, versión de formato {1}; esta lee hasta la {2})",
+        Msg::StatsLeftAlone => "{0}, así que se deja como está y no se guarda nada en esta sesión",
+        Msg::SaveUnknownMode => "modo desconocido {0}",
+        Msg::SaveMissingHeader => "falta la cabecera",
+        Msg::SaveExpectedField => "se esperaba {0}",
+        Msg::SaveInvalidField => "{0} no válido",
+        Msg::SaveInvalidNote => "nota no válida {0}",
+        Msg::SaveLayoutTooShort => "el tablero es demasiado corto",
+        Msg::SaveLayoutWrongLength => "una fila del tablero tiene una longitud incorrecta",
+        Msg::SaveInvalidMove => "movimiento no válido {0}",
+        Msg::SaveInvalidMoveBecause => "movimiento no válido {0}: {1}",
+        Msg::SaveMoveNotReplayed => "el movimiento {0} no se puede repetir: {1}",
+        Msg::SaveNoteNotKept => "la nota en {0} no se puede conservar: {1}",
+        Msg::InterruptNotCaught => "Ctrl-C no se puede capturar en esta plataforma: la partida se guarda tras cada movimiento, pero se pierde el tiempo desde el último",
+        Msg::ServeIdle => "No se envió nada durante demasiado tiempo, así que la partida queda aparcada para resume",
+        Msg::AnswerKeyOneGame => "--answer-key escribe el tablero de una partida jugada en la terminal, así que solo sirve con una partida single, coop, daily o weekly, y no con --batch ni --serve",
+        Msg::ShareHeading => "Buscaminas {0}",
+        Msg::AchievementEarned => "Logro conseguido: {0} ({1})",
+        Msg::AchievementFirstWin => "Primera victoria",
+        Msg::AchievementFirstWinInfo => "Gana una partida",
+        Msg::AchievementNoFlags => "Sin banderas",
+        Msg::AchievementNoFlagsInfo => "Gana sin poner ni una bandera",
+        Msg::AchievementNinetyNine => "Campo minado",
+        Msg::AchievementNinetyNineInfo => "Gana en un tablero con 99 minas o más",
+        Msg::AchievementCascade => "Avalancha",
+        Msg::AchievementCascadeInfo => "Abre 50 celdas o más de un solo clic",
+        Msg::AchievementQuick => "Contrarreloj",
+        Msg::AchievementQuickInfo => "Gana en menos de un minuto",
+
+        Msg::InvalidCellPosition => "Posición de celda no válida",
+        Msg::CellAlreadyCleared => "La celda ya está despejada",
+        Msg::ClearedBomb => "Has despejado una mina",
+        Msg::NothingToUndo => "No hay nada que deshacer",
+        Msg::GameIsPaused => "La partida está en pausa; escribe resume para seguir",
+        Msg::GameIsntPaused => "La partida no está en pausa",
+        Msg::Paused => "En pausa",
+        Msg::IdleNotCounted => "(inactivo durante {0}; no cuenta)",
+        Msg::SplitsHeading => "Parcial",
+        Msg::SplitsTime => "Tiempo",
+        Msg::SplitsBest => "Mejor",
+        Msg::SlowestMoves => "Jugadas más lentas:",
+        Msg::ChordNotOpen => "Solo se puede hacer un acorde sobre una celda abierta",
+        Msg::ChordNothingToOpen => "No queda nada alrededor por abrir",
+        Msg::ChordFlagsDontMatch => "Hay {0} banderas alrededor de esta celda, pero necesita {1}",
+
+        Msg::NoCommand => "No se ha introducido ninguna orden",
+        Msg::UnknownCommand => "Orden desconocida: {0}",
+        Msg::MissingCoordinate => "Se esperaba una fila y una columna",
+        Msg::MissingPath => "Se esperaba una ruta de archivo",
+        Msg::UnknownExport => "Formato de exportación desconocido: {0}",
+        Msg::TooManyArguments => "Demasiados argumentos",
+        Msg::InvertedRect => "La segunda esquina del rectángulo debe quedar debajo y a la derecha de la primera",
+
+        Msg::ActionClear => "despejar",
+        Msg::ActionChord => "acorde",
+        Msg::ActionFlag => "bandera",
+        Msg::ActionQuestion => "interrogación",
+        Msg::MoveOpenedOne => "{0} {1} abrió 1 celda",
+        Msg::MoveOpened => "{0} {1} abrió {2} celdas",
+        Msg::MoveAreaOpened => "{0} {1} abrió {2} celdas y se saltó {3}",
+        Msg::MoveHitBomb => "{0} {1} pisó una mina",
+        Msg::MoveHitBombAt => "{0} {1} pisó una mina en {2}",
+        Msg::MoveMarkPlaced => "{0} {1} colocada",
+        Msg::MoveMarkRemoved => "{0} {1} quitada",
+        Msg::FlagPlacedAt => "Bandera colocada en {0}; faltan {1} minas por localizar",
+        Msg::FlagRemovedFrom => "Bandera quitada de {0}; faltan {1} minas por localizar",
+        Msg::QuestionPlacedAt => "Interrogación colocada en {0}",
+        Msg::QuestionRemovedFrom => "Interrogación quitada de {0}",
+
+        Msg::StatusMines => "Minas: {0}",
+        Msg::StatusFlags => "Banderas: {0}",
+        Msg::StatusLives => "Vidas: {0}",
+        Msg::StatusTime => "Tiempo: {0}",
+        Msg::StatusMoves => "Jugadas: {0}",
+        Msg::StatusView => "Filas {0}-{1} de {2}, columnas {3}-{4} de {5}",
+        Msg::TerminalTooSmall => "Terminal demasiado pequeño (hace falta al menos {0}x{1})",
+
+        Msg::ResultWon => "ganada",
+        Msg::ResultLost => "perdida",
+        Msg::ResultInProgress => "en curso",
+        Msg::ShareSummary => "{0}x{1}, {2} minas: {3} en {4}",
+        Msg::TranscriptStart => "inicio",
+        Msg::TranscriptMove => "jugada {0}: {1}",
+        Msg::TranscriptResult => "resultado: {0} en {1}",
+        Msg::TranscriptWritten => "Transcripción guardada en {0}",
+
+        Msg::MoveUndone => "Jugada deshecha",
+        Msg::ExportsAfterGame => "Las exportaciones están disponibles al terminar la partida",
+        Msg::UndoNotInDaily => "No se puede deshacer en el reto diario",
+        Msg::GameIsOver => "La partida ha terminado",
+        Msg::LivesLeft => "Quedan {0} vidas",
+        Msg::PlayerName => "Jugador {0}",
+        Msg::PlayerTurn => "Turno del jugador {0}",
+        Msg::PlayerMove => "Jugador {0}: {1}",
+        Msg::OneCommandPerTurn => "Una orden por turno, por favor",
+        Msg::PlayerStats => {
+            "Jugador {0}: {1} celdas abiertas, {2} banderas colocadas, {3} errores"
+        }
+        Msg::ErrorLine => "error: {0}",
+
+        Msg::RaceBoard => "Tablero de {0}:",
+        Msg::RaceStartTurn => "{0}, pulsa intro para empezar tu turno",
+        Msg::RaceHandOver => "Pulsa intro y pásale el turno al otro jugador",
+        Msg::RaceHitBomb => "{0} pisó una mina",
+        Msg::RaceStats => "{0}: {1} jugadas, {2} celdas abiertas",
+        Msg::RaceSeed => "Semilla: {0}",
+
+        Msg::CouldntAutosave => "No se pudo autoguardar: {0}",
+        Msg::CouldntSaveStats => "No se pudieron guardar las estadísticas: {0}",
+        Msg::LineCancelled => "(cancelado)",
+        Msg::CouldntRemoveAutosave => "No se pudo borrar el autoguardado: {0}",
+        Msg::NoDataDir => "No hay ningún directorio de datos disponible",
+        Msg::GameSaved => "Partida guardada, vuelve a abrir minesweeper para continuarla",
+        Msg::CouldntSave => "No se pudo guardar la partida: {0}",
+        Msg::IgnoringAutosave => "Se ignora un autoguardado ilegible ({0})",
+        Msg::MalformedSave => "Partida guardada mal formada: {0}",
+
+        Msg::Help => HELP_ES,
+        Msg::ProtocolHelp => PROTOCOL_HELP_ES,
+        Msg::WroteConfig => "Configuración por defecto guardada en {0}",
+        Msg::CouldntRead => "No se pudo leer {0}: {1}",
+        Msg::BatchSingleOnly => "--batch solo admite partidas de un jugador",
+        Msg::JsonNeedsBatch => "--json solo funciona junto con --batch, --serve o --broadcast",
+        Msg::UnknownArgument => "Argumento desconocido `{0}`, consulta --help",
+        Msg::MissingValue => "--{0} necesita un valor",
+        Msg::OptionMustBe => "--{0} debe ser {1}",
+        Msg::OptionsConflict => "--{0} y --{1} dicen cada uno cuántas minas poner, así que solo se puede dar uno",
+        Msg::ConfigCouldntAccess => "No se pudo acceder a {0}: {1}",
+        Msg::ConfigSyntax => "Línea {0} de la configuración: se esperaba `clave = valor`",
+        Msg::ConfigUnknownKey => "Línea {0} de la configuración: clave desconocida `{1}`",
+        Msg::ConfigInvalidValue => "Línea {0} de la configuración: `{1}` debe ser {2}",
+        Msg::ConfigTooManyMines => {
+            "Debe haber menos minas que celdas, pero hay {0} minas para {1} celdas"
+        }
+        Msg::ConfigTooManyRocks => {
+            "Debe haber menos rocas que celdas sin mina, pero hay {0} rocas para {1}"
+        }
+        Msg::ConfigAlreadyExists => "{0} ya existe, no se sobrescribe",
+        Msg::NoConfigDir => "No hay ningún directorio de configuración disponible",
+
+        Msg::ExpectTrueOrFalse => "true o false",
+        Msg::ExpectSize => "un número entero de al menos 1",
+        Msg::ExpectWholeNumber => "un número entero",
+        Msg::ExpectDensity => "un número entre 0 y 1",
+        Msg::ExpectTheme => "\"ascii\" o \"emoji\"",
+        Msg::ExpectBanner => "\"block\", \"outline\" o \"none\"",
+        Msg::ExpectCoordinates => "\"numeric\" o \"letters\"",
+        Msg::ExpectAssist => "\"none\", \"hints\" o \"full\"",
+        Msg::ExpectPreset => "beginner, intermediate o expert",
+        Msg::ExpectLang => "en o es",
+
+        Msg::TutorialOnlyClearAndFlag => "En el tutorial solo se usan c y f",
+        Msg::TutorialNotQuite => "No es exactamente eso. {0}",
+        Msg::TutorialWon => "¡Has ganado tu primera partida!",
+        Msg::TutorialLost => "¡Bum! Ahí había una mina.",
+        Msg::TutorialStopped => "Tutorial interrumpido",
+        Msg::TutorialComplete => "Tutorial completado",
+
+        _ => return None,
+    };
+
+    Some(text)
+}
+
+pub fn text(msg: Msg) -> &'static str {
+    let translated = match lang() {
+        Lang::English => None,
+        Lang::Spanish => spanish(msg),
+    };
+
+    translated.unwrap_or_else(|| english(msg))
+}
+
+// the message with each {N} replaced by the Nth argument
+pub fn fill(msg: Msg, args: &[&dyn Display]) -> String {
+    let template = text(msg);
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let argument = rest
+            .find('}')
+            .and_then(|end| Some((rest[1..end].parse::<usize>().ok()?, end)))
+            .and_then(|(index, end)| Some((args.get(index)?, end)));
+
+        match argument {
+            Some((argument, end)) => {
+                out.push_str(&argument.to_string());
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+
+    out
+}
+
+const HELP_EN: &str = "\
+Usage: minesweeper [MODE] [OPTIONS]
+       minesweeper config init
+
+Modes:
+  (none)                 Single-player game
+  race                   Two-player hot-seat race on the same board
+  coop                   Two players, one board, shared lives
+  daily                  Today's daily challenge
+  weekly                 This week's challenge, with its twist on the rules
+  tutorial               Learn the rules on a guided board
+  campaign               Play the next level of the campaign
+  gauntlet               Clear as many small boards as you can in 5 minutes
+  endless                Open as much as you can of a board without edges
+  achievements           List the achievements, earned or not
+  history                List the last games played; --last <N> (10 by default),
+                         --result won|lost|resigned, --no-resigned and
+                         --preset <NAME> pick which
+  history weekly         Only the weekly challenges
+  history heatmap        Where on the board games were lost, shaded by how often
+  history openings       Where games were first clicked, and how those games went
+  load                   List the games saved with `save as <name>`, newest first
+  load <N|NAME>          Resume a saved game, by its number in the list or its name
+  save delete <N|NAME>   Delete a saved game, once you confirm
+  config init            Write a commented default config file
+
+Options:
+  --size <N>             Square board of N x N cells (at least 1)
+  --width <N>            Board width
+  --height <N>           Board height
+  --mines <N>            Exact number of mines (fewer than the number of cells)
+  --density <P>          Chance (0 to 1) of each cell being a mine
+  --preset <NAME>        beginner (9x9, 10 mines), intermediate (16x16, 40 mines)
+                         or expert (16x30, 99 mines)
+  --seed <N>             Generate the board from this seed
+  --no-color             Don't colour the board
+  --peeks <N>            Allow N peeks at whether a cell is a mine
+  --shields              Earn shields against mines by opening 15+ cells at once
+  --flag-win             Also win by flagging every mine and nothing else
+  --minutes <N>          How long a gauntlet lasts
+  --symmetry <S>         Lay the mines out symmetrically: horizontal, vertical or rotational
+  --distribution <D>     Scatter the mines uniform, clustered or sparse
+  --stencil <FILE>       Place the mines more often where the picture in FILE has a #
+  --min-per-row <N>      Give every row at least N mines (also --max-per-row)
+  --min-per-column <N>   Give every column at least N mines (also --max-per-column)
+  --rocks <N>            Turn N of the safe cells into rocks
+  --moving-mines <K>     Every K moves, some of the mines move (the numbers don't)
+  --redraw               Keep the board in place and repaint only what changes
+  --difficulty           Rate how hard the board is before playing it
+  --debug-dump           Print a debug dump of the game, mines included, when it ends
+  --answer-key <FILE>    Write the board, mines and numbers, to FILE as each game starts,
+                         for showing afterwards; nothing of it is printed
+  --plain                Only lines of ASCII: no colour, emoji, banner, bell or clearing
+  --no-summary           No session summary after the last single-player game
+  --batch                Read one command per line from stdin, without prompts
+  --json                 With --batch, --serve or --broadcast, write one JSON object per
+                         line instead of text
+  --diff                 With --json, answer each move with only the cells it changed
+                         (see --help protocol)
+  --serve <PORT>         Serve games to one client at a time on localhost:PORT
+                         (with race, a race between two clients)
+  --broadcast <PORT>     Let others watch the game from localhost:PORT
+  --load <SAVEFILE>      Resume a saved game, e.g. a copy of the autosave
+  --import-mbf <FILE>    Play the board in an .mbf minefield file
+  --lang <CODE>          Language of the messages: en or es (defaults to $LANG)
+  -h, --help             Show this help; --help protocol for what --batch and
+                         --serve answer
+  --print-reference      Print a reference of the commands, in the manner of a man page
+
+Commands, at the prompt (`help` lists them there too):
+{0}
+
+Options given here take precedence over the config file.
+
+Exit status: 0 when the game is won, 1 when it is lost, 130 when it is
+abandoned (ctrl-c or the end of input), 2 for invalid options.";
+
+const HELP_ES: &str = "\
+Uso: minesweeper [MODO] [OPCIONES]
+     minesweeper config init
+
+Modos:
+  (ninguno)              Partida de un jugador
+  race                   Carrera por turnos de dos jugadores en el mismo tablero
+  coop                   Dos jugadores, un tablero y vidas compartidas
+  daily                  El reto diario de hoy
+  weekly                 El reto de esta semana, con su variación de las reglas
+  tutorial               Aprende las reglas en un tablero guiado
+  campaign               Juega el siguiente nivel de la campaña
+  gauntlet               Despeja tantos tableros pequeños como puedas en 5 minutos
+  endless                Abre todo lo que puedas de un tablero sin bordes
+  achievements           Muestra los logros, conseguidos o no
+  history                Muestra las últimas partidas; --last <N> (10 por defecto),
+                         --result won|lost|resigned, --no-resigned y
+                         --preset <NOMBRE> eligen cuáles
+  history weekly         Solo los retos semanales
+  history heatmap        Dónde se perdieron las partidas, sombreado según la frecuencia
+  history openings       Dónde fue el primer clic de cada partida, y cómo acabaron
+  load                   Lista las partidas guardadas con `save as <nombre>`, la más
+                         reciente primero
+  load <N|NOMBRE>        Reanuda una partida guardada, por su número en la lista o su nombre
+  save delete <N|NOMBRE> Borra una partida guardada, tras confirmarlo
+  config init            Escribe un archivo de configuración comentado
+
+Opciones:
+  --size <N>             Tablero cuadrado de N x N celdas (al menos 1)
+  --width <N>            Ancho del tablero
+  --height <N>           Alto del tablero
+  --mines <N>            Número exacto de minas (menos que el número de celdas)
+  --density <P>          Probabilidad (de 0 a 1) de que cada celda sea una mina
+  --preset <NOMBRE>      beginner (9x9, 10 minas), intermediate (16x16, 40 minas)
+                         o expert (16x30, 99 minas)
+  --seed <N>             Genera el tablero a partir de esta semilla
+  --no-color             No colorea el tablero
+  --peeks <N>            Permite N vistazos para saber si una celda es una mina
+  --shields              Gana escudos contra las minas abriendo 15+ celdas de golpe
+  --flag-win             Gana también marcando cada mina y nada más
+  --minutes <N>          Cuánto dura un gauntlet
+  --symmetry <S>         Coloca las minas con simetría: horizontal, vertical o rotational
+  --distribution <D>     Reparte las minas: uniform, clustered o sparse
+  --stencil <ARCHIVO>    Pon las minas más a menudo donde el dibujo de ARCHIVO tiene un #
+  --min-per-row <N>      Pon al menos N minas en cada fila (también --max-per-row)
+  --min-per-column <N>   Pon al menos N minas en cada columna (también --max-per-column)
+  --rocks <N>            Convierte N de las celdas seguras en rocas
+  --moving-mines <K>     Cada K jugadas, algunas minas se mueven (los números no)
+  --redraw               Mantiene el tablero en su sitio y solo repinta lo que cambia
+  --difficulty           Valora lo difícil que es el tablero antes de jugarlo
+  --debug-dump           Muestra un volcado de depuración, con las minas, al terminar
+  --answer-key <ARCHIVO> Escribe el tablero, con minas y números, en ARCHIVO al empezar
+                         cada partida, para mostrarlo después; no se imprime nada de él
+  --plain                Solo líneas en ASCII: sin color, emoji, pancarta, campana ni borrado
+  --no-summary           Sin resumen de la sesión tras la última partida individual
+  --batch                Lee una orden por línea de stdin, sin preguntas
+  --json                 Con --batch, --serve o --broadcast, escribe un objeto JSON por
+                         línea en vez de texto
+  --serve <PUERTO>       Sirve partidas a un cliente a la vez en localhost:PUERTO
+                         (con race, una carrera entre dos clientes)
+  --broadcast <PUERTO>   Deja que otros vean la partida desde localhost:PUERTO
+  --load <ARCHIVO>       Continúa una partida guardada, p. ej. una copia del autoguardado
+  --import-mbf <ARCHIVO> Juega el tablero de un archivo de minas .mbf
+  --lang <CÓDIGO>        Idioma de los mensajes: en o es (por defecto, $LANG)
+  -h, --help             Muestra esta ayuda
+  --print-reference      Imprime una referencia de las órdenes, a la manera de una página man
+
+Órdenes, en la partida (`help` también las muestra allí):
+{0}
+
+Las opciones dadas aquí tienen prioridad sobre el archivo de configuración.
+
+Código de salida: 0 si se gana la partida, 1 si se pierde, 130 si se abandona
+(ctrl-c o fin de la entrada), 2 si las opciones no son válidas.";
+
+const PROTOCOL_HELP_EN: &str = "\
+With --batch (and over --serve), each line read is a line of commands, as at
+the prompt; `full` on a line of its own asks for the whole board again. With
+--json every answer is one JSON object on a line:
+
+  a move      {\"command\": \"c 2 7\", \"state\": \"playing\", \"events\": [...],
+               \"board\": [\"# # 1 0 0\", ...]}, with \"opened\" and \"skipped\" for
+               a clear and \"change\" and \"mines_left\" for a flag
+  with --diff {\"command\": \"c 2 7\", \"state\": \"playing\",
+               \"changes\": [[0, 2, \"1\"], [0, 3, \"0\"], ...], \"counters\": {...}},
+               each cell the move changed as its row, its column and how it
+               looks now; a move that can change any cell (an undo, a rewind,
+               mines moving) has the whole \"board\" instead of \"changes\"
+  full        {\"state\": \"playing\", \"counters\": {...}, \"board\": [...]}
+  counters    {\"mines_left\": 9, \"flags\": 1, \"open\": 23, \"moves\": 2,
+               \"lives\": 1, \"shields\": 0, \"peeks\": 0}
+  an error    {\"error\": \"...\"}, after which the rest of the line is skipped; a
+               line that doesn't parse also has a \"code\" for what was wrong,
+               such as \"unknown_command\", \"not_a_number\", \"too_large\" or
+               \"out_of_range\"
+  the end     {\"outcome\": \"won\", \"moves\": 31, \"opened\": 71, \"elapsed_ms\": ...,
+               \"three_bv\": 18}; lost also has the \"hit\" cell, resigned the
+               \"progress\" percentage, and aborted has the outcome alone
+
+Each \"state\" is playing, won, lost or resigned. Cells are drawn as # closed, ^ flagged,
+? questioned, ~ peeked at and safe, ! peeked at and a mine, * a mine set off,
++ a mine defused, % a rock, and 0 to 8 open.";
+
+const PROTOCOL_HELP_ES: &str = "\
+Con --batch (y por --serve), cada línea leída es una línea de órdenes, como en
+la partida; `full` en una línea sola pide de nuevo el tablero entero. Con
+--json cada respuesta es un objeto JSON en una línea:
+
+  una jugada  {\"command\": \"c 2 7\", \"state\": \"playing\", \"events\": [...],
+               \"board\": [\"# # 1 0 0\", ...]}, con \"opened\" y \"skipped\" al
+               despejar y \"change\" y \"mines_left\" al marcar
+  con --diff  {\"command\": \"c 2 7\", \"state\": \"playing\",
+               \"changes\": [[0, 2, \"1\"], [0, 3, \"0\"], ...], \"counters\": {...}},
+               cada celda que cambió la jugada como su fila, su columna y cómo
+               se ve ahora; una jugada que puede cambiar cualquier celda
+               (deshacer, rebobinar, minas que se mueven) trae el \"board\"
+               entero en lugar de \"changes\"
+  full        {\"state\": \"playing\", \"counters\": {...}, \"board\": [...]}
+  counters    {\"mines_left\": 9, \"flags\": 1, \"open\": 23, \"moves\": 2,
+               \"lives\": 1, \"shields\": 0, \"peeks\": 0}
+  un error    {\"error\": \"...\"}, tras el que se salta el resto de la línea; una
+               línea que no se entiende trae además un \"code\" con lo que fallaba,
+               como \"unknown_command\", \"not_a_number\", \"too_large\" o
+               \"out_of_range\"
+  el final    {\"outcome\": \"won\", \"moves\": 31, \"opened\": 71, \"elapsed_ms\": ...,
+               \"three_bv\": 18}; lost tiene además la celda \"hit\", resigned el
+               porcentaje \"progress\", y aborted solo el resultado
+
+Cada \"state\" es playing, won, lost o resigned. Las celdas se dibujan como # cerrada,
+^ marcada, ? con interrogación, ~ espiada y segura, ! espiada y con mina,
+* una mina que estalló, + una mina desactivada, % una roca, y de 0 a 8 abierta.";
+
+const REFERENCE_EN: &str = "\
+NAME
+    minesweeper - minesweeper at the command line
+
+SYNOPSIS
+    minesweeper [MODE] [OPTIONS]
+
+DESCRIPTION
+    The aim is to open every safe cell of a board without setting off a mine.
+    When asked for a move, enter a row and then a column, or one of the
+    commands below.
+
+COMMANDS
+{0}
+
+SEE ALSO
+    minesweeper --help, for the modes and options;
+    minesweeper --help protocol, for what --batch and --serve answer";
+
+const REFERENCE_ES: &str = "\
+NOMBRE
+    minesweeper - buscaminas en la línea de órdenes
+
+SINOPSIS
+    minesweeper [MODO] [OPCIONES]
+
+DESCRIPCIÓN
+    El objetivo es abrir todas las celdas seguras de un tablero sin hacer
+    estallar una mina. Cuando pide una jugada, escribe una fila y luego una
+    columna, o una de las órdenes de abajo.
+
+ÓRDENES
+{0}
+
+VÉASE TAMBIÉN
+    minesweeper --help, para los modos y las opciones;
+    minesweeper --help protocol, para lo que responden --batch y --serve";
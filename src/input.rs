@@ -0,0 +1,325 @@
+use crate::config::Config;
+use crate::driver::GameInput;
+use crate::i18n::{text, Msg};
+use std::io::{self, stdin, ErrorKind, IsTerminal};
+
+// plain lines from stdin, for when it isn't a terminal
+pub struct StdinInput;
+
+impl GameInput for StdinInput {
+    fn read_command(&mut self) -> io::Result<Option<String>> {
+        let mut line = String::new();
+        if stdin().read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(line.trim_end_matches(['\n', '\r']).to_string()))
+    }
+}
+
+fn cancelled() -> io::Error {
+    io::Error::new(ErrorKind::Interrupted, text(Msg::LineCancelled))
+}
+
+// the terminal's settings, put back when dropped so that nothing after the
+// prompt (or a panic in the middle of it) is left in raw mode
+#[cfg(unix)]
+struct RawMode(libc::termios);
+
+#[cfg(unix)]
+impl RawMode {
+    fn enable() -> io::Result<RawMode> {
+        use std::mem;
+
+        // SAFETY: tcgetattr fills in the zeroed struct, and both calls only
+        // read or write the struct passed to them
+        unsafe {
+            let mut original: libc::termios = mem::zeroed();
+            if libc::tcgetattr(libc::STDIN_FILENO, &mut original) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            // byte at a time, without echo, and with ctrl-c arriving as a
+            // key rather than a signal
+            let mut raw = original;
+            raw.c_lflag &= !(libc::ICANON | libc::ECHO | libc::ISIG);
+            raw.c_cc[libc::VMIN] = 1;
+            raw.c_cc[libc::VTIME] = 0;
+            if libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            Ok(RawMode(original))
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        // SAFETY: the struct is the one tcgetattr filled in
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.0);
+        }
+    }
+}
+
+enum Key {
+    Char(char),
+    Enter,
+    Backspace,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    Cancel,
+    EndOfInput,
+    Other,
+}
+
+fn read_byte(input: &mut impl io::Read) -> io::Result<Option<u8>> {
+    let mut byte = [0];
+    match input.read(&mut byte)? {
+        0 => Ok(None),
+        _ => Ok(Some(byte[0])),
+    }
+}
+
+// one keypress, decoding escape sequences and multi-byte characters
+fn read_key(input: &mut impl io::Read) -> io::Result<Option<Key>> {
+    let Some(first) = read_byte(input)? else {
+        return Ok(None);
+    };
+
+    let key = match first {
+        b'\r' | b'\n' => Key::Enter,
+        0x7f | 0x08 => Key::Backspace,
+        0x03 => Key::Cancel,
+        0x04 => Key::EndOfInput,
+        0x01 => Key::Home,
+        0x05 => Key::End,
+        0x1b => match (read_byte(input)?, read_byte(input)?) {
+            (Some(b'[' | b'O'), Some(b'A')) => Key::Up,
+            (Some(b'[' | b'O'), Some(b'B')) => Key::Down,
+            (Some(b'[' | b'O'), Some(b'C')) => Key::Right,
+            (Some(b'[' | b'O'), Some(b'D')) => Key::Left,
+            (Some(b'[' | b'O'), Some(b'H')) => Key::Home,
+            (Some(b'[' | b'O'), Some(b'F')) => Key::End,
+            // the rest of a sequence like delete's "\x1b[3~"
+            (Some(b'['), Some(b'0'..=b'9')) => {
+                while !matches!(read_byte(input)?, Some(b'~') | None) {}
+                Key::Other
+            }
+            _ => Key::Other,
+        },
+        byte if byte < 0x20 => Key::Other,
+        byte => {
+            // the first byte of a UTF-8 character says how many follow
+            let length = match byte.leading_ones() {
+                0 => 1,
+                count @ 2..=4 => count as usize,
+                _ => return Ok(Some(Key::Other)),
+            };
+            let mut bytes = vec![byte];
+            for _ in 1..length {
+                match read_byte(input)? {
+                    Some(next) => bytes.push(next),
+                    None => return Ok(None),
+                }
+            }
+            match std::str::from_utf8(&bytes)
+                .ok()
+                .and_then(|s| s.chars().next())
+            {
+                Some(character) => Key::Char(character),
+                None => Key::Other,
+            }
+        }
+    };
+
+    Ok(Some(key))
+}
+
+// readline-style input for a terminal: left and right move through the
+// line, up and down recall earlier lines from this session, and ctrl-c
+// drops the line instead of ending the game
+#[derive(Default)]
+pub struct LineEditor {
+    history: Vec<String>,
+}
+
+impl LineEditor {
+    fn redraw(line: &[char], cursor: usize) {
+        use std::io::Write;
+
+        let mut out = format!("\r\x1b[K{}", line.iter().collect::<String>());
+        if cursor < line.len() {
+            out += &format!("\x1b[{}D", line.len() - cursor);
+        }
+        print!("{out}");
+        let _ = io::stdout().flush();
+    }
+
+    fn edit(&mut self, input: &mut impl io::Read) -> io::Result<Option<String>> {
+        let mut line: Vec<char> = vec![];
+        let mut cursor = 0;
+        // how far back in the history the line is, with the line being
+        // typed kept aside while browsing
+        let mut recalled = 0;
+        let mut draft: Vec<char> = vec![];
+
+        loop {
+            let Some(key) = read_key(input)? else {
+                return Ok(None);
+            };
+
+            match key {
+                Key::Char(character) => {
+                    line.insert(cursor, character);
+                    cursor += 1;
+                }
+                Key::Backspace if cursor > 0 => {
+                    cursor -= 1;
+                    line.remove(cursor);
+                }
+                Key::Left if cursor > 0 => cursor -= 1,
+                Key::Right if cursor < line.len() => cursor += 1,
+                Key::Home => cursor = 0,
+                Key::End => cursor = line.len(),
+                Key::Up if recalled < self.history.len() => {
+                    if recalled == 0 {
+                        draft = line;
+                    }
+                    recalled += 1;
+                    line = self.history[self.history.len() - recalled]
+                        .chars()
+                        .collect();
+                    cursor = line.len();
+                }
+                Key::Down if recalled > 0 => {
+                    recalled -= 1;
+                    line = match recalled {
+                        0 => std::mem::take(&mut draft),
+                        _ => self.history[self.history.len() - recalled]
+                            .chars()
+                            .collect(),
+                    };
+                    cursor = line.len();
+                }
+                Key::Enter => {
+                    println!();
+                    let line: String = line.into_iter().collect();
+                    if !line.trim().is_empty() && self.history.last() != Some(&line) {
+                        self.history.push(line.clone());
+                    }
+                    return Ok(Some(line));
+                }
+                Key::Cancel => {
+                    println!();
+                    return Err(cancelled());
+                }
+                Key::EndOfInput if line.is_empty() => {
+                    println!();
+                    return Ok(None);
+                }
+                _ => continue,
+            }
+
+            LineEditor::redraw(&line, cursor);
+        }
+    }
+}
+
+impl GameInput for LineEditor {
+    #[cfg(unix)]
+    fn read_command(&mut self) -> io::Result<Option<String>> {
+        let _raw = RawMode::enable()?;
+        self.edit(&mut stdin().lock())
+    }
+
+    #[cfg(not(unix))]
+    fn read_command(&mut self) -> io::Result<Option<String>> {
+        StdinInput.read_command()
+    }
+}
+
+// how many more tries a prompt gives input that isn't being typed: a script
+// that keeps answering wrong has already gone wrong, and asking on would
+// read the rest of it as answers
+const PIPED_RETRIES: u32 = 3;
+
+// the retry limit for prompts: none at a terminal, where a person can
+// always try again
+pub fn piped_retries() -> Option<u32> {
+    (!stdin().is_terminal()).then_some(PIPED_RETRIES)
+}
+
+// line editing when a person is typing, plain lines when stdin is a pipe
+// or the output can't be redrawn in place
+pub fn stdin_input(config: &Config) -> Box<dyn GameInput> {
+    if stdin().is_terminal() && config.output.clear {
+        Box::new(LineEditor::default())
+    } else {
+        Box::new(StdinInput)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LineEditor;
+    use std::io::ErrorKind;
+
+    const UP: &str = "\x1b[A";
+    const DOWN: &str = "\x1b[B";
+    const LEFT: &str = "\x1b[D";
+
+    // each line the keys make, in turn, until they run out
+    fn lines(editor: &mut LineEditor, keys: &str) -> Vec<Option<String>> {
+        let mut input = keys.as_bytes();
+        let mut lines = vec![];
+        while !input.is_empty() {
+            lines.push(editor.edit(&mut input).expect("The keys should be read"));
+        }
+        lines
+    }
+
+    #[test]
+    fn up_and_down_recall_earlier_lines() {
+        let mut editor = LineEditor::default();
+        let keys = format!("c 1 1\nf 0 0\n{UP}{UP}\n{UP}{UP}{DOWN}\nc 2{UP}{DOWN}\n");
+
+        let lines = lines(&mut editor, &keys);
+        let lines: Vec<&str> = lines.iter().flatten().map(String::as_str).collect();
+        // the line being typed comes back after browsing
+        assert_eq!(lines, ["c 1 1", "f 0 0", "c 1 1", "c 1 1", "c 2"]);
+        // a repeat of the last line isn't kept twice
+        assert_eq!(editor.history, ["c 1 1", "f 0 0", "c 1 1", "c 2"]);
+    }
+
+    #[test]
+    fn left_and_backspace_edit_within_the_line() {
+        let mut editor = LineEditor::default();
+        let keys = format!("c 12{LEFT}{LEFT}{LEFT} 3\x7f2\n");
+
+        assert_eq!(lines(&mut editor, &keys), [Some(String::from("c 2 12"))]);
+    }
+
+    #[test]
+    fn ctrl_c_drops_the_line_and_ctrl_d_ends_the_input() {
+        let mut editor = LineEditor::default();
+        let mut keys = "c 1\x03".as_bytes();
+        let e = editor
+            .edit(&mut keys)
+            .expect_err("The line should be dropped");
+        assert_eq!(e.kind(), ErrorKind::Interrupted);
+        assert!(editor.history.is_empty());
+
+        // only on an empty line
+        assert_eq!(
+            lines(&mut editor, "c\x04 1 1\n\x04"),
+            [Some(String::from("c 1 1")), None]
+        );
+    }
+}
@@ -0,0 +1,306 @@
+use crate::i18n::{fill, Msg};
+use std::fmt::{Display, Write};
+
+// just enough JSON to write machine-readable output and read back what was
+// written, without a dependency
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    // keeps the keys in the order they were added
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub fn object<const N: usize>(fields: [(&str, Value); N]) -> Value {
+        Value::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        )
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(fields) => fields
+                .iter()
+                .find(|(field, _)| field == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    // only for whole numbers that fit
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_f64()
+            .filter(|value| value.fract() == 0.0 && (0.0..=u64::MAX as f64).contains(value))
+            .map(|value| value as u64)
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum JsonError {
+    // what was expected, and the byte offset it was expected at
+    Expected(&'static str, usize),
+    TrailingCharacters(usize),
+}
+
+impl Display for JsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            JsonError::Expected(expected, at) => {
+                write!(f, "{}", fill(Msg::JsonExpected, &[expected, at]))
+            }
+            JsonError::TrailingCharacters(at) => {
+                write!(f, "{}", fill(Msg::JsonTrailing, &[at]))
+            }
+        }
+    }
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    at: usize,
+}
+
+impl Parser<'_> {
+    fn rest(&self) -> &str {
+        &self.text[self.at..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.at += rest.len() - rest.trim_start().len();
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        let found = self.rest().starts_with(token);
+        if found {
+            self.at += token.len();
+        }
+        found
+    }
+
+    fn expect(&mut self, token: &'static str) -> Result<(), JsonError> {
+        match self.eat(token) {
+            true => Ok(()),
+            false => Err(JsonError::Expected(token, self.at)),
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, JsonError> {
+        self.skip_whitespace();
+        match self.rest().chars().next() {
+            Some('{') => self.object(),
+            Some('[') => self.array(),
+            Some('"') => self.string().map(Value::String),
+            _ if self.eat("true") => Ok(Value::Bool(true)),
+            _ if self.eat("false") => Ok(Value::Bool(false)),
+            _ => self.number(),
+        }
+    }
+
+    fn object(&mut self) -> Result<Value, JsonError> {
+        self.expect("{")?;
+        let mut fields = vec![];
+        if self.eat("}") {
+            return Ok(Value::Object(fields));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.expect(":")?;
+            fields.push((key, self.value()?));
+            if !self.eat(",") {
+                self.expect("}")?;
+                return Ok(Value::Object(fields));
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, JsonError> {
+        self.expect("[")?;
+        let mut values = vec![];
+        if self.eat("]") {
+            return Ok(Value::Array(values));
+        }
+        loop {
+            values.push(self.value()?);
+            if !self.eat(",") {
+                self.expect("]")?;
+                return Ok(Value::Array(values));
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, JsonError> {
+        let start = self.at;
+        let expected = JsonError::Expected("a string", start);
+        if !self.rest().starts_with('"') {
+            return Err(expected);
+        }
+
+        let mut out = String::new();
+        let mut characters = self.rest()[1..].char_indices();
+        while let Some((index, character)) = characters.next() {
+            match character {
+                '"' => {
+                    self.at += index + 2;
+                    return Ok(out);
+                }
+                '\\' => {
+                    let escaped = match characters.next() {
+                        Some((_, 'n')) => '\n',
+                        Some((_, 'r')) => '\r',
+                        Some((_, 't')) => '\t',
+                        Some((_, 'u')) => {
+                            let hex: String = characters.by_ref().take(4).map(|(_, c)| c).collect();
+                            u32::from_str_radix(&hex, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .ok_or(JsonError::Expected("a unicode escape", start))?
+                        }
+                        Some((_, other)) => other,
+                        None => return Err(expected),
+                    };
+                    out.push(escaped);
+                }
+                other => out.push(other),
+            }
+        }
+        Err(expected)
+    }
+
+    fn number(&mut self) -> Result<Value, JsonError> {
+        let rest = self.rest();
+        let length = rest
+            .find(|c: char| !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')))
+            .unwrap_or(rest.len());
+        let number = rest[..length]
+            .parse()
+            .map_err(|_| JsonError::Expected("a value", self.at))?;
+        self.at += length;
+        Ok(Value::Number(number))
+    }
+}
+
+// a single value, with nothing but whitespace after it
+pub fn parse(text: &str) -> Result<Value, JsonError> {
+    let mut parser = Parser { text, at: 0 };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    match parser.at == text.len() {
+        true => Ok(value),
+        false => Err(JsonError::TrailingCharacters(parser.at)),
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Number(value)
+    }
+}
+
+impl From<usize> for Value {
+    fn from(value: usize) -> Self {
+        Value::Number(value as f64)
+    }
+}
+
+impl From<u32> for Value {
+    fn from(value: u32) -> Self {
+        Value::Number(f64::from(value))
+    }
+}
+
+impl From<u64> for Value {
+    fn from(value: u64) -> Self {
+        Value::Number(value as f64)
+    }
+}
+
+fn write_string(f: &mut std::fmt::Formatter, value: &str) -> std::fmt::Result {
+    f.write_char('"')?;
+    for character in value.chars() {
+        match character {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            control if control.is_control() => write!(f, "\\u{:04x}", control as u32)?,
+            other => f.write_char(other)?,
+        }
+    }
+    f.write_char('"')
+}
+
+// compact, on one line, so that each value can be a line of output
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Value::Bool(value) => write!(f, "{value}"),
+            // JSON has no NaN or infinity
+            Value::Number(value) if !value.is_finite() => f.write_str("null"),
+            Value::Number(value) => write!(f, "{value}"),
+            Value::String(value) => write_string(f, value),
+            Value::Array(values) => {
+                f.write_char('[')?;
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{value}")?;
+                }
+                f.write_char(']')
+            }
+            Value::Object(fields) => {
+                f.write_char('{')?;
+                for (index, (key, value)) in fields.iter().enumerate() {
+                    if index > 0 {
+                        f.write_char(',')?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{value}")?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
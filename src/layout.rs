@@ -0,0 +1,141 @@
+// lining a board's cells up in columns, whatever they are drawn with: every
+// cell is padded to the widest glyph that could be in its place, so that a
+// row is as wide as any other row of the same board
+
+// every symbol a cell can be written as in plain text, by `Cell`, `CellView`
+// and the endless board alike
+const TEXT_GLYPHS: [&str; 19] = [
+    "#", "^", "?", "!", "~", "*", "+", "%", "@", "E", "0", "1", "2", "3", "4", "5", "6", "7", "8",
+];
+
+// the characters a terminal draws two columns wide: the East Asian wide and
+// fullwidth blocks and the emoji, by the ranges that are wide in Unicode 15
+const WIDE: [(u32, u32); 26] = [
+    (0x1100, 0x115F),
+    (0x231A, 0x231B),
+    (0x23E9, 0x23EC),
+    (0x23F0, 0x23F3),
+    (0x25FD, 0x25FE),
+    (0x2614, 0x2615),
+    (0x2648, 0x2653),
+    (0x26AA, 0x26AB),
+    (0x26BD, 0x26BE),
+    (0x26F5, 0x26FA),
+    (0x2705, 0x2705),
+    (0x270A, 0x270B),
+    (0x274C, 0x274C),
+    (0x2753, 0x2755),
+    (0x2795, 0x2797),
+    (0x2B1B, 0x2B1C),
+    (0x2B50, 0x2B55),
+    (0x2E80, 0x303E),
+    (0x3041, 0xA4CF),
+    (0xAC00, 0xD7A3),
+    (0xF900, 0xFAFF),
+    (0xFE30, 0xFE4F),
+    (0xFF00, 0xFF60),
+    (0xFFE0, 0xFFE6),
+    (0x1F300, 0x1F64F),
+    (0x1F680, 0x1FAFF),
+];
+
+// the characters drawn over the one before them, taking no column of their
+// own: combining marks, zero-width joiners and spaces, and the variation
+// selectors that pick an emoji's style
+const ZERO_WIDTH: [(u32, u32); 6] = [
+    (0x0300, 0x036F),
+    (0x200B, 0x200F),
+    (0x20D0, 0x20FF),
+    (0xFE00, 0xFE0F),
+    (0xFEFF, 0xFEFF),
+    (0xE0100, 0xE01EF),
+];
+
+fn within(c: char, ranges: &[(u32, u32)]) -> bool {
+    let c = u32::from(c);
+    ranges
+        .iter()
+        .any(|&(first, last)| (first..=last).contains(&c))
+}
+
+fn char_width(c: char) -> usize {
+    if c.is_control() || within(c, &ZERO_WIDTH) {
+        0
+    } else if within(c, &WIDE) {
+        2
+    } else {
+        1
+    }
+}
+
+// how many columns `text` takes up at a terminal, with any colour escapes
+// (ESC [ ... m) taking none
+pub fn display_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1B' {
+            chars.by_ref().find(|&c| c == 'm');
+        } else {
+            width += char_width(c);
+        }
+    }
+    width
+}
+
+// how the cells of a board are laid out across a line
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CellLayout {
+    // columns per cell, before the gap
+    cell: usize,
+    // columns of space between one cell and the next
+    gap: usize,
+}
+
+impl CellLayout {
+    // cells as wide as the widest of `glyphs`, `gap` columns apart
+    pub fn fit<'a>(glyphs: impl IntoIterator<Item = &'a str>, gap: usize) -> Self {
+        let cell = glyphs.into_iter().map(display_width).max().unwrap_or(1);
+        CellLayout { cell, gap }
+    }
+
+    // the one plain text uses
+    pub fn text() -> Self {
+        CellLayout::fit(TEXT_GLYPHS, 1)
+    }
+
+    pub fn cell_width(&self) -> usize {
+        self.cell
+    }
+
+    // columns from where one cell starts to where the next does
+    pub fn pitch(&self) -> usize {
+        self.cell + self.gap
+    }
+
+    // the column the cell `col_index` cells in starts at, from 0
+    pub fn column(&self, col_index: usize) -> usize {
+        col_index * self.pitch()
+    }
+
+    // how wide a row of `cells` cells is
+    pub fn row_width(&self, cells: usize) -> usize {
+        (cells * self.pitch()).saturating_sub(self.gap)
+    }
+
+    // the cell padded out to the cell width with spaces after it
+    pub fn pad(&self, cell: &str) -> String {
+        let padding = self.cell.saturating_sub(display_width(cell));
+        format!("{cell}{}", " ".repeat(padding))
+    }
+
+    // the cells of a row, each padded, with the gap between them
+    pub fn row<S: AsRef<str>>(&self, cells: impl IntoIterator<Item = S>) -> String {
+        let gap = " ".repeat(self.gap);
+        cells
+            .into_iter()
+            .map(|cell| self.pad(cell.as_ref()))
+            .collect::<Vec<String>>()
+            .join(&gap)
+    }
+}
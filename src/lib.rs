@@ -0,0 +1,34 @@
+// the game itself: boards, the rules, the solver and the events moves
+// cause. Nothing here reads the terminal, files, the network or the OS's
+// randomness, so that it builds for wasm32-unknown-unknown; all of that is
+// in the binary
+
+#[cfg(all(feature = "clock", target_arch = "wasm32", target_os = "unknown"))]
+compile_error!(
+    "the `clock` feature reads the OS's clock, which wasm32-unknown-unknown doesn't have"
+);
+
+pub mod analysis;
+pub mod board;
+pub mod calendar;
+pub mod command;
+pub mod driver;
+pub mod dump;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod game;
+pub mod ghost;
+pub mod grid;
+pub mod i18n;
+pub mod json;
+pub mod layout;
+pub mod odds;
+pub mod reference;
+pub mod rules;
+pub mod solver;
+pub mod timer;
+pub mod topology;
+pub mod view;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod weekly;
@@ -1,7 +1,31 @@
-use rand::distributions::WeightedIndex;
-use rand::prelude::Distribution;
-use rand::thread_rng;
-use std::{fmt::Display, io::stdin};
+// the core of the game is the library; these are the names the rest of
+// the binary knows its modules by
+use minesweeper::{
+    analysis, board, calendar, command, driver, dump, game, ghost, i18n, json, layout, odds,
+    reference, rules, solver, timer, topology, view, weekly,
+};
+
+use board::{MineDistribution, Mines, Stencil};
+use broadcast::{Broadcast, Broadcasting};
+use cli::{env_lang, parse_args, Action, ModeArg};
+use config::Config;
+use driver::{Degenerate, GameOutput};
+use game::{Game, GameOutcome};
+use i18n::{fill, text, Msg};
+use output::{OutputCapabilities, StdoutOutput};
+use rand::random;
+use reference::{render_commands, render_reference};
+use run::{run_game, GameOptions};
+use save::{deserialize, Mode, SavedGame};
+use screen::RedrawOutput;
+use session::Summary;
+use stats::StatsFile;
+use std::fs;
+use std::io::stdin;
+use std::path::Path;
+use std::process::exit;
+use std::sync::Arc;
+use timer::SystemClock;
 
 macro_rules! unwrap_or_continue {
     ($fallible:expr) => {
@@ -13,316 +37,450 @@ macro_rules! unwrap_or_continue {
             }
         }
     };
+    // shows the error through a GameOutput instead
+    ($fallible:expr, $output:expr) => {
+        match $fallible {
+            Ok(success) => success,
+            Err(e) => {
+                $output.show(&$crate::driver::Screen::Message(e.to_string()));
+                continue;
+            }
+        }
+    };
 }
 
-#[derive(Debug, Clone)]
-enum CellState {
-    Bomb { flagged: bool },
-    Safe { flagged: bool, open: bool },
-}
-
-// WARNING: there are no checks to ensure this has valid indeces;
-// it is only intended as a convenient abstraction
-#[derive(Debug, Clone, Copy)]
-struct CellPosition {
-    row_index: usize,
-    col_index: usize,
+mod achievements;
+mod alert;
+mod answer_key;
+mod autosave;
+mod banner;
+mod batch;
+mod broadcast;
+mod campaign;
+mod cast;
+mod cli;
+mod config;
+mod coop;
+mod daily;
+mod dirs;
+mod endless;
+mod gauntlet;
+mod history;
+mod input;
+mod mbf;
+mod netrace;
+mod output;
+mod postgame;
+mod queries;
+mod race;
+mod render;
+mod replay;
+mod run;
+mod save;
+mod saves;
+mod screen;
+mod serve;
+mod session;
+mod share;
+mod stats;
+mod transcript;
+mod tutorial;
+mod versions;
+
+fn new_single(config: &Config, seed: Option<u64>) -> (String, Game) {
+    let seed = seed.unwrap_or_else(random);
+    (
+        format!("#{seed}"),
+        Game::from_ruleset(
+            &config
+                .ruleset()
+                .expect("The config should be checked before a game starts"),
+            seed,
+        ),
+    )
 }
 
-impl PartialEq for CellPosition {
-    fn eq(&self, other: &Self) -> bool {
-        self.row_index == other.row_index && self.col_index == other.col_index
+// `requested` is what a new board was generated from; a saved one could
+// have come from anything
+fn play(
+    mode: Mode,
+    code: String,
+    game: Game,
+    config: &Config,
+    requested: Option<Mines>,
+    broadcast: Option<&Broadcast>,
+    answer_key: Option<&Path>,
+) -> GameOutcome {
+    // as `driver::run_game` does, before the game below is taken as won
+    if let Some(degenerate @ Degenerate::NoSafeCells) = Degenerate::of(&game) {
+        fail(degenerate);
     }
-
-    fn ne(&self, other: &Self) -> bool {
-        !self.eq(other)
+    // a loaded save can already be finished, leaving nothing to play
+    if let Some(outcome) = game.outcome() {
+        return outcome;
+    }
+    if let Some(path) = answer_key {
+        answer_key::write_answer_key(&game, &code, config, path)
+            .unwrap_or_else(|e| fail(fill(Msg::CouldntWriteAnswerKey, &[&path.display(), &e])));
+        println!("{}", fill(Msg::AnswerKeyWritten, &[&path.display()]));
     }
-}
-
-#[derive(Debug, Clone)]
-struct Cell {
-    bombs_around: u8,
-    state: CellState,
-    position: CellPosition,
-}
 
-impl Cell {
-    fn new<const N: usize>(
-        row_index: usize,
-        col_index: usize,
-        bombs: &[[bool; N]; N],
-        is_bomb: bool,
-    ) -> Self {
-        let position = CellPosition {
-            row_index,
-            col_index,
-        };
+    let game = match config.ghost {
+        true => with_ghost(game, &code),
+        false => game,
+    };
 
-        Cell {
-            bombs_around: get_bombs_around(bombs, position),
-            state: if is_bomb {
-                CellState::Bomb { flagged: false }
+    let mut input = input::stdin_input(config);
+    match mode {
+        Mode::Single => {
+            let mut output: Box<dyn GameOutput> = if config.redraw && config.output.clear {
+                Box::new(RedrawOutput::new(config))
             } else {
-                CellState::Safe {
-                    flagged: false,
-                    open: false,
-                }
-            },
-            position,
+                Box::new(StdoutOutput { config })
+            };
+            let mut output: Box<dyn GameOutput + '_> = match broadcast {
+                Some(broadcast) => Box::new(Broadcasting {
+                    output: &mut *output,
+                    broadcast,
+                    config,
+                }),
+                None => output,
+            };
+            let options = GameOptions {
+                game,
+                code,
+                autosave: true,
+                stats: true,
+                idle_limit: config.idle_limit(),
+                requested,
+                config,
+            };
+            run_game(&mut *input, &mut *output, options)
         }
+        Mode::Coop => coop::play(game, code, config, &mut *input, requested),
+        Mode::Daily | Mode::Weekly => daily::play(mode, game, code, config, &mut *input, requested),
     }
 }
 
-impl Display for Cell {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let out = match self.state {
-            CellState::Bomb { flagged: true } => "^",
-            CellState::Bomb { flagged: false } => "@",
-            CellState::Safe {
-                flagged: true,
-                open: true,
-            } => panic!("Cell should not be both flagged and open"),
-            CellState::Safe {
-                flagged: true,
-                open: false,
-            } => "?",
-            CellState::Safe {
-                flagged: false,
-                open: true,
-            } => return write!(f, "{}", self.bombs_around),
-            CellState::Safe {
-                flagged: false,
-                open: false,
-            } => "#",
-        };
-
-        write!(f, "{out}")
+// a history that can't be read just leaves the game without its ghost
+fn with_ghost(game: Game, code: &str) -> Game {
+    let records = history::load().unwrap_or_default();
+    match history::best_run(&records, code, &game) {
+        Some(ghost) => game.with_ghost(ghost),
+        None => game,
     }
 }
 
-impl PartialEq for Cell {
-    fn eq(&self, other: &Self) -> bool {
-        self.position == other.position
+// asks first, as a deleted save can't be had back
+fn delete_save(save: &str) {
+    let entries = saves::list().unwrap_or_else(|e| fail(e));
+    let entry = saves::find(&entries, save)
+        .unwrap_or_else(|| fail(saves::SlotError::NotFound(save.to_string())));
+
+    let size = format!("{}x{}", entry.height, entry.width);
+    println!(
+        "{}",
+        fill(
+            Msg::ConfirmDeleteSave,
+            &[&entry.name, &size, &entry.progress]
+        )
+    );
+    let mut answer = String::new();
+    if stdin().read_line(&mut answer).is_err() || !driver::is_yes(&answer) {
+        println!("{}", text(Msg::SaveKept));
+        return;
     }
-    fn ne(&self, other: &Self) -> bool {
-        !self.eq(other)
-    }
-}
 
-#[derive(Debug)]
-enum ClearError {
-    ClearedBomb,
-    CellNotFound,
-    AlreadyCleared,
+    saves::delete(entry).unwrap_or_else(|e| fail(e));
+    println!("{}", fill(Msg::SaveDeleted, &[&entry.name]));
 }
 
-#[derive(Clone)]
-struct Board<const N: usize> {
-    board: [[Cell; N]; N],
+fn fail(message: impl std::fmt::Display) -> ! {
+    eprintln!("{message}");
+    exit(2)
 }
 
-impl<const N: usize> Board<N> {
-    fn new() -> Self {
-        let bombs: [[bool; N]; N] = generate_bombs();
-
-        let cells: [[Cell; N]; N] = bombs
-            .iter()
-            .enumerate()
-            .map(|(row_index, row)| {
-                row.iter()
-                    .enumerate()
-                    .map(|(col_index, &is_bomb)| Cell::new(row_index, col_index, &bombs, is_bomb))
-                    .collect::<Vec<Cell>>()
-                    .try_into()
-                    .expect("Vector of cells should have the correct length")
-            })
-            .collect::<Vec<[Cell; N]>>()
-            .try_into()
-            .expect("Vector of vector of cells should have the correct length");
-
-        Board { board: cells }
-    }
-
-    fn get_cell_mut(&mut self, position: CellPosition) -> Option<&mut Cell> {
-        self.board
-            .get_mut(position.row_index)
-            .and_then(|row| row.get_mut(position.col_index))
-    }
-
-    fn is_won(&self) -> bool {
-        let cells = self.board.concat();
-        // check if there is any cell that is closed and safe
-        !cells.iter().any(|cell| match cell.state {
-            CellState::Safe { open: false, .. } => true,
-            _ => false,
-        })
+fn main() {
+    // the environment's language until the command line says otherwise, so
+    // that errors in the arguments themselves are still localized
+    i18n::set_lang(env_lang());
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let cli = parse_args(&args).unwrap_or_else(|e| fail(e));
+    if let Some(lang) = cli.lang {
+        i18n::set_lang(lang);
     }
 
-    fn clear(
-        &mut self,
-        position: CellPosition,
-        traversed: &Vec<CellPosition>,
-    ) -> Result<(), ClearError> {
-        let board_before_mutation = self.board.clone();
-
-        if traversed.contains(&position) {
-            return Ok(());
+    let mode = match cli.action {
+        Action::Help => {
+            println!("{}", fill(Msg::Help, &[&render_commands()]));
+            return;
         }
-
-        let cell = self
-            .get_cell_mut(position)
-            .ok_or(ClearError::CellNotFound)?;
-
-        match cell.state {
-            CellState::Bomb { .. } => return Err(ClearError::ClearedBomb),
-            CellState::Safe { open: true, .. } => return Err(ClearError::AlreadyCleared),
-            CellState::Safe { open: false, .. } => {
-                cell.state = CellState::Safe {
-                    open: true,
-                    flagged: false,
+        Action::PrintReference => {
+            println!("{}", render_reference());
+            return;
+        }
+        Action::ProtocolHelp => {
+            println!("{}", text(Msg::ProtocolHelp));
+            return;
+        }
+        Action::ConfigInit => {
+            match Config::init() {
+                Ok(path) => println!("{}", fill(Msg::WroteConfig, &[&path.display()])),
+                Err(e) => fail(e),
+            }
+            return;
+        }
+        Action::Achievements => {
+            println!("{}", achievements::list(&StatsFile::load()));
+            return;
+        }
+        Action::History(query) => {
+            match history::load() {
+                Ok(records) if query.heatmap => {
+                    println!("{}", history::render_heatmap(&records, &query))
+                }
+                Ok(records) if query.openings => {
+                    println!("{}", history::render_openings(&records, &query))
                 }
+                Ok(records) => println!("{}", history::render_history(&records, &query)),
+                Err(e) => fail(fill(Msg::CouldntReadHistory, &[&e])),
             }
-        };
-
-        if cell.bombs_around == 0 {
-            let new_traversed = [&traversed[..], &[cell.position]].concat();
-
-            for cell_around in get_cells_around(&board_before_mutation, position) {
-                self.clear(cell_around.position, &new_traversed)
-                    .unwrap_or_else(|err| match err {
-                        ClearError::CellNotFound => {
-                            panic!("get_cells_around should return only valid cells")
-                        }
-                        ClearError::ClearedBomb => {
-                            panic!("Cell with bombs_around==0 should have no bombs around it")
-                        }
-                        ClearError::AlreadyCleared => (),
-                    });
+            return;
+        }
+        Action::ListSaves => {
+            match saves::list() {
+                Ok(entries) => println!("{}", saves::render_saves(&entries)),
+                Err(e) => fail(e),
             }
+            return;
+        }
+        Action::DeleteSave(save) => {
+            delete_save(&save);
+            return;
         }
+        Action::Play(mode) => mode,
+    };
 
-        Ok(())
+    // built-in defaults, then the config file, then the command line
+    let mut config = Config::load().unwrap_or_else(|e| fail(e));
+    for (key, value) in &cli.settings {
+        if let Err(config::SetError::InvalidValue(expected)) = config.set(key, value) {
+            fail(fill(Msg::OptionMustBe, &[key, &text(expected)]));
+        }
     }
-}
-
-impl<const N: usize> Display for Board<N> {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        let out = self
-            .board
-            .clone()
-            .map(|row| row.map(|cell| cell.to_string()).join(" "))
-            .join("\n");
-        write!(f, "{out}")
+    if let Some(path) = &cli.stencil {
+        let stencil = fs::read_to_string(path)
+            .unwrap_or_else(|e| fail(fill(Msg::CouldntReadStencil, &[&path.display(), &e])));
+        config.distribution = MineDistribution::Stencil(Stencil::parse(&stencil));
     }
-}
-
-fn get_cells_around<T, const N: usize>(
-    board: &[[T; N]; N],
-    position: CellPosition,
-) -> impl Iterator<Item = &T> {
-    let CellPosition {
-        row_index,
-        col_index,
-    } = position;
-
-    let min_row_index = if row_index > 0 { row_index - 1 } else { 0 };
-    let max_row_index = if row_index < N - 1 {
-        row_index + 1
-    } else {
-        N - 1
-    };
-
-    let min_col_index = if col_index > 0 { col_index - 1 } else { 0 };
-    let max_col_index = if col_index < N - 1 {
-        col_index + 1
-    } else {
-        N - 1
-    };
-
-    board
-        .get(min_row_index..max_row_index + 1)
-        .expect("Hardcoded row bounds checks should succeed")
-        .iter()
-        .map(move |row| {
-            row.get(min_col_index..max_col_index + 1)
-                .expect("Hardcoded col bounds checks should succeed")
-        })
-        .flatten()
-}
-
-fn get_bombs_around<const N: usize>(board: &[[bool; N]; N], position: CellPosition) -> u8 {
-    let cells_around = get_cells_around(board, position);
-    let num_bombs_around = cells_around.filter(|&&is_bomb| is_bomb).count();
-    num_bombs_around as u8
-}
+    config.validate().unwrap_or_else(|e| fail(e));
+    config.output = OutputCapabilities::detect(&config);
 
-fn generate_bombs<const N: usize>() -> [[bool; N]; N] {
-    let mut rng = thread_rng();
-
-    // true = bomb; false = safe
-    let choices = [true, false];
-    // 1 bomb for every 5 safe tiles
-    // (16.66% bombs)
-    let weights = [1, 5];
-    let dist = WeightedIndex::new(&weights).expect("Hardcoded weights are correct");
-
-    (0..N)
-        .map(|_| {
-            (0..N)
-                .map(|_| choices[dist.sample(&mut rng)])
-                .collect::<Vec<bool>>()
-                .try_into()
-                .expect("Vector of booleans should have the correct length")
-        })
-        .collect::<Vec<[bool; N]>>()
-        .try_into()
-        .expect("Vector of vectors of booleans should have the correct length")
-}
-
-fn main() {
-    let mut board = Board::<10>::new();
+    if cli.batch && mode != ModeArg::Single {
+        fail(text(Msg::BatchSingleOnly));
+    }
+    if cli.json && !cli.batch && cli.serve.is_none() && cli.broadcast.is_none() {
+        fail(text(Msg::JsonNeedsBatch));
+    }
+    if cli.diff && (!cli.json || cli.broadcast.is_some()) {
+        fail(text(Msg::DiffNeedsJson));
+    }
+    // the dump would print at the terminal what the key is kept from it
+    if cli.answer_key.is_some() && config.debug_dump {
+        fail(text(Msg::AnswerKeyWithDebugDump));
+    }
+    // the other modes start boards of their own as they go, and a script or
+    // a client has no terminal for the key to be kept from
+    let keyed = matches!(
+        mode,
+        ModeArg::Single | ModeArg::Coop | ModeArg::Daily | ModeArg::Weekly
+    );
+    if cli.answer_key.is_some() && (cli.batch || cli.serve.is_some() || !keyed) {
+        fail(text(Msg::AnswerKeyOneGame));
+    }
+    if cli.broadcast.is_some() && (cli.batch || cli.serve.is_some() || mode != ModeArg::Single) {
+        fail(text(Msg::BroadcastSingleOnly));
+    }
 
-    let result = loop {
-        if board.is_won() {
-            break "Game won";
+    // a server has its own ctrl-c handling, and saves nothing
+    if let Some(port) = cli.serve {
+        let protocol = batch::Protocol {
+            config: &config,
+            json: cli.json,
+            diff: cli.diff,
+            files: false,
+        };
+        if mode == ModeArg::Race {
+            // both players get the same board
+            let seed = cli.seed.unwrap_or_else(random);
+            let winner = netrace::serve(&protocol, port, Arc::new(SystemClock), || {
+                new_single(&config, Some(seed))
+            })
+            .unwrap_or_else(|e| fail(fill(Msg::CouldntServe, &[&e])));
+            println!("{winner}");
+        } else {
+            serve::serve(&protocol, port, || new_single(&config, cli.seed))
+                .unwrap_or_else(|e| fail(fill(Msg::CouldntServe, &[&e])));
         }
+        return;
+    }
 
-        println!("{board}\n------");
-
-        let mut row_index = String::new();
-        println!("Enter row index:");
-
-        unwrap_or_continue!(stdin().read_line(&mut row_index));
-        let row_index: usize = unwrap_or_continue!(row_index.trim_end().parse());
-
-        let mut col_index = String::new();
-        println!("Enter col index:");
+    if !cli.batch {
+        autosave::install_interrupt_handler();
+        autosave::install_panic_handler();
+    }
 
-        unwrap_or_continue!(stdin().read_line(&mut col_index));
-        let col_index: usize = unwrap_or_continue!(col_index.trim_end().parse());
+    let broadcast = cli.broadcast.map(|port| {
+        Broadcast::start(port, cli.json)
+            .unwrap_or_else(|e| fail(fill(Msg::CouldntBroadcast, &[&e])))
+    });
+
+    let sources = [
+        cli.load.is_some(),
+        cli.import_mbf.is_some(),
+        cli.named_save.is_some(),
+    ];
+    if sources.into_iter().filter(|&given| given).count() > 1 {
+        fail(text(Msg::LoadOrImport));
+    }
+    let saved = match (&cli.load, &cli.import_mbf) {
+        (None, None) if cli.named_save.is_some() => {
+            let save = cli.named_save.as_deref().unwrap_or_default();
+            Some(saves::load(save).unwrap_or_else(|e| fail(e)))
+        }
+        (Some(path), _) => {
+            let text = fs::read_to_string(path)
+                .unwrap_or_else(|e| fail(fill(Msg::CouldntRead, &[&path.display(), &e])));
+            Some(deserialize(&text).unwrap_or_else(|e| fail(e)))
+        }
+        // an imported board is played like a new single-player game on it
+        (None, Some(path)) => {
+            let board = mbf::read_mbf(path)
+                .unwrap_or_else(|e| fail(fill(Msg::CouldntImport, &[&path.display(), &e])));
+            Some(SavedGame {
+                mode: Mode::Single,
+                code: path.display().to_string(),
+                game: Game::new(board, 1)
+                    .with_peeks(config.peeks)
+                    .shielded(config.shields)
+                    .with_win_condition(config.win),
+            })
+        }
+        (None, None) if cli.batch => None,
+        (None, None) => autosave::offer_resume(),
+    };
 
-        let position = CellPosition {
-            row_index,
-            col_index,
+    // a single-player game can be followed by more in the same session
+    let single = !cli.batch
+        && match &saved {
+            Some(saved) => saved.mode == Mode::Single,
+            None => mode == ModeArg::Single,
         };
 
-        match board.clear(position, &vec![]) {
-            Ok(_) => (),
-            Err(ClearError::CellNotFound) => {
-                println!("Invalid cell position");
-                continue;
+    let key = cli.answer_key.as_deref();
+    let mut outcome = match saved {
+        Some(SavedGame { code, game, .. }) if cli.batch => {
+            batch::play(game, code, &config, cli.json, cli.diff)
+        }
+        Some(SavedGame { mode, code, game }) => {
+            play(mode, code, game, &config, None, broadcast.as_ref(), key)
+        }
+        None => match mode {
+            // these report their own result and don't have a single outcome
+            ModeArg::Race => {
+                println!("{}", race::play(&config, cli.seed));
+                return;
             }
-            Err(ClearError::ClearedBomb) => {
-                break "Game lost";
+            ModeArg::Tutorial => {
+                println!("{}", tutorial::run(&config));
+                return;
             }
-            Err(ClearError::AlreadyCleared) => {
-                println!("Cell already cleared");
-                continue;
+            ModeArg::Campaign => {
+                println!("{}", campaign::run(&config));
+                return;
             }
-        };
-
-        println!("------");
+            ModeArg::Gauntlet => {
+                println!("{}", gauntlet::run(&config, cli.seed));
+                return;
+            }
+            ModeArg::Endless => {
+                println!("{}", endless::run(&config, cli.seed));
+                return;
+            }
+            ModeArg::Coop => {
+                let (code, game) = coop::new_game(&config, cli.seed);
+                play(
+                    Mode::Coop,
+                    code,
+                    game,
+                    &config,
+                    Some(config.mines),
+                    None,
+                    key,
+                )
+            }
+            ModeArg::Daily => {
+                let (code, game) = daily::new_game();
+                play(
+                    Mode::Daily,
+                    code,
+                    game,
+                    &config,
+                    Some(daily::MINES),
+                    None,
+                    key,
+                )
+            }
+            ModeArg::Weekly => {
+                let (code, game, modifier) = daily::new_weekly();
+                println!("{}", daily::render_modifier(modifier));
+                let requested = Some(modifier.rules().layout().mines);
+                play(Mode::Weekly, code, game, &config, requested, None, key)
+            }
+            ModeArg::Single if cli.batch => {
+                let (code, game) = new_single(&config, cli.seed);
+                batch::play(game, code, &config, cli.json, cli.diff)
+            }
+            ModeArg::Single => {
+                let (code, game) = new_single(&config, cli.seed);
+                let requested = Some(config.mines);
+                play(
+                    Mode::Single,
+                    code,
+                    game,
+                    &config,
+                    requested,
+                    broadcast.as_ref(),
+                    key,
+                )
+            }
+        },
     };
 
-    println!("{result}")
+    if cli.json && cli.batch {
+        println!("{}", outcome.to_json());
+        exit(outcome.exit_code())
+    }
+    println!("{outcome}");
+
+    if single {
+        let mut outcomes = vec![outcome];
+        while outcome != GameOutcome::Aborted && session::play_again() {
+            let (code, game) = new_single(&config, None);
+            let requested = Some(config.mines);
+            let broadcast = broadcast.as_ref();
+            outcome = play(Mode::Single, code, game, &config, requested, broadcast, key);
+            println!("{outcome}");
+            outcomes.push(outcome);
+        }
+
+        // one game on its own doesn't need summing up
+        if cli.summary && outcomes.len() > 1 {
+            if let Some(summary) = Summary::of(&outcomes, config.count_resigned) {
+                println!("{summary}");
+            }
+        }
+    }
+    exit(outcome.exit_code())
 }
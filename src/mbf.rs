@@ -0,0 +1,245 @@
+use crate::board::{Board, CellState};
+use crate::i18n::{fill, text, Msg};
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// the .mbf minefield format: a byte each for the width and the height, the
+// number of mines as two bytes, most significant first, then a byte each for
+// the column and the row of every mine
+
+#[derive(Debug)]
+pub enum MbfError {
+    Io(io::Error),
+    Truncated { expected: usize, found: usize },
+    EmptyBoard,
+    OutOfRange { col: u8, row: u8 },
+    DuplicateMine { col: u8, row: u8 },
+    // every cell a mine, which leaves nothing to open
+    NoSafeCells,
+    // more than a byte can hold in either direction
+    TooLarge { height: usize, width: usize },
+}
+
+impl Display for MbfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let out = match self {
+            MbfError::Io(e) => e.to_string(),
+            MbfError::Truncated { expected, found } => fill(Msg::MbfTruncated, &[expected, found]),
+            MbfError::EmptyBoard => text(Msg::MbfEmptyBoard).to_string(),
+            MbfError::OutOfRange { col, row } => fill(Msg::MbfOutOfRange, &[row, col]),
+            MbfError::DuplicateMine { col, row } => fill(Msg::MbfDuplicateMine, &[row, col]),
+            MbfError::NoSafeCells => text(Msg::MbfNoSafeCells).to_string(),
+            MbfError::TooLarge { height, width } => fill(Msg::MbfTooLarge, &[height, width]),
+        };
+
+        write!(f, "{out}")
+    }
+}
+
+// anything after the last mine is ignored
+pub fn parse(bytes: &[u8]) -> Result<Vec<Vec<bool>>, MbfError> {
+    let truncated = |expected| MbfError::Truncated {
+        expected,
+        found: bytes.len(),
+    };
+    let &[width, height, high, low, ref mines @ ..] = bytes else {
+        return Err(truncated(4));
+    };
+    let (width, height) = (usize::from(width), usize::from(height));
+    if width == 0 || height == 0 {
+        return Err(MbfError::EmptyBoard);
+    }
+
+    let count = usize::from(u16::from_be_bytes([high, low]));
+    if mines.len() < count * 2 {
+        return Err(truncated(4 + count * 2));
+    }
+    if count >= width * height {
+        return Err(MbfError::NoSafeCells);
+    }
+
+    let mut bombs = vec![vec![false; width]; height];
+    for mine in mines[..count * 2].chunks(2) {
+        let (col, row) = (mine[0], mine[1]);
+        let cell = bombs
+            .get_mut(usize::from(row))
+            .and_then(|cells| cells.get_mut(usize::from(col)))
+            .ok_or(MbfError::OutOfRange { col, row })?;
+        if *cell {
+            return Err(MbfError::DuplicateMine { col, row });
+        }
+        *cell = true;
+    }
+    Ok(bombs)
+}
+
+pub fn encode(board: &Board) -> Result<Vec<u8>, MbfError> {
+    let (height, width) = (board.height(), board.width());
+    let too_large = MbfError::TooLarge { height, width };
+    let (Ok(height_byte), Ok(width_byte)) = (u8::try_from(height), u8::try_from(width)) else {
+        return Err(too_large);
+    };
+
+    let mines: Vec<[u8; 2]> = board
+        .rows()
+        .flatten()
+        .filter(|cell| matches!(cell.state, CellState::Bomb { .. }))
+        // the width and height fit in a byte, so every position does too
+        .map(|cell| [cell.position.col_index as u8, cell.position.row_index as u8])
+        .collect();
+    let count = u16::try_from(mines.len()).map_err(|_| too_large)?;
+
+    let mut bytes = vec![width_byte, height_byte];
+    bytes.extend(count.to_be_bytes());
+    bytes.extend(mines.into_iter().flatten());
+    Ok(bytes)
+}
+
+pub fn read_mbf(path: &Path) -> Result<Board, MbfError> {
+    let bytes = fs::read(path).map_err(MbfError::Io)?;
+    parse(&bytes).map(Board::from_bombs)
+}
+
+pub fn write_mbf(board: &Board, path: &Path) -> Result<(), MbfError> {
+    fs::write(path, encode(board)?).map_err(MbfError::Io)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a beginner's board as the files shared around have it: 9x9 with ten
+    // mines, listed column then row
+    const BEGINNER: [u8; 24] = [
+        9, 9, 0, 10, 0, 0, 8, 0, 0, 8, 8, 8, 4, 4, 1, 2, 2, 1, 7, 3, 3, 7, 5, 6,
+    ];
+
+    fn mines_of(bombs: &[Vec<bool>]) -> Vec<(usize, usize)> {
+        let mut mines = vec![];
+        for (row, cells) in bombs.iter().enumerate() {
+            for (col, &is_bomb) in cells.iter().enumerate() {
+                if is_bomb {
+                    mines.push((col, row));
+                }
+            }
+        }
+        mines
+    }
+
+    #[test]
+    fn a_hand_built_file_parses_to_its_mines() {
+        let bombs = parse(&BEGINNER).expect("The file should parse");
+        assert_eq!((bombs.len(), bombs[0].len()), (9, 9));
+        let mut listed: Vec<(usize, usize)> = BEGINNER[4..]
+            .chunks(2)
+            .map(|mine| (usize::from(mine[0]), usize::from(mine[1])))
+            .collect();
+        listed.sort_by_key(|&(col, row)| (row, col));
+        assert_eq!(mines_of(&bombs), listed);
+
+        // and a wide board, not square, keeps its width and height apart
+        let bombs = parse(&[3, 2, 0, 1, 2, 1]).expect("The file should parse");
+        assert_eq!(bombs, [vec![false; 3], vec![false, false, true]]);
+    }
+
+    #[test]
+    fn boards_round_trip_through_the_format() {
+        // an expert's board, 30 wide and 16 high with 99 mines
+        let expert = Board::from_seed(3, 16, 30, crate::board::Mines::Count(99));
+        let bytes = encode(&expert).expect("The board should fit");
+        assert_eq!(&bytes[..4], [30, 16, 0, 99]);
+        assert_eq!(bytes.len(), 4 + 99 * 2);
+        assert_eq!(
+            Board::from_bombs(parse(&bytes).expect("The file should parse")).layout(),
+            expert.layout()
+        );
+
+        // and written back out, a file read in is the same bytes
+        let beginner = Board::from_bombs(parse(&BEGINNER).expect("The file should parse"));
+        let again = encode(&beginner).expect("The board should fit");
+        assert_eq!(
+            parse(&again).expect("The file should parse"),
+            parse(&BEGINNER).expect("The file should parse")
+        );
+        assert_eq!(again.len(), BEGINNER.len());
+    }
+
+    #[test]
+    fn a_truncated_file_says_how_long_it_should_be() {
+        assert!(matches!(
+            parse(&[]),
+            Err(MbfError::Truncated {
+                expected: 4,
+                found: 0
+            })
+        ));
+        assert!(matches!(
+            parse(&[9, 9, 0]),
+            Err(MbfError::Truncated {
+                expected: 4,
+                found: 3
+            })
+        ));
+        // ten mines need 20 bytes after the header, and the last is cut off
+        assert!(matches!(
+            parse(&BEGINNER[..23]),
+            Err(MbfError::Truncated {
+                expected: 24,
+                found: 23
+            })
+        ));
+        assert_eq!(
+            parse(&BEGINNER[..23]).map_err(|e| e.to_string()).err(),
+            Some(String::from(
+                "The file ends early: it should have at least 24 bytes, but has 23"
+            ))
+        );
+        // while bytes past the last mine are left alone
+        assert!(parse(&[BEGINNER.as_slice(), &[1, 2, 3]].concat()).is_ok());
+    }
+
+    #[test]
+    fn mines_off_the_board_or_listed_twice_are_turned_down() {
+        // a column of 9 on a board 9 wide
+        assert!(matches!(
+            parse(&[9, 9, 0, 2, 0, 0, 9, 4]),
+            Err(MbfError::OutOfRange { col: 9, row: 4 })
+        ));
+        assert!(matches!(
+            parse(&[9, 9, 0, 1, 255, 255]),
+            Err(MbfError::OutOfRange { col: 255, row: 255 })
+        ));
+        // the row, on a board wider than it is high
+        assert!(matches!(
+            parse(&[5, 2, 0, 1, 4, 2]),
+            Err(MbfError::OutOfRange { col: 4, row: 2 })
+        ));
+        assert_eq!(
+            parse(&[5, 2, 0, 1, 4, 2]).map_err(|e| e.to_string()).err(),
+            Some(String::from("The mine at (2,4) is off the board"))
+        );
+        assert!(matches!(
+            parse(&[9, 9, 0, 2, 3, 1, 3, 1]),
+            Err(MbfError::DuplicateMine { col: 3, row: 1 })
+        ));
+        assert!(matches!(parse(&[0, 9, 0, 0]), Err(MbfError::EmptyBoard)));
+        assert!(matches!(
+            parse(&[1, 2, 0, 2, 0, 0, 0, 1]),
+            Err(MbfError::NoSafeCells)
+        ));
+    }
+
+    #[test]
+    fn a_board_too_large_for_a_byte_isnt_encoded() {
+        let board = Board::from_bombs(vec![vec![false; 256]; 2]);
+        assert!(matches!(
+            encode(&board),
+            Err(MbfError::TooLarge {
+                height: 2,
+                width: 256
+            })
+        ));
+    }
+}
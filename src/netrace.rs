@@ -0,0 +1,538 @@
+use crate::autosave::on_interrupt;
+use crate::batch::Protocol;
+use crate::game::{Game, GameEvent, GameOutcome};
+use crate::i18n::{fill, text, Msg};
+use crate::json::Value;
+use crate::serve::error_line;
+use crate::timer::Clock;
+use crate::view::GameView;
+use rand::random;
+use std::io::{self, BufRead, BufReader, Lines, Write};
+use std::net::{Ipv4Addr, Shutdown, TcpListener, TcpStream};
+use std::process;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+// how long a player who disconnects has to come back before they forfeit
+pub const GRACE: Duration = Duration::from_secs(30);
+
+// while a player is away, the clock is read at least this often, as it
+// needn't keep the real time
+const POLL: Duration = Duration::from_millis(50);
+
+type Shared = Arc<Mutex<TcpStream>>;
+
+enum Event {
+    Connected(TcpStream),
+    // a connection that arrived while a player was away, with its first line
+    Resuming(TcpStream, Lines<BufReader<TcpStream>>, String),
+    // how much of their board a player has opened, as a percentage
+    Progress(usize, usize),
+    // the game is boxed, as it is much bigger than the other events
+    Finished(usize, GameOutcome, Box<Game>),
+}
+
+struct Player {
+    token: String,
+    out: Shared,
+    // the game and its input while no thread is playing it, which is before
+    // the race starts and while the player is away
+    game: Option<Game>,
+    lines: Option<Lines<BufReader<TcpStream>>>,
+    away_since: Option<Duration>,
+    progress: usize,
+}
+
+// writes whole lines at a time, so that the opponent's progress can't land
+// in the middle of one
+struct Outlet {
+    stream: Shared,
+    pending: Vec<u8>,
+}
+
+impl Write for Outlet {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        if let Some(end) = self.pending.iter().rposition(|&byte| byte == b'\n') {
+            let mut stream = self.stream.lock().map_err(|_| io::ErrorKind::Other)?;
+            stream.write_all(&self.pending[..=end])?;
+            self.pending.drain(..=end);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// a line to a player; one who has gone away is noticed by their game
+fn tell(out: &Shared, line: &str) {
+    if let Ok(mut stream) = out.lock() {
+        let _ = writeln!(stream, "{line}");
+    }
+}
+
+// a notice about the race, as `object` in json or as `msg` otherwise
+fn notice(json: bool, object: Value, msg: String) -> String {
+    if json {
+        object.to_string()
+    } else {
+        msg
+    }
+}
+
+fn started(json: bool) -> String {
+    let object = Value::object([("race", Value::from("started"))]);
+    notice(json, object, text(Msg::NetRaceStarted).to_string())
+}
+
+fn percent_open(view: &GameView) -> usize {
+    let counters = view.counters();
+    counters.open * 100 / counters.safe.max(1)
+}
+
+// the player's game, played on its own thread until it is over or the
+// player disconnects; the opponent hears about every cell it opens
+fn start_playing<'scope>(
+    scope: &'scope thread::Scope<'scope, '_>,
+    protocol: &'scope Protocol,
+    code: &'scope str,
+    index: usize,
+    player: &mut Player,
+    events: Sender<Event>,
+) {
+    let (Some(mut game), Some(lines)) = (player.game.take(), player.lines.take()) else {
+        return;
+    };
+    let mut out = Outlet {
+        stream: player.out.clone(),
+        pending: vec![],
+    };
+    scope.spawn(move || {
+        let outcome = protocol.run_with(&mut game, code, lines, &mut out, |game| {
+            let view = GameView::of(game);
+            let opened = view
+                .events()
+                .iter()
+                .any(|event| matches!(event, GameEvent::CellsOpened(_)));
+            if opened {
+                let _ = events.send(Event::Progress(index, percent_open(&view)));
+            }
+        });
+        let _ = events.send(Event::Finished(index, outcome, Box::new(game)));
+    });
+}
+
+fn accept(listener: TcpListener, events: Sender<Event>) {
+    for stream in listener.incoming().flatten() {
+        if events.send(Event::Connected(stream)).is_err() {
+            break;
+        }
+    }
+}
+
+// waits on its own thread for the first line of a connection, which should
+// be `resume <token>` from a player who went away
+fn read_resume(stream: TcpStream, events: Sender<Event>) {
+    thread::spawn(move || {
+        let Ok(reader) = stream.try_clone() else {
+            return;
+        };
+        let mut lines = BufReader::new(reader).lines();
+        if let Some(Ok(first)) = lines.next() {
+            let _ = events.send(Event::Resuming(stream, lines, first));
+        }
+    });
+}
+
+// races two clients on localhost, each on their own copy of the same board
+// from `new_game`, speaking the batch protocol; each is told how far the
+// other has got, but never what their board shows. The first to clear their
+// board wins, a mine hands the win to the other, and a player who
+// disconnects forfeits unless they come back within GRACE. Returns who won
+pub fn serve(
+    protocol: &Protocol,
+    port: u16,
+    clock: Arc<dyn Clock>,
+    new_game: impl Fn() -> (String, Game),
+) -> io::Result<String> {
+    let json = protocol.json;
+    let clients: Arc<Mutex<Vec<Shared>>> = Arc::default();
+    let stopping = clients.clone();
+    on_interrupt(move || {
+        for client in stopping.lock().iter().flat_map(|clients| clients.iter()) {
+            tell(client, &error_line(json, Msg::ServeStopping));
+            if let Ok(stream) = client.lock() {
+                let _ = stream.shutdown(Shutdown::Both);
+            }
+        }
+        process::exit(0);
+    });
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+    println!("{}", fill(Msg::ServeListening, &[&listener.local_addr()?]));
+    serve_on(protocol, listener, clients, clock, new_game)
+}
+
+// as `serve`, on a listener already bound; `clients` is kept to everyone
+// connected
+pub fn serve_on(
+    protocol: &Protocol,
+    listener: TcpListener,
+    clients: Arc<Mutex<Vec<Shared>>>,
+    clock: Arc<dyn Clock>,
+    new_game: impl Fn() -> (String, Game),
+) -> io::Result<String> {
+    let json = protocol.json;
+    let (events, received) = mpsc::channel();
+    let accepting = events.clone();
+    thread::spawn(move || accept(listener, accepting));
+
+    let (code, _) = new_game();
+    let mut players: Vec<Player> = vec![];
+
+    let winner = thread::scope(|scope| {
+        let winner = loop {
+            // the server keeps a sender of its own, so there is always
+            // something that could still send
+            let event = match players.iter().filter_map(|player| player.away_since).min() {
+                Some(since) => {
+                    let left = (since + GRACE).saturating_sub(clock.now());
+                    received.recv_timeout(left.min(POLL))
+                }
+                None => received.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            if let Some(away) = players.iter().position(|player| {
+                player
+                    .away_since
+                    .is_some_and(|since| clock.now() >= since + GRACE)
+            }) {
+                break 1 - away;
+            }
+            let Ok(event) = event else {
+                continue;
+            };
+
+            match event {
+                Event::Connected(mut stream) if players.len() < 2 => {
+                    let token = format!("{:016x}", random::<u64>());
+                    let greeting = if json {
+                        Value::object([
+                            ("code", Value::from(code.as_str())),
+                            ("token", Value::from(token.as_str())),
+                        ])
+                        .to_string()
+                    } else {
+                        fill(Msg::ServeToken, &[&code, &token])
+                    };
+                    let _ = writeln!(stream, "{greeting}");
+                    let Ok(reader) = stream.try_clone() else {
+                        continue;
+                    };
+                    let out = Arc::new(Mutex::new(stream));
+                    if let Ok(mut clients) = clients.lock() {
+                        clients.push(out.clone());
+                    }
+                    players.push(Player {
+                        token,
+                        out,
+                        game: Some(new_game().1),
+                        lines: Some(BufReader::new(reader).lines()),
+                        away_since: None,
+                        progress: 0,
+                    });
+
+                    if players.len() < 2 {
+                        let waiting = Value::object([("race", Value::from("waiting"))]);
+                        let message = text(Msg::NetRaceWaiting).to_string();
+                        tell(&players[0].out, &notice(json, waiting, message));
+                        continue;
+                    }
+                    for (index, player) in players.iter_mut().enumerate() {
+                        tell(&player.out, &started(json));
+                        if let (Some(game), Ok(mut out)) = (&player.game, player.out.lock()) {
+                            protocol.opening(game, &mut *out);
+                        }
+                        start_playing(scope, protocol, &code, index, player, events.clone());
+                    }
+                }
+                Event::Connected(stream)
+                    if players.iter().any(|player| player.away_since.is_some()) =>
+                {
+                    read_resume(stream, events.clone());
+                }
+                Event::Connected(mut stream) => {
+                    let _ = writeln!(stream, "{}", error_line(json, Msg::ServeBusy));
+                }
+                Event::Resuming(mut stream, lines, first) => {
+                    let token = first.trim().strip_prefix("resume").map(str::trim);
+                    let Some(index) = players.iter().position(|player| {
+                        player.away_since.is_some() && Some(player.token.as_str()) == token
+                    }) else {
+                        let _ = writeln!(stream, "{}", error_line(json, Msg::ServeNoSuchGame));
+                        continue;
+                    };
+                    let player = &mut players[index];
+                    if let Ok(mut out) = player.out.lock() {
+                        *out = stream;
+                    }
+                    player.away_since = None;
+                    player.lines = Some(lines);
+                    tell(&player.out, &started(json));
+                    start_playing(scope, protocol, &code, index, player, events.clone());
+
+                    let back = Value::object([("opponent", Value::from("returned"))]);
+                    let message = text(Msg::NetRaceBack).to_string();
+                    tell(&players[1 - index].out, &notice(json, back, message));
+                }
+                Event::Progress(index, progress) => {
+                    if players[index].progress != progress {
+                        players[index].progress = progress;
+                        let object = Value::object([("opponent_progress", Value::from(progress))]);
+                        let message = fill(Msg::NetRaceProgress, &[&progress]);
+                        tell(&players[1 - index].out, &notice(json, object, message));
+                    }
+                }
+                Event::Finished(index, GameOutcome::Won(_), _) => break index,
+                Event::Finished(index, GameOutcome::Aborted, game) => {
+                    players[index].game = Some(*game);
+                    players[index].away_since = Some(clock.now());
+                    let message = fill(Msg::NetRaceLeft, &[&GRACE.as_secs()]);
+                    let left = Value::object([
+                        ("opponent", Value::from("left")),
+                        ("grace", Value::from(GRACE.as_secs() as usize)),
+                    ]);
+                    tell(&players[1 - index].out, &notice(json, left, message));
+                }
+                Event::Finished(index, _, _) => break 1 - index,
+            }
+        };
+
+        // the other game is still waiting on its player, so it is cut off
+        for (index, player) in players.iter().enumerate() {
+            let (result, msg) = if index == winner {
+                ("won", Msg::NetRaceWon)
+            } else {
+                ("lost", Msg::NetRaceLost)
+            };
+            let message = text(msg).to_string();
+            let object = Value::object([("race", Value::from(result))]);
+            tell(&player.out, &notice(json, object, message));
+            if let Ok(stream) = player.out.lock() {
+                let _ = stream.shutdown(Shutdown::Both);
+            }
+        }
+        winner
+    });
+
+    Ok(fill(
+        Msg::PlayerWins,
+        &[&fill(Msg::PlayerName, &[&(winner + 1)])],
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{Board, CellPosition};
+    use crate::command::Command;
+    use crate::config::Config;
+    use crate::stats::tests::SetByHand;
+    use std::net::SocketAddr;
+    use std::thread::JoinHandle;
+
+    // a mine at the bottom, out of reach of a clear at the top; (0,0) opens
+    // six of the eight safe cells
+    const LAYOUT: &str = "...\n...\n.*.";
+
+    // a race on a port of its own, whose result is who won
+    fn start(clock: Arc<SetByHand>) -> (SocketAddr, JoinHandle<String>) {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).expect("A port should be free");
+        let address = listener.local_addr().expect("The listener has an address");
+        let race = thread::spawn(move || {
+            let config = Config::default();
+            let protocol = Protocol {
+                config: &config,
+                json: false,
+                diff: false,
+                files: false,
+            };
+            let new_game = || {
+                let board = LAYOUT.parse().expect("The layout should parse");
+                (String::from("#1"), Game::new(board, 1))
+            };
+            serve_on(&protocol, listener, Arc::default(), clock, new_game)
+                .expect("The race should be run")
+        });
+        (address, race)
+    }
+
+    struct Client {
+        stream: TcpStream,
+        lines: Lines<BufReader<TcpStream>>,
+    }
+
+    impl Client {
+        fn connect(address: SocketAddr) -> Client {
+            let stream = TcpStream::connect(address).expect("The server should accept");
+            // a test that goes wrong fails rather than hanging
+            stream
+                .set_read_timeout(Some(Duration::from_secs(5)))
+                .expect("The timeout should be set");
+            let reader = stream.try_clone().expect("The stream should clone");
+            Client {
+                stream,
+                lines: BufReader::new(reader).lines(),
+            }
+        }
+
+        fn send(&mut self, line: &str) {
+            writeln!(self.stream, "{line}").expect("The line should be sent");
+        }
+
+        // each line up to and including `last`
+        fn until(&mut self, last: &str) -> Vec<String> {
+            let mut lines = vec![];
+            for line in self.lines.by_ref() {
+                let line = line.expect("The line should be read");
+                let done = line == last;
+                lines.push(line);
+                if done {
+                    return lines;
+                }
+            }
+            panic!("{last:?} never came, after {lines:?}");
+        }
+    }
+
+    const STARTED: &str = "The race is on: both players have the same board";
+
+    // both players, connected and told the race has started
+    fn both(address: SocketAddr) -> (Client, Client) {
+        let mut first = Client::connect(address);
+        first.until("Waiting for an opponent to connect");
+        let mut second = Client::connect(address);
+        first.until(STARTED);
+        second.until(STARTED);
+        (first, second)
+    }
+
+    #[test]
+    fn the_first_to_clear_their_board_wins() {
+        let (address, race) = start(Arc::default());
+        let (mut first, mut second) = both(address);
+
+        // the second player hears how far the first has got, and nothing of
+        // what their board shows
+        first.send("c 0 0");
+        first.until("------");
+        let heard = second.until("Your opponent has opened 75% of their board");
+        assert_eq!(heard.len(), 1, "{heard:?}");
+
+        second.send("c 0 2");
+        second.until("------");
+        first.until("Your opponent has opened 75% of their board");
+
+        first.send("c 2 0");
+        first.until("------");
+        first.send("c 2 2");
+        first.until("You won the race");
+        second.until("Your opponent won the race");
+        assert_eq!(
+            race.join().expect("The race should finish"),
+            "Player 1 wins"
+        );
+    }
+
+    #[test]
+    fn a_mine_hands_the_win_to_the_other_player() {
+        let (address, race) = start(Arc::default());
+        let (_first, mut second) = both(address);
+
+        second.send("c 2 1");
+        second.until("Your opponent won the race");
+        assert_eq!(
+            race.join().expect("The race should finish"),
+            "Player 1 wins"
+        );
+    }
+
+    #[test]
+    fn a_player_who_stays_away_past_the_grace_period_forfeits() {
+        let clock = Arc::new(SetByHand::default());
+        let (address, race) = start(clock.clone());
+        let (mut first, second) = both(address);
+
+        clock.set(100);
+        drop(second);
+        first.until(
+            "Your opponent disconnected; they forfeit unless they are back within 30 seconds",
+        );
+
+        // not yet
+        clock.set(129);
+        thread::sleep(POLL * 4);
+        assert!(!race.is_finished());
+
+        clock.set(130);
+        first.until("You won the race");
+        assert_eq!(
+            race.join().expect("The race should finish"),
+            "Player 1 wins"
+        );
+    }
+
+    #[test]
+    fn a_player_back_within_the_grace_period_plays_on() {
+        let clock = Arc::new(SetByHand::default());
+        let (address, race) = start(clock.clone());
+        let mut first = Client::connect(address);
+        let greeting = first.until("Waiting for an opponent to connect");
+        let token = greeting[0]
+            .split("send resume ")
+            .nth(1)
+            .expect("The greeting should give a token")
+            .to_string();
+        let mut second = Client::connect(address);
+        first.until(STARTED);
+        second.until(STARTED);
+
+        first.send("c 0 0");
+        first.until("------");
+        drop(first);
+        second.until(
+            "Your opponent disconnected; they forfeit unless they are back within 30 seconds",
+        );
+
+        // back with 10 seconds to spare, and the board as it was left
+        clock.set(20);
+        let mut back = Client::connect(address);
+        back.send(&format!("resume {token}"));
+        back.until(STARTED);
+        second.until("Your opponent is back");
+        clock.set(1000);
+        back.send("c 2 0; c 2 2");
+        back.until("You won the race");
+        assert_eq!(
+            race.join().expect("The race should finish"),
+            "Player 1 wins"
+        );
+    }
+
+    #[test]
+    fn progress_leaves_rocks_out() {
+        // of the two safe cells, one open is half
+        let board: Board = ".o\n.*".parse().expect("The layout should parse");
+        let mut game = Game::new(board, 1);
+        assert_eq!(percent_open(&GameView::of(&game)), 0);
+        game.apply(0, Command::Clear(CellPosition::new(0, 0)))
+            .expect("The cell should open");
+        assert_eq!(percent_open(&GameView::of(&game)), 50);
+        game.apply(0, Command::Clear(CellPosition::new(1, 0)))
+            .expect("The cell should open");
+        assert_eq!(percent_open(&GameView::of(&game)), 100);
+    }
+}
@@ -0,0 +1,624 @@
+use crate::board::{Board, CellPosition, CellState};
+use crate::i18n::{fill, text, Msg};
+use crate::solver::{finishes, CellOrder, Deadline, Found, FrontierSearch};
+use crate::timer::Clock;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::seq::index;
+use rand::{Rng, SeedableRng};
+use std::time::Duration;
+
+// the order the frontier is searched in, which the benchmarks in bench/
+// find the quickest on dense boards
+pub const ORDER: CellOrder = CellOrder::Breadth;
+
+// the most layouts of any one part of the frontier that are weighed against
+// each other; past that they are only drawn, unevenly, one search at a time
+const FRONTIER_LAYOUTS: usize = 20_000;
+
+// more closed cells than this out of reach of the numbers decide too much
+// of how the game goes for a few layouts to say, and each takes a while to
+// play through, so only COARSE_LAYOUTS are tried
+const HUGE_AREA: usize = 200;
+const COARSE_LAYOUTS: usize = 25;
+
+// the z-score of a 95% confidence interval
+const Z_95: f64 = 1.96;
+
+// how much an estimate may try: at most `layouts` layouts, and no more once
+// `time` is up
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Budget {
+    pub layouts: usize,
+    pub time: Duration,
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Budget {
+            layouts: 400,
+            time: Duration::from_secs(3),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Accuracy {
+    // every layout the numbers allow was played through
+    Exact,
+    // layouts drawn at random, each as often as it would be the board
+    Sampled,
+    // too few layouts, or drawn unevenly, to go by
+    Coarse,
+}
+
+// of the layouts of the mines that fit what the player can see, how many
+// the solver cleared without guessing
+#[derive(Debug, Clone, PartialEq)]
+pub struct WinEstimate {
+    pub winnable: usize,
+    pub layouts: usize,
+    pub accuracy: Accuracy,
+}
+
+impl WinEstimate {
+    // with the default budget, timed by `clock`; the layouts are drawn the
+    // same way for the same position, so that asking again without moving
+    // mostly gives the same answer
+    pub fn of(board: &Board, clock: &dyn Clock) -> Option<WinEstimate> {
+        let rng = &mut StdRng::seed_from_u64(board.count_open() as u64);
+        estimate(board, &Budget::default(), clock, rng)
+    }
+
+    pub fn chance(&self) -> f64 {
+        self.winnable as f64 / self.layouts.max(1) as f64
+    }
+
+    // half the width of the 95% confidence interval around `chance`
+    pub fn margin(&self) -> f64 {
+        match self.accuracy {
+            Accuracy::Exact => 0.0,
+            _ => {
+                let chance = self.chance();
+                Z_95 * (chance * (1.0 - chance) / self.layouts.max(1) as f64).sqrt()
+            }
+        }
+    }
+}
+
+// what the player can see of the board, which is all a layout has to fit
+struct Position {
+    height: usize,
+    width: usize,
+    // flagged, set off, or peeked at and shown to be mines
+    known: Vec<CellPosition>,
+    exploded: Vec<CellPosition>,
+    // peeked at and shown to be safe
+    safe: Vec<CellPosition>,
+    rocks: Vec<CellPosition>,
+    open: Vec<CellPosition>,
+    // the closed cells no number reaches
+    interior: Vec<CellPosition>,
+    // the mines left to place, on the frontier and the interior
+    mines: usize,
+}
+
+impl Position {
+    // with the search for its frontier, its cells in `order`; None if there
+    // are more flags than mines
+    fn of(board: &Board, order: CellOrder) -> Option<(Position, FrontierSearch)> {
+        let mut position = Position {
+            height: board.height(),
+            width: board.width(),
+            known: vec![],
+            exploded: vec![],
+            safe: vec![],
+            rocks: vec![],
+            open: vec![],
+            interior: vec![],
+            mines: 0,
+        };
+        let mut closed = vec![];
+        for cell in board.rows().flatten() {
+            let at = cell.position;
+            match cell.state {
+                CellState::Rock => position.rocks.push(at),
+                CellState::Safe { open: true, .. } => position.open.push(at),
+                CellState::Bomb { exploded: true, .. } => position.exploded.push(at),
+                CellState::Bomb { flagged: true, .. } | CellState::Safe { flagged: true, .. } => {
+                    position.known.push(at)
+                }
+                CellState::Bomb { .. } if cell.peeked => position.known.push(at),
+                CellState::Safe { .. } if cell.peeked => {
+                    position.safe.push(at);
+                    closed.push(at);
+                }
+                _ => closed.push(at),
+            }
+        }
+        position.mines = board
+            .count_bombs()
+            .checked_sub(position.known.len() + position.exploded.len())?;
+        position.known.extend(position.exploded.iter().copied());
+
+        let search = FrontierSearch::new(board, &position.known, position.mines).ordered(order);
+        let frontier = search.cells();
+        position.interior = closed
+            .into_iter()
+            .filter(|at| !frontier.contains(at) && !position.safe.contains(at))
+            .collect();
+        Some((position, search))
+    }
+
+    // the board with mines on `mines` and on the known cells, opened as far
+    // as the player's board is
+    fn layout(&self, mines: impl IntoIterator<Item = CellPosition>) -> Board {
+        let mut bombs = vec![vec![false; self.width]; self.height];
+        for at in mines.into_iter().chain(self.known.iter().copied()) {
+            bombs[at.row_index][at.col_index] = true;
+        }
+
+        let mut board = Board::from_bombs(bombs).with_rocks(&self.rocks);
+        for &at in &self.open {
+            // the numbers agree, so this only ever opens cells the player has
+            let _ = board.clear(at);
+        }
+        for &at in &self.exploded {
+            board.explode(at);
+        }
+        board
+    }
+
+    fn fits(&self, frontier: &[CellPosition], layout: &[bool]) -> bool {
+        let placed = layout.iter().filter(|&&mine| mine).count();
+        placed <= self.mines
+            && self.mines - placed <= self.interior.len()
+            && !self.peeked_safe_is_mine(frontier, layout)
+    }
+
+    fn peeked_safe_is_mine(&self, cells: &[CellPosition], layout: &[bool]) -> bool {
+        cells
+            .iter()
+            .zip(layout)
+            .any(|(at, &mine)| mine && self.safe.contains(at))
+    }
+}
+
+// how many ways there are of choosing `picked` of `of`
+fn choose(of: usize, picked: usize) -> f64 {
+    ln_choose(of, picked).exp()
+}
+
+fn ln_choose(of: usize, picked: usize) -> f64 {
+    (0..picked)
+        .map(|i| ((of - i) as f64 / (i + 1) as f64).ln())
+        .sum()
+}
+
+// every way of choosing `picked` of `of`, in order, until `each` says stop
+fn combinations(of: usize, picked: usize, each: &mut dyn FnMut(&[usize]) -> bool) -> bool {
+    let mut chosen: Vec<usize> = (0..picked).collect();
+    loop {
+        if !each(&chosen) {
+            return false;
+        }
+        // the last choice that can still move on, and the ones after it
+        // straight after that
+        let Some(i) = (0..picked).rev().find(|&i| chosen[i] < of - picked + i) else {
+            return true;
+        };
+        chosen[i] += 1;
+        for j in i + 1..picked {
+            chosen[j] = chosen[j - 1] + 1;
+        }
+    }
+}
+
+// how many ways there are of getting each total from one of `first`'s and
+// one of `second`'s, by how many ways each has of getting each number
+fn convolve(first: &[f64], second: &[f64]) -> Vec<f64> {
+    let mut ways = vec![0.0; first.len() + second.len() - 1];
+    for (i, &a) in first.iter().enumerate() {
+        for (j, &b) in second.iter().enumerate() {
+            ways[i + j] += a * b;
+        }
+    }
+    ways
+}
+
+// of the frontier, a part that no number joins to any other, with every
+// layout of it that doesn't put a mine where a peek showed there wasn't one
+struct Part {
+    cells: Vec<CellPosition>,
+    layouts: Vec<Vec<bool>>,
+    // the layouts with each number of mines, as indices into `layouts`
+    by_mines: Vec<Vec<usize>>,
+}
+
+impl Part {
+    fn new(position: &Position, search: &FrontierSearch, layouts: Vec<Vec<bool>>) -> Part {
+        let cells = search.cells().to_vec();
+        let layouts: Vec<Vec<bool>> = layouts
+            .into_iter()
+            .filter(|layout| !position.peeked_safe_is_mine(&cells, layout))
+            .collect();
+        let mut by_mines = vec![vec![]; cells.len() + 1];
+        for (index, layout) in layouts.iter().enumerate() {
+            by_mines[placed(layout)].push(index);
+        }
+        Part {
+            cells,
+            layouts,
+            by_mines,
+        }
+    }
+
+    // how many layouts there are with each number of mines
+    fn counts(&self) -> Vec<f64> {
+        self.by_mines
+            .iter()
+            .map(|layouts| layouts.len() as f64)
+            .collect()
+    }
+
+    fn mines(&self, layout: usize) -> impl Iterator<Item = CellPosition> + '_ {
+        self.cells
+            .iter()
+            .zip(&self.layouts[layout])
+            .filter(|(_, &mine)| mine)
+            .map(|(&at, _)| at)
+    }
+}
+
+fn placed(layout: &[bool]) -> usize {
+    layout.iter().filter(|&&mine| mine).count()
+}
+
+// every layout of the frontier, a part at a time. A layout of the whole of
+// it is a layout of each part, so there are as many as the parts' counts
+// multiplied together, and each part is searched through on its own rather
+// than every part's cells along with every other's
+pub struct Enumeration {
+    parts: Vec<Part>,
+    // the closed cells beyond the frontier
+    interior: Vec<CellPosition>,
+    mines: usize,
+    // false if a part had too many layouts to keep, or the time ran out
+    // before they were all found, in which case the rest are left out
+    pub complete: bool,
+}
+
+// the frontier's layouts, searched with its cells in `order`, by parts
+// unless `split` is false, in which case it is all one part; None if there
+// are more flags than mines
+pub fn enumerate(
+    board: &Board,
+    order: CellOrder,
+    split: bool,
+    deadline: Option<Deadline>,
+) -> Option<Enumeration> {
+    let (position, search) = Position::of(board, order)?;
+    Some(Enumeration::of(&position, &search, split, deadline))
+}
+
+impl Enumeration {
+    fn of(
+        position: &Position,
+        search: &FrontierSearch,
+        split: bool,
+        deadline: Option<Deadline>,
+    ) -> Enumeration {
+        let searches = match split {
+            true => search.parts(),
+            false => vec![search.clone()],
+        };
+        let mut enumeration = Enumeration {
+            parts: vec![],
+            interior: position.interior.clone(),
+            mines: position.mines,
+            complete: true,
+        };
+        for search in &searches {
+            let Found { layouts, complete } = search.all(FRONTIER_LAYOUTS, deadline);
+            enumeration.parts.push(Part::new(position, search, layouts));
+            if !complete {
+                enumeration.complete = false;
+                break;
+            }
+        }
+        enumeration
+    }
+
+    // the ways of laying out the mines over the parts from `first` on, by
+    // how many of them there are
+    fn frontier_ways(&self, first: usize) -> Vec<f64> {
+        self.parts[first..]
+            .iter()
+            .rev()
+            .fold(vec![1.0], |ways, part| convolve(&part.counts(), &ways))
+    }
+
+    // the ways of laying out the rest of the mines beyond the frontier,
+    // once each number of them is on it, as a share of the most there are
+    // for any number (which can be too many to count as they are)
+    fn interior_ways(&self) -> Vec<f64> {
+        let frontier: usize = self.parts.iter().map(|part| part.cells.len()).sum();
+        let interior = self.interior.len();
+        let ways: Vec<Option<f64>> = (0..=frontier)
+            .map(|placed| {
+                let rest = self.mines.checked_sub(placed)?;
+                (rest <= interior).then(|| ln_choose(interior, rest))
+            })
+            .collect();
+        let most = ways
+            .iter()
+            .flatten()
+            .copied()
+            .fold(f64::NEG_INFINITY, f64::max);
+        ways.iter()
+            .map(|ways| ways.map_or(0.0, |ways| (ways - most).exp()))
+            .collect()
+    }
+
+    fn weights(&self) -> Vec<f64> {
+        let ways = self.frontier_ways(0);
+        ways.iter()
+            .zip(self.interior_ways())
+            .map(|(a, b)| a * b)
+            .collect()
+    }
+
+    fn any_fit(&self) -> bool {
+        self.weights().iter().any(|&weight| weight > 0.0)
+    }
+
+    // how many parts the frontier was searched in
+    pub fn parts(&self) -> usize {
+        self.parts.len()
+    }
+
+    // how many layouts of every mine fit, frontier and interior together
+    pub fn layouts(&self) -> f64 {
+        let interior = self.interior.len();
+        self.frontier_ways(0)
+            .iter()
+            .enumerate()
+            .filter(|&(placed, _)| placed <= self.mines && self.mines - placed <= interior)
+            .map(|(placed, ways)| ways * choose(interior, self.mines - placed))
+            .sum()
+    }
+
+    // the chance of each closed cell that isn't known being a mine, over
+    // every layout that fits; None if none do
+    pub fn mine_chances(&self) -> Option<Vec<(CellPosition, f64)>> {
+        let interior_ways = self.interior_ways();
+        let ways = self.frontier_ways(0);
+        let total: f64 = ways.iter().zip(&interior_ways).map(|(a, b)| a * b).sum();
+        if total == 0.0 {
+            return None;
+        }
+
+        let mut chances = vec![];
+        for (index, part) in self.parts.iter().enumerate() {
+            // the other parts' ways, to go with each of this part's layouts
+            let others = self
+                .parts
+                .iter()
+                .enumerate()
+                .filter(|&(other, _)| other != index);
+            let others = others.fold(vec![1.0], |ways, (_, other)| {
+                convolve(&other.counts(), &ways)
+            });
+            let by_layout: Vec<f64> = (0..=part.cells.len())
+                .map(|placed| {
+                    let rest = &interior_ways[placed..];
+                    others.iter().zip(rest).map(|(a, b)| a * b).sum()
+                })
+                .collect();
+
+            let mut mines = vec![0.0; part.cells.len()];
+            for layout in &part.layouts {
+                for (cell, _) in layout.iter().enumerate().filter(|(_, &mine)| mine) {
+                    mines[cell] += by_layout[placed(layout)];
+                }
+            }
+            chances.extend(
+                part.cells
+                    .iter()
+                    .zip(mines)
+                    .map(|(&at, ways)| (at, ways / total)),
+            );
+        }
+
+        // and of the mines beyond it, each cell there has as much chance as
+        // any other of being one
+        let interior = self.interior.len() as f64;
+        let beyond: f64 = (0..ways.len())
+            .map(|placed| {
+                ways[placed] * interior_ways[placed] * self.mines.saturating_sub(placed) as f64
+            })
+            .sum();
+        chances.extend(
+            self.interior
+                .iter()
+                .map(|&at| (at, beyond / interior / total)),
+        );
+        Some(chances)
+    }
+
+    // every layout of the frontier, as one of each part's, until `each`
+    // says stop
+    fn each_layout(&self, each: &mut dyn FnMut(&[usize]) -> bool) -> bool {
+        let mut picked = vec![0; self.parts.len()];
+        loop {
+            if !each(&picked) {
+                return false;
+            }
+            let more = |part: usize| picked[part] + 1 < self.parts[part].layouts.len();
+            let Some(index) = (0..picked.len()).rev().find(|&part| more(part)) else {
+                return true;
+            };
+            picked[index] += 1;
+            picked[index + 1..].fill(0);
+        }
+    }
+
+    fn mines<'a>(&'a self, picked: &'a [usize]) -> impl Iterator<Item = CellPosition> + 'a {
+        self.parts
+            .iter()
+            .zip(picked)
+            .flat_map(|(part, &layout)| part.mines(layout))
+    }
+
+    fn placed(&self, picked: &[usize]) -> usize {
+        self.parts
+            .iter()
+            .zip(picked)
+            .map(|(part, &layout)| placed(&part.layouts[layout]))
+            .sum()
+    }
+
+    // a layout of the frontier drawn at random, each as often as it would
+    // be the board's
+    fn sample(&self, rng: &mut impl Rng) -> Option<Vec<usize>> {
+        let mut rest = WeightedIndex::new(self.weights()).ok()?.sample(rng);
+
+        // then how many of those mines each part has, as likely as there
+        // are ways of placing the rest on the parts after it
+        let mut picked = vec![];
+        for (index, part) in self.parts.iter().enumerate() {
+            let after = self.frontier_ways(index + 1);
+            let weights = (0..=part.cells.len()).map(|placed| match rest.checked_sub(placed) {
+                Some(left) => part.by_mines[placed].len() as f64 * after.get(left).unwrap_or(&0.0),
+                None => 0.0,
+            });
+            let placed = WeightedIndex::new(weights).ok()?.sample(rng);
+            let layouts = &part.by_mines[placed];
+            picked.push(layouts[rng.gen_range(0..layouts.len())]);
+            rest -= placed;
+        }
+        Some(picked)
+    }
+}
+
+// the chance of clearing the board from here without guessing, estimated by
+// playing the solver through layouts of the mines that fit the open numbers
+// and the flags (which are taken to be right): all of them if there are few
+// enough, and otherwise ones drawn at random, each as often as it would be
+// the board. None if no layout fits, which means a flag is wrong
+pub fn estimate(
+    board: &Board,
+    budget: &Budget,
+    clock: &dyn Clock,
+    rng: &mut impl Rng,
+) -> Option<WinEstimate> {
+    let started = clock.now();
+    let deadline = Deadline {
+        clock,
+        at: started + budget.time,
+    };
+    let out_of_time = || clock.now() >= deadline.at;
+
+    let (position, search) = Position::of(board, ORDER)?;
+    let enumeration = Enumeration::of(&position, &search, true, Some(deadline));
+    let interior = position.interior.len();
+    let huge = interior > HUGE_AREA;
+
+    let mut estimate = WinEstimate {
+        winnable: 0,
+        layouts: 0,
+        accuracy: Accuracy::Sampled,
+    };
+    let mut play = |board: Board| {
+        estimate.layouts += 1;
+        estimate.winnable += usize::from(finishes(board));
+    };
+
+    if !enumeration.complete {
+        let frontier = search.cells();
+        for _ in 0..COARSE_LAYOUTS.min(budget.layouts) {
+            if let Some(layout) = search.any(rng) {
+                if position.fits(frontier, &layout) {
+                    let chosen = index::sample(rng, interior, position.mines - placed(&layout));
+                    let mines = frontier.iter().zip(&layout).filter(|(_, &mine)| mine);
+                    let mines = mines.map(|(&at, _)| at);
+                    play(position.layout(mines.chain(chosen.iter().map(|i| position.interior[i]))));
+                }
+            }
+            if out_of_time() {
+                break;
+            }
+        }
+        return Some(WinEstimate {
+            accuracy: Accuracy::Coarse,
+            ..estimate
+        });
+    }
+
+    if !enumeration.any_fit() {
+        return None;
+    }
+    if !huge && enumeration.layouts() <= budget.layouts as f64 {
+        let mut finished = true;
+        enumeration.each_layout(&mut |picked| {
+            let Some(rest) = position.mines.checked_sub(enumeration.placed(picked)) else {
+                return true;
+            };
+            if rest > interior {
+                return true;
+            }
+            finished = combinations(interior, rest, &mut |chosen| {
+                let chosen = chosen.iter().map(|&i| position.interior[i]);
+                play(position.layout(enumeration.mines(picked).chain(chosen)));
+                !out_of_time()
+            });
+            finished
+        });
+        let accuracy = if finished {
+            Accuracy::Exact
+        } else {
+            Accuracy::Coarse
+        };
+        return Some(WinEstimate {
+            accuracy,
+            ..estimate
+        });
+    }
+
+    let layouts_to_try = if huge { COARSE_LAYOUTS } else { budget.layouts };
+    for _ in 0..layouts_to_try.min(budget.layouts) {
+        let picked = enumeration.sample(rng)?;
+        let chosen = index::sample(rng, interior, position.mines - enumeration.placed(&picked));
+        let chosen = chosen.iter().map(|i| position.interior[i]);
+        play(position.layout(enumeration.mines(&picked).chain(chosen)));
+        if out_of_time() {
+            break;
+        }
+    }
+
+    if huge {
+        estimate.accuracy = Accuracy::Coarse;
+    }
+    Some(estimate)
+}
+
+fn percent(fraction: f64) -> String {
+    format!("{:.1}", fraction * 100.0)
+}
+
+pub fn render_estimate(estimate: Option<&WinEstimate>) -> String {
+    let Some(estimate) = estimate else {
+        return text(Msg::WinProbInconsistent).to_string();
+    };
+    let (chance, margin) = (percent(estimate.chance()), percent(estimate.margin()));
+    match estimate.accuracy {
+        _ if estimate.layouts == 0 => text(Msg::WinProbNone).to_string(),
+        Accuracy::Exact => fill(Msg::WinProbExact, &[&chance, &estimate.layouts]),
+        Accuracy::Sampled => fill(Msg::WinProbSampled, &[&chance, &margin, &estimate.layouts]),
+        Accuracy::Coarse => [
+            fill(Msg::WinProbCoarse, &[&chance, &margin, &estimate.layouts]),
+            text(Msg::WinProbCoarseWarning).to_string(),
+        ]
+        .join("\n"),
+    }
+}
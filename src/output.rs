@@ -0,0 +1,90 @@
+use crate::alert::ring_bell;
+use crate::banner::render_ending;
+use crate::config::{BannerStyle, Config, Theme};
+use crate::driver::{GameOutput, Screen};
+use crate::i18n::{text, Msg};
+use crate::render::{render_game, render_status};
+use std::env;
+use std::io::{stdout, IsTerminal};
+
+pub const CLEAR_ALL: &str = "\x1B[H\x1B[2J\x1B[3J";
+
+// what is sent to stdout beyond lines of ASCII, decided in one place from
+// the settings, the environment and where stdout goes, and gone by
+// everywhere that draws: a terminal gets what the settings ask for, a pipe
+// or a file gets no escapes, bell or banner, and `plain` gets none of it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OutputCapabilities {
+    pub color: bool,
+    pub theme: Theme,
+    pub banner: BannerStyle,
+    // whether there is anyone to hear the bell
+    pub bell: bool,
+    // clearing the screen, moving the cursor, and editing a line in place
+    pub clear: bool,
+}
+
+impl OutputCapabilities {
+    pub fn plain() -> Self {
+        OutputCapabilities {
+            color: false,
+            theme: Theme::Ascii,
+            banner: BannerStyle::None,
+            bell: false,
+            clear: false,
+        }
+    }
+
+    // `no_color` is whether NO_COLOR is set, and `terminal` whether stdout
+    // is one
+    pub fn of(config: &Config, no_color: bool, terminal: bool) -> Self {
+        if config.plain {
+            return OutputCapabilities::plain();
+        }
+        OutputCapabilities {
+            color: config.color && terminal && !no_color,
+            theme: config.theme,
+            banner: if terminal {
+                config.banner
+            } else {
+                BannerStyle::None
+            },
+            bell: terminal,
+            clear: terminal,
+        }
+    }
+
+    // for this run; NO_COLOR only counts when it isn't empty, as
+    // no-color.org asks
+    pub fn detect(config: &Config) -> Self {
+        let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        OutputCapabilities::of(config, no_color, stdout().is_terminal())
+    }
+}
+
+// prints to stdout, rendered with the player's settings
+pub struct StdoutOutput<'a> {
+    pub config: &'a Config,
+}
+
+impl GameOutput for StdoutOutput<'_> {
+    fn show(&mut self, screen: &Screen) {
+        match screen {
+            Screen::Board(game) => {
+                ring_bell(game, self.config);
+                println!("{}", render_status(game, self.config));
+                println!("{}\n------", render_game(game, self.config));
+            }
+            Screen::Revealed(game) => {
+                ring_bell(game, self.config);
+                println!("{}", render_ending(game, self.config));
+            }
+            Screen::Prompt(msg) => println!("{}", text(*msg)),
+            Screen::Message(message) => println!("{message}"),
+            Screen::Divider => println!("------"),
+            // the scrollback goes too, since it is full of boards
+            Screen::Paused if self.config.output.clear => print!("{CLEAR_ALL}"),
+            Screen::Paused => println!("{}", text(Msg::Paused)),
+        }
+    }
+}
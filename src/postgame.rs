@@ -0,0 +1,42 @@
+use crate::cast::write_cast;
+use crate::command::{Command, Export};
+use crate::config::Config;
+use crate::game::Game;
+use crate::i18n::{fill, text, Msg};
+use crate::mbf::write_mbf;
+use crate::reference::render_commands;
+use crate::share::{discord_block, share_block};
+use crate::transcript::write_transcript;
+use std::io::stdin;
+
+// once a game is over, lets the player share or export it before exiting
+pub fn run(game: &Game, code: &str, config: &Config) {
+    loop {
+        println!("{}", text(Msg::PostgamePrompt));
+
+        let mut line = String::new();
+        unwrap_or_continue!(stdin().read_line(&mut line));
+        if line.trim().is_empty() {
+            break;
+        }
+
+        match unwrap_or_continue!(line.parse()) {
+            Command::Share => println!("{}", share_block(game, code)),
+            Command::Help => println!("{}", render_commands()),
+            Command::Export(Export::Transcript(path)) => {
+                unwrap_or_continue!(write_transcript(game, code, &path));
+                println!("{}", fill(Msg::TranscriptWritten, &[&path.display()]));
+            }
+            Command::Export(Export::Discord) => println!("{}", discord_block(game, code)),
+            Command::Export(Export::Cast(path)) => {
+                unwrap_or_continue!(write_cast(game, code, config, &path));
+                println!("{}", fill(Msg::CastWritten, &[&path.display()]));
+            }
+            Command::Export(Export::Mbf(path)) => {
+                unwrap_or_continue!(write_mbf(game.board(), &path));
+                println!("{}", fill(Msg::MbfWritten, &[&path.display()]));
+            }
+            _ => println!("{}", text(Msg::GameIsOver)),
+        }
+    }
+}
@@ -0,0 +1,154 @@
+// the commands that ask about a game without changing it, answered for
+// whoever is on the other end of a protocol. Working out what the player
+// could see for themselves (how many cells are proved safe, which region
+// needs how many mines, the odds) needs the board, so this is the one place
+// a protocol's answers read it, and what they give back is only counts and
+// cells the player can already see; the protocols themselves get the game
+// as a GameView
+use crate::analysis::{
+    closed_regions, render_analysis, render_board_info, render_remaining, BoardInfo, Region,
+    Remaining,
+};
+use crate::board::{CellPosition, ChordError, ChordPreview};
+use crate::command::Command;
+use crate::config::Config;
+use crate::game::Game;
+use crate::i18n::{text, Msg};
+use crate::json::Value;
+use crate::odds::{render_estimate, Accuracy, WinEstimate};
+use crate::reference::render_commands;
+use crate::render::{render_chord_preview, render_hint_for, render_moves};
+use crate::share::share_block;
+use crate::view::position_json;
+
+fn board_info_json(info: &BoardInfo) -> Value {
+    Value::object([
+        ("height", Value::from(info.height)),
+        ("width", Value::from(info.width)),
+        ("mines", Value::from(info.mines)),
+        ("density", Value::from(info.density())),
+        ("zeros", Value::from(info.zeros)),
+        ("openings", Value::from(info.openings)),
+        ("three_bv", Value::from(info.three_bv)),
+    ])
+}
+
+// what `chord?` sees, or why the cell can't be chorded at all
+fn chord_preview_json(position: CellPosition, preview: Result<ChordPreview, ChordError>) -> Value {
+    match preview {
+        Ok(preview) => Value::object([(
+            "chord_preview",
+            Value::object([
+                ("cell", position_json(position)),
+                ("satisfied", Value::from(preview.satisfied())),
+                ("flags", Value::from(preview.flags)),
+                ("number", Value::from(u64::from(preview.bombs_around))),
+                (
+                    "targets",
+                    Value::Array(preview.targets.into_iter().map(position_json).collect()),
+                ),
+            ]),
+        )]),
+        Err(e) => Value::object([("error", Value::from(e.to_string()))]),
+    }
+}
+
+fn estimate_json(estimate: &WinEstimate) -> Value {
+    let accuracy = match estimate.accuracy {
+        Accuracy::Exact => "exact",
+        Accuracy::Sampled => "sampled",
+        Accuracy::Coarse => "coarse",
+    };
+    Value::object([
+        ("chance", Value::from(estimate.chance())),
+        ("margin", Value::from(estimate.margin())),
+        ("winnable", Value::from(estimate.winnable)),
+        ("layouts", Value::from(estimate.layouts)),
+        ("accuracy", Value::from(accuracy)),
+    ])
+}
+
+fn regions_json(regions: &[Region]) -> Value {
+    Value::Array(
+        regions
+            .iter()
+            .map(|region| {
+                Value::object([
+                    (
+                        "cells",
+                        Value::Array(region.cells.iter().map(|&at| position_json(at)).collect()),
+                    ),
+                    ("frontier", Value::from(region.frontier)),
+                    ("numbers", Value::from(region.numbers)),
+                    ("mines_needed", Value::from(region.mines_needed)),
+                ])
+            })
+            .collect(),
+    )
+}
+
+// the line that answers `command`, as text or with `json` as an object, or
+// the error to report instead; None if it isn't a query
+pub fn answer(
+    config: &Config,
+    game: &Game,
+    code: &str,
+    command: &Command,
+    json: bool,
+) -> Option<Result<String, String>> {
+    let board = game.board();
+    let line = match *command {
+        Command::Share if json => {
+            Value::object([("share", Value::from(share_block(game, code)))]).to_string()
+        }
+        Command::Share => share_block(game, code),
+        Command::Help if json => {
+            Value::object([("help", Value::from(render_commands().as_str()))]).to_string()
+        }
+        Command::Help => render_commands(),
+        Command::BoardInfo if json => {
+            Value::object([("boardinfo", board_info_json(&BoardInfo::of(board)))]).to_string()
+        }
+        Command::BoardInfo => render_board_info(&BoardInfo::of(board), None),
+        Command::Analyze if json => {
+            Value::object([("regions", regions_json(&closed_regions(board)))]).to_string()
+        }
+        Command::Analyze => render_analysis(board),
+        Command::Remaining if json => {
+            let remaining = Remaining::of(board);
+            let remaining = Value::object([
+                ("safe", Value::from(remaining.safe)),
+                ("mines", Value::from(remaining.mines)),
+            ]);
+            Value::object([("remaining", remaining)]).to_string()
+        }
+        Command::Remaining => render_remaining(&Remaining::of(board)),
+        Command::ChordCheck(position) if json => {
+            chord_preview_json(position, board.chord_preview(position)).to_string()
+        }
+        Command::ChordCheck(position) => {
+            render_chord_preview(position, board.chord_preview(position))
+        }
+        Command::WinProb if json => match WinEstimate::of(board, &*game.clock()) {
+            Some(estimate) => Value::object([("winprob", estimate_json(&estimate))]).to_string(),
+            None => return Some(Err(text(Msg::WinProbInconsistent).to_string())),
+        },
+        Command::WinProb => render_estimate(WinEstimate::of(board, &*game.clock()).as_ref()),
+        Command::Hint { why } if json => {
+            let hint = render_hint_for(config, board, why);
+            let lines = hint.lines().map(|line| Value::from(line.trim())).collect();
+            Value::object([("hint", Value::Array(lines))]).to_string()
+        }
+        Command::Hint { why } => render_hint_for(config, board, why),
+        Command::Moves { all } if json => {
+            let moves = render_moves(game.log(), all)
+                .iter()
+                .map(|line| Value::from(line.as_str()))
+                .collect();
+            Value::object([("moves", Value::Array(moves))]).to_string()
+        }
+        Command::Moves { all } => render_moves(game.log(), all).join("\n"),
+        _ => return None,
+    };
+    Some(Ok(line))
+}
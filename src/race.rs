@@ -0,0 +1,160 @@
+use crate::board::{Board, CellPosition, ClearError};
+use crate::command::Field;
+use crate::config::Config;
+use crate::driver::{parse_index, prompt_with, PromptError};
+use crate::i18n::{fill, text, Msg};
+use crate::input::{self, StdinInput};
+use crate::output::StdoutOutput;
+use crate::render::render_board;
+use rand::random;
+use std::io::stdin;
+
+const CLEAR_SCREEN: &str = "\x1B[2J\x1B[1;1H";
+
+struct Player {
+    name: String,
+    board: Board,
+    moves: usize,
+}
+
+enum TurnResult {
+    Continue,
+    Finished,
+    HitBomb,
+    // the input ended, or kept giving answers that weren't cells
+    Aborted,
+}
+
+// a row and then a column; a mistake in either asks for it again, so that a
+// wrong column keeps the row
+fn prompt_cell(board: &Board, config: &Config) -> Result<CellPosition, PromptError> {
+    let mut output = StdoutOutput { config };
+    let retries = input::piped_retries();
+    let mut ask = |msg, field, length| {
+        prompt_with(&mut StdinInput, &mut output, msg, retries, |line| {
+            parse_index(line, field, length)
+        })
+    };
+
+    Ok(CellPosition {
+        row_index: ask(Msg::EnterRowIndex, Field::Row, board.height())?,
+        col_index: ask(Msg::EnterColIndex, Field::Column, board.width())?,
+    })
+}
+
+impl Player {
+    fn new(name: String, board: Board) -> Self {
+        Player {
+            name,
+            board,
+            moves: 0,
+        }
+    }
+
+    fn take_turn(&mut self, config: &Config) -> TurnResult {
+        loop {
+            println!(
+                "{}\n{}\n------",
+                fill(Msg::RaceBoard, &[&self.name]),
+                render_board(&self.board, config)
+            );
+
+            let position = match prompt_cell(&self.board, config) {
+                Ok(position) => position,
+                Err(e @ PromptError::Io(_)) => {
+                    println!("{e}");
+                    continue;
+                }
+                Err(PromptError::Eof) => return TurnResult::Aborted,
+                Err(e) => {
+                    println!("{e}");
+                    return TurnResult::Aborted;
+                }
+            };
+
+            match self.board.clear(position) {
+                Ok(_) => (),
+                Err(ClearError::ClearedBomb) => {
+                    self.moves += 1;
+                    return TurnResult::HitBomb;
+                }
+                Err(e) => {
+                    println!("{e}");
+                    continue;
+                }
+            };
+
+            self.moves += 1;
+
+            return if self.board.is_won() {
+                TurnResult::Finished
+            } else {
+                println!("{}\n------", render_board(&self.board, config));
+                TurnResult::Continue
+            };
+        }
+    }
+}
+
+// blanks the screen and waits, so that the next player can't study the
+// previous player's board; output that can't be cleared only waits
+fn hand_over(message: &str, config: &Config) {
+    let clear = if config.output.clear {
+        CLEAR_SCREEN
+    } else {
+        ""
+    };
+    print!("{clear}");
+    println!("{message}");
+    // a read error here just means we skip the pause
+    let _ = stdin().read_line(&mut String::new());
+    print!("{clear}");
+}
+
+// two players race on copies of the same board, alternating one move at a
+// time; the first to clear their board wins, and hitting a bomb hands the
+// win to the other player
+pub fn play(config: &Config, seed: Option<u64>) -> String {
+    let seed = seed.unwrap_or_else(random);
+    let board = config.board(seed);
+
+    let mut players = [
+        Player::new(fill(Msg::PlayerName, &[&1]), board.clone()),
+        Player::new(fill(Msg::PlayerName, &[&2]), board),
+    ];
+
+    let mut current = 0;
+
+    let winner = loop {
+        hand_over(&fill(Msg::RaceStartTurn, &[&players[current].name]), config);
+
+        match players[current].take_turn(config) {
+            TurnResult::Continue => (),
+            TurnResult::Finished => break Some(current),
+            TurnResult::HitBomb => {
+                println!("{}", fill(Msg::RaceHitBomb, &[&players[current].name]));
+                break Some(1 - current);
+            }
+            TurnResult::Aborted => break None,
+        }
+
+        hand_over(text(Msg::RaceHandOver), config);
+        current = 1 - current;
+    };
+
+    for player in &players {
+        println!(
+            "{}",
+            fill(
+                Msg::RaceStats,
+                &[&player.name, &player.moves, &player.board.count_open()]
+            )
+        );
+    }
+    println!("{}", fill(Msg::RaceSeed, &[&seed]));
+
+    match winner {
+        Some(winner) => fill(Msg::PlayerWins, &[&players[winner].name]),
+        None => text(Msg::RaceAborted).to_string(),
+    }
+}
@@ -0,0 +1,73 @@
+// the commands as the player is shown them, all from command::COMMANDS:
+// the list `help` prints at the prompt, which --help prints too, and the
+// longer reference --print-reference prints
+use crate::command::{commands, CommandSpec};
+use crate::i18n::{fill, text, Msg};
+
+// where the list's descriptions start, and how wide its lines can get
+const COLUMN: usize = 25;
+const WIDTH: usize = 80;
+
+// the names and the arguments, e.g. "c, clear <cell> | row <row> | ..."
+pub fn usage(spec: &CommandSpec) -> String {
+    let names = spec.names().collect::<Vec<&str>>().join(", ");
+    match spec.args {
+        "" => names,
+        args => format!("{names} {args}"),
+    }
+}
+
+// `words` in lines no wider than WIDTH, each indented by `indent`
+fn wrap(words: &str, indent: usize) -> Vec<String> {
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in words.split_whitespace() {
+        let width = indent + line.chars().count() + 1 + word.chars().count();
+        if !line.is_empty() && width > WIDTH {
+            lines.push(format!("{:indent$}{line}", ""));
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    lines.push(format!("{:indent$}{line}", ""));
+    lines
+}
+
+// each command's usage with what it does beside it, or under it when the
+// usage is too long to leave room, and then what a <cell> is
+pub fn render_commands() -> String {
+    let mut lines = vec![];
+    for spec in commands() {
+        let usage = usage(spec);
+        let mut description = wrap(text(spec.description), COLUMN);
+        if usage.chars().count() < COLUMN - 3 {
+            let first = description.remove(0);
+            lines.push(format!(
+                "  {usage:<width$}{}",
+                first.trim_start(),
+                width = COLUMN - 2
+            ));
+        } else {
+            lines.push(format!("  {usage}"));
+        }
+        lines.extend(description);
+    }
+    lines.push(String::new());
+    lines.extend(wrap(text(Msg::CommandsNote), 2));
+    lines.join("\n")
+}
+
+// a page in the manner of a man page, with an example of each command
+pub fn render_reference() -> String {
+    let mut entries = vec![wrap(text(Msg::CommandsNote), 4).join("\n")];
+    for spec in commands() {
+        let mut entry = vec![format!("    {}", usage(spec))];
+        entry.extend(wrap(text(spec.description), 8));
+        entry.extend(wrap(&fill(Msg::ReferenceExample, &[&spec.example]), 8));
+        entries.push(entry.join("\n"));
+    }
+    fill(Msg::Reference, &[&entries.join("\n\n")])
+}
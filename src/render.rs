@@ -0,0 +1,527 @@
+use crate::analysis::{render_hint, Remaining};
+use crate::board::{
+    Board, Cell, CellPosition, CellState, ChordError, ChordPreview, FlagChange, InvariantViolation,
+};
+use crate::config::{AssistLevel, Config, Theme};
+use crate::game::{CascadeSummary, Game, GameEvent, LoggedMove, Move, MoveOutcome, WinCondition};
+use crate::i18n::{fill, text, Msg};
+use crate::layout::CellLayout;
+use crate::timer::format_duration;
+use std::cmp::Reverse;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+const RESET: &str = "\x1B[0m";
+
+struct Glyphs {
+    closed: &'static str,
+    flag: &'static str,
+    // a flag on a safe cell, only shown once the board is revealed
+    wrong_flag: &'static str,
+    bomb: &'static str,
+    exploded: &'static str,
+    // a mine a shield took the blast of
+    defused: &'static str,
+    rock: &'static str,
+    question: &'static str,
+    // closed cells whose peek said what they are
+    peeked_mine: &'static str,
+    peeked_safe: &'static str,
+    // a cell that can't be, see `Cell::check`
+    broken: &'static str,
+    digits: [&'static str; 9],
+}
+
+const ASCII: Glyphs = Glyphs {
+    closed: "#",
+    flag: "^",
+    wrong_flag: "x",
+    bomb: "@",
+    exploded: "*",
+    defused: "+",
+    rock: "%",
+    question: "?",
+    peeked_mine: "!",
+    peeked_safe: "~",
+    broken: "E",
+    digits: ["0", "1", "2", "3", "4", "5", "6", "7", "8"],
+};
+
+const EMOJI: Glyphs = Glyphs {
+    closed: "⬜",
+    flag: "🚩",
+    wrong_flag: "❌",
+    bomb: "💣",
+    exploded: "💥",
+    defused: "🟦",
+    rock: "⬛",
+    question: "❓",
+    peeked_mine: "🟥",
+    peeked_safe: "🟩",
+    broken: "🚫",
+    // fullwidth digits take up two columns, like the emoji around them
+    digits: ["０", "１", "２", "３", "４", "５", "６", "７", "８"],
+};
+
+impl Glyphs {
+    // every glyph a cell can be drawn with
+    fn all(&self) -> impl Iterator<Item = &'static str> {
+        [
+            self.closed,
+            self.flag,
+            self.wrong_flag,
+            self.bomb,
+            self.exploded,
+            self.defused,
+            self.rock,
+            self.question,
+            self.peeked_mine,
+            self.peeked_safe,
+            self.broken,
+        ]
+        .into_iter()
+        .chain(self.digits)
+    }
+}
+
+fn glyphs(theme: Theme) -> &'static Glyphs {
+    match theme {
+        Theme::Ascii => &ASCII,
+        Theme::Emoji => &EMOJI,
+    }
+}
+
+// how the theme's cells line up: as wide as its widest glyph, with a space
+// between ASCII cells and none between emoji, which are spaced already
+pub fn cell_layout(theme: Theme) -> CellLayout {
+    let gap = match theme {
+        Theme::Ascii => 1,
+        Theme::Emoji => 0,
+    };
+    CellLayout::fit(glyphs(theme).all(), gap)
+}
+
+// a cell no game leaves as it is, which a damaged save or an editor can:
+// said once on stderr, and drawn as the error mark so that the board can
+// still be shown and put right; with debug-checks it panics instead, as a
+// board contradicting itself does everywhere else
+fn warn_broken(e: &InvariantViolation) {
+    static WARNED: AtomicBool = AtomicBool::new(false);
+    if cfg!(feature = "debug-checks") {
+        panic!("{e}");
+    }
+    if !WARNED.swap(true, Ordering::Relaxed) {
+        eprintln!("{}", fill(Msg::CellDrawnBroken, &[e]));
+    }
+}
+
+// revealing shows where every bomb is, and which flags were wrong; it is
+// only meant for boards whose game is over
+fn glyph(cell: &Cell, theme: Theme, reveal: bool) -> &'static str {
+    let glyphs = glyphs(theme);
+    if let Err(e) = cell.check() {
+        warn_broken(&e);
+        return glyphs.broken;
+    }
+
+    match cell.state {
+        CellState::Rock => glyphs.rock,
+        CellState::Bomb { exploded: true, .. } if cell.defused => glyphs.defused,
+        CellState::Bomb { exploded: true, .. } => glyphs.exploded,
+        CellState::Bomb { flagged: true, .. } => glyphs.flag,
+        CellState::Bomb { .. } if reveal => glyphs.bomb,
+        CellState::Safe { flagged: true, .. } if reveal => glyphs.wrong_flag,
+        CellState::Safe { flagged: true, .. } => glyphs.flag,
+        CellState::Safe { open: true, .. } => glyphs.digits[usize::from(cell.bombs_around).min(8)],
+        CellState::Bomb { .. } if cell.peeked => glyphs.peeked_mine,
+        _ if cell.peeked => glyphs.peeked_safe,
+        _ if cell.question => glyphs.question,
+        _ => glyphs.closed,
+    }
+}
+
+// the usual minesweeper palette: a distinct colour per number, red mines
+fn color_code(cell: &Cell, reveal: bool) -> Option<&'static str> {
+    match cell.state {
+        CellState::Rock => Some("90"),
+        CellState::Bomb { exploded: true, .. } if cell.defused => Some("1;36"),
+        CellState::Bomb { exploded: true, .. } => Some("1;31"),
+        CellState::Bomb { flagged: true, .. } | CellState::Safe { flagged: true, .. } => Some("33"),
+        CellState::Bomb { .. } if reveal => Some("31"),
+        CellState::Safe { open: true, .. } => match cell.bombs_around {
+            1 => Some("34"),
+            2 => Some("32"),
+            3 => Some("31"),
+            4 => Some("35"),
+            5 => Some("33"),
+            6 => Some("36"),
+            7 => Some("90"),
+            8 => Some("37"),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// padded to the theme's cell width, so that it lines up whatever it is
+pub fn render_cell(cell: &Cell, config: &Config, reveal: bool) -> String {
+    let glyph = cell_layout(config.output.theme).pad(glyph(cell, config.output.theme, reveal));
+
+    match color_code(cell, reveal) {
+        Some(code) if config.output.color => format!("\x1B[{code}m{glyph}{RESET}"),
+        _ => glyph,
+    }
+}
+
+// a note shows wherever the cell would otherwise look closed
+pub fn render_noted(cell: &Cell, note: Option<char>, config: &Config) -> String {
+    let theme = config.output.theme;
+    match note {
+        Some(note) if glyph(cell, theme, false) == glyphs(theme).closed => {
+            cell_layout(theme).pad(&note.to_string())
+        }
+        _ => render_cell(cell, config, false),
+    }
+}
+
+fn render(board: &Board, notes: &[(CellPosition, char)], config: &Config, reveal: bool) -> String {
+    let layout = cell_layout(config.output.theme);
+    let note = |cell: &Cell| {
+        notes
+            .iter()
+            .find(|&&(noted, _)| noted == cell.position)
+            .map(|&(_, note)| note)
+    };
+
+    board
+        .rows()
+        .map(|row| {
+            layout.row(row.iter().map(|cell| match note(cell) {
+                Some(note) if !reveal => render_noted(cell, Some(note), config),
+                _ => render_cell(cell, config, reveal),
+            }))
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// the board as the player sees it, following the configured theme and colours
+pub fn render_board(board: &Board, config: &Config) -> String {
+    render(board, &[], config, false)
+}
+
+// the board with the player's notes on it
+pub fn render_game(game: &Game, config: &Config) -> String {
+    render(game.board(), game.notes(), config, false)
+}
+
+// the board with every bomb shown, for once the game is over
+pub fn render_revealed(board: &Board, config: &Config) -> String {
+    render(board, &[], config, true)
+}
+
+// every cell as it is under, a number on each safe one and every mine
+// shown, drawn as the revealed board is; for an answer key, never for the
+// terminal
+pub fn render_key(board: &Board, config: &Config) -> String {
+    let mut key = board.clone();
+    let positions: Vec<CellPosition> = board.rows().flatten().map(|cell| cell.position).collect();
+    for position in positions {
+        let Some(cell) = key.get_cell_mut(position) else {
+            continue;
+        };
+        cell.state = match cell.state {
+            CellState::Safe { .. } => CellState::Safe {
+                flagged: false,
+                open: true,
+            },
+            CellState::Bomb { .. } => CellState::Bomb {
+                flagged: false,
+                exploded: false,
+            },
+            CellState::Rock => CellState::Rock,
+        };
+        (cell.question, cell.peeked, cell.defused) = (false, false, false);
+    }
+    render(&key, &[], config, true)
+}
+
+// minutes:seconds to a tenth of a second
+pub fn format_tenths(duration: Duration) -> String {
+    let tenths = duration.as_millis() / 100;
+    format!("{}:{:02}.{}", tenths / 600, tenths / 10 % 60, tenths % 10)
+}
+
+// the moves the player took longest over, slowest first, with the command
+// that was entered; None if no move was timed
+pub fn render_slowest(game: &Game, count: usize) -> Option<String> {
+    let mut timed: Vec<_> = game
+        .moves()
+        .iter()
+        .filter(|made| !made.thinking.is_zero())
+        .collect();
+    if timed.is_empty() {
+        return None;
+    }
+
+    // stable, so the earlier move comes first among equals
+    timed.sort_by_key(|made| Reverse(made.thinking));
+    let mut report = text(Msg::SlowestMoves).to_string();
+    for made in timed.into_iter().take(count) {
+        report += &format!("\n{:>9}  {}", format_tenths(made.thinking), made.command);
+    }
+    Some(report)
+}
+
+// the one-line summary shown above the board every turn; fields only show
+// up when they mean something (lives in lives modes, the time once it has
+// started and if the timer is enabled, what can be proven with an assist)
+pub fn render_status(game: &Game, config: &Config) -> String {
+    let board = game.board();
+
+    let mut fields = vec![
+        fill(Msg::StatusMines, &[&board.mines_left()]),
+        fill(Msg::StatusFlags, &[&board.count_flags()]),
+    ];
+
+    if game.initial_lives() > 1 {
+        fields.push(fill(Msg::StatusLives, &[&game.lives()]));
+    }
+
+    // a variant, rather than the usual rules
+    if let Some(moving) = game.moving_mines() {
+        fields.push(fill(Msg::StatusMovingMines, &[&moving.every]));
+    }
+
+    if game.win_condition() == WinCondition::FlagAll {
+        fields.push(text(Msg::StatusFlagWin).to_string());
+    }
+
+    if game.has_shields() {
+        fields.push(fill(Msg::StatusShields, &[&game.shields()]));
+    }
+
+    if game.initial_peeks() > 0 {
+        fields.push(fill(Msg::PeeksLeft, &[&game.peeks_left()]));
+    }
+
+    if config.assist != AssistLevel::None {
+        let remaining = Remaining::of(board);
+        fields.push(fill(
+            Msg::StatusRemaining,
+            &[&remaining.safe, &remaining.mines],
+        ));
+    }
+
+    if config.timer && !game.moves().is_empty() {
+        fields.push(fill(Msg::StatusTime, &[&format_duration(game.elapsed())]));
+    }
+
+    // where the earlier run had got to by now, against the game's own moves
+    if let Some(ghost) = game.ghost() {
+        fields.push(fill(
+            Msg::StatusGhost,
+            &[&ghost.moves_by(game.elapsed()), &ghost.moves()],
+        ));
+    }
+
+    fields.push(fill(Msg::StatusMoves, &[&game.moves().len()]));
+
+    fields.join("  ")
+}
+
+// a hint, if the assist level gives them
+pub fn render_hint_for(config: &Config, board: &Board, why: bool) -> String {
+    match config.assist {
+        AssistLevel::None => text(Msg::HintsOff).to_string(),
+        AssistLevel::Hints | AssistLevel::Full => render_hint(board, why),
+    }
+}
+
+// the one-line confirmation after marking a cell, so that the player can
+// tell whether a mark went on or came off
+pub fn render_mark(change: FlagChange, position: CellPosition, board: &Board) -> String {
+    match change {
+        FlagChange::FlagPlaced => fill(Msg::FlagPlacedAt, &[&position, &board.mines_left()]),
+        FlagChange::FlagRemoved => fill(Msg::FlagRemovedFrom, &[&position, &board.mines_left()]),
+        FlagChange::QuestionPlaced => fill(Msg::QuestionPlacedAt, &[&position]),
+        FlagChange::QuestionRemoved => fill(Msg::QuestionRemovedFrom, &[&position]),
+    }
+}
+
+// a line of feedback for moves whose effect is hard to read off the board
+pub fn render_feedback(game_move: &Move, board: &Board) -> Option<String> {
+    match (game_move.mark(), game_move.outcome) {
+        (Some((change, position)), _) => Some(render_mark(change, position, board)),
+        (
+            None,
+            MoveOutcome::AreaOpened { .. } | MoveOutcome::Peeked(_) | MoveOutcome::Defused(_),
+        ) => Some(game_move.to_string()),
+        _ => None,
+    }
+}
+
+pub fn render_cascade(summary: &CascadeSummary) -> String {
+    let regions = match summary.regions {
+        1 => text(Msg::CascadeOneRegion).to_string(),
+        regions => fill(Msg::CascadeRegions, &[&regions]),
+    };
+    let numbered = match summary.numbered {
+        1 => text(Msg::CascadeOneNumber).to_string(),
+        numbered => fill(Msg::CascadeNumbers, &[&numbered]),
+    };
+    fill(Msg::CascadeOpened, &[&summary.opened, &regions, &numbered])
+}
+
+// how many moves `moves` lists without `all`
+pub const RECENT_MOVES: usize = 5;
+
+// a line for each entry of the move log, or for only the last RECENT_MOVES
+pub fn render_moves(log: &[LoggedMove], all: bool) -> Vec<String> {
+    if log.is_empty() {
+        return vec![text(Msg::NoMovesYet).to_string()];
+    }
+    let shown = if all {
+        log
+    } else {
+        &log[log.len().saturating_sub(RECENT_MOVES)..]
+    };
+
+    shown
+        .iter()
+        .map(|logged| {
+            let msg = if logged.undone {
+                Msg::LoggedMoveUndone
+            } else {
+                Msg::LoggedMove
+            };
+            fill(msg, &[&logged.number, &logged.game_move])
+        })
+        .collect()
+}
+
+// what chording `position` would do, as `chord?` answers; a wrong flag
+// looks like any other, so it can't be told apart from a right one
+pub fn render_chord_preview(
+    position: CellPosition,
+    preview: Result<ChordPreview, ChordError>,
+) -> String {
+    match preview {
+        Err(e) => e.to_string(),
+        Ok(preview) if !preview.satisfied() => fill(
+            Msg::ChordPreviewUnsatisfied,
+            &[&position, &preview.bombs_around, &preview.flags],
+        ),
+        Ok(preview) if preview.targets.is_empty() => fill(Msg::ChordPreviewNothing, &[&position]),
+        Ok(preview) => {
+            let targets = preview
+                .targets
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            fill(
+                Msg::ChordPreviewClears,
+                &[&position, &targets.len(), &targets.join(", ")],
+            )
+        }
+    }
+}
+
+pub fn render_note(position: CellPosition, note: Option<char>) -> String {
+    match note {
+        Some(note) => fill(Msg::NotePlacedAt, &[&note, &position]),
+        None => fill(Msg::NoteRemovedFrom, &[&position]),
+    }
+}
+
+// feedback on the latest move, if any; `every_move` is for when several
+// moves run from one line, where a plain clear would otherwise pass without
+// a word
+pub fn render_last_move(game: &Game, every_move: bool) -> Option<String> {
+    // a note isn't a move, so the last move has nothing to do with it
+    if let &[GameEvent::NoteChanged(position, note)] = game.events() {
+        return Some(render_note(position, note));
+    }
+    let last = game.moves().last()?;
+    let feedback = match render_feedback(last, game.board()) {
+        None if every_move => Some(last.to_string()),
+        feedback => feedback,
+    };
+
+    let notices: Vec<String> = game
+        .events()
+        .iter()
+        .filter_map(|event| match event {
+            // a cell opened on its own says all there is to say by itself
+            GameEvent::CellsOpened(cells) if cells.len() > 1 => {
+                Some(render_cascade(&CascadeSummary::of(cells, game.board())))
+            }
+            GameEvent::ShieldEarned(shields) => Some(fill(Msg::ShieldEarned, &[shields])),
+            GameEvent::MinesMoved(count) => Some(fill(Msg::MinesMoved, &[count])),
+            _ => None,
+        })
+        .collect();
+    let lines: Vec<String> = feedback.into_iter().chain(notices).collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::Command;
+    use crate::stats::tests::SetByHand;
+    use std::sync::Arc;
+
+    fn at(row_index: usize, col_index: usize) -> CellPosition {
+        CellPosition::new(row_index, col_index)
+    }
+
+    // asked at `prompted` and answered `thinking` seconds later
+    fn timed(game: &mut Game, clock: &SetByHand, prompted: u64, thinking: u64, command: Command) {
+        clock.set(prompted);
+        game.prompted();
+        clock.set(prompted + thinking);
+        game.answered(None);
+        game.apply(0, command).expect("The move should be played");
+    }
+
+    #[test]
+    fn the_slowest_moves_are_listed_slowest_first() {
+        let board: Board = "..*\n*.*\n**.".parse().expect("The layout should parse");
+        let clock = Arc::new(SetByHand::default());
+        let mut game = Game::with_clock(board, 1, clock.clone());
+        assert_eq!(render_slowest(&game, 5), None);
+
+        timed(&mut game, &clock, 0, 4, Command::Flag(at(0, 2)));
+        timed(&mut game, &clock, 10, 12, Command::Flag(at(1, 0)));
+        timed(&mut game, &clock, 30, 1, Command::Clear(at(0, 0)));
+        timed(&mut game, &clock, 40, 12, Command::Flag(at(1, 2)));
+        timed(&mut game, &clock, 60, 0, Command::Clear(at(0, 1)));
+
+        // 3 seconds before pausing and 4 after resuming, and none of the
+        // hour in between
+        timed(&mut game, &clock, 70, 3, Command::Pause);
+        clock.set(3673);
+        game.apply(0, Command::Resume)
+            .expect("The game should resume");
+        timed(&mut game, &clock, 3673, 4, Command::Clear(at(1, 1)));
+        timed(&mut game, &clock, 3680, 2, Command::Clear(at(2, 2)));
+        assert_eq!(game.state(), crate::game::GameState::Won);
+
+        let report = render_slowest(&game, 5).expect("The moves were timed");
+        let expected = [
+            "Slowest moves:",
+            "   0:12.0  f 1 0",
+            "   0:12.0  f 1 2",
+            "   0:07.0  c 1 1",
+            "   0:04.0  f 0 2",
+            "   0:02.0  c 2 2",
+        ];
+        assert_eq!(report, expected.join("\n"));
+        // the untimed clear is never listed, however many are asked for
+        assert_eq!(
+            render_slowest(&game, 10).map(|r| r.lines().count()),
+            Some(7)
+        );
+    }
+}
@@ -0,0 +1,18 @@
+use crate::board::Board;
+use crate::game::{Game, Move};
+
+// replays the move log of a game from its starting position, returning the
+// board as it was after each move
+pub fn replay(game: &Game) -> Vec<(&Move, Board)> {
+    let mut replayed = game.restart();
+
+    game.moves()
+        .iter()
+        .map(|game_move| {
+            replayed
+                .apply(game_move.player, game_move.command.clone())
+                .expect("Moves in the log should be valid when replayed in order");
+            (game_move, replayed.board().clone())
+        })
+        .collect()
+}
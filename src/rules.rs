@@ -0,0 +1,381 @@
+use crate::board::{
+    Board, CellState, Layout, LimitError, LineLimits, MineDistribution, Mines, Symmetry,
+};
+use crate::game::{Game, MovingMines, WinCondition};
+use crate::i18n::{fill, text, Msg};
+use std::fmt::Display;
+
+// the most cells a board can have unless the builder is told otherwise,
+// well short of what would take long to lay out or fill the memory
+pub const MAX_CELLS: usize = 1_000_000;
+
+// everything a game is played by, from the size of the board to how it is
+// won; only made by `RulesetBuilder::build`, which checks the rules against
+// each other, or read back from a game
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ruleset {
+    height: usize,
+    width: usize,
+    layout: Layout,
+    lives: u32,
+    peeks: u32,
+    shields: bool,
+    win: WinCondition,
+    // every how many moves the mines move, and what fraction of them
+    moving: Option<(usize, f64)>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum RulesetError {
+    NoCells,
+    // more cells than `max`, or more than can be counted
+    TooBig {
+        height: usize,
+        width: usize,
+        max: usize,
+    },
+    NoLives,
+    TooManyMines {
+        mines: usize,
+        cells: usize,
+    },
+    TooManyRocks {
+        rocks: usize,
+        free: usize,
+    },
+    // an odd number of mines on a board where every cell has a distinct image
+    UnevenSymmetry {
+        mines: usize,
+    },
+    Limits(LimitError),
+    StencilSize {
+        stencil: (usize, usize),
+        board: (usize, usize),
+    },
+    // two rules that each lay the mines out their own way
+    Conflicting(&'static str, &'static str),
+    InvalidFraction(f64),
+}
+
+impl Display for RulesetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let out = match self {
+            RulesetError::NoCells => text(Msg::RulesNoCells).to_string(),
+            RulesetError::TooBig { height, width, max } => {
+                fill(Msg::RulesTooBig, &[height, width, max])
+            }
+            RulesetError::NoLives => text(Msg::RulesNoLives).to_string(),
+            RulesetError::TooManyMines { mines, cells } => {
+                fill(Msg::ConfigTooManyMines, &[mines, cells])
+            }
+            RulesetError::TooManyRocks { rocks, free } => {
+                fill(Msg::ConfigTooManyRocks, &[rocks, free])
+            }
+            RulesetError::UnevenSymmetry { mines } => fill(Msg::ConfigUnevenSymmetry, &[mines]),
+            RulesetError::Limits(e) => e.to_string(),
+            RulesetError::StencilSize { stencil, board } => fill(
+                Msg::ConfigStencilSize,
+                &[&stencil.0, &stencil.1, &board.0, &board.1],
+            ),
+            RulesetError::Conflicting(first, second) => {
+                fill(Msg::ConfigConflicting, &[first, second])
+            }
+            RulesetError::InvalidFraction(fraction) => fill(Msg::RulesInvalidFraction, &[fraction]),
+        };
+
+        write!(f, "{out}")
+    }
+}
+
+// the rules one at a time, starting from the classic ones: a beginner's
+// board, one life, and nothing else
+#[derive(Debug, Clone)]
+pub struct RulesetBuilder {
+    rules: Ruleset,
+    max_cells: usize,
+}
+
+impl Default for RulesetBuilder {
+    fn default() -> Self {
+        RulesetBuilder {
+            rules: Ruleset {
+                height: 9,
+                width: 9,
+                layout: Layout::new(Mines::Count(10)),
+                lives: 1,
+                peeks: 0,
+                shields: false,
+                win: WinCondition::OpenAll,
+                moving: None,
+            },
+            max_cells: MAX_CELLS,
+        }
+    }
+}
+
+impl RulesetBuilder {
+    pub fn size(mut self, height: usize, width: usize) -> Self {
+        self.rules.height = height;
+        self.rules.width = width;
+        self
+    }
+
+    // the most cells the board can have
+    pub fn max_cells(mut self, max_cells: usize) -> Self {
+        self.max_cells = max_cells;
+        self
+    }
+
+    pub fn mines(mut self, mines: Mines) -> Self {
+        self.rules.layout.mines = mines;
+        self
+    }
+
+    pub fn symmetry(mut self, symmetry: Symmetry) -> Self {
+        self.rules.layout.symmetry = symmetry;
+        self
+    }
+
+    pub fn limits(mut self, limits: LineLimits) -> Self {
+        self.rules.layout.limits = limits;
+        self
+    }
+
+    pub fn distribution(mut self, distribution: MineDistribution) -> Self {
+        self.rules.layout.distribution = distribution;
+        self
+    }
+
+    pub fn rocks(mut self, rocks: usize) -> Self {
+        self.rules.layout.rocks = rocks;
+        self
+    }
+
+    pub fn lives(mut self, lives: u32) -> Self {
+        self.rules.lives = lives;
+        self
+    }
+
+    pub fn peeks(mut self, peeks: u32) -> Self {
+        self.rules.peeks = peeks;
+        self
+    }
+
+    pub fn shields(mut self, shields: bool) -> Self {
+        self.rules.shields = shields;
+        self
+    }
+
+    pub fn win(mut self, win: WinCondition) -> Self {
+        self.rules.win = win;
+        self
+    }
+
+    // every `every` moves, `fraction` of the mines move; 0 for never
+    pub fn moving_mines(mut self, every: usize, fraction: f64) -> Self {
+        self.rules.moving = (every > 0).then_some((every, fraction));
+        self
+    }
+
+    // the rules, once they have been checked against each other
+    pub fn build(self) -> Result<Ruleset, RulesetError> {
+        let rules = self.rules;
+        let Layout {
+            mines,
+            symmetry,
+            limits,
+            distribution,
+            rocks,
+        } = &rules.layout;
+
+        let (height, width) = (rules.height, rules.width);
+        let cells = match height.checked_mul(width) {
+            Some(0) => return Err(RulesetError::NoCells),
+            Some(cells) if cells <= self.max_cells => cells,
+            _ => {
+                let max = self.max_cells;
+                return Err(RulesetError::TooBig { height, width, max });
+            }
+        };
+        if rules.lives == 0 {
+            return Err(RulesetError::NoLives);
+        }
+        if let Some((_, fraction)) = rules.moving {
+            if !(0.0..=1.0).contains(&fraction) {
+                return Err(RulesetError::InvalidFraction(fraction));
+            }
+        }
+
+        let shapers = [
+            ("symmetry", *symmetry != Symmetry::None),
+            match distribution {
+                MineDistribution::Stencil(_) => ("stencil", true),
+                _ => ("distribution", *distribution != MineDistribution::Uniform),
+            },
+            ("min_per_row", !limits.is_none()),
+        ];
+        let mut chosen = shapers
+            .iter()
+            .filter(|(_, chosen)| *chosen)
+            .map(|(key, _)| *key);
+        if let (Some(first), Some(second)) = (chosen.next(), chosen.next()) {
+            return Err(RulesetError::Conflicting(first, second));
+        }
+        if let MineDistribution::Stencil(stencil) = distribution {
+            let stencil = (stencil.height(), stencil.width());
+            if stencil != (rules.height, rules.width) {
+                let board = (rules.height, rules.width);
+                return Err(RulesetError::StencilSize { stencil, board });
+            }
+        }
+        if !limits.is_none() {
+            limits
+                .check(rules.height, rules.width, mines.count_on(cells))
+                .map_err(RulesetError::Limits)?;
+        }
+        // a density as the mines it comes to, as the board is laid out
+        let count = mines.count_on(cells);
+        match *mines {
+            _ if count >= cells => Err(RulesetError::TooManyMines {
+                mines: count,
+                cells,
+            }),
+            // at least one cell has to be left to open
+            _ if *rocks >= cells - count => Err(RulesetError::TooManyRocks {
+                rocks: *rocks,
+                free: cells - count,
+            }),
+            Mines::Count(mines)
+                if mines % 2 == 1 && !symmetry.has_axis_cells(rules.height, rules.width) =>
+            {
+                Err(RulesetError::UnevenSymmetry { mines })
+            }
+            _ => Ok(rules),
+        }
+    }
+}
+
+impl Default for Ruleset {
+    fn default() -> Self {
+        Ruleset::classic()
+    }
+}
+
+impl Ruleset {
+    pub fn builder() -> RulesetBuilder {
+        RulesetBuilder::default()
+    }
+
+    pub fn classic() -> Self {
+        Ruleset::builder()
+            .build()
+            .expect("The classic rules should be consistent")
+    }
+
+    // the rules a game is being played by, which are the rules it was
+    // started with, with mines and rocks as many as its board has however
+    // they were laid out
+    pub fn of(game: &Game) -> Self {
+        let board = game.initial_board();
+        let rocks = board
+            .rows()
+            .flatten()
+            .filter(|cell| matches!(cell.state, CellState::Rock))
+            .count();
+        let mut layout = Layout::new(Mines::Count(board.count_bombs()));
+        layout.rocks = rocks;
+
+        Ruleset {
+            height: board.height(),
+            width: board.width(),
+            layout,
+            lives: game.initial_lives(),
+            peeks: game.initial_peeks(),
+            shields: game.has_shields(),
+            win: game.win_condition(),
+            moving: game
+                .moving_mines()
+                .map(|moving| (moving.every, moving.fraction)),
+        }
+    }
+
+    // the same rules, to change some of them
+    pub fn to_builder(&self) -> RulesetBuilder {
+        RulesetBuilder {
+            rules: self.clone(),
+            ..RulesetBuilder::default()
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    pub fn lives(&self) -> u32 {
+        self.lives
+    }
+
+    pub fn peeks(&self) -> u32 {
+        self.peeks
+    }
+
+    pub fn shields(&self) -> bool {
+        self.shields
+    }
+
+    pub fn win(&self) -> WinCondition {
+        self.win
+    }
+
+    // the board these rules lay out from `seed`
+    pub fn board(&self, seed: u64) -> Board {
+        Board::generate(seed, self.height, self.width, &self.layout)
+    }
+
+    // how the mines move in a game seeded with `seed`, if they do
+    pub fn moving_mines(&self, seed: u64) -> Option<MovingMines> {
+        self.moving.map(|(every, fraction)| MovingMines {
+            every,
+            fraction,
+            seed,
+        })
+    }
+
+    // the rules besides the board's size and mines, as kept with each game
+    // in the history, so that only games played by the same rules are
+    // compared; classic rules are "classic"
+    pub fn key(&self) -> String {
+        let mut rules = vec![];
+        if self.lives != 1 {
+            rules.push(format!("lives={}", self.lives));
+        }
+        if self.peeks > 0 {
+            rules.push(format!("peeks={}", self.peeks));
+        }
+        if self.shields {
+            rules.push(String::from("shields"));
+        }
+        if self.win == WinCondition::FlagAll {
+            rules.push(String::from("flag_win"));
+        }
+        if let Some((every, fraction)) = self.moving {
+            rules.push(format!("moving_mines={every}:{fraction}"));
+        }
+        if self.layout.rocks > 0 {
+            rules.push(format!("rocks={}", self.layout.rocks));
+        }
+        if rules.is_empty() {
+            String::from("classic")
+        } else {
+            rules.join(",")
+        }
+    }
+}
@@ -0,0 +1,331 @@
+use crate::achievements::{announce, award};
+use crate::analysis::{
+    render_board_info, render_difficulty, render_efficiency, BoardInfo, Efficiency,
+};
+use crate::autosave;
+use crate::board::{CellPosition, CellState, Mines};
+use crate::cast::write_cast;
+use crate::command::{cell_name, Command, Export};
+use crate::config::Config;
+use crate::driver::{
+    self, is_yes, play, GameConfig, GameInput, GameOutput, Hooks, Io, Screen, Verdict,
+};
+use crate::dump::debug_dump;
+use crate::game::{Game, GameOutcome, GameState};
+use crate::history::{self, Record};
+use crate::i18n::{fill, text, Msg};
+use crate::input;
+use crate::mbf::write_mbf;
+use crate::render::{
+    render_chord_preview, render_hint_for, render_last_move, render_moves, render_slowest,
+};
+use crate::save::Mode;
+use crate::saves::save_named;
+use crate::share::share_block;
+use crate::solver::bot_move;
+use crate::stats::{record_splits, StatsFile};
+use crate::transcript::write_transcript;
+#[cfg(feature = "crash-test")]
+use std::env;
+use std::io::{stdin, IsTerminal};
+use std::thread;
+use std::time::Duration;
+
+// how many moves `moves all` shows at a time at a terminal
+const MOVES_PAGE: usize = 20;
+
+// for testing the crash report: with the `crash-test` feature, a game
+// panics once it has had as many moves as this says
+#[cfg(feature = "crash-test")]
+const PANIC_AFTER_VAR: &str = "MINESWEEPER_TEST_PANIC_AFTER";
+
+pub struct GameOptions<'a> {
+    pub game: Game,
+    pub code: String,
+    // off for games that shouldn't touch the autosave file, such as scripted
+    // ones
+    pub autosave: bool,
+    // likewise for the best splits in the stats file
+    pub stats: bool,
+    // see `read_move`
+    pub idle_limit: Option<Duration>,
+    // what the board was generated from, if known
+    pub requested: Option<Mines>,
+    // how a recording of the game is drawn
+    pub config: &'a Config,
+}
+
+// the cell a command acts on, with how to say what it does to it
+fn target(command: &Command) -> Option<(Msg, CellPosition)> {
+    match *command {
+        Command::Clear(position) => Some((Msg::EchoClearing, position)),
+        Command::Flag(position) => Some((Msg::EchoFlagging, position)),
+        Command::Chord(position) => Some((Msg::EchoChording, position)),
+        Command::Question(position) => Some((Msg::EchoQuestioning, position)),
+        Command::Peek(position) => Some((Msg::EchoPeeking, position)),
+        _ => None,
+    }
+}
+
+fn next_to_flag(game: &Game, position: CellPosition) -> bool {
+    game.board().cells_around(position).any(|cell| {
+        cell.position != position
+            && matches!(
+                cell.state,
+                CellState::Bomb { flagged: true, .. } | CellState::Safe { flagged: true, .. }
+            )
+    })
+}
+
+// says which cell a command is about to act on, and has the player confirm
+// clearing one next to a flag, each if the config asks for it
+pub fn check_target(game: &Game, command: &Command, io: &mut Io, config: &Config) -> Verdict {
+    let Some((verb, position)) = target(command) else {
+        return Verdict::Allow;
+    };
+    if config.echo_moves {
+        io.output.show(&Screen::Message(fill(
+            verb,
+            &[&position, &cell_name(position)],
+        )));
+    }
+    let clearing = matches!(command, Command::Clear(_));
+    if !(config.confirm_near_flags && clearing && next_to_flag(game, position)) {
+        return Verdict::Allow;
+    }
+
+    io.output
+        .show(&Screen::Message(fill(Msg::ConfirmNearFlag, &[&position])));
+    if matches!(io.input.read_command(), Ok(Some(answer)) if is_yes(&answer)) {
+        return Verdict::Allow;
+    }
+    io.output
+        .show(&Screen::Message(text(Msg::MoveLeftUndone).to_string()));
+    Verdict::Veto
+}
+
+// the move log, a page at a time at a terminal so that a long one doesn't
+// scroll away; anything but an empty line stops it
+fn show_moves(game: &Game, all: bool, input: &mut dyn GameInput, output: &mut dyn GameOutput) {
+    let lines = render_moves(game.log(), all);
+    let paged = stdin().is_terminal();
+
+    for (page, chunk) in lines.chunks(MOVES_PAGE).enumerate() {
+        if page > 0 && paged {
+            output.show(&Screen::Prompt(Msg::MoreMoves));
+            if !matches!(input.read_command(), Ok(Some(line)) if line.trim().is_empty()) {
+                break;
+            }
+        }
+        output.show(&Screen::Message(chunk.join("\n")));
+    }
+}
+
+// the solver plays on from the board as it stands, a move at a time with
+// `delay` between them, until the game is over or the next move would be a
+// guess; the player then keeps its moves, which makes the game assisted, or
+// has the game back as it was
+fn watch(game: &mut Game, input: &mut dyn GameInput, output: &mut dyn GameOutput, delay: Duration) {
+    let before = game.clone();
+    let mut moves = 0;
+    while game.state() == GameState::Playing {
+        let Some(next) = bot_move(game.board()) else {
+            break;
+        };
+        if let Err(e) = play(game, 0, next.command.clone()) {
+            output.show(&Screen::Message(e));
+            break;
+        }
+        moves += 1;
+        output.show(&Screen::Board(game));
+        output.show(&Screen::Message(fill(
+            Msg::SolverMove,
+            &[&next.command, &next.deduction],
+        )));
+        thread::sleep(delay);
+    }
+
+    let end = match (moves, game.state()) {
+        (0, _) => {
+            output.show(&Screen::Message(text(Msg::SolverNothingProven).to_string()));
+            return;
+        }
+        (_, GameState::Playing) => Msg::SolverNeedsGuess,
+        _ => Msg::SolverFinished,
+    };
+    output.show(&Screen::Message(fill(end, &[&moves])));
+
+    output.show(&Screen::Prompt(Msg::KeepSolverMoves));
+    if matches!(input.read_command(), Ok(Some(answer)) if is_yes(&answer)) {
+        game.mark_solver_played();
+        output.show(&Screen::Message(text(Msg::SolverMovesKept).to_string()));
+    } else {
+        *game = before;
+        output.show(&Screen::Message(text(Msg::SolverMovesUndone).to_string()));
+    }
+}
+
+// the commands only the binary can carry out, for writing files and the
+// like, and those it shows its own way; false for the rest. Only a single
+// game can be watched, dumped or exported before it is over
+pub fn carry_out(
+    game: &mut Game,
+    command: &Command,
+    io: &mut Io,
+    mode: Mode,
+    code: &str,
+    config: &Config,
+    requested: Option<Mines>,
+) -> Result<bool, String> {
+    let single = mode == Mode::Single;
+    let shown = match command {
+        Command::Watch if !single => text(Msg::WatchNotHere).to_string(),
+        Command::Export(_) if !single => text(Msg::ExportsAfterGame).to_string(),
+        Command::Dump if !single => return Ok(false),
+        Command::Share => share_block(game, code),
+        Command::BoardInfo => render_board_info(&BoardInfo::of(game.board()), requested),
+        Command::ChordCheck(position) => {
+            render_chord_preview(*position, game.board().chord_preview(*position))
+        }
+        Command::Hint { why } => render_hint_for(config, game.board(), *why),
+        Command::Moves { all } => {
+            show_moves(game, *all, io.input, io.output);
+            return Ok(true);
+        }
+        Command::Dump => debug_dump(game, code),
+        Command::SaveAs(name) => save_named(name, mode, code, game),
+        Command::Watch => {
+            watch(game, io.input, io.output, config.watch_delay());
+            return Ok(true);
+        }
+        Command::Export(Export::Transcript(path)) => {
+            write_transcript(game, code, path).map_err(|e| e.to_string())?;
+            fill(Msg::TranscriptWritten, &[&path.display()])
+        }
+        // it would give the board away
+        Command::Export(Export::Discord) => text(Msg::ExportsAfterGame).to_string(),
+        Command::Export(Export::Cast(path)) => {
+            write_cast(game, code, config, path).map_err(|e| e.to_string())?;
+            fill(Msg::CastWritten, &[&path.display()])
+        }
+        Command::Export(Export::Mbf(path)) => {
+            write_mbf(game.board(), path).map_err(|e| e.to_string())?;
+            fill(Msg::MbfWritten, &[&path.display()])
+        }
+        _ => return Ok(false),
+    };
+
+    io.output.show(&Screen::Message(shown));
+    Ok(true)
+}
+
+// the library's game loop, with the binary's autosave, files, settings and
+// records hooked in
+pub fn run_game(
+    input: &mut dyn GameInput,
+    output: &mut dyn GameOutput,
+    options: GameOptions,
+) -> GameOutcome {
+    let GameOptions {
+        game,
+        code,
+        autosave,
+        stats,
+        idle_limit,
+        requested,
+        config,
+    } = options;
+    let code = code.as_str();
+    let save = |game: &Game| {
+        if autosave {
+            autosave::save(Mode::Single, code, game);
+        }
+        #[cfg(feature = "crash-test")]
+        if env::var(PANIC_AFTER_VAR).is_ok_and(|moves| moves == game.moves().len().to_string()) {
+            panic!("{PANIC_AFTER_VAR} was reached");
+        }
+    };
+
+    // before the first move only, so not again when a save is loaded
+    if config.difficulty && game.moves().is_empty() {
+        let report = game.initial_board().difficulty_report();
+        output.show(&Screen::Message(render_difficulty(&report)));
+    }
+
+    let after_move = |game: &Game, command: &Command, several: bool, io: &mut Io| {
+        save(game);
+        let feedback = match command {
+            Command::Rewind(moves) => Some(fill(Msg::Rewound, &[moves])),
+            _ => render_last_move(game, several),
+        };
+        if let Some(feedback) = feedback {
+            io.output.show(&Screen::Message(feedback));
+        }
+    };
+    let on_command = |game: &mut Game, command: &Command, io: &mut Io| {
+        let handled = carry_out(game, command, io, Mode::Single, code, config, requested);
+        // the solver's moves are the game's to keep
+        if matches!(command, Command::Watch) {
+            save(game);
+        }
+        handled
+    };
+    // an aborted game stays autosaved so it can be resumed
+    let on_game_end = |game: &Game, outcome: GameOutcome, io: &mut Io| {
+        let output = &mut *io.output;
+        if outcome != GameOutcome::Aborted {
+            if autosave {
+                autosave::discard();
+            }
+            let board_info = render_board_info(&BoardInfo::of(game.board()), requested);
+            output.show(&Screen::Message(board_info));
+            // a lost game didn't clear its 3BV, so there is nothing to measure
+            if let GameOutcome::Won(won) = outcome {
+                let efficiency = Efficiency::of(game);
+                let line = render_efficiency(&efficiency, won.elapsed, config.flag_scoring);
+                output.show(&Screen::Message(line));
+            }
+
+            if stats {
+                let mut kept = StatsFile::load();
+                if let Some(splits) = record_splits(&mut kept, game) {
+                    output.show(&Screen::Message(splits));
+                }
+                for achievement in award(&mut kept, outcome, game.moves()) {
+                    output.show(&Screen::Message(announce(achievement)));
+                }
+                kept.save();
+
+                if let Some(record) = Record::new(game, outcome, code, config.flag_scoring) {
+                    if let Err(e) = history::append(&record) {
+                        output.show(&Screen::Message(fill(Msg::CouldntWriteHistory, &[&e])));
+                    }
+                }
+            }
+            if let Some(slowest) = render_slowest(game, 5) {
+                output.show(&Screen::Message(slowest));
+            }
+        }
+        if config.debug_dump {
+            output.show(&Screen::Message(debug_dump(game, code)));
+        }
+    };
+
+    let hooks = Hooks {
+        on_move: Some(Box::new(|game: &Game, command: &Command, io: &mut Io| {
+            check_target(game, command, io, config)
+        })),
+        after_move: Some(Box::new(after_move)),
+        on_command: Some(Box::new(on_command)),
+        on_board_render: None,
+        on_game_end: Some(Box::new(on_game_end)),
+    };
+    let setup = GameConfig {
+        game,
+        retries: input::piped_retries(),
+        idle_limit,
+        prompt: Msg::EnterRowOrCommand,
+        players: 1,
+    };
+    driver::run_game(setup, Io { input, output }, hooks)
+}
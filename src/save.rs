@@ -0,0 +1,294 @@
+use crate::board::{Board, LayoutError, Mines};
+use crate::command::Command;
+use crate::game::{Game, MovingMines, WinCondition};
+use crate::i18n::{fill, text, Msg};
+use crate::rules::{Ruleset, RulesetError};
+use crate::versions::{parse_version, unchanged, upgrade, Format, VersionError};
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::Duration;
+
+const HEADER: &str = "minesweeper save";
+
+// the version written after the header
+pub const VERSION: u32 = 2;
+
+// each from the version before to the next, applied to what follows the
+// version line
+const MIGRATIONS: [fn(String) -> String; VERSION as usize - 1] = [
+    // 2 began with the version line
+    unchanged,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+    Single,
+    Coop,
+    Daily,
+    Weekly,
+}
+
+impl Display for Mode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let out = match self {
+            Mode::Single => "single",
+            Mode::Coop => "coop",
+            Mode::Daily => "daily",
+            Mode::Weekly => "weekly",
+        };
+
+        write!(f, "{out}")
+    }
+}
+
+impl FromStr for Mode {
+    type Err = SaveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "single" => Ok(Mode::Single),
+            "coop" => Ok(Mode::Coop),
+            "daily" => Ok(Mode::Daily),
+            "weekly" => Ok(Mode::Weekly),
+            other => Err(SaveError::Malformed(fill(Msg::SaveUnknownMode, &[&other]))),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Malformed(String),
+    Version(VersionError),
+}
+
+impl Display for SaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SaveError::Malformed(detail) => write!(f, "{}", fill(Msg::MalformedSave, &[detail])),
+            SaveError::Version(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+pub struct SavedGame {
+    pub mode: Mode,
+    pub code: String,
+    pub game: Game,
+}
+
+// the save stores the starting layout and the move log rather than the
+// current board, so loading replays the game and ends up with exactly the
+// same state (including the undo history)
+pub fn serialize(mode: Mode, code: &str, game: &Game) -> String {
+    let layout = game.initial_board().layout();
+
+    let moves = game
+        .moves()
+        .iter()
+        .map(|game_move| format!("{} {}\n", game_move.player, game_move.command))
+        .collect::<String>();
+
+    // only written for games that have them, so other saves read as before
+    let peeks = match game.initial_peeks() {
+        0 => String::new(),
+        peeks => format!("peeks {peeks}\n"),
+    };
+    let shields = if game.has_shields() { "shields\n" } else { "" };
+    let flag_win = match game.win_condition() {
+        WinCondition::FlagAll => "flag_win\n",
+        WinCondition::OpenAll => "",
+    };
+    let moving = match game.moving_mines() {
+        Some(MovingMines {
+            every,
+            fraction,
+            seed,
+        }) => format!("moving_mines {every} {fraction} {seed}\n"),
+        None => String::new(),
+    };
+    let solver = if game.solver_played() {
+        "solver_played\n"
+    } else {
+        ""
+    };
+    let rewound = if game.rewound() { "rewound\n" } else { "" };
+    // the notes on the board as it is now, which aren't in the move log
+    let notes = game
+        .notes()
+        .iter()
+        .map(|&(position, note)| {
+            format!(
+                "note {} {} {note}\n",
+                position.row_index, position.col_index
+            )
+        })
+        .collect::<String>();
+
+    format!(
+        "{HEADER}\nversion {VERSION}\nmode {mode}\ncode {code}\nsize {}x{}\nlives {}\n{peeks}{shields}{flag_win}{moving}{solver}{rewound}{notes}elapsed {}\nlayout\n{layout}\nmoves\n{moves}",
+        game.initial_board().height(),
+        game.initial_board().width(),
+        game.initial_lives(),
+        game.elapsed().as_secs()
+    )
+}
+
+fn field<'a>(lines: &mut impl Iterator<Item = &'a str>, name: &str) -> Result<&'a str, SaveError> {
+    lines
+        .next()
+        .and_then(|line| line.strip_prefix(name))
+        .and_then(|rest| rest.strip_prefix(' '))
+        .ok_or_else(|| SaveError::Malformed(fill(Msg::SaveExpectedField, &[&name])))
+}
+
+fn number<T: FromStr>(value: &str, name: &str) -> Result<T, SaveError> {
+    value
+        .parse()
+        .map_err(|_| SaveError::Malformed(fill(Msg::SaveInvalidField, &[&name])))
+}
+
+pub fn deserialize(contents: &str) -> Result<SavedGame, SaveError> {
+    let mut lines = contents.lines().peekable();
+
+    if lines.next() != Some(HEADER) {
+        return Err(SaveError::Malformed(
+            text(Msg::SaveMissingHeader).to_string(),
+        ));
+    }
+    // a save from before versions were written is version 1
+    let version = match lines.peek() {
+        Some(line) if line.starts_with("version ") => {
+            parse_version(field(&mut lines, "version")?).map_err(SaveError::Version)?
+        }
+        _ => 1,
+    };
+    let rest = lines.collect::<Vec<&str>>().join("\n");
+    let rest = upgrade(Format::Save, version, rest, &MIGRATIONS).map_err(SaveError::Version)?;
+    let mut lines = rest.lines().peekable();
+
+    let mode: Mode = field(&mut lines, "mode")?.parse()?;
+    let code = field(&mut lines, "code")?.to_string();
+    let (height, width) = field(&mut lines, "size")?
+        .split_once('x')
+        .ok_or_else(|| SaveError::Malformed(fill(Msg::SaveInvalidField, &[&"size"])))?;
+    let height: usize = number(height, "height")?;
+    let width: usize = number(width, "width")?;
+    let lives: u32 = number(field(&mut lines, "lives")?, "lives")?;
+    let peeks: u32 = match lines.peek() {
+        Some(line) if line.starts_with("peeks ") => number(field(&mut lines, "peeks")?, "peeks")?,
+        _ => 0,
+    };
+    let shielded = lines.next_if_eq(&"shields").is_some();
+    let win_condition = match lines.next_if_eq(&"flag_win") {
+        Some(_) => WinCondition::FlagAll,
+        None => WinCondition::OpenAll,
+    };
+    let moving = match lines.peek() {
+        Some(line) if line.starts_with("moving_mines ") => {
+            let fields: Vec<&str> = field(&mut lines, "moving_mines")?.split(' ').collect();
+            let [every, fraction, seed] = fields[..] else {
+                return Err(SaveError::Malformed(fill(
+                    Msg::SaveInvalidField,
+                    &[&"moving_mines"],
+                )));
+            };
+            Some(MovingMines {
+                every: number(every, "moving_mines")?,
+                fraction: number(fraction, "moving_mines")?,
+                seed: number(seed, "moving_mines")?,
+            })
+        }
+        _ => None,
+    };
+    let solver_played = lines.next_if_eq(&"solver_played").is_some();
+    let rewound = lines.next_if_eq(&"rewound").is_some();
+    let mut notes = vec![];
+    while lines.peek().is_some_and(|line| line.starts_with("note ")) {
+        let note = field(&mut lines, "note")?;
+        match Command::from_str(&format!("note {note}")) {
+            Ok(Command::Note(position, Some(note))) => notes.push((position, note)),
+            _ => return Err(SaveError::Malformed(fill(Msg::SaveInvalidNote, &[&note]))),
+        }
+    }
+    let elapsed: u64 = number(field(&mut lines, "elapsed")?, "elapsed")?;
+
+    if lines.next() != Some("layout") {
+        return Err(SaveError::Malformed(fill(
+            Msg::SaveExpectedField,
+            &[&"layout"],
+        )));
+    }
+
+    // read a line at a time, so that a size far too big for the file can't
+    // make it ask for more memory than there is
+    let mut layout = vec![];
+    for _ in 0..height {
+        let line = lines
+            .next()
+            .ok_or_else(|| SaveError::Malformed(text(Msg::SaveLayoutTooShort).to_string()))?;
+        layout.push(line);
+    }
+    let board: Board = layout
+        .join("\n")
+        .parse()
+        .map_err(|e: LayoutError| SaveError::Malformed(e.to_string()))?;
+    if board.width() != width {
+        return Err(SaveError::Malformed(
+            text(Msg::SaveLayoutWrongLength).to_string(),
+        ));
+    }
+
+    if lines.next() != Some("moves") {
+        return Err(SaveError::Malformed(fill(
+            Msg::SaveExpectedField,
+            &[&"moves"],
+        )));
+    }
+
+    // checked as the command line and the config are, so that a file edited
+    // by hand can't start a game those would have turned down; the board
+    // is laid out already, at whatever size max_cells allowed then
+    let (every, fraction) = moving.map_or((0, 0.0), |moving| (moving.every, moving.fraction));
+    Ruleset::builder()
+        .size(height, width)
+        .max_cells(usize::MAX)
+        .mines(Mines::Count(board.count_bombs()))
+        .rocks(board.count_rocks())
+        .lives(lives)
+        .peeks(peeks)
+        .shields(shielded)
+        .win(win_condition)
+        .moving_mines(every, fraction)
+        .build()
+        .map_err(|e: RulesetError| SaveError::Malformed(e.to_string()))?;
+
+    let mut game = Game::new(board, lives)
+        .with_peeks(peeks)
+        .shielded(shielded)
+        .with_moving_mines(moving)
+        .with_win_condition(win_condition);
+    for line in lines.filter(|line| !line.trim().is_empty()) {
+        let (player, command) = line
+            .split_once(' ')
+            .ok_or_else(|| SaveError::Malformed(fill(Msg::SaveInvalidMove, &[&line])))?;
+        let player: usize = number(player, "player")?;
+        let command: Command = command
+            .parse()
+            .map_err(|e| SaveError::Malformed(fill(Msg::SaveInvalidMoveBecause, &[&line, &e])))?;
+        game.apply(player, command)
+            .map_err(|e| SaveError::Malformed(fill(Msg::SaveMoveNotReplayed, &[&line, &e])))?;
+    }
+    for (position, note) in notes {
+        game.annotate(position, Some(note))
+            .map_err(|e| SaveError::Malformed(fill(Msg::SaveNoteNotKept, &[&position, &e])))?;
+    }
+    if solver_played {
+        game.mark_solver_played();
+    }
+    if rewound {
+        game.mark_rewound();
+    }
+    game.restore_elapsed(Duration::from_secs(elapsed));
+
+    Ok(SavedGame { mode, code, game })
+}
@@ -0,0 +1,302 @@
+use crate::board::CellState;
+use crate::dirs::data_dir;
+use crate::game::Game;
+use crate::history::format_timestamp;
+use crate::i18n::{fill, text, Msg};
+use crate::json::{self, Value};
+use crate::save::{deserialize, serialize, Mode, SaveError, SavedGame};
+use std::cmp::Reverse;
+use std::fmt::Display;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SAVES_DIR: &str = "saves";
+const INDEX_FILE: &str = "index.jsonl";
+
+// the longest a save's file name gets before its extension
+const MAX_STEM: usize = 40;
+
+// names Windows won't give a file, whatever the extension
+const RESERVED: [&str; 22] = [
+    "con", "prn", "aux", "nul", "com1", "com2", "com3", "com4", "com5", "com6", "com7", "com8",
+    "com9", "lpt1", "lpt2", "lpt3", "lpt4", "lpt5", "lpt6", "lpt7", "lpt8", "lpt9",
+];
+
+// a named save, as the index lists it; the index keeps what the list shows,
+// so that listing doesn't read every save
+#[derive(Debug, Clone, PartialEq)]
+pub struct SaveEntry {
+    pub name: String,
+    // in the saves directory, from the name made safe for a file name
+    pub file: String,
+    // seconds since the Unix epoch
+    pub saved: u64,
+    pub height: usize,
+    pub width: usize,
+    // the percentage of the safe cells open
+    pub progress: usize,
+}
+
+impl SaveEntry {
+    fn to_json(&self) -> Value {
+        Value::object([
+            ("name", Value::from(self.name.as_str())),
+            ("file", Value::from(self.file.as_str())),
+            ("saved", Value::from(self.saved)),
+            ("height", Value::from(self.height)),
+            ("width", Value::from(self.width)),
+            ("progress", Value::from(self.progress)),
+        ])
+    }
+
+    fn from_json(value: &Value) -> Option<SaveEntry> {
+        let text = |key| value.get(key).and_then(Value::as_str).map(str::to_string);
+        let number = |key| value.get(key).and_then(Value::as_u64);
+        Some(SaveEntry {
+            name: text("name")?,
+            file: text("file")?,
+            saved: number("saved")?,
+            height: number("height")? as usize,
+            width: number("width")? as usize,
+            progress: number("progress")? as usize,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum SlotError {
+    NoDataDir,
+    // neither a number in the list nor the name of a save
+    NotFound(String),
+    Io(io::Error),
+    Save(SaveError),
+}
+
+impl Display for SlotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let out = match self {
+            SlotError::NoDataDir => text(Msg::NoDataDir).to_string(),
+            SlotError::NotFound(save) => fill(Msg::NoSuchSave, &[save]),
+            SlotError::Io(e) => e.to_string(),
+            SlotError::Save(e) => e.to_string(),
+        };
+
+        write!(f, "{out}")
+    }
+}
+
+impl From<io::Error> for SlotError {
+    fn from(e: io::Error) -> Self {
+        SlotError::Io(e)
+    }
+}
+
+fn saves_dir() -> Result<PathBuf, SlotError> {
+    data_dir()
+        .map(|dir| dir.join(SAVES_DIR))
+        .ok_or(SlotError::NoDataDir)
+}
+
+// the name as a file name that is safe on every platform: letters and digits
+// lowercased, anything else run together into one underscore, and cut short
+fn sanitize(name: &str) -> String {
+    let mut stem = String::new();
+    for c in name.chars().flat_map(char::to_lowercase) {
+        if c.is_ascii_alphanumeric() || c == '-' {
+            stem.push(c);
+        } else if !stem.is_empty() && !stem.ends_with('_') {
+            stem.push('_');
+        }
+    }
+    let mut stem: String = stem.trim_end_matches('_').chars().take(MAX_STEM).collect();
+
+    if stem.is_empty() {
+        stem = String::from("save");
+    } else if RESERVED.contains(&stem.as_str()) {
+        stem.push('_');
+    }
+    stem
+}
+
+// the first of stem.txt, stem-2.txt, ... that no other save has; compared
+// without case, for filesystems that don't tell case apart
+fn unique_file(stem: &str, entries: &[SaveEntry]) -> String {
+    let taken = |file: &str| {
+        entries
+            .iter()
+            .any(|entry| entry.file.eq_ignore_ascii_case(file))
+    };
+    (1..)
+        .map(|n| match n {
+            1 => format!("{stem}.txt"),
+            n => format!("{stem}-{n}.txt"),
+        })
+        .find(|file| !taken(file))
+        .expect("Some suffix should be free")
+}
+
+// in the order they were saved, oldest first; a line that can't be read is
+// skipped with a warning, so that one bad line doesn't hide the rest
+fn read_index(dir: &Path) -> Result<Vec<SaveEntry>, SlotError> {
+    let contents = match fs::read_to_string(dir.join(INDEX_FILE)) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(SlotError::Io(e)),
+    };
+
+    Ok(contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter_map(|(index, line)| {
+            let entry = json::parse(line)
+                .ok()
+                .and_then(|value| SaveEntry::from_json(&value));
+            if entry.is_none() {
+                println!("{}", fill(Msg::SkippingSaveIndexLine, &[&(index + 1)]));
+            }
+            entry
+        })
+        .collect())
+}
+
+// written whole to a temporary file first, as the autosave is
+fn write_file(path: &Path, contents: &str) -> io::Result<()> {
+    let temp = path.with_extension("tmp");
+    fs::write(&temp, contents)?;
+    fs::rename(temp, path)
+}
+
+fn write_index(dir: &Path, entries: &[SaveEntry]) -> io::Result<()> {
+    let contents = entries
+        .iter()
+        .map(|entry| format!("{}\n", entry.to_json()))
+        .collect::<String>();
+    write_file(&dir.join(INDEX_FILE), &contents)
+}
+
+fn progress(game: &Game) -> usize {
+    let board = game.board();
+    let safe = board
+        .rows()
+        .flatten()
+        .filter(|cell| matches!(cell.state, CellState::Safe { .. }))
+        .count();
+    (board.count_open() * 100).checked_div(safe).unwrap_or(100)
+}
+
+// every save, newest first, as numbered by `load`
+pub fn list() -> Result<Vec<SaveEntry>, SlotError> {
+    let mut entries = read_index(&saves_dir()?)?;
+    entries.reverse();
+    entries.sort_by_key(|entry| Reverse(entry.saved));
+    Ok(entries)
+}
+
+// the save `save` picks out of the list: its number there, or else its name
+pub fn find<'a>(entries: &'a [SaveEntry], save: &str) -> Option<&'a SaveEntry> {
+    let by_number = save
+        .parse::<usize>()
+        .ok()
+        .and_then(|number| entries.get(number.checked_sub(1)?));
+    by_number.or_else(|| entries.iter().find(|entry| entry.name == save))
+}
+
+// keeps the game under `name`, replacing any save of that name (which keeps
+// its file); true if one was replaced
+pub fn save_as(name: &str, mode: Mode, code: &str, game: &Game) -> Result<bool, SlotError> {
+    let dir = saves_dir()?;
+    fs::create_dir_all(&dir)?;
+    let mut entries = read_index(&dir)?;
+
+    let replaced = entries.iter().position(|entry| entry.name == name);
+    let file = match replaced {
+        Some(index) => entries.remove(index).file,
+        None => unique_file(&sanitize(name), &entries),
+    };
+    write_file(&dir.join(&file), &serialize(mode, code, game))?;
+
+    entries.push(SaveEntry {
+        name: name.to_string(),
+        file,
+        saved: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs()),
+        height: game.board().height(),
+        width: game.board().width(),
+        progress: progress(game),
+    });
+    write_index(&dir, &entries)?;
+    Ok(replaced.is_some())
+}
+
+// `save as`, and the line that says how it went
+pub fn save_named(name: &str, mode: Mode, code: &str, game: &Game) -> String {
+    match save_as(name, mode, code, game) {
+        Ok(false) => fill(Msg::SavedAs, &[&name]),
+        Ok(true) => fill(Msg::SaveReplaced, &[&name]),
+        Err(e) => fill(Msg::CouldntSaveAs, &[&e]),
+    }
+}
+
+pub fn load(save: &str) -> Result<SavedGame, SlotError> {
+    let entries = list()?;
+    let entry = find(&entries, save).ok_or_else(|| SlotError::NotFound(save.to_string()))?;
+    let contents = fs::read_to_string(saves_dir()?.join(&entry.file))?;
+    deserialize(&contents).map_err(SlotError::Save)
+}
+
+// takes the save off the index and removes its file
+pub fn delete(entry: &SaveEntry) -> Result<(), SlotError> {
+    let dir = saves_dir()?;
+    let mut entries = read_index(&dir)?;
+    entries.retain(|other| other.name != entry.name);
+    write_index(&dir, &entries)?;
+    match fs::remove_file(dir.join(&entry.file)) {
+        Err(e) if e.kind() != ErrorKind::NotFound => Err(SlotError::Io(e)),
+        _ => Ok(()),
+    }
+}
+
+// `names` is how wide the names' column is
+fn saves_row([number, name, size, progress, saved]: [&str; 5], names: usize) -> String {
+    let row = format!("{number:>3}  {name:<names$}  {size:<9}{progress:>8}  {saved}");
+    row.trim_end().to_string()
+}
+
+pub fn render_saves(entries: &[SaveEntry]) -> String {
+    if entries.is_empty() {
+        return String::from(text(Msg::NoSaves));
+    }
+
+    let names = entries
+        .iter()
+        .map(|entry| entry.name.chars().count())
+        .fold(text(Msg::SavesName).chars().count(), usize::max);
+    let mut table = saves_row(
+        [
+            "#",
+            text(Msg::SavesName),
+            text(Msg::SavesSize),
+            text(Msg::SavesProgress),
+            text(Msg::SavesSaved),
+        ],
+        names,
+    );
+    for (index, entry) in entries.iter().enumerate() {
+        table += "\n";
+        table += &saves_row(
+            [
+                &(index + 1).to_string(),
+                &entry.name,
+                &format!("{}x{}", entry.height, entry.width),
+                &format!("{}%", entry.progress),
+                &format_timestamp(entry.saved),
+            ],
+            names,
+        );
+    }
+    table
+}
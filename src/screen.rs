@@ -0,0 +1,597 @@
+use crate::alert::{default_flash, play_frames, ring_bell};
+use crate::banner::render_ending;
+use crate::board::{Board, CellPosition};
+use crate::command::Command;
+use crate::config::{Config, Theme};
+use crate::driver::{GameOutput, Screen};
+use crate::game::{Game, GameEvent, MoveOutcome};
+use crate::i18n::{fill, text, Msg};
+use crate::output::CLEAR_ALL;
+use crate::render::{cell_layout, render_cell, render_noted, render_status};
+use std::io::{self, Write};
+use std::thread;
+use std::time::Duration;
+
+// rows kept free under the board for the prompt, what is typed at it, and a
+// line or two of feedback
+const RESERVED_BELOW: usize = 4;
+// the least of the board worth showing, in cells each way
+const MIN_VIEW: usize = 3;
+
+// columns per cell, the gap after it included
+fn cell_width(theme: Theme) -> usize {
+    cell_layout(theme).pitch()
+}
+
+// the part of the board that fits on the terminal, in cells
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub top: usize,
+    pub left: usize,
+    pub rows: usize,
+    pub cols: usize,
+}
+
+// the terminal size a board needs at the least
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TooSmall {
+    pub columns: usize,
+    pub rows: usize,
+}
+
+// keeps the start of a scrolled span where it was where possible, moving it
+// only as far as it takes to bring `focus` back into view or to stop it
+// running past the end
+fn scroll(start: usize, span: usize, total: usize, focus: Option<usize>) -> usize {
+    let start = match focus {
+        Some(focus) if focus < start => focus,
+        Some(focus) if focus >= start + span => focus + 1 - span,
+        _ => start,
+    };
+
+    start.min(total - span)
+}
+
+impl Viewport {
+    // as much of a `height` by `width` board as fits on a terminal of
+    // `terminal` (columns, rows), scrolled on from `previous` just enough to
+    // show `focus`
+    pub fn fit(
+        terminal: (usize, usize),
+        (height, width): (usize, usize),
+        theme: Theme,
+        previous: Option<Viewport>,
+        focus: Option<CellPosition>,
+    ) -> Result<Viewport, TooSmall> {
+        let (columns, rows) = terminal;
+        let cell_width = cell_width(theme);
+
+        let view_rows = height.min(rows.saturating_sub(1 + RESERVED_BELOW));
+        let view_cols = width.min(columns / cell_width);
+        if view_rows < height.min(MIN_VIEW) || view_cols < width.min(MIN_VIEW) {
+            return Err(TooSmall {
+                columns: width.min(MIN_VIEW) * cell_width,
+                rows: 1 + height.min(MIN_VIEW) + RESERVED_BELOW,
+            });
+        }
+
+        let (top, left) = previous.map_or((0, 0), |previous| (previous.top, previous.left));
+        Ok(Viewport {
+            top: scroll(top, view_rows, height, focus.map(|focus| focus.row_index)),
+            left: scroll(left, view_cols, width, focus.map(|focus| focus.col_index)),
+            rows: view_rows,
+            cols: view_cols,
+        })
+    }
+
+    pub fn contains(&self, position: CellPosition) -> bool {
+        (self.top..self.top + self.rows).contains(&position.row_index)
+            && (self.left..self.left + self.cols).contains(&position.col_index)
+    }
+
+    pub fn is_whole(&self, board: &Board) -> bool {
+        self.rows == board.height() && self.cols == board.width()
+    }
+}
+
+// where things sit on a screen that is drawn in place: the status line at
+// the top, the visible part of the board below it, and the prompt and
+// messages under that; rows and columns are 1-based, as terminals count them
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Layout {
+    pub board_top: usize,
+    pub board_left: usize,
+    pub cell_width: usize,
+    pub view: Viewport,
+}
+
+impl Layout {
+    pub fn new(view: Viewport, theme: Theme) -> Self {
+        Layout {
+            board_top: 2,
+            board_left: 1,
+            cell_width: cell_width(theme),
+            view,
+        }
+    }
+
+    pub fn status_row(&self) -> usize {
+        self.board_top - 1
+    }
+
+    // the first row under the board, where the prompt goes
+    pub fn below_board(&self) -> usize {
+        self.board_top + self.view.rows
+    }
+
+    // the terminal (row, column) of a cell's glyph, if it is in view
+    pub fn cell_origin(&self, position: CellPosition) -> Option<(usize, usize)> {
+        self.view.contains(position).then(|| {
+            (
+                self.board_top + position.row_index - self.view.top,
+                self.board_left + (position.col_index - self.view.left) * self.cell_width,
+            )
+        })
+    }
+
+    // the cell under a terminal (row, column), the inverse of `cell_origin`;
+    // the space after an ascii glyph belongs to the glyph. Nothing reads
+    // clicks yet, but they will map back through this
+    #[allow(dead_code)]
+    pub fn cell_at(&self, row: usize, column: usize) -> Option<CellPosition> {
+        let position = CellPosition {
+            row_index: row.checked_sub(self.board_top)? + self.view.top,
+            col_index: column.checked_sub(self.board_left)? / self.cell_width + self.view.left,
+        };
+
+        self.view.contains(position).then_some(position)
+    }
+}
+
+// the smallest terminal (columns, rows) that shows all of a board and the
+// prompt under it
+pub fn terminal_for(board: &Board, theme: Theme) -> (usize, usize) {
+    (
+        board.width() * cell_width(theme),
+        1 + board.height() + RESERVED_BELOW,
+    )
+}
+
+// the cells whose glyph the events changed, in the order they happened
+pub fn repainted(events: &[GameEvent]) -> Vec<CellPosition> {
+    events
+        .iter()
+        .flat_map(|event| match event {
+            GameEvent::CellsOpened(cells) => cells.clone(),
+            &GameEvent::FlagToggled(position, _)
+            | &GameEvent::QuestionToggled(position, _)
+            | &GameEvent::NoteChanged(position, _)
+            | &GameEvent::Peeked(position, _)
+            | &GameEvent::MineHit(position)
+            | &GameEvent::MineDefused(position) => vec![position],
+            GameEvent::GameWon
+            | GameEvent::ShieldEarned(_)
+            | GameEvent::MinesMoved(_)
+            | GameEvent::MineCountChanged(_)
+            | GameEvent::Undone => vec![],
+        })
+        .collect()
+}
+
+// the cell the last move was about, which the view follows around the board
+fn focus(game: &Game) -> Option<CellPosition> {
+    let last = game.moves().last()?;
+    match (&last.command, last.outcome) {
+        (_, MoveOutcome::HitBomb(hit) | MoveOutcome::Defused(hit)) => Some(hit),
+        (
+            &Command::Clear(position)
+            | &Command::Flag(position)
+            | &Command::Chord(position)
+            | &Command::Question(position)
+            | &Command::Peek(position),
+            _,
+        ) => Some(position),
+        _ => None,
+    }
+}
+
+// (columns, rows), or None if stdout isn't a terminal that can say
+#[cfg(unix)]
+fn terminal_size() -> Option<(usize, usize)> {
+    // SAFETY: TIOCGWINSZ only writes to the winsize struct passed to it
+    let size = unsafe {
+        let mut size: libc::winsize = std::mem::zeroed();
+        if libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) != 0 {
+            return None;
+        }
+        size
+    };
+
+    (size.ws_col > 0 && size.ws_row > 0)
+        .then(|| (usize::from(size.ws_col), usize::from(size.ws_row)))
+}
+
+#[cfg(not(unix))]
+fn terminal_size() -> Option<(usize, usize)> {
+    None
+}
+
+fn move_to(row: usize, column: usize) -> String {
+    format!("\x1B[{row};{column}H")
+}
+
+const CLEAR_SCREEN: &str = "\x1B[H\x1B[2J";
+const CLEAR_LINE: &str = "\x1B[2K";
+const CLEAR_BELOW: &str = "\x1B[J";
+const INVERSE: &str = "\x1B[7m";
+const RESET: &str = "\x1B[0m";
+
+// draws the board once and then repaints just the cells each move changed,
+// which keeps large boards from flickering over slow connections; messages
+// wait until the board has been brought up to date and then go under it.
+// A board bigger than the terminal is shown a part at a time, following the
+// moves, and the terminal's size is checked again before every redraw
+pub struct RedrawOutput<'a> {
+    config: &'a Config,
+    // what the screen was last fully drawn with, or None if it has to be
+    // drawn again from scratch
+    drawn: Option<(Layout, Theme)>,
+    messages: Vec<String>,
+    // how a flash waits between frames
+    wait: fn(Duration),
+}
+
+impl<'a> RedrawOutput<'a> {
+    pub fn new(config: &'a Config) -> Self {
+        RedrawOutput {
+            config,
+            drawn: None,
+            messages: vec![],
+            wait: thread::sleep,
+        }
+    }
+
+    // blinks the mine that ended the game where it is on the screen
+    fn flash(&self, game: &Game) {
+        let Some((layout, _)) = self.drawn else {
+            return;
+        };
+        let hit = game.events().iter().find_map(|event| match event {
+            &GameEvent::MineHit(hit) => Some(hit),
+            _ => None,
+        });
+        let Some((position, (row, column))) =
+            hit.and_then(|hit| Some((hit, layout.cell_origin(hit)?)))
+        else {
+            return;
+        };
+        let Some(cell) = game.board().get_cell(position) else {
+            return;
+        };
+
+        let glyph = render_cell(cell, self.config, false);
+        play_frames(
+            &default_flash(),
+            |highlighted| {
+                let glyph = if highlighted {
+                    format!("{INVERSE}{glyph}{RESET}")
+                } else {
+                    glyph.clone()
+                };
+                print!(
+                    "{}{glyph}{}",
+                    move_to(row, column),
+                    move_to(layout.below_board(), 1)
+                );
+                let _ = io::stdout().flush();
+            },
+            self.wait,
+        );
+    }
+
+    fn draw_view(&self, game: &Game, layout: &Layout) -> String {
+        let view = layout.view;
+        let rows = game.board().rows().skip(view.top).take(view.rows);
+
+        let mut out = String::new();
+        for (offset, row) in rows.enumerate() {
+            out += &move_to(layout.board_top + offset, layout.board_left);
+            out += &cell_layout(self.config.output.theme).row(
+                row[view.left..view.left + view.cols]
+                    .iter()
+                    .map(|cell| render_noted(cell, game.note(cell.position), self.config)),
+            );
+        }
+        out
+    }
+
+    fn draw(&mut self, game: &Game) -> String {
+        // assumes the board fits when the terminal can't say how big it is
+        let terminal = terminal_size().unwrap_or((usize::MAX, usize::MAX));
+        self.frame(game, terminal)
+    }
+
+    // what drawing the game on a terminal of `terminal` (columns, rows)
+    // writes, after whatever was drawn before
+    pub fn frame(&mut self, game: &Game, terminal: (usize, usize)) -> String {
+        let board = game.board();
+        let previous = self.drawn.map(|(layout, _)| layout.view);
+
+        let view = match Viewport::fit(
+            terminal,
+            (board.height(), board.width()),
+            self.config.output.theme,
+            previous,
+            focus(game),
+        ) {
+            Ok(view) => view,
+            Err(TooSmall { columns, rows }) => {
+                self.drawn = None;
+                return format!(
+                    "{CLEAR_SCREEN}{}\n",
+                    fill(Msg::TerminalTooSmall, &[&columns, &rows])
+                );
+            }
+        };
+        let layout = Layout::new(view, self.config.output.theme);
+
+        // a resize or a scroll moves everything, so only a screen laid out
+        // exactly as before can be patched
+        let full = match self.drawn {
+            Some((drawn, theme)) => {
+                drawn != layout
+                    || theme != self.config.output.theme
+                    || game.events().contains(&GameEvent::Undone)
+            }
+            None => true,
+        };
+
+        let mut out = String::new();
+        if full {
+            out += CLEAR_SCREEN;
+            out += &self.draw_view(game, &layout);
+        } else {
+            for position in repainted(game.events()) {
+                if let (Some((row, column)), Some(cell)) =
+                    (layout.cell_origin(position), board.get_cell(position))
+                {
+                    out += &move_to(row, column);
+                    out += &render_noted(cell, game.note(position), self.config);
+                }
+            }
+        }
+        self.drawn = Some((layout, self.config.output.theme));
+
+        // the status line changes with nearly every move, and is cheap
+        out += &move_to(layout.status_row(), 1);
+        out += CLEAR_LINE;
+        out += &render_status(game, self.config);
+        if !view.is_whole(board) {
+            out += "  ";
+            out += &fill(
+                Msg::StatusView,
+                &[
+                    &view.top,
+                    &(view.top + view.rows - 1),
+                    &board.height(),
+                    &view.left,
+                    &(view.left + view.cols - 1),
+                    &board.width(),
+                ],
+            );
+        }
+
+        out += &move_to(layout.below_board(), 1);
+        out += CLEAR_BELOW;
+        out
+    }
+
+    // the screen at the end of a game, with the board revealed
+    pub fn ending(&mut self, game: &Game) -> String {
+        self.drawn = None;
+        format!("{CLEAR_SCREEN}{}\n", render_ending(game, self.config))
+    }
+
+    fn flush_messages(&mut self) {
+        for message in self.messages.drain(..) {
+            println!("{message}");
+        }
+    }
+}
+
+impl GameOutput for RedrawOutput<'_> {
+    fn show(&mut self, screen: &Screen) {
+        match screen {
+            Screen::Board(game) => {
+                ring_bell(game, self.config);
+                print!("{}", self.draw(game));
+                self.flush_messages();
+            }
+            // the game is over, so the board goes back to being printed
+            Screen::Revealed(game) => {
+                ring_bell(game, self.config);
+                if self.config.flash {
+                    self.flash(game);
+                }
+                print!("{}", self.ending(game));
+                self.flush_messages();
+            }
+            Screen::Prompt(msg) => println!("{}", text(*msg)),
+            Screen::Message(message) => self.messages.push(message.clone()),
+            Screen::Divider => (),
+            Screen::Paused => {
+                print!("{CLEAR_ALL}");
+                self.drawn = None;
+            }
+        }
+        let _ = io::stdout().flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+
+    fn at(row_index: usize, col_index: usize) -> CellPosition {
+        CellPosition::new(row_index, col_index)
+    }
+
+    fn view(top: usize, left: usize, rows: usize, cols: usize) -> Viewport {
+        Viewport {
+            top,
+            left,
+            rows,
+            cols,
+        }
+    }
+
+    // a terminal `cols` cells wide in the ascii theme, with room for `rows`
+    // rows of the board
+    fn terminal(cols: usize, rows: usize) -> (usize, usize) {
+        (cols * cell_width(Theme::Ascii), 1 + rows + RESERVED_BELOW)
+    }
+
+    fn fit(
+        terminal: (usize, usize),
+        previous: Option<Viewport>,
+        focus: Option<CellPosition>,
+    ) -> Result<Viewport, TooSmall> {
+        Viewport::fit(terminal, (20, 30), Theme::Ascii, previous, focus)
+    }
+
+    #[test]
+    fn cells_map_to_the_terminal_and_back() {
+        let layout = Layout::new(view(0, 0, 5, 5), Theme::Ascii);
+        assert_eq!(layout.cell_width, 2);
+        // under the status line, two columns a cell
+        assert_eq!(layout.cell_origin(at(0, 0)), Some((2, 1)));
+        assert_eq!(layout.cell_origin(at(3, 4)), Some((5, 9)));
+        assert_eq!(layout.status_row(), 1);
+        assert_eq!(layout.below_board(), 7);
+        // the gap after a glyph is the glyph's
+        assert_eq!(layout.cell_at(5, 9), Some(at(3, 4)));
+        assert_eq!(layout.cell_at(5, 10), Some(at(3, 4)));
+        assert_eq!(layout.cell_at(1, 1), None);
+        assert_eq!(layout.cell_at(7, 1), None);
+
+        // scrolled, the cells out of view go nowhere
+        let layout = Layout::new(view(10, 20, 5, 5), Theme::Emoji);
+        assert_eq!(layout.cell_origin(at(10, 20)), Some((2, 1)));
+        assert_eq!(layout.cell_origin(at(9, 20)), None);
+        assert_eq!(layout.cell_origin(at(10, 25)), None);
+        for position in [at(10, 20), at(12, 23), at(14, 24)] {
+            let (row, column) = layout
+                .cell_origin(position)
+                .expect("The cell should be in view");
+            assert_eq!(layout.cell_at(row, column), Some(position));
+            assert_eq!(
+                layout.cell_at(row, column + layout.cell_width - 1),
+                Some(position)
+            );
+        }
+    }
+
+    #[test]
+    fn the_repainted_cells_are_the_ones_the_events_name() {
+        let events = [
+            GameEvent::CellsOpened(vec![at(0, 0), at(0, 1)]),
+            GameEvent::MineCountChanged(3),
+            GameEvent::FlagToggled(at(2, 2), true),
+            GameEvent::QuestionToggled(at(2, 2), false),
+            GameEvent::MineHit(at(4, 4)),
+            GameEvent::GameWon,
+        ];
+        assert_eq!(
+            repainted(&events),
+            [at(0, 0), at(0, 1), at(2, 2), at(2, 2), at(4, 4)]
+        );
+    }
+
+    #[test]
+    fn a_move_repaints_only_its_cells_and_a_resize_everything() {
+        let config = Config::default();
+        let mut output = RedrawOutput::new(&config);
+        let board: Board = "....\n....\n...*".parse().expect("The layout should parse");
+        let mut game = Game::new(board, 1);
+
+        let first = output.frame(&game, terminal(10, 10));
+        assert!(first.starts_with(CLEAR_SCREEN));
+
+        game.apply(0, Command::Flag(at(2, 3)))
+            .expect("The flag should go on");
+        let patch = output.frame(&game, terminal(10, 10));
+        assert!(!patch.contains(CLEAR_SCREEN));
+        // the flag, then the status line and the prompt under the board
+        assert!(
+            patch.starts_with(&format!("{}^", move_to(4, 7))),
+            "{patch:?}"
+        );
+        assert_eq!(patch.matches('H').count(), 3, "{patch:?}");
+
+        // a terminal too narrow for the board now shows a part of it,
+        // laid out afresh
+        let resized = output.frame(&game, terminal(3, 10));
+        assert!(resized.starts_with(CLEAR_SCREEN));
+    }
+
+    #[test]
+    fn a_viewport_shrinks_around_the_focus_and_grows_back() {
+        // the whole board, when there is room
+        let whole = fit(terminal(30, 20), None, Some(at(19, 29)));
+        assert_eq!(whole, Ok(view(0, 0, 20, 30)));
+
+        // shrunk with the last move in the bottom right corner, the view
+        // scrolls to keep it in sight, and no further than the board's end
+        let shrunk = fit(terminal(10, 8), whole.ok(), Some(at(19, 29)));
+        assert_eq!(shrunk, Ok(view(12, 20, 8, 10)));
+
+        // a move elsewhere in view leaves it where it is
+        let kept = fit(terminal(10, 8), shrunk.ok(), Some(at(14, 22)));
+        assert_eq!(kept, shrunk);
+
+        // grown a little, it stays put until it would run past the end
+        let grown = fit(terminal(12, 10), kept.ok(), Some(at(14, 22)));
+        assert_eq!(grown, Ok(view(10, 18, 10, 12)));
+
+        // and grown all the way, it is the whole board again
+        let regrown = fit(terminal(40, 30), grown.ok(), Some(at(0, 0)));
+        assert_eq!(regrown, Ok(view(0, 0, 20, 30)));
+    }
+
+    #[test]
+    fn a_terminal_too_small_for_a_minimal_view_says_what_it_needs() {
+        let need = TooSmall {
+            columns: MIN_VIEW * cell_width(Theme::Ascii),
+            rows: 1 + MIN_VIEW + RESERVED_BELOW,
+        };
+        assert_eq!(fit(terminal(2, 20), None, None), Err(need));
+        assert_eq!(fit(terminal(30, 2), None, None), Err(need));
+        assert_eq!(fit(terminal(3, 3), None, None), Ok(view(0, 0, 3, 3)));
+
+        // a board smaller than the minimal view only needs itself
+        let small = Viewport::fit(terminal(1, 20), (2, 2), Theme::Ascii, None, None);
+        assert_eq!(
+            small,
+            Err(TooSmall {
+                columns: 2 * cell_width(Theme::Ascii),
+                rows: 1 + 2 + RESERVED_BELOW,
+            })
+        );
+
+        // drawn, it is a screen of its own until the terminal grows again
+        let config = Config::default();
+        let mut output = RedrawOutput::new(&config);
+        let game = Game::new(
+            "...\n..*"
+                .parse::<Board>()
+                .expect("The layout should parse"),
+            1,
+        );
+        let too_small = output.frame(&game, (2, 2));
+        assert!(too_small.contains("too small"), "{too_small:?}");
+        assert!(output
+            .frame(&game, terminal(10, 10))
+            .starts_with(CLEAR_SCREEN));
+    }
+}
@@ -0,0 +1,357 @@
+use crate::autosave::on_interrupt;
+use crate::batch::Protocol;
+use crate::game::{Game, GameOutcome};
+use crate::i18n::{fill, text, Msg};
+use crate::json::Value;
+use rand::random;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, Shutdown, TcpListener, TcpStream};
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+// how long a client can go without sending a line before its game is put
+// aside, so that one left idle doesn't keep everyone else out
+pub const CLIENT_TIMEOUT: Duration = Duration::from_secs(300);
+
+type Current = Arc<Mutex<Option<TcpStream>>>;
+
+// a game whose client went away before it was over, kept until a client
+// sends `resume <token>` or starts another game
+struct Parked {
+    token: String,
+    code: String,
+    game: Game,
+}
+
+// a line for the client about something other than a command, as batch
+// mode reports its errors
+pub fn error_line(json: bool, msg: Msg) -> String {
+    if json {
+        Value::object([("error", Value::from(text(msg)))]).to_string()
+    } else {
+        fill(Msg::ErrorLine, &[&text(msg)])
+    }
+}
+
+// the game's code and token, then its board
+fn greet(protocol: &Protocol, playing: &Parked, out: &mut TcpStream) -> io::Result<()> {
+    let greeting = if protocol.json {
+        Value::object([
+            ("code", Value::from(playing.code.as_str())),
+            ("token", Value::from(playing.token.as_str())),
+        ])
+        .to_string()
+    } else {
+        fill(Msg::ServeToken, &[&playing.code, &playing.token])
+    };
+    writeln!(out, "{greeting}")?;
+    protocol.show(&playing.game, out);
+    protocol.opening(&playing.game, out);
+    Ok(())
+}
+
+// plays one game with a client, which is shown a new one as soon as it
+// connects; its first command starts playing it, or `resume <token>` picks
+// up the parked one instead
+fn serve_client(
+    protocol: &Protocol,
+    stream: TcpStream,
+    timeout: Duration,
+    parked: &mut Option<Parked>,
+    new_game: &impl Fn() -> (String, Game),
+) -> io::Result<Option<GameOutcome>> {
+    stream.set_read_timeout(Some(timeout))?;
+    let mut out = stream.try_clone()?;
+    // a read that times out is answered like any other error, which ends
+    // the game as if the client had gone
+    let idle = |e: &io::Error| {
+        matches!(
+            e.kind(),
+            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+        )
+    };
+    let mut lines = BufReader::new(stream).lines().map(|line| {
+        line.map_err(|e| match idle(&e) {
+            true => io::Error::new(e.kind(), text(Msg::ServeIdle)),
+            false => e,
+        })
+    });
+
+    let (code, game) = new_game();
+    let token = format!("{:016x}", random::<u64>());
+    let mut playing = Parked { token, code, game };
+    greet(protocol, &playing, &mut out)?;
+
+    let first = match lines.next() {
+        Some(Ok(first)) => first,
+        Some(Err(e)) => {
+            if idle(&e) {
+                let _ = writeln!(out, "{}", error_line(protocol.json, Msg::ServeIdle));
+            }
+            return Err(e);
+        }
+        None => return Ok(None),
+    };
+    let first = match first.trim().strip_prefix("resume") {
+        Some(token) => match parked.take() {
+            Some(waiting) if waiting.token == token.trim() => {
+                playing = waiting;
+                greet(protocol, &playing, &mut out)?;
+                None
+            }
+            other => {
+                *parked = other;
+                writeln!(out, "{}", error_line(protocol.json, Msg::ServeNoSuchGame))?;
+                return Ok(None);
+            }
+        },
+        None => {
+            *parked = None;
+            Some(first)
+        }
+    };
+    let lines = first.map(Ok).into_iter().chain(lines);
+    let outcome = protocol.run(&mut playing.game, &playing.code, lines, &mut out);
+
+    if outcome == GameOutcome::Aborted {
+        println!(
+            "{}",
+            fill(Msg::ServeParked, &[&playing.code, &playing.token])
+        );
+        *parked = Some(playing);
+    }
+    Ok(Some(outcome))
+}
+
+// serves single-player games on localhost, speaking the batch protocol to
+// one client at a time; others are turned away while it plays. Ctrl-C tells
+// the client being served before the server exits
+pub fn serve(
+    protocol: &Protocol,
+    port: u16,
+    new_game: impl Fn() -> (String, Game),
+) -> io::Result<()> {
+    let current: Current = Arc::default();
+    let json = protocol.json;
+    let stopping = current.clone();
+    on_interrupt(move || {
+        let client = stopping.lock().ok().and_then(|mut client| client.take());
+        if let Some(mut client) = client {
+            let _ = writeln!(client, "{}", error_line(json, Msg::ServeStopping));
+            let _ = client.shutdown(Shutdown::Both);
+        }
+        process::exit(0);
+    });
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+    println!("{}", fill(Msg::ServeListening, &[&listener.local_addr()?]));
+    serve_on(protocol, listener, CLIENT_TIMEOUT, &current, new_game)
+}
+
+// as `serve`, on a listener already bound; `current` is kept to the client
+// being served
+pub fn serve_on(
+    protocol: &Protocol,
+    listener: TcpListener,
+    timeout: Duration,
+    current: &Current,
+    new_game: impl Fn() -> (String, Game),
+) -> io::Result<()> {
+    let json = protocol.json;
+    let busy = Arc::new(AtomicBool::new(false));
+    let (clients, accepted) = mpsc::channel();
+    let accepting = busy.clone();
+    thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            if accepting.swap(true, Ordering::SeqCst) {
+                let _ = writeln!(stream, "{}", error_line(json, Msg::ServeBusy));
+                continue;
+            }
+            if clients.send(stream).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut parked = None;
+    for stream in accepted {
+        if let Ok(mut client) = current.lock() {
+            *client = stream.try_clone().ok();
+        }
+        let served = serve_client(protocol, stream, timeout, &mut parked, &new_game);
+        if let Ok(mut client) = current.lock() {
+            *client = None;
+        }
+        // the next client can come in before the last one's game is reported
+        busy.store(false, Ordering::SeqCst);
+        match served {
+            Ok(Some(outcome)) if outcome != GameOutcome::Aborted => println!("{outcome}"),
+            Ok(_) => (),
+            Err(e) => println!("{}", fill(Msg::ErrorLine, &[&e])),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use std::io::Lines;
+    use std::net::SocketAddr;
+
+    // a mine at the bottom of a 3x3 board, out of reach of a clear at the top
+    const LAYOUT: &str = "...\n...\n.*.";
+
+    // a server on a port of its own, letting clients idle for `timeout`
+    fn start(timeout: Duration) -> SocketAddr {
+        start_on(LAYOUT, timeout)
+    }
+
+    fn start_on(layout: &'static str, timeout: Duration) -> SocketAddr {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).expect("A port should be free");
+        let address = listener.local_addr().expect("The listener has an address");
+        thread::spawn(move || {
+            let config = Config::default();
+            let protocol = Protocol {
+                config: &config,
+                json: false,
+                diff: false,
+                files: false,
+            };
+            let new_game = || {
+                let board = layout.parse().expect("The layout should parse");
+                (String::from("#1"), Game::new(board, 1))
+            };
+            serve_on(&protocol, listener, timeout, &Arc::default(), new_game)
+        });
+        address
+    }
+
+    struct Client {
+        stream: TcpStream,
+        lines: Lines<BufReader<TcpStream>>,
+    }
+
+    impl Client {
+        fn connect(address: SocketAddr) -> Client {
+            let stream = TcpStream::connect(address).expect("The server should accept");
+            // a test that goes wrong fails rather than hanging
+            stream
+                .set_read_timeout(Some(Duration::from_secs(5)))
+                .expect("The timeout should be set");
+            let reader = stream.try_clone().expect("The stream should clone");
+            Client {
+                stream,
+                lines: BufReader::new(reader).lines(),
+            }
+        }
+
+        fn send(&mut self, line: &str) {
+            writeln!(self.stream, "{line}").expect("The line should be sent");
+        }
+
+        fn line(&mut self) -> Option<String> {
+            self.lines
+                .next()
+                .map(|line| line.expect("The line should be read"))
+        }
+
+        // up to and including the line the board ends with
+        fn board(&mut self) -> Vec<String> {
+            let mut lines = vec![];
+            while let Some(line) = self.line() {
+                let end = line == "------";
+                lines.push(line);
+                if end {
+                    break;
+                }
+            }
+            lines
+        }
+    }
+
+    // what the client is told to send to come back to its game
+    fn token(greeting: &str) -> &str {
+        greeting
+            .split("send resume ")
+            .nth(1)
+            .expect("The greeting should give a token")
+    }
+
+    #[test]
+    fn a_client_is_greeted_with_the_board_before_sending_anything() {
+        let mut client = Client::connect(start(CLIENT_TIMEOUT));
+
+        let greeting = client.board();
+        assert!(
+            greeting[0].starts_with("Playing #1; to continue after disconnecting, send resume "),
+            "{greeting:?}"
+        );
+        assert_eq!(token(&greeting[0]).len(), 16);
+        assert!(greeting[1].starts_with("Mines: 1"), "{greeting:?}");
+        assert_eq!(&greeting[2..], ["# # #", "# # #", "# # #", "------"]);
+
+        client.send("c 0 0");
+        let board = client.board();
+        assert_eq!(
+            &board[board.len() - 4..],
+            ["0 0 0", "1 1 1", "# # #", "------"]
+        );
+    }
+
+    #[test]
+    fn an_idle_client_is_let_go_and_its_game_kept() {
+        let address = start(Duration::from_millis(300));
+        let mut idle = Client::connect(address);
+        let greeting = idle.board();
+        let parked = token(&greeting[0]).to_string();
+        idle.send("c 0 0");
+        idle.board();
+
+        // while it is connected, no one else gets in
+        let mut turned_away = Client::connect(address);
+        assert_eq!(
+            turned_away.line().as_deref(),
+            Some("error: Another client is playing; try again once its game is over")
+        );
+
+        assert_eq!(
+            idle.line().as_deref(),
+            Some("error: Nothing was sent for too long, so the game is put aside for resume")
+        );
+        assert_eq!(idle.line(), None);
+
+        // and once it has gone, the next client can pick the game up; the
+        // server may not have seen it go until a moment after
+        let mut next = loop {
+            let mut next = Client::connect(address);
+            if next.board().len() > 1 {
+                break next;
+            }
+            thread::sleep(Duration::from_millis(20));
+        };
+        next.send(&format!("resume {parked}"));
+        let resumed = next.board();
+        assert_eq!(token(&resumed[0]), parked);
+        assert!(resumed[1].contains("Moves: 1"), "{resumed:?}");
+        assert_eq!(&resumed[2..], ["0 0 0", "1 1 1", "# # #", "------"]);
+    }
+
+    #[test]
+    fn a_board_with_one_safe_cell_is_said_to_be_a_guess_on_greeting() {
+        let mut client = Client::connect(start_on("*.\n**", CLIENT_TIMEOUT));
+
+        let greeting = client.board();
+        assert_eq!(&greeting[2..], ["# #", "# #", "------"]);
+        assert_eq!(
+            client.line().as_deref(),
+            Some(
+                "Only one of this board's 4 cells is safe, so the first clear wins or loses the game"
+            )
+        );
+    }
+}
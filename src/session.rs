@@ -0,0 +1,219 @@
+use crate::driver::is_yes;
+use crate::game::{GameOutcome, Stats};
+use crate::i18n::{fill, text, Msg};
+use crate::timer::format_duration;
+use std::fmt::Display;
+use std::io::stdin;
+use std::time::Duration;
+
+// how the games since the program started went; aborted games don't count
+// towards any of it, and don't break a streak either, and nor do resigned
+// ones unless they are asked to count as losses
+#[derive(Debug, PartialEq)]
+pub struct Summary {
+    pub played: usize,
+    pub won: usize,
+    // however they were counted
+    pub resigned: usize,
+    pub total_time: Duration,
+    pub best_time: Option<Duration>,
+    // the mean over won games, leaving out any that took no time at all
+    pub bv_per_second: Option<f64>,
+    pub longest_streak: usize,
+}
+
+impl Summary {
+    // None if no game got as far as being won or lost
+    pub fn of(outcomes: &[GameOutcome], count_resigned: bool) -> Option<Summary> {
+        let finished: Vec<(bool, Stats)> = outcomes
+            .iter()
+            .filter_map(|&outcome| match outcome {
+                GameOutcome::Won(stats) => Some((true, stats)),
+                GameOutcome::Lost { stats, .. } => Some((false, stats)),
+                GameOutcome::Resigned { stats, .. } if count_resigned => Some((false, stats)),
+                GameOutcome::Resigned { .. } | GameOutcome::Aborted => None,
+            })
+            .collect();
+        let resigned = outcomes
+            .iter()
+            .filter(|outcome| matches!(outcome, GameOutcome::Resigned { .. }))
+            .count();
+        if finished.is_empty() {
+            return None;
+        }
+
+        let wins: Vec<Stats> = finished
+            .iter()
+            .filter(|(won, _)| *won)
+            .map(|&(_, stats)| stats)
+            .collect();
+        let rates: Vec<f64> = wins
+            .iter()
+            .filter(|stats| !stats.elapsed.is_zero())
+            .map(|stats| stats.three_bv as f64 / stats.elapsed.as_secs_f64())
+            .collect();
+
+        let mut longest_streak = 0;
+        let mut streak = 0;
+        for &(won, _) in &finished {
+            streak = if won { streak + 1 } else { 0 };
+            longest_streak = longest_streak.max(streak);
+        }
+
+        Some(Summary {
+            played: finished.len(),
+            won: wins.len(),
+            resigned,
+            total_time: finished.iter().map(|(_, stats)| stats.elapsed).sum(),
+            best_time: wins.iter().map(|stats| stats.elapsed).min(),
+            bv_per_second: (!rates.is_empty())
+                .then(|| rates.iter().sum::<f64>() / rates.len() as f64),
+            longest_streak,
+        })
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let win_rate = self.won * 100 / self.played;
+        let none = String::from("-");
+
+        let lines = [
+            String::from(text(Msg::SummaryHeading)),
+            fill(Msg::SummaryPlayed, &[&self.played]),
+            fill(Msg::SummaryWinRate, &[&win_rate, &self.won, &self.played]),
+            fill(Msg::SummaryTotalTime, &[&format_duration(self.total_time)]),
+            fill(
+                Msg::SummaryBestTime,
+                &[&self.best_time.map_or(none.clone(), format_duration)],
+            ),
+            fill(
+                Msg::SummaryBvPerSecond,
+                &[&self.bv_per_second.map_or(none, |rate| format!("{rate:.2}"))],
+            ),
+            fill(Msg::SummaryStreak, &[&self.longest_streak]),
+        ];
+
+        write!(f, "{}", lines.join("\n"))?;
+        if self.resigned > 0 {
+            write!(f, "\n{}", fill(Msg::SummaryResigned, &[&self.resigned]))?;
+        }
+        Ok(())
+    }
+}
+
+// asked once a single-player game is over; anything but yes, including the
+// end of input, is a no
+pub fn play_again() -> bool {
+    println!("{}", text(Msg::PlayAgainPrompt));
+
+    let mut answer = String::new();
+    if stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+    is_yes(&answer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::CellPosition;
+
+    fn stats(seconds: u64, three_bv: usize) -> Stats {
+        Stats {
+            moves: 1,
+            opened: 1,
+            elapsed: Duration::from_secs(seconds),
+            three_bv,
+            mines: 1,
+            assisted: false,
+            won_by: None,
+        }
+    }
+
+    fn won(seconds: u64, three_bv: usize) -> GameOutcome {
+        GameOutcome::Won(stats(seconds, three_bv))
+    }
+
+    fn lost(seconds: u64) -> GameOutcome {
+        GameOutcome::Lost {
+            hit: CellPosition::new(0, 0),
+            stats: stats(seconds, 1),
+        }
+    }
+
+    // a run of three wins with an aborted and a resigned game in it, a loss,
+    // two more wins, the second taking no time, and a loss to finish
+    fn session() -> Vec<GameOutcome> {
+        vec![
+            won(10, 20),
+            won(30, 15),
+            GameOutcome::Aborted,
+            GameOutcome::Resigned {
+                progress: 40,
+                stats: stats(7, 1),
+            },
+            won(20, 10),
+            lost(5),
+            won(8, 4),
+            won(0, 3),
+            lost(50),
+        ]
+    }
+
+    #[test]
+    fn every_aggregate_leaves_out_aborted_and_resigned_games() {
+        let summary = Summary::of(&session(), false).expect("Games were finished");
+        assert_eq!(
+            summary,
+            Summary {
+                played: 7,
+                won: 5,
+                resigned: 1,
+                total_time: Duration::from_secs(123),
+                best_time: Some(Duration::ZERO),
+                // 2.0, 0.5, 0.5 and 0.5, without the game that took no time
+                bv_per_second: Some(0.875),
+                longest_streak: 3,
+            }
+        );
+
+        let expected = [
+            "This session:",
+            "  Games played: 7",
+            "  Win rate: 71% (5 of 7)",
+            "  Total time: 02:03",
+            "  Best time: 00:00",
+            "  Average 3BV/s: 0.88",
+            "  Longest win streak: 3",
+            "  Resigned: 1",
+        ];
+        assert_eq!(summary.to_string(), expected.join("\n"));
+    }
+
+    #[test]
+    fn resigned_games_can_count_as_losses() {
+        let summary = Summary::of(&session(), true).expect("Games were finished");
+        assert_eq!(summary.played, 8);
+        assert_eq!(summary.won, 5);
+        assert_eq!(summary.resigned, 1);
+        assert_eq!(summary.total_time, Duration::from_secs(130));
+        // the resignation now ends the first run of wins
+        assert_eq!(summary.longest_streak, 2);
+    }
+
+    #[test]
+    fn a_session_with_nothing_finished_has_no_summary() {
+        let unfinished = [session()[2], session()[3]];
+        assert_eq!(Summary::of(&unfinished, false), None);
+        assert_eq!(Summary::of(&[], true), None);
+
+        // and losses alone have no best time or rate
+        let summary = Summary::of(&[lost(3), lost(4)], false).expect("Games were lost");
+        assert_eq!((summary.won, summary.longest_streak), (0, 0));
+        assert_eq!((summary.best_time, summary.bv_per_second), (None, None));
+        assert!(summary
+            .to_string()
+            .contains("  Best time: -\n  Average 3BV/s: -\n"));
+    }
+}
@@ -0,0 +1,120 @@
+use crate::board::CellState;
+use crate::game::{Game, GameState};
+use crate::i18n::{fill, text, Msg};
+use crate::timer::format_duration;
+use crate::view::{BoardView, CellView};
+
+// a spoiler-free summary that is safe to paste into chat: the grid only
+// shows what the player did (opened, flagged, or untouched), so mines the
+// player never found stay hidden among the untouched squares
+pub fn share_block(game: &Game, code: &str) -> String {
+    let grid = BoardView::of(game.board())
+        .rows()
+        .map(|row| {
+            row.iter()
+                .map(|cell| match cell {
+                    CellView::Open(_) => "🟩",
+                    CellView::Flagged | CellView::Exploded | CellView::Defused => "🟥",
+                    _ => "⬛",
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    format!(
+        "{}\n{}\n{grid}",
+        fill(Msg::ShareHeading, &[&code]),
+        summary(game)
+    )
+}
+
+fn summary(game: &Game) -> String {
+    let board = game.board();
+    let result = match game.state() {
+        GameState::Won => text(Msg::ResultWon),
+        GameState::Lost => text(Msg::ResultLost),
+        GameState::Resigned => text(Msg::ResultResigned),
+        GameState::Playing => text(Msg::ResultInProgress),
+    };
+
+    fill(
+        Msg::ShareSummary,
+        &[
+            &board.height(),
+            &board.width(),
+            &board.count_bombs(),
+            &result,
+            &format_duration(game.elapsed()),
+        ],
+    )
+}
+
+const KEYCAPS: [&str; 9] = ["0️⃣", "1️⃣", "2️⃣", "3️⃣", "4️⃣", "5️⃣", "6️⃣", "7️⃣", "8️⃣"];
+
+// a number no cell can show, as a board set by hand can hold, marked as
+// render.rs marks a broken cell
+fn keycap(bombs_around: u8) -> &'static str {
+    KEYCAPS
+        .get(usize::from(bombs_around))
+        .copied()
+        .unwrap_or("🚫")
+}
+
+// the whole board for a chat that has spoilers (Discord's ||bars||): the
+// cells the player opened are shown as they are, and every other one is
+// hidden until clicked, so friends can guess at it before they look; the
+// one export that reads past the view, so it is only offered once a game
+// is over
+pub fn discord_block(game: &Game, code: &str) -> String {
+    let grid = game
+        .board()
+        .rows()
+        .map(|row| {
+            row.iter()
+                .map(|cell| match cell.state {
+                    CellState::Safe { open: true, .. } => keycap(cell.bombs_around).to_string(),
+                    CellState::Bomb { exploded: true, .. } => String::from("💥"),
+                    CellState::Bomb { .. } => String::from("||💣||"),
+                    CellState::Safe { .. } => {
+                        format!("||{}||", keycap(cell.bombs_around))
+                    }
+                    CellState::Rock => String::from("||🪨||"),
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    format!(
+        "**{}**\n{}\n{grid}",
+        fill(Msg::ShareHeading, &[&code]),
+        summary(game)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{Board, CellPosition};
+
+    #[test]
+    fn a_number_past_eight_is_marked_broken() {
+        let mut board: Board = "..\n.*".parse().expect("The layout should parse");
+        for (at, open) in [
+            (CellPosition::new(0, 0), true),
+            (CellPosition::new(0, 1), false),
+        ] {
+            let cell = board.get_cell_mut(at).expect("The cell is on the board");
+            cell.bombs_around = 9;
+            cell.state = CellState::Safe {
+                open,
+                flagged: false,
+            };
+        }
+        let block = discord_block(&Game::new(board, 1), "#1");
+
+        let grid: Vec<&str> = block.lines().skip(2).collect();
+        assert_eq!(grid, ["🚫||🚫||", "||1️⃣||||💣||"]);
+    }
+}
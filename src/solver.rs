@@ -0,0 +1,655 @@
+use crate::board::{Board, CellPosition, CellState, ClearError};
+use crate::command::Command;
+use crate::i18n::{fill, Msg};
+use crate::timer::Clock;
+use rand::Rng;
+use std::cmp::Reverse;
+use std::fmt::Display;
+use std::time::Duration;
+
+// the most branches a search for frontier layouts takes before giving up,
+// and every how many it looks at the time
+const SEARCH_STEPS: usize = 2_000_000;
+const DEADLINE_EVERY: usize = 1024;
+
+// what the open number at `at` says: exactly `mines` of `cells` are mines,
+// once the mines around it already known are counted
+#[derive(Debug, Clone, PartialEq)]
+pub struct Constraint {
+    pub at: CellPosition,
+    // the number it shows
+    pub shows: u8,
+    pub known: Vec<CellPosition>,
+    pub cells: Vec<CellPosition>,
+    pub mines: usize,
+}
+
+// which numbers a cell was worked out from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Reason {
+    // one number on its own
+    SinglePoint(CellPosition),
+    // the cells around the first number are all around the second, which
+    // settles the second's other cells
+    Subset(CellPosition, CellPosition),
+}
+
+// a closed cell the numbers prove safe or a mine, and why
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Deduction {
+    pub position: CellPosition,
+    pub mine: bool,
+    pub reason: Reason,
+}
+
+// e.g. "single-point: (4,2) satisfied", for showing the solver's reasoning
+impl Display for Deduction {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let out = match self.reason {
+            Reason::SinglePoint(at) if self.mine => fill(Msg::ReasonAllMines, &[&at]),
+            Reason::SinglePoint(at) => fill(Msg::ReasonSatisfied, &[&at]),
+            Reason::Subset(smaller, larger) => fill(Msg::ReasonSubset, &[&smaller, &larger]),
+        };
+
+        write!(f, "{out}")
+    }
+}
+
+// closed cells that the open numbers prove to be safe or to be mines
+#[derive(Debug, Default, PartialEq)]
+pub struct Deductions {
+    pub safe: Vec<CellPosition>,
+    pub mines: Vec<CellPosition>,
+}
+
+impl Deductions {
+    pub fn is_empty(&self) -> bool {
+        self.safe.is_empty() && self.mines.is_empty()
+    }
+
+    fn add(&mut self, cells: &[CellPosition], mines: bool) {
+        let list = if mines {
+            &mut self.mines
+        } else {
+            &mut self.safe
+        };
+        for &cell in cells {
+            if !list.contains(&cell) {
+                list.push(cell);
+            }
+        }
+    }
+}
+
+fn is_open(state: &CellState) -> bool {
+    matches!(state, CellState::Safe { open: true, .. })
+}
+
+// one per open number still touching a cell that isn't worked out; mines
+// that went off count as known, like the ones in `known`
+fn constraints(board: &Board, known: &[CellPosition]) -> Vec<Constraint> {
+    let is_known = |position: CellPosition, state: &CellState| {
+        known.contains(&position) || matches!(state, CellState::Bomb { exploded: true, .. })
+    };
+
+    let mut constraints = vec![];
+    for cell in board.rows().flatten().filter(|cell| is_open(&cell.state)) {
+        let mut cells = vec![];
+        let mut found = vec![];
+        for around in board.cells_around(cell.position) {
+            if is_open(&around.state) || matches!(around.state, CellState::Rock) {
+                continue;
+            }
+            if is_known(around.position, &around.state) {
+                found.push(around.position);
+            } else {
+                cells.push(around.position);
+            }
+        }
+        if !cells.is_empty() {
+            constraints.push(Constraint {
+                at: cell.position,
+                shows: cell.bombs_around,
+                mines: usize::from(cell.bombs_around).saturating_sub(found.len()),
+                known: found,
+                cells,
+            });
+        }
+    }
+    constraints
+}
+
+// the cells of `larger` that aren't in `smaller`, and how many mines they
+// hold, if all of `smaller`'s are in `larger`
+fn difference(smaller: &Constraint, larger: &Constraint) -> Option<(Vec<CellPosition>, usize)> {
+    if smaller.cells.len() >= larger.cells.len()
+        || !smaller.cells.iter().all(|cell| larger.cells.contains(cell))
+        || smaller.mines > larger.mines
+    {
+        return None;
+    }
+    let rest = larger
+        .cells
+        .iter()
+        .copied()
+        .filter(|cell| !smaller.cells.contains(cell))
+        .collect();
+    Some((rest, larger.mines - smaller.mines))
+}
+
+// cells that are all safe or all mines; a cell already worked out keeps its
+// first reason
+fn settle(cells: &[CellPosition], mines: usize, reason: Reason, found: &mut Vec<Deduction>) {
+    let mine = match mines {
+        0 => false,
+        mines if mines == cells.len() => true,
+        _ => return,
+    };
+    for &position in cells {
+        if !found.iter().any(|deduction| deduction.position == position) {
+            found.push(Deduction {
+                position,
+                mine,
+                reason,
+            });
+        }
+    }
+}
+
+// what follows from each number on its own (all of its cells are safe, or
+// all are mines) and, failing that, from one number's cells being a subset
+// of another's, with the numbers each cell was worked out from; `known`
+// are mines already worked out
+pub fn explain(board: &Board, known: &[CellPosition]) -> Vec<Deduction> {
+    explain_from(&constraints(board, known))
+}
+
+fn explain_from(constraints: &[Constraint]) -> Vec<Deduction> {
+    let mut found = vec![];
+
+    for constraint in constraints {
+        let reason = Reason::SinglePoint(constraint.at);
+        settle(&constraint.cells, constraint.mines, reason, &mut found);
+    }
+    if found.is_empty() {
+        for smaller in constraints {
+            for larger in constraints {
+                if let Some((rest, mines)) = difference(smaller, larger) {
+                    let reason = Reason::Subset(smaller.at, larger.at);
+                    settle(&rest, mines, reason, &mut found);
+                }
+            }
+        }
+    }
+    found
+}
+
+// the same, without the reasons
+pub fn deduce(board: &Board, known: &[CellPosition]) -> Deductions {
+    let mut deductions = Deductions::default();
+    for deduction in explain(board, known) {
+        deductions.add(&[deduction.position], deduction.mine);
+    }
+    deductions
+}
+
+// a move the solver would make, and the deduction it comes from
+#[derive(Debug, Clone, PartialEq)]
+pub struct BotMove {
+    pub command: Command,
+    pub deduction: Deduction,
+}
+
+// a deduction with the numbers it was worked out from, as they stood then
+#[derive(Debug, Clone, PartialEq)]
+pub struct Proof {
+    pub deduction: Deduction,
+    // the one number, or the smaller and then the larger of a subset
+    pub constraints: Vec<Constraint>,
+}
+
+fn proof(deduction: Deduction, constraints: &[Constraint]) -> Proof {
+    let numbers = match deduction.reason {
+        Reason::SinglePoint(at) => vec![at],
+        Reason::Subset(smaller, larger) => vec![smaller, larger],
+    };
+    let constraints = numbers
+        .iter()
+        .filter_map(|&at| constraints.iter().find(|constraint| constraint.at == at))
+        .cloned()
+        .collect();
+    Proof {
+        deduction,
+        constraints,
+    }
+}
+
+// the proof of `last`, after the proofs of the mines it counted as known
+// and of the ones those counted, and so on: only what it took, each once,
+// in the order they were worked out
+fn chain(proven: &[Proof], last: Proof) -> Vec<Proof> {
+    let mut needed = vec![false; proven.len()];
+    let mut unseen = vec![&last];
+    while let Some(proof) = unseen.pop() {
+        let known = proof
+            .constraints
+            .iter()
+            .flat_map(|constraint| &constraint.known);
+        for at in known {
+            let found = proven
+                .iter()
+                .position(|other| other.deduction.position == *at);
+            if let Some(index) = found.filter(|&index| !needed[index]) {
+                needed[index] = true;
+                unseen.push(&proven[index]);
+            }
+        }
+    }
+
+    let mut chain: Vec<Proof> = proven
+        .iter()
+        .zip(needed)
+        .filter(|(_, needed)| *needed)
+        .map(|(proof, _)| proof.clone())
+        .collect();
+    chain.push(last);
+    chain
+}
+
+// the solver's next move on the board as it stands: clearing a cell proven
+// safe (taking a wrong flag off it first) or flagging a proven mine, with
+// how it was proven; None when the numbers prove nothing left to do, and
+// the next move would be a guess. The player's flags aren't trusted, as
+// they can be wrong
+pub fn reasoning(board: &Board) -> Option<(BotMove, Vec<Proof>)> {
+    let mut known = vec![];
+    let mut proven = vec![];
+    loop {
+        let constraints = constraints(board, &known);
+        let found = explain_from(&constraints);
+        let next = found.iter().find_map(|&deduction| {
+            let cell = board.get_cell(deduction.position)?;
+            let command = match cell.state {
+                CellState::Safe { flagged: true, .. } => Command::Flag(deduction.position),
+                CellState::Safe { open: false, .. } => Command::Clear(deduction.position),
+                CellState::Bomb { flagged: false, .. } => Command::Flag(deduction.position),
+                _ => return None,
+            };
+            Some(BotMove { command, deduction })
+        });
+        if let Some(next) = next {
+            let last = proof(next.deduction, &constraints);
+            return Some((next, chain(&proven, last)));
+        }
+
+        // only flagged mines were found, which tell the next pass more
+        let mines: Vec<Deduction> = found
+            .into_iter()
+            .filter(|deduction| deduction.mine)
+            .collect();
+        if mines.is_empty() {
+            return None;
+        }
+        for deduction in mines {
+            known.push(deduction.position);
+            proven.push(proof(deduction, &constraints));
+        }
+    }
+}
+
+// the same, without the proofs
+pub fn bot_move(board: &Board) -> Option<BotMove> {
+    reasoning(board).map(|(next, _)| next)
+}
+
+// everything `deduce` can prove before another cell is opened, with each
+// mine it finds counted as known for the next pass; the player's flags
+// aren't trusted, as they can be wrong
+pub fn provable(board: &Board) -> Deductions {
+    let mut proven = Deductions::default();
+    loop {
+        let deductions = deduce(board, &proven.mines);
+        proven.add(&deductions.safe, false);
+        if deductions.mines.is_empty() {
+            return proven;
+        }
+        proven.add(&deductions.mines, true);
+    }
+}
+
+// whether the board can be cleared from `start` without ever guessing
+pub fn solves(board: &Board, start: CellPosition) -> bool {
+    let mut board = board.clone();
+    if board.clear(start).is_err() {
+        return false;
+    }
+    finishes(board)
+}
+
+// whether the board can be cleared from where it has got to without ever
+// guessing
+pub fn finishes(mut board: Board) -> bool {
+    let mut known = vec![];
+    while !board.is_won() {
+        let deductions = deduce(&board, &known);
+        if deductions.is_empty() {
+            return false;
+        }
+        known.extend(deductions.mines);
+        for safe in deductions.safe {
+            match board.clear(safe) {
+                Ok(()) | Err(ClearError::AlreadyCleared) => (),
+                Err(ClearError::ClearedBomb | ClearError::CellNotFound | ClearError::Rock) => {
+                    panic!("Deduced cells should be safe and on the board")
+                }
+                // nothing can be deduced from a board that contradicts itself
+                Err(ClearError::Inconsistent { .. }) => return false,
+            }
+        }
+    }
+    true
+}
+
+// in what order a search for frontier layouts decides the cells, which
+// makes no difference to what it finds but can to how soon it finds them
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CellOrder {
+    // as the numbers reach them, a number at a time down the board
+    Declaration,
+    // the cells next to the most numbers first
+    MostConstrained,
+    // outward from the first cell through the numbers it shares, so that
+    // each number's cells are decided close together and a wrong choice is
+    // soon caught
+    Breadth,
+}
+
+// when a search gives up: once `clock` reads `at`
+#[derive(Clone, Copy)]
+pub struct Deadline<'a> {
+    pub clock: &'a dyn Clock,
+    pub at: Duration,
+}
+
+// the layouts a search found, and whether they are all there are; one that
+// was stopped for having found too many, or for time, has only some
+#[derive(Debug, Clone, PartialEq)]
+pub struct Found {
+    pub layouts: Vec<Vec<bool>>,
+    pub complete: bool,
+}
+
+// how far a search has got: the mines placed around each number and the
+// cells of it still to be decided, and the layout so far
+struct Progress<'a> {
+    placed: Vec<usize>,
+    undecided: Vec<usize>,
+    mines: usize,
+    layout: Vec<bool>,
+    steps: usize,
+    deadline: Option<Deadline<'a>>,
+}
+
+// the ways of placing mines on the closed cells next to open numbers (the
+// frontier) that every number agrees with, searched a cell at a time
+#[derive(Debug, Clone)]
+pub struct FrontierSearch {
+    cells: Vec<CellPosition>,
+    // each number's cells, as indices into `cells`, and the mines it needs
+    numbers: Vec<(Vec<usize>, usize)>,
+    // the numbers each cell is next to
+    touching: Vec<Vec<usize>>,
+    // the most mines there are left to place
+    mines: usize,
+}
+
+impl FrontierSearch {
+    // `known` are mines already worked out, as for `explain`, which then
+    // aren't on the frontier; at most `mines` mines go on it. The cells are
+    // in `CellOrder::Declaration`
+    pub fn new(board: &Board, known: &[CellPosition], mines: usize) -> Self {
+        let constraints = constraints(board, known);
+        let mut index = vec![vec![None; board.width()]; board.height()];
+        let mut cells = vec![];
+        let mut touching: Vec<Vec<usize>> = vec![];
+        let mut numbers = vec![];
+
+        for (number, constraint) in constraints.iter().enumerate() {
+            let mut around = vec![];
+            for &cell in &constraint.cells {
+                let at = &mut index[cell.row_index][cell.col_index];
+                let i = *at.get_or_insert_with(|| {
+                    cells.push(cell);
+                    touching.push(vec![]);
+                    cells.len() - 1
+                });
+                touching[i].push(number);
+                around.push(i);
+            }
+            numbers.push((around, constraint.mines));
+        }
+
+        FrontierSearch {
+            cells,
+            numbers,
+            touching,
+            mines,
+        }
+    }
+
+    pub fn cells(&self) -> &[CellPosition] {
+        &self.cells
+    }
+
+    // the cells that share a number with the cell `at`
+    fn sharing(&self, at: usize) -> impl Iterator<Item = usize> + '_ {
+        self.touching[at]
+            .iter()
+            .flat_map(|&number| self.numbers[number].0.iter().copied())
+    }
+
+    // every cell, a part at a time, each part outward from its first cell
+    fn breadth_first(&self) -> Vec<usize> {
+        let mut seen = vec![false; self.cells.len()];
+        let mut order = vec![];
+        for first in 0..self.cells.len() {
+            if seen[first] {
+                continue;
+            }
+            seen[first] = true;
+            let start = order.len();
+            order.push(first);
+            let mut next = start;
+            while let Some(&at) = order.get(next) {
+                for other in self.sharing(at) {
+                    if !seen[other] {
+                        seen[other] = true;
+                        order.push(other);
+                    }
+                }
+                next += 1;
+            }
+        }
+        order
+    }
+
+    // the search for only the cells of `order`, in that order, and the
+    // numbers next to them
+    fn pick(&self, order: &[usize]) -> FrontierSearch {
+        let mut index = vec![None; self.cells.len()];
+        for (new, &old) in order.iter().enumerate() {
+            index[old] = Some(new);
+        }
+        let mut numbers = vec![];
+        let mut touching = vec![vec![]; order.len()];
+        for (cells, mines) in &self.numbers {
+            let cells: Option<Vec<usize>> = cells.iter().map(|&cell| index[cell]).collect();
+            if let Some(cells) = cells {
+                for &cell in &cells {
+                    touching[cell].push(numbers.len());
+                }
+                numbers.push((cells, *mines));
+            }
+        }
+        FrontierSearch {
+            cells: order.iter().map(|&old| self.cells[old]).collect(),
+            numbers,
+            touching,
+            mines: self.mines,
+        }
+    }
+
+    // the same search, deciding the cells in `order`
+    pub fn ordered(&self, order: CellOrder) -> FrontierSearch {
+        let mut cells: Vec<usize> = (0..self.cells.len()).collect();
+        match order {
+            CellOrder::Declaration => (),
+            CellOrder::MostConstrained => cells.sort_by_key(|&at| Reverse(self.touching[at].len())),
+            CellOrder::Breadth => cells = self.breadth_first(),
+        }
+        self.pick(&cells)
+    }
+
+    // the frontier split into parts that no number joins, each searched on
+    // its own with its cells in the order they are in here, which a layout
+    // of the whole frontier is a layout of each part of
+    pub fn parts(&self) -> Vec<FrontierSearch> {
+        let mut part = vec![None; self.cells.len()];
+        let mut parts = 0;
+        for at in self.breadth_first() {
+            let joined = self.sharing(at).find_map(|other| part[other]);
+            part[at] = Some(joined.unwrap_or_else(|| {
+                parts += 1;
+                parts - 1
+            }));
+        }
+        (0..parts)
+            .map(|index| {
+                let cells: Vec<usize> = (0..self.cells.len())
+                    .filter(|&at| part[at] == Some(index))
+                    .collect();
+                self.pick(&cells)
+            })
+            .collect()
+    }
+
+    // whether the cell `at` can be a mine, or can be safe, with the numbers
+    // around it still able to get the mines they need
+    fn fits(&self, at: usize, mine: bool, progress: &Progress) -> bool {
+        self.touching[at].iter().all(|&number| {
+            let (placed, undecided) = (progress.placed[number], progress.undecided[number]);
+            let needed = self.numbers[number].1;
+            match mine {
+                true => placed < needed,
+                false => placed + undecided > needed,
+            }
+        }) && (!mine || progress.mines < self.mines)
+    }
+
+    fn decide(&self, at: usize, mine: bool, progress: &mut Progress) {
+        for &number in &self.touching[at] {
+            progress.undecided[number] -= 1;
+            progress.placed[number] += usize::from(mine);
+        }
+        progress.mines += usize::from(mine);
+        progress.layout[at] = mine;
+    }
+
+    fn undecide(&self, at: usize, mine: bool, progress: &mut Progress) {
+        for &number in &self.touching[at] {
+            progress.undecided[number] += 1;
+            progress.placed[number] -= usize::from(mine);
+        }
+        progress.mines -= usize::from(mine);
+    }
+
+    // whether the search has to stop, having had its every step, or its
+    // time, which is only read every so often
+    fn stopped(progress: &mut Progress) -> bool {
+        let taken = SEARCH_STEPS - progress.steps;
+        let out_of_time = taken > 0
+            && taken.is_multiple_of(DEADLINE_EVERY)
+            && progress
+                .deadline
+                .is_some_and(|deadline| deadline.clock.now() >= deadline.at);
+        if progress.steps == 0 || out_of_time {
+            progress.steps = 0;
+            return true;
+        }
+        progress.steps -= 1;
+        false
+    }
+
+    // goes through the layouts from the cell `at` on, trying a mine first
+    // whenever `mine_first` says to; false once `found` has had enough, or
+    // the search has had to stop
+    fn walk(
+        &self,
+        at: usize,
+        progress: &mut Progress,
+        mine_first: &mut dyn FnMut() -> bool,
+        found: &mut dyn FnMut(&[bool]) -> bool,
+    ) -> bool {
+        if Self::stopped(progress) {
+            return false;
+        }
+        if at == self.cells.len() {
+            return found(&progress.layout);
+        }
+
+        let first = mine_first();
+        for mine in [first, !first] {
+            if self.fits(at, mine, progress) {
+                self.decide(at, mine, progress);
+                let more = self.walk(at + 1, progress, mine_first, found);
+                self.undecide(at, mine, progress);
+                if !more {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    // a search that takes its choices from `mine_first`; true if it got
+    // to the end without being stopped
+    fn search(
+        &self,
+        deadline: Option<Deadline>,
+        mine_first: &mut dyn FnMut() -> bool,
+        found: &mut dyn FnMut(&[bool]) -> bool,
+    ) -> bool {
+        let mut progress = Progress {
+            placed: vec![0; self.numbers.len()],
+            undecided: self.numbers.iter().map(|(cells, _)| cells.len()).collect(),
+            mines: 0,
+            layout: vec![false; self.cells.len()],
+            steps: SEARCH_STEPS,
+            deadline,
+        };
+        self.walk(0, &mut progress, mine_first, found)
+    }
+
+    // every layout, as whether each of `cells` is a mine, unless there are
+    // more than `limit` of them, or too many to search through by the
+    // deadline
+    pub fn all(&self, limit: usize, deadline: Option<Deadline>) -> Found {
+        let mut layouts = vec![];
+        let complete = self.search(deadline, &mut || false, &mut |layout| {
+            layouts.push(layout.to_vec());
+            layouts.len() <= limit
+        });
+        Found { layouts, complete }
+    }
+
+    // the first layout a search that decides each cell at random comes
+    // to; any layout can come up, but not all as often as each other
+    pub fn any(&self, rng: &mut impl Rng) -> Option<Vec<bool>> {
+        let mut layout = None;
+        self.search(None, &mut || rng.gen(), &mut |found| {
+            layout = Some(found.to_vec());
+            false
+        });
+        layout
+    }
+}
@@ -0,0 +1,382 @@
+use crate::dirs::data_dir;
+use crate::game::{Game, SPLIT_PERCENTS};
+use crate::gauntlet::Score;
+use crate::i18n::{fill, text, Msg};
+use crate::render::format_tenths;
+use crate::versions::{parse_version, unchanged, upgrade, Format};
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::PathBuf;
+use std::time::Duration;
+
+const STATS_FILE: &str = "stats.txt";
+
+// one line per board configuration, e.g. "splits 10x10/16 4.213 9.870 - -",
+// with the rocks after another slash if there are any, e.g. "10x10/16/5",
+// holding the best time for each of SPLIT_PERCENTS, or "-" if never reached,
+// a line listing the achievements earned, e.g. "achievements first_win",
+// one per campaign level won with the best time, e.g. "campaign 3 41.250",
+// and one per gauntlet budget with the best score, as the minutes, boards
+// cleared and time left, e.g. "gauntlet 5 12 20.100"
+const HEADER: &str = "# minesweeper-cli stats";
+
+// the version written on the line after the header
+pub const VERSION: u32 = 2;
+
+// each from the version before to the next, applied to the whole file
+const MIGRATIONS: [fn(String) -> String; VERSION as usize - 1] = [
+    // 2 began with the version line
+    unchanged,
+];
+
+type Splits = [Option<Duration>; SPLIT_PERCENTS.len()];
+
+// everything kept across games
+#[derive(Default)]
+pub struct StatsFile {
+    splits: Vec<(String, Splits)>,
+    pub achievements: Vec<String>,
+    // level numbers and best times
+    pub campaign: Vec<(usize, Duration)>,
+    gauntlet: Vec<(u64, Score)>,
+    // read from a later release's file, which is left as it is
+    newer: bool,
+}
+
+fn stats_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join(STATS_FILE))
+}
+
+// games are only comparable on a board of the same size, mine count and
+// rock count; the rocks are left out when there are none, as they were
+// before there could be any
+fn configuration(game: &Game) -> String {
+    let board = game.initial_board();
+    let base = format!(
+        "{}x{}/{}",
+        board.height(),
+        board.width(),
+        board.count_bombs()
+    );
+    match board.count_rocks() {
+        0 => base,
+        rocks => format!("{base}/{rocks}"),
+    }
+}
+
+fn parse_splits<'a>(fields: impl Iterator<Item = &'a str>) -> Splits {
+    let mut splits = [None; SPLIT_PERCENTS.len()];
+    for (split, field) in splits.iter_mut().zip(fields) {
+        *split = field
+            .parse()
+            .ok()
+            .and_then(|secs| Duration::try_from_secs_f64(secs).ok());
+    }
+    splits
+}
+
+fn format_splits(splits: &Splits) -> String {
+    splits
+        .iter()
+        .map(|split| match split {
+            Some(split) => format!("{:.3}", split.as_secs_f64()),
+            None => "-".to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl StatsFile {
+    // a missing file is the same as no games played, and lines that make no
+    // sense are skipped; a file from before versions were written is version 1
+    pub fn load() -> StatsFile {
+        let mut stats = StatsFile::default();
+        let Some(contents) = stats_path().and_then(|path| fs::read_to_string(path).ok()) else {
+            return stats;
+        };
+        let version = contents
+            .lines()
+            .find_map(|line| line.strip_prefix("version "))
+            .map_or(Ok(1), parse_version);
+        let contents = match version
+            .and_then(|version| upgrade(Format::Stats, version, contents, &MIGRATIONS))
+        {
+            Ok(contents) => contents,
+            Err(e) => {
+                println!("{}", fill(Msg::StatsLeftAlone, &[&e]));
+                stats.newer = true;
+                return stats;
+            }
+        };
+
+        for line in contents.lines() {
+            let mut fields = line.split_whitespace();
+            match fields.next() {
+                Some("splits") => {
+                    if let Some(configuration) = fields.next() {
+                        stats
+                            .splits
+                            .push((configuration.to_string(), parse_splits(fields)));
+                    }
+                }
+                Some("achievements") => stats.achievements.extend(fields.map(String::from)),
+                Some("campaign") => {
+                    let level = fields.next().and_then(|level| level.parse().ok());
+                    let best = parse_splits(fields)[0];
+                    if let (Some(level), Some(best)) = (level, best) {
+                        stats.campaign.push((level, best));
+                    }
+                }
+                Some("gauntlet") => {
+                    let minutes = fields.next().and_then(|minutes| minutes.parse().ok());
+                    let cleared = fields.next().and_then(|cleared| cleared.parse().ok());
+                    let remaining = parse_splits(fields)[0];
+                    if let (Some(minutes), Some(cleared), Some(remaining)) =
+                        (minutes, cleared, remaining)
+                    {
+                        stats.gauntlet.push((minutes, Score { cleared, remaining }));
+                    }
+                }
+                _ => (),
+            }
+        }
+        stats
+    }
+
+    fn store(&self) -> io::Result<()> {
+        let path = stats_path()
+            .ok_or_else(|| io::Error::new(ErrorKind::NotFound, text(Msg::NoDataDir)))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut contents = format!("{HEADER}\nversion {VERSION}\n");
+        for (configuration, splits) in &self.splits {
+            contents += &format!("splits {configuration} {}\n", format_splits(splits));
+        }
+        if !self.achievements.is_empty() {
+            contents += &format!("achievements {}\n", self.achievements.join(" "));
+        }
+        for (level, best) in &self.campaign {
+            contents += &format!("campaign {level} {:.3}\n", best.as_secs_f64());
+        }
+        for (minutes, best) in &self.gauntlet {
+            contents += &format!(
+                "gauntlet {minutes} {} {:.3}\n",
+                best.cleared,
+                best.remaining.as_secs_f64()
+            );
+        }
+
+        // as with the autosave, so that a crash mid-write keeps the old bests
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, contents)?;
+        fs::rename(temp, path)
+    }
+
+    pub fn gauntlet_best(&self, minutes: u64) -> Option<Score> {
+        self.gauntlet
+            .iter()
+            .find(|&&(budget, _)| budget == minutes)
+            .map(|&(_, best)| best)
+    }
+
+    pub fn record_gauntlet(&mut self, minutes: u64, score: Score) {
+        match self
+            .gauntlet
+            .iter_mut()
+            .find(|(budget, _)| *budget == minutes)
+        {
+            Some((_, best)) => *best = (*best).max(score),
+            None => self.gauntlet.push((minutes, score)),
+        }
+    }
+
+    // a failure to save is reported rather than ending the game
+    pub fn save(&self) {
+        if self.newer {
+            return;
+        }
+        if let Err(e) = self.store() {
+            println!("{}", fill(Msg::CouldntSaveStats, &[&e]));
+        }
+    }
+}
+
+// negative when ahead of the best
+fn format_delta(split: Duration, best: Duration) -> String {
+    format!("{:+.1}", split.as_secs_f64() - best.as_secs_f64())
+}
+
+// a table of this game's splits against the best ones from before it
+fn render_splits(splits: &[(usize, Duration)], best: &Splits) -> String {
+    let mut table = format!(
+        "{:<7}{:>9}{:>9}{:>8}",
+        text(Msg::SplitsHeading),
+        text(Msg::SplitsTime),
+        text(Msg::SplitsBest),
+        "+/-"
+    );
+
+    for (index, percent) in SPLIT_PERCENTS.iter().enumerate() {
+        let Some(&(_, split)) = splits.iter().find(|(p, _)| p == percent) else {
+            continue;
+        };
+        let (best, delta) = match best[index] {
+            Some(best) => (format_tenths(best), format_delta(split, best)),
+            None => ("-".to_string(), String::new()),
+        };
+        let row = format!(
+            "{:<7}{:>9}{:>9}{:>8}",
+            format!("{percent}%"),
+            format_tenths(split),
+            best,
+            delta
+        );
+        table += &format!("\n{}", row.trim_end());
+    }
+
+    table
+}
+
+// compares a finished game's splits with the bests for its configuration,
+// then keeps whichever of each is better in `stats`, for the caller to
+// save; None if it reached no milestone
+pub fn record_splits(stats: &mut StatsFile, game: &Game) -> Option<String> {
+    if game.splits().is_empty() {
+        return None;
+    }
+
+    let configuration = configuration(game);
+    let index = match stats.splits.iter().position(|(c, _)| *c == configuration) {
+        Some(index) => index,
+        None => {
+            stats
+                .splits
+                .push((configuration, [None; SPLIT_PERCENTS.len()]));
+            stats.splits.len() - 1
+        }
+    };
+
+    let best = &mut stats.splits[index].1;
+    let table = render_splits(game.splits(), best);
+    for (index, percent) in SPLIT_PERCENTS.iter().enumerate() {
+        if let Some(&(_, split)) = game.splits().iter().find(|(p, _)| p == percent) {
+            if best[index].is_none_or(|best| split < best) {
+                best[index] = Some(split);
+            }
+        }
+    }
+    Some(table)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::board::{Board, CellPosition};
+    use crate::command::Command;
+    use crate::timer::Clock;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    // reads whatever it was last set to, in seconds
+    #[derive(Default)]
+    pub struct SetByHand(AtomicU64);
+
+    impl SetByHand {
+        pub fn set(&self, seconds: u64) {
+            self.0.store(seconds, Ordering::Relaxed);
+        }
+    }
+
+    impl Clock for SetByHand {
+        fn now(&self) -> Duration {
+            Duration::from_secs(self.0.load(Ordering::Relaxed))
+        }
+    }
+
+    fn secs(seconds: u64) -> Option<Duration> {
+        Some(Duration::from_secs(seconds))
+    }
+
+    // four safe cells walled off from each other, so that each clear is the
+    // next quarter, cleared at the given seconds
+    fn played(seconds: [u64; 4]) -> Game {
+        let board: Board = "..*\n*.*\n**.".parse().expect("The layout should parse");
+        let clock = Arc::new(SetByHand::default());
+        let mut game = Game::with_clock(board, 1, clock.clone());
+        let cells = [(0, 0), (0, 1), (1, 1), (2, 2)];
+        for (second, (row, col)) in seconds.into_iter().zip(cells) {
+            clock.set(second);
+            game.apply(0, Command::Clear(CellPosition::new(row, col)))
+                .expect("The cell should open");
+        }
+        game
+    }
+
+    #[test]
+    fn splits_are_taken_from_the_clock_at_each_quarter() {
+        let game = played([10, 13, 18, 28]);
+        let splits: Vec<(usize, Option<Duration>)> = game
+            .splits()
+            .iter()
+            .map(|&(percent, split)| (percent, Some(split)))
+            .collect();
+        assert_eq!(
+            splits,
+            [(25, secs(0)), (50, secs(3)), (75, secs(8)), (100, secs(18))]
+        );
+    }
+
+    #[test]
+    fn splits_are_compared_with_the_bests_and_the_better_kept() {
+        let mut stats = StatsFile {
+            splits: vec![
+                ("9x9/10".to_string(), [secs(1); 4]),
+                ("3x3/5".to_string(), [secs(1), secs(2), None, secs(20)]),
+            ],
+            ..StatsFile::default()
+        };
+
+        let table = record_splits(&mut stats, &played([10, 13, 18, 28]));
+        let expected = [
+            "Split       Time     Best     +/-",
+            "25%       0:00.0   0:01.0    -1.0",
+            "50%       0:03.0   0:02.0    +1.0",
+            "75%       0:08.0        -",
+            "100%      0:18.0   0:20.0    -2.0",
+        ];
+        assert_eq!(table.as_deref(), Some(expected.join("\n").as_str()));
+        assert_eq!(stats.splits[1].1, [secs(0), secs(2), secs(8), secs(18)]);
+        // and only the game's own configuration
+        assert_eq!(stats.splits[0].1, [secs(1); 4]);
+
+        // a first game on a board is its own best
+        let mut stats = StatsFile::default();
+        let table = record_splits(&mut stats, &played([0, 1, 2, 3])).unwrap_or_default();
+        assert!(table.lines().nth(1).is_some_and(|row| row.ends_with('-')));
+        assert_eq!(
+            stats.splits,
+            [("3x3/5".to_string(), [secs(0), secs(1), secs(2), secs(3)])]
+        );
+    }
+
+    #[test]
+    fn boards_with_rocks_keep_bests_of_their_own() {
+        let game = |layout: &str| {
+            let board: Board = layout.parse().expect("The layout should parse");
+            Game::new(board, 1)
+        };
+        assert_eq!(configuration(&game("..*\n*.*\n**.")), "3x3/5");
+        assert_eq!(configuration(&game("..*\n*o*\n**.")), "3x3/5/1");
+        assert_eq!(configuration(&game("o.*\n*o*\n**.")), "3x3/5/2");
+    }
+
+    #[test]
+    fn splits_are_written_and_read_back_to_the_millisecond() {
+        let splits = [Some(Duration::from_millis(4213)), None, secs(9), None];
+        let line = format_splits(&splits);
+        assert_eq!(line, "4.213 - 9.000 -");
+        assert_eq!(parse_splits(line.split_whitespace()), splits);
+    }
+}
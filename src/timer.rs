@@ -0,0 +1,135 @@
+use std::sync::Arc;
+#[cfg(feature = "clock")]
+use std::sync::OnceLock;
+use std::time::Duration;
+#[cfg(feature = "clock")]
+use std::time::Instant;
+
+// where a timer gets the time from, so that it can be driven by hand or by
+// a host without a clock of its own: the time since the clock started
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Duration;
+}
+
+// the time since it was first read; Instant panics on wasm32, so it is only
+// there with the `clock` feature
+#[cfg(feature = "clock")]
+pub struct SystemClock;
+
+#[cfg(feature = "clock")]
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        static STARTED: OnceLock<Instant> = OnceLock::new();
+        STARTED.get_or_init(Instant::now).elapsed()
+    }
+}
+
+// a stopwatch that can be paused: the time it has run for is the time
+// banked from earlier stretches, plus the current stretch if it is running
+#[derive(Clone)]
+pub struct Timer {
+    clock: Arc<dyn Clock>,
+    banked: Duration,
+    running_since: Option<Duration>,
+    // when the player was last asked for a move
+    prompted_at: Option<Duration>,
+    started: bool,
+    stopped: bool,
+}
+
+impl Timer {
+    pub fn new(clock: Arc<dyn Clock>) -> Self {
+        Timer {
+            clock,
+            banked: Duration::ZERO,
+            running_since: None,
+            prompted_at: None,
+            started: false,
+            stopped: false,
+        }
+    }
+
+    pub fn clock(&self) -> Arc<dyn Clock> {
+        Arc::clone(&self.clock)
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        match self.running_since {
+            Some(since) => self.banked + self.clock.now().saturating_sub(since),
+            None => self.banked,
+        }
+    }
+
+    // the first call starts it; after that it does nothing
+    pub fn start(&mut self) {
+        if !self.started {
+            self.started = true;
+            self.running_since = Some(self.clock.now());
+        }
+    }
+
+    pub fn pause(&mut self) {
+        if let Some(since) = self.running_since.take() {
+            self.banked += self.clock.now().saturating_sub(since);
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if self.started && !self.stopped && self.running_since.is_none() {
+            self.running_since = Some(self.clock.now());
+        }
+    }
+
+    pub fn prompted(&mut self) {
+        self.prompted_at = Some(self.clock.now());
+    }
+
+    // how long the player has been looking at the current prompt
+    pub fn since_prompt(&self) -> Option<Duration> {
+        let prompted_at = self.prompted_at?;
+        Some(self.clock.now().saturating_sub(prompted_at))
+    }
+
+    // takes back whatever the wait since the last prompt ran past `limit`,
+    // as though the timer had paused there; returns how much that was
+    pub fn answered(&mut self, limit: Duration) -> Option<Duration> {
+        let prompted_at = self.prompted_at.take()?;
+        let since = self.running_since?;
+        let now = self.clock.now();
+
+        // only the part of the gap the timer was running for can come off
+        let waited = now.saturating_sub(prompted_at.max(since));
+        let idle = waited.checked_sub(limit).filter(|idle| !idle.is_zero())?;
+        self.pause();
+        self.banked = self.banked.saturating_sub(idle);
+        self.resume();
+        Some(idle)
+    }
+
+    // for good, once the game is over
+    pub fn stop(&mut self) {
+        self.pause();
+        self.stopped = true;
+    }
+
+    // picks up from a saved game: `elapsed` already on the clock, and still
+    // running unless the game had finished
+    pub fn restore(&mut self, elapsed: Duration, finished: bool) {
+        self.banked = elapsed;
+        self.started = true;
+        self.stopped = finished;
+        self.running_since = (!finished).then(|| self.clock.now());
+    }
+}
+
+// minutes:seconds, growing an hours field for long games
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes:02}:{seconds:02}")
+    }
+}
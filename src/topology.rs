@@ -0,0 +1,107 @@
+// which cells touch which, apart from what is on them, and the cascade that
+// spreads over them: a board with edges, one wrapped round onto itself both
+// ways, and the endless plane all open the same way
+use crate::board::{CellPosition, Dims};
+use crate::command::Point;
+use std::collections::{HashSet, VecDeque};
+use std::hash::Hash;
+
+pub trait Topology {
+    type Position: Copy + Eq + Hash;
+
+    // the positions touching `position`, as many as there are and in any
+    // order; a small wrapped board can name the same one twice
+    fn neighbours(&self, position: Self::Position) -> Vec<Self::Position>;
+}
+
+// a board with edges, where the cells along them have fewer neighbours
+impl Topology for Dims {
+    type Position = CellPosition;
+
+    fn neighbours(&self, position: CellPosition) -> Vec<CellPosition> {
+        position.neighbors::<1>(*self).collect()
+    }
+}
+
+// a board whose left edge touches its right and whose top touches its
+// bottom, so that every cell has a full ring of neighbours
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Wrapped(pub Dims);
+
+impl Topology for Wrapped {
+    type Position = CellPosition;
+
+    fn neighbours(&self, position: CellPosition) -> Vec<CellPosition> {
+        let Dims { height, width } = self.0;
+        let step = |index: usize, length: usize, by: isize| {
+            (index as isize + by).rem_euclid(length as isize) as usize
+        };
+        (-1..=1)
+            .flat_map(|rows| (-1..=1).map(move |cols| (rows, cols)))
+            .filter(|&offset| offset != (0, 0))
+            .map(|(rows, cols)| CellPosition {
+                row_index: step(position.row_index, height, rows),
+                col_index: step(position.col_index, width, cols),
+            })
+            .filter(|&around| around != position)
+            .collect()
+    }
+}
+
+// the endless board, without edges in any direction
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plane;
+
+impl Topology for Plane {
+    type Position = Point;
+
+    fn neighbours(&self, point: Point) -> Vec<Point> {
+        (-1..=1)
+            .flat_map(|row| (-1..=1).map(move |col| (row, col)))
+            .filter(|&offset| offset != (0, 0))
+            .map(|(row, col)| Point {
+                row: point.row + row,
+                col: point.col + col,
+            })
+            .collect()
+    }
+}
+
+// what a cascade does at a cell it reaches
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Reach {
+    // stops there, without opening it
+    Stop,
+    // opens it, and goes no further from it
+    Open,
+    // opens it and goes on to its neighbours
+    Spread,
+}
+
+// the cells opened from `start`, breadth first and in the order they were
+// opened; `reach` is asked about each cell once, however many of the
+// cells it touches spread to it, and an error from it ends the cascade
+pub fn cascade<T: Topology, E>(
+    topology: &T,
+    start: T::Position,
+    mut reach: impl FnMut(T::Position) -> Result<Reach, E>,
+) -> Result<Vec<T::Position>, E> {
+    let mut visited = HashSet::from([start]);
+    let mut pending = VecDeque::from([start]);
+    let mut opened = vec![];
+    while let Some(position) = pending.pop_front() {
+        match reach(position)? {
+            Reach::Stop => continue,
+            Reach::Open => opened.push(position),
+            Reach::Spread => {
+                opened.push(position);
+                for around in topology.neighbours(position) {
+                    if visited.insert(around) {
+                        pending.push_back(around);
+                    }
+                }
+            }
+        }
+    }
+    Ok(opened)
+}
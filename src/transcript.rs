@@ -0,0 +1,41 @@
+use crate::game::{Game, GameState};
+use crate::i18n::{fill, text, Msg};
+use crate::replay::replay;
+use crate::timer::format_duration;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const FRAME_SEPARATOR: &str = "==========";
+
+// a plain-text "animation" of a game: the board after every move, in order
+pub fn render_transcript(game: &Game, code: &str) -> String {
+    let mut frames = vec![
+        format!("Minesweeper {code}"),
+        format!("{}\n{}", text(Msg::TranscriptStart), game.restart().board()),
+    ];
+
+    for (index, (game_move, board)) in replay(game).into_iter().enumerate() {
+        frames.push(format!(
+            "{}\n{board}",
+            fill(Msg::TranscriptMove, &[&(index + 1), game_move])
+        ));
+    }
+
+    let result = match game.state() {
+        GameState::Won => text(Msg::ResultWon),
+        GameState::Lost => text(Msg::ResultLost),
+        GameState::Resigned => text(Msg::ResultResigned),
+        GameState::Playing => text(Msg::ResultInProgress),
+    };
+    frames.push(fill(
+        Msg::TranscriptResult,
+        &[&result, &format_duration(game.elapsed())],
+    ));
+
+    frames.join(&format!("\n{FRAME_SEPARATOR}\n")) + "\n"
+}
+
+pub fn write_transcript(game: &Game, code: &str, path: &Path) -> io::Result<()> {
+    fs::write(path, render_transcript(game, code))
+}
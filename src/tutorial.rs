@@ -0,0 +1,173 @@
+use crate::board::{Board, Mines};
+use crate::command::Command;
+use crate::config::Config;
+use crate::game::{Game, GameState};
+use crate::i18n::{fill, text, Msg};
+use crate::render::{render_game, render_last_move, render_revealed};
+use rand::random;
+use std::io::stdin;
+
+// a lesson is a script of steps that the engine below walks through; new
+// lessons only need new data, not new control flow
+enum Step {
+    Say(Msg),
+    // starts a fresh board from rows of `*` (mine) and `.` (safe)
+    Layout(&'static [&'static str]),
+    // waits until the player enters exactly this command, repeating the
+    // instruction on anything else
+    Expect {
+        command: &'static str,
+        instruction: Msg,
+    },
+    // a random board to play freely until it is won or lost
+    FreePlay {
+        height: usize,
+        width: usize,
+        mines: usize,
+    },
+}
+
+const BASICS: &[Step] = &[
+    Step::Say(Msg::TutorialWelcome),
+    Step::Layout(&[".....", ".....", ".....", "...**", "...*."]),
+    Step::Expect {
+        command: "c 2 2",
+        instruction: Msg::TutorialFirstClear,
+    },
+    Step::Say(Msg::TutorialNumbers),
+    Step::Expect {
+        command: "c 0 0",
+        instruction: Msg::TutorialCorner,
+    },
+    Step::Say(Msg::TutorialCascade),
+    Step::Expect {
+        command: "f 3 3",
+        instruction: Msg::TutorialFlagFirst,
+    },
+    Step::Expect {
+        command: "f 3 4",
+        instruction: Msg::TutorialFlagSecond,
+    },
+    Step::Say(Msg::TutorialThirdMine),
+    Step::Expect {
+        command: "f 4 3",
+        instruction: Msg::TutorialFlagThird,
+    },
+    Step::Say(Msg::TutorialAllFlagged),
+    Step::Expect {
+        command: "c 4 4",
+        instruction: Msg::TutorialLastCell,
+    },
+    Step::Say(Msg::TutorialFreePlay),
+    Step::FreePlay {
+        height: 6,
+        width: 6,
+        mines: 5,
+    },
+];
+
+fn layout_board(rows: &[&str]) -> Board {
+    Board::from_bombs(
+        rows.iter()
+            .map(|row| row.chars().map(|symbol| symbol == '*').collect())
+            .collect(),
+    )
+}
+
+// None means stdin was closed, and the tutorial should stop
+fn read_command() -> Option<Result<Command, String>> {
+    let mut line = String::new();
+    match stdin().read_line(&mut line) {
+        Ok(0) => None,
+        Ok(_) => Some(line.parse().map_err(|e| format!("{e}"))),
+        Err(e) => Some(Err(format!("{e}"))),
+    }
+}
+
+fn free_play(game: &mut Game, config: &Config) -> Option<GameState> {
+    loop {
+        println!("{}\n------", render_game(game, config));
+        println!("{}", text(Msg::EnterCommand));
+
+        match read_command()? {
+            Ok(command @ (Command::Clear(_) | Command::Flag(_))) => match game.apply(0, command) {
+                Ok(GameState::Playing) => {
+                    if let Some(feedback) = render_last_move(game, false) {
+                        println!("{feedback}");
+                    }
+                }
+                Ok(state) => return Some(state),
+                Err(e) => println!("{e}"),
+            },
+            Ok(_) => println!("{}", text(Msg::TutorialOnlyClearAndFlag)),
+            Err(e) => println!("{e}"),
+        }
+    }
+}
+
+fn run_lesson(steps: &[Step], config: &Config) -> String {
+    let mut game: Option<Game> = None;
+
+    for step in steps {
+        match step {
+            Step::Say(message) => println!("{}", text(*message)),
+            Step::Layout(rows) => {
+                let new_game = Game::new(layout_board(rows), 1);
+                println!("{}\n------", render_game(&new_game, config));
+                game = Some(new_game);
+            }
+            Step::Expect {
+                command,
+                instruction,
+            } => {
+                let expected: Command = command
+                    .parse()
+                    .expect("Tutorial commands should be valid commands");
+                let game = game
+                    .as_mut()
+                    .expect("Tutorial steps should set up a board before expecting moves");
+
+                let instruction = text(*instruction);
+                println!("{instruction}");
+                loop {
+                    match read_command() {
+                        None => return String::from(text(Msg::TutorialStopped)),
+                        Some(Ok(entered)) if entered == expected => break,
+                        Some(_) => println!("{}", fill(Msg::TutorialNotQuite, &[&instruction])),
+                    }
+                }
+
+                if let Err(e) = game.apply(0, expected) {
+                    println!("{e}");
+                }
+                println!("{}\n------", render_game(game, config));
+            }
+            &Step::FreePlay {
+                height,
+                width,
+                mines,
+            } => {
+                let game = game.insert(Game::new(
+                    Board::from_seed(random(), height, width, Mines::Count(mines)),
+                    1,
+                ));
+
+                match free_play(game, config) {
+                    Some(GameState::Won) => println!("{}", text(Msg::TutorialWon)),
+                    Some(GameState::Lost) => println!("{}", text(Msg::TutorialLost)),
+                    _ => return String::from(text(Msg::TutorialStopped)),
+                }
+            }
+        }
+    }
+
+    if let Some(game) = &game {
+        println!("{}", render_revealed(game.board(), config));
+    }
+
+    String::from(text(Msg::TutorialComplete))
+}
+
+pub fn run(config: &Config) -> String {
+    run_lesson(BASICS, config)
+}
@@ -0,0 +1,92 @@
+use crate::i18n::{fill, text, Msg};
+use std::fmt::Display;
+
+// the files kept on disk, each with a version of its own that goes up
+// whenever what it writes changes; files from before versions were written
+// are version 1, and everything a release has written must keep loading
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    // named saves and the autosave alike
+    Save,
+    // each line of the history on its own
+    History,
+    Stats,
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let out = match self {
+            Format::Save => text(Msg::FormatSave),
+            Format::History => text(Msg::FormatHistory),
+            Format::Stats => text(Msg::FormatStats),
+        };
+
+        write!(f, "{out}")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum VersionError {
+    Invalid(String),
+    // written by a later release than this one, which can't know what it
+    // changed; `current` is the newest this one reads
+    Newer {
+        format: Format,
+        version: u32,
+        current: u32,
+    },
+}
+
+impl Display for VersionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let out = match self {
+            VersionError::Invalid(version) => fill(Msg::InvalidFormatVersion, &[version]),
+            VersionError::Newer {
+                format,
+                version,
+                current,
+            } => fill(Msg::NewerFormatVersion, &[format, version, current]),
+        };
+
+        write!(f, "{out}")
+    }
+}
+
+// a version as it is written after the word that names it
+pub fn parse_version(version: &str) -> Result<u32, VersionError> {
+    version
+        .parse()
+        .ok()
+        .filter(|&version| version > 0)
+        .ok_or_else(|| VersionError::Invalid(version.to_string()))
+}
+
+// a step for a version that only began saying which it is, and otherwise
+// reads the same as the one before
+pub fn unchanged<T>(data: T) -> T {
+    data
+}
+
+// `data` as it was read at `version`, brought up to the newest version a
+// step at a time; `migrations` has one step per version after the first,
+// the first of them from version 1 to 2, so the newest is one more than
+// their number
+pub fn upgrade<T>(
+    format: Format,
+    version: u32,
+    data: T,
+    migrations: &[fn(T) -> T],
+) -> Result<T, VersionError> {
+    let current = migrations.len() as u32 + 1;
+    if version > current {
+        return Err(VersionError::Newer {
+            format,
+            version,
+            current,
+        });
+    }
+    let first = version.saturating_sub(1) as usize;
+    Ok(migrations[first..]
+        .iter()
+        .fold(data, |data, step| step(data)))
+}
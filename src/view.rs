@@ -0,0 +1,323 @@
+// what of a game can be seen from outside it, and that alone: everything
+// that goes to someone other than the player at the terminal (JSON lines,
+// clients, spectators, exports) is written from a `GameView`, which copies out
+// what is shown and keeps nothing of the game, so that what isn't in it can't
+// be written however the protocols grow
+use crate::board::{Board, Cell, CellPosition, CellState, FlagChange};
+use crate::game::{Game, GameEvent, GameState, Move};
+use crate::json::Value;
+use crate::layout::CellLayout;
+use std::fmt::Display;
+
+// what can be seen of a cell from outside the game: a closed cell is only
+// ever closed, whatever is under it, and there is no view of a closed mine
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CellView {
+    Closed,
+    Flagged,
+    Question,
+    Open(u8),
+    // what a peek showed
+    PeekedMine,
+    PeekedSafe,
+    Exploded,
+    Defused,
+    Rock,
+    // a cell that can't be, as a board set by hand can hold; see `Cell::check`
+    Broken,
+}
+
+impl CellView {
+    pub fn of(cell: &Cell) -> Self {
+        match cell.state {
+            _ if cell.check().is_err() => CellView::Broken,
+            CellState::Bomb { exploded: true, .. } if cell.defused => CellView::Defused,
+            CellState::Bomb { exploded: true, .. } => CellView::Exploded,
+            CellState::Safe { open: true, .. } => CellView::Open(cell.bombs_around),
+            CellState::Rock => CellView::Rock,
+            CellState::Bomb { flagged: true, .. } | CellState::Safe { flagged: true, .. } => {
+                CellView::Flagged
+            }
+            CellState::Bomb { .. } if cell.peeked => CellView::PeekedMine,
+            CellState::Safe { .. } if cell.peeked => CellView::PeekedSafe,
+            _ if cell.question => CellView::Question,
+            _ => CellView::Closed,
+        }
+    }
+}
+
+// the same symbols as the cells themselves
+impl Display for CellView {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let out = match self {
+            CellView::Closed => "#",
+            CellView::Flagged => "^",
+            CellView::Question => "?",
+            CellView::Open(bombs_around) => return write!(f, "{bombs_around}"),
+            CellView::PeekedMine => "!",
+            CellView::PeekedSafe => "~",
+            CellView::Exploded => "*",
+            CellView::Defused => "+",
+            CellView::Rock => "%",
+            CellView::Broken => "E",
+        };
+
+        write!(f, "{out}")
+    }
+}
+
+// a board as the player sees it, for anything that goes to someone else
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardView {
+    rows: Vec<Vec<CellView>>,
+}
+
+impl BoardView {
+    pub fn of(board: &Board) -> Self {
+        BoardView {
+            rows: board
+                .rows()
+                .map(|row| row.iter().map(CellView::of).collect())
+                .collect(),
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn width(&self) -> usize {
+        self.rows.first().map_or(0, Vec::len)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[CellView]> {
+        self.rows.iter().map(Vec::as_slice)
+    }
+
+    pub fn get(&self, position: CellPosition) -> Option<CellView> {
+        self.rows
+            .get(position.row_index)?
+            .get(position.col_index)
+            .copied()
+    }
+
+    // one string per row, as the board is shown
+    pub fn lines(&self) -> Vec<String> {
+        self.rows
+            .iter()
+            .map(|row| CellLayout::text().row(row.iter().map(CellView::to_string)))
+            .collect()
+    }
+}
+
+impl Display for BoardView {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.lines().join("\n"))
+    }
+}
+
+// the numbers shown beside the board
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Counters {
+    pub mines_left: isize,
+    pub flags: usize,
+    pub open: usize,
+    // open or not, which the mine count gives away already
+    pub safe: usize,
+    pub moves: usize,
+    pub lives: u32,
+    pub shields: u32,
+    pub peeks: u32,
+}
+
+// a game as the player sees it, as of its last move
+#[derive(Debug, Clone)]
+pub struct GameView {
+    state: GameState,
+    counters: Counters,
+    board: BoardView,
+    events: Vec<GameEvent>,
+    last_move: Option<Move>,
+}
+
+impl GameView {
+    pub fn of(game: &Game) -> Self {
+        let board = game.board();
+        GameView {
+            state: game.state(),
+            counters: Counters {
+                mines_left: board.mines_left(),
+                flags: board.count_flags(),
+                open: board.count_open(),
+                safe: board.count_safe(),
+                moves: game.moves().len(),
+                lives: game.lives(),
+                shields: game.shields(),
+                peeks: game.peeks_left(),
+            },
+            board: BoardView::of(board),
+            events: game.events().to_vec(),
+            last_move: game.moves().last().cloned(),
+        }
+    }
+
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    pub fn counters(&self) -> Counters {
+        self.counters
+    }
+
+    pub fn board(&self) -> &BoardView {
+        &self.board
+    }
+
+    // what the last move changed
+    pub fn events(&self) -> &[GameEvent] {
+        &self.events
+    }
+
+    pub fn last_move(&self) -> Option<&Move> {
+        self.last_move.as_ref()
+    }
+
+    // the board, one string per row
+    pub fn board_json(&self) -> Value {
+        Value::Array(self.board.lines().into_iter().map(Value::from).collect())
+    }
+
+    pub fn counters_json(&self) -> Value {
+        let counters = self.counters;
+        Value::object([
+            ("mines_left", Value::from(counters.mines_left as f64)),
+            ("flags", Value::from(counters.flags)),
+            ("open", Value::from(counters.open)),
+            ("moves", Value::from(counters.moves)),
+            ("lives", Value::from(u64::from(counters.lives))),
+            ("shields", Value::from(u64::from(counters.shields))),
+            ("peeks", Value::from(u64::from(counters.peeks))),
+        ])
+    }
+
+    pub fn events_json(&self) -> Value {
+        Value::Array(self.events.iter().map(event_json).collect())
+    }
+
+    // each cell the last move changed as [row, col, how it looks now], in
+    // board order; None if the move could have changed any of them
+    pub fn changes_json(&self) -> Option<Value> {
+        let change = |at: CellPosition| {
+            let cell = self
+                .board
+                .get(at)
+                .expect("A changed cell should be on the board");
+            Value::Array(vec![
+                Value::from(at.row_index),
+                Value::from(at.col_index),
+                Value::from(cell.to_string()),
+            ])
+        };
+        let cells = changed_cells(&self.events)?;
+        Some(Value::Array(cells.into_iter().map(change).collect()))
+    }
+}
+
+pub fn position_json(position: CellPosition) -> Value {
+    Value::object([
+        ("row", Value::from(position.row_index)),
+        ("col", Value::from(position.col_index)),
+    ])
+}
+
+pub fn change_name(change: FlagChange) -> &'static str {
+    match change {
+        FlagChange::FlagPlaced => "flag_placed",
+        FlagChange::FlagRemoved => "flag_removed",
+        FlagChange::QuestionPlaced => "question_placed",
+        FlagChange::QuestionRemoved => "question_removed",
+    }
+}
+
+fn event_json(event: &GameEvent) -> Value {
+    match event {
+        GameEvent::CellsOpened(cells) => Value::object([
+            ("type", Value::from("cells_opened")),
+            (
+                "cells",
+                Value::Array(cells.iter().copied().map(position_json).collect()),
+            ),
+        ]),
+        &GameEvent::FlagToggled(position, flagged) => Value::object([
+            ("type", Value::from("flag_toggled")),
+            ("cell", position_json(position)),
+            ("flagged", Value::from(flagged)),
+        ]),
+        &GameEvent::QuestionToggled(position, question) => Value::object([
+            ("type", Value::from("question_toggled")),
+            ("cell", position_json(position)),
+            ("question", Value::from(question)),
+        ]),
+        // an empty note once it has been taken off
+        &GameEvent::NoteChanged(position, note) => Value::object([
+            ("type", Value::from("note_changed")),
+            ("cell", position_json(position)),
+            (
+                "note",
+                Value::from(note.map(String::from).unwrap_or_default().as_str()),
+            ),
+        ]),
+        &GameEvent::Peeked(position, mine) => Value::object([
+            ("type", Value::from("peeked")),
+            ("cell", position_json(position)),
+            ("mine", Value::from(mine)),
+        ]),
+        &GameEvent::MineHit(position) => Value::object([
+            ("type", Value::from("mine_hit")),
+            ("cell", position_json(position)),
+        ]),
+        &GameEvent::MineDefused(position) => Value::object([
+            ("type", Value::from("mine_defused")),
+            ("cell", position_json(position)),
+        ]),
+        &GameEvent::ShieldEarned(shields) => Value::object([
+            ("type", Value::from("shield_earned")),
+            ("shields", Value::from(u64::from(shields))),
+        ]),
+        &GameEvent::MinesMoved(count) => Value::object([
+            ("type", Value::from("mines_moved")),
+            ("count", Value::from(count)),
+        ]),
+        GameEvent::GameWon => Value::object([("type", Value::from("game_won"))]),
+        &GameEvent::MineCountChanged(count) => Value::object([
+            ("type", Value::from("mine_count_changed")),
+            ("mines_left", Value::from(count as f64)),
+        ]),
+        GameEvent::Undone => Value::object([("type", Value::from("undone"))]),
+    }
+}
+
+// the cells the last move changed the look of, by the events it caused, in
+// board order; None if it could have changed any of them
+fn changed_cells(events: &[GameEvent]) -> Option<Vec<CellPosition>> {
+    let mut cells = vec![];
+    for event in events {
+        match event {
+            GameEvent::CellsOpened(opened) => cells.extend(opened.iter().copied()),
+            &GameEvent::FlagToggled(at, _)
+            | &GameEvent::QuestionToggled(at, _)
+            | &GameEvent::Peeked(at, _)
+            | &GameEvent::MineHit(at)
+            | &GameEvent::MineDefused(at) => cells.push(at),
+            GameEvent::MinesMoved(_) | GameEvent::Undone => return None,
+            // notes aren't drawn on the board's rows
+            GameEvent::NoteChanged(..)
+            | GameEvent::ShieldEarned(_)
+            | GameEvent::GameWon
+            | GameEvent::MineCountChanged(_) => (),
+        }
+    }
+    cells.sort_by_key(|at| (at.row_index, at.col_index));
+    cells.dedup();
+    Some(cells)
+}
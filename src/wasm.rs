@@ -0,0 +1,152 @@
+use crate::board::{Board, Mines};
+use crate::command;
+use crate::game::Game;
+use crate::json::{self, Value};
+use crate::timer::Clock;
+use crate::view::GameView;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// a beginner board
+const SIZE: usize = 9;
+const MINES: Mines = Mines::Count(10);
+
+// the time as the page last gave it, since std can't read a browser's clock
+#[derive(Default)]
+struct HostClock(Mutex<Duration>);
+
+impl Clock for HostClock {
+    fn now(&self) -> Duration {
+        self.0.lock().map(|now| *now).unwrap_or_default()
+    }
+}
+
+// a game for a browser frontend to drive, taking and giving JSON so that
+// nothing but strings and numbers cross over
+pub struct WasmGame {
+    game: Game,
+    clock: Arc<HostClock>,
+}
+
+pub fn new_game(seed: u64) -> WasmGame {
+    let clock = Arc::new(HostClock::default());
+    WasmGame {
+        game: Game::with_clock(Board::from_seed(seed, SIZE, SIZE, MINES), 1, clock.clone()),
+        clock,
+    }
+}
+
+fn error(message: impl ToString) -> String {
+    Value::object([("error", Value::from(message.to_string()))]).to_string()
+}
+
+impl WasmGame {
+    // `json_cmd` is an object with a `command`, as it would be typed at the
+    // terminal, and `now_ms`, the page's clock, which should only go forward;
+    // the answer is the game as the player sees it, or an `error`
+    pub fn apply(&mut self, json_cmd: &str) -> String {
+        let request = match json::parse(json_cmd) {
+            Ok(request) => request,
+            Err(e) => return error(e),
+        };
+        if let Some(now) = request.get("now_ms").and_then(Value::as_u64) {
+            if let Ok(mut clock) = self.clock.0.lock() {
+                *clock = Duration::from_millis(now);
+            }
+        }
+        let Some(line) = request.get("command").and_then(Value::as_str) else {
+            return error("expected a command");
+        };
+
+        let (height, width) = (self.game.board().height(), self.game.board().width());
+        let commands = match command::parse_line_within(line, height, width) {
+            Ok(commands) => commands,
+            Err(e) => {
+                let code = Value::from(e.code());
+                return Value::object([("error", Value::from(e.to_string())), ("code", code)])
+                    .to_string();
+            }
+        };
+        for command in commands {
+            if let Err(e) = self.game.apply(0, command) {
+                return error(e);
+            }
+        }
+        self.view()
+    }
+
+    pub fn view(&self) -> String {
+        let view = GameView::of(&self.game);
+        Value::object([
+            ("state", Value::from(view.state().name())),
+            ("mines_left", Value::from(view.counters().mines_left as f64)),
+            (
+                "elapsed_ms",
+                Value::from(self.game.elapsed().as_millis() as u64),
+            ),
+            ("board", view.board_json()),
+        ])
+        .to_string()
+    }
+}
+
+// what the module exports, without wasm-bindgen's glue: the page holds one
+// game, writes each command's UTF-8 into the buffer wasm_input hands it,
+// calls wasm_apply with its length, and reads that many bytes of answer
+// from wasm_output
+struct Session {
+    game: Option<WasmGame>,
+    input: Vec<u8>,
+    output: String,
+}
+
+static SESSION: Mutex<Session> = Mutex::new(Session {
+    game: None,
+    input: Vec::new(),
+    output: String::new(),
+});
+
+fn session() -> std::sync::MutexGuard<'static, Session> {
+    SESSION
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+// starts the page's game over, on a board from `seed`
+#[no_mangle]
+pub extern "C" fn wasm_new_game(seed: u64) {
+    session().game = Some(new_game(seed));
+}
+
+// room for a command of `len` bytes, which stays where it is until the
+// next call
+#[no_mangle]
+pub extern "C" fn wasm_input(len: usize) -> *mut u8 {
+    let mut session = session();
+    session.input = vec![0; len];
+    session.input.as_mut_ptr()
+}
+
+// the length of the answer to the command in the first `len` bytes of the
+// input
+#[no_mangle]
+pub extern "C" fn wasm_apply(len: usize) -> usize {
+    let mut session = session();
+    let Session {
+        game,
+        input,
+        output,
+    } = &mut *session;
+    let command = String::from_utf8_lossy(&input[..len.min(input.len())]);
+    *output = match game {
+        Some(game) => game.apply(&command),
+        None => error("no game has been started"),
+    };
+    output.len()
+}
+
+// the last answer, until the next command
+#[no_mangle]
+pub extern "C" fn wasm_output() -> *const u8 {
+    session().output.as_ptr()
+}
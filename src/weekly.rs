@@ -0,0 +1,133 @@
+// the weekly challenge: one board for everyone in the same ISO week, played
+// by a twist on the rules that is picked from a fixed table by the week's
+// number, so that the twist comes round again every few weeks
+use crate::board::{MineDistribution, Mines, Symmetry};
+#[cfg(feature = "clock")]
+use crate::game::Game;
+use crate::game::WinCondition;
+use crate::i18n::Msg;
+use crate::rules::{Ruleset, RulesetBuilder};
+
+// a twist and the rules it is played by
+#[derive(Debug, Clone, Copy)]
+pub struct Modifier {
+    // as kept in the history
+    pub name: &'static str,
+    pub description: Msg,
+    rules: fn() -> RulesetBuilder,
+}
+
+impl Modifier {
+    pub fn rules(&self) -> Ruleset {
+        (self.rules)()
+            .build()
+            .expect("A weekly modifier's rules should be consistent")
+    }
+}
+
+// week 1 is the first, and the table starts again after its last
+pub const MODIFIERS: [Modifier; 8] = [
+    Modifier {
+        name: "lives",
+        description: Msg::WeeklyLives,
+        rules: || {
+            Ruleset::builder()
+                .size(16, 16)
+                .mines(Mines::Density(99.0 / 480.0))
+                .lives(2)
+        },
+    },
+    Modifier {
+        name: "rocks",
+        description: Msg::WeeklyRocks,
+        rules: || {
+            Ruleset::builder()
+                .size(12, 12)
+                .mines(Mines::Count(22))
+                .rocks(12)
+        },
+    },
+    Modifier {
+        name: "mirror",
+        description: Msg::WeeklyMirror,
+        rules: || {
+            Ruleset::builder()
+                .size(12, 12)
+                .mines(Mines::Count(24))
+                .symmetry(Symmetry::Horizontal)
+        },
+    },
+    Modifier {
+        name: "clusters",
+        description: Msg::WeeklyClusters,
+        rules: || {
+            Ruleset::builder()
+                .size(14, 14)
+                .mines(Mines::Count(36))
+                .distribution(MineDistribution::Clustered)
+        },
+    },
+    Modifier {
+        name: "moving",
+        description: Msg::WeeklyMoving,
+        rules: || {
+            Ruleset::builder()
+                .size(10, 10)
+                .mines(Mines::Count(15))
+                .moving_mines(10, 0.2)
+        },
+    },
+    Modifier {
+        name: "flags",
+        description: Msg::WeeklyFlags,
+        rules: || {
+            Ruleset::builder()
+                .size(12, 12)
+                .mines(Mines::Count(25))
+                .win(WinCondition::FlagAll)
+        },
+    },
+    Modifier {
+        name: "sparse",
+        description: Msg::WeeklySparse,
+        rules: || {
+            Ruleset::builder()
+                .size(12, 12)
+                .mines(Mines::Count(28))
+                .distribution(MineDistribution::Sparse)
+        },
+    },
+    Modifier {
+        name: "peeks",
+        description: Msg::WeeklyPeeks,
+        rules: || {
+            Ruleset::builder()
+                .size(16, 16)
+                .mines(Mines::Count(50))
+                .peeks(3)
+                .shields(true)
+        },
+    },
+];
+
+// the twist for ISO week `week`, from 1 to 53
+pub fn modifier(week: u32) -> &'static Modifier {
+    &MODIFIERS[week.saturating_sub(1) as usize % MODIFIERS.len()]
+}
+
+// as the week is written in the game's code and the history, "2026-W07"
+pub fn tag(year: i64, week: u32) -> String {
+    format!("{year:04}-W{week:02}")
+}
+
+// the board is laid out from this, so that it is the same for everyone
+// that week and different from every other week's
+pub fn seed(year: i64, week: u32) -> u64 {
+    year as u64 * 100 + u64::from(week)
+}
+
+// the week's game, played by its twist's rules
+#[cfg(feature = "clock")]
+pub fn new_game(year: i64, week: u32) -> Game {
+    Game::from_ruleset(&modifier(week).rules(), seed(year, week))
+}
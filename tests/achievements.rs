@@ -0,0 +1,43 @@
+// achievements through the binary: announced the first time they are
+// earned, kept in the stats file, and never announced again
+mod common;
+
+use common::Home;
+
+// with no mines, the first clear wins
+const ARGS: [&str; 7] = ["--width", "2", "--height", "2", "--mines", "0", "--plain"];
+
+fn announced(stdout: &str) -> Vec<&str> {
+    stdout
+        .lines()
+        .filter(|line| line.starts_with("Achievement earned: "))
+        .collect()
+}
+
+#[test]
+fn achievements_are_announced_once_and_kept() {
+    let home = Home::new("achievements_once");
+    // won twice in one session
+    let first = home.run(&ARGS, "c 0 0\ny\nc 0 0\n");
+    assert_eq!(
+        announced(&first.stdout),
+        [
+            "Achievement earned: First win (Win a game)",
+            "Achievement earned: No flags needed (Win without placing a single flag)",
+            "Achievement earned: Against the clock (Win in under a minute)",
+        ]
+    );
+    assert_eq!(first.stdout.matches("Game won").count(), 2);
+    assert!(home
+        .read("stats.txt")
+        .is_some_and(|stats| stats.contains("achievements first_win no_flags under_a_minute")));
+
+    // and in the next session, won again
+    let second = home.run(&ARGS, "c 0 0\n");
+    assert!(second.stdout.contains("Game won"));
+    assert_eq!(announced(&second.stdout), [] as [&str; 0]);
+
+    let listed = home.run(&["achievements"], "");
+    assert!(listed.stdout.contains("[x] First win"));
+    assert!(listed.stdout.contains("[ ] Minefield"));
+}
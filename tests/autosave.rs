@@ -0,0 +1,72 @@
+// a game cut off by the end of its input is left in the autosave, and the
+// next start offers it back as it was
+mod common;
+
+use common::Home;
+
+const ARGS: [&str; 6] = [
+    "--seed",
+    "42",
+    "--preset",
+    "beginner",
+    "--plain",
+    "--no-color",
+];
+
+// the status line and the rows after it, for each time the board is drawn
+fn boards(stdout: &str) -> Vec<String> {
+    let mut boards = vec![];
+    let mut lines = stdout.lines();
+    while let Some(line) = lines.next() {
+        if line.starts_with("Mines: ") {
+            let rows = lines.by_ref().take_while(|row| *row != "------");
+            boards.push(
+                std::iter::once(line)
+                    .chain(rows)
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            );
+        }
+    }
+    boards
+}
+
+#[test]
+fn an_interrupted_game_resumes_as_it_was_left() {
+    let home = Home::new("autosave_resume");
+    let first = home.run(&ARGS, "c 2 7\nf 0 0\n");
+    assert_eq!(first.code, Some(130));
+    let saved = home
+        .read("autosave.txt")
+        .expect("The game should be autosaved");
+    let left = boards(&first.stdout)
+        .pop()
+        .expect("The board should be drawn");
+
+    let resumed = home.run(&["--plain", "--no-color"], "y\n");
+    assert!(resumed
+        .stdout
+        .starts_with("Found an interrupted single game (#42, 2 moves made)"));
+    assert_eq!(boards(&resumed.stdout).first(), Some(&left));
+    assert!(left.starts_with("Mines: 9  Flags: 1  Time: 00:00  Moves: 2"));
+    // stopped again, it is still there to pick up
+    assert_eq!(home.read("autosave.txt"), Some(saved));
+
+    // and played on, it is the same game: (1,1) is still a mine
+    let lost = home.run(&["--plain", "--no-color"], "y\nc 1 1\n");
+    assert_eq!(lost.code, Some(1), "{}", lost.stdout);
+    assert_eq!(home.read("autosave.txt"), None);
+}
+
+#[test]
+fn a_declined_game_is_discarded() {
+    let home = Home::new("autosave_declined");
+    home.run(&ARGS, "c 2 7\n");
+    assert!(home.read("autosave.txt").is_some());
+
+    // turned down, a new game is started in its place, and nothing is offered after
+    let declined = home.run(&ARGS, "n\n");
+    let first = boards(&declined.stdout).remove(0);
+    assert!(first.contains("Moves: 0"), "{first}");
+    assert_eq!(home.read("autosave.txt"), None);
+}
@@ -0,0 +1,70 @@
+// the board report's numbers on layouts small enough to count by hand
+mod common;
+
+use common::board;
+use minesweeper::analysis::{render_board_info, BoardInfo};
+use minesweeper::board::Mines;
+
+fn info(layout: &str) -> BoardInfo {
+    BoardInfo::of(&board(layout))
+}
+
+#[test]
+fn a_board_without_mines_is_one_opening() {
+    assert_eq!(
+        info("...\n...\n..."),
+        BoardInfo {
+            height: 3,
+            width: 3,
+            mines: 0,
+            zeros: 9,
+            openings: 1,
+            three_bv: 1,
+        }
+    );
+    assert_eq!(info("...\n...\n...").density(), 0.0);
+}
+
+#[test]
+fn zeros_that_only_share_a_border_are_separate_openings() {
+    // (0,2) and (2,0) are the zeros, and both border (1,1), which takes
+    // no click of its own
+    let info = info("*..\n...\n..*");
+    assert_eq!((info.height, info.width, info.mines), (3, 3, 2));
+    assert_eq!((info.zeros, info.openings), (2, 2));
+    assert_eq!(info.three_bv, 2);
+}
+
+#[test]
+fn a_board_without_zeros_takes_a_click_for_every_safe_cell() {
+    let info = info("*.*\n.*.\n*.*");
+    assert_eq!((info.mines, info.zeros, info.openings), (5, 0, 0));
+    assert_eq!(info.three_bv, 4);
+    assert!((info.density() - 5.0 / 9.0).abs() < 1e-9);
+}
+
+#[test]
+fn numbers_no_opening_borders_are_a_click_each() {
+    // (0,2) is the only zero, and its opening reaches the row below it but
+    // none of the corners or the bottom row
+    let info = info(".....\n*...*\n.*...");
+    assert_eq!(info.mines, 3);
+    assert_eq!((info.zeros, info.openings), (1, 1));
+    // the opening, then (0,0), (0,4), (2,0), (2,2), (2,3) and (2,4)
+    assert_eq!(info.three_bv, 7);
+}
+
+#[test]
+fn the_report_lists_every_metric() {
+    let info = info("*..\n...\n..*");
+    let report = render_board_info(&info, Some(Mines::Count(3)));
+    assert_eq!(
+        report,
+        "Board: 3x3\nMines: 2 (22.2% of the cells; 3 were asked for)\n\
+         Zeros: 2, in 2 openings\n3BV: 2"
+    );
+    let report = render_board_info(&info, Some(Mines::Density(0.1666)));
+    assert!(report.contains("(22.2% of the cells; 16.7% was asked for)"));
+    let report = render_board_info(&info, None);
+    assert!(report.contains("(22.2% of the cells)\n"));
+}
@@ -0,0 +1,195 @@
+// random boards and random moves, checking after every move that the board
+// is still consistent with itself, that nothing was opened twice, and that a
+// previewed clear does just what clearing does; each
+// case is generated from its own seed, which a failure names, so that it
+// can be played again on its own
+use minesweeper::board::{Board, CellPosition, CellState, Layout, Mines};
+use minesweeper::command::Command;
+use minesweeper::game::{Game, GameEvent, GameState};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const CASES: u64 = 300;
+const MOVES: usize = 60;
+
+fn random_board(rng: &mut StdRng) -> Board {
+    let height = rng.gen_range(1..=16);
+    let width = rng.gen_range(1..=16);
+    let cells = height * width;
+
+    match rng.gen_range(0..3) {
+        // at least one cell is left safe, as a configured board always has
+        0 => Board::from_seed(
+            rng.gen(),
+            height,
+            width,
+            Mines::Count(rng.gen_range(0..cells)),
+        ),
+        1 => {
+            let mut layout = Layout::new(Mines::Density(rng.gen_range(0.0..0.5)));
+            layout.rocks = rng.gen_range(0..=cells / 4);
+            Board::generate(rng.gen(), height, width, &layout)
+        }
+        _ => {
+            let density = rng.gen_range(0.0..0.9);
+            Board::from_bombs(
+                (0..height)
+                    .map(|_| (0..width).map(|_| rng.gen_bool(density)).collect())
+                    .collect(),
+            )
+        }
+    }
+}
+
+// now and then off the board, so that the errors get exercised too
+fn random_position(rng: &mut StdRng, board: &Board) -> CellPosition {
+    CellPosition {
+        row_index: rng.gen_range(0..board.height() + 2),
+        col_index: rng.gen_range(0..board.width() + 2),
+    }
+}
+
+fn random_command(rng: &mut StdRng, board: &Board) -> Command {
+    let position = random_position(rng, board);
+    match rng.gen_range(0..10) {
+        0..=4 => Command::Clear(position),
+        5 | 6 => Command::Flag(position),
+        7 => Command::Chord(position),
+        8 => Command::Question(position),
+        _ => Command::Undo,
+    }
+}
+
+fn is_open(board: &Board, position: CellPosition) -> bool {
+    board
+        .get_cell(position)
+        .is_some_and(|cell| matches!(cell.state, CellState::Safe { open: true, .. }))
+}
+
+fn play_case(seed: u64) {
+    let rng = &mut StdRng::seed_from_u64(seed);
+    let board = random_board(rng);
+    if let Err(e) = board.check_invariants() {
+        panic!("case {seed}: a new board should be consistent: {e}");
+    }
+    let mut game = Game::new(board, rng.gen_range(1..=3)).shielded(rng.gen_bool(0.3));
+
+    for _ in 0..MOVES {
+        let before = game.board().clone();
+        let command = random_command(rng, &before);
+        let Ok(state) = game.apply(0, command.clone()) else {
+            continue;
+        };
+
+        if let Err(e) = game.board().check_invariants() {
+            panic!("case {seed}: after {command}: {e}");
+        }
+        for event in game.events() {
+            let GameEvent::CellsOpened(cells) = event else {
+                continue;
+            };
+            for (index, &cell) in cells.iter().enumerate() {
+                assert!(
+                    !is_open(&before, cell),
+                    "case {seed}: {command} opened {cell}, which was already open"
+                );
+                assert!(
+                    !cells[..index].contains(&cell),
+                    "case {seed}: {command} opened {cell} twice"
+                );
+                assert!(
+                    is_open(game.board(), cell),
+                    "case {seed}: {command} said it opened {cell}, which is still closed"
+                );
+            }
+        }
+        if state != GameState::Playing {
+            break;
+        }
+    }
+}
+
+#[test]
+fn moves_keep_the_board_consistent() {
+    for seed in 0..CASES {
+        play_case(seed);
+    }
+}
+
+#[test]
+fn generated_boards_have_the_mines_asked_for() {
+    for seed in 0..CASES {
+        let rng = &mut StdRng::seed_from_u64(seed);
+        let (height, width) = (rng.gen_range(1..=30), rng.gen_range(1..=30));
+        let mines = rng.gen_range(0..height * width);
+        let board = Board::from_seed(seed, height, width, Mines::Count(mines));
+
+        assert_eq!(
+            (board.height(), board.width()),
+            (height, width),
+            "case {seed}"
+        );
+        assert_eq!(board.count_bombs(), mines, "case {seed}");
+        assert_eq!(board.check_invariants(), Ok(()), "case {seed}");
+    }
+}
+
+// everything about every cell, to compare two boards by
+fn cells(board: &Board) -> String {
+    format!("{:?}", board.rows().collect::<Vec<_>>())
+}
+
+#[test]
+fn a_previewed_clear_applies_as_a_clear() {
+    for seed in 0..CASES {
+        let rng = &mut StdRng::seed_from_u64(seed);
+        let mut game = Game::new(random_board(rng), 3);
+
+        for _ in 0..MOVES {
+            let board = game.board().clone();
+            let position = random_position(rng, &board);
+            let preview = board.clear_preview(position);
+            assert_eq!(
+                cells(&board),
+                cells(game.board()),
+                "case {seed}: preview changed it"
+            );
+
+            let mut cleared = board.clone();
+            let result = cleared.clear(position);
+            match preview {
+                Ok(outcome) if outcome.mine => {
+                    assert!(result.is_err(), "case {seed}: {position} is a mine");
+                    assert!(
+                        outcome.opened.is_empty(),
+                        "case {seed}: a mine opens nothing"
+                    );
+                }
+                Ok(outcome) => {
+                    assert!(result.is_ok(), "case {seed}: {position} should clear");
+                    let mut applied = board.clone();
+                    applied.apply_clear(&outcome);
+                    assert_eq!(cells(&applied), cells(&cleared), "case {seed}: {position}");
+                    assert_eq!(outcome.open_count, cleared.count_open(), "case {seed}");
+                    assert!(
+                        outcome.opened.iter().all(|&cell| !is_open(&board, cell)),
+                        "case {seed}: {position} would open a cell already open"
+                    );
+                }
+                Err(e) => assert_eq!(
+                    result.map_err(|e| e.to_string()),
+                    Err(e.to_string()),
+                    "case {seed}: {position}"
+                ),
+            }
+
+            let command = random_command(rng, &board);
+            if game
+                .apply(0, command)
+                .is_ok_and(|state| state != GameState::Playing)
+            {
+                break;
+            }
+        }
+    }
+}
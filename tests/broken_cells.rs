@@ -0,0 +1,82 @@
+// boards no game could leave, set by hand: every way of writing one out
+// still works, with the cells that can't be marked, and the invariant
+// checker names what is wrong with them
+mod common;
+
+use common::at;
+use minesweeper::board::{Board, Cell, CellPosition, CellState, InvariantViolation};
+use minesweeper::view::{BoardView, CellView};
+
+const LAYOUT: &str = "*..\n...\n..*";
+
+// the board with one cell set by hand past what the game allows
+fn broken(position: CellPosition, set: impl FnOnce(&mut Cell)) -> Board {
+    let mut board: Board = LAYOUT.parse().expect("The layout should parse");
+    set(board
+        .get_cell_mut(position)
+        .expect("The cell should be on the board"));
+    board
+}
+
+// each row written as wide as the others, with the mark where the cell is
+fn assert_marked(board: &Board, position: CellPosition) {
+    for lines in [
+        board.to_string().lines().map(String::from).collect(),
+        BoardView::of(board).lines(),
+    ] {
+        assert!(
+            lines.iter().all(|line| line.len() == lines[0].len()),
+            "{lines:?}"
+        );
+        let line = &lines[position.row_index];
+        assert_eq!(line.split(' ').nth(position.col_index), Some("E"), "{line}");
+    }
+    assert_eq!(BoardView::of(board).get(position), Some(CellView::Broken));
+}
+
+#[test]
+fn a_flagged_open_cell_is_written_as_a_mark() {
+    let board = broken(at(1, 1), |cell| {
+        cell.state = CellState::Safe {
+            flagged: true,
+            open: true,
+        }
+    });
+
+    assert_marked(&board, at(1, 1));
+    let violation = InvariantViolation::FlaggedAndOpen { at: at(1, 1) };
+    assert_eq!(board.check_invariants(), Err(violation));
+}
+
+#[test]
+fn a_count_no_cell_can_have_is_written_as_a_mark() {
+    let board = broken(at(0, 2), |cell| {
+        cell.bombs_around = 200;
+        cell.state = CellState::Safe {
+            flagged: false,
+            open: true,
+        }
+    });
+
+    assert_marked(&board, at(0, 2));
+    let violation = InvariantViolation::TooManyAround {
+        at: at(0, 2),
+        shown: 200,
+    };
+    assert_eq!(
+        board.get_cell(at(0, 2)).map(Cell::check),
+        Some(Err(violation))
+    );
+}
+
+// not a cell the checker minds on its own, but the board does
+#[test]
+fn a_cell_that_thinks_it_is_elsewhere_is_still_written() {
+    let board = broken(at(2, 0), |cell| cell.position = at(7, 7));
+
+    assert_eq!(board.to_string().lines().nth(2), Some("# # #"));
+    assert_eq!(
+        board.check_invariants(),
+        Err(InvariantViolation::WrongPosition { at: at(2, 0) })
+    );
+}
@@ -0,0 +1,61 @@
+// the campaign through the binary: a level won is kept in the stats file,
+// and the next time the level after it is the one played
+mod common;
+
+use common::Home;
+use std::fs;
+use std::path::PathBuf;
+
+// a clear on every safe cell of the first level, which wins it whatever
+// order its cascades open them in
+fn first_level_cleared() -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets/campaign/01.txt");
+    let level = fs::read_to_string(path).expect("The level should be read");
+    let mut script = String::new();
+    for (row, line) in level.lines().skip(2).enumerate() {
+        for (col, symbol) in line.chars().enumerate() {
+            if symbol == '.' {
+                script += &format!("c {row} {col}\n");
+            }
+        }
+    }
+    script
+}
+
+fn progress(stdout: &str) -> Vec<&str> {
+    stdout
+        .lines()
+        .take_while(|line| !line.starts_with("Level "))
+        .collect()
+}
+
+#[test]
+fn a_won_level_is_kept_and_unlocks_the_next() {
+    let home = Home::new("campaign_unlock");
+    let first = home.run(&["campaign", "--plain"], &first_level_cleared());
+    let listed = progress(&first.stdout);
+    assert_eq!(listed.len(), 12);
+    assert!(listed[0].starts_with(" 1. First steps") && listed[0].ends_with("next"));
+    assert!(listed[1].ends_with("locked"));
+    assert!(first.stdout.contains("Level 1: First steps\n"));
+    assert!(
+        first.stdout.contains("Level 1 complete! Level 2 ("),
+        "{}",
+        first.stdout
+    );
+    let stats = home.read("stats.txt").expect("The stats should be saved");
+    assert!(
+        stats.lines().any(|line| line.starts_with("campaign 1 ")),
+        "{stats}"
+    );
+
+    // the next time, the first is done and the second is played
+    let second = home.run(&["campaign", "--plain"], "");
+    let listed = progress(&second.stdout);
+    assert!(listed[0].contains("won, best 00:"), "{}", listed[0]);
+    assert!(listed[1].ends_with("next"));
+    assert!(listed[2].ends_with("locked"));
+    assert!(second.stdout.contains("Level 2: "));
+    // and stopping partway through keeps nothing more
+    assert_eq!(home.read("stats.txt"), Some(stats));
+}
@@ -0,0 +1,74 @@
+// the summary of what a cascade opened, on boards made by hand
+mod common;
+
+use common::{at, game_on};
+use minesweeper::board::{Board, CellPosition};
+use minesweeper::command::Command;
+use minesweeper::game::{CascadeSummary, Game, GameEvent};
+
+// mines across the middle both ways, which split it into four corners
+const WALLS: &str = "..*..\n..*..\n*****\n..*..\n..*..";
+
+fn opened(game: &Game) -> Vec<CellPosition> {
+    game.events()
+        .iter()
+        .find_map(|event| match event {
+            GameEvent::CellsOpened(cells) => Some(cells.clone()),
+            _ => None,
+        })
+        .expect("The move should have opened cells")
+}
+
+// the zeros in the left column join up, the numbers beside them don't
+#[test]
+fn a_cascade_is_one_region_and_its_numbers() {
+    let mut game = game_on("..*..\n..*..\n..*..");
+    game.apply(0, Command::Clear(at(0, 0)))
+        .expect("(0,0) is safe");
+
+    assert_eq!(
+        CascadeSummary::of(&opened(&game), game.board()),
+        CascadeSummary {
+            opened: 6,
+            regions: 1,
+            numbered: 3,
+        }
+    );
+}
+
+#[test]
+fn zeros_that_dont_touch_are_separate_regions() {
+    let board: Board = WALLS.parse().expect("The layout should parse");
+    let cells: Vec<CellPosition> = [at(0, 0), at(0, 4), at(4, 0)]
+        .into_iter()
+        .flat_map(|corner| {
+            board
+                .clear_preview(corner)
+                .expect("The corner is safe")
+                .opened
+        })
+        .collect();
+
+    assert_eq!(
+        CascadeSummary::of(&cells, &board),
+        CascadeSummary {
+            opened: 12,
+            regions: 3,
+            numbered: 9,
+        }
+    );
+}
+
+#[test]
+fn a_single_number_is_no_region() {
+    let board: Board = WALLS.parse().expect("The layout should parse");
+
+    assert_eq!(
+        CascadeSummary::of(&[at(1, 1)], &board),
+        CascadeSummary {
+            opened: 1,
+            regions: 0,
+            numbered: 1,
+        }
+    );
+}
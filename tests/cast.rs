@@ -0,0 +1,62 @@
+// a cast exported through the binary from a saved game: a header for the
+// terminal it was drawn on, then a frame for the board and one per move
+mod common;
+
+use common::Home;
+use minesweeper::json::{self, Value};
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn a_saved_game_plays_back_a_frame_a_move() {
+    // the beginner's game #42, two moves in, as a release saved it
+    let save = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/compatibility/save_v2.txt");
+    let home = Home::new("cast_export");
+    let cast = home.path.join("game.cast");
+    let run = home.run(
+        &[
+            "--load",
+            save.to_str().expect("The path should be UTF-8"),
+            "--plain",
+        ],
+        &format!("export cast {}\n", cast.display()),
+    );
+    assert!(
+        run.stdout
+            .contains(&format!("Recording written to {}", cast.display())),
+        "{}",
+        run.stdout
+    );
+
+    let text = fs::read_to_string(&cast).expect("The cast should be written");
+    let lines: Vec<Value> = text
+        .lines()
+        .map(|line| json::parse(line).expect("Every line should be JSON"))
+        .collect();
+    let header = &lines[0];
+    let field = |key| header.get(key).and_then(Value::as_u64);
+    assert_eq!(field("version"), Some(2));
+    // the status line's width at least, and the board's rows with the
+    // status and the prompt around them
+    assert_eq!((field("width"), field("height")), (Some(80), Some(14)));
+    assert_eq!(
+        header.get("title").and_then(Value::as_str),
+        Some("Minesweeper #42")
+    );
+
+    // a save keeps no timings, so the frames are a second apart
+    let times: Vec<f64> = lines[1..]
+        .iter()
+        .map(|line| match line {
+            Value::Array(fields) => {
+                assert_eq!(fields[1].as_str(), Some("o"));
+                assert!(fields[2].as_str().is_some());
+                fields[0]
+                    .as_f64()
+                    .expect("An event should start with its time")
+            }
+            _ => panic!("An event should be an array: {line}"),
+        })
+        .collect();
+    assert_eq!(times, [0.0, 1.0, 2.0]);
+}
@@ -0,0 +1,84 @@
+// which cells are within a distance of another, at the middle, edges and
+// corners of a small board
+mod common;
+
+use common::at;
+use minesweeper::board::{cells_within, CellPosition};
+
+const HEIGHT: usize = 4;
+const WIDTH: usize = 5;
+
+// each cell holds its own position, to check that the two agree
+fn board() -> Vec<Vec<CellPosition>> {
+    (0..HEIGHT)
+        .map(|row| (0..WIDTH).map(|col| at(row, col)).collect())
+        .collect()
+}
+
+fn within(position: CellPosition, radius: usize) -> Vec<CellPosition> {
+    let board = board();
+    cells_within(&board, position, radius)
+        .map(|(at, &cell)| {
+            assert_eq!(at, cell, "A cell should come with its own position");
+            at
+        })
+        .collect()
+}
+
+#[test]
+fn radius_zero_is_the_cell_alone() {
+    assert_eq!(within(at(2, 3), 0), [at(2, 3)]);
+    assert_eq!(within(at(0, 0), 0), [at(0, 0)]);
+}
+
+#[test]
+fn radius_one_is_the_cell_and_its_neighbours() {
+    assert_eq!(
+        within(at(1, 1), 1),
+        [
+            at(0, 0),
+            at(0, 1),
+            at(0, 2),
+            at(1, 0),
+            at(1, 1),
+            at(1, 2),
+            at(2, 0),
+            at(2, 1),
+            at(2, 2)
+        ]
+    );
+    assert_eq!(
+        within(at(0, 0), 1),
+        [at(0, 0), at(0, 1), at(1, 0), at(1, 1)]
+    );
+    assert_eq!(
+        within(at(3, 4), 1),
+        [at(2, 3), at(2, 4), at(3, 3), at(3, 4)]
+    );
+}
+
+#[test]
+fn radius_two_is_cut_off_at_the_edges() {
+    let cells = within(at(0, 2), 2);
+
+    assert_eq!(cells.len(), 3 * 5);
+    assert_eq!(cells.first(), Some(&at(0, 0)));
+    assert_eq!(cells.last(), Some(&at(2, 4)));
+}
+
+#[test]
+fn a_radius_bigger_than_the_board_is_the_whole_board() {
+    let everything: Vec<CellPosition> = board().into_iter().flatten().collect();
+
+    assert_eq!(within(at(0, 0), 100), everything);
+    assert_eq!(within(at(3, 4), usize::MAX), everything);
+}
+
+#[test]
+fn nothing_is_within_reach_of_a_cell_off_the_board() {
+    assert_eq!(within(at(HEIGHT + 5, 0), 1), []);
+    assert_eq!(within(at(usize::MAX, usize::MAX), 3), []);
+
+    let empty: Vec<Vec<u8>> = vec![];
+    assert_eq!(cells_within(&empty, at(0, 0), 1).count(), 0);
+}
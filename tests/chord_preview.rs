@@ -0,0 +1,122 @@
+// what a chord would do, asked before it is made: worked out from what the
+// player can see, and leaving the game as it was
+mod common;
+
+use common::{at, game_on};
+use minesweeper::board::{CellPosition, ChordError, ChordPreview};
+use minesweeper::command::Command;
+use minesweeper::dump::debug_dump;
+use minesweeper::game::{Game, GameError, GameState};
+
+// the corner opened on a 1, with its three neighbours still closed and
+// `flags` flagged
+fn game(layout: &str, flags: &[CellPosition]) -> Game {
+    let mut game = game_on(layout);
+    game.apply(0, Command::Clear(at(1, 1)))
+        .expect("The corner should be safe");
+    for &flag in flags {
+        game.apply(0, Command::Flag(flag))
+            .expect("The cell should take a flag");
+    }
+    game
+}
+
+// the preview, checking that asking for it changed nothing
+fn checked(game: &mut Game, position: CellPosition) -> Result<ChordPreview, ChordError> {
+    let before = debug_dump(game, "test");
+    let moves = game.moves().len();
+    assert_eq!(
+        game.apply(0, Command::ChordCheck(position)).ok(),
+        Some(GameState::Playing)
+    );
+    assert_eq!(debug_dump(game, "test"), before);
+    assert_eq!(game.moves().len(), moves);
+    game.board().chord_preview(position)
+}
+
+#[test]
+fn a_satisfied_chord_lists_the_cells_it_would_clear() {
+    let mut game = game("*.\n..", &[at(0, 0)]);
+    let preview = checked(&mut game, at(1, 1)).expect("An open cell can be previewed");
+
+    assert!(preview.satisfied());
+    assert_eq!(preview.targets, [at(0, 1), at(1, 0)]);
+    assert_eq!((preview.flags, preview.bombs_around), (1, 1));
+    assert_eq!(
+        game.board().chord_targets(at(1, 1)).ok(),
+        Some(preview.targets)
+    );
+}
+
+#[test]
+fn an_unsatisfied_chord_would_do_nothing() {
+    let mut game = game("*.\n..", &[]);
+    let preview = checked(&mut game, at(1, 1)).expect("An open cell can be previewed");
+
+    assert!(!preview.satisfied());
+    assert_eq!((preview.flags, preview.bombs_around), (0, 1));
+    assert_eq!(
+        game.board().chord_targets(at(1, 1)),
+        Err(ChordError::FlagsDontMatch {
+            flags: 0,
+            bombs_around: 1
+        })
+    );
+    assert_eq!(checked(&mut game, at(0, 1)), Err(ChordError::NotOpen));
+    assert_eq!(checked(&mut game, at(5, 5)), Err(ChordError::CellNotFound));
+}
+
+// with every cell around it open, a chord isn't a move at all
+#[test]
+fn a_chord_with_nothing_to_open_is_turned_down() {
+    let mut game = game_on("*.*\n...\n...");
+    game.apply(0, Command::Clear(at(2, 0)))
+        .expect("The bottom row should open");
+    let before = debug_dump(&game, "test");
+
+    assert!(matches!(
+        game.apply(0, Command::Chord(at(2, 1))),
+        Err(GameError::Chord(ChordError::NothingToOpen))
+    ));
+    assert_eq!(game.moves().len(), 1);
+    assert_eq!(debug_dump(&game, "test"), before);
+    assert!(checked(&mut game, at(2, 1)).is_ok_and(|preview| preview.targets.is_empty()));
+}
+
+// the same flag on the mine and beside it looks the same, so the preview
+// is the same, though one chord would win and the other set off the mine
+#[test]
+fn a_wrong_flag_looks_like_a_right_one() {
+    let mut wrong = game("*.\n..", &[at(0, 1)]);
+    let mut right = game(".*\n..", &[at(0, 1)]);
+
+    let previewed = checked(&mut wrong, at(1, 1));
+    assert_eq!(previewed, checked(&mut right, at(1, 1)));
+    assert!(previewed.is_ok_and(|preview| preview.satisfied()));
+
+    assert_eq!(
+        wrong.apply(0, Command::Chord(at(1, 1))).ok(),
+        Some(GameState::Lost)
+    );
+    assert_eq!(
+        right.apply(0, Command::Chord(at(1, 1))).ok(),
+        Some(GameState::Won)
+    );
+}
+
+#[test]
+fn a_chord_check_is_written_as_it_is_typed() {
+    for line in [
+        "ch? 1 1",
+        "chord? 1 1",
+        "ch --check 1 1",
+        "chord --check 1 1",
+    ] {
+        assert_eq!(
+            line.parse::<Command>().ok(),
+            Some(Command::ChordCheck(at(1, 1))),
+            "{line}"
+        );
+    }
+    assert_eq!(Command::ChordCheck(at(1, 1)).to_string(), "ch? 1 1");
+}
@@ -0,0 +1,123 @@
+// `clear row` and `clear rect`: every closed, unflagged cell in the area is
+// cleared in turn, up to the first mine
+mod common;
+
+use common::{at, game_on};
+use minesweeper::board::{CellState, ClearError};
+use minesweeper::command::{Area, Command, CommandError};
+use minesweeper::game::{Game, GameError, GameState, MoveOutcome};
+
+// a mine at (2,2), well away from the top-left corner
+const LAYOUT: &str = "....\n....\n..*.\n....";
+
+fn area(game: &mut Game, command: &str) -> Result<GameState, GameError> {
+    let command: Command = command.parse().expect("The command should parse");
+    assert!(matches!(command, Command::ClearArea(_)), "{command}");
+    game.apply(0, command)
+}
+
+fn play(game: &mut Game, command: &str) -> GameState {
+    area(game, command).expect("The area should be cleared")
+}
+
+fn last_outcome(game: &Game) -> MoveOutcome {
+    game.moves().last().expect("There should be a move").outcome
+}
+
+fn open(game: &Game, row: usize, col: usize) -> bool {
+    game.board()
+        .get_cell(at(row, col))
+        .is_some_and(|cell| matches!(cell.state, CellState::Safe { open: true, .. }))
+}
+
+#[test]
+fn a_safe_rectangle_is_opened_and_counted() {
+    let mut game = game_on(LAYOUT);
+
+    // the three 1s above the mine
+    assert_eq!(play(&mut game, "clear rect 1 1 1 3"), GameState::Playing);
+    assert!(matches!(
+        last_outcome(&game),
+        MoveOutcome::AreaOpened {
+            opened: 3,
+            skipped: 0
+        }
+    ));
+    assert_eq!(game.board().count_open(), 3);
+}
+
+#[test]
+fn cascades_open_more_than_the_area_without_counting_as_skipped() {
+    let mut game = game_on(LAYOUT);
+
+    // (0,0) is a 0, and its cascade opens the rest of the row and beyond
+    play(&mut game, "c row 0");
+    let MoveOutcome::AreaOpened { opened, skipped } = last_outcome(&game) else {
+        panic!("The row should have opened");
+    };
+    assert_eq!(skipped, 0);
+    assert_eq!(opened, game.board().count_open());
+    assert!(opened > 4);
+}
+
+#[test]
+fn a_rectangle_with_a_mine_stops_on_it() {
+    let mut game = game_on(LAYOUT);
+
+    assert_eq!(play(&mut game, "c rect 2 1 3 3"), GameState::Lost);
+    assert!(matches!(last_outcome(&game), MoveOutcome::HitBomb(hit) if hit == at(2, 2)));
+    // (2,1) was cleared before the mine, and nothing after it
+    assert!(open(&game, 2, 1));
+    assert!(!open(&game, 2, 3));
+    assert!(!open(&game, 3, 3));
+}
+
+#[test]
+fn flagged_and_open_cells_are_skipped() {
+    let mut game = game_on(LAYOUT);
+    game.apply(0, Command::Flag(at(2, 2)))
+        .expect("The flag should go on");
+    game.apply(0, Command::Clear(at(3, 3)))
+        .expect("The cell should open");
+
+    assert_eq!(play(&mut game, "c rect 2 2 3 3"), GameState::Playing);
+    assert!(matches!(
+        last_outcome(&game),
+        MoveOutcome::AreaOpened {
+            opened: 2,
+            skipped: 2
+        }
+    ));
+}
+
+#[test]
+fn inverted_or_off_board_rectangles_are_turned_down() {
+    assert!(matches!(
+        "c rect 3 0 1 2".parse::<Command>(),
+        Err(CommandError::InvertedRect)
+    ));
+    assert!(matches!(
+        "c rect 0 3 2 1".parse::<Command>(),
+        Err(CommandError::InvertedRect)
+    ));
+    assert!(matches!(
+        "c rect 1 1 1 1".parse::<Command>(),
+        Ok(Command::ClearArea(Area::Rect {
+            top: 1,
+            left: 1,
+            bottom: 1,
+            right: 1
+        }))
+    ));
+
+    let mut game = game_on(LAYOUT);
+    assert!(matches!(
+        area(&mut game, "c rect 2 2 4 3"),
+        Err(GameError::Clear(ClearError::CellNotFound))
+    ));
+    assert!(matches!(
+        area(&mut game, "c row 4"),
+        Err(GameError::Clear(ClearError::CellNotFound))
+    ));
+    assert_eq!(game.board().count_open(), 0);
+}
@@ -0,0 +1,155 @@
+// the command line through the binary: what each combination of options
+// starts, and what it turns down before a game begins
+mod common;
+
+use common::{Home, Run};
+
+fn run(name: &str, args: &[&str]) -> Run {
+    Home::new(name).run(&[&["--plain"], args].concat(), "")
+}
+
+// the status line and the rows after it, the first time the board is drawn
+fn first_board(run: &Run) -> Vec<&str> {
+    run.stdout
+        .lines()
+        .skip_while(|line| !line.starts_with("Mines: "))
+        .take_while(|line| *line != "------")
+        .collect()
+}
+
+#[test]
+fn options_shape_the_board_that_starts() {
+    let cases: [(&[&str], &str, usize, usize); 4] = [
+        (&["--preset", "beginner"], "Mines: 10", 9, 9),
+        (&["--preset", "expert"], "Mines: 99", 16, 30),
+        (
+            &["--width", "3", "--height", "2", "--mines", "1"],
+            "Mines: 1",
+            2,
+            3,
+        ),
+        // the last of two that set the same thing wins
+        (
+            &["--size", "4", "--density", "0.5", "--size", "5"],
+            "Mines: ",
+            5,
+            5,
+        ),
+    ];
+    for (index, (args, status, height, width)) in cases.into_iter().enumerate() {
+        let run = run(
+            &format!("cli_board_{index}"),
+            &[&["--seed", "1"], args].concat(),
+        );
+        let board = first_board(&run);
+        assert!(board[0].starts_with(status), "{args:?}: {}", board[0]);
+        assert_eq!(board.len() - 1, height, "{args:?}");
+        assert!(board[1..].iter().all(|row| row.split(' ').count() == width));
+        assert_eq!(run.code, Some(130));
+    }
+}
+
+#[test]
+fn a_seed_picks_the_same_board_every_time() {
+    let args = ["--seed", "7", "--size", "6", "--mines", "5"];
+    let input = "c 0 0\n";
+    let first = Home::new("cli_seed_first").run(&args, input);
+    let second = Home::new("cli_seed_second").run(&args, input);
+    assert_eq!(first.stdout, second.stdout);
+}
+
+#[test]
+fn no_color_and_batch_leave_out_what_they_should() {
+    let colorless = Home::new("cli_no_color").run(&["--seed", "1", "--no-color"], "c 0 0\n");
+    assert!(!colorless.stdout.contains('\x1b'));
+
+    // a batch game draws nothing until it is asked to
+    let batch = run(
+        "cli_batch",
+        &["--batch", "--seed", "1", "--size", "3", "--mines", "1"],
+    );
+    assert!(first_board(&batch).is_empty(), "{}", batch.stdout);
+    assert_eq!(batch.code, Some(130));
+}
+
+#[test]
+fn help_lists_the_options_and_exits_cleanly() {
+    let help = run("cli_help", &["--help"]);
+    assert!(help
+        .stdout
+        .starts_with("Usage: minesweeper [MODE] [OPTIONS]"));
+    for option in [
+        "--size",
+        "--width",
+        "--mines",
+        "--density",
+        "--seed",
+        "--preset",
+    ] {
+        assert!(help.stdout.contains(&format!("  {option} ")), "{option}");
+    }
+    assert_eq!(help.code, Some(0));
+}
+
+#[test]
+fn invalid_options_are_turned_down_before_a_game() {
+    let cases: [(&[&str], &str); 9] = [
+        (
+            &["--size", "0"],
+            "--size must be a whole number of at least 1",
+        ),
+        (
+            &["--size", "3", "--mines", "9"],
+            "There must be fewer mines than cells, but there are 9 mines for 9 cells",
+        ),
+        (
+            &["--density", "2"],
+            "--density must be a number between 0 and 1",
+        ),
+        (&["--mines", "many"], "--mines must be a whole number"),
+        (
+            &["--mines", "10", "--density", "0.2"],
+            "--mines and --density each say how many mines to lay, so only one can be given",
+        ),
+        (
+            &["--preset", "huge"],
+            "--preset must be beginner, intermediate or expert",
+        ),
+        (&["--seed", "x"], "--seed must be a whole number"),
+        (&["--bogus"], "Unknown argument `--bogus`, see --help"),
+        (
+            &["--load", "/nowhere/saved.txt"],
+            "Couldn't read /nowhere/saved.txt: No such file or directory (os error 2)",
+        ),
+    ];
+    for (index, (args, error)) in cases.into_iter().enumerate() {
+        let run = run(&format!("cli_invalid_{index}"), args);
+        assert_eq!(run.stderr.trim(), error, "{args:?}");
+        assert_eq!(run.stdout, "", "{args:?}");
+        assert_eq!(run.code, Some(2), "{args:?}");
+    }
+}
+
+// the settings for how moves are made go with the daily challenge and co-op
+// as well, whatever the board of the day
+#[test]
+fn challenges_confirm_a_clear_next_to_a_flag() {
+    for mode in ["daily", "coop"] {
+        let home = Home::new(&format!("cli_confirm_{mode}"));
+        home.write(
+            "config.toml",
+            "echo_moves = true\nconfirm_near_flags = true\n",
+        );
+        let run = home.run(&[mode, "--plain"], "f 0 0\nc 0 1\nn\n");
+
+        assert!(run.stdout.contains("Flagging (0,0) = A1"), "{}", run.stdout);
+        assert!(
+            run.stdout.contains(
+                "Clearing (0,1) = B1\n(0,1) is next to a flag. Clear it anyway? [y/n]\nLeft as it was"
+            ),
+            "{}",
+            run.stdout
+        );
+        assert_eq!(run.code, Some(130), "{mode}");
+    }
+}
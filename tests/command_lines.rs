@@ -0,0 +1,112 @@
+// several commands on one line, split at semicolons and played in order up
+// to the first that ends the game
+mod common;
+
+use common::{at, game_on, Home};
+use minesweeper::board::CellState;
+use minesweeper::command::{parse_line, Command, CommandError};
+use minesweeper::driver::{run_game, GameConfig, GameInput, GameOutput, Hooks, Io, Screen};
+use minesweeper::game::{Game, GameOutcome};
+use minesweeper::i18n::Msg;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io;
+
+// walled off, so that each clear opens the one cell
+const LAYOUT: &str = "..*\n*.*\n**.";
+
+struct Script(VecDeque<&'static str>);
+
+impl GameInput for Script {
+    fn read_command(&mut self) -> io::Result<Option<String>> {
+        Ok(self.0.pop_front().map(String::from))
+    }
+}
+
+struct Ignored;
+
+impl GameOutput for Ignored {
+    fn show(&mut self, _: &Screen) {}
+}
+
+#[test]
+fn empty_segments_and_trailing_semicolons_are_ignored() {
+    assert_eq!(
+        parse_line("f 0 1;; c 3 3 ;").ok(),
+        Some(vec![Command::Flag(at(0, 1)), Command::Clear(at(3, 3))])
+    );
+    assert!(matches!(parse_line(" ; ;"), Err(CommandError::Empty)));
+    // nothing is played of a line with a bad segment
+    assert!(matches!(
+        parse_line("f 0 1; x 2 2; c 3 3"),
+        Err(CommandError::Unknown(name)) if name == "x"
+    ));
+}
+
+#[test]
+fn a_loss_on_the_third_of_five_commands_stops_the_rest() {
+    let ended: RefCell<Option<Game>> = RefCell::new(None);
+    let hooks = Hooks {
+        on_game_end: Some(Box::new(|game: &Game, _, _: &mut Io| {
+            *ended.borrow_mut() = Some(game.clone());
+        })),
+        ..Hooks::default()
+    };
+    let mut input = Script(VecDeque::from(["f 0 2; c 0 0; c 1 0; c 0 1; f 2 0"]));
+    let mut output = Ignored;
+    let io = Io {
+        input: &mut input,
+        output: &mut output,
+    };
+    let config = GameConfig {
+        game: game_on(LAYOUT),
+        retries: Some(3),
+        idle_limit: None,
+        prompt: Msg::EnterRowOrCommand,
+        players: 1,
+    };
+
+    let outcome = run_game(config, io, hooks);
+    let GameOutcome::Lost { hit, .. } = outcome else {
+        panic!("The third command should lose the game");
+    };
+    assert_eq!(hit, at(1, 0));
+
+    let game = ended.into_inner().expect("The game should have ended");
+    let played: Vec<String> = game
+        .moves()
+        .iter()
+        .map(|made| made.command.to_string())
+        .collect();
+    assert_eq!(played, ["f 0 2", "c 0 0", "c 1 0"]);
+    let board = game.board();
+    assert_eq!(board.count_flags(), 1);
+    let cell = |row, col| board.get_cell(at(row, col)).map(|cell| &cell.state);
+    assert!(matches!(
+        cell(0, 1),
+        Some(CellState::Safe { open: false, .. })
+    ));
+    assert!(matches!(
+        cell(2, 0),
+        Some(CellState::Bomb { flagged: false, .. })
+    ));
+}
+
+// and through the binary, with a line of feedback on each move before it
+#[test]
+fn the_binary_says_what_each_move_on_the_line_did() {
+    let args = ["--seed", "42", "--preset", "beginner", "--plain"];
+    let run = Home::new("command_lines").run(&args, "f 0 0; c 2 7; c 1 1; c 0 5; f 8 8\n");
+
+    let flagged = run
+        .stdout
+        .find("Flag placed at (0,0)")
+        .expect("The flag should be fed back");
+    let lost = run
+        .stdout
+        .find("Game lost")
+        .expect("The game should be lost");
+    assert!(flagged < lost);
+    assert!(!run.stdout.contains("(8,8)"));
+    assert_eq!(run.code, Some(1));
+}
@@ -0,0 +1,199 @@
+// what many of the tests set up the same way: positions by row and column,
+// one-life games on boards written out by hand, runs of the binary in a
+// home of their own, reviewed snapshots, and a clock set by hand; each test
+// uses its own few
+#![allow(dead_code)]
+
+use minesweeper::board::{Board, CellPosition, CellState};
+use minesweeper::driver::GameInput;
+use minesweeper::game::Game;
+use minesweeper::timer::Clock;
+use std::collections::VecDeque;
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command as Process, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub fn at(row_index: usize, col_index: usize) -> CellPosition {
+    CellPosition::new(row_index, col_index)
+}
+
+// `layout` as `Board::layout` writes it
+pub fn board(layout: &str) -> Board {
+    layout.parse().expect("The layout should parse")
+}
+
+pub fn game_on(layout: &str) -> Game {
+    Game::new(board(layout), 1)
+}
+
+// row by row
+pub fn safe_cells(board: &Board) -> Vec<CellPosition> {
+    board
+        .rows()
+        .flatten()
+        .filter(|cell| matches!(cell.state, CellState::Safe { .. }))
+        .map(|cell| cell.position)
+        .collect()
+}
+
+pub fn mines(board: &Board) -> Vec<CellPosition> {
+    board
+        .rows()
+        .flatten()
+        .filter(|cell| matches!(cell.state, CellState::Bomb { .. }))
+        .map(|cell| cell.position)
+        .collect()
+}
+
+// a clock that reads whatever it was last set to, in seconds
+#[derive(Default)]
+pub struct SetByHand(AtomicU64);
+
+impl SetByHand {
+    pub fn set(&self, seconds: u64) {
+        self.0.store(seconds, Ordering::Relaxed);
+    }
+}
+
+impl Clock for SetByHand {
+    fn now(&self) -> Duration {
+        Duration::from_secs(self.0.load(Ordering::Relaxed))
+    }
+}
+
+// each line read at the second it is paired with, on `clock`
+pub struct Timed {
+    pub clock: Arc<SetByHand>,
+    pub lines: VecDeque<(u64, &'static str)>,
+}
+
+impl GameInput for Timed {
+    fn read_command(&mut self) -> io::Result<Option<String>> {
+        Ok(self.lines.pop_front().map(|(second, line)| {
+            self.clock.set(second);
+            line.to_string()
+        }))
+    }
+}
+
+// what the binary printed, and how it exited
+pub struct Run {
+    pub stdout: String,
+    pub stderr: String,
+    pub code: Option<i32>,
+}
+
+// a data and config directory for the binary, kept apart from the user's
+// and from every other test's
+pub struct Home {
+    pub path: PathBuf,
+}
+
+impl Home {
+    pub fn new(name: &str) -> Self {
+        let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+            .join("homes")
+            .join(name);
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(path.join("minesweeper-cli")).expect("The test's home should be made");
+        Home { path }
+    }
+
+    // `file` in the binary's own directory, where its config and data go
+    pub fn write(&self, file: &str, contents: &str) {
+        let path = self.path.join("minesweeper-cli").join(file);
+        fs::write(path, contents).expect("The file should be written");
+    }
+
+    pub fn read(&self, file: &str) -> Option<String> {
+        fs::read_to_string(self.path.join("minesweeper-cli").join(file)).ok()
+    }
+
+    // in English, with `input` piped in
+    pub fn run(&self, args: &[&str], input: &str) -> Run {
+        self.run_in("en", args, input)
+    }
+
+    pub fn run_in(&self, lang: &str, args: &[&str], input: &str) -> Run {
+        let mut child = Process::new(env!("CARGO_BIN_EXE_minesweeper"))
+            .args(args)
+            .args(["--lang", lang])
+            .env("XDG_DATA_HOME", &self.path)
+            .env("XDG_CONFIG_HOME", &self.path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("The binary should start");
+        // a binary that stops reading early leaves the rest unread
+        let _ = child
+            .stdin
+            .take()
+            .expect("The binary's stdin should be piped")
+            .write_all(input.as_bytes());
+        let output = child.wait_with_output().expect("The binary should finish");
+        Run {
+            stdout: String::from_utf8(output.stdout).expect("The output should be UTF-8"),
+            stderr: String::from_utf8(output.stderr).expect("The errors should be UTF-8"),
+            code: output.status.code(),
+        }
+    }
+}
+
+// against its reviewed snapshot in tests/snapshots; run with UPDATE_SNAPSHOTS=1
+// to write the snapshots afresh after a change that is meant to alter them,
+// then review the diff
+pub fn assert_snapshot(name: &str, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{name}.txt"));
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, actual).unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+        return;
+    }
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("{}: {e}; run with UPDATE_SNAPSHOTS=1", path.display()));
+    assert!(
+        expected == actual,
+        "{name} doesn't match {}; run with UPDATE_SNAPSHOTS=1 to see the difference\n\n{actual}",
+        path.display()
+    );
+}
+
+// minutes and seconds, with or without tenths, e.g. 00:03 or 0:03.2
+pub fn redact_times(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::new();
+    let mut index = 0;
+    while index < chars.len() {
+        let digits = chars[index..]
+            .iter()
+            .take_while(|c| c.is_ascii_digit())
+            .count();
+        let rest = &chars[index + digits..];
+        let starts_a_word = index == 0 || !chars[index - 1].is_ascii_digit();
+        if starts_a_word
+            && digits > 0
+            && rest.len() >= 3
+            && rest[0] == ':'
+            && rest[1].is_ascii_digit()
+            && rest[2].is_ascii_digit()
+        {
+            index += digits + 3;
+            let tenths = chars.get(index + 1).is_some_and(char::is_ascii_digit);
+            if chars.get(index) == Some(&'.') && tenths {
+                index += 2;
+            }
+            out.push_str("--:--");
+        } else {
+            out.push(chars[index]);
+            index += 1;
+        }
+    }
+    out
+}
@@ -0,0 +1,188 @@
+// the files a released version wrote, kept as they were in
+// tests/compatibility, must keep loading however the formats change after:
+// a file is named for its format and the version it was written at, and a
+// fixture, once added, is never edited. Each is read through the binary,
+// in a data directory kept apart from the user's
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command as Process, Stdio};
+
+fn fixture(name: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/compatibility")
+        .join(name);
+    fs::read_to_string(path).expect("The fixture should be read")
+}
+
+struct Run {
+    stdout: String,
+    stderr: String,
+    code: Option<i32>,
+}
+
+struct Home {
+    path: PathBuf,
+}
+
+impl Home {
+    // with each of `files` written into the data directory as it is named
+    fn new(name: &str, files: &[(&str, &str)]) -> Self {
+        let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+            .join("compatibility")
+            .join(name);
+        let _ = fs::remove_dir_all(&path);
+        let data = path.join("minesweeper-cli");
+        fs::create_dir_all(&data).expect("The test's data directory should be made");
+        for (file, contents) in files {
+            fs::write(data.join(file), contents).expect("The file should be written");
+        }
+        Home { path }
+    }
+
+    fn file(&self, name: &str) -> String {
+        fs::read_to_string(self.path.join("minesweeper-cli").join(name))
+            .expect("The file should still be there")
+    }
+
+    fn run(&self, args: &[&str], input: &str) -> Run {
+        let mut child = Process::new(env!("CARGO_BIN_EXE_minesweeper"))
+            .args(args)
+            .args(["--lang", "en", "--plain", "--no-color", "--no-summary"])
+            .env("XDG_DATA_HOME", &self.path)
+            .env("XDG_CONFIG_HOME", &self.path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("The binary should start");
+        child
+            .stdin
+            .take()
+            .expect("The binary's stdin should be piped")
+            .write_all(input.as_bytes())
+            .expect("The binary should read its input");
+        let output = child.wait_with_output().expect("The binary should finish");
+        Run {
+            stdout: String::from_utf8(output.stdout).expect("The output should be UTF-8"),
+            stderr: String::from_utf8(output.stderr).expect("The errors should be UTF-8"),
+            code: output.status.code(),
+        }
+    }
+}
+
+// the same game, two moves in, saved at each version
+#[test]
+fn saves_from_every_version_still_load() {
+    for name in ["save_v1.txt", "save_v2.txt"] {
+        let home = Home::new(name, &[("saved.txt", &fixture(name))]);
+        let saved = home.path.join("minesweeper-cli").join("saved.txt");
+        let saved = saved.to_str().expect("The path should be UTF-8");
+        let run = home.run(&["--load", saved], "");
+
+        assert!(
+            run.stdout.contains("Flags: 1  Time: 00:37  Moves: 2"),
+            "{name}: {}",
+            run.stderr
+        );
+        assert_eq!(run.code, Some(130), "{name}");
+    }
+}
+
+#[test]
+fn a_save_from_a_newer_version_is_turned_down() {
+    let newer = fixture("save_v2.txt").replace("version 2", "version 3");
+    let home = Home::new("newer_save", &[("saved.txt", &newer)]);
+    let saved = home.path.join("minesweeper-cli").join("saved.txt");
+    let run = home.run(
+        &["--load", saved.to_str().expect("The path should be UTF-8")],
+        "",
+    );
+
+    let message = "This save was written by a newer version of minesweeper-cli \
+                   (format version 3; this one reads up to 2)";
+    assert_eq!(run.stderr.trim(), message);
+    assert_eq!(run.code, Some(2));
+}
+
+// edited by hand to a value the rules turn down, it is turned down as it
+// loads, and not left to break the game that would be played from it
+#[test]
+fn a_save_with_no_lives_is_turned_down() {
+    let edited = fixture("save_v2.txt").replace("lives 1", "lives 0");
+    let home = Home::new("no_lives", &[("saved.txt", &edited)]);
+    let saved = home.path.join("minesweeper-cli").join("saved.txt");
+    let saved = saved.to_str().expect("The path should be UTF-8");
+    for args in [&["--load", saved][..], &["--load", saved, "--batch"]] {
+        let run = home.run(args, "");
+        assert_eq!(
+            run.stderr.trim(),
+            "Malformed save: A game needs at least one life",
+            "{args:?}"
+        );
+        assert_eq!(run.code, Some(2), "{args:?}");
+    }
+}
+
+// and a history with lines from every version, as a history is appended to
+// by each release in turn
+#[test]
+fn history_records_from_every_version_still_load() {
+    let history = fixture("history_v1.jsonl") + &fixture("history_v2.jsonl");
+    let home = Home::new("history", &[("history.jsonl", &history)]);
+    let run = home.run(&["history"], "");
+
+    assert!(!run.stdout.contains("Skipping"), "{}", run.stdout);
+    for code in ["#7", "#8", "#9", "#42", "#43"] {
+        assert!(
+            run.stdout
+                .lines()
+                .any(|line| line.ends_with(&format!("  {code}"))),
+            "{code}"
+        );
+    }
+}
+
+#[test]
+fn a_newer_history_record_is_skipped_and_the_rest_kept() {
+    let newer = fixture("history_v2.jsonl").replace("\"version\":2", "\"version\":3");
+    let history = fixture("history_v1.jsonl") + &newer;
+    let home = Home::new("newer_history", &[("history.jsonl", &history)]);
+    let run = home.run(&["history"], "");
+
+    let message = "Skipping line 4 of the history: This history record was written by a newer \
+                   version of minesweeper-cli (format version 3; this one reads up to 2)";
+    assert!(run.stdout.contains(message), "{}", run.stdout);
+    assert!(run.stdout.contains("  #9"));
+    assert!(!run.stdout.contains("  #42"));
+}
+
+#[test]
+fn stats_from_every_version_still_load() {
+    for name in ["stats_v1.txt", "stats_v2.txt"] {
+        let home = Home::new(name, &[("stats.txt", &fixture(name))]);
+        let run = home.run(&["achievements"], "");
+        assert!(
+            run.stdout.contains("[x] First win"),
+            "{name}: {}",
+            run.stdout
+        );
+    }
+}
+
+// not written over, so that the newer release still finds its bests
+#[test]
+fn a_newer_stats_file_is_left_as_it_is() {
+    let newer = fixture("stats_v2.txt").replace("version 2", "version 3");
+    let home = Home::new("newer_stats", &[("stats.txt", &newer)]);
+    let run = home.run(&["--seed", "42", "--preset", "beginner"], "c 2 7\nc 0 0\n");
+
+    assert!(run
+        .stdout
+        .contains("This stats file was written by a newer version"));
+    assert!(run
+        .stdout
+        .contains("so it is left as it is and nothing is kept this session"));
+    assert_eq!(run.code, Some(1), "Hitting the mine should lose the game");
+    assert_eq!(home.file("stats.txt"), newer);
+}
@@ -0,0 +1,78 @@
+// the config file through the binary: built-in defaults, then the file, then
+// the command line, and a bad file named down to its line and key
+mod common;
+
+use common::{Home, Run};
+
+// the status line and the rows after it, the first time the board is drawn
+fn first_board(run: &Run) -> Vec<&str> {
+    run.stdout
+        .lines()
+        .skip_while(|line| !line.starts_with("Mines: "))
+        .take_while(|line| *line != "------")
+        .collect()
+}
+
+fn with_config(name: &str, config: &str, args: &[&str]) -> Run {
+    let home = Home::new(name);
+    home.write("config.toml", config);
+    home.run(&[&["--seed", "1", "--plain"], args].concat(), "")
+}
+
+#[test]
+fn the_command_line_overrides_the_file_and_the_file_the_defaults() {
+    // the height from the file, the width from the command line, and the
+    // theme from the defaults
+    let run = with_config(
+        "config_precedence",
+        "size = 4\nmines = 1\n",
+        &["--width", "6"],
+    );
+
+    let board = first_board(&run);
+    assert_eq!(board[0], "Mines: 1  Flags: 0  Moves: 0");
+    assert_eq!(&board[1..], ["# # # # # #"; 4]);
+    assert_eq!(run.code, Some(130));
+
+    // and with nothing given, the defaults
+    let run = Home::new("config_defaults").run(&["--seed", "1", "--plain"], "");
+    assert_eq!(&first_board(&run)[1..], ["# # # # # # # # # #"; 10]);
+}
+
+#[test]
+fn a_malformed_file_names_the_bad_line_and_key() {
+    let cases = [
+        ("size = 5\nsise = 6\n", "Config line 2: unknown key `sise`"),
+        (
+            "# the theme\ntheme = neon\n",
+            "Config line 2: `theme` must be \"ascii\" or \"emoji\"",
+        ),
+        (
+            "size = 5\nheight = zero\n",
+            "Config line 2: `height` must be a whole number of at least 1",
+        ),
+        ("width 5\n", "Config line 1: expected `key = value`"),
+    ];
+    for (index, (config, error)) in cases.into_iter().enumerate() {
+        let run = with_config(&format!("config_malformed_{index}"), config, &[]);
+        assert_eq!(run.stderr.trim(), error);
+        assert_eq!(run.code, Some(2), "{error}");
+    }
+}
+
+#[test]
+fn config_init_writes_a_file_that_loads_as_the_defaults() {
+    let home = Home::new("config_init");
+    let run = home.run(&["config", "init"], "");
+    assert_eq!(run.code, Some(0), "{}", run.stderr);
+    assert!(home.read("config.toml").is_some());
+
+    let run = home.run(&["--seed", "1", "--plain"], "");
+    assert_eq!(run.stderr, "");
+    assert_eq!(&first_board(&run)[1..], ["# # # # # # # # # #"; 10]);
+
+    // and isn't written over
+    let again = home.run(&["config", "init"], "");
+    assert_eq!(again.code, Some(2));
+    assert!(again.stderr.contains("already exists, not overwriting it"));
+}
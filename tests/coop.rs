@@ -0,0 +1,53 @@
+// co-op through the binary: two players scripted in turn on the seeded
+// beginner's board, sharing three lives
+mod common;
+
+use common::{Home, Run};
+
+fn coop(name: &str, input: &str) -> Run {
+    let args = ["coop", "--seed", "42", "--preset", "beginner", "--plain"];
+    Home::new(name).run(&args, input)
+}
+
+fn turns(run: &Run) -> Vec<&str> {
+    run.stdout
+        .lines()
+        .filter_map(|line| line.split_once("'s turn"))
+        .map(|(player, _)| player)
+        .collect()
+}
+
+#[test]
+fn players_take_turns_and_share_their_lives() {
+    // a cascade, a flag, then three mines between them
+    let run = coop("coop_lost", "c 2 7\nf 0 0\nc 1 1\nc 1 5\nc 3 4\n");
+
+    assert_eq!(
+        turns(&run),
+        ["Player 1", "Player 2", "Player 1", "Player 2", "Player 1"]
+    );
+    assert!(run
+        .stdout
+        .contains("Player 2: clear (1,5) hit a bomb\n1 lives left"));
+    assert!(run
+        .stdout
+        .contains("Player 1: 39 cells opened, 0 flags placed, 2 mistakes"));
+    assert!(run
+        .stdout
+        .contains("Player 2: 0 cells opened, 1 flags placed, 1 mistakes"));
+    assert!(run.stdout.contains("Game lost: hit a mine at (3,4)"));
+    assert_eq!(run.code, Some(1));
+}
+
+// undoing a move hands the turn back to whoever made it
+#[test]
+fn an_undo_gives_the_turn_back() {
+    let run = coop("coop_undo", "c 2 7\nu\nc 2 7\n");
+
+    assert!(run.stdout.contains("Move undone"));
+    assert_eq!(
+        turns(&run),
+        ["Player 1", "Player 2", "Player 1", "Player 2"]
+    );
+    assert_eq!(run.code, Some(130));
+}
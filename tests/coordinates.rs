@@ -0,0 +1,138 @@
+// rows and columns as they are typed: anything but plain digits isn't a
+// number, too many digits are too large for any board, and a number past
+// the board's edge is out of its range; none of it panics
+use minesweeper::board::CellPosition;
+use minesweeper::command::{
+    check_coordinate, parse_coordinate, parse_line_within, parse_signed_coordinate, Command,
+    CommandError, CoordinateError, EndlessCommand, Field, Point,
+};
+
+fn not_a_number(word: &str) -> Result<usize, CoordinateError> {
+    Err(CoordinateError::NotANumber(word.to_string()))
+}
+
+#[test]
+fn plain_digits_are_coordinates() {
+    assert_eq!(parse_coordinate("0"), Ok(0));
+    assert_eq!(parse_coordinate("007"), Ok(7));
+    assert_eq!(parse_coordinate(&usize::MAX.to_string()), Ok(usize::MAX));
+}
+
+#[test]
+fn anything_but_plain_digits_is_not_a_number() {
+    let words = [
+        "",
+        "x",
+        "+3",
+        "-3",
+        "3.0",
+        "3 4",
+        " 3",
+        "3\0",
+        "\u{0}3",
+        "0x10",
+        "1_000",
+        "١٢",
+        "３",
+        "٣",
+        "3\u{200b}",
+        "½",
+    ];
+    for word in words {
+        assert_eq!(parse_coordinate(word), not_a_number(word), "{word:?}");
+    }
+}
+
+#[test]
+fn too_many_digits_are_too_large_for_any_board() {
+    for word in [
+        "99999999999999999999",
+        "18446744073709551616",
+        &"9".repeat(500),
+    ] {
+        assert_eq!(
+            parse_coordinate(word),
+            Err(CoordinateError::TooLarge(word.to_string()))
+        );
+    }
+    assert!(parse_coordinate("99999999999999999999")
+        .unwrap_err()
+        .to_string()
+        .contains("too large"));
+}
+
+#[test]
+fn a_number_past_the_edge_is_out_of_range() {
+    assert_eq!(check_coordinate(8, Field::Row, 9), Ok(8));
+    assert_eq!(
+        check_coordinate(9, Field::Column, 9),
+        Err(CoordinateError::OutOfRange {
+            field: Field::Column,
+            index: 9,
+            max: 8
+        })
+    );
+    let off = check_coordinate(usize::MAX, Field::Row, 1).unwrap_err();
+    let message = format!(
+        "Row {} is off the board, whose rows go from 0 to 0",
+        usize::MAX
+    );
+    assert_eq!(off.to_string(), message);
+}
+
+#[test]
+fn the_endless_board_takes_a_minus_and_nothing_else() {
+    assert_eq!(parse_signed_coordinate("-12"), Ok(-12));
+    assert_eq!(parse_signed_coordinate(&i64::MIN.to_string()), Ok(i64::MIN));
+    for word in ["+3", "--3", "-", "- 3", "-٣"] {
+        assert_eq!(
+            parse_signed_coordinate(word),
+            Err(CoordinateError::NotANumber(word.to_string()))
+        );
+    }
+    assert_eq!(
+        parse_signed_coordinate("9223372036854775808"),
+        Err(CoordinateError::TooLarge(String::from(
+            "9223372036854775808"
+        )))
+    );
+    assert_eq!(
+        "go -3 4".parse::<EndlessCommand>().ok(),
+        Some(EndlessCommand::Go(Point { row: -3, col: 4 }))
+    );
+}
+
+// as the parser gives them, each with its code for the JSON protocol
+#[test]
+fn commands_say_which_kind_of_coordinate_was_wrong() {
+    let code = |line: &str| parse_line_within(line, 9, 9).map_err(|e| e.code());
+    assert_eq!(code("c +3 4"), Err("not_a_number"));
+    assert_eq!(code("f 3 ４"), Err("not_a_number"));
+    assert_eq!(code("c 3\0 4"), Err("not_a_number"));
+    assert_eq!(code("c 99999999999999999999 4"), Err("too_large"));
+    assert_eq!(code("c 9 4"), Err("out_of_range"));
+    assert_eq!(code("f 0 0; ch 3 9"), Err("out_of_range"));
+    assert_eq!(code("c rect 0 0 2 12"), Err("out_of_range"));
+    assert_eq!(code("c row 9"), Err("out_of_range"));
+    assert_eq!(code("zap 3 4"), Err("unknown_command"));
+
+    let at = CellPosition {
+        row_index: 8,
+        col_index: 8,
+    };
+    let parsed = parse_line_within("c 8 8; share", 9, 9);
+    assert_eq!(parsed.ok(), Some(vec![Command::Clear(at), Command::Share]));
+
+    // and without a board to be within, only the number is checked
+    assert!(matches!(
+        "c 9 4000".parse::<Command>(),
+        Ok(Command::Clear(CellPosition {
+            row_index: 9,
+            col_index: 4000
+        }))
+    ));
+    assert!(matches!(
+        "peek 1 99999999999999999999".parse::<Command>(),
+        Err(CommandError::Coordinate(CoordinateError::TooLarge(_)))
+    ));
+}
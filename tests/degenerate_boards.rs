@@ -0,0 +1,138 @@
+// boards with no safe cell, or only the one: the constructors that check
+// boards turn the first down, and the game loop says what each is rather
+// than calling it won
+mod common;
+
+use common::Home;
+use minesweeper::board::{Board, CellPosition, CellState, LayoutError, Mines};
+use minesweeper::command::Command;
+use minesweeper::driver::{run_game, GameConfig, GameInput, GameOutput, Hooks, Io, Screen};
+use minesweeper::game::{Game, GameOutcome};
+use minesweeper::i18n::Msg;
+use minesweeper::rules::{Ruleset, RulesetError};
+use std::collections::VecDeque;
+use std::io;
+
+struct Script(VecDeque<&'static str>);
+
+impl GameInput for Script {
+    fn read_command(&mut self) -> io::Result<Option<String>> {
+        Ok(self.0.pop_front().map(String::from))
+    }
+}
+
+// the messages shown, and whether the mines were
+#[derive(Default)]
+struct Kept {
+    messages: Vec<String>,
+    revealed: bool,
+}
+
+impl GameOutput for Kept {
+    fn show(&mut self, screen: &Screen) {
+        match screen {
+            Screen::Message(message) => self.messages.push(message.clone()),
+            Screen::Revealed(_) => self.revealed = true,
+            _ => (),
+        }
+    }
+}
+
+fn run(game: Game, lines: &[&'static str]) -> (GameOutcome, Kept) {
+    let mut input = Script(lines.iter().copied().collect());
+    let mut output = Kept::default();
+    let io = Io {
+        input: &mut input,
+        output: &mut output,
+    };
+    let config = GameConfig {
+        game,
+        retries: Some(3),
+        idle_limit: None,
+        prompt: Msg::EnterRowOrCommand,
+        players: 1,
+    };
+    let outcome = run_game(config, io, Hooks::default());
+    (outcome, output)
+}
+
+#[test]
+fn a_board_of_only_mines_is_turned_down() {
+    assert_eq!(
+        "**\n**".parse::<Board>().err(),
+        Some(LayoutError::NoSafeCells)
+    );
+    assert_eq!(
+        "*o\n**".parse::<Board>().err(),
+        Some(LayoutError::NoSafeCells)
+    );
+    let rules = Ruleset::builder().size(2, 2).mines(Mines::Count(4)).build();
+    assert_eq!(
+        rules.err(),
+        Some(RulesetError::TooManyMines { mines: 4, cells: 4 })
+    );
+
+    // made without being checked, it is won before a move
+    let game = Game::new(Board::from_bombs(vec![vec![true, true]]), 1);
+    assert!(game.outcome().is_some());
+    let (outcome, kept) = run(game, &["c 0 0"]);
+    assert_eq!(outcome, GameOutcome::Aborted);
+    assert_eq!(
+        kept.messages,
+        ["Every cell on this board is a mine, so there is nothing to play"]
+    );
+    assert!(!kept.revealed);
+}
+
+#[test]
+fn a_board_with_one_safe_cell_is_said_to_be_a_guess() {
+    let board: Board = "*.\n**".parse().expect("One safe cell is enough to play");
+    let (outcome, kept) = run(Game::new(board, 1), &["c 0 1"]);
+    assert!(matches!(outcome, GameOutcome::Won(_)));
+    let warning =
+        "Only one of this board's 4 cells is safe, so the first clear wins or loses the game";
+    assert_eq!(kept.messages.first().map(String::as_str), Some(warning));
+
+    let rules = Ruleset::builder().size(3, 3).mines(Mines::Count(8)).build();
+    let mut game = Game::from_ruleset(&rules.expect("Eight mines leave a cell to open"), 7);
+    assert_eq!(game.board().count_safe(), 1);
+    let board = game.board();
+    let is_safe = |at| {
+        board
+            .get_cell(at)
+            .is_some_and(|cell| matches!(cell.state, CellState::Safe { .. }))
+    };
+    let safe = (0..9)
+        .map(|index| CellPosition {
+            row_index: index / 3,
+            col_index: index % 3,
+        })
+        .find(|&at| is_safe(at))
+        .expect("There should be a safe cell");
+    game.apply(0, Command::Clear(safe))
+        .expect("The safe cell should open");
+    assert!(matches!(game.outcome(), Some(GameOutcome::Won(_))));
+}
+
+// and batch mode, which doesn't go through the game loop, says so as well
+#[test]
+fn batch_mode_says_one_safe_cell_is_a_guess() {
+    let warning =
+        "Only one of this board's 4 cells is safe, so the first clear wins or loses the game";
+    let args = ["--batch", "--size", "2", "--mines", "3", "--seed", "1"];
+    let run = Home::new("one_safe_batch").run(&args, "c 0 0\n");
+    assert_eq!(run.stdout.lines().next(), Some(warning), "{}", run.stdout);
+
+    let json = [&args[..], &["--json"]].concat();
+    let run = Home::new("one_safe_json").run(&json, "c 0 0\n");
+    let expected = format!(r#"{{"warning":"{warning}"}}"#);
+    assert_eq!(run.stdout.lines().next(), Some(expected.as_str()));
+}
+
+// lost before a move, with no mine gone off: abandoned, not lost
+#[test]
+fn a_game_with_no_lives_is_abandoned() {
+    let board: Board = "*.\n..".parse().expect("The layout should parse");
+    let game = Game::new(board, 0);
+    assert_eq!(game.outcome(), Some(GameOutcome::Aborted));
+}
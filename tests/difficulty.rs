@@ -0,0 +1,65 @@
+// the difficulty report on boards made by hand, whose guesses are known
+use minesweeper::analysis::DifficultyReport;
+use minesweeper::board::Board;
+
+fn report(layout: &str) -> DifficultyReport {
+    let board: Board = layout.parse().expect("The layout should parse");
+    board.difficulty_report()
+}
+
+#[test]
+fn a_board_without_mines_needs_no_guessing() {
+    let report = report("....\n....\n....");
+
+    assert_eq!(
+        report,
+        DifficultyReport {
+            three_bv: 1,
+            guesses: 0,
+            largest_frontier: 0,
+            stars: 1,
+        }
+    );
+}
+
+// between them, the ones along the middle leave only the centre of the top
+// row for the mine
+#[test]
+fn a_board_the_numbers_clear() {
+    let report = report(".*.\n...\n...\n...");
+
+    assert_eq!(report.guesses, 0);
+    assert_eq!(report.largest_frontier, 3);
+}
+
+// the ones under the corner can't tell its two cells apart
+#[test]
+fn a_corner_fifty_fifty_is_one_guess() {
+    let report = report("*.\n..\n..\n..");
+
+    assert_eq!(
+        report,
+        DifficultyReport {
+            three_bv: 2,
+            guesses: 1,
+            largest_frontier: 2,
+            stars: 3,
+        }
+    );
+}
+
+#[test]
+fn each_fifty_fifty_is_a_guess_of_its_own() {
+    let report = report("*.\n..\n..\n..\n..\n..\n.*");
+
+    assert_eq!(report.guesses, 2);
+    assert_eq!(report.stars, 4);
+}
+
+// walled off by mines, the corner can only be reached by guessing
+#[test]
+fn a_cell_with_no_numbers_next_to_it_is_a_guess() {
+    let report = report(".*...\n**...\n.....\n.....");
+
+    assert_eq!(report.guesses, 1);
+}
@@ -0,0 +1,90 @@
+// the board exported for Discord once a game is over: what the players
+// opened is shown, and every other cell is behind a spoiler
+mod common;
+
+use common::{assert_snapshot, redact_times, Home};
+
+const ARGS: [&str; 8] = [
+    "coop", "--seed", "3", "--size", "4", "--mines", "4", "--plain",
+];
+
+// the block, from its heading up to the next prompt
+fn exported(stdout: &str) -> String {
+    let lines: Vec<&str> = stdout
+        .lines()
+        .skip_while(|line| !line.starts_with("**Minesweeper"))
+        .take_while(|line| !line.starts_with("Enter share"))
+        .collect();
+    lines.join("\n")
+}
+
+// each cell of a grid row: a spoiler with what is behind it, or an emoji
+// out in the open
+fn cells(row: &str) -> Vec<String> {
+    let mut cells = vec![];
+    let mut rest = row;
+    while !rest.is_empty() {
+        if let Some(hidden) = rest.strip_prefix("||") {
+            let (inside, after) = hidden.split_once("||").expect("A spoiler should be closed");
+            cells.push(format!("||{inside}||"));
+            rest = after;
+        } else {
+            // a keycap is three chars, and a mine one
+            let first = rest.chars().next().expect("There should be a cell");
+            let length = match first.is_ascii_digit() {
+                true => 3,
+                false => 1,
+            };
+            let cell: String = rest.chars().take(length).collect();
+            rest = &rest[cell.len()..];
+            cells.push(cell);
+        }
+    }
+    cells
+}
+
+#[test]
+fn a_finished_game_is_exported_with_the_unopened_cells_spoilered() {
+    // three cells opened, then three mines hit
+    let script = "c 0 0\nc 0 3\nc 1 1\nc 0 1\nc 0 2\nc 2 0\nexport discord\n\n";
+    let run = Home::new("discord_export").run(&ARGS, script);
+    let block = redact_times(&exported(&run.stdout));
+    assert_snapshot("discord", &block);
+
+    let mut lines = block.lines();
+    assert_eq!(lines.next(), Some("**Minesweeper Co-op #3**"));
+    assert_eq!(lines.next(), Some("4x4, 4 mines: lost in --:--"));
+    let grid: Vec<Vec<String>> = lines.map(cells).collect();
+    assert_eq!(grid.len(), 4);
+
+    for (row, cells) in grid.iter().enumerate() {
+        assert_eq!(cells.len(), 4, "{cells:?}");
+        for (col, cell) in cells.iter().enumerate() {
+            let opened = [(0, 0), (0, 3), (1, 1)].contains(&(row, col));
+            let hit = [(0, 1), (0, 2), (2, 0)].contains(&(row, col));
+            match (opened, hit) {
+                (true, _) => assert!(cell.ends_with('\u{20E3}'), "({row},{col}) {cell}"),
+                (_, true) => assert_eq!(cell, "💥", "({row},{col})"),
+                _ => assert!(cell.starts_with("||"), "({row},{col}) {cell}"),
+            }
+        }
+    }
+    // the mine that wasn't hit is hidden like any other cell
+    assert_eq!(
+        grid.iter()
+            .flatten()
+            .filter(|cell| *cell == "||💣||")
+            .count(),
+        1
+    );
+}
+
+// while the game is on, it would give the board away
+#[test]
+fn the_export_waits_for_the_end_of_the_game() {
+    let run = Home::new("discord_early").run(
+        &["--seed", "3", "--size", "4", "--plain"],
+        "c 0 0\nexport discord\n",
+    );
+    assert!(!run.stdout.contains("||"), "{}", run.stdout);
+}
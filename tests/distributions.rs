@@ -0,0 +1,106 @@
+// the ways mines can be scattered: each places exactly the count asked
+// for, and over many seeds the pockets of a clustered board and the spacing
+// of a sparse one show in how the mines fall
+mod common;
+
+use common::mines;
+use minesweeper::board::{Board, CellPosition, Layout, MineDistribution, Mines};
+
+const DISTRIBUTIONS: [MineDistribution; 3] = [
+    MineDistribution::Uniform,
+    MineDistribution::Clustered,
+    MineDistribution::Sparse,
+];
+
+const SEEDS: u64 = 50;
+
+fn generate(
+    distribution: MineDistribution,
+    height: usize,
+    width: usize,
+    count: usize,
+    seed: u64,
+) -> Vec<CellPosition> {
+    let layout = Layout {
+        distribution,
+        ..Layout::new(Mines::Count(count))
+    };
+    mines(&Board::generate(seed, height, width, &layout))
+}
+
+// the variance of the mines counted in each 4x4 block of a 16x16 board
+fn block_variance(mines: &[CellPosition]) -> f64 {
+    let mut blocks = [0.0; 16];
+    for mine in mines {
+        blocks[mine.row_index / 4 * 4 + mine.col_index / 4] += 1.0;
+    }
+    let mean = blocks.iter().sum::<f64>() / 16.0;
+    blocks
+        .iter()
+        .map(|count| (count - mean).powi(2))
+        .sum::<f64>()
+        / 16.0
+}
+
+// how far, in rows or columns, each mine is from the nearest other one
+fn nearest_gap(mines: &[CellPosition]) -> f64 {
+    let gaps = mines.iter().map(|mine| {
+        mines
+            .iter()
+            .filter(|other| *other != mine)
+            .map(|other| {
+                mine.row_index
+                    .abs_diff(other.row_index)
+                    .max(mine.col_index.abs_diff(other.col_index))
+            })
+            .min()
+            .unwrap_or(0)
+    });
+    gaps.sum::<usize>() as f64 / mines.len() as f64
+}
+
+// over every seed
+fn average(distribution: MineDistribution, measure: fn(&[CellPosition]) -> f64) -> f64 {
+    (0..SEEDS)
+        .map(|seed| measure(&generate(distribution.clone(), 16, 16, 40, seed)))
+        .sum::<f64>()
+        / SEEDS as f64
+}
+
+#[test]
+fn every_distribution_places_exactly_the_count() {
+    for distribution in DISTRIBUTIONS {
+        for (height, width, count) in [
+            (9, 9, 10),
+            (16, 16, 40),
+            (16, 30, 99),
+            (5, 7, 30),
+            (1, 1, 0),
+        ] {
+            for seed in 0..10 {
+                let mines = generate(distribution.clone(), height, width, count, seed);
+                assert_eq!(
+                    mines.len(),
+                    count,
+                    "{distribution:?} {height}x{width} #{seed}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn clustered_mines_bunch_up_and_sparse_ones_spread_out() {
+    let uniform = average(MineDistribution::Uniform, block_variance);
+    let clustered = average(MineDistribution::Clustered, block_variance);
+    let sparse = average(MineDistribution::Sparse, block_variance);
+    assert!(clustered > uniform, "{clustered} against {uniform}");
+    assert!(sparse < uniform, "{sparse} against {uniform}");
+
+    let uniform = average(MineDistribution::Uniform, nearest_gap);
+    let clustered = average(MineDistribution::Clustered, nearest_gap);
+    let sparse = average(MineDistribution::Sparse, nearest_gap);
+    assert!(clustered < uniform, "{clustered} against {uniform}");
+    // by a good half a cell; chance alone leaves most mines next to another
+    assert!(sparse > uniform + 0.5, "{sparse} against {uniform}");
+}
@@ -0,0 +1,254 @@
+// the game loop as an embedder drives it: lines from a script, screens
+// kept, and hooks that watch each step or turn a move down
+mod common;
+
+use common::{mines, safe_cells};
+use minesweeper::board::{Board, CellState, Mines};
+use minesweeper::command::Command;
+use minesweeper::driver::{
+    run_game, GameConfig, GameInput, GameOutput, Hooks, Io, Screen, Verdict,
+};
+use minesweeper::game::{Game, GameOutcome, GameState};
+use minesweeper::i18n::{text, Msg};
+use minesweeper::reference::render_commands;
+use minesweeper::rules::Ruleset;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io;
+
+// walled off, so that each clear opens the one cell
+const LAYOUT: &str = "..*\n*.*\n**.";
+
+struct Lines(VecDeque<String>);
+
+impl GameInput for Lines {
+    fn read_command(&mut self) -> io::Result<Option<String>> {
+        Ok(self.0.pop_front())
+    }
+}
+
+// each screen as a line: the board and the mines by name, the rest as shown
+#[derive(Default)]
+struct Kept(Vec<String>);
+
+impl GameOutput for Kept {
+    fn show(&mut self, screen: &Screen) {
+        self.0.push(match screen {
+            Screen::Board(_) => String::from("board"),
+            Screen::Revealed(_) => String::from("revealed"),
+            Screen::Prompt(msg) => text(*msg).to_string(),
+            Screen::Message(message) => message.clone(),
+            Screen::Divider => String::from("------"),
+            Screen::Paused => String::from("paused"),
+        });
+    }
+}
+
+fn setup() -> GameConfig {
+    let board: Board = LAYOUT.parse().expect("The layout should parse");
+    GameConfig {
+        game: Game::new(board, 1),
+        retries: Some(3),
+        idle_limit: None,
+        prompt: Msg::EnterRowOrCommand,
+        players: 1,
+    }
+}
+
+fn run(lines: &[&'static str], hooks: Hooks) -> (GameOutcome, Vec<String>) {
+    run_on(setup(), lines.iter().map(|line| line.to_string()), hooks)
+}
+
+fn run_on(
+    config: GameConfig,
+    lines: impl IntoIterator<Item = String>,
+    hooks: Hooks,
+) -> (GameOutcome, Vec<String>) {
+    let mut input = Lines(lines.into_iter().collect());
+    let mut output = Kept::default();
+    let io = Io {
+        input: &mut input,
+        output: &mut output,
+    };
+    let outcome = run_game(config, io, hooks);
+    (outcome, output.0)
+}
+
+// the beginner's board the binary deals for `seed`
+fn seeded(seed: u64) -> GameConfig {
+    let rules = Ruleset::builder()
+        .size(9, 9)
+        .mines(Mines::Count(10))
+        .build()
+        .expect("The beginner's rules should build");
+    GameConfig {
+        game: Game::new(rules.board(seed), 1),
+        retries: Some(3),
+        idle_limit: None,
+        prompt: Msg::EnterRowOrCommand,
+        players: 1,
+    }
+}
+
+#[test]
+fn hooks_hear_of_each_step_of_a_scripted_game() {
+    let heard = RefCell::new(vec![]);
+    let hooks = Hooks {
+        on_move: Some(Box::new(|_: &Game, command: &Command, _: &mut Io| {
+            heard.borrow_mut().push(format!("on_move {command}"));
+            Verdict::Allow
+        })),
+        after_move: Some(Box::new(
+            |_: &Game, command: &Command, several, _: &mut Io| {
+                heard
+                    .borrow_mut()
+                    .push(format!("after_move {command} {several}"));
+            },
+        )),
+        on_command: Some(Box::new(|_: &mut Game, command: &Command, _: &mut Io| {
+            heard.borrow_mut().push(format!("on_command {command}"));
+            Ok(false)
+        })),
+        on_board_render: Some(Box::new(|game: &Game| {
+            Some(format!("{} moves", game.moves().len()))
+        })),
+        on_game_end: Some(Box::new(|game: &Game, outcome, _: &mut Io| {
+            heard
+                .borrow_mut()
+                .push(format!("on_game_end {:?}", game.state()));
+            assert!(matches!(outcome, GameOutcome::Won(_)));
+        })),
+    };
+
+    let lines = ["0", "0", "f 1 0; c 0 1", "help", "c 1 1; c 2 2"];
+    let (outcome, shown) = run(&lines, hooks);
+    assert!(matches!(outcome, GameOutcome::Won(_)));
+    assert_eq!(
+        heard.into_inner(),
+        [
+            "on_move c 0 0",
+            "after_move c 0 0 false",
+            "on_move f 1 0",
+            "after_move f 1 0 true",
+            "on_move c 0 1",
+            "after_move c 0 1 true",
+            "on_command help",
+            "on_move c 1 1",
+            "after_move c 1 1 true",
+            "on_move c 2 2",
+            "after_move c 2 2 true",
+            "on_game_end Won",
+        ]
+    );
+
+    // under each board, and the loop's own answer to what the hook left
+    assert_eq!(shown.iter().filter(|line| *line == "board").count(), 4);
+    assert!(shown.contains(&String::from("3 moves")));
+    assert!(shown.contains(&render_commands()));
+    assert_eq!(shown.last().map(String::as_str), Some("revealed"));
+}
+
+#[test]
+fn a_vetoed_move_is_never_made() {
+    let ended = RefCell::new(None);
+    let hooks = Hooks {
+        // clearing a mine, which would lose the game
+        on_move: Some(Box::new(|game: &Game, command: &Command, _: &mut Io| {
+            let cell = match command {
+                Command::Clear(position) => game.board().get_cell(*position),
+                _ => None,
+            };
+            match cell.map(|cell| &cell.state) {
+                Some(CellState::Bomb { .. }) => Verdict::Veto,
+                _ => Verdict::Allow,
+            }
+        })),
+        on_game_end: Some(Box::new(|game: &Game, outcome, _: &mut Io| {
+            *ended.borrow_mut() = Some((game.state(), game.moves().len(), outcome));
+        })),
+        ..Hooks::default()
+    };
+
+    let (outcome, shown) = run(&["c 0 2; c 0 0", "1", "0"], hooks);
+    assert_eq!(outcome, GameOutcome::Aborted);
+    assert_eq!(
+        ended.into_inner(),
+        Some((GameState::Playing, 1, GameOutcome::Aborted))
+    );
+    // an unfinished game keeps its mines hidden
+    assert!(!shown.contains(&String::from("revealed")));
+}
+
+#[test]
+fn without_hooks_the_loop_plays_on_its_own() {
+    let lines = [
+        "share",
+        "c 0 0; c 0 1",
+        "pause",
+        "c 1 1",
+        "resume",
+        "1",
+        "1",
+        "c 2 2",
+    ];
+    let (outcome, shown) = run(&lines, Hooks::default());
+    assert!(matches!(outcome, GameOutcome::Won(_)));
+    assert!(shown.contains(&String::from("`share` isn't available in this game")));
+    assert!(shown.contains(&String::from("paused")));
+}
+
+#[test]
+fn a_seeded_game_is_won_by_clearing_every_safe_cell() {
+    let config = seeded(42);
+    let safe = safe_cells(config.game.board());
+    // as a row and then a column for the first, and commands after
+    let first = safe[0];
+    let mut lines = vec![first.row_index.to_string(), first.col_index.to_string()];
+    lines.extend(
+        safe[1..]
+            .iter()
+            .map(|at| format!("c {} {}", at.row_index, at.col_index)),
+    );
+
+    let (outcome, shown) = run_on(config, lines, Hooks::default());
+    let GameOutcome::Won(stats) = outcome else {
+        panic!("Clearing every safe cell should win, not {outcome:?}");
+    };
+    assert_eq!(stats.opened, 71);
+    assert_eq!(stats.mines, 10);
+    assert!(stats.moves <= safe.len());
+    assert_eq!(shown.last().map(String::as_str), Some("revealed"));
+}
+
+#[test]
+fn a_seeded_game_is_lost_on_its_first_mine() {
+    let config = seeded(42);
+    let mine = mines(config.game.board())[3];
+    let lines = [
+        format!("f {} {}", mine.row_index, mine.col_index),
+        format!("f {} {}", mine.row_index, mine.col_index),
+        format!("c {} {}", mine.row_index, mine.col_index),
+        String::from("c 0 0"),
+    ];
+
+    let (outcome, shown) = run_on(config, lines, Hooks::default());
+    let GameOutcome::Lost { hit, stats } = outcome else {
+        panic!("Clearing a mine should lose, not {outcome:?}");
+    };
+    assert_eq!(hit, mine);
+    assert_eq!(stats.moves, 3);
+    assert_eq!(shown.last().map(String::as_str), Some("revealed"));
+}
+
+#[test]
+fn the_same_seed_plays_the_same_game() {
+    let script = || ["c 4 4", "c 0 8", "f 1 1"].map(String::from);
+    let (first, first_shown) = run_on(seeded(7), script(), Hooks::default());
+    let (second, second_shown) = run_on(seeded(7), script(), Hooks::default());
+    assert_eq!(first, second);
+    assert_eq!(first_shown, second_shown);
+
+    let layout = |seed| seeded(seed).game.board().layout();
+    assert_eq!(layout(7), layout(7));
+    assert_ne!(layout(7), layout(8));
+}
@@ -0,0 +1,98 @@
+// debug dumps of games made by hand, parsed back into the games they came from
+mod common;
+
+use common::at;
+use minesweeper::board::Board;
+use minesweeper::command::Command;
+use minesweeper::dump::{debug_dump, parse_dump, DumpError};
+use minesweeper::game::{Game, GameState, WinCondition};
+use minesweeper::timer::SystemClock;
+use std::sync::Arc;
+
+// a mine in each top corner, and a wall of them along the bottom right
+const LAYOUT: &str = "*...*\n.....\n...**\n..*..";
+
+fn played() -> Game {
+    let board: Board = LAYOUT.parse().expect("The layout should parse");
+    let mut game = Game::new(board, 2)
+        .with_peeks(1)
+        .shielded(true)
+        .with_win_condition(WinCondition::FlagAll);
+    for command in [
+        Command::Clear(at(1, 0)),
+        Command::Flag(at(0, 0)),
+        Command::Flag(at(1, 1)),
+        Command::Undo,
+        Command::Question(at(0, 4)),
+        Command::Peek(at(3, 4)),
+        Command::Clear(at(3, 2)),
+    ] {
+        game.apply(0, command).expect("The move should be allowed");
+    }
+    game
+}
+
+#[test]
+fn a_dump_parses_back_into_the_same_game() {
+    let game = played();
+    let dump = debug_dump(&game, "layout");
+
+    let parsed = parse_dump(&dump, Arc::new(SystemClock)).expect("The dump should parse");
+    assert_eq!(parsed.code, "layout");
+    assert_eq!(debug_dump(&parsed.game, &parsed.code), dump);
+    assert_eq!(parsed.game.board().layout(), LAYOUT);
+    assert_eq!(parsed.game.state(), GameState::Playing);
+    assert_eq!(parsed.game.lives(), game.lives());
+    assert_eq!(parsed.game.peeks_left(), 0);
+}
+
+#[test]
+fn the_dump_shows_the_mines_and_every_number() {
+    let dump = debug_dump(&played(), "layout");
+
+    assert!(dump.starts_with("===== MINESWEEPER DEBUG DUMP"));
+    assert!(dump.contains("\nnumbers\n*101*\n11133\n012**\n01*32\n"));
+    assert!(dump.contains("\ncells\n^###?\n.####\n#####\n##!##\n"));
+    assert!(dump.ends_with("===== END OF DEBUG DUMP ====="));
+}
+
+// as it would be pasted into a bug report
+#[test]
+fn a_dump_is_found_among_other_text() {
+    let game = played();
+    let pasted = format!(
+        "It went wrong here:\n\n{}  \n\nThanks",
+        debug_dump(&game, "x")
+    );
+
+    let parsed = parse_dump(&pasted, Arc::new(SystemClock)).expect("The dump should be found");
+    assert_eq!(parsed.game.moves().len(), game.moves().len());
+}
+
+#[test]
+fn a_dump_that_doesnt_replay_is_rejected() {
+    let dump = debug_dump(&played(), "layout").replace("flags=1", "flags=2");
+
+    assert!(matches!(
+        parse_dump(&dump, Arc::new(SystemClock)),
+        Err(DumpError::Mismatch)
+    ));
+    assert!(matches!(
+        parse_dump("no dump here", Arc::new(SystemClock)),
+        Err(DumpError::Missing)
+    ));
+}
+
+// a dump mid-game gives the mines away, so the command is only there in a
+// build made for debugging
+#[cfg(not(feature = "debug"))]
+#[test]
+fn the_dump_command_needs_the_debug_feature() {
+    assert!("dump".parse::<Command>().is_err());
+}
+
+#[cfg(feature = "debug")]
+#[test]
+fn the_dump_command_parses_with_the_debug_feature() {
+    assert_eq!("dump".parse::<Command>().ok(), Some(Command::Dump));
+}
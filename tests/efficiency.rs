@@ -0,0 +1,161 @@
+// clicks against 3BV, as competitive players count them, for games played
+// out move by move
+mod common;
+
+use common::at;
+use minesweeper::analysis::{render_efficiency, three_bv, Efficiency, FlagScoring};
+use minesweeper::board::{Board, ChordError};
+use minesweeper::command::Command;
+use minesweeper::game::{Game, GameError, GameState};
+use std::time::Duration;
+
+// a mine in the corner, the 1s around it, and an opening with everything
+// else; 3BV is 2, for the opening and the 1 below the mine that it
+// doesn't reach
+const LAYOUT: &str = "*...\n....";
+
+fn game() -> Game {
+    let board: Board = LAYOUT.parse().expect("The layout should parse");
+    assert_eq!(three_bv(&board), 2);
+    Game::new(board, 1)
+}
+
+#[test]
+fn a_chord_that_opens_many_cells_is_one_click() {
+    let mut game = game();
+    let moves = [
+        Command::Clear(at(0, 1)),
+        // already open, so wasted
+        Command::Clear(at(0, 1)),
+        Command::Flag(at(0, 0)),
+        // a flag put on and taken off again is one flag, and two toggles
+        Command::Flag(at(1, 3)),
+        Command::Flag(at(1, 3)),
+        Command::Chord(at(0, 1)),
+    ];
+    for command in moves {
+        let _ = game.apply(0, command);
+    }
+    assert_eq!(game.state(), GameState::Won);
+
+    let efficiency = Efficiency::of(&game);
+    assert_eq!(
+        efficiency,
+        Efficiency {
+            three_bv: 2,
+            clicks: 2,
+            flags: 2,
+            toggles: 3,
+            wasted: 1
+        }
+    );
+    assert_eq!(efficiency.percent(FlagScoring::Ignore), Some(100.0));
+    assert_eq!(efficiency.per_second(Duration::from_secs(4)), Some(0.5));
+}
+
+// the same game under each way of counting its flags
+#[test]
+fn flags_count_as_the_scoring_says() {
+    let mut game = game();
+    let moves = [
+        Command::Flag(at(1, 1)),
+        Command::Flag(at(1, 1)),
+        Command::Flag(at(0, 0)),
+        Command::Flag(at(0, 0)),
+        Command::Flag(at(0, 0)),
+        Command::Clear(at(0, 3)),
+        Command::Clear(at(1, 0)),
+    ];
+    for command in moves {
+        game.apply(0, command).expect("Each move should be made");
+    }
+    assert_eq!(game.state(), GameState::Won);
+
+    let efficiency = Efficiency::of(&game);
+    let scored = [
+        FlagScoring::Ignore,
+        FlagScoring::Placements,
+        FlagScoring::Toggles,
+    ]
+    .map(|scoring| {
+        (
+            efficiency.total_clicks(scoring),
+            efficiency.percent(scoring),
+        )
+    });
+    assert_eq!(
+        scored,
+        [(2, Some(100.0)), (5, Some(40.0)), (7, Some(200.0 / 7.0))]
+    );
+
+    let elapsed = Duration::from_secs(1);
+    assert_eq!(
+        render_efficiency(&efficiency, elapsed, FlagScoring::Toggles),
+        "3BV 2 in 7 clicks (counting every flag put on or taken off), 0 wasted: 29% efficient, \
+         2.00 3BV/s"
+    );
+    for name in ["ignore", "placements", "toggles"] {
+        assert_eq!(
+            FlagScoring::from_name(name).map(FlagScoring::name),
+            Some(name)
+        );
+    }
+    assert_eq!(FlagScoring::from_name("both"), None);
+}
+
+// clears that open nothing aren't clicks, and chords with nothing left to
+// open aren't even moves
+#[test]
+fn only_moves_that_open_something_are_clicks() {
+    let mut game = game();
+    game.apply(0, Command::Clear(at(0, 3)))
+        .expect("The far corner should be safe");
+    for _ in 0..3 {
+        assert!(game.apply(0, Command::Clear(at(0, 2))).is_err());
+    }
+    game.apply(0, Command::Flag(at(0, 0)))
+        .expect("The mine should take a flag");
+    assert!(matches!(
+        game.apply(0, Command::Chord(at(0, 2))),
+        Err(GameError::Chord(ChordError::NothingToOpen))
+    ));
+    assert_eq!(
+        game.apply(0, Command::Chord(at(0, 1))).ok(),
+        Some(GameState::Won)
+    );
+    let efficiency = Efficiency::of(&game);
+    assert_eq!(
+        (efficiency.clicks, efficiency.flags, efficiency.wasted),
+        (2, 1, 3)
+    );
+
+    let mut lost = Game::new(LAYOUT.parse().expect("The layout should parse"), 1);
+    lost.apply(0, Command::Clear(at(0, 0)))
+        .expect("The mine should go off");
+    let efficiency = Efficiency::of(&lost);
+    assert_eq!(
+        (efficiency.clicks, efficiency.percent(FlagScoring::Ignore)),
+        (0, None)
+    );
+    assert_eq!(efficiency.per_second(Duration::ZERO), None);
+}
+
+#[test]
+fn efficiency_is_shown_as_a_percentage() {
+    let efficiency = Efficiency {
+        three_bv: 18,
+        clicks: 24,
+        flags: 6,
+        toggles: 10,
+        wasted: 2,
+    };
+    let elapsed = Duration::from_secs(8);
+    assert_eq!(
+        render_efficiency(&efficiency, elapsed, FlagScoring::Ignore),
+        "3BV 18 in 24 clicks (flags not counted), 2 wasted: 75% efficient, 2.25 3BV/s"
+    );
+    assert_eq!(
+        render_efficiency(&efficiency, elapsed, FlagScoring::Placements),
+        "3BV 18 in 30 clicks (counting flags put on), 2 wasted: 60% efficient, 2.25 3BV/s"
+    );
+}
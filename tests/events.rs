@@ -0,0 +1,108 @@
+// the events each kind of move sets off, exactly and in order, on boards
+// made by hand
+mod common;
+
+use common::{at, game_on};
+use minesweeper::command::Command;
+use minesweeper::game::{Game, GameEvent};
+
+// a mine in the bottom right corner, with the 1s around it
+const LAYOUT: &str = "...\n...\n..*";
+
+fn events(game: &mut Game, command: Command) -> Vec<GameEvent> {
+    game.apply(0, command).expect("The move should be allowed");
+    game.events().to_vec()
+}
+
+#[test]
+fn a_cascade_opens_every_cell_it_reaches_row_by_row() {
+    // up to the wall of mines, leaving the far side closed
+    let mut game = game_on(".*..\n.*..");
+    assert_eq!(
+        events(&mut game, Command::Clear(at(0, 3))),
+        [GameEvent::CellsOpened(vec![
+            at(0, 2),
+            at(0, 3),
+            at(1, 2),
+            at(1, 3)
+        ])]
+    );
+
+    // and the last of the safe cells, which wins
+    let mut game = game_on(LAYOUT);
+
+    assert_eq!(
+        events(&mut game, Command::Clear(at(0, 0))),
+        [
+            GameEvent::CellsOpened(vec![
+                at(0, 0),
+                at(0, 1),
+                at(0, 2),
+                at(1, 0),
+                at(1, 1),
+                at(1, 2),
+                at(2, 0),
+                at(2, 1),
+            ]),
+            GameEvent::GameWon,
+        ]
+    );
+}
+
+#[test]
+fn a_single_number_opens_alone() {
+    let mut game = game_on(LAYOUT);
+
+    assert_eq!(
+        events(&mut game, Command::Clear(at(1, 1))),
+        [GameEvent::CellsOpened(vec![at(1, 1)])]
+    );
+}
+
+#[test]
+fn a_flag_on_and_off_changes_the_mine_count_each_time() {
+    let mut game = game_on(LAYOUT);
+
+    assert_eq!(
+        events(&mut game, Command::Flag(at(2, 2))),
+        [
+            GameEvent::FlagToggled(at(2, 2), true),
+            GameEvent::MineCountChanged(0),
+        ]
+    );
+    assert_eq!(
+        events(&mut game, Command::Flag(at(2, 2))),
+        [
+            GameEvent::FlagToggled(at(2, 2), false),
+            GameEvent::MineCountChanged(1),
+        ]
+    );
+    // a wrong flag counts against the mines all the same
+    assert_eq!(
+        events(&mut game, Command::Flag(at(0, 0))),
+        [
+            GameEvent::FlagToggled(at(0, 0), true),
+            GameEvent::MineCountChanged(0),
+        ]
+    );
+}
+
+#[test]
+fn a_losing_clear_hits_the_mine_and_nothing_else() {
+    let mut game = game_on(LAYOUT);
+    events(&mut game, Command::Clear(at(1, 1)));
+
+    assert_eq!(
+        events(&mut game, Command::Clear(at(2, 2))),
+        [GameEvent::MineHit(at(2, 2)), GameEvent::MineCountChanged(0)]
+    );
+}
+
+#[test]
+fn a_turned_down_move_sets_off_nothing() {
+    let mut game = game_on(LAYOUT);
+    events(&mut game, Command::Clear(at(1, 1)));
+
+    assert!(game.apply(0, Command::Clear(at(1, 1))).is_err());
+    assert_eq!(game.events(), []);
+}
@@ -0,0 +1,77 @@
+// the exit code each way a game ends, for scripts to react to, in the
+// interactive and the batch modes alike
+mod common;
+
+use common::Home;
+
+const LAYOUT: [&str; 9] = [
+    "*........",
+    ".*...*...",
+    ".........",
+    "....*....",
+    "..**.....",
+    "..*......",
+    ".........",
+    "*..*.....",
+    "..*......",
+];
+
+// a clear of every safe cell on the seeded beginner's board
+fn winning_script() -> String {
+    let mut script = String::new();
+    for (row, cells) in LAYOUT.iter().enumerate() {
+        for (col, cell) in cells.chars().enumerate() {
+            if cell == '.' {
+                script += &format!("c {row} {col}\n");
+            }
+        }
+    }
+    script
+}
+
+fn exit_code(name: &str, args: &[&str], input: &str) -> Option<i32> {
+    let args = [&["--seed", "42", "--preset", "beginner", "--plain"], args].concat();
+    Home::new(name).run(&args, input).code
+}
+
+#[test]
+fn a_won_game_exits_with_0() {
+    assert_eq!(exit_code("exit_won", &[], &winning_script()), Some(0));
+    assert_eq!(
+        exit_code("exit_won_batch", &["--batch"], &winning_script()),
+        Some(0)
+    );
+}
+
+#[test]
+fn a_lost_game_exits_with_1() {
+    assert_eq!(exit_code("exit_lost", &[], "c 2 7\nc 1 1\n"), Some(1));
+    assert_eq!(
+        exit_code("exit_lost_batch", &["--batch"], "c 1 1\n"),
+        Some(1)
+    );
+}
+
+#[test]
+fn an_abandoned_game_exits_with_130() {
+    assert_eq!(exit_code("exit_aborted", &[], "c 2 7\n"), Some(130));
+    assert_eq!(exit_code("exit_aborted_batch", &["--batch"], ""), Some(130));
+}
+
+#[test]
+fn json_mode_ends_with_the_outcome() {
+    let home = Home::new("exit_json");
+    let args = ["--seed", "42", "--preset", "beginner", "--batch", "--json"];
+    let run = home.run(&args, "c 2 7\nc 1 1\n");
+
+    let last = run.stdout.lines().last().expect("There should be output");
+    assert!(
+        last.starts_with(r#"{"outcome":"lost","hit":{"row":1,"col":1},"moves":2,"opened":39,"#),
+        "{last}"
+    );
+    assert_eq!(run.code, Some(1));
+
+    let won = home.run(&args, &winning_script());
+    let last = won.stdout.lines().last().expect("There should be output");
+    assert!(last.starts_with(r#"{"outcome":"won","#), "{last}");
+}
@@ -0,0 +1,147 @@
+// boards at the edges of what the rules allow: one cell, no mines, a single
+// safe cell, and sizes too big to lay out
+mod common;
+
+use common::at;
+use minesweeper::board::{Board, CellPosition, CellState, Layout, Mines};
+use minesweeper::command::Command;
+use minesweeper::game::{Game, GameState};
+use minesweeper::rules::{Ruleset, RulesetError, MAX_CELLS};
+
+fn cells(board: &Board) -> Vec<CellPosition> {
+    (0..board.height())
+        .flat_map(|row| (0..board.width()).map(move |col| at(row, col)))
+        .collect()
+}
+
+fn safe_cells(board: &Board) -> Vec<CellPosition> {
+    cells(board)
+        .into_iter()
+        .filter(|&cell| {
+            let state = board.get_cell(cell).map(|cell| &cell.state);
+            matches!(state, Some(CellState::Safe { .. }))
+        })
+        .collect()
+}
+
+#[test]
+fn a_board_without_mines_is_won_by_any_single_clear() {
+    for (height, width) in [(1, 1), (1, 7), (7, 1), (4, 4), (5, 3)] {
+        let board = Board::from_bombs(vec![vec![false; width]; height]);
+        for cell in cells(&board) {
+            let mut game = Game::new(board.clone(), 1);
+            assert_eq!(game.state(), GameState::Playing);
+            assert_eq!(
+                game.apply(0, Command::Clear(cell)).ok(),
+                Some(GameState::Won)
+            );
+            assert_eq!(game.board().count_open(), height * width);
+        }
+    }
+}
+
+#[test]
+fn a_one_cell_board_can_be_made_and_won() {
+    let rules = Ruleset::builder()
+        .size(1, 1)
+        .mines(Mines::Count(0))
+        .build()
+        .expect("A single cell without a mine should be a board");
+    let mut game = Game::new(rules.board(5), rules.lives());
+    assert_eq!(
+        game.apply(0, Command::Clear(at(0, 0))).ok(),
+        Some(GameState::Won)
+    );
+
+    assert_eq!(
+        Ruleset::builder().size(1, 1).mines(Mines::Count(1)).build(),
+        Err(RulesetError::TooManyMines { mines: 1, cells: 1 })
+    );
+}
+
+// the one safe cell wins, anything else loses
+#[test]
+fn a_board_with_one_safe_cell_is_decided_by_the_first_clear() {
+    let rules = Ruleset::builder()
+        .size(3, 4)
+        .mines(Mines::Count(11))
+        .build()
+        .expect("One safe cell should be enough for a board");
+    for seed in 0..10 {
+        let board = rules.board(seed);
+        let safe = safe_cells(&board);
+        assert_eq!(safe.len(), 1);
+
+        for cell in cells(&board) {
+            let mut game = Game::new(board.clone(), 1);
+            let expected = match cell == safe[0] {
+                true => GameState::Won,
+                false => GameState::Lost,
+            };
+            assert_eq!(game.apply(0, Command::Clear(cell)).ok(), Some(expected));
+        }
+    }
+}
+
+// a density can round up to every cell, and a generous one can happen to
+// land on every cell of a small board; either way one cell is left to open
+#[test]
+fn a_board_always_has_a_cell_to_open() {
+    assert_eq!(
+        Ruleset::builder()
+            .size(2, 2)
+            .mines(Mines::Density(0.9))
+            .build(),
+        Err(RulesetError::TooManyMines { mines: 4, cells: 4 })
+    );
+    assert_eq!(
+        Ruleset::builder()
+            .size(2, 2)
+            .mines(Mines::Density(0.5))
+            .rocks(2)
+            .build(),
+        Err(RulesetError::TooManyRocks { rocks: 2, free: 2 })
+    );
+
+    // laid out without the rules to check them
+    let mut layout = Layout::new(Mines::Density(0.6));
+    for rocks in [0, 3] {
+        layout.rocks = rocks;
+        for seed in 0..200 {
+            let board = Board::generate(seed, 2, 2, &layout);
+            assert!(
+                !safe_cells(&board).is_empty(),
+                "Seed {seed} left nothing to open"
+            );
+            assert_eq!(Game::new(board, 1).state(), GameState::Playing);
+        }
+    }
+}
+
+#[test]
+fn boards_too_big_to_lay_out_are_turned_down() {
+    let huge = Ruleset::builder().size(10_000, 10_000).build();
+    assert_eq!(
+        huge,
+        Err(RulesetError::TooBig {
+            height: 10_000,
+            width: 10_000,
+            max: MAX_CELLS
+        })
+    );
+    assert!(huge.unwrap_err().to_string().contains("10000x10000"));
+
+    // more cells than there are numbers for
+    assert!(matches!(
+        Ruleset::builder().size(usize::MAX, 3).build(),
+        Err(RulesetError::TooBig { .. })
+    ));
+
+    // and the limit is the caller's to set
+    let limited = || Ruleset::builder().size(20, 20).max_cells(400);
+    assert!(limited().build().is_ok());
+    assert!(matches!(
+        limited().size(20, 21).build(),
+        Err(RulesetError::TooBig { max: 400, .. })
+    ));
+}
@@ -0,0 +1,66 @@
+// the C API from Rust: what it gives back for pointers it never handed out
+// or has already taken back, and for buffers too small for the board
+use minesweeper::ffi::{
+    ms_clear, ms_free, ms_new, ms_render_visible, MS_BUFFER_TOO_SMALL, MS_NO_GAME, MS_PLAYING,
+};
+use minesweeper::game::Game;
+use std::ffi::{c_char, CStr};
+
+fn render(game: *const Game, len: usize) -> (i32, Vec<u8>) {
+    let mut buf = vec![b'!'; len];
+    // SAFETY: `buf` has `len` bytes
+    let written = unsafe { ms_render_visible(game, buf.as_mut_ptr().cast::<c_char>(), len) };
+    (written, buf)
+}
+
+#[test]
+fn a_null_game_is_turned_down_everywhere() {
+    let null = std::ptr::null_mut();
+    assert_eq!(ms_clear(null, 0, 0), MS_NO_GAME);
+    assert_eq!(render(null, 64).0, MS_NO_GAME);
+    assert_eq!(ms_free(null), MS_NO_GAME);
+
+    let game = ms_new(3, 3, 1, 7);
+    // SAFETY: a null buffer is checked for before anything is written
+    let written = unsafe { ms_render_visible(game, std::ptr::null_mut(), 64) };
+    assert_eq!(written, MS_NO_GAME);
+    assert_eq!(ms_free(game), MS_PLAYING);
+}
+
+#[test]
+fn a_freed_game_is_not_followed() {
+    let game = ms_new(4, 4, 2, 1);
+    assert!(!game.is_null());
+    assert_eq!(ms_free(game), MS_PLAYING);
+
+    assert_eq!(ms_free(game), MS_NO_GAME);
+    assert_eq!(ms_clear(game, 0, 0), MS_NO_GAME);
+    assert_eq!(render(game, 64).0, MS_NO_GAME);
+}
+
+#[test]
+fn a_buffer_too_small_is_left_as_it_was() {
+    let game = ms_new(3, 2, 1, 3);
+    // "# # #\n# # #" and its NUL
+    let (written, buf) = render(game, 11);
+    assert_eq!(written, MS_BUFFER_TOO_SMALL);
+    assert!(buf.iter().all(|&byte| byte == b'!'));
+
+    let (written, buf) = render(game, 12);
+    assert_eq!(written, 11);
+    let rendered = CStr::from_bytes_until_nul(&buf).expect("The board should end in a NUL");
+    assert_eq!(rendered.to_str(), Ok("# # #\n# # #"));
+    assert_eq!(ms_free(game), MS_PLAYING);
+}
+
+#[test]
+fn numbers_the_ruleset_turns_down_give_no_game() {
+    assert!(ms_new(0, 5, 0, 1).is_null());
+    assert!(ms_new(3, 3, 9, 1).is_null());
+    // past MAX_CELLS
+    assert!(ms_new(2000, 2000, 10, 1).is_null());
+    // one safe cell is still a board
+    let game = ms_new(1, 2, 1, 1);
+    assert!(!game.is_null());
+    assert_eq!(ms_free(game), MS_PLAYING);
+}
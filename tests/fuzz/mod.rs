@@ -0,0 +1,48 @@
+// what the fuzz targets check of every input, kept here so that the inputs
+// they have found can be replayed as ordinary tests
+use minesweeper::board::Board;
+use minesweeper::command::{parse_line, Command};
+
+// whatever the line, parsing it returns rather than panics, and every command
+// it gives reads back the same from how it is shown
+pub fn check_command(data: &[u8]) {
+    let Ok(line) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(commands) = parse_line(line) else {
+        return;
+    };
+    for command in commands {
+        let shown = command.to_string();
+        let reread: Command = shown
+            .parse()
+            .unwrap_or_else(|e| panic!("{shown:?}, shown from {command:?}, should parse: {e}"));
+        assert_eq!(
+            reread, command,
+            "{shown:?} should read back as it was shown"
+        );
+    }
+}
+
+// whatever the text, parsing it as a layout returns rather than panics, and
+// any board it gives is consistent and writes back out as the same layout
+pub fn check_board(data: &[u8]) {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(board) = text.parse::<Board>() else {
+        return;
+    };
+    if let Err(e) = board.check_invariants() {
+        panic!("{text:?} parsed to an inconsistent board: {e}");
+    }
+    let layout = board.layout();
+    let reread: Board = layout
+        .parse()
+        .unwrap_or_else(|e| panic!("{layout:?}, written from {text:?}, should parse: {e}"));
+    assert_eq!(
+        reread.layout(),
+        layout,
+        "{text:?} should write back the same layout"
+    );
+}
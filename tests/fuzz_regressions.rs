@@ -0,0 +1,36 @@
+// replays the fuzz corpus, and every crash the fuzzer has found, through the
+// same checks as the fuzz targets; a crasher goes in fuzz/regressions/<target>
+// under any name, and is kept once it is fixed
+mod fuzz;
+
+use std::fs;
+use std::path::Path;
+
+fn replay(target: &str, check: fn(&[u8])) {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("fuzz");
+    for dir in ["corpus", "regressions"] {
+        let Ok(entries) = fs::read_dir(root.join(dir).join(target)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.file_name().is_some_and(|name| name == ".gitkeep") {
+                continue;
+            }
+            let data = fs::read(&path).unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+            if std::panic::catch_unwind(|| check(&data)).is_err() {
+                panic!("{} failed the {target} checks", path.display());
+            }
+        }
+    }
+}
+
+#[test]
+fn command_inputs_found_by_fuzzing() {
+    replay("command", fuzz::check_command);
+}
+
+#[test]
+fn board_inputs_found_by_fuzzing() {
+    replay("board", fuzz::check_board);
+}
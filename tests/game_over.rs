@@ -0,0 +1,71 @@
+// a game that has been won or lost keeps its board as it ended: every move
+// that would change it is turned down
+mod common;
+
+use common::{at, game_on};
+use minesweeper::command::Command;
+use minesweeper::game::{Game, GameError, GameState};
+
+// a mine in each top corner, with the 1s between them
+const LAYOUT: &str = "*.*\n...\n...";
+
+// every cell as it is, flags, questions and all
+fn cells(game: &Game) -> String {
+    format!("{:?}", game.board().rows().collect::<Vec<_>>())
+}
+
+fn assert_every_move_turned_down(mut game: Game) {
+    let before = cells(&game);
+    let moves = game.moves().len();
+    for command in [
+        Command::Clear(at(0, 1)),
+        Command::Clear(at(0, 0)),
+        Command::Flag(at(0, 2)),
+        Command::Flag(at(1, 1)),
+        Command::Chord(at(1, 1)),
+        Command::Undo,
+    ] {
+        let turned_down = game.apply(0, command.clone());
+        assert!(matches!(turned_down, Err(GameError::GameOver)), "{command}");
+        assert_eq!(cells(&game), before, "{command}");
+    }
+    assert_eq!(game.moves().len(), moves);
+}
+
+#[test]
+fn a_won_game_turns_down_every_move() {
+    let mut game = game_on(LAYOUT);
+    game.apply(0, Command::Clear(at(2, 1)))
+        .expect("The cell should open");
+    let state = game
+        .apply(0, Command::Clear(at(0, 1)))
+        .expect("The cell should open");
+    assert_eq!(state, GameState::Won);
+
+    assert_every_move_turned_down(game);
+}
+
+#[test]
+fn a_lost_game_turns_down_every_move() {
+    let mut game = game_on(LAYOUT);
+    game.apply(0, Command::Flag(at(0, 2)))
+        .expect("The flag should go on");
+    let state = game
+        .apply(0, Command::Clear(at(0, 0)))
+        .expect("The mine should go off");
+    assert_eq!(state, GameState::Lost);
+
+    assert_every_move_turned_down(game);
+}
+
+#[test]
+fn a_finished_board_can_still_be_looked_at() {
+    let mut game = game_on(LAYOUT);
+    game.apply(0, Command::Clear(at(0, 0)))
+        .expect("The mine should go off");
+
+    let before = cells(&game);
+    assert!(game.apply(0, Command::Share).is_ok());
+    assert!(game.apply(0, Command::Remaining).is_ok());
+    assert_eq!(cells(&game), before);
+}
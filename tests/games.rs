@@ -0,0 +1,1036 @@
+// whole games from fixed seeds and scripted moves, through the library and
+// through the binary, each checked against a reviewed snapshot of how it went
+// in tests/snapshots; run with UPDATE_SNAPSHOTS=1 to write the snapshots
+// afresh after a change that is meant to alter them, then review the diff
+mod common;
+
+use common::{assert_snapshot, mines, redact_times, Home};
+use minesweeper::board::{Board, CellPosition, CellState, Mines};
+use minesweeper::command::{parse_line, Command};
+use minesweeper::dump::parse_dump;
+use minesweeper::game::{Game, GameEvent, GameState};
+use minesweeper::json::{self, Value};
+use minesweeper::layout::display_width;
+use minesweeper::solver::deduce;
+use minesweeper::timer::SystemClock;
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command as Process, Stdio};
+use std::sync::Arc;
+
+const SEED: u64 = 42;
+
+// a zero on the seeded board, which opens a good part of it
+const START: CellPosition = CellPosition {
+    row_index: 2,
+    col_index: 7,
+};
+
+fn beginner() -> Game {
+    Game::new(Board::from_seed(SEED, 9, 9, Mines::Count(10)), 1)
+}
+
+fn cells(board: &Board) -> Vec<CellPosition> {
+    board.rows().flatten().map(|cell| cell.position).collect()
+}
+
+fn is_closed_safe(board: &Board, position: CellPosition) -> bool {
+    board
+        .get_cell(position)
+        .is_some_and(|cell| matches!(cell.state, CellState::Safe { open: false, .. }))
+}
+
+// every command with what it led to, then the board as it ended
+struct Transcript {
+    game: Game,
+    lines: Vec<String>,
+}
+
+impl Transcript {
+    fn new(game: Game) -> Self {
+        Transcript {
+            game,
+            lines: vec![],
+        }
+    }
+
+    fn play(&mut self, command: Command) -> Option<GameState> {
+        let result = self.game.apply(0, command.clone());
+        self.lines.push(match &result {
+            Ok(state) => format!("{command}: {}", state.name()),
+            Err(e) => format!("{command}: {e}"),
+        });
+        result.ok()
+    }
+
+    // a line as a player would type it, which might not parse
+    fn type_line(&mut self, line: &str) {
+        match parse_line(line) {
+            Ok(commands) => {
+                for command in commands {
+                    self.play(command);
+                }
+            }
+            Err(e) => self.lines.push(format!("{line:?}: {e}")),
+        }
+    }
+
+    fn finish(self) -> String {
+        format!("{}\n\n{}\n", self.lines.join("\n"), self.game.board())
+    }
+}
+
+// the game as the binary plays it, with its data and config kept apart from
+// the user's, and every time shown as --:-- so that the output is the same
+// however long the run took
+fn run_binary(name: &str, args: &[&str], input: &str) -> (String, Option<i32>) {
+    run_configured(name, "", args, input)
+}
+
+// likewise, with `config` as the config file
+fn run_configured(name: &str, config: &str, args: &[&str], input: &str) -> (String, Option<i32>) {
+    run_with_env(name, config, &[], args, input)
+}
+
+// likewise, with `vars` set in its environment
+fn run_with_env(
+    name: &str,
+    config: &str,
+    vars: &[(&str, &str)],
+    args: &[&str],
+    input: &str,
+) -> (String, Option<i32>) {
+    let home = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&home);
+    let config_dir = home.join("minesweeper-cli");
+    fs::create_dir_all(&config_dir).expect("The test's config directory should be made");
+    fs::write(config_dir.join("config.toml"), config).expect("The test's config should be written");
+    run_in(&home, vars, args, input)
+}
+
+// another run after one of the above, keeping what the last one left behind
+fn run_again(name: &str, args: &[&str], input: &str) -> (String, Option<i32>) {
+    run_in(
+        &PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name),
+        &[],
+        args,
+        input,
+    )
+}
+
+fn run_in(home: &Path, vars: &[(&str, &str)], args: &[&str], input: &str) -> (String, Option<i32>) {
+    let mut child = Process::new(env!("CARGO_BIN_EXE_minesweeper"))
+        .args(["--seed", "42", "--preset", "beginner", "--lang", "en"])
+        .args(args)
+        .env("XDG_DATA_HOME", home)
+        .env("XDG_CONFIG_HOME", home)
+        .envs(vars.iter().copied())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("The binary should start");
+    child
+        .stdin
+        .take()
+        .expect("The binary's stdin should be piped")
+        .write_all(input.as_bytes())
+        .expect("The binary should read its input");
+    let output = child.wait_with_output().expect("The binary should finish");
+
+    let stdout = String::from_utf8(output.stdout).expect("The output should be UTF-8");
+    (redact_times(&stdout), output.status.code())
+}
+
+#[test]
+fn a_won_game() {
+    let mut transcript = Transcript::new(beginner());
+    transcript.play(Command::Clear(START));
+    for position in cells(transcript.game.initial_board()) {
+        if is_closed_safe(transcript.game.board(), position) {
+            transcript.play(Command::Clear(position));
+        }
+    }
+
+    assert_eq!(transcript.game.state(), GameState::Won);
+    assert_snapshot("won", &transcript.finish());
+}
+
+#[test]
+fn a_lost_game() {
+    let mut transcript = Transcript::new(beginner());
+    transcript.play(Command::Clear(START));
+    let mine = mines(transcript.game.board())[0];
+    transcript.play(Command::Flag(mine));
+    transcript.play(Command::Flag(mine));
+    transcript.play(Command::Clear(mine));
+
+    assert_eq!(transcript.game.state(), GameState::Lost);
+    assert_snapshot("lost", &transcript.finish());
+}
+
+#[test]
+fn a_game_full_of_invalid_input() {
+    let mut transcript = Transcript::new(beginner());
+    for line in [
+        "",
+        "dig 1 1",
+        "c 1",
+        "c one 1",
+        "c 1 1 1",
+        "c 99 99",
+        "u",
+        "resume",
+        "c rect 4 4 1 1",
+        "export gif",
+        "c 2 7",
+        "c 2 7",
+        "f 2 7",
+        "ch 0 8",
+        "c 2 8; c 99 0",
+        ";;",
+    ] {
+        transcript.type_line(line);
+    }
+
+    assert_eq!(transcript.game.state(), GameState::Playing);
+    assert_snapshot("invalid_input", &transcript.finish());
+}
+
+// flags every mine the numbers prove, then chords around them, until the
+// board is clear; nothing is ever cleared directly but the first cell
+#[test]
+fn a_game_of_flags_and_chords() {
+    let mut transcript = Transcript::new(beginner());
+    transcript.play(Command::Clear(START));
+
+    while transcript.game.state() == GameState::Playing {
+        let board = transcript.game.board().clone();
+        let flagged = |position: CellPosition| {
+            board.get_cell(position).is_some_and(|cell| {
+                matches!(
+                    cell.state,
+                    CellState::Bomb { flagged: true, .. } | CellState::Safe { flagged: true, .. }
+                )
+            })
+        };
+        let mut progress = false;
+        for mine in deduce(&board, &[]).mines {
+            if !flagged(mine) {
+                progress |= transcript.play(Command::Flag(mine)).is_some();
+            }
+        }
+        for position in cells(&board) {
+            let board = transcript.game.board();
+            let ready = board
+                .chord_targets(position)
+                .is_ok_and(|targets| !targets.is_empty());
+            if ready && transcript.game.state() == GameState::Playing {
+                progress |= transcript.play(Command::Chord(position)).is_some();
+            }
+        }
+        assert!(
+            progress,
+            "The seeded board should be clear without guessing"
+        );
+    }
+
+    assert_eq!(transcript.game.state(), GameState::Won);
+    assert_snapshot("flags_and_chords", &transcript.finish());
+}
+
+#[test]
+fn a_batch_game_through_the_binary() {
+    let input = "c 2 7\nf 1 5\nbogus\nch 0 6\nc 0 0\n";
+    let (output, code) = run_binary("batch", &["--batch"], input);
+
+    assert_eq!(code, Some(1), "A lost game should exit with 1");
+    assert_snapshot("batch", &output);
+}
+
+// prompted for each move, as a player at the terminal is, with mistakes
+// along the way
+#[test]
+fn an_interactive_game_through_the_binary() {
+    let script = "c 2 7\nx\nc 1\nc 99 99\nc 2 7\nf 1 5; f 3 4\nch 2 5\nu\n";
+    let (output, code) = run_binary(
+        "interactive",
+        &["--no-color", "--plain", "--no-summary"],
+        script,
+    );
+
+    assert_eq!(
+        code,
+        Some(130),
+        "A game left at the end of input should exit with 130"
+    );
+    assert_snapshot("interactive", &output);
+}
+
+// a row and a column on lines of their own, where a mistake in the row asks
+// for the row again and a mistake in the column asks only for the column
+#[test]
+fn a_mistyped_field_is_asked_for_again() {
+    let script = "9\n2\nseven\n9\n7\n8\n0\n";
+    let (output, code) = run_binary("fields", &["--no-color", "--plain", "--no-summary"], script);
+
+    assert_eq!(
+        code,
+        Some(130),
+        "A game left at the end of input should exit with 130"
+    );
+    assert_snapshot("fields", &output);
+}
+
+// with both turned on, every cell is named as it is acted on, and clearing
+// one next to a flag waits for a yes; "c E4" is the cell at (3,4)
+#[test]
+fn moves_are_echoed_and_confirmed_next_to_flags() {
+    let config = "echo_moves = true\nconfirm_near_flags = true\n";
+    let script = "c 2 7\nf 1 5\nc 0 5\nn\nc F1\ny\nq C1\n";
+    let (output, code) = run_configured(
+        "confirm",
+        config,
+        &["--no-color", "--plain", "--no-summary"],
+        script,
+    );
+
+    assert_eq!(
+        code,
+        Some(130),
+        "A game left at the end of input should exit with 130"
+    );
+    assert_snapshot("confirm", &output);
+}
+
+// each board drawn in `output`: the lines between a status line and the
+// divider under them
+fn board_frames(output: &str) -> Vec<Vec<&str>> {
+    let mut frames = vec![];
+    let mut lines = output.lines();
+    while lines.by_ref().any(|line| line.starts_with("Mines:")) {
+        frames.push(
+            lines
+                .by_ref()
+                .take_while(|&line| line != "------")
+                .collect(),
+        );
+    }
+    frames
+}
+
+// a game on a `height` by `width` board in `theme`, with a number, a flag, a
+// note and a question mark on it, checked against its snapshot; every row of
+// every board drawn has to be as wide as the others. Not --plain, which
+// would only ever draw ascii
+fn assert_aligned(theme: &str, (height, width, mines): (usize, usize, usize), script: &str) {
+    let name = format!("layout_{theme}_{height}x{width}");
+    let config = format!("theme = \"{theme}\"\n");
+    let (height, width, mines) = (height.to_string(), width.to_string(), mines.to_string());
+    let args = [
+        "--no-color",
+        "--no-summary",
+        "--height",
+        &height,
+        "--width",
+        &width,
+        "--mines",
+        &mines,
+    ];
+    let (output, code) = run_configured(&name, &config, &args, script);
+    assert_eq!(
+        code,
+        Some(130),
+        "A game left at the end of input should exit with 130"
+    );
+
+    let frames = board_frames(&output);
+    assert!(
+        frames.len() > 1,
+        "{name} should draw the board after each move"
+    );
+    for frame in frames {
+        let widths: Vec<usize> = frame.iter().map(|line| display_width(line)).collect();
+        assert!(
+            widths.windows(2).all(|pair| pair[0] == pair[1]),
+            "{name}: {widths:?}"
+        );
+    }
+    assert_snapshot(&name, &output);
+}
+
+#[test]
+fn boards_line_up_in_every_theme_and_size() {
+    let small = "c 4 0\nf 0 3\nnote 0 0 a\nq 0 4\n";
+    let medium = "c 3 0\nf 0 3\nnote 9 0 b\nq 9 9\n";
+    let large = "c 19 20\nf 0 0\nnote 25 29 c\nq 0 29\n";
+    for theme in ["ascii", "emoji"] {
+        assert_aligned(theme, (5, 5, 3), small);
+        assert_aligned(theme, (10, 10, 15), medium);
+        assert_aligned(theme, (26, 30, 99), large);
+    }
+}
+
+#[test]
+fn notes_are_drawn_on_closed_cells_through_the_binary() {
+    let script = "c 2 7\nnote 0 5 A\nnote 0 5 B\nnote 0 4 C\nnote 0 4 -\nnote 2 7 D\nc 0 5\n";
+    let (output, code) = run_binary("notes", &["--no-color", "--plain", "--no-summary"], script);
+
+    assert_eq!(
+        code,
+        Some(130),
+        "A game left at the end of input should exit with 130"
+    );
+    assert_snapshot("notes", &output);
+}
+
+// the undone question mark keeps its number, and the moves after it go on
+// from there
+#[test]
+fn the_move_log_keeps_its_numbers_through_an_undo() {
+    let script = "moves\nc 2 7\nf 1 5\nq 0 0\nu\nf 3 4\nf 4 2\nc 0 5\nmoves\nmoves all\n";
+    let (output, code) = run_binary("moves", &["--no-color", "--plain", "--no-summary"], script);
+
+    assert_eq!(
+        code,
+        Some(130),
+        "A game left at the end of input should exit with 130"
+    );
+    assert_snapshot("moves", &output);
+}
+
+// the dump after the game is found in the rest of the output, and replays
+// to the game it was printed for
+#[test]
+fn a_debug_dump_printed_at_the_end_parses_back() {
+    let script = "c 2 7\nf 0 0\nu\nf 1 1\nc 0 5\n";
+    let args = ["--no-color", "--plain", "--no-summary", "--debug-dump"];
+    let (output, code) = run_binary("debug_dump", &args, script);
+
+    assert_eq!(
+        code,
+        Some(130),
+        "A game left at the end of input should exit with 130"
+    );
+    let dump =
+        parse_dump(&output, Arc::new(SystemClock)).expect("The output should hold a debug dump");
+    assert_eq!(dump.code, "#42");
+    assert_eq!(dump.game.moves().len(), 3);
+    assert_eq!(dump.game.board().count_flags(), 1);
+}
+
+// the key holds the whole board before the first move, and the terminal
+// shows none of it
+#[test]
+fn an_answer_key_is_written_and_never_shown() {
+    let home = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("answer_key");
+    let key = home.join("key.txt");
+    let args = ["--no-color", "--plain", "--no-summary", "--answer-key"];
+    let args = [
+        &args[..],
+        &[key.to_str().expect("The path should be UTF-8")],
+    ]
+    .concat();
+    let (output, code) = run_binary(
+        "answer_key",
+        &args,
+        "c 2 7
+f 0 0
+",
+    );
+
+    assert_eq!(code, Some(130));
+    let board = beginner().board().clone();
+    let expected: Vec<String> = board
+        .rows()
+        .map(|row| {
+            let cells = row.iter().map(|cell| match cell.state {
+                CellState::Bomb { .. } => String::from("@"),
+                _ => cell.bombs_around.to_string(),
+            });
+            cells.collect::<Vec<String>>().join(" ")
+        })
+        .collect();
+    let written = fs::read_to_string(&key).expect("The answer key should be written");
+    let mut lines = written.lines();
+    assert_eq!(
+        lines.next(),
+        Some("Minesweeper #42, 9x9 with 10 mines: the answer key")
+    );
+    assert_eq!(lines.collect::<Vec<&str>>(), expected);
+
+    assert!(output.contains("The answer key is in"), "{output}");
+    assert!(!output.contains('@'), "{output}");
+    for line in &expected {
+        assert!(!output.contains(line.as_str()), "{output}");
+    }
+
+    // a dump would print what the key keeps back, so the two don't mix
+    let _ = fs::remove_file(&key);
+    let args = [&args[..], &["--debug-dump"]].concat();
+    let (_, code) = run_again("answer_key", &args, "");
+    assert_eq!(code, Some(2));
+    assert!(!key.exists());
+}
+
+// nor do the modes that would play on without writing one
+#[test]
+fn an_answer_key_is_turned_down_where_it_would_not_be_written() {
+    let home = Home::new("answer_key_modes");
+    let key = home.path.join("key.txt");
+    let key = key.to_str().expect("The path should be UTF-8");
+    let message = "--answer-key writes the board of a game played at the terminal, so it only \
+                   goes with a single, coop, daily or weekly game, and not with --batch or --serve";
+    let cases: [&[&str]; 8] = [
+        &["--batch"],
+        &["--serve", "0"],
+        &["race"],
+        &["tutorial"],
+        &["campaign"],
+        &["gauntlet"],
+        &["endless"],
+        &["race", "--serve", "0"],
+    ];
+    for args in cases {
+        let run = home.run(&[args, &["--answer-key", key]].concat(), "c 0 0\n");
+        assert_eq!(run.stderr.trim(), message, "{args:?}");
+        assert_eq!(run.code, Some(2), "{args:?}");
+        assert!(!home.path.join("key.txt").exists(), "{args:?}");
+    }
+}
+
+// a panic partway through leaves the game saved, with a crash report beside
+// it that has the moves made before it; only a build with `crash-test`
+// can be made to panic
+#[cfg(feature = "crash-test")]
+#[test]
+fn a_panic_mid_game_writes_a_crash_report() {
+    let vars = [("MINESWEEPER_TEST_PANIC_AFTER", "2")];
+    let args = ["--no-color", "--plain", "--no-summary"];
+    let (_, code) = run_with_env("crash", "", &vars, &args, "c 2 7\nf 0 0\nc 0 5\n");
+    assert_eq!(code, Some(101), "A crash should exit as a panic does");
+
+    let data = PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+        .join("crash")
+        .join("minesweeper-cli");
+    let saved = fs::read_to_string(data.join("autosave.txt")).expect("The game should be saved");
+    assert!(saved.contains("f 0 0"));
+    let report = fs::read_dir(&data)
+        .expect("The data directory should be there")
+        .flatten()
+        .find(|entry| entry.file_name().to_string_lossy().starts_with("crash-"))
+        .expect("A crash report should be written");
+    let report = fs::read_to_string(report.path()).expect("The crash report should be readable");
+    assert!(report.contains("MINESWEEPER_TEST_PANIC_AFTER was reached"));
+    let dump = parse_dump(&report, Arc::new(SystemClock))
+        .expect("The crash report should hold a debug dump");
+    assert_eq!(dump.game.moves().len(), 2);
+}
+
+// a game that ends as usual leaves neither behind
+#[test]
+fn a_clean_exit_writes_no_crash_report() {
+    let args = ["--no-color", "--plain", "--no-summary"];
+    let (_, code) = run_binary("no_crash", &args, "c 2 7\nc 0 0\n");
+    assert_eq!(code, Some(1), "A lost game should exit with 1");
+
+    let data = PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+        .join("no_crash")
+        .join("minesweeper-cli");
+    let files: Vec<String> = fs::read_dir(&data)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect();
+    assert!(
+        files.iter().all(|name| !name.starts_with("crash-")),
+        "{files:?}"
+    );
+}
+
+// a saved game comes back under the rules it was started with, whatever the
+// rules of the run that loads it
+#[test]
+fn a_loaded_game_keeps_its_rules() {
+    let args = [
+        "--no-color",
+        "--plain",
+        "--no-summary",
+        "--peeks",
+        "2",
+        "--shields",
+        "--flag-win",
+    ];
+    let (_, code) = run_binary("rules_saved", &args, "c 2 7\n");
+    assert_eq!(
+        code,
+        Some(130),
+        "A game left at the end of input should exit with 130"
+    );
+
+    let data = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("rules_saved");
+    let save = data.with_extension("txt");
+    fs::copy(data.join("minesweeper-cli").join("autosave.txt"), &save)
+        .expect("The game should be saved");
+    let save = save.to_string_lossy();
+    let args = [
+        "--no-color",
+        "--plain",
+        "--no-summary",
+        "--debug-dump",
+        "--load",
+        &save,
+    ];
+    let (output, _) = run_binary("rules_loaded", &args, "");
+
+    let dump =
+        parse_dump(&output, Arc::new(SystemClock)).expect("The output should hold a debug dump");
+    let rules = dump.game.ruleset();
+    assert_eq!(rules.key(), "peeks=2,shields,flag_win");
+    assert_eq!((rules.height(), rules.width()), (9, 9));
+    assert_eq!(dump.game.moves().len(), 1);
+}
+
+// with an assist, the status line keeps the count `remaining` gives
+#[test]
+fn an_assist_counts_what_can_be_proven_every_turn() {
+    let args = ["--no-color", "--plain", "--no-summary"];
+    let config = "assist = \"hints\"\n";
+    let (output, _) = run_configured("remaining", config, &args, "c 2 7\nremaining\n");
+
+    assert!(output.contains("Provable: 0 safe, 0 mines"));
+    assert!(output.contains("The numbers prove 3 closed cells safe and 2 unflagged cells mines"));
+    assert!(output.contains("Provable: 3 safe, 2 mines"));
+
+    let (output, _) = run_binary("no_assist", &args, "c 2 7\n");
+    assert!(!output.contains("Provable"));
+}
+
+// a finished game's history record says where it was first clicked and
+// what that did
+#[test]
+fn the_history_keeps_each_game_s_first_move() {
+    let args = ["--no-color", "--plain", "--no-summary"];
+    let (_, code) = run_binary("first_move", &args, "c 2 7\nc 0 0\n");
+    assert_eq!(code, Some(1), "Hitting the mine should lose the game");
+
+    let history = PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+        .join("first_move")
+        .join("minesweeper-cli")
+        .join("history.jsonl");
+    let history = fs::read_to_string(history).expect("The game should be in the history");
+    assert!(history.contains("\"first\":{\"row\":2,\"col\":7,\"opened\":\"cascade\"}"));
+}
+
+// the solver plays the rest of this board out, and turning its moves down
+// leaves the game as it was before `watch`
+#[test]
+fn watching_the_solver_can_be_taken_back() {
+    let args = ["--no-color", "--plain", "--no-summary"];
+    let config = "watch_delay_ms = 0\n";
+    let (output, code) = run_configured("watch_undone", config, &args, "c 2 7\nwatch\nn\n");
+
+    assert!(output.contains("Solver: c 8 1  [single-point: (7,1) satisfied]"));
+    assert!(output.contains("The solver finished the game in 29 moves"));
+    assert!(output.contains("Back to where you were before watching"));
+    let after = output
+        .split("Back to where")
+        .nth(1)
+        .expect("The game should go on");
+    assert!(after.contains("Moves: 1"));
+    assert_eq!(
+        code,
+        Some(130),
+        "A game left at the end of input should exit with 130"
+    );
+}
+
+#[test]
+fn the_solver_s_moves_can_be_kept_and_mark_the_game_assisted() {
+    let args = ["--no-color", "--plain", "--no-summary"];
+    let config = "watch_delay_ms = 0\n";
+    let (output, code) = run_configured("watch_kept", config, &args, "c 2 7\nwatch\ny\n");
+
+    assert!(output.contains("Kept the solver's moves"));
+    assert_eq!(code, Some(0), "The solver should have won the game");
+
+    let history = PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+        .join("watch_kept")
+        .join("minesweeper-cli")
+        .join("history.jsonl");
+    let history = fs::read_to_string(history).expect("The game should be in the history");
+    assert!(history.contains("\"assisted\":true"));
+}
+
+// the efficiency says how its flags were counted, and the record keeps it
+#[test]
+fn a_won_game_keeps_how_its_flags_were_scored() {
+    let args = ["--no-color", "--plain", "--no-summary"];
+    let config = "watch_delay_ms = 0\nflag_scoring = \"toggles\"\n";
+    let (output, code) = run_configured("flag_scoring", config, &args, "c 2 7\nwatch\ny\n");
+
+    assert!(output.contains("clicks (counting every flag put on or taken off)"));
+    assert_eq!(code, Some(0), "The solver should have won the game");
+
+    let history = PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+        .join("flag_scoring")
+        .join("minesweeper-cli")
+        .join("history.jsonl");
+    let history = fs::read_to_string(history).expect("The game should be in the history");
+    assert!(history.contains("\"flag_scoring\":\"toggles\""));
+}
+
+// the layouts are drawn the same way for the same position, so the estimate
+// is the same every run
+#[test]
+fn winprob_estimates_the_chance_of_winning_without_guessing() {
+    let args = ["--no-color", "--plain", "--no-summary"];
+    let (output, _) = run_binary("winprob", &args, "c 2 7\nwinprob\n");
+
+    assert!(output.contains(
+        "Winnable without guessing: about 70.2% (±4.5% at 95% confidence, from 400 sampled layouts"
+    ));
+}
+
+// colour, emoji, the banner and the bell all asked for, and --plain
+// overriding every one of them through to the winning screen
+#[test]
+fn plain_output_has_no_escapes_from_start_to_finish() {
+    let config = "color = true\ntheme = \"emoji\"\nbanner = \"block\"\nbell_on_win = true\n\
+                  watch_delay_ms = 0\n";
+    let script = "c 2 7\nf 0 0\nf 0 0\nboardinfo\nmoves\nwatch\ny\n";
+    let (output, code) = run_configured("plain", config, &["--plain"], script);
+
+    assert_eq!(code, Some(0), "The solver should have won the game");
+    assert!(output.contains("Kept the solver's moves"));
+    assert!(output.contains("Flag placed at (0,0); 9 mines unaccounted for"));
+    assert!(!output.contains('\x1B'), "{output}");
+    assert!(!output.contains('\x07'));
+    let bytes = |output: &str| output.bytes().filter(|byte| !byte.is_ascii()).count();
+    assert_eq!(bytes(&output), 0, "{output}");
+
+    // and the list of achievements
+    let listed = Home::new("plain_achievements").run(&["achievements", "--plain"], "");
+    assert!(
+        listed.stdout.contains("[ ] First win: "),
+        "{}",
+        listed.stdout
+    );
+    assert_eq!(bytes(&listed.stdout), 0, "{}", listed.stdout);
+}
+
+// without --plain, output that isn't a terminal still gets no colour or
+// banner, but keeps the theme
+#[test]
+fn piped_output_is_left_uncoloured() {
+    let config = "color = true\ntheme = \"emoji\"\nbanner = \"block\"\nwatch_delay_ms = 0\n";
+    let (output, code) = run_configured("piped", config, &[], "c 2 7\nwatch\ny\n");
+
+    assert_eq!(code, Some(0), "The solver should have won the game");
+    assert!(!output.contains('\x1B'), "{output}");
+    assert!(!output.is_ascii(), "The emoji theme should be kept");
+}
+
+// the cells in a move's `changes`, as (row, col, how it looks now)
+fn changes(line: &str) -> Vec<(usize, usize, String)> {
+    let value = json::parse(line).expect("Each line should be JSON");
+    let Some(Value::Array(changes)) = value.get("changes") else {
+        panic!("{line} should list its changes");
+    };
+    changes
+        .iter()
+        .map(|change| match change {
+            Value::Array(change) => (
+                change[0].as_u64().expect("The row should be a number") as usize,
+                change[1].as_u64().expect("The column should be a number") as usize,
+                change[2]
+                    .as_str()
+                    .expect("The cell should be a string")
+                    .to_string(),
+            ),
+            _ => panic!("{change} should be a [row, col, state] tuple"),
+        })
+        .collect()
+}
+
+// the cascade's diff is the cells the library says it opened, and nothing
+// else; an undo and `full` send the whole board instead
+#[test]
+fn batch_diffs_list_only_the_cells_a_move_changed() {
+    let args = ["--batch", "--json", "--diff"];
+    let (output, code) = run_binary("diff", &args, "c 2 7\nf 0 0\nfull\nu\n");
+    assert_eq!(
+        code,
+        Some(130),
+        "A game left at the end of input should exit with 130"
+    );
+    let lines: Vec<&str> = output.lines().collect();
+
+    let mut game = beginner();
+    game.apply(
+        0,
+        Command::Clear(CellPosition {
+            row_index: 2,
+            col_index: 7,
+        }),
+    )
+    .expect("The cell should be safe");
+    let Some(GameEvent::CellsOpened(opened)) = game.events().first() else {
+        panic!("The clear should open cells");
+    };
+    let mut opened: Vec<(usize, usize, String)> = opened
+        .iter()
+        .map(|&at| {
+            let cell = game
+                .board()
+                .get_cell(at)
+                .expect("An opened cell should be on the board");
+            (at.row_index, at.col_index, cell.bombs_around.to_string())
+        })
+        .collect();
+    opened.sort();
+    assert!(opened.len() > 10, "The clear should cascade");
+    assert_eq!(changes(lines[0]), opened);
+
+    assert_eq!(changes(lines[1]), [(0, 0, String::from("^"))]);
+    let counters = json::parse(lines[1]).expect("Each line should be JSON");
+    let counters = counters
+        .get("counters")
+        .expect("The counters should be sent");
+    assert_eq!(counters.get("flags").and_then(Value::as_u64), Some(1));
+    assert_eq!(
+        counters.get("open").and_then(Value::as_u64),
+        Some(opened.len() as u64)
+    );
+
+    for line in &lines[2..4] {
+        let value = json::parse(line).expect("Each line should be JSON");
+        assert!(
+            value.get("changes").is_none() && value.get("board").is_some(),
+            "{line}"
+        );
+    }
+}
+
+// every line a client is sent, whether each move's in full or its changes,
+// through to the mine that ends the game; only the flagged mine and the one
+// set off are ever named, and the rest are closed on every board
+#[test]
+fn json_clients_are_never_sent_a_mine_they_havent_found() {
+    let board = beginner().board().clone();
+    let mines = mines(&board);
+    let (flagged, hit) = (mines[0], mines[1]);
+    let script = format!(
+        "c 2 7\nf {} {}\nfull\nshare\nboardinfo\nremaining\nc {} {}\n",
+        flagged.row_index, flagged.col_index, hit.row_index, hit.col_index
+    );
+
+    for args in [&["--batch", "--json"][..], &["--batch", "--json", "--diff"]] {
+        let (output, code) = run_binary("hidden_mines", args, &script);
+        assert_eq!(code, Some(1), "Setting off the mine should lose the game");
+        for line in output.lines() {
+            let value = json::parse(line).expect("Each line should be JSON");
+            let rows: Vec<&str> = match value.get("board") {
+                Some(Value::Array(rows)) => rows.iter().filter_map(Value::as_str).collect(),
+                _ => vec![],
+            };
+            for &mine in &mines[2..] {
+                let (row, col) = (mine.row_index, mine.col_index);
+                assert!(
+                    !line.contains(&format!("{{\"row\":{row},\"col\":{col}}}")),
+                    "{line}"
+                );
+                assert!(!line.contains(&format!("[{row},{col},")), "{line}");
+                if let Some(shown) = rows.get(row) {
+                    assert_eq!(shown.split(' ').nth(col), Some("#"), "{line}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn a_chord_check_says_what_a_chord_would_do_through_the_binary() {
+    let mut game = beginner();
+    game.apply(0, Command::Clear(START))
+        .expect("The start should be safe");
+    let number = game
+        .board()
+        .rows()
+        .flatten()
+        .find(|cell| {
+            matches!(cell.state, CellState::Safe { open: true, .. }) && cell.bombs_around > 0
+        })
+        .expect("The cascade should open a number");
+    let (position, shows) = (number.position, number.bombs_around);
+    let script = format!("c 2 7\nch? {} {}\n", position.row_index, position.col_index);
+
+    let (output, _) = run_binary("chord_check", &["--batch"], &script);
+    let expected = format!(
+        "Chording {position} would do nothing yet: it shows {shows} and has 0 flags around it"
+    );
+    assert!(output.contains(&expected), "{output}");
+
+    let (output, _) = run_binary("chord_check_json", &["--batch", "--json"], &script);
+    let line = output.lines().nth(1).expect("The check should be answered");
+    let preview = json::parse(line).expect("Each line should be JSON");
+    let preview = preview
+        .get("chord_preview")
+        .expect("The preview should be sent");
+    assert_eq!(preview.get("satisfied"), Some(&Value::Bool(false)));
+    assert_eq!(
+        preview.get("number").and_then(Value::as_u64),
+        Some(u64::from(shows))
+    );
+}
+
+#[test]
+fn hints_are_only_given_with_an_assist_level() {
+    let args = ["--no-color", "--plain", "--no-summary"];
+    let (output, _) = run_binary("hints_off", &args, "c 2 7\nhint\n");
+    assert!(output.contains("Hints are off"));
+
+    let config = "assist = \"hints\"\n";
+    let (output, _) = run_configured("hints_on", config, &args, "c 2 7\nhint why\n");
+    assert!(output.contains(
+        "Hint: (1,5) is a mine\n  \
+         (2,6) shows 1, with room for its mines only at (1,5); therefore (1,5) is a mine"
+    ));
+}
+
+// a wrong column is asked for again, with what was wrong with it, and the
+// row is kept
+#[test]
+fn a_column_prompt_asks_again_until_it_gets_a_column() {
+    let args = ["--no-color", "--plain", "--no-summary"];
+    let (output, _) = run_binary("column_retries", &args, "2\nx\n99\n7\n");
+
+    assert_eq!(output.matches("Enter col index:").count(), 3);
+    assert!(output.contains("Not a row or a column: x"));
+    assert!(output.contains("Column 99 is off the board, whose columns go from 0 to 8"));
+    assert!(output.contains("Opened 39 cells"));
+}
+
+// piped input gets three more tries, and the game is left after that
+#[test]
+fn a_column_prompt_gives_up_on_piped_input_after_its_retries() {
+    let args = ["--no-color", "--plain", "--no-summary"];
+    let (output, code) = run_binary("column_limit", &args, "2\nx\nx\nx\nx\nc 0 5\n");
+
+    assert_eq!(
+        code,
+        Some(130),
+        "A game given up on should exit as if the input ended"
+    );
+    assert_eq!(output.matches("Enter col index:").count(), 4);
+    assert!(output.contains("Giving up after 4 invalid answers in a row"));
+    assert!(!output.contains("Opened"));
+}
+
+#[test]
+fn the_input_ending_at_a_column_prompt_leaves_the_game() {
+    let args = ["--no-color", "--plain", "--no-summary"];
+    let (output, code) = run_binary("column_eof", &args, "2\n");
+
+    assert_eq!(
+        code,
+        Some(130),
+        "A game left at the end of input should exit with 130"
+    );
+    assert_eq!(output.matches("Enter col index:").count(), 1);
+}
+
+// resigning is asked about first, and once it is said yes to the game is
+// over as a loss, with its mines shown and the resignation in the history
+#[test]
+fn a_game_is_resigned_once_the_player_confirms() {
+    let args = ["--no-color", "--plain", "--no-summary"];
+    let input = "c 2 7\nresign\nn\nresign\ny\n";
+    let (output, code) = run_binary("resign", &args, input);
+
+    assert_eq!(code, Some(1), "A resigned game should exit as a loss");
+    assert_eq!(output.matches("Resign this game?").count(), 2);
+    assert_eq!(output.matches("Left as it was").count(), 1);
+    assert!(output.contains("Game resigned at 54% complete"), "{output}");
+    assert!(!output.contains("hit a mine"));
+
+    let history = PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+        .join("resign")
+        .join("minesweeper-cli")
+        .join("history.jsonl");
+    let history = fs::read_to_string(history).expect("The game should be in the history");
+    assert!(history.contains("\"result\":\"resigned\""), "{history}");
+}
+
+// with --batch there is no one to ask
+#[test]
+fn a_batch_game_is_resigned_by_the_bare_command() {
+    let (output, code) = run_binary("resign_batch", &["--batch", "--json"], "c 2 7\nresign\n");
+    assert_eq!(code, Some(1), "A resigned game should exit as a loss");
+
+    let lines: Vec<Value> = output
+        .lines()
+        .map(|line| json::parse(line).expect("Every line should be JSON"))
+        .collect();
+    let field = |index: usize, key| lines[index].get(key).and_then(Value::as_str);
+    assert_eq!(field(1, "state"), Some("resigned"));
+    assert_eq!(field(2, "outcome"), Some("resigned"));
+    assert_eq!(lines[2].get("progress").and_then(Value::as_u64), Some(54));
+}
+
+// a seed won before is raced against its fastest win, whose moves the
+// history kept the times of
+#[test]
+fn a_board_won_before_is_raced_against_its_ghost() {
+    let args = ["--no-color", "--plain", "--no-summary"];
+    let config = "watch_delay_ms = 0\n";
+    let (output, code) = run_configured("ghost", config, &args, "c 2 7\nwatch\ny\n");
+    assert_eq!(code, Some(0), "The solver should have won the game");
+    assert!(!output.contains("Ghost"));
+
+    // the solver's first moves can be kept at the same millisecond as the
+    // one before, so the ghost may be more than one move in at the start
+    let (output, _) = run_again("ghost", &args, "c 2 7\n");
+    let ghost = output
+        .lines()
+        .find_map(|line| line.split("Ghost: move ").nth(1))
+        .and_then(|rest| rest.split_once(" of 30"))
+        .and_then(|(moves, _)| moves.parse::<usize>().ok());
+    assert!(ghost.is_some_and(|moves| moves >= 1), "{output}");
+
+    // turning down the game the last run left off
+    let (output, _) = run_again(
+        "ghost",
+        &["--no-color", "--plain", "--seed", "7"],
+        "n\nc 2 7\n",
+    );
+    assert!(
+        output.contains("Moves: 1") && !output.contains("Ghost"),
+        "{output}"
+    );
+}
+
+// a row too long to be any board's is still a row, not an unknown command,
+// and the JSON protocol says which kind of mistake a line was
+#[test]
+fn coordinates_that_cant_be_say_why() {
+    let args = ["--no-color", "--plain", "--no-summary"];
+    let (output, _) = run_binary("huge_row", &args, "99999999999999999999\n+2\n");
+    assert!(output.contains("99999999999999999999 is too large to be a row or a column"));
+    assert!(output.contains("Unknown command: +2"), "{output}");
+
+    let args = ["--batch", "--json"];
+    let (output, _) = run_binary(
+        "error_codes",
+        &args,
+        "c 2 9\nc 2 x\nc 2 99999999999999999999\n",
+    );
+    let codes: Vec<String> = output
+        .lines()
+        .filter_map(|line| json::parse(line).ok())
+        .filter_map(|answer| Some(answer.get("code")?.as_str()?.to_string()))
+        .collect();
+    assert_eq!(codes, ["out_of_range", "not_a_number", "too_large"]);
+}
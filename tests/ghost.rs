@@ -0,0 +1,110 @@
+// an earlier run raced against by the clock, from games played on clocks
+// set by hand
+mod common;
+
+use common::at;
+use minesweeper::board::Board;
+use minesweeper::command::Command;
+use minesweeper::game::{Game, GameState};
+use minesweeper::ghost::Ghost;
+use minesweeper::timer::Clock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+// walled off, so that each clear opens the one cell
+const LAYOUT: &str = "..*\n*.*\n**.";
+
+// reads whatever it was last set to, in seconds
+#[derive(Default)]
+struct SetByHand(AtomicU64);
+
+impl SetByHand {
+    fn set(&self, seconds: u64) {
+        self.0.store(seconds, Ordering::Relaxed);
+    }
+}
+
+impl Clock for SetByHand {
+    fn now(&self) -> Duration {
+        Duration::from_secs(self.0.load(Ordering::Relaxed))
+    }
+}
+
+fn secs(seconds: &[u64]) -> Vec<Duration> {
+    seconds.iter().copied().map(Duration::from_secs).collect()
+}
+
+// the board's safe cells cleared at the given seconds, from the game's
+// start at 10
+fn played(clock: &Arc<SetByHand>, seconds: &[u64]) -> Game {
+    let board: Board = LAYOUT.parse().expect("The layout should parse");
+    let mut game = Game::with_clock(board, 1, clock.clone());
+    for (&second, cell) in seconds.iter().zip([at(0, 0), at(0, 1), at(1, 1), at(2, 2)]) {
+        clock.set(10 + second);
+        game.apply(0, Command::Clear(cell))
+            .expect("The cell should be safe");
+    }
+    game
+}
+
+#[test]
+fn each_move_keeps_when_it_was_made() {
+    let clock = Arc::new(SetByHand::default());
+    let game = played(&clock, &[0, 4, 9, 30]);
+    assert_eq!(game.state(), GameState::Won);
+
+    let made: Vec<Duration> = game.moves().iter().map(|made| made.at).collect();
+    assert_eq!(made, secs(&[0, 4, 9, 30]));
+    assert_eq!(Ghost::of(&game).times(), secs(&[0, 4, 9, 30]));
+}
+
+// an undone move isn't one the ghost made
+#[test]
+fn a_ghost_leaves_out_undone_moves() {
+    let clock = Arc::new(SetByHand::default());
+    let mut game = played(&clock, &[0, 4]);
+    clock.set(15);
+    game.apply(0, Command::Undo)
+        .expect("The clear should be undone");
+    assert_eq!(Ghost::of(&game).times(), secs(&[0]));
+}
+
+#[test]
+fn a_ghost_is_as_far_along_as_it_was_at_the_same_time() {
+    let clock = Arc::new(SetByHand::default());
+    let ghost = Ghost::of(&played(&clock, &[0, 4, 9, 30]));
+    assert_eq!(ghost.moves(), 4);
+
+    // raced by a slower game, which is behind it after every move
+    let racer = Arc::new(SetByHand::default());
+    let mut game = played(&racer, &[]).with_ghost(ghost.clone());
+    let mut seen = vec![];
+    for (second, cell) in [(0, at(0, 0)), (6, at(0, 1)), (20, at(1, 1)), (45, at(2, 2))] {
+        racer.set(100 + second);
+        game.apply(0, Command::Clear(cell))
+            .expect("The cell should be safe");
+        let ghost = game.ghost().expect("The game should keep its ghost");
+        seen.push(ghost.moves_by(game.elapsed()));
+    }
+    assert_eq!(game.state(), GameState::Won);
+    assert_eq!(seen, [1, 2, 3, 4]);
+
+    // and the readout holds between its moves
+    let readout: Vec<usize> = [0, 3, 4, 8, 9, 29, 30, 600]
+        .map(|second| ghost.moves_by(Duration::from_secs(second)))
+        .to_vec();
+    assert_eq!(readout, [1, 1, 2, 2, 3, 3, 4, 4]);
+}
+
+#[test]
+fn a_game_without_moves_has_nothing_to_race() {
+    let ghost = Ghost::new(vec![]);
+    assert_eq!(
+        (ghost.moves(), ghost.moves_by(Duration::from_secs(60))),
+        (0, 0)
+    );
+    assert!(played(&Arc::new(SetByHand::default()), &[])
+        .ghost()
+        .is_none());
+}
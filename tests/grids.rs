@@ -0,0 +1,158 @@
+// the same rules played on each way of storing a board's cells, which
+// should be impossible to tell apart from the moves
+mod common;
+
+use common::at;
+use minesweeper::board::{ArrayBoard, Board, ClearError, FlagChange};
+use minesweeper::grid::{ArrayGrid, CellGrid, VecGrid};
+
+// a mine in two corners, two in the middle row, and a zero in each of the
+// other corners
+const LAYOUT: &str = "*....\n.....\n..**.\n.....\n....*";
+
+fn bombs() -> Vec<Vec<bool>> {
+    LAYOUT
+        .lines()
+        .map(|line| line.chars().map(|symbol| symbol == '*').collect())
+        .collect()
+}
+
+macro_rules! game_logic_suite {
+    ($name:ident, $grid:ty) => {
+        mod $name {
+            use super::*;
+
+            fn board() -> Board<$grid> {
+                Board::<$grid>::with_bombs(bombs()).expect("The grid should hold a 5x5 board")
+            }
+
+            #[test]
+            fn the_board_is_laid_out_as_given() {
+                let board = board();
+                assert_eq!((board.height(), board.width()), (5, 5));
+                assert_eq!(board.layout(), LAYOUT);
+                assert_eq!(board.count_bombs(), 4);
+                assert_eq!(
+                    board.get_cell(at(1, 2)).map(|cell| cell.bombs_around),
+                    Some(2)
+                );
+                assert!(board.get_cell(at(5, 0)).is_none());
+            }
+
+            #[test]
+            fn a_zero_cascades_up_to_the_numbers() {
+                let mut board = board();
+                board.clear(at(0, 4)).expect("(0,4) is safe");
+
+                assert_eq!(board.count_open(), 8);
+                assert!(!board.is_won());
+                assert!(matches!(
+                    board.clear(at(0, 4)),
+                    Err(ClearError::AlreadyCleared)
+                ));
+                assert!(matches!(
+                    board.clear(at(0, 0)),
+                    Err(ClearError::ClearedBomb)
+                ));
+                assert!(matches!(
+                    board.clear(at(9, 9)),
+                    Err(ClearError::CellNotFound)
+                ));
+            }
+
+            #[test]
+            fn flags_count_towards_the_mines() {
+                let mut board = board();
+                assert_eq!(
+                    board.toggle_flag(at(0, 0)).ok(),
+                    Some(FlagChange::FlagPlaced)
+                );
+                assert_eq!(
+                    board.toggle_flag(at(1, 1)).ok(),
+                    Some(FlagChange::FlagPlaced)
+                );
+                assert_eq!((board.count_flags(), board.mines_left()), (2, 2));
+                assert!(!board.all_mines_flagged());
+
+                board.toggle_flag(at(1, 1)).expect("(1,1) is flagged");
+                for mine in [at(2, 2), at(2, 3), at(4, 4)] {
+                    board.toggle_flag(mine).expect("The mine is closed");
+                }
+                assert!(board.all_mines_flagged());
+            }
+
+            #[test]
+            fn a_chord_opens_around_a_satisfied_number() {
+                let mut board = board();
+                board.clear(at(1, 1)).expect("(1,1) is safe");
+                board.toggle_flag(at(0, 0)).expect("(0,0) is closed");
+                board.toggle_flag(at(2, 2)).expect("(2,2) is closed");
+
+                let targets = board
+                    .chord_targets(at(1, 1))
+                    .expect("Both its mines are flagged");
+                assert_eq!(targets.len(), 6);
+            }
+
+            #[test]
+            fn opening_every_safe_cell_wins() {
+                let mut board = board();
+                for row_index in 0..5 {
+                    for col_index in 0..5 {
+                        let _ = board.clear(at(row_index, col_index));
+                    }
+                }
+                assert_eq!(board.count_open(), 21);
+                assert!(board.is_won());
+            }
+
+            #[test]
+            fn moving_a_mine_recounts_the_closed_cells() {
+                let mut board = board();
+                assert!(board.move_mine(at(0, 0), at(0, 2)));
+
+                assert_eq!(
+                    board.get_cell(at(0, 1)).map(|cell| cell.bombs_around),
+                    Some(1)
+                );
+                assert_eq!(
+                    board.get_cell(at(1, 1)).map(|cell| cell.bombs_around),
+                    Some(2)
+                );
+                assert_eq!(
+                    board.get_cell(at(1, 3)).map(|cell| cell.bombs_around),
+                    Some(3)
+                );
+            }
+
+            #[test]
+            fn a_board_regrids_with_its_cells() {
+                let mut board = board();
+                board.clear(at(0, 4)).expect("(0,4) is safe");
+                board.toggle_flag(at(0, 0)).expect("(0,0) is closed");
+
+                let moved: Board<VecGrid> = board.regrid().expect("A VecGrid holds any board");
+                assert_eq!(moved.layout(), board.layout());
+                assert_eq!(moved.count_open(), 8);
+                assert_eq!(moved.count_flags(), 1);
+            }
+        }
+    };
+}
+
+game_logic_suite!(vec_grid, VecGrid);
+game_logic_suite!(array_grid, ArrayGrid<5>);
+
+#[test]
+fn an_array_grid_holds_only_its_own_size() {
+    assert!(ArrayBoard::<4>::with_bombs(bombs()).is_none());
+    assert!(Board::from_bombs(bombs())
+        .regrid::<ArrayGrid<6>>()
+        .is_none());
+
+    let board: ArrayBoard<5> = Board::from_bombs(bombs())
+        .regrid()
+        .expect("The board is 5x5");
+    assert_eq!(board.layout(), LAYOUT);
+    assert!(ArrayGrid::<5>::from_rows(vec![]).is_none());
+}
@@ -0,0 +1,72 @@
+// the solver's reasoning behind a hint, in words, on boards made by hand
+mod common;
+
+use common::{at, game_on};
+use minesweeper::analysis::render_hint;
+use minesweeper::command::Command;
+use minesweeper::game::Game;
+
+fn played(layout: &str, commands: &[Command]) -> Game {
+    let mut game = game_on(layout);
+    for command in commands {
+        game.apply(0, command.clone())
+            .expect("The move should be allowed");
+    }
+    game
+}
+
+// the 2 under the left pair needs both, so the rest of the row is safe
+#[test]
+fn a_single_point_hint_names_its_number() {
+    let game = played("**.\n...\n...", &[Command::Clear(at(2, 2))]);
+
+    assert_eq!(
+        render_hint(game.board(), true),
+        "Hint: (0,0) is a mine\n  \
+         (1,0) shows 2, with room for its mines only at (0,0), (0,1); therefore (0,0) is a mine"
+    );
+    assert_eq!(render_hint(game.board(), false), "Hint: (0,0) is a mine");
+}
+
+// with both mines flagged, the solver works them out again rather than
+// trusting the flags, and then the 2 beside them has all it needs; the
+// mines past the rocks are worked out too, but have nothing to do with it
+#[test]
+fn a_hint_goes_back_through_the_mines_it_counts_on_and_no_others() {
+    let mut commands = vec![Command::Clear(at(2, 2)), Command::Clear(at(2, 5))];
+    commands.extend([at(0, 0), at(0, 1), at(0, 4), at(0, 5)].map(Command::Flag));
+    let game = played("**.o**\n...o..\n...o..", &commands);
+
+    assert_eq!(
+        render_hint(game.board(), true),
+        "Hint: (0,2) is safe\n  \
+         (1,0) shows 2, with room for its mines only at (0,0), (0,1); therefore (0,0), (0,1) \
+         are mines\n  \
+         (1,1) shows 2, and all of its mines are accounted for by (0,0), (0,1); therefore (0,2) \
+         is safe"
+    );
+}
+
+// no 1 on the second row settles the top row alone, but the left one's
+// cells are all the middle one's bar one
+#[test]
+fn a_subset_hint_names_both_numbers_and_what_is_left() {
+    let game = played(".*.\n...\n...", &[Command::Clear(at(2, 0))]);
+
+    assert_eq!(
+        render_hint(game.board(), true),
+        "Hint: (0,2) is safe\n  \
+         (1,0) has 1 left to find among (0,0), (0,1), all of which are next to (1,1) too, which \
+         has 1 left among (0,0), (0,1), (0,2); so 0 are left for (0,2); therefore (0,2) is safe"
+    );
+}
+
+#[test]
+fn a_guess_gets_no_hint() {
+    let game = played("..*\n...\n*..", &[Command::Clear(at(0, 0))]);
+
+    assert_eq!(
+        render_hint(game.board(), true),
+        "The numbers don't prove anything more; the next move would be a guess"
+    );
+}
@@ -0,0 +1,207 @@
+// the history and its reports through the binary, on histories written by hand
+// into a data directory kept apart from the user's
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command as Process;
+
+// `history` with `args`, on a history of `records`, each a record's JSON
+// less its braces
+fn history(name: &str, records: &[&str], args: &[&str]) -> String {
+    let home = PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+        .join("history")
+        .join(name);
+    let _ = fs::remove_dir_all(&home);
+    let data = home.join("minesweeper-cli");
+    fs::create_dir_all(&data).expect("The test's data directory should be made");
+    let lines = records
+        .iter()
+        .map(|record| format!("{{{record}}}\n"))
+        .collect::<String>();
+    fs::write(data.join("history.jsonl"), lines).expect("The history should be written");
+
+    let output = Process::new(env!("CARGO_BIN_EXE_minesweeper"))
+        .arg("history")
+        .args(args)
+        .args(["--lang", "en"])
+        .env("XDG_DATA_HOME", &home)
+        .env("XDG_CONFIG_HOME", &home)
+        .output()
+        .expect("The binary should run");
+    String::from_utf8(output.stdout).expect("The output should be UTF-8")
+}
+
+// a game on a 9x9 board with 10 mines whose first move was at (row, col)
+fn game(won: bool, row: usize, col: usize, opened: &str) -> String {
+    let result = if won { "won" } else { "lost" };
+    format!(
+        "\"timestamp\":0,\"code\":\"1\",\"height\":9,\"width\":9,\"mines\":10,\
+         \"result\":\"{result}\",\"elapsed_ms\":1000,\"three_bv\":10,\"moves\":5,\
+         \"assisted\":false,\"first\":{{\"row\":{row},\"col\":{col},\"opened\":\"{opened}\"}}"
+    )
+}
+
+// the report's grid, a part of the board to a string
+fn grid(output: &str) -> Vec<Vec<String>> {
+    output
+        .lines()
+        .skip(2)
+        .map(|line| {
+            let line: Vec<char> = line.chars().collect();
+            line.chunks(16)
+                .map(|part| part.iter().collect::<String>().trim().to_string())
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn first_moves_are_put_together_by_part_of_the_board() {
+    let records = [
+        game(true, 0, 0, "cascade"),
+        game(false, 1, 2, "mine"),
+        game(true, 0, 8, "number"),
+        game(true, 4, 4, "cascade"),
+        game(false, 5, 3, "number"),
+        game(true, 8, 8, "cascade"),
+    ];
+    let records: Vec<&str> = records.iter().map(String::as_str).collect();
+    let output = history("parts", &records, &["openings"]);
+
+    assert!(output.starts_with("Where the first move was, over 6 games:"));
+    let grid = grid(&output);
+    assert_eq!(grid.len(), 3);
+    assert_eq!(grid[0][0], "2 50% 1/1/0");
+    assert_eq!(grid[0][1], "·");
+    assert_eq!(grid[0][2], "1 100% 0/0/1");
+    assert_eq!(grid[1][1], "2 50% 0/1/1");
+    assert_eq!(grid[2][2], "1 100% 0/1/0");
+}
+
+// a 16x30 board's first row and column are a 9x9's first part too
+#[test]
+fn boards_of_every_size_are_scaled_to_the_same_parts() {
+    let small = game(true, 2, 2, "cascade");
+    let large =
+        game(false, 4, 9, "mine").replace("\"height\":9,\"width\":9", "\"height\":16,\"width\":30");
+    let output = history("scaled", &[&small, &large], &["openings"]);
+
+    assert_eq!(grid(&output)[0][0], "2 50% 1/1/0");
+}
+
+#[test]
+fn openings_take_the_history_s_filters() {
+    let records = [game(true, 0, 0, "cascade"), game(false, 0, 0, "mine")];
+    let records: Vec<&str> = records.iter().map(String::as_str).collect();
+    let output = history("filtered", &records, &["openings", "--result", "won"]);
+
+    assert_eq!(grid(&output)[0][0], "1 100% 0/1/0");
+}
+
+// records from before first moves were kept are left out, but still read
+#[test]
+fn older_records_without_a_first_move_are_left_out() {
+    let old = game(true, 0, 0, "cascade");
+    let old = &old[..old
+        .find(",\"first\"")
+        .expect("The record should have a first move")];
+    let output = history("older", &[old], &["openings"]);
+    assert_eq!(output.trim(), "No first moves in the history");
+
+    let output = history("older_listed", &[old], &[]);
+    assert!(output.contains("9x9/10"));
+}
+
+// a game on a `height` x `width` board with `mines` mines, with nothing but
+// what the listing shows
+fn played(code: &str, (height, width, mines): (usize, usize, usize), result: &str) -> String {
+    format!(
+        "\"timestamp\":86400,\"code\":\"{code}\",\"height\":{height},\"width\":{width},\
+         \"mines\":{mines},\"result\":\"{result}\",\"elapsed_ms\":61000,\"three_bv\":12,\
+         \"moves\":7,\"assisted\":false"
+    )
+}
+
+// the codes that end the listing's rows, in order
+fn codes(output: &str) -> Vec<&str> {
+    output
+        .lines()
+        .skip_while(|line| !line.starts_with("Date"))
+        .skip(1)
+        .filter_map(|line| line.rsplit("  ").next())
+        .collect()
+}
+
+#[test]
+fn a_corrupt_line_is_skipped_and_the_rest_listed_and_filtered() {
+    let records = [
+        played("#1", (9, 9, 10), "won"),
+        // cut off partway through
+        "\"timestamp\":86400,\"code\":".to_string(),
+        played("#2", (16, 16, 40), "lost"),
+        played("#3", (9, 9, 10), "resigned"),
+        played("#4", (16, 30, 99), "won"),
+    ];
+    let records: Vec<&str> = records.iter().map(String::as_str).collect();
+    let listed = |name: &str, args: &[&str]| history(name, &records, args);
+
+    let output = listed("corrupt_all", &[]);
+    assert!(
+        output.starts_with("Skipping line 2 of the history: "),
+        "{output}"
+    );
+    assert_eq!(codes(&output), ["#1", "#2", "#3", "#4"]);
+    let header = output.lines().nth(1).unwrap_or_default();
+    assert_eq!(
+        header,
+        "Date              Board      Result        Time   3BV    Moves  Code"
+    );
+    assert_eq!(
+        output.lines().nth(2).unwrap_or_default(),
+        "1970-01-02 00:00  9x9/10     won          01:01    12        7  #1"
+    );
+
+    assert_eq!(
+        codes(&listed("corrupt_won", &["--result", "won"])),
+        ["#1", "#4"]
+    );
+    // a resigned game is lost as well, unless it is left out
+    assert_eq!(
+        codes(&listed("corrupt_lost", &["--result", "lost"])),
+        ["#2", "#3"]
+    );
+    assert_eq!(
+        codes(&listed(
+            "corrupt_not_resigned",
+            &["--result", "lost", "--no-resigned"]
+        )),
+        ["#2"]
+    );
+    assert_eq!(
+        codes(&listed("corrupt_resigned", &["--result", "resigned"])),
+        ["#3"]
+    );
+    assert_eq!(
+        codes(&listed("corrupt_expert", &["--preset", "expert"])),
+        ["#4"]
+    );
+    assert_eq!(
+        codes(&listed(
+            "corrupt_beginner",
+            &["--preset", "beginner", "--result", "won"]
+        )),
+        ["#1"]
+    );
+    assert_eq!(
+        codes(&listed("corrupt_last", &["--last", "2"])),
+        ["#3", "#4"]
+    );
+    assert_eq!(
+        listed(
+            "corrupt_none",
+            &["--preset", "intermediate", "--result", "won"]
+        )
+        .lines()
+        .last(),
+        Some("No matching games in the history")
+    );
+}
@@ -0,0 +1,70 @@
+// the message catalog: every key in English, the same placeholders in each
+// language, and the language in use switched at startup
+mod common;
+
+use common::Home;
+use minesweeper::i18n::{fill, set_lang, text, Lang, Msg};
+use std::collections::BTreeSet;
+
+// the {N} a template takes
+fn placeholders(template: &str) -> BTreeSet<&str> {
+    template
+        .split('{')
+        .skip(1)
+        .filter_map(|rest| rest.split_once('}'))
+        .map(|(index, _)| index)
+        .filter(|index| index.parse::<usize>().is_ok())
+        .collect()
+}
+
+// one test, as the language is set for the whole process
+#[test]
+fn every_key_has_an_english_entry_and_spanish_follows_it() {
+    set_lang(Lang::English);
+    let english: Vec<&str> = Msg::ALL.iter().map(|&msg| text(msg)).collect();
+    for (msg, english) in Msg::ALL.iter().zip(&english) {
+        assert!(!english.trim().is_empty(), "{msg:?}");
+    }
+
+    set_lang(Lang::Spanish);
+    for (msg, english) in Msg::ALL.iter().zip(&english) {
+        assert_eq!(
+            placeholders(text(*msg)),
+            placeholders(english),
+            "{msg:?} takes different arguments in Spanish"
+        );
+    }
+    assert_eq!(text(Msg::GameWon), "Partida ganada");
+    assert_eq!(
+        fill(Msg::UnknownArgument, &[&"--x"]),
+        "Argumento desconocido `--x`, consulta --help"
+    );
+    set_lang(Lang::English);
+    assert_eq!(
+        fill(Msg::UnknownArgument, &[&"--x"]),
+        "Unknown argument `--x`, see --help"
+    );
+}
+
+#[test]
+fn locales_name_their_language() {
+    assert_eq!(Lang::from_code("es_ES.UTF-8"), Some(Lang::Spanish));
+    assert_eq!(Lang::from_code("es-MX"), Some(Lang::Spanish));
+    assert_eq!(Lang::from_code("C"), Some(Lang::English));
+    assert_eq!(Lang::from_code("fr_FR"), None);
+}
+
+#[test]
+fn the_binary_prompts_in_the_language_it_is_given() {
+    let home = Home::new("i18n_prompts");
+    let args = ["--seed", "42", "--preset", "beginner", "--plain"];
+    let english = home.run(&args, "");
+    let spanish = home.run_in("es", &args, "");
+    assert_ne!(english.stdout, spanish.stdout);
+    assert!(english.stdout.contains("Enter row index"));
+    assert!(
+        spanish.stdout.contains("Introduce la fila"),
+        "{}",
+        spanish.stdout
+    );
+}
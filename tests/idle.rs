@@ -0,0 +1,79 @@
+// a long wait for a move, with an idle limit set and without: past the
+// limit it is left off the timer and the player told so, and with no limit
+// every second of it counts
+mod common;
+
+use common::{board, SetByHand, Timed};
+use minesweeper::driver::{run_game, GameConfig, GameOutput, Hooks, Io, Screen};
+use minesweeper::game::{Game, GameOutcome};
+use minesweeper::i18n::Msg;
+use std::sync::Arc;
+use std::time::Duration;
+
+// walled off, so that each clear opens the one cell
+const LAYOUT: &str = "..*\n*.*\n**.";
+
+#[derive(Default)]
+struct Messages(Vec<String>);
+
+impl GameOutput for Messages {
+    fn show(&mut self, screen: &Screen) {
+        if let Screen::Message(message) = screen {
+            self.0.push(message.clone());
+        }
+    }
+}
+
+// the game's time, and what the player was told, for clears at the given
+// seconds from a start at 0
+fn played(idle_limit: Option<u64>, seconds: [u64; 4]) -> (Duration, Vec<String>) {
+    let clock = Arc::new(SetByHand::default());
+    let moves = ["c 0 0", "c 0 1", "c 1 1", "c 2 2"];
+    let mut input = Timed {
+        clock: clock.clone(),
+        lines: seconds.into_iter().zip(moves).collect(),
+    };
+    let mut output = Messages::default();
+    let config = GameConfig {
+        game: Game::with_clock(board(LAYOUT), 1, clock),
+        retries: None,
+        idle_limit: idle_limit.map(Duration::from_secs),
+        prompt: Msg::EnterRowOrCommand,
+        players: 1,
+    };
+    let io = Io {
+        input: &mut input,
+        output: &mut output,
+    };
+    match run_game(config, io, Hooks::default()) {
+        GameOutcome::Won(stats) => (stats.elapsed, output.0),
+        outcome => panic!("The game should be won: {outcome:?}"),
+    }
+}
+
+#[test]
+fn gaps_past_the_limit_are_left_off() {
+    // a wait of 600 seconds before the third clear, and of 40 before the last
+    let (elapsed, messages) = played(Some(30), [5, 10, 610, 650]);
+    // 5 seconds to the second clear, then 30 and 30 counted of the two waits
+    assert_eq!(elapsed, Duration::from_secs(65));
+    assert_eq!(
+        messages,
+        [
+            "(idle for 09:30; not counted)",
+            "(idle for 00:10; not counted)"
+        ]
+    );
+
+    // and a wait within the limit is counted in full
+    let (elapsed, messages) = played(Some(30), [5, 10, 40, 50]);
+    assert_eq!(elapsed, Duration::from_secs(45));
+    assert!(messages.is_empty(), "{messages:?}");
+}
+
+#[test]
+fn with_no_limit_every_gap_counts() {
+    let (elapsed, messages) = played(None, [5, 10, 610, 650]);
+    assert_eq!(elapsed, Duration::from_secs(645));
+    assert!(messages.is_empty(), "{messages:?}");
+}
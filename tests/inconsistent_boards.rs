@@ -0,0 +1,60 @@
+// boards whose numbers don't match their mines, as a bug in making them
+// would leave them, which should fail the move rather than the game; with
+// debug-checks they panic instead, as fuzzing wants
+#![cfg(not(feature = "debug-checks"))]
+mod common;
+
+use common::at;
+use minesweeper::board::{Board, ClearError};
+use minesweeper::command::Command;
+use minesweeper::game::{Game, GameError, GameState};
+
+// a rock wall down the middle, and a mine on the right that none of the
+// numbers count
+const LAYOUT: &str = "..o..\n..o..\n..o.*";
+
+fn wrong_board() -> Board {
+    let board: Board = LAYOUT.parse().expect("The layout should parse");
+    board.with_counts_from(&vec![vec![false; 5]; 3])
+}
+
+#[test]
+fn a_zero_beside_a_mine_fails_the_clear() {
+    let mut board = wrong_board();
+
+    let Err(ClearError::Inconsistent { detail, .. }) = board.clear(at(0, 4)) else {
+        panic!("Clearing into the uncounted mine should fail");
+    };
+    assert!(detail.contains(&at(2, 4).to_string()), "{detail}");
+    assert_eq!(board.count_open(), 0, "A failed clear should open nothing");
+}
+
+#[test]
+fn the_game_goes_on_after_an_inconsistent_clear() {
+    let mut game = Game::new(wrong_board(), 1);
+
+    let result = game.apply(0, Command::Clear(at(0, 3)));
+    assert!(matches!(
+        result,
+        Err(GameError::Clear(ClearError::Inconsistent { .. }))
+    ));
+    assert_eq!(game.state(), GameState::Playing);
+    assert!(game.moves().is_empty());
+
+    // the left of the wall is counted right, and still plays
+    let state = game
+        .apply(0, Command::Clear(at(0, 0)))
+        .expect("(0,0) is counted right");
+    assert_eq!(state, GameState::Playing);
+    assert_eq!(game.board().count_open(), 6);
+}
+
+// the left of the row opens before the right goes wrong, and is closed again
+#[test]
+fn an_area_that_goes_wrong_partway_opens_nothing() {
+    let mut game = Game::new(wrong_board(), 1);
+    let command: Command = "c row 0".parse().expect("The command should parse");
+
+    assert!(game.apply(0, command).is_err());
+    assert_eq!(game.board().count_open(), 0);
+}
@@ -0,0 +1,123 @@
+// the game loop reading through a mock input source: lines, cancelled
+// lines and the end of input, with nothing read once the game is over
+mod common;
+
+use common::game_on;
+use minesweeper::driver::{run_game, GameConfig, GameInput, GameOutput, Hooks, Io, Screen};
+use minesweeper::game::GameOutcome;
+use minesweeper::i18n::{text, Msg};
+use std::collections::VecDeque;
+use std::io::{self, ErrorKind};
+
+// walled off, so that each clear opens the one cell
+const LAYOUT: &str = "..*\n*.*\n**.";
+
+// what to answer each read with, and how many reads there were
+struct Mock {
+    answers: VecDeque<io::Result<Option<String>>>,
+    reads: usize,
+}
+
+impl Mock {
+    fn new(answers: impl IntoIterator<Item = io::Result<Option<String>>>) -> Self {
+        Mock {
+            answers: answers.into_iter().collect(),
+            reads: 0,
+        }
+    }
+}
+
+impl GameInput for Mock {
+    fn read_command(&mut self) -> io::Result<Option<String>> {
+        self.reads += 1;
+        self.answers.pop_front().unwrap_or(Ok(None))
+    }
+}
+
+fn line(line: &str) -> io::Result<Option<String>> {
+    Ok(Some(line.to_string()))
+}
+
+fn cancelled() -> io::Result<Option<String>> {
+    Err(io::Error::new(ErrorKind::Interrupted, "(cancelled)"))
+}
+
+#[derive(Default)]
+struct Kept(Vec<String>);
+
+impl GameOutput for Kept {
+    fn show(&mut self, screen: &Screen) {
+        self.0.push(match screen {
+            Screen::Prompt(msg) => text(*msg).to_string(),
+            Screen::Message(message) => message.clone(),
+            _ => String::from("-"),
+        });
+    }
+}
+
+fn run(input: &mut Mock) -> (GameOutcome, Vec<String>) {
+    let mut output = Kept::default();
+    let io = Io {
+        input,
+        output: &mut output,
+    };
+    let config = GameConfig {
+        game: game_on(LAYOUT),
+        retries: Some(3),
+        idle_limit: None,
+        prompt: Msg::EnterRowOrCommand,
+        players: 1,
+    };
+    let outcome = run_game(config, io, Hooks::default());
+    (outcome, output.0)
+}
+
+#[test]
+fn a_cancelled_line_is_dropped_and_the_game_goes_on() {
+    let mut input = Mock::new([
+        cancelled(),
+        line("c 0 0"),
+        // a row, then a column that is cancelled, which drops the row too
+        line("0"),
+        cancelled(),
+        line("c 0 1; c 1 1"),
+        line("c 2 2"),
+        line("c 0 0"),
+    ]);
+    let (outcome, shown) = run(&mut input);
+
+    assert!(matches!(outcome, GameOutcome::Won(_)));
+    assert_eq!(
+        shown.iter().filter(|line| *line == "(cancelled)").count(),
+        2
+    );
+    // the line after the win is never read
+    assert_eq!(input.reads, 6);
+    assert_eq!(input.answers.len(), 1);
+}
+
+#[test]
+fn the_end_of_input_leaves_the_game() {
+    let mut input = Mock::new([line("c 0 0")]);
+    let (outcome, _) = run(&mut input);
+
+    assert_eq!(outcome, GameOutcome::Aborted);
+    assert_eq!(input.reads, 2);
+}
+
+#[test]
+fn a_failing_source_is_reported_and_read_again() {
+    let broken = || Err(io::Error::other("the source broke"));
+    let mut input = Mock::new([broken(), broken(), line("c 0 0")]);
+    let (outcome, shown) = run(&mut input);
+
+    assert_eq!(outcome, GameOutcome::Aborted);
+    assert_eq!(
+        shown
+            .iter()
+            .filter(|line| *line == "the source broke")
+            .count(),
+        2
+    );
+    assert_eq!(input.reads, 4);
+}
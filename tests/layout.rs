@@ -0,0 +1,105 @@
+// lining cells up in columns: how wide glyphs are, and random rows of glyphs
+// of every width, each case from its own seed, which a failure names
+use minesweeper::board::{Board, CellPosition, Mines};
+use minesweeper::command::Command;
+use minesweeper::game::Game;
+use minesweeper::layout::{display_width, CellLayout};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+const CASES: u64 = 300;
+
+// one and two columns wide, with a variation selector, and coloured
+const GLYPHS: [&str; 12] = [
+    "#",
+    "^",
+    "7",
+    "⬜",
+    "🚩",
+    "💣",
+    "❓",
+    "🟩",
+    "８",
+    "✔\u{FE0F}",
+    "\x1B[34m1\x1B[0m",
+    "a",
+];
+
+#[test]
+fn glyphs_are_as_wide_as_a_terminal_draws_them() {
+    assert_eq!(display_width("# 1 ^"), 5);
+    assert_eq!(display_width("⬜🚩💥"), 6);
+    assert_eq!(display_width("０８"), 4);
+    assert_eq!(display_width("❓❌⬛"), 6);
+    assert_eq!(display_width("\x1B[1;31m*\x1B[0m"), 1);
+    assert_eq!(display_width("e\u{301}"), 1);
+    assert_eq!(display_width(""), 0);
+}
+
+#[test]
+fn cells_are_padded_to_the_widest_glyph() {
+    let layout = CellLayout::fit(["#", "🚩"], 1);
+    assert_eq!(layout.cell_width(), 2);
+    assert_eq!(layout.pitch(), 3);
+    assert_eq!(layout.row(["#", "🚩", "#"]), "#  🚩 # ");
+    assert_eq!(layout.row_width(3), 8);
+    assert_eq!(layout.column(2), 6);
+
+    let layout = CellLayout::text();
+    assert_eq!(layout.row(["1", "#", "^"]), "1 # ^");
+}
+
+#[test]
+fn every_row_of_random_glyphs_is_as_wide_as_the_layout_says() {
+    for seed in 0..CASES {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let count = rng.gen_range(1..=GLYPHS.len());
+        let theme: Vec<&str> = GLYPHS.choose_multiple(&mut rng, count).copied().collect();
+        let layout = CellLayout::fit(theme.iter().copied(), rng.gen_range(0..=2));
+        let width = rng.gen_range(1..=40);
+
+        for _ in 0..rng.gen_range(1..=10) {
+            let row: Vec<&str> = (0..width)
+                .map(|_| *theme.choose(&mut rng).expect("The theme has glyphs"))
+                .collect();
+            assert_eq!(
+                display_width(&layout.row(&row)),
+                layout.row_width(width),
+                "seed {seed}: {row:?}"
+            );
+        }
+    }
+}
+
+// the plain-text board, with numbers, flags and mines gone off on it
+#[test]
+fn every_line_of_a_random_board_is_as_wide_as_the_others() {
+    for seed in 0..CASES {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (height, width) = (rng.gen_range(1..=30), rng.gen_range(1..=30));
+        let mines = rng.gen_range(0..height * width);
+        let mut game = Game::new(
+            Board::from_seed(seed, height, width, Mines::Count(mines)),
+            3,
+        );
+        for _ in 0..10 {
+            let position = CellPosition {
+                row_index: rng.gen_range(0..height),
+                col_index: rng.gen_range(0..width),
+            };
+            let command = if rng.gen_bool(0.7) {
+                Command::Clear(position)
+            } else {
+                Command::Flag(position)
+            };
+            let _ = game.apply(0, command);
+        }
+
+        let board = game.board().to_string();
+        let expected = CellLayout::text().row_width(width);
+        for line in board.lines() {
+            assert_eq!(display_width(line), expected, "seed {seed}:\n{board}");
+        }
+    }
+}
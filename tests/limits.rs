@@ -0,0 +1,194 @@
+// mines per row and per column: a feasible set of limits is met by every
+// line of every board, and an infeasible one is turned down with the limit
+// that can't be met
+mod common;
+
+use common::{mines, Home};
+use minesweeper::board::{Board, Layout, LimitError, Line, LineLimits, Mines};
+use minesweeper::rules::{Ruleset, RulesetError};
+
+// how many mines each row and each column has
+fn lines(board: &Board) -> (Vec<usize>, Vec<usize>) {
+    let mut rows = vec![0; board.height()];
+    let mut columns = vec![0; board.width()];
+    for mine in mines(board) {
+        rows[mine.row_index] += 1;
+        columns[mine.col_index] += 1;
+    }
+    (rows, columns)
+}
+
+fn within(counts: &[usize], min: usize, max: Option<usize>) -> bool {
+    counts
+        .iter()
+        .all(|&count| count >= min && max.is_none_or(|max| count <= max))
+}
+
+#[test]
+fn every_row_and_column_keeps_to_feasible_limits() {
+    let cases = [
+        // at most 3 per row
+        (
+            9,
+            9,
+            20,
+            LineLimits {
+                max_per_row: Some(3),
+                ..LineLimits::default()
+            },
+        ),
+        // at least 1 per column
+        (
+            8,
+            12,
+            15,
+            LineLimits {
+                min_per_column: 1,
+                ..LineLimits::default()
+            },
+        ),
+        // exactly 2 in every row and every column
+        (
+            6,
+            6,
+            12,
+            LineLimits {
+                min_per_row: 2,
+                max_per_row: Some(2),
+                min_per_column: 2,
+                max_per_column: Some(2),
+            },
+        ),
+        // all four, just short of tight
+        (
+            10,
+            7,
+            30,
+            LineLimits {
+                min_per_row: 2,
+                max_per_row: Some(4),
+                min_per_column: 3,
+                max_per_column: Some(5),
+            },
+        ),
+    ];
+    for (height, width, count, limits) in cases {
+        assert_eq!(limits.check(height, width, count), Ok(()));
+        let layout = Layout {
+            limits,
+            ..Layout::new(Mines::Count(count))
+        };
+        for seed in 0..20 {
+            let board = Board::generate(seed, height, width, &layout);
+            let (rows, columns) = lines(&board);
+            let case = format!("{height}x{width}, {count} mines, #{seed}: {rows:?} {columns:?}");
+            assert_eq!(rows.iter().sum::<usize>(), count, "{case}");
+            assert!(
+                within(&rows, limits.min_per_row, limits.max_per_row),
+                "{case}"
+            );
+            assert!(
+                within(&columns, limits.min_per_column, limits.max_per_column),
+                "{case}"
+            );
+        }
+    }
+}
+
+#[test]
+fn infeasible_limits_are_turned_down_with_the_limit_at_fault() {
+    let limits = |min_per_row, max_per_row, min_per_column, max_per_column| LineLimits {
+        min_per_row,
+        max_per_row,
+        min_per_column,
+        max_per_column,
+    };
+    let cases = [
+        // 2 per column on 10 columns is 20 mines, with 15 to place
+        (
+            limits(0, None, 2, None),
+            LimitError::TooFewMines {
+                line: Line::Column,
+                min: 2,
+                needed: 20,
+                mines: 15,
+            },
+        ),
+        // 1 per row on 8 rows leaves room for 8
+        (
+            limits(0, Some(1), 0, None),
+            LimitError::TooManyMines {
+                line: Line::Row,
+                max: 1,
+                allowed: 8,
+                mines: 15,
+            },
+        ),
+        (
+            limits(3, Some(2), 0, None),
+            LimitError::MinAboveMax {
+                line: Line::Row,
+                min: 3,
+                max: 2,
+            },
+        ),
+        // a column is 8 cells long
+        (
+            limits(0, None, 9, None),
+            LimitError::MinAboveLength {
+                line: Line::Column,
+                min: 9,
+                length: 8,
+            },
+        ),
+    ];
+    for (limits, error) in cases {
+        assert_eq!(limits.check(8, 10, 15).as_ref(), Err(&error));
+        let rules = Ruleset::builder()
+            .size(8, 10)
+            .mines(Mines::Count(15))
+            .limits(limits)
+            .build();
+        assert_eq!(rules, Err(RulesetError::Limits(error)));
+    }
+
+    assert_eq!(
+        limits(0, None, 2, None)
+            .check(8, 10, 15)
+            .map_err(|e| e.to_string()),
+        Err(String::from(
+            "At least 2 mines per column needs 20 mines, but the board has 15"
+        ))
+    );
+}
+
+#[test]
+fn limits_are_taken_from_the_command_line_and_the_config_file() {
+    let home = Home::new("limits_command_line");
+    let run = home.run(
+        &[
+            "--size",
+            "4",
+            "--mines",
+            "5",
+            "--min-per-column",
+            "2",
+            "--plain",
+        ],
+        "",
+    );
+    assert_eq!(
+        run.stderr.trim(),
+        "At least 2 mines per column needs 8 mines, but the board has 5"
+    );
+    assert_eq!(run.code, Some(2));
+
+    let home = Home::new("limits_config");
+    home.write("config.toml", "size = 4\nmines = 5\nmax_per_row = 1\n");
+    let run = home.run(&["--plain"], "");
+    assert_eq!(
+        run.stderr.trim(),
+        "At most 1 mines per row leaves room for 4 mines, but the board has 5"
+    );
+    assert_eq!(run.code, Some(2));
+}
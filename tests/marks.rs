@@ -0,0 +1,108 @@
+// what each step of marking a cell says it did, on the board, through a
+// game, and in the batch mode's JSON
+mod common;
+
+use common::{at, board, game_on, Home};
+use minesweeper::board::{FlagChange, FlagError};
+use minesweeper::command::Command;
+
+#[test]
+fn each_step_of_the_marking_cycle_says_what_it_did() {
+    let mut board = board("*.\n..");
+    let cell = at(0, 0);
+
+    assert_eq!(board.toggle_flag(cell).ok(), Some(FlagChange::FlagPlaced));
+    assert_eq!(board.toggle_flag(cell).ok(), Some(FlagChange::FlagRemoved));
+    assert_eq!(
+        board.toggle_question(cell).ok(),
+        Some(FlagChange::QuestionPlaced)
+    );
+    assert_eq!(
+        board.toggle_question(cell).ok(),
+        Some(FlagChange::QuestionRemoved)
+    );
+
+    // each mark replaces the other
+    assert_eq!(
+        board.toggle_question(cell).ok(),
+        Some(FlagChange::QuestionPlaced)
+    );
+    assert_eq!(board.toggle_flag(cell).ok(), Some(FlagChange::FlagPlaced));
+    assert_eq!(board.count_flags(), 1);
+    assert_eq!(
+        board.toggle_question(cell).ok(),
+        Some(FlagChange::QuestionPlaced)
+    );
+    assert_eq!(board.count_flags(), 0);
+    assert_eq!(board.toggle_flag(cell).ok(), Some(FlagChange::FlagPlaced));
+    assert!(
+        !board
+            .get_cell(cell)
+            .expect("The cell should be there")
+            .question
+    );
+}
+
+#[test]
+fn an_open_cell_or_one_off_the_board_can_t_be_marked() {
+    let mut board = board("*.\n..");
+    board.clear(at(1, 1)).expect("The cell should open");
+
+    assert!(matches!(
+        board.toggle_flag(at(1, 1)),
+        Err(FlagError::AlreadyCleared)
+    ));
+    assert!(matches!(
+        board.toggle_question(at(1, 1)),
+        Err(FlagError::AlreadyCleared)
+    ));
+    assert!(matches!(
+        board.toggle_flag(at(2, 0)),
+        Err(FlagError::CellNotFound)
+    ));
+}
+
+#[test]
+fn a_game_s_move_keeps_what_the_mark_did() {
+    let mut game = game_on("*.\n..");
+    let steps = [
+        (Command::Flag(at(0, 0)), FlagChange::FlagPlaced),
+        (Command::Question(at(0, 0)), FlagChange::QuestionPlaced),
+        (Command::Question(at(0, 0)), FlagChange::QuestionRemoved),
+        (Command::Flag(at(0, 1)), FlagChange::FlagPlaced),
+        (Command::Flag(at(0, 1)), FlagChange::FlagRemoved),
+    ];
+    for (command, change) in steps {
+        game.apply(0, command).expect("The mark should be allowed");
+        let made = game.moves().last().expect("The mark should be a move");
+        assert_eq!(made.mark().map(|(change, _)| change), Some(change));
+    }
+}
+
+#[test]
+fn the_batch_mode_says_what_each_mark_did() {
+    let home = Home::new("marks_json");
+    let args = ["--seed", "42", "--preset", "beginner", "--batch", "--json"];
+    let run = home.run(&args, "f 0 0\nq 0 0\nq 0 0\nf 1 1\nf 1 1\n");
+
+    let changes: Vec<&str> = run
+        .stdout
+        .lines()
+        .filter_map(|line| line.split_once(r#""change":""#))
+        .filter_map(|(_, rest)| rest.split_once('"'))
+        .map(|(change, _)| change)
+        .collect();
+    assert_eq!(
+        changes,
+        [
+            "flag_placed",
+            "question_placed",
+            "question_removed",
+            "flag_placed",
+            "flag_removed"
+        ]
+    );
+    assert!(run
+        .stdout
+        .contains(r#""change":"flag_placed","mines_left":9"#));
+}
@@ -0,0 +1,77 @@
+// .mbf files through the binary: a board is imported and played, written
+// back out as the same bytes, and a broken file is named with its fault
+mod common;
+
+use common::Home;
+use std::fs;
+
+// 9x9 with ten mines, in the order the board lists them: row by row
+const BEGINNER: [u8; 24] = [
+    9, 9, 0, 10, 0, 0, 8, 0, 2, 1, 1, 2, 7, 3, 4, 4, 5, 6, 3, 7, 0, 8, 8, 8,
+];
+
+#[test]
+fn an_imported_board_is_played_and_exported_as_it_came() {
+    let home = Home::new("mbf_round_trip");
+    let file = home.path.join("beginner.mbf");
+    let out = home.path.join("out.mbf");
+    fs::write(&file, BEGINNER).expect("The file should be written");
+
+    let script = format!("c 4 0\nexport mbf {}\n", out.display());
+    let run = home.run(
+        &[
+            "--import-mbf",
+            file.to_str().expect("The path should be UTF-8"),
+            "--plain",
+        ],
+        &script,
+    );
+    assert_eq!(run.stderr, "");
+    assert!(run.stdout.contains("Mines: 10"), "{}", run.stdout);
+    assert!(run
+        .stdout
+        .contains(&format!("Board written to {}", out.display())));
+    assert_eq!(
+        fs::read(&out).expect("The board should be exported"),
+        BEGINNER
+    );
+
+    // and the mine at (4,4) is where the file put it
+    let run = home.run(
+        &[
+            "--import-mbf",
+            file.to_str().expect("The path should be UTF-8"),
+            "--plain",
+        ],
+        "c 4 4\n",
+    );
+    assert_eq!(run.code, Some(1), "{}", run.stdout);
+}
+
+#[test]
+fn a_broken_file_is_turned_down_with_its_fault() {
+    let cases: [(&[u8], &str); 3] = [
+        (
+            &BEGINNER[..20],
+            "The file ends early: it should have at least 24 bytes, but has 20",
+        ),
+        (&[4, 4, 0, 1, 4, 0], "The mine at (0,4) is off the board"),
+        (
+            &[4, 4, 0, 2, 1, 1, 1, 1],
+            "The mine at (1,1) is listed twice",
+        ),
+    ];
+    for (index, (bytes, error)) in cases.into_iter().enumerate() {
+        let home = Home::new(&format!("mbf_broken_{index}"));
+        let file = home.path.join("broken.mbf");
+        fs::write(&file, bytes).expect("The file should be written");
+        let path = file.to_str().expect("The path should be UTF-8");
+
+        let run = home.run(&["--import-mbf", path, "--plain"], "");
+        assert_eq!(
+            run.stderr.trim(),
+            format!("Couldn't import {path}: {error}")
+        );
+        assert_eq!(run.code, Some(2), "{error}");
+    }
+}
@@ -0,0 +1,131 @@
+// the moving-mines variant: after each turn of it, flagged cells are as
+// they were, the open numbers show what they showed when they were opened,
+// and only the closed cells are recounted
+mod common;
+
+use common::{at, board, mines};
+use minesweeper::board::{Board, CellPosition, CellState};
+use minesweeper::command::Command;
+use minesweeper::game::{Game, GameEvent, MovingMines};
+use std::collections::HashMap;
+
+const LAYOUT: &str = "*.....\n......\n..*...\n....*.\n.*....\n.....*";
+
+fn game(seed: u64) -> Game {
+    Game::new(board(LAYOUT), 1).with_moving_mines(Some(MovingMines {
+        every: 1,
+        fraction: 0.5,
+        seed,
+    }))
+}
+
+fn flagged(board: &Board) -> Vec<(CellPosition, bool)> {
+    board
+        .rows()
+        .flatten()
+        .filter_map(|cell| match cell.state {
+            CellState::Bomb { flagged: true, .. } => Some((cell.position, true)),
+            CellState::Safe { flagged: true, .. } => Some((cell.position, false)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn mines_around(board: &Board, position: CellPosition) -> u8 {
+    board
+        .cells_around(position)
+        .filter(|cell| matches!(cell.state, CellState::Bomb { .. }))
+        .count() as u8
+}
+
+// plays `command`, checking that the mines that moved left the flags and
+// the open numbers alone; the number of mines that moved
+fn play(game: &mut Game, shown: &mut HashMap<CellPosition, u8>, command: Command) -> usize {
+    let before = mines(game.board());
+    game.apply(0, command).expect("The move should be played");
+    let board = game.board();
+
+    // a flagged cell is a mine now only if it was one before the move
+    for (position, mine) in flagged(board) {
+        assert_eq!(mine, before.contains(&position), "{position}");
+    }
+    assert_eq!(board.count_bombs(), 5);
+    for cell in board.rows().flatten() {
+        match cell.state {
+            CellState::Safe { open: true, .. } => {
+                let first = *shown.entry(cell.position).or_insert(cell.bombs_around);
+                assert_eq!(cell.bombs_around, first, "{}", cell.position);
+            }
+            _ => assert_eq!(
+                cell.bombs_around,
+                mines_around(board, cell.position),
+                "{}",
+                cell.position
+            ),
+        }
+    }
+    game.events()
+        .iter()
+        .find_map(|event| match event {
+            &GameEvent::MinesMoved(count) => Some(count),
+            _ => None,
+        })
+        .unwrap_or(0)
+}
+
+#[test]
+fn flags_stay_put_and_open_numbers_are_frozen() {
+    for seed in 0..20 {
+        let mut game = game(seed);
+        let mut shown = HashMap::new();
+
+        // a 1, opened on its own
+        assert_eq!(play(&mut game, &mut shown, Command::Clear(at(0, 1))), 3);
+
+        // wherever the mines went, a flag on one of them and on a safe cell
+        let mine = mines(game.board())[0];
+        play(&mut game, &mut shown, Command::Flag(mine));
+        let safe = game
+            .board()
+            .rows()
+            .flatten()
+            .find(|cell| matches!(cell.state, CellState::Safe { open: false, .. }))
+            .map(|cell| cell.position)
+            .expect("There should be a closed safe cell");
+        play(&mut game, &mut shown, Command::Flag(safe));
+
+        // and more turns, with neither flag ever moved or moved onto
+        let mut moved = 0;
+        for col in 0..6 {
+            if [mine, safe].contains(&at(5, col)) {
+                continue;
+            }
+            let question = Command::Question(at(5, col));
+            moved += play(&mut game, &mut shown, question.clone());
+            moved += play(&mut game, &mut shown, question);
+        }
+        assert!(moved > 0, "seed {seed}");
+        let mut kept = [(mine, true), (safe, false)];
+        kept.sort_by_key(|(at, _)| (at.row_index, at.col_index));
+        assert_eq!(flagged(game.board()), kept);
+    }
+}
+
+#[test]
+fn mines_only_move_when_it_is_their_turn() {
+    let mut game = Game::new(board(LAYOUT), 1).with_moving_mines(Some(MovingMines {
+        every: 3,
+        fraction: 1.0,
+        seed: 7,
+    }));
+    let mut shown = HashMap::new();
+    let before = mines(game.board());
+
+    play(&mut game, &mut shown, Command::Clear(at(0, 1)));
+    play(&mut game, &mut shown, Command::Question(at(5, 0)));
+    assert_eq!(mines(game.board()), before);
+    // every mine, on the third move
+    let moved = play(&mut game, &mut shown, Command::Question(at(5, 0)));
+    assert_eq!(moved, 5);
+    assert_ne!(mines(game.board()), before);
+}
@@ -0,0 +1,198 @@
+// boards one cell wide or one tall, and the others up to four by four: the
+// cells around each one, worked out the long way, and the mines, cascades,
+// chords and 3BV on them
+mod common;
+
+use common::{at, game_on};
+use minesweeper::analysis::three_bv;
+use minesweeper::board::{
+    cells_within, Board, CellPosition, CellState, ChordError, Dims, Layout, Mines, Symmetry,
+};
+use minesweeper::command::Command;
+use minesweeper::game::GameState;
+use minesweeper::rules::{Ruleset, RulesetError};
+use minesweeper::topology::Topology;
+use std::collections::BTreeSet;
+
+fn shapes() -> impl Iterator<Item = Dims> {
+    (1..=4).flat_map(|height| (1..=4).map(move |width| Dims { height, width }))
+}
+
+fn cells(dims: Dims) -> impl Iterator<Item = CellPosition> {
+    (0..dims.height).flat_map(move |row| (0..dims.width).map(move |col| at(row, col)))
+}
+
+fn sorted(positions: impl IntoIterator<Item = CellPosition>) -> BTreeSet<(usize, usize)> {
+    positions
+        .into_iter()
+        .map(|position| (position.row_index, position.col_index))
+        .collect()
+}
+
+// every cell on the board a step away in each direction, but not the cell
+fn brute_force(dims: Dims, center: CellPosition) -> BTreeSet<(usize, usize)> {
+    let apart = |a: usize, b: usize| a.abs_diff(b) <= 1;
+    sorted(cells(dims).filter(|&cell| {
+        cell != center
+            && apart(cell.row_index, center.row_index)
+            && apart(cell.col_index, center.col_index)
+    }))
+}
+
+#[test]
+fn the_cells_around_each_cell_are_those_a_step_away() {
+    for dims in shapes() {
+        let board = vec![vec![(); dims.width]; dims.height];
+        for cell in cells(dims) {
+            let expected = brute_force(dims, cell);
+            let message = format!("{cell} on {}x{}", dims.height, dims.width);
+            assert_eq!(sorted(cell.neighbors::<1>(dims)), expected, "{message}");
+            assert_eq!(sorted(dims.neighbours(cell)), expected, "{message}");
+
+            let mut within = sorted(cells_within(&board, cell, 1).map(|(at, _)| at));
+            assert!(
+                within.remove(&(cell.row_index, cell.col_index)),
+                "{message}"
+            );
+            assert_eq!(within, expected, "{message}");
+
+            // a line has at most two, and two lines at most five
+            let most = match dims.height.min(dims.width) {
+                1 => 2,
+                2 => 5,
+                _ => 8,
+            };
+            assert!(expected.len() <= most, "{message}");
+        }
+        // and nothing is around a cell off the board
+        assert_eq!(at(dims.height, 0).neighbors::<1>(dims).count(), 0);
+        assert_eq!(at(0, dims.width).neighbors::<1>(dims).count(), 0);
+    }
+}
+
+#[test]
+fn boards_without_rows_or_columns_cant_be_made() {
+    for (height, width) in [(0, 0), (0, 1), (1, 0), (0, 9), (9, 0)] {
+        let rules = Ruleset::builder()
+            .size(height, width)
+            .mines(Mines::Count(0))
+            .build();
+        assert_eq!(rules, Err(RulesetError::NoCells), "{height}x{width}");
+    }
+    // and laid out without the rules to stop them, they are simply empty
+    let symmetries = [
+        Symmetry::None,
+        Symmetry::Horizontal,
+        Symmetry::Vertical,
+        Symmetry::Rotational,
+    ];
+    for symmetry in symmetries {
+        let mut layout = Layout::new(Mines::Density(0.5));
+        layout.symmetry = symmetry;
+        for (height, width) in [(0, 0), (0, 3), (3, 0)] {
+            let board = Board::generate(7, height, width, &layout);
+            assert_eq!((board.count_bombs(), board.count_open()), (0, 0));
+        }
+    }
+    assert!("".parse::<Board>().is_err());
+    assert!("\n\n".parse::<Board>().is_err());
+}
+
+#[test]
+fn mines_are_laid_out_and_counted_on_every_shape() {
+    for dims in shapes() {
+        let total = dims.height * dims.width;
+        for mines in 0..total {
+            for seed in 0..5 {
+                let layout = Layout::new(Mines::Count(mines));
+                let board = Board::generate(seed, dims.height, dims.width, &layout);
+                assert_eq!((board.height(), board.width()), (dims.height, dims.width));
+                assert_eq!(board.count_bombs(), mines);
+                assert!(board.check_invariants().is_ok());
+                let is_bomb = |(row, col): (usize, usize)| {
+                    let cell = board.get_cell(at(row, col));
+                    matches!(cell.map(|cell| &cell.state), Some(CellState::Bomb { .. }))
+                };
+                // the numbers shown, which only the safe cells have
+                for cell in cells(dims) {
+                    if is_bomb((cell.row_index, cell.col_index)) {
+                        continue;
+                    }
+                    let around = brute_force(dims, cell)
+                        .into_iter()
+                        .filter(|&at| is_bomb(at));
+                    let around = around.count();
+                    let counted = board.get_cell(cell).map(|cell| cell.bombs_around);
+                    assert_eq!(counted, Some(around as u8), "{cell} seed {seed}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn a_line_opens_to_the_mines_either_side() {
+    let row: fn(usize) -> CellPosition = |index| at(0, index);
+    let column: fn(usize) -> CellPosition = |index| at(index, 0);
+    for (layout, along) in [("..*...*..", row), (".\n.\n*\n.\n.\n.\n*\n.\n.", column)] {
+        let mut game = game_on(layout);
+        game.apply(0, Command::Clear(along(0)))
+            .expect("The end should be safe");
+        // the blank at the end and the 1 before the first mine
+        assert_eq!(game.board().count_open(), 2, "{layout:?}");
+        // and the blank in the middle with the 1s either side of it
+        game.apply(0, Command::Clear(along(4)))
+            .expect("The middle should be safe");
+        assert_eq!(game.board().count_open(), 5, "{layout:?}");
+    }
+}
+
+#[test]
+fn a_line_chords_along_itself() {
+    let mut game = game_on(".*.");
+    game.apply(0, Command::Clear(at(0, 0)))
+        .expect("The end should be safe");
+    game.apply(0, Command::Flag(at(0, 1)))
+        .expect("The mine should take a flag");
+    assert_eq!(
+        game.board().chord_targets(at(0, 0)),
+        Err(ChordError::NothingToOpen)
+    );
+    game.apply(0, Command::Clear(at(0, 2)))
+        .expect("The other end should be safe");
+    assert_eq!(game.state(), GameState::Won);
+
+    let mut game = game_on("*\n.\n.");
+    game.apply(0, Command::Clear(at(1, 0)))
+        .expect("The middle should be safe");
+    game.apply(0, Command::Flag(at(0, 0)))
+        .expect("The mine should take a flag");
+    assert_eq!(
+        game.board().chord_targets(at(1, 0)).ok(),
+        Some(vec![at(2, 0)])
+    );
+    assert_eq!(
+        game.apply(0, Command::Chord(at(1, 0))).ok(),
+        Some(GameState::Won)
+    );
+}
+
+#[test]
+fn the_3bv_of_a_line_counts_its_openings_and_lone_numbers() {
+    let cases = [
+        (".", 1),
+        ("*.", 1),
+        ("...", 1),
+        (".*.", 2),
+        ("..*..", 2),
+        ("*.*.*", 2),
+        ("..*.*..", 3),
+    ];
+    for (layout, expected) in cases {
+        let row: Board = layout.parse().expect("The layout should parse");
+        assert_eq!(three_bv(&row), expected, "{layout}");
+        let column: String = layout.chars().map(|symbol| format!("{symbol}\n")).collect();
+        let column: Board = column.parse().expect("The layout should parse");
+        assert_eq!(three_bv(&column), expected, "{layout} standing up");
+    }
+}
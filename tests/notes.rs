@@ -0,0 +1,99 @@
+// notes on closed cells, which the rules never look at
+mod common;
+
+use common::{at, game_on};
+use minesweeper::board::{CellPosition, FlagError};
+use minesweeper::command::Command;
+use minesweeper::game::{Game, GameError, GameEvent, GameState};
+
+// one mine in the corner, so that clearing the far corner opens everything
+// but the cells around it
+const LAYOUT: &str = "*...\n....\n....";
+
+fn game() -> Game {
+    game_on(LAYOUT)
+}
+
+fn note(game: &mut Game, position: CellPosition, note: Option<char>) {
+    game.apply(0, Command::Note(position, note))
+        .expect("A closed cell should take a note");
+}
+
+#[test]
+fn a_note_can_be_put_on_replaced_and_taken_off() {
+    let mut game = game();
+
+    note(&mut game, at(0, 1), Some('A'));
+    assert_eq!(game.note(at(0, 1)), Some('A'));
+    assert_eq!(game.events(), [GameEvent::NoteChanged(at(0, 1), Some('A'))]);
+
+    note(&mut game, at(0, 1), Some('B'));
+    assert_eq!(game.notes(), [(at(0, 1), 'B')]);
+
+    note(&mut game, at(0, 1), None);
+    assert_eq!(game.note(at(0, 1)), None);
+    assert!(game.notes().is_empty());
+}
+
+#[test]
+fn notes_are_left_out_of_the_game() {
+    let mut game = game();
+
+    note(&mut game, at(0, 0), Some('M'));
+    note(&mut game, at(1, 1), Some('M'));
+    assert!(game.moves().is_empty());
+    assert_eq!(game.board().count_flags(), 0);
+
+    // nothing to undo, since a note isn't a move
+    assert!(matches!(
+        game.apply(0, Command::Undo),
+        Err(GameError::NothingToUndo)
+    ));
+}
+
+#[test]
+fn opening_a_cell_takes_its_note_off() {
+    let mut game = game();
+
+    note(&mut game, at(0, 1), Some('A'));
+    note(&mut game, at(2, 3), Some('B'));
+    note(&mut game, at(0, 0), Some('C'));
+    // the cascade opens both noted safe cells
+    assert_eq!(
+        game.apply(0, Command::Clear(at(2, 3))).ok(),
+        Some(GameState::Won)
+    );
+
+    assert_eq!(game.notes(), [(at(0, 0), 'C')]);
+}
+
+#[test]
+fn an_open_cell_cant_take_a_note() {
+    let mut game = game();
+    game.apply(0, Command::Clear(at(0, 1)))
+        .expect("(0,1) is safe");
+
+    assert!(matches!(
+        game.apply(0, Command::Note(at(0, 1), Some('A'))),
+        Err(GameError::Flag(FlagError::AlreadyCleared))
+    ));
+    assert!(matches!(
+        game.annotate(at(5, 5), Some('A')),
+        Err(FlagError::CellNotFound)
+    ));
+}
+
+#[test]
+fn notes_parse_and_print_alike() {
+    for line in ["note 3 4 A", "note 0 0 -"] {
+        let command: Command = line.parse().expect("The note should parse");
+        assert_eq!(command.to_string(), line);
+    }
+    assert_eq!(
+        "note E4 z".parse::<Command>().ok(),
+        Some(Command::Note(at(3, 4), Some('z')))
+    );
+    for bad in ["note 3 4", "note 3 4 AB", "note 3 4 7", "note"] {
+        assert!(bad.parse::<Command>().is_err(), "{bad} should not parse");
+    }
+}
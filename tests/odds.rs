@@ -0,0 +1,340 @@
+// the chance of winning without guessing, checked against trying every
+// placement of the mines on boards small enough to
+mod common;
+
+use common::at;
+use minesweeper::board::{Board, CellPosition, CellState, Mines};
+use minesweeper::command::Command;
+use minesweeper::game::{Game, GameState};
+use minesweeper::odds::{enumerate, estimate, render_estimate, Accuracy, Budget, WinEstimate};
+use minesweeper::solver::{bot_move, finishes, CellOrder, Deadline};
+use minesweeper::timer::{Clock, SystemClock};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+fn opened(board: Board, position: CellPosition) -> Game {
+    let mut game = Game::new(board, 1);
+    game.apply(0, Command::Clear(position))
+        .expect("The cell should be safe");
+    game
+}
+
+fn plenty() -> Budget {
+    Budget {
+        layouts: 10_000,
+        time: Duration::from_secs(60),
+    }
+}
+
+fn estimated(board: &Board, budget: &Budget) -> Option<WinEstimate> {
+    estimate(board, budget, &SystemClock, &mut StdRng::seed_from_u64(1))
+}
+
+// a second a reading, so that a budget runs out after a set number of them
+struct Ticking(AtomicU64);
+
+impl Clock for Ticking {
+    fn now(&self) -> Duration {
+        Duration::from_secs(self.0.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+// every way of choosing `picked` of `cells`, done the slow way
+fn placements(cells: &[CellPosition], picked: usize) -> Vec<Vec<CellPosition>> {
+    match (picked, cells.split_first()) {
+        (0, _) => vec![vec![]],
+        (_, None) => vec![],
+        (_, Some((&first, rest))) => {
+            let mut with: Vec<Vec<CellPosition>> = placements(rest, picked - 1);
+            for placement in &mut with {
+                placement.push(first);
+            }
+            with.extend(placements(rest, picked));
+            with
+        }
+    }
+}
+
+// how many placements of the mines on the closed cells give the open
+// numbers the player sees, and on how many of them the solver wins
+fn by_hand(board: &Board) -> (usize, usize) {
+    let closed: Vec<CellPosition> = board
+        .rows()
+        .flatten()
+        .filter(|cell| !matches!(cell.state, CellState::Safe { open: true, .. }))
+        .map(|cell| cell.position)
+        .collect();
+    let open: Vec<&minesweeper::board::Cell> = board
+        .rows()
+        .flatten()
+        .filter(|cell| matches!(cell.state, CellState::Safe { open: true, .. }))
+        .collect();
+
+    let (mut layouts, mut winnable) = (0, 0);
+    for mines in placements(&closed, board.count_bombs()) {
+        let mut bombs = vec![vec![false; board.width()]; board.height()];
+        for mine in mines {
+            bombs[mine.row_index][mine.col_index] = true;
+        }
+        let mut layout = Board::from_bombs(bombs);
+        let fits = open.iter().all(|cell| {
+            layout
+                .get_cell(cell.position)
+                .map(|other| other.bombs_around)
+                == Some(cell.bombs_around)
+        });
+        if fits {
+            for cell in &open {
+                let _ = layout.clear(cell.position);
+            }
+            layouts += 1;
+            winnable += usize::from(finishes(layout));
+        }
+    }
+    (layouts, winnable)
+}
+
+// two of the three 1s down the second column leave a mine in either of two
+// places at the top, and the solver only gets past it one way round in two
+// of every three layouts
+#[test]
+fn a_position_with_few_layouts_is_counted_exactly() {
+    let board: Board = "..*.*\n.....\n.....\n...**"
+        .parse()
+        .expect("The layout should parse");
+    let game = opened(board, at(0, 0));
+
+    let estimate = estimated(game.board(), &Budget::default());
+    assert_eq!(
+        estimate,
+        Some(WinEstimate {
+            winnable: 4,
+            layouts: 6,
+            accuracy: Accuracy::Exact,
+        })
+    );
+    assert_eq!(
+        render_estimate(estimate.as_ref()),
+        "Winnable without guessing: 66.7% (exact, over all 6 layouts of the mines that fit the numbers)"
+    );
+}
+
+#[test]
+fn exact_counts_match_trying_every_placement_of_the_mines() {
+    let mut checked = 0;
+    for seed in 0..60 {
+        let mut game = Game::new(Board::from_seed(seed, 4, 5, Mines::Count(4)), 1);
+        if game.apply(0, Command::Clear(at(0, 0))).ok() != Some(GameState::Playing) {
+            continue;
+        }
+
+        let estimate = estimated(game.board(), &plenty()).expect("The real layout should fit");
+        assert_eq!(estimate.accuracy, Accuracy::Exact, "seed {seed}");
+        assert_eq!(
+            (estimate.layouts, estimate.winnable),
+            by_hand(game.board()),
+            "seed {seed}"
+        );
+        checked += 1;
+    }
+    assert!(
+        checked > 10,
+        "Only {checked} boards were still being played"
+    );
+}
+
+#[test]
+fn a_wrong_flag_leaves_no_layout() {
+    let board: Board = "..*.*\n.....\n.....\n...**"
+        .parse()
+        .expect("The layout should parse");
+    let mut game = opened(board, at(0, 0));
+    // the 1 at (0, 1) only touches (0, 2), so the 1 at (1, 2) can't have
+    // another mine by it
+    game.apply(0, Command::Flag(at(0, 3)))
+        .expect("The cell should be closed");
+
+    let estimate = estimated(game.board(), &plenty());
+    assert_eq!(estimate, None);
+    assert_eq!(
+        render_estimate(estimate.as_ref()),
+        "No layout of the mines fits both the numbers and the flags; one of the flags must be wrong"
+    );
+}
+
+// 1820 layouts fit, 1065 of them winnable; 200 drawn at random come close
+#[test]
+fn a_small_budget_samples_layouts_as_often_as_they_would_come_up() {
+    let game = opened(Board::from_seed(10, 6, 6, Mines::Count(7)), at(0, 0));
+    let exact = estimated(game.board(), &plenty()).expect("The real layout should fit");
+    assert_eq!(
+        (exact.winnable, exact.layouts, exact.accuracy),
+        (1065, 1820, Accuracy::Exact)
+    );
+
+    let budget = Budget {
+        layouts: 200,
+        ..plenty()
+    };
+    let sampled = estimated(game.board(), &budget).expect("The real layout should fit");
+    assert_eq!(
+        (sampled.layouts, sampled.accuracy),
+        (200, Accuracy::Sampled)
+    );
+    assert!((sampled.chance() - exact.chance()).abs() <= sampled.margin());
+}
+
+#[test]
+fn the_time_budget_stops_an_estimate_early() {
+    let game = opened(Board::from_seed(10, 6, 6, Mines::Count(7)), at(0, 0));
+    let budget = Budget {
+        layouts: 200,
+        time: Duration::from_secs(3),
+    };
+    let clock = Ticking(AtomicU64::new(0));
+    let estimate = estimate(game.board(), &budget, &clock, &mut StdRng::seed_from_u64(1))
+        .expect("The real layout should fit");
+
+    // read once to start with and once after each layout
+    assert_eq!(estimate.layouts, 3);
+    assert_eq!(estimate.accuracy, Accuracy::Sampled);
+}
+
+// a number in the middle of a big board leaves almost all of it out of
+// reach, which only a few layouts are tried for
+#[test]
+fn huge_unconstrained_areas_fall_back_to_a_coarse_estimate() {
+    let board = Board::from_seed(3, 30, 30, Mines::Count(150));
+    let number = board
+        .rows()
+        .flatten()
+        .find(|cell| matches!(cell.state, CellState::Safe { .. }) && cell.bombs_around > 0)
+        .map(|cell| cell.position)
+        .expect("Some safe cell should be next to a mine");
+    let game = opened(board, number);
+
+    let estimate = estimated(game.board(), &plenty()).expect("The real layout should fit");
+    assert_eq!(estimate.accuracy, Accuracy::Coarse);
+    assert!(estimate.layouts <= 25);
+    assert!(render_estimate(Some(&estimate)).ends_with("this is only a coarse estimate"));
+}
+
+// each cell's chance of being a mine, in board order
+fn chances(board: &Board, order: CellOrder, split: bool) -> Vec<(CellPosition, f64)> {
+    let enumeration = enumerate(board, order, split, None).expect("The flags are all right");
+    assert!(enumeration.complete);
+    let mut chances = enumeration
+        .mine_chances()
+        .expect("The real layout should fit");
+    chances.sort_by_key(|&(at, _)| (at.row_index, at.col_index));
+    chances
+}
+
+// the game after opening cells of `board` the solver can work out, until
+// there are at least `open` open or it can't work any more out
+fn opened_to(board: Board, open: usize) -> Option<Game> {
+    let first = board
+        .rows()
+        .flatten()
+        .find(|cell| matches!(cell.state, CellState::Safe { .. }) && cell.bombs_around == 0)?
+        .position;
+    let mut game = opened(board, first);
+    while game.board().count_open() < open && game.state() == GameState::Playing {
+        let Some(next) = bot_move(game.board()) else {
+            break;
+        };
+        game.apply(0, next.command).ok()?;
+    }
+    (game.state() == GameState::Playing).then_some(game)
+}
+
+// a frontier that splits in parts has as many layouts as the parts' counts
+// multiplied together, which gives each cell the same chance as searching
+// it whole does, whatever order the cells are decided in
+#[test]
+fn splitting_the_frontier_gives_the_same_chances_as_searching_it_whole() {
+    let orders = [
+        CellOrder::Declaration,
+        CellOrder::MostConstrained,
+        CellOrder::Breadth,
+    ];
+    let mut split = 0;
+    for seed in 0..40 {
+        let Some(game) = opened_to(Board::from_seed(seed, 8, 10, Mines::Count(14)), 20) else {
+            continue;
+        };
+        let board = game.board();
+        let whole = chances(board, CellOrder::Declaration, false);
+        for order in orders {
+            for parts in [false, true] {
+                let other = chances(board, order, parts);
+                assert_eq!(other.len(), whole.len(), "seed {seed}");
+                for ((at, chance), (other_at, other_chance)) in whole.iter().zip(&other) {
+                    assert_eq!(at, other_at);
+                    assert!(
+                        (chance - other_chance).abs() < 1e-9,
+                        "seed {seed}, {order:?}"
+                    );
+                }
+            }
+        }
+        let parts = enumerate(board, CellOrder::Breadth, true, None).expect("The flags fit");
+        split += usize::from(parts.parts() > 1);
+    }
+    assert!(split > 5, "Only {split} frontiers split");
+}
+
+#[test]
+fn layouts_counted_in_parts_match_trying_every_placement_of_the_mines() {
+    for seed in 0..60 {
+        let mut game = Game::new(Board::from_seed(seed, 4, 5, Mines::Count(4)), 1);
+        if game.apply(0, Command::Clear(at(0, 0))).ok() != Some(GameState::Playing) {
+            continue;
+        }
+
+        let enumeration = enumerate(game.board(), CellOrder::Breadth, true, None)
+            .expect("The flags are all right");
+        let (layouts, _) = by_hand(game.board());
+        assert!(
+            (enumeration.layouts() - layouts as f64).abs() < 1e-6,
+            "seed {seed}"
+        );
+    }
+}
+
+// every `every`th safe cell of `board` opened, as if from a long way into a
+// game: numbers all over, too many layouts of the mines around them to
+// search through quickly
+fn scattered(board: Board, every: usize) -> Game {
+    let safe: Vec<CellPosition> = board
+        .rows()
+        .flatten()
+        .filter(|cell| matches!(cell.state, CellState::Safe { .. }))
+        .map(|cell| cell.position)
+        .collect();
+    let mut game = Game::new(board, 1);
+    for at in safe.into_iter().step_by(every) {
+        // some of them opened with the ones before already
+        let _ = game.apply(0, Command::Clear(at));
+    }
+    game
+}
+
+// a deadline that has already passed stops the search the first time it
+// looks at the time, with only the layouts it had found by then
+#[test]
+fn a_search_out_of_time_says_it_is_incomplete() {
+    let game = scattered(Board::from_seed(0, 16, 30, Mines::Count(99)), 9);
+    let clock = Ticking(AtomicU64::new(0));
+    let deadline = Deadline {
+        clock: &clock,
+        at: Duration::ZERO,
+    };
+
+    let enumeration = enumerate(game.board(), CellOrder::Declaration, false, Some(deadline))
+        .expect("The flags are all right");
+    assert!(!enumeration.complete);
+    assert_eq!(clock.0.load(Ordering::Relaxed), 1);
+}
@@ -0,0 +1,174 @@
+// a paused game: the timer stands still until it is resumed, and no move
+// is played meanwhile, nor the board shown
+mod common;
+
+use common::{at, board, SetByHand, Timed};
+use minesweeper::command::Command;
+use minesweeper::driver::{run_game, GameConfig, GameOutput, Hooks, Io, Screen};
+use minesweeper::game::{Game, GameError, GameOutcome, GameState};
+use minesweeper::i18n::{text, Msg};
+use minesweeper::reference::render_commands;
+use std::sync::Arc;
+use std::time::Duration;
+
+// walled off, so that each clear opens the one cell
+const LAYOUT: &str = "..*\n*.*\n**.";
+
+fn game(clock: &Arc<SetByHand>) -> Game {
+    Game::with_clock(board(LAYOUT), 1, clock.clone())
+}
+
+#[test]
+fn time_spent_paused_is_left_out_and_moves_are_turned_down() {
+    let clock = Arc::new(SetByHand::default());
+    let mut game = game(&clock);
+    let at_second = |second: u64, game: &mut Game, command: Command| {
+        clock.set(second);
+        game.apply(0, command)
+    };
+
+    at_second(10, &mut game, Command::Clear(at(0, 0))).expect("The cell should open");
+    at_second(20, &mut game, Command::Pause).expect("The game should pause");
+    assert_eq!(game.elapsed(), Duration::from_secs(10));
+
+    let opened = game.board().count_open();
+    for command in [
+        Command::Clear(at(0, 1)),
+        Command::Flag(at(0, 2)),
+        Command::Undo,
+        Command::Pause,
+    ] {
+        assert!(matches!(
+            at_second(500, &mut game, command),
+            Err(GameError::Paused)
+        ));
+    }
+    assert_eq!(game.board().count_open(), opened);
+    assert_eq!(game.elapsed(), Duration::from_secs(10));
+
+    at_second(900, &mut game, Command::Resume).expect("The game should resume");
+    assert!(matches!(
+        at_second(901, &mut game, Command::Resume),
+        Err(GameError::NotPaused)
+    ));
+    at_second(901, &mut game, Command::Clear(at(0, 1))).expect("The cell should open");
+    at_second(903, &mut game, Command::Clear(at(1, 1))).expect("The cell should open");
+    let state = at_second(905, &mut game, Command::Clear(at(2, 2))).expect("The cell should open");
+
+    assert_eq!(state, GameState::Won);
+    // 10 seconds before the pause and 5 after it
+    let Some(GameOutcome::Won(stats)) = game.outcome() else {
+        panic!("The game should be won");
+    };
+    assert_eq!(stats.elapsed, Duration::from_secs(15));
+}
+
+// the screens shown, by name
+#[derive(Default)]
+struct Screens(Vec<String>);
+
+impl GameOutput for Screens {
+    fn show(&mut self, screen: &Screen) {
+        let name = match screen {
+            Screen::Board(_) => "board".to_string(),
+            Screen::Revealed(_) => "revealed".to_string(),
+            Screen::Paused => "paused".to_string(),
+            Screen::Message(message) => message.clone(),
+            _ => return,
+        };
+        self.0.push(name);
+    }
+}
+
+#[test]
+fn the_game_loop_hides_the_board_and_waits_for_resume() {
+    let clock = Arc::new(SetByHand::default());
+    let mut input = Timed {
+        clock: clock.clone(),
+        lines: [
+            (10, "c 0 0"),
+            (20, "pause"),
+            // neither played nor answered while paused
+            (300, "c 0 1"),
+            (400, "f 0 2"),
+            (900, "resume"),
+            (904, "c 0 1"),
+            (906, "c 1 1"),
+            (910, "c 2 2"),
+        ]
+        .into(),
+    };
+    let mut output = Screens::default();
+    let config = GameConfig {
+        game: game(&clock),
+        retries: None,
+        idle_limit: None,
+        prompt: Msg::EnterRowOrCommand,
+        players: 1,
+    };
+    let io = Io {
+        input: &mut input,
+        output: &mut output,
+    };
+    let outcome = run_game(config, io, Hooks::default());
+
+    let GameOutcome::Won(stats) = outcome else {
+        panic!("The game should be won: {outcome:?}");
+    };
+    assert_eq!(stats.elapsed, Duration::from_secs(20));
+    assert_eq!(stats.moves, 4);
+    assert_eq!(
+        output.0,
+        ["board", "board", "paused", "board", "board", "board", "revealed"]
+    );
+}
+
+// the commands are there to read while the board is hidden, and the game
+// can be given up without showing it again
+#[test]
+fn help_and_resigning_are_answered_while_paused() {
+    let clock = Arc::new(SetByHand::default());
+    let mut input = Timed {
+        clock: clock.clone(),
+        lines: [
+            (10, "c 0 0"),
+            (20, "pause"),
+            (30, "help"),
+            (40, "resign"),
+            (50, "n"),
+            (60, "resign"),
+            (70, "y"),
+        ]
+        .into(),
+    };
+    let mut output = Screens::default();
+    let config = GameConfig {
+        game: game(&clock),
+        retries: None,
+        idle_limit: None,
+        prompt: Msg::EnterRowOrCommand,
+        players: 1,
+    };
+    let io = Io {
+        input: &mut input,
+        output: &mut output,
+    };
+    let outcome = run_game(config, io, Hooks::default());
+
+    let GameOutcome::Resigned { stats, .. } = outcome else {
+        panic!("The game should be resigned: {outcome:?}");
+    };
+    // nothing of the time paused, up to the resignation
+    assert_eq!(stats.elapsed, Duration::from_secs(10));
+    assert_eq!(
+        output.0,
+        [
+            "board",
+            "board",
+            "paused",
+            &render_commands(),
+            text(Msg::MoveLeftUndone),
+            "revealed"
+        ]
+    );
+}
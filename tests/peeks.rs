@@ -0,0 +1,157 @@
+// peeking at closed cells: each peek tells whether one cell is a mine
+// without opening it, until the game's peeks run out, and a game that used
+// any is assisted
+mod common;
+
+use common::{at, board, Home};
+use minesweeper::board::{CellState, FlagError};
+use minesweeper::command::Command;
+use minesweeper::game::{Game, GameError, GameOutcome, GameState, MoveOutcome};
+
+// walled off, so that each clear opens the one cell
+const LAYOUT: &str = "..*\n*.*\n**.";
+
+fn game(peeks: u32) -> Game {
+    Game::new(board(LAYOUT), 1).with_peeks(peeks)
+}
+
+fn peek(game: &mut Game, row: usize, col: usize) -> Result<GameState, GameError> {
+    game.apply(0, Command::Peek(at(row, col)))
+}
+
+fn last_outcome(game: &Game) -> MoveOutcome {
+    game.moves().last().expect("There should be a move").outcome
+}
+
+fn win(game: &mut Game) -> GameOutcome {
+    for (row, col) in [(0, 0), (0, 1), (1, 1), (2, 2)] {
+        game.apply(0, Command::Clear(at(row, col)))
+            .expect("The cell should open");
+    }
+    game.outcome().expect("The game should be over")
+}
+
+#[test]
+fn a_peeked_mine_is_marked_and_the_game_goes_on() {
+    let mut game = game(2);
+    assert!(matches!(peek(&mut game, 0, 2), Ok(GameState::Playing)));
+    assert!(matches!(last_outcome(&game), MoveOutcome::Peeked(true)));
+
+    let cell = game
+        .board()
+        .get_cell(at(0, 2))
+        .expect("The cell is on the board");
+    assert!(cell.peeked);
+    assert!(matches!(
+        cell.state,
+        CellState::Bomb {
+            exploded: false,
+            ..
+        }
+    ));
+    assert_eq!(game.lives(), 1);
+    assert_eq!(game.peeks_left(), 1);
+
+    // and once is enough
+    assert!(matches!(
+        peek(&mut game, 0, 2),
+        Err(GameError::AlreadyPeeked)
+    ));
+    assert_eq!(game.peeks_left(), 1);
+}
+
+#[test]
+fn a_peeked_safe_cell_stays_closed() {
+    let mut game = game(1);
+    peek(&mut game, 1, 1).expect("The peek should be played");
+    assert!(matches!(last_outcome(&game), MoveOutcome::Peeked(false)));
+    let cell = game
+        .board()
+        .get_cell(at(1, 1))
+        .expect("The cell is on the board");
+    assert!(cell.peeked);
+    assert!(matches!(cell.state, CellState::Safe { open: false, .. }));
+    assert_eq!(game.board().count_open(), 0);
+}
+
+#[test]
+fn peeks_run_out() {
+    let mut game = game(2);
+    peek(&mut game, 0, 2).expect("The first peek should be played");
+    peek(&mut game, 1, 1).expect("The second peek should be played");
+    assert_eq!(game.peeks_left(), 0);
+
+    let moves = game.moves().len();
+    assert!(matches!(peek(&mut game, 2, 2), Err(GameError::NoPeeksLeft)));
+    assert!(!game
+        .board()
+        .get_cell(at(2, 2))
+        .is_some_and(|cell| cell.peeked));
+    assert_eq!(game.moves().len(), moves);
+
+    // a game that grants none has none to spend
+    assert!(matches!(
+        peek(&mut Game::new(board(LAYOUT), 1), 0, 2),
+        Err(GameError::NoPeeksLeft)
+    ));
+
+    // and an open cell can't be peeked at, nor does trying use a peek
+    let mut game = self::game(1);
+    game.apply(0, Command::Clear(at(0, 0)))
+        .expect("The cell should open");
+    assert!(matches!(
+        peek(&mut game, 0, 0),
+        Err(GameError::Flag(FlagError::AlreadyCleared))
+    ));
+    assert_eq!(game.peeks_left(), 1);
+}
+
+#[test]
+fn a_game_that_peeked_is_assisted() {
+    let mut peeked = game(3);
+    peek(&mut peeked, 1, 0).expect("The peek should be played");
+    assert!(peeked.assisted());
+    let GameOutcome::Won(stats) = win(&mut peeked) else {
+        panic!("The game should be won");
+    };
+    assert!(stats.assisted);
+
+    // having peeks isn't enough, only using one
+    let mut unused = game(3);
+    let GameOutcome::Won(stats) = win(&mut unused) else {
+        panic!("The game should be won");
+    };
+    assert!(!unused.assisted());
+    assert!(!stats.assisted);
+}
+
+// through the binary, each peek stays marked on the board, a mine as `!`
+// and a safe cell as `~`, for the rest of the game
+#[test]
+fn peeks_stay_marked_on_the_board() {
+    let args = [
+        "--seed", "42", "--preset", "beginner", "--plain", "--peeks", "2",
+    ];
+    let run = Home::new("peeks_marked").run(&args, "peek 0 0\npeek 2 0\nc 2 7\n");
+    assert!(
+        run.stdout.contains("peek (0,0): a mine\n"),
+        "{}",
+        run.stdout
+    );
+
+    let last: Vec<&str> = run
+        .stdout
+        .lines()
+        .skip_while(|line| !line.contains("Moves: 3"))
+        .skip(1)
+        .take(3)
+        .collect();
+    assert_eq!(
+        last,
+        [
+            "! # # # # # 1 0 0",
+            "# # # # # # 1 0 0",
+            "~ # # # # 2 1 0 0"
+        ]
+    );
+}
@@ -0,0 +1,119 @@
+// the neighbour and offset helpers against the obvious way of finding the
+// same cells: going through every cell of the board and keeping the close
+// ones. Every board from 1x1 to 6x6 is tried, from every cell on it and a
+// few off it
+mod common;
+
+use common::at;
+use minesweeper::board::{cells_within, CellPosition, Dims};
+
+const MAX_SIDE: usize = 6;
+
+fn all_dims() -> impl Iterator<Item = Dims> {
+    (1..=MAX_SIDE).flat_map(|height| (1..=MAX_SIDE).map(move |width| Dims { height, width }))
+}
+
+// two past the edges as well, and the furthest a position can be
+fn positions(dims: Dims) -> impl Iterator<Item = CellPosition> {
+    (0..dims.height + 2)
+        .flat_map(move |row| (0..dims.width + 2).map(move |col| at(row, col)))
+        .chain([
+            at(usize::MAX, 0),
+            at(0, usize::MAX),
+            at(usize::MAX, usize::MAX),
+        ])
+}
+
+fn every_cell(dims: Dims) -> Vec<CellPosition> {
+    (0..dims.height)
+        .flat_map(|row| (0..dims.width).map(move |col| at(row, col)))
+        .collect()
+}
+
+fn brute_force(dims: Dims, center: CellPosition, radius: usize) -> Vec<CellPosition> {
+    if !dims.contains(center) {
+        return vec![];
+    }
+    every_cell(dims)
+        .into_iter()
+        .filter(|cell| {
+            cell.row_index.abs_diff(center.row_index) <= radius
+                && cell.col_index.abs_diff(center.col_index) <= radius
+        })
+        .collect()
+}
+
+fn without(cells: Vec<CellPosition>, center: CellPosition) -> Vec<CellPosition> {
+    cells.into_iter().filter(|&cell| cell != center).collect()
+}
+
+#[test]
+fn neighbors_are_the_cells_close_by() {
+    for dims in all_dims() {
+        for center in positions(dims) {
+            let one: Vec<CellPosition> = center.neighbors::<1>(dims).collect();
+            let two: Vec<CellPosition> = center.neighbors::<2>(dims).collect();
+            let far: Vec<CellPosition> = center.neighbors::<{ usize::MAX }>(dims).collect();
+
+            assert_eq!(
+                one,
+                without(brute_force(dims, center, 1), center),
+                "{dims:?} {center}"
+            );
+            assert_eq!(
+                two,
+                without(brute_force(dims, center, 2), center),
+                "{dims:?} {center}"
+            );
+            assert_eq!(far, without(brute_force(dims, center, usize::MAX), center));
+        }
+    }
+}
+
+#[test]
+fn cells_within_agrees_with_the_clamped_ranges() {
+    for dims in all_dims() {
+        let board: Vec<Vec<CellPosition>> = (0..dims.height)
+            .map(|row| (0..dims.width).map(|col| at(row, col)).collect())
+            .collect();
+        for center in positions(dims) {
+            for radius in [0, 1, 2, MAX_SIDE, usize::MAX] {
+                let found: Vec<CellPosition> = cells_within(&board, center, radius)
+                    .map(|(at, _)| at)
+                    .collect();
+                let (rows, cols) = dims.clamp_range(center, radius);
+
+                let expected = brute_force(dims, center, radius);
+                assert_eq!(found, expected, "{dims:?} {center} radius {radius}");
+                assert_eq!(rows.len() * cols.len(), expected.len(), "{dims:?} {center}");
+            }
+        }
+    }
+}
+
+#[test]
+fn offsets_stay_on_the_board() {
+    let offsets = [-3, -2, -1, 0, 1, 2, 3, isize::MIN, isize::MAX];
+    for dims in all_dims() {
+        for center in positions(dims) {
+            for rows in offsets {
+                for cols in offsets {
+                    let expected = Some(at(
+                        center.row_index.wrapping_add_signed(rows),
+                        center.col_index.wrapping_add_signed(cols),
+                    ))
+                    .filter(|&target| {
+                        center.row_index.checked_add_signed(rows).is_some()
+                            && center.col_index.checked_add_signed(cols).is_some()
+                            && every_cell(dims).contains(&target)
+                    });
+                    assert_eq!(
+                        center.checked_offset(rows, cols, dims),
+                        expected,
+                        "{dims:?} {center} by ({rows},{cols})"
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+// the hot-seat race through the binary, both players scripted on the same
+// tiny board: the first to clear it wins, and a mine hands the win over
+mod common;
+
+use common::{safe_cells, Home, Run};
+use minesweeper::board::{Board, CellPosition, Mines};
+use minesweeper::rules::Ruleset;
+
+const SEED: u64 = 3;
+
+// one mine on four cells, so each safe cell is a 1 and opens on its own
+fn board() -> Board {
+    let rules = Ruleset::builder().size(2, 2).mines(Mines::Count(1)).build();
+    rules.expect("The rules should build").board(SEED)
+}
+
+// a turn: enter to start it, the cell, then enter to hand over
+fn turn(at: CellPosition) -> String {
+    format!("\n{}\n{}\n\n", at.row_index, at.col_index)
+}
+
+fn race(name: &str, turns: &[CellPosition]) -> Run {
+    let input: String = turns.iter().map(|&at| turn(at)).collect();
+    let seed = SEED.to_string();
+    let args = [
+        "race", "--seed", &seed, "--size", "2", "--mines", "1", "--plain",
+    ];
+    Home::new(name).run(&args, &input)
+}
+
+#[test]
+fn the_first_to_clear_the_board_wins() {
+    let safe = safe_cells(&board());
+    let turns = [safe[0], safe[0], safe[1], safe[1], safe[2]];
+    let run = race("race_first", &turns);
+
+    assert!(
+        run.stdout.contains("Player 1: 3 moves, 3 cells opened"),
+        "{}",
+        run.stdout
+    );
+    assert!(run.stdout.contains("Player 2: 2 moves, 2 cells opened"));
+    assert_eq!(run.stdout.lines().last(), Some("Player 1 wins"));
+}
+
+#[test]
+fn the_last_to_survive_wins() {
+    let board = board();
+    let mine = common::mines(&board)[0];
+    let run = race("race_survive", &[safe_cells(&board)[0], mine]);
+
+    assert!(run.stdout.contains("Player 2 hit a bomb"), "{}", run.stdout);
+    assert!(run.stdout.contains("Player 2: 1 moves, 0 cells opened"));
+    assert_eq!(run.stdout.lines().last(), Some("Player 1 wins"));
+}
+
+// each turn shows only its own player's board
+#[test]
+fn each_player_sees_only_their_own_board() {
+    let safe = safe_cells(&board());
+    let run = race("race_boards", &[safe[0], safe[1]]);
+    let boards: Vec<&str> = run
+        .stdout
+        .lines()
+        .filter(|line| line.ends_with("'s board:"))
+        .collect();
+    assert_eq!(
+        boards,
+        [
+            "Player 1's board:",
+            "Player 2's board:",
+            "Player 1's board:"
+        ]
+    );
+    assert_eq!(
+        run.stdout.lines().last(),
+        Some("The race ended before anyone won")
+    );
+}
@@ -0,0 +1,156 @@
+// the help and the reference are written from the same table the parser is
+// built from, so that every command it takes is listed and every command
+// listed is taken
+use minesweeper::board::CellPosition;
+use minesweeper::command::{commands, find_command, Area, Command, CommandError, Export};
+use minesweeper::reference::{render_commands, render_reference, usage};
+use std::path::PathBuf;
+
+const AT: CellPosition = CellPosition {
+    row_index: 3,
+    col_index: 4,
+};
+
+// one of each command; the match has no wildcard, so that a new command
+// can't be left out of it
+fn every_command() -> Vec<Command> {
+    let every = vec![
+        Command::Clear(AT),
+        Command::ClearArea(Area::Row(2)),
+        Command::Flag(AT),
+        Command::Chord(AT),
+        Command::ChordCheck(AT),
+        Command::Question(AT),
+        Command::Note(AT, Some('A')),
+        Command::Peek(AT),
+        Command::Undo,
+        Command::Rewind(4),
+        Command::Pause,
+        Command::Resume,
+        Command::Share,
+        Command::BoardInfo,
+        Command::Analyze,
+        Command::Remaining,
+        Command::WinProb,
+        Command::Hint { why: true },
+        Command::Moves { all: false },
+        Command::Dump,
+        Command::Export(Export::Mbf(PathBuf::from("board.mbf"))),
+        Command::SaveAs(String::from("before the guess")),
+        Command::Watch,
+        Command::Resign,
+        Command::Help,
+    ];
+    for command in &every {
+        match command {
+            Command::Clear(_)
+            | Command::ClearArea(_)
+            | Command::Flag(_)
+            | Command::Chord(_)
+            | Command::ChordCheck(_)
+            | Command::Question(_)
+            | Command::Note(..)
+            | Command::Peek(_)
+            | Command::Undo
+            | Command::Rewind(_)
+            | Command::Pause
+            | Command::Resume
+            | Command::Share
+            | Command::BoardInfo
+            | Command::Analyze
+            | Command::Remaining
+            | Command::WinProb
+            | Command::Hint { .. }
+            | Command::Moves { .. }
+            | Command::Dump
+            | Command::Export(_)
+            | Command::SaveAs(_)
+            | Command::Watch
+            | Command::Resign
+            | Command::Help => (),
+        }
+    }
+    every
+}
+
+#[test]
+fn every_command_the_parser_takes_is_listed() {
+    let (help, reference) = (render_commands(), render_reference());
+    for command in every_command() {
+        let written = command.to_string();
+        let Ok(parsed) = written.parse::<Command>() else {
+            // only parsed by a build with the `debug` feature
+            assert_eq!(command, Command::Dump);
+            continue;
+        };
+        assert_eq!(parsed, command);
+
+        let name = written
+            .split_whitespace()
+            .next()
+            .expect("A command should have a name");
+        let spec = find_command(name).unwrap_or_else(|| panic!("{name} isn't in the table"));
+        assert!(
+            help.contains(&format!("  {}", usage(spec))),
+            "{name} isn't in the help"
+        );
+        assert!(
+            reference.contains(&format!("    {}", usage(spec))),
+            "{name} isn't listed"
+        );
+    }
+}
+
+#[test]
+fn every_command_listed_is_taken_by_the_parser() {
+    let (help, reference) = (render_commands(), render_reference());
+    for spec in commands() {
+        assert!(help.contains(&usage(spec)), "{}", spec.name);
+        assert!(
+            reference.contains(&format!("Example: {}", spec.example)),
+            "{}",
+            spec.name
+        );
+
+        let example = spec.example.parse::<Command>();
+        let example = example.unwrap_or_else(|e| panic!("{}: {e}", spec.example));
+        let again = example.to_string();
+        let name = again
+            .split_whitespace()
+            .next()
+            .expect("A command should have a name");
+        assert!(find_command(name).is_some(), "{again}");
+
+        // every name goes to the same command as the first one
+        for alias in spec.names() {
+            let line = spec.example.replacen(spec.name, alias, 1);
+            assert_eq!(
+                line.parse::<Command>().ok(),
+                Some(example.clone()),
+                "{line}"
+            );
+        }
+    }
+}
+
+#[test]
+fn names_that_arent_listed_arent_commands() {
+    for name in ["clearall", "fl", "chords", "help!", "3"] {
+        assert!(find_command(name).is_none(), "{name}");
+        let parsed = format!("{name} 3 4").parse::<Command>();
+        assert!(matches!(parsed, Err(CommandError::Unknown(unknown)) if unknown == name));
+    }
+
+    // and `save` is only a command with `as` after it
+    let parsed = "save 3 4".parse::<Command>();
+    assert!(matches!(parsed, Err(CommandError::Unknown(name)) if name == "save"));
+}
+
+#[test]
+fn no_two_commands_go_by_the_same_name() {
+    let mut names: Vec<&str> = commands().flat_map(|spec| spec.names()).collect();
+    let listed = names.len();
+    names.sort();
+    names.dedup();
+    assert_eq!(names.len(), listed);
+}
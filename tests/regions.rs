@@ -0,0 +1,116 @@
+// the closed cells split into regions, on positions played out by hand,
+// and what the open numbers around each still ask for
+mod common;
+
+use common::{at, game_on};
+use minesweeper::analysis::{closed_regions, render_analysis, Region};
+use minesweeper::board::CellPosition;
+use minesweeper::command::Command;
+use minesweeper::game::Game;
+
+// a mine under every other cell of the top row, and a safe cell after them
+// that no cascade reaches
+const ROW: &str = "*.*.*.\n......\n......";
+
+fn played(layout: &str, commands: &[Command]) -> Game {
+    let mut game = game_on(layout);
+    for command in commands {
+        game.apply(0, command.clone())
+            .expect("The move should be played");
+    }
+    game
+}
+
+fn cells(positions: &[(usize, usize)]) -> Vec<CellPosition> {
+    positions.iter().map(|&(row, col)| at(row, col)).collect()
+}
+
+#[test]
+fn open_cells_split_the_closed_ones_and_numbers_count_for_each_side() {
+    let opened = [
+        Command::Clear(at(2, 2)),
+        Command::Clear(at(0, 1)),
+        Command::Clear(at(0, 3)),
+    ];
+    let game = played(ROW, &opened);
+    assert_eq!(
+        closed_regions(game.board()),
+        [
+            // (0,1), (1,0) and (1,1): 2, 1 and 2
+            Region {
+                cells: cells(&[(0, 0)]),
+                frontier: 1,
+                numbers: 3,
+                mines_needed: 5,
+            },
+            // the 2s either side and the 2, 1 and 2 below
+            Region {
+                cells: cells(&[(0, 2)]),
+                frontier: 1,
+                numbers: 5,
+                mines_needed: 9,
+            },
+            // the 2s at (0,3) and (1,3), and the 1s at (1,4) and (1,5)
+            Region {
+                cells: cells(&[(0, 4), (0, 5)]),
+                frontier: 2,
+                numbers: 4,
+                mines_needed: 6,
+            },
+        ]
+    );
+
+    // a flag takes its cell out, and a mine off what every number next to
+    // it asks for
+    let flagged = played(ROW, &[&opened[..], &[Command::Flag(at(0, 2))]].concat());
+    let regions = closed_regions(flagged.board());
+    assert_eq!(regions.len(), 2);
+    assert_eq!(regions[0].cells, cells(&[(0, 0)]));
+    assert_eq!(regions[0].mines_needed, 3);
+    assert_eq!(regions[1].cells, cells(&[(0, 4), (0, 5)]));
+    assert_eq!(regions[1].mines_needed, 4);
+}
+
+#[test]
+fn only_the_edge_of_a_region_is_its_frontier() {
+    // a block of nine mines in the corner, with everything else opened
+    let layout = "***...\n***...\n***...\n......\n......\n......";
+    let game = played(layout, &[Command::Clear(at(5, 5))]);
+    let regions = closed_regions(game.board());
+
+    let block: Vec<(usize, usize)> = (0..3)
+        .flat_map(|row| (0..3).map(move |col| (row, col)))
+        .collect();
+    assert_eq!(
+        regions,
+        [Region {
+            cells: cells(&block),
+            // the bottom row and the right column, but not the four inside
+            frontier: 5,
+            // 2, 3, 2 and 1 along the bottom, then 2, 3 and 2 up the side
+            numbers: 7,
+            mines_needed: 15,
+        }]
+    );
+}
+
+#[test]
+fn the_analysis_labels_each_region_on_the_board() {
+    let game = played(ROW, &[Command::Clear(at(2, 2)), Command::Clear(at(0, 1))]);
+    let expected = [
+        "Closed cells: 5, mines not yet flagged: 3",
+        "A 2 B B B B",
+        "1 2 1 2 1 1",
+        "0 0 0 0 0 0",
+        "A: 1 cells, 1 of them next to numbers that ask for 5 more mines between them",
+        "B: 4 cells, 4 of them next to numbers that ask for 9 more mines between them",
+    ];
+    assert_eq!(render_analysis(game.board()), expected.join("\n"));
+
+    // and before a move, there are no numbers to go by
+    let fresh = render_analysis(game_on(ROW).board());
+    assert!(
+        fresh.ends_with("\nA: 18 cells, none of them next to a number"),
+        "{fresh}"
+    );
+}
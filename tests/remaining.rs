@@ -0,0 +1,58 @@
+// counting what the numbers prove, without saying where, on boards made by
+// hand
+mod common;
+
+use common::{at, game_on};
+use minesweeper::analysis::Remaining;
+use minesweeper::board::{Board, CellPosition};
+use minesweeper::command::Command;
+use minesweeper::game::Game;
+
+fn opened(layout: &str, position: CellPosition) -> Game {
+    let mut game = game_on(layout);
+    game.apply(0, Command::Clear(position))
+        .expect("The cell should be safe");
+    game
+}
+
+// the 2 under the left pair says both are mines, and then the 1 beside it
+// says the last is safe
+#[test]
+fn mines_found_count_towards_the_next_pass() {
+    let game = opened("**.\n...\n...", at(2, 2));
+
+    assert_eq!(Remaining::of(game.board()), Remaining { safe: 1, mines: 2 });
+}
+
+// none of the three 1s settles the top row alone, but the middle one less
+// either side does
+#[test]
+fn overlapping_numbers_prove_cells_safe() {
+    let game = opened(".*.\n...\n...", at(2, 0));
+
+    assert_eq!(Remaining::of(game.board()), Remaining { safe: 2, mines: 0 });
+}
+
+#[test]
+fn flagged_mines_are_no_longer_counted() {
+    let mut game = opened("**.\n...\n...", at(2, 2));
+    game.apply(0, Command::Flag(at(0, 0)))
+        .expect("(0,0) can be flagged");
+
+    assert_eq!(Remaining::of(game.board()), Remaining { safe: 1, mines: 1 });
+}
+
+// the same 1 twice over two cells: a guess either way
+#[test]
+fn a_fifty_fifty_proves_nothing() {
+    let game = opened("..\n..\n*.", at(0, 0));
+
+    assert_eq!(Remaining::of(game.board()), Remaining { safe: 0, mines: 0 });
+}
+
+#[test]
+fn a_board_with_nothing_open_proves_nothing() {
+    let board: Board = "*..\n...".parse().expect("The layout should parse");
+
+    assert_eq!(Remaining::of(&board), Remaining { safe: 0, mines: 0 });
+}
@@ -0,0 +1,80 @@
+// giving a game up: lost without a mine going off, with how far it got
+mod common;
+
+use common::{at, game_on};
+use minesweeper::command::Command;
+use minesweeper::game::{Game, GameError, GameOutcome, GameState};
+use minesweeper::json::Value;
+
+// a corner walled off by mines, which opens at once, and the rest beyond
+const LAYOUT: &str = "..*.\n..*.\n***.\n....";
+
+fn resigned() -> Game {
+    let mut game = game_on(LAYOUT);
+    game.apply(0, Command::Clear(at(0, 0)))
+        .expect("The corner should be safe");
+    assert_eq!(
+        game.apply(0, Command::Resign).ok(),
+        Some(GameState::Resigned)
+    );
+    game
+}
+
+#[test]
+fn a_resigned_game_is_over_without_a_mine_going_off() {
+    let mut game = resigned();
+    assert_eq!(game.state(), GameState::Resigned);
+    // not a move of its own, and nothing on the board changed
+    assert_eq!(game.moves().len(), 1);
+    assert_eq!(game.board().count_open(), 4);
+    assert!(game.events().is_empty());
+
+    assert!(matches!(
+        game.apply(0, Command::Clear(at(3, 0))),
+        Err(GameError::GameOver)
+    ));
+    assert!(matches!(
+        game.apply(0, Command::Undo),
+        Err(GameError::GameOver)
+    ));
+    assert!(matches!(
+        game.apply(0, Command::Resign),
+        Err(GameError::GameOver)
+    ));
+    assert!(game.apply(0, Command::BoardInfo).is_ok());
+}
+
+#[test]
+fn a_resignation_says_how_far_the_game_got() {
+    let game = resigned();
+    // 4 of the 11 safe cells
+    assert_eq!(game.progress(), 36);
+    let outcome = game
+        .outcome()
+        .expect("A resigned game should have an outcome");
+    assert!(matches!(
+        outcome,
+        GameOutcome::Resigned { progress: 36, .. }
+    ));
+    assert_eq!(outcome.to_string(), "Game resigned at 36% complete");
+    assert_eq!(outcome.exit_code(), 1);
+
+    let json = outcome.to_json();
+    assert_eq!(
+        json.get("outcome").and_then(Value::as_str),
+        Some("resigned")
+    );
+    assert_eq!(json.get("progress").and_then(Value::as_u64), Some(36));
+    assert_eq!(json.get("moves").and_then(Value::as_u64), Some(1));
+    assert_eq!(GameState::Resigned.name(), "resigned");
+}
+
+#[test]
+fn a_game_can_be_resigned_before_its_first_move() {
+    let mut game = game_on(LAYOUT);
+    game.apply(0, Command::Resign)
+        .expect("A new game can be resigned");
+    assert_eq!(game.progress(), 0);
+    assert_eq!("resign".parse::<Command>().ok(), Some(Command::Resign));
+    assert_eq!(Command::Resign.to_string(), "resign");
+}
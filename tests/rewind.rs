@@ -0,0 +1,154 @@
+// going back to an earlier move, checked against the same moves played
+// afresh on the same board, and on boards too big to keep every snapshot of
+mod common;
+
+use common::at;
+use minesweeper::board::Board;
+use minesweeper::command::Command;
+use minesweeper::dump::debug_dump;
+use minesweeper::game::{Game, GameError, GameState, MovingMines};
+
+// a blank corner that opens together, and numbers around the mines
+const LAYOUT: &str = "....*\n....*\n..*..\n.....\n*....";
+
+fn play(game: &mut Game, commands: &[Command]) {
+    for command in commands {
+        game.apply(0, command.clone())
+            .expect("The move should be allowed");
+    }
+}
+
+// everything about the game but the time, which a replay doesn't keep
+fn state(game: &Game) -> String {
+    debug_dump(game, "test")
+}
+
+// the same game played from the start with only `moves` of `commands`
+fn replayed(board: &Board, commands: &[Command], moves: usize) -> Game {
+    let mut game = Game::new(board.clone(), 2);
+    play(&mut game, &commands[..moves]);
+    game
+}
+
+#[test]
+fn rewinding_across_a_cascade_matches_a_fresh_replay() {
+    let board: Board = LAYOUT.parse().expect("The layout should parse");
+    let commands = [
+        Command::Flag(at(0, 4)),
+        Command::Clear(at(0, 0)),
+        Command::Flag(at(2, 2)),
+        Command::Flag(at(4, 0)),
+    ];
+    let mut game = Game::new(board.clone(), 2);
+    play(&mut game, &commands);
+    assert!(
+        game.board().count_open() > 10,
+        "The second move should cascade"
+    );
+
+    assert_eq!(
+        game.apply(0, Command::Rewind(1)).ok(),
+        Some(GameState::Playing)
+    );
+    assert_eq!(state(&game), state(&replayed(&board, &commands, 1)));
+    assert_eq!(game.board().count_open(), 0);
+
+    // and forward again from there, as if nothing had been rewound
+    play(&mut game, &commands[1..]);
+    assert_eq!(state(&game), state(&replayed(&board, &commands, 4)));
+}
+
+#[test]
+fn a_rewind_undoes_the_moves_after_it_in_the_log_and_marks_the_game_assisted() {
+    let board: Board = LAYOUT.parse().expect("The layout should parse");
+    let mut game = Game::new(board, 2);
+    play(
+        &mut game,
+        &[
+            Command::Flag(at(0, 4)),
+            Command::Clear(at(0, 0)),
+            Command::Flag(at(2, 2)),
+        ],
+    );
+    assert!(!game.assisted());
+
+    game.apply(0, Command::Rewind(0))
+        .expect("The game should rewind to the start");
+    assert_eq!(game.moves().len(), 0);
+    assert_eq!(game.log().len(), 3);
+    assert!(game.log().iter().all(|logged| logged.undone));
+    assert!(game.assisted());
+    assert!(game.stats().assisted);
+}
+
+#[test]
+fn a_rewind_only_goes_back() {
+    let board: Board = LAYOUT.parse().expect("The layout should parse");
+    let mut game = Game::new(board, 2);
+    play(
+        &mut game,
+        &[Command::Flag(at(0, 4)), Command::Clear(at(0, 0))],
+    );
+
+    assert!(matches!(
+        game.apply(0, Command::Rewind(2)),
+        Err(GameError::RewindTooFar { moves: 2, made: 2 })
+    ));
+    assert_eq!(game.moves().len(), 2);
+    assert!(!game.assisted());
+}
+
+// the mines move every other move, by the move count, so the board a rewind
+// lands on has them where they were then
+#[test]
+fn rewinding_puts_moving_mines_back_where_they_were() {
+    let board = Board::from_seed(7, 12, 12, minesweeper::board::Mines::Count(20));
+    let moving = Some(MovingMines {
+        every: 2,
+        fraction: 0.5,
+        seed: 3,
+    });
+    let commands: Vec<Command> = (0..12).map(|col| Command::Flag(at(col, col))).collect();
+    let mut game = Game::new(board.clone(), 2).with_moving_mines(moving);
+    play(&mut game, &commands);
+
+    game.apply(0, Command::Rewind(5))
+        .expect("The game should rewind");
+    let mut fresh = Game::new(board, 2).with_moving_mines(moving);
+    play(&mut fresh, &commands[..5]);
+    assert_eq!(state(&game), state(&fresh));
+}
+
+// a board this big only keeps the snapshots of the last few moves, so
+// rewinding further and undoing every move replay the log instead
+#[test]
+fn a_board_too_big_to_keep_every_snapshot_replays_from_the_start() {
+    let (height, width) = (600, 600);
+    // mines all along the second row keep the first from cascading
+    let bombs = (0..height)
+        .map(|row| (0..width).map(|_| row == 1).collect())
+        .collect();
+    let board = Board::from_bombs(bombs);
+    let commands: Vec<Command> = (0..6)
+        .map(|col| match col % 2 {
+            0 => Command::Clear(at(0, col)),
+            _ => Command::Flag(at(1, col)),
+        })
+        .collect();
+    let mut game = Game::new(board.clone(), 2);
+    play(&mut game, &commands);
+
+    game.apply(0, Command::Rewind(2))
+        .expect("The game should rewind");
+    assert_eq!(state(&game), state(&replayed(&board, &commands, 2)));
+
+    for _ in 0..2 {
+        game.apply(0, Command::Undo)
+            .expect("The move should be undone");
+    }
+    assert_eq!(state(&game), state(&replayed(&board, &commands, 0)));
+    assert!(matches!(
+        game.apply(0, Command::Undo),
+        Err(GameError::NothingToUndo)
+    ));
+}
@@ -0,0 +1,88 @@
+// rocks: never opened or flagged, not counted as mines or towards a win,
+// and a wall to a cascade of zeros
+mod common;
+
+use common::{at, board, game_on};
+use minesweeper::board::{CellState, ClearError, FlagError};
+use minesweeper::command::Command;
+use minesweeper::game::{GameError, GameState, MoveOutcome};
+
+// a column of rocks between two rooms, the right one with a mine in it
+const WALLED: &str = "..o...\n..o...\n..o..*";
+
+fn opened(layout: &str, row: usize, col: usize) -> Vec<(usize, usize)> {
+    let mut game = game_on(layout);
+    game.apply(0, Command::Clear(at(row, col)))
+        .expect("The cell should open");
+    game.board()
+        .rows()
+        .flatten()
+        .filter(|cell| matches!(cell.state, CellState::Safe { open: true, .. }))
+        .map(|cell| (cell.position.row_index, cell.position.col_index))
+        .collect()
+}
+
+#[test]
+fn a_cascade_stops_at_rocks() {
+    let left: Vec<(usize, usize)> = (0..3).flat_map(|row| [(row, 0), (row, 1)]).collect();
+    assert_eq!(opened(WALLED, 1, 0), left);
+
+    // and rocks are no mines: the cells next to them are zeros
+    let board = board(WALLED);
+    assert_eq!(board.count_rocks(), 3);
+    assert_eq!(board.count_bombs(), 1);
+    assert_eq!(board.count_safe(), 14);
+    assert_eq!(
+        board.get_cell(at(0, 1)).map(|cell| cell.bombs_around),
+        Some(0)
+    );
+
+    // a gap in the wall lets it through
+    assert_eq!(opened("..o...\n......\n..o..*", 0, 0).len(), 15);
+}
+
+#[test]
+fn rocks_cant_be_opened_or_flagged() {
+    let mut game = game_on(WALLED);
+    assert!(matches!(
+        game.apply(0, Command::Clear(at(0, 2))),
+        Err(GameError::Clear(ClearError::Rock))
+    ));
+    assert!(matches!(
+        game.apply(0, Command::Flag(at(1, 2))),
+        Err(GameError::Flag(FlagError::Rock))
+    ));
+    assert!(game.moves().is_empty());
+}
+
+#[test]
+fn a_board_is_won_with_its_rocks_still_closed() {
+    let mut game = game_on(WALLED);
+    game.apply(0, Command::Clear(at(0, 0)))
+        .expect("The left room should open");
+    let state = game
+        .apply(0, Command::Clear(at(0, 3)))
+        .expect("The right room should open");
+    // the 8 safe cells of the right room are the last of them
+    assert!(matches!(game.moves()[1].outcome, MoveOutcome::Opened(8)));
+    assert_eq!(state, GameState::Won);
+    assert!(matches!(
+        game.board().get_cell(at(1, 2)).map(|cell| &cell.state),
+        Some(CellState::Rock)
+    ));
+}
+
+#[test]
+fn splits_go_by_the_safe_cells_alone() {
+    // two safe cells, so that each clear is half the board
+    let mut game = game_on(".o\n.*");
+    game.apply(0, Command::Clear(at(0, 0)))
+        .expect("The cell should open");
+    let percents = |game: &minesweeper::game::Game| -> Vec<usize> {
+        game.splits().iter().map(|&(percent, _)| percent).collect()
+    };
+    assert_eq!(percents(&game), [25, 50]);
+    game.apply(0, Command::Clear(at(1, 0)))
+        .expect("The cell should open");
+    assert_eq!(percents(&game), [25, 50, 75, 100]);
+}
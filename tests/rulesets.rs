@@ -0,0 +1,110 @@
+// building rules, and the games they start
+use minesweeper::board::{LineLimits, Mines, Symmetry};
+use minesweeper::game::{Game, WinCondition};
+use minesweeper::rules::{Ruleset, RulesetError};
+
+#[test]
+fn the_classic_rules_are_a_beginners_game() {
+    let rules = Ruleset::classic();
+
+    assert_eq!((rules.height(), rules.width()), (9, 9));
+    assert_eq!(rules.layout().mines, Mines::Count(10));
+    assert_eq!(rules.lives(), 1);
+    assert_eq!(rules.win(), WinCondition::OpenAll);
+    assert_eq!(rules.key(), "classic");
+    assert_eq!(Ruleset::default(), rules);
+}
+
+#[test]
+fn rules_that_cant_make_a_game_are_turned_down() {
+    let builder = || Ruleset::builder().size(4, 4);
+
+    assert_eq!(
+        builder().mines(Mines::Count(16)).build(),
+        Err(RulesetError::TooManyMines {
+            mines: 16,
+            cells: 16
+        })
+    );
+    assert_eq!(
+        builder().mines(Mines::Count(10)).rocks(6).build(),
+        Err(RulesetError::TooManyRocks { rocks: 6, free: 6 })
+    );
+    assert_eq!(builder().lives(0).build(), Err(RulesetError::NoLives));
+    assert_eq!(builder().size(0, 4).build(), Err(RulesetError::NoCells));
+    assert_eq!(
+        builder().moving_mines(3, 1.5).build(),
+        Err(RulesetError::InvalidFraction(1.5))
+    );
+    // turned off, the fraction doesn't matter
+    assert!(builder().moving_mines(0, 1.5).build().is_ok());
+}
+
+#[test]
+fn two_ways_of_laying_the_mines_out_dont_combine() {
+    let limits = LineLimits {
+        min_per_row: 1,
+        ..LineLimits::default()
+    };
+    let result = Ruleset::builder()
+        .symmetry(Symmetry::Rotational)
+        .limits(limits)
+        .build();
+
+    assert_eq!(
+        result,
+        Err(RulesetError::Conflicting("symmetry", "min_per_row"))
+    );
+    assert_eq!(
+        result.map_err(|e| e.to_string()),
+        Err(String::from(
+            "`symmetry` and `min_per_row` each lay the mines out their own way, so only one can be set"
+        ))
+    );
+}
+
+#[test]
+fn a_game_is_played_by_the_rules_it_was_started_with() {
+    let rules = Ruleset::builder()
+        .size(8, 12)
+        .mines(Mines::Count(20))
+        .lives(3)
+        .peeks(2)
+        .shields(true)
+        .win(WinCondition::FlagAll)
+        .moving_mines(5, 0.5)
+        .build()
+        .expect("The rules should be consistent");
+    let game = Game::from_ruleset(&rules, 7);
+
+    assert_eq!((game.board().height(), game.board().width()), (8, 12));
+    assert_eq!(game.board().count_bombs(), 20);
+    assert_eq!((game.lives(), game.initial_peeks()), (3, 2));
+    assert!(game.has_shields());
+    assert_eq!(game.moving_mines().map(|moving| moving.seed), Some(7));
+    assert_eq!(game.ruleset(), rules);
+    assert_eq!(
+        rules.key(),
+        "lives=3,peeks=2,shields,flag_win,moving_mines=5:0.5"
+    );
+}
+
+// a density is played as the mines it came to
+#[test]
+fn a_game_reads_its_rules_back_from_its_board() {
+    let rules = Ruleset::builder()
+        .mines(Mines::Density(0.2))
+        .build()
+        .expect("The rules should be consistent");
+    let game = Game::from_ruleset(&rules, 7);
+
+    let played = game.ruleset();
+    assert_eq!(
+        played.layout().mines,
+        Mines::Count(game.board().count_bombs())
+    );
+    assert_eq!(
+        played.to_builder().mines(Mines::Density(0.2)).build(),
+        Ok(rules)
+    );
+}
@@ -0,0 +1,144 @@
+// named saves through the binary: `save as` during a game, then `load` and
+// `save delete`, all in one data directory kept apart from the user's
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command as Process, Stdio};
+
+struct Home {
+    path: PathBuf,
+}
+
+impl Home {
+    fn new(name: &str) -> Self {
+        let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+            .join("saves")
+            .join(name);
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).expect("The test's home should be made");
+        Home { path }
+    }
+
+    fn run(&self, args: &[&str], input: &str) -> String {
+        let mut child = Process::new(env!("CARGO_BIN_EXE_minesweeper"))
+            .args(args)
+            .args(["--lang", "en"])
+            .env("XDG_DATA_HOME", &self.path)
+            .env("XDG_CONFIG_HOME", &self.path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("The binary should start");
+        child
+            .stdin
+            .take()
+            .expect("The binary's stdin should be piped")
+            .write_all(input.as_bytes())
+            .expect("The binary should read its input");
+        let output = child.wait_with_output().expect("The binary should finish");
+        String::from_utf8(output.stdout).expect("The output should be UTF-8")
+    }
+
+    // a beginner's game from seed 42, played with `input`
+    fn play(&self, input: &str) -> String {
+        let args = ["--seed", "42", "--preset", "beginner"];
+        self.run(
+            &[&args[..], &["--no-color", "--plain", "--no-summary"]].concat(),
+            input,
+        )
+    }
+
+    // the names in `load`'s list, in its order; every board is 9x9
+    fn names(&self) -> Vec<String> {
+        self.run(&["load"], "")
+            .lines()
+            .skip(1)
+            .map(|line| {
+                line[5..line.rfind("9x9").unwrap_or(5)]
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    fn files(&self) -> Vec<String> {
+        let mut files: Vec<String> = fs::read_dir(self.path.join("minesweeper-cli").join("saves"))
+            .expect("The saves directory should be there")
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect();
+        files.sort();
+        files
+    }
+}
+
+#[test]
+fn saves_are_listed_newest_first_and_load_by_number_or_name() {
+    let home = Home::new("order");
+    home.play("c 2 7\nsave as first\nf 0 0\nsave as second\n");
+
+    assert_eq!(home.names(), ["second", "first"]);
+    let listing = home.run(&["load"], "");
+    assert!(listing.contains("9x9"));
+    assert!(listing.contains("54%"));
+
+    let args = ["--no-color", "--plain", "--no-summary"];
+    let loaded = home.run(&[&["load", "2"][..], &args].concat(), "");
+    assert!(loaded.contains("Moves: 1"));
+    let loaded = home.run(&[&["load", "second"][..], &args].concat(), "");
+    assert!(loaded.contains("Flags: 1  Time: 00:00  Moves: 2"));
+    assert!(home.run(&["load", "third"], "").is_empty());
+}
+
+// the same name replaces its save, while names that only come to the same
+// file name get a file each
+#[test]
+fn names_that_collide_keep_their_own_saves() {
+    let home = Home::new("collisions");
+    let script = "save as friday game\nsave as Friday/Game\nc 2 7\nsave as friday game\n";
+    let output = home.play(script);
+
+    assert!(output.contains("Replaced the save \"friday game\""));
+    assert_eq!(home.names(), ["friday game", "Friday/Game"]);
+    assert_eq!(
+        home.files(),
+        ["friday_game-2.txt", "friday_game.txt", "index.jsonl"]
+    );
+}
+
+#[test]
+fn weird_names_are_kept_but_give_safe_file_names() {
+    let home = Home::new("sanitized");
+    let long = "a".repeat(60);
+    let script = format!("save as ../../etc/passwd\nsave as CON\nsave as \"🎉\"\nsave as {long}");
+    let output = home.play(&script);
+
+    assert_eq!(output.matches("Saved as").count(), 4);
+    let files = [
+        &format!("{}.txt", "a".repeat(40)),
+        "con_.txt",
+        "etc_passwd.txt",
+        "index.jsonl",
+    ];
+    assert_eq!(home.files(), [&files[..], &["save.txt"]].concat());
+    assert_eq!(
+        home.names(),
+        [long.as_str(), "🎉", "CON", "../../etc/passwd"]
+    );
+}
+
+#[test]
+fn a_save_is_only_deleted_once_confirmed() {
+    let home = Home::new("deletion");
+    home.play("save as keep\nsave as drop\n");
+
+    let kept = home.run(&["save", "delete", "1"], "n\n");
+    assert!(kept.contains("Delete the save \"drop\" (9x9, 0% open)? [y/N]"));
+    assert!(kept.contains("The save was kept"));
+    assert_eq!(home.names(), ["drop", "keep"]);
+
+    let deleted = home.run(&["save", "delete", "drop"], "y\n");
+    assert!(deleted.contains("Deleted the save \"drop\""));
+    assert_eq!(home.names(), ["keep"]);
+    assert_eq!(home.files(), ["index.jsonl", "keep.txt"]);
+}
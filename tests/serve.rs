@@ -0,0 +1,144 @@
+// the server through the binary: a client on a localhost socket is shown
+// its board on connecting, plays a scripted game to its end, and is told
+// how it went as batch mode would tell it
+mod common;
+
+use common::Home;
+use minesweeper::json::{self, Value};
+use std::io::{BufRead, BufReader, Lines, Write};
+use std::net::TcpStream;
+use std::process::{Child, ChildStdout, Command as Process, Stdio};
+use std::time::Duration;
+
+// the server, killed when the test is done with it
+struct Server {
+    child: Child,
+    stdout: Lines<BufReader<ChildStdout>>,
+    address: String,
+}
+
+impl Server {
+    // on a port the system picks, which the server says it is listening on
+    fn start(home: &Home, args: &[&str]) -> Server {
+        let mut child = Process::new(env!("CARGO_BIN_EXE_minesweeper"))
+            .args(["--serve", "0", "--seed", "42", "--preset", "beginner"])
+            .args(args)
+            .args(["--lang", "en", "--plain"])
+            .env("XDG_DATA_HOME", &home.path)
+            .env("XDG_CONFIG_HOME", &home.path)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("The binary should start");
+        let stdout = child.stdout.take().expect("The output should be piped");
+        let mut stdout = BufReader::new(stdout).lines();
+        let listening = stdout
+            .next()
+            .and_then(Result::ok)
+            .expect("The server should say where it listens");
+        let address = listening
+            .strip_prefix("Listening on ")
+            .expect("The first line should be the address")
+            .to_string();
+        Server {
+            child,
+            stdout,
+            address,
+        }
+    }
+
+    fn connect(&self) -> (TcpStream, Lines<BufReader<TcpStream>>) {
+        let stream = TcpStream::connect(&self.address).expect("The server should accept");
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .expect("The timeout should be set");
+        let reader = stream.try_clone().expect("The stream should clone");
+        (stream, BufReader::new(reader).lines())
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn read(lines: &mut Lines<BufReader<TcpStream>>) -> Option<String> {
+    lines
+        .next()
+        .map(|line| line.expect("The line should be read"))
+}
+
+// up to and including the line a board ends with
+fn board(lines: &mut Lines<BufReader<TcpStream>>) -> Vec<String> {
+    let mut board = vec![];
+    while let Some(line) = read(lines) {
+        let end = line == "------";
+        board.push(line);
+        if end {
+            break;
+        }
+    }
+    board
+}
+
+#[test]
+fn a_scripted_game_is_played_over_the_socket() {
+    let home = Home::new("serve_text");
+    let mut server = Server::start(&home, &[]);
+    let (mut stream, mut lines) = server.connect();
+
+    // the board before anything is sent
+    let greeting = board(&mut lines);
+    assert!(
+        greeting[0].starts_with("Playing #42; to continue after disconnecting, send resume "),
+        "{greeting:?}"
+    );
+    assert_eq!(greeting[1], "Mines: 10  Flags: 0  Moves: 0");
+    assert_eq!(&greeting[2..11], ["# # # # # # # # #"; 9]);
+
+    writeln!(stream, "c 2 7").expect("The command should be sent");
+    let cascade = board(&mut lines);
+    assert!(
+        cascade.iter().any(|line| line.contains("Moves: 1")),
+        "{cascade:?}"
+    );
+
+    // (1,1) is a mine, and the game is over with it, so the server hangs up
+    writeln!(stream, "c 1 1").expect("The command should be sent");
+    let lost: Vec<String> = std::iter::from_fn(|| read(&mut lines)).collect();
+    assert!(
+        lost.contains(&String::from("# * # # # # 1 0 0")),
+        "{lost:?}"
+    );
+
+    // and the server says how it went, then waits for the next client
+    let outcome = server.stdout.next().and_then(Result::ok);
+    assert_eq!(outcome.as_deref(), Some("Game lost: hit a mine at (1,1)"));
+    let (_, mut next) = server.connect();
+    assert!(read(&mut next).is_some_and(|line| line.starts_with("Playing #")));
+}
+
+#[test]
+fn json_clients_get_the_token_and_the_full_board_on_connecting() {
+    let home = Home::new("serve_json");
+    let server = Server::start(&home, &["--json"]);
+    let (mut stream, mut lines) = server.connect();
+
+    let parse = |line: Option<String>| {
+        json::parse(&line.expect("There should be a line")).expect("Every line should be JSON")
+    };
+    let greeting = parse(read(&mut lines));
+    assert_eq!(greeting.get("code").and_then(Value::as_str), Some("#42"));
+    assert_eq!(
+        greeting.get("token").and_then(Value::as_str).map(str::len),
+        Some(16)
+    );
+    let full = parse(read(&mut lines));
+    assert_eq!(full.get("state").and_then(Value::as_str), Some("playing"));
+
+    writeln!(stream, "c 2 7").expect("The command should be sent");
+    let moved = parse(read(&mut lines));
+    assert_eq!(moved.get("state").and_then(Value::as_str), Some("playing"));
+}
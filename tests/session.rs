@@ -0,0 +1,57 @@
+// a session of several games through the binary, summed up when the player
+// stops, unless asked not to be
+mod common;
+
+use common::{Home, Run};
+
+// with no mines, each game is won by its first clear: won, resigned, won,
+// and then left at the end of the input
+const ARGS: [&str; 7] = ["--width", "2", "--height", "2", "--mines", "0", "--plain"];
+const SCRIPT: &str = "c 0 0\ny\nresign\ny\ny\nc 1 1\ny\n";
+
+// the summary, without the time and rate lines, which depend on how fast
+// the games were played
+fn summary(run: &Run) -> Vec<&str> {
+    run.stdout
+        .lines()
+        .skip_while(|line| *line != "This session:")
+        .filter(|line| !line.contains("time:") && !line.contains("3BV/s"))
+        .collect()
+}
+
+#[test]
+fn the_session_is_summed_up_leaving_out_the_unfinished_game() {
+    let run = Home::new("session_summary").run(&ARGS, SCRIPT);
+    assert_eq!(
+        summary(&run),
+        [
+            "This session:",
+            "  Games played: 3",
+            "  Win rate: 66% (2 of 3)",
+            "  Longest win streak: 1",
+            "  Resigned: 1",
+        ]
+    );
+
+    // and with resigning not counted as losing, it is left out as well
+    let home = Home::new("session_summary_resigned");
+    home.write("config.toml", "count_resigned = false\n");
+    assert_eq!(
+        summary(&home.run(&ARGS, SCRIPT)),
+        [
+            "This session:",
+            "  Games played: 2",
+            "  Win rate: 100% (2 of 2)",
+            "  Longest win streak: 2",
+            "  Resigned: 1",
+        ]
+    );
+}
+
+#[test]
+fn no_summary_leaves_it_out() {
+    let args = [&ARGS[..], &["--no-summary"]].concat();
+    let run = Home::new("session_no_summary").run(&args, SCRIPT);
+    assert!(run.stdout.contains("Game resigned"));
+    assert!(!run.stdout.contains("This session:"), "{}", run.stdout);
+}
@@ -0,0 +1,72 @@
+// the share block offered once a game is over: what the players did, and
+// nothing of where the mines they never found are
+mod common;
+
+use common::{assert_snapshot, redact_times, Home};
+
+const ARGS: [&str; 8] = [
+    "coop", "--seed", "3", "--size", "4", "--mines", "4", "--plain",
+];
+
+// three cells opened, then three mines hit, then the block shared and the
+// board exported, to read the mines from
+const SCRIPT: &str = "c 0 0\nc 0 3\nc 1 1\nc 0 1\nc 0 2\nc 2 0\nshare\nexport discord\n\n";
+
+// the lines from `heading` up to the next prompt
+fn block<'a>(stdout: &'a str, heading: &str) -> Vec<&'a str> {
+    stdout
+        .lines()
+        .skip_while(|line| *line != heading)
+        .take_while(|line| !line.starts_with("Enter share"))
+        .collect()
+}
+
+// a discord row split into its cells, spoilered or not
+fn cells(row: &str) -> Vec<&str> {
+    let mut cells = Vec::new();
+    let mut rest = row;
+    while !rest.is_empty() {
+        let len = if let Some(inner) = rest.strip_prefix("||") {
+            inner.find("||").map_or(rest.len(), |end| end + 4)
+        } else {
+            // a keycap is its digit and two joiners, a hit mine one char
+            let first = rest.chars().next().map_or(0, char::len_utf8);
+            rest[first..]
+                .strip_prefix("\u{fe0f}\u{20e3}")
+                .map_or(first, |_| first + 6)
+        };
+        cells.push(&rest[..len]);
+        rest = &rest[len..];
+    }
+    cells
+}
+
+#[test]
+fn a_finished_game_is_shared_without_its_mines() {
+    let run = Home::new("share_block").run(&ARGS, SCRIPT);
+    let shared = block(&run.stdout, "Minesweeper Co-op #3");
+    assert_snapshot("share", &redact_times(&shared.join("\n")));
+    assert_eq!(shared.len(), 6, "{}", run.stdout);
+
+    // every mine left closed is a closed square, like any other
+    let board = block(&run.stdout, "**Minesweeper Co-op #3**");
+    let mut hidden = 0;
+    for (shared, exported) in shared[2..].iter().zip(&board[2..]) {
+        let squares: Vec<char> = shared.chars().collect();
+        let cells = cells(exported);
+        assert_eq!(cells.len(), squares.len(), "{exported}");
+        for (square, cell) in squares.iter().zip(&cells) {
+            if *cell == "||💣||" {
+                assert_eq!(*square, '⬛', "{shared}");
+                hidden += 1;
+            }
+        }
+    }
+    assert_eq!(hidden, 1, "One mine should never have been found");
+}
+
+#[test]
+fn the_heading_is_in_the_players_language() {
+    let run = Home::new("share_block_es").run_in("es", &ARGS, SCRIPT);
+    assert!(run.stdout.lines().any(|line| line == "Buscaminas Co-op #3"));
+}
@@ -0,0 +1,85 @@
+// the shield variant: a big enough cascade earns a shield, which takes the
+// blast of the next mine set off, and the next mine after that loses
+mod common;
+
+use common::{at, board};
+use minesweeper::board::CellState;
+use minesweeper::command::Command;
+use minesweeper::game::{Game, GameEvent, GameState, MoveOutcome, SHIELD_CASCADE};
+
+// a cascade from the bottom corner opens all but the top row's first three
+// cells, two mines either side of a safe cell
+const LAYOUT: &str = "*.*...\n......\n......\n......\n......\n......";
+
+fn shielded() -> Game {
+    let mut game = Game::new(board(LAYOUT), 1).shielded(true);
+    game.apply(0, Command::Clear(at(5, 5)))
+        .expect("The cascade should open");
+    game
+}
+
+fn clear(game: &mut Game, row: usize, col: usize) -> GameState {
+    game.apply(0, Command::Clear(at(row, col)))
+        .expect("The clear should be played")
+}
+
+fn last_outcome(game: &Game) -> MoveOutcome {
+    game.moves().last().expect("There should be a move").outcome
+}
+
+#[test]
+fn a_big_cascade_earns_a_shield() {
+    let game = shielded();
+    assert!(matches!(last_outcome(&game), MoveOutcome::Opened(33)));
+    const { assert!(33 >= SHIELD_CASCADE) };
+    assert_eq!(game.shields(), 1);
+    assert_eq!(game.events().last(), Some(&GameEvent::ShieldEarned(1)));
+
+    // and a small one doesn't
+    let mut small = Game::new(board("*...\n....\n...."), 1).shielded(true);
+    clear(&mut small, 2, 3);
+    assert!(matches!(last_outcome(&small), MoveOutcome::Opened(opened) if opened < SHIELD_CASCADE));
+    assert_eq!(small.shields(), 0);
+}
+
+#[test]
+fn a_shield_is_spent_on_the_next_mine_and_the_one_after_loses() {
+    let mut game = shielded();
+
+    assert_eq!(clear(&mut game, 0, 0), GameState::Playing);
+    assert!(matches!(last_outcome(&game), MoveOutcome::Defused(hit) if hit == at(0, 0)));
+    assert!(game.events().contains(&GameEvent::MineDefused(at(0, 0))));
+    assert_eq!((game.shields(), game.lives()), (0, 1));
+    let defused = game
+        .board()
+        .get_cell(at(0, 0))
+        .expect("The mine is on the board");
+    assert!(defused.defused);
+    assert!(matches!(
+        defused.state,
+        CellState::Bomb { exploded: true, .. }
+    ));
+    assert_eq!(defused.to_string(), "+");
+
+    // with none left, the other mine ends the game
+    assert_eq!(clear(&mut game, 0, 2), GameState::Lost);
+    assert!(matches!(last_outcome(&game), MoveOutcome::HitBomb(hit) if hit == at(0, 2)));
+    assert_eq!(game.lives(), 0);
+}
+
+#[test]
+fn a_defused_mine_counts_as_dealt_with() {
+    let mut game = shielded();
+    clear(&mut game, 0, 2);
+    // the last safe cell wins, with the mine at (0,0) neither flagged nor hit
+    assert_eq!(clear(&mut game, 0, 1), GameState::Won);
+}
+
+#[test]
+fn without_the_variant_there_are_no_shields() {
+    let mut game = Game::new(board(LAYOUT), 1);
+    clear(&mut game, 5, 5);
+    assert!(!game.has_shields());
+    assert_eq!(game.shields(), 0);
+    assert_eq!(clear(&mut game, 0, 0), GameState::Lost);
+}
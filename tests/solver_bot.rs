@@ -0,0 +1,109 @@
+// the solver playing on boards made by hand, a move at a time, as `watch`
+// shows it
+mod common;
+
+use common::{at, game_on};
+use minesweeper::board::CellPosition;
+use minesweeper::command::Command;
+use minesweeper::game::{Game, GameState};
+use minesweeper::solver::{bot_move, Reason};
+
+fn opened(layout: &str, position: CellPosition) -> Game {
+    let mut game = game_on(layout);
+    game.apply(0, Command::Clear(position))
+        .expect("The cell should be safe");
+    game
+}
+
+// every move the solver makes until it is stuck or the game is over
+fn play_out(game: &mut Game) -> Vec<Command> {
+    let mut commands = vec![];
+    while game.state() == GameState::Playing {
+        let Some(next) = bot_move(game.board()) else {
+            break;
+        };
+        game.apply(0, next.command.clone())
+            .expect("The solver's move should be allowed");
+        commands.push(next.command);
+    }
+    commands
+}
+
+// the 2 in the corner has only the two mines to be, after which the 2 in
+// the middle has all it needs
+#[test]
+fn the_solver_plays_a_board_it_can_prove_to_a_win() {
+    let mut game = opened("**.\n...\n...", at(2, 2));
+
+    let first = bot_move(game.board()).expect("There should be a move");
+    assert_eq!(first.command, Command::Flag(at(0, 0)));
+    assert_eq!(first.deduction.reason, Reason::SinglePoint(at(1, 0)));
+    assert_eq!(
+        first.deduction.to_string(),
+        "single-point: (1,0) needs them all"
+    );
+
+    assert_eq!(
+        play_out(&mut game),
+        [
+            Command::Flag(at(0, 0)),
+            Command::Flag(at(0, 1)),
+            Command::Clear(at(0, 2))
+        ]
+    );
+    assert_eq!(game.state(), GameState::Won);
+}
+
+#[test]
+fn a_safe_cell_is_cleared_because_its_number_is_satisfied() {
+    let mut game = opened("**.\n...\n...", at(2, 2));
+    game.apply(0, Command::Flag(at(0, 0)))
+        .expect("The cell should be flaggable");
+    game.apply(0, Command::Flag(at(0, 1)))
+        .expect("The cell should be flaggable");
+
+    let next = bot_move(game.board()).expect("There should be a move");
+    assert_eq!(next.command, Command::Clear(at(0, 2)));
+    assert_eq!(next.deduction.to_string(), "single-point: (1,1) satisfied");
+}
+
+// a flag the numbers say is wrong comes off before the cell is cleared
+#[test]
+fn the_solver_takes_a_wrong_flag_off() {
+    let mut game = opened("**.\n...\n...", at(2, 2));
+    game.apply(0, Command::Flag(at(0, 2)))
+        .expect("The cell should be flaggable");
+
+    let commands = play_out(&mut game);
+    let unflagged = commands
+        .iter()
+        .position(|command| *command == Command::Flag(at(0, 2)));
+    let cleared = commands
+        .iter()
+        .position(|command| *command == Command::Clear(at(0, 2)));
+    assert!(unflagged.is_some() && unflagged < cleared);
+    assert_eq!(game.state(), GameState::Won);
+}
+
+// only the top row is left, and the two 1s under it can't say which is the
+// mine
+#[test]
+fn the_solver_stops_at_a_guess() {
+    let mut game = opened("*.\n..\n..", at(2, 0));
+
+    assert_eq!(bot_move(game.board()), None);
+    assert!(play_out(&mut game).is_empty());
+    assert_eq!(game.state(), GameState::Playing);
+}
+
+// no number settles the top row alone, but the middle 1 less the left one
+// leaves the right corner safe
+#[test]
+fn the_solver_gives_the_two_numbers_of_a_subset() {
+    let game = opened(".*.\n...\n...", at(2, 0));
+
+    let next = bot_move(game.board()).expect("There should be a move");
+    assert!(matches!(next.command, Command::Clear(_)));
+    assert!(matches!(next.deduction.reason, Reason::Subset(..)));
+    assert!(next.deduction.to_string().starts_with("subset: "));
+}
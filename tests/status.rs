@@ -0,0 +1,106 @@
+// the status line above the board, through the binary, for games in several
+// states; the fields each takes up are snapshotted together in
+// tests/snapshots/status.txt
+mod common;
+
+use common::{assert_snapshot, redact_times, Home};
+
+const LAYOUT: &str = "\
+*........
+.*...*...
+.........
+....*....
+..**.....
+..*......
+.........
+*..*.....
+..*......";
+
+// each status line drawn, in order
+fn statuses(stdout: &str) -> Vec<&str> {
+    stdout
+        .lines()
+        .filter(|line| line.starts_with("Mines: "))
+        .collect()
+}
+
+fn played(name: &str, config: &str, args: &[&str], input: &str) -> Vec<String> {
+    let home = Home::new(name);
+    home.write("config.toml", config);
+    let args = [&["--seed", "42", "--preset", "beginner", "--plain"], args].concat();
+    let run = home.run(&args, input);
+    statuses(&run.stdout)
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
+// the seeded beginner's board two moves in, with `elapsed` seconds on the
+// clock, loaded from a save
+fn loaded(name: &str, elapsed: u64) -> String {
+    let home = Home::new(name);
+    let save = format!(
+        "minesweeper save\nversion 2\nmode single\ncode #42\nsize 9x9\nlives 1\n\
+         elapsed {elapsed}\nlayout\n{LAYOUT}\nmoves\n0 c 2 7\n0 f 0 0\n"
+    );
+    home.write("saved.txt", &save);
+    let path = home.path.join("minesweeper-cli/saved.txt");
+    let run = home.run(
+        &[
+            "--load",
+            path.to_str().expect("The path should be UTF-8"),
+            "--plain",
+        ],
+        "",
+    );
+    statuses(&run.stdout)[0].to_string()
+}
+
+#[test]
+fn the_status_line_in_several_states() {
+    let mut report = String::new();
+    let mut section = |name: &str, lines: &[String]| {
+        report += &format!("{name}\n");
+        for line in lines {
+            report += &format!("  {line}\n");
+        }
+    };
+
+    // no time before the first move, then a flag and a cascade
+    section(
+        "fresh, then a flag and a clear",
+        &played("status_fresh", "", &[], "f 0 0\nc 2 7\n"),
+    );
+    section(
+        "timer off",
+        &played("status_no_timer", "timer = false\n", &[], "f 0 0\n"),
+    );
+    section(
+        "assisted",
+        &played("status_assisted", "assist = hints\n", &[], "c 2 7\n"),
+    );
+    section(
+        "variants",
+        &played(
+            "status_variants",
+            "",
+            &["--peeks", "2", "--shields", "--flag-win"],
+            "",
+        ),
+    );
+    // minutes and seconds, and hours once there are any
+    let times = [37, 59 * 60 + 59, 3600, 3600 + 2 * 60 + 5, 25 * 3600];
+    let loaded: Vec<String> = times
+        .iter()
+        .map(|&elapsed| loaded(&format!("status_loaded_{elapsed}"), elapsed))
+        .collect();
+    section("loaded with time on the clock", &loaded);
+
+    // the clock runs between moves made this session, so only the loaded
+    // times are left as they are
+    let start = report
+        .find("loaded with")
+        .expect("The sections should be in order");
+    let report = redact_times(&report[..start]) + &report[start..];
+    assert_snapshot("status", &report);
+}
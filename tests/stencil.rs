@@ -0,0 +1,115 @@
+// stencils: one of the wrong size is turned down, and one that fits keeps
+// the count while its marked cells get mines far more often
+mod common;
+
+use common::{mines, Home};
+use minesweeper::board::{MineDistribution, Mines, Stencil};
+use minesweeper::rules::{Ruleset, RulesetError};
+
+// a 6x8 frame, with 20 marked cells of the 48
+const FRAME: &str = "########\n#......#\n#......#\n#......#\n#......#\n########\n";
+
+fn rules(height: usize, width: usize, count: usize) -> Result<Ruleset, RulesetError> {
+    Ruleset::builder()
+        .size(height, width)
+        .mines(Mines::Count(count))
+        .distribution(MineDistribution::Stencil(Stencil::parse(FRAME)))
+        .build()
+}
+
+#[test]
+fn a_stencil_has_to_be_the_size_of_the_board() {
+    let stencil = Stencil::parse(FRAME);
+    assert_eq!((stencil.height(), stencil.width()), (6, 8));
+    // a short line is unmarked past its end, and blank lines at the end go
+    let ragged = Stencil::parse("##\n#\n########\n\n\n");
+    assert_eq!((ragged.height(), ragged.width()), (3, 8));
+
+    assert!(rules(6, 8, 10).is_ok());
+    for (height, width) in [(8, 6), (6, 9), (5, 8)] {
+        let error = rules(height, width, 10).expect_err("The sizes don't match");
+        assert_eq!(
+            error,
+            RulesetError::StencilSize {
+                stencil: (6, 8),
+                board: (height, width)
+            }
+        );
+    }
+    assert_eq!(
+        rules(8, 6, 10).map(|_| ()).map_err(|e| e.to_string()),
+        Err(String::from("The stencil is 6x8, but the board is 8x6"))
+    );
+}
+
+#[test]
+fn the_count_is_kept_and_marked_cells_are_mined_more_often() {
+    let marked = |row: usize, col: usize| row == 0 || row == 5 || col == 0 || col == 7;
+    let (mut on_marks, mut off_marks) = (0, 0);
+    for count in [1, 10, 30, 47] {
+        let rules = rules(6, 8, count).expect("The stencil fits");
+        for seed in 0..100 {
+            let mines = mines(&rules.board(seed));
+            assert_eq!(mines.len(), count, "{count} mines, #{seed}");
+            if count == 10 {
+                for mine in mines {
+                    match marked(mine.row_index, mine.col_index) {
+                        true => on_marks += 1,
+                        false => off_marks += 1,
+                    }
+                }
+            }
+        }
+    }
+
+    // per cell, with 20 marked and 28 not; by chance alone the two would be
+    // about the same
+    let on_rate = on_marks as f64 / 20.0;
+    let off_rate = off_marks as f64 / 28.0;
+    assert!(on_rate > 4.0 * off_rate, "{on_rate} against {off_rate}");
+}
+
+#[test]
+fn the_binary_reads_the_stencil_and_checks_its_size() {
+    let home = Home::new("stencil_size");
+    home.write("frame.txt", FRAME);
+    let path = home.path.join("minesweeper-cli").join("frame.txt");
+    let path = path.to_str().expect("The path should be UTF-8");
+
+    let run = home.run(
+        &["--stencil", path, "--size", "6", "--mines", "10", "--plain"],
+        "",
+    );
+    assert_eq!(
+        run.stderr.trim(),
+        "The stencil is 6x8, but the board is 6x6"
+    );
+    assert_eq!(run.code, Some(2));
+
+    let run = home.run(
+        &[
+            "--stencil",
+            path,
+            "--height",
+            "6",
+            "--width",
+            "8",
+            "--mines",
+            "10",
+            "--plain",
+        ],
+        "",
+    );
+    assert_eq!(run.stderr, "");
+    assert!(run.stdout.contains("Mines: 10"), "{}", run.stdout);
+
+    let missing = home.run(&["--stencil", "/nonexistent/frame.txt", "--plain"], "");
+    assert!(
+        missing
+            .stderr
+            .starts_with("Couldn't read the stencil /nonexistent/frame.txt: "),
+        "{}",
+        missing.stderr
+    );
+    assert_eq!(missing.code, Some(2));
+}
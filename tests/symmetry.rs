@@ -0,0 +1,115 @@
+// symmetric layouts: every mine's image is a mine, and a count is met
+// exactly, whether the board has cells on its axis or not
+mod common;
+
+use common::mines;
+use minesweeper::board::{Board, Layout, Mines, Symmetry};
+use minesweeper::rules::{Ruleset, RulesetError};
+
+const SYMMETRIES: [Symmetry; 3] = [
+    Symmetry::Horizontal,
+    Symmetry::Vertical,
+    Symmetry::Rotational,
+];
+
+// odd and even heights and widths, in each combination
+const SIZES: [(usize, usize); 5] = [(8, 8), (9, 9), (8, 9), (9, 8), (7, 6)];
+
+fn grid(
+    height: usize,
+    width: usize,
+    symmetry: Symmetry,
+    mines_asked: Mines,
+    seed: u64,
+) -> Vec<Vec<bool>> {
+    let layout = Layout {
+        symmetry,
+        ..Layout::new(mines_asked)
+    };
+    let board = Board::generate(seed, height, width, &layout);
+    let mut grid = vec![vec![false; width]; height];
+    for mine in mines(&board) {
+        grid[mine.row_index][mine.col_index] = true;
+    }
+    grid
+}
+
+fn is_symmetric(grid: &[Vec<bool>], symmetry: Symmetry) -> bool {
+    let (height, width) = (grid.len(), grid[0].len());
+    (0..height).all(|row| {
+        (0..width).all(|col| {
+            let (image_row, image_col) = match symmetry {
+                Symmetry::None => (row, col),
+                Symmetry::Horizontal => (row, width - 1 - col),
+                Symmetry::Vertical => (height - 1 - row, col),
+                Symmetry::Rotational => (height - 1 - row, width - 1 - col),
+            };
+            grid[row][col] == grid[image_row][image_col]
+        })
+    })
+}
+
+fn count(grid: &[Vec<bool>]) -> usize {
+    grid.iter().flatten().filter(|&&mine| mine).count()
+}
+
+#[test]
+fn counted_layouts_are_exactly_symmetric_with_exactly_the_count() {
+    for symmetry in SYMMETRIES {
+        for (height, width) in SIZES {
+            // an odd count needs a cell on the axis
+            let counts = match symmetry.has_axis_cells(height, width) {
+                true => [10, 11, 1, 0],
+                false => [10, 12, 2, 0],
+            };
+            for mines_asked in counts {
+                for seed in 0..10 {
+                    let grid = grid(height, width, symmetry, Mines::Count(mines_asked), seed);
+                    let case =
+                        format!("{symmetry:?} {height}x{width}, {mines_asked} mines, #{seed}");
+                    assert!(is_symmetric(&grid, symmetry), "{case}");
+                    assert_eq!(count(&grid), mines_asked, "{case}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn the_axis_cells_are_the_ones_an_odd_count_needs() {
+    assert!(Symmetry::Horizontal.has_axis_cells(8, 9));
+    assert!(!Symmetry::Horizontal.has_axis_cells(9, 8));
+    assert!(Symmetry::Vertical.has_axis_cells(9, 8));
+    assert!(!Symmetry::Vertical.has_axis_cells(8, 9));
+    assert!(Symmetry::Rotational.has_axis_cells(9, 9));
+    assert!(!Symmetry::Rotational.has_axis_cells(9, 8));
+    assert!(!Symmetry::Rotational.has_axis_cells(8, 9));
+
+    // a nearly full board still gets its count
+    let grid = grid(9, 9, Symmetry::Rotational, Mines::Count(79), 3);
+    assert_eq!(count(&grid), 79);
+    assert!(is_symmetric(&grid, Symmetry::Rotational));
+
+    // and without an axis, the rules turn an odd count down
+    let rules = Ruleset::builder()
+        .size(8, 8)
+        .mines(Mines::Count(11))
+        .symmetry(Symmetry::Rotational)
+        .build();
+    assert_eq!(rules, Err(RulesetError::UnevenSymmetry { mines: 11 }));
+}
+
+#[test]
+fn a_density_is_symmetric_too() {
+    for symmetry in SYMMETRIES {
+        for (height, width) in SIZES {
+            for seed in 0..5 {
+                let grid = grid(height, width, symmetry, Mines::Density(0.3), seed);
+                assert!(
+                    is_symmetric(&grid, symmetry),
+                    "{symmetry:?} {height}x{width}"
+                );
+            }
+        }
+    }
+}
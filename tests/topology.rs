@@ -0,0 +1,208 @@
+// the cascade on random maps of mines, with edges and wrapped round: each
+// cell is looked at once, and everything a zero can reach is opened; each
+// case is generated from its own seed, which a failure names
+use minesweeper::board::{Board, CellPosition, Dims};
+use minesweeper::command::{Command, Point};
+use minesweeper::game::{Game, GameEvent};
+use minesweeper::topology::{cascade, Plane, Reach, Topology, Wrapped};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
+
+const CASES: u64 = 300;
+
+fn random_mines(rng: &mut StdRng) -> (Dims, HashSet<CellPosition>) {
+    let dims = Dims {
+        height: rng.gen_range(1..=12),
+        width: rng.gen_range(1..=12),
+    };
+    let density = rng.gen_range(0.0..0.4);
+    let mines = (0..dims.height)
+        .flat_map(|row_index| {
+            (0..dims.width).map(move |col_index| CellPosition {
+                row_index,
+                col_index,
+            })
+        })
+        .filter(|_| rng.gen_bool(density))
+        .collect();
+    (dims, mines)
+}
+
+// what should open: every safe cell joined to `start` through zeros,
+// worked out again and again until nothing more is added
+fn reachable<T: Topology>(
+    topology: &T,
+    start: T::Position,
+    is_mine: impl Fn(T::Position) -> bool,
+) -> HashSet<T::Position> {
+    let is_zero = |at| {
+        topology
+            .neighbours(at)
+            .into_iter()
+            .all(|around| !is_mine(around))
+    };
+    let mut reached = HashSet::from([start]);
+    loop {
+        let more: Vec<T::Position> = reached
+            .iter()
+            .filter(|&&at| is_zero(at))
+            .flat_map(|&at| topology.neighbours(at))
+            .filter(|at| !reached.contains(at))
+            .collect();
+        if more.is_empty() {
+            return reached;
+        }
+        reached.extend(more);
+    }
+}
+
+// cascades over `topology` from a safe cell, counting how often each cell
+// is looked at
+fn open_from<T: Topology>(
+    topology: &T,
+    start: T::Position,
+    is_mine: impl Fn(T::Position) -> bool,
+) -> (Vec<T::Position>, HashMap<T::Position, usize>) {
+    let mut looked_at = HashMap::new();
+    let opened = cascade(topology, start, |at| {
+        *looked_at.entry(at).or_insert(0) += 1;
+        let zero = topology
+            .neighbours(at)
+            .into_iter()
+            .all(|around| !is_mine(around));
+        Ok::<_, Infallible>(match (is_mine(at), zero) {
+            (true, _) => Reach::Stop,
+            (false, true) => Reach::Spread,
+            (false, false) => Reach::Open,
+        })
+    });
+    (opened.unwrap_or_else(|never| match never {}), looked_at)
+}
+
+fn check<T: Topology>(
+    topology: &T,
+    start: T::Position,
+    is_mine: impl Fn(T::Position) -> bool + Copy,
+) -> Result<(), String>
+where
+    T::Position: std::fmt::Debug,
+{
+    let (opened, looked_at) = open_from(topology, start, is_mine);
+    if let Some((at, times)) = looked_at.iter().find(|(_, &times)| times > 1) {
+        return Err(format!("{at:?} was looked at {times} times"));
+    }
+    let unique: HashSet<T::Position> = opened.iter().copied().collect();
+    if unique.len() != opened.len() {
+        return Err(String::from("a cell was opened twice"));
+    }
+    if unique != reachable(topology, start, is_mine) {
+        return Err(format!("opened {unique:?}"));
+    }
+    Ok(())
+}
+
+fn safe_start(rng: &mut StdRng, dims: Dims, mines: &HashSet<CellPosition>) -> CellPosition {
+    loop {
+        let start = CellPosition {
+            row_index: rng.gen_range(0..dims.height),
+            col_index: rng.gen_range(0..dims.width),
+        };
+        if !mines.contains(&start) {
+            return start;
+        }
+    }
+}
+
+#[test]
+fn a_cascade_with_edges_opens_what_its_zeros_reach_once_each() {
+    for seed in 0..CASES {
+        let rng = &mut StdRng::seed_from_u64(seed);
+        let (dims, mines) = random_mines(rng);
+        if mines.len() == dims.height * dims.width {
+            continue;
+        }
+        let start = safe_start(rng, dims, &mines);
+        let result = check(&dims, start, |at| mines.contains(&at));
+        assert_eq!(result, Ok(()), "Seed {seed}");
+    }
+}
+
+// small wrapped boards are where a cell is its own neighbour's neighbour
+// by more than one way round
+#[test]
+fn a_wrapped_cascade_opens_what_its_zeros_reach_once_each() {
+    for seed in 0..CASES {
+        let rng = &mut StdRng::seed_from_u64(seed);
+        let (dims, mines) = random_mines(rng);
+        if mines.len() == dims.height * dims.width {
+            continue;
+        }
+        let start = safe_start(rng, dims, &mines);
+        let result = check(&Wrapped(dims), start, |at| mines.contains(&at));
+        assert_eq!(result, Ok(()), "Seed {seed}");
+    }
+}
+
+// a wall of mines around the start, so that the plane's cascade ends
+#[test]
+fn a_cascade_on_the_plane_stops_at_the_mines_around_it() {
+    for seed in 0..CASES {
+        let rng = &mut StdRng::seed_from_u64(seed);
+        let radius = rng.gen_range(2..8);
+        let holes: HashSet<Point> = (0..rng.gen_range(0..6))
+            .map(|_| Point {
+                row: rng.gen_range(-radius + 1..radius),
+                col: rng.gen_range(-radius + 1..radius),
+            })
+            .filter(|&hole| hole != Point { row: 0, col: 0 })
+            .collect();
+        let is_mine = |at: Point| at.row.abs().max(at.col.abs()) == radius || holes.contains(&at);
+        let result = check(&Plane, Point { row: 0, col: 0 }, is_mine);
+        assert_eq!(result, Ok(()), "Seed {seed}");
+    }
+}
+
+// and a board's own clear opens what the cascade says it should
+#[test]
+fn a_clear_opens_the_cells_the_cascade_reaches() {
+    for seed in 0..CASES {
+        let rng = &mut StdRng::seed_from_u64(seed);
+        let (dims, mines) = random_mines(rng);
+        if mines.len() == dims.height * dims.width {
+            continue;
+        }
+        let start = safe_start(rng, dims, &mines);
+        let board = Board::from_bombs(
+            (0..dims.height)
+                .map(|row_index| {
+                    (0..dims.width)
+                        .map(|col_index| {
+                            mines.contains(&CellPosition {
+                                row_index,
+                                col_index,
+                            })
+                        })
+                        .collect()
+                })
+                .collect(),
+        );
+        let mut game = Game::new(board, 1);
+        game.apply(0, Command::Clear(start))
+            .expect("The start should be safe");
+        let opened: HashSet<CellPosition> = game
+            .events()
+            .iter()
+            .find_map(|event| match event {
+                GameEvent::CellsOpened(cells) => Some(cells.iter().copied().collect()),
+                _ => None,
+            })
+            .expect("The clear should open cells");
+        assert_eq!(
+            opened,
+            reachable(&dims, start, |at| mines.contains(&at)),
+            "Seed {seed}"
+        );
+    }
+}
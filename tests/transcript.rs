@@ -0,0 +1,24 @@
+// a transcript exported once a co-op game ended, without having been asked
+// for before: the board after each move, rebuilt from the move log
+mod common;
+
+use common::{assert_snapshot, redact_times, Home};
+
+#[test]
+fn a_finished_game_exports_its_transcript() {
+    let home = Home::new("transcript");
+    let path = home.path.join("game.txt");
+    let input = format!(
+        "c 2 7\nf 0 0\nc 1 1\nc 1 5\nc 3 4\nexport transcript {}\n\n",
+        path.display()
+    );
+    let args = ["coop", "--seed", "42", "--preset", "beginner", "--plain"];
+    let run = home.run(&args, &input);
+
+    assert_eq!(run.code, Some(1), "A lost game should exit with 1");
+    assert!(run
+        .stdout
+        .contains(&format!("Transcript written to {}", path.display())));
+    let transcript = std::fs::read_to_string(path).expect("The transcript should be written");
+    assert_snapshot("transcript", &redact_times(&transcript));
+}
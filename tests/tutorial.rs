@@ -0,0 +1,58 @@
+// the tutorial through the binary, up to the free play board at its end,
+// which is random and left unplayed
+mod common;
+
+use common::{Home, Run};
+
+const LESSON: [&str; 7] = ["c 2 2", "c 0 0", "f 3 3", "f 3 4", "f 4 3", "c 4 4", ""];
+
+const NOT_QUITE: &str = "That's not quite it. ";
+
+fn tutorial(name: &str, input: &str) -> Run {
+    Home::new(name).run(&["tutorial", "--plain"], input)
+}
+
+#[test]
+fn the_expected_inputs_go_through_to_free_play() {
+    let run = tutorial("tutorial_exact", &LESSON.join("\n"));
+
+    assert!(!run.stdout.contains(NOT_QUITE), "{}", run.stdout);
+    // the board as the last step left it
+    assert!(run
+        .stdout
+        .contains("0 0 1 2 2\n0 0 2 ^ ^\n0 0 2 ^ 3\n------\nWell done"));
+    assert!(run.stdout.contains("Now try a small board on your own"));
+    assert_eq!(run.stdout.lines().last(), Some("Tutorial stopped"));
+    assert_eq!(run.code, Some(0));
+}
+
+#[test]
+fn a_wrong_input_repeats_the_instruction_and_isn_t_played() {
+    // a mine, another cell, a typo and a flag in place of the clear
+    let wrong = ["c 3 3", "c 1 1", "hello", "f 0 0"];
+    let input = [&LESSON[..1], &wrong, &LESSON[1..]].concat().join("\n");
+    let run = tutorial("tutorial_wrong", &input);
+
+    let instruction = "The top-left corner is far from the 1. Clear it with: c 0 0";
+    let reprompt = format!("{NOT_QUITE}{instruction}");
+    assert_eq!(run.stdout.matches(&reprompt).count(), wrong.len());
+    assert_eq!(run.stdout.matches(instruction).count(), wrong.len() + 1);
+    // the mine wasn't cleared, nor the corner flagged
+    assert!(!run
+        .stdout
+        .lines()
+        .any(|line| line.contains('*') || line.starts_with('^')));
+    assert!(run
+        .stdout
+        .contains("0 0 0 0 0\n0 0 0 0 0\n0 0 1 2 2\n0 0 2 # #"));
+    assert!(run.stdout.contains("Now try a small board on your own"));
+}
+
+#[test]
+fn the_end_of_input_stops_the_lesson() {
+    let run = tutorial("tutorial_stopped", "c 2 2\nc 4 4\n");
+
+    assert_eq!(run.stdout.matches(NOT_QUITE).count(), 1);
+    assert!(!run.stdout.contains("Now try a small board on your own"));
+    assert_eq!(run.stdout.lines().last(), Some("Tutorial stopped"));
+}
@@ -0,0 +1,94 @@
+// what goes out of a game: every way a view of it is written, checked for
+// the mines the player hasn't found
+mod common;
+
+use common::{at, game_on};
+use minesweeper::board::{Board, CellPosition};
+use minesweeper::command::Command;
+use minesweeper::game::{Game, GameState};
+use minesweeper::view::{BoardView, CellView, GameView};
+
+// a blank corner that opens together, and numbers around the mines
+const LAYOUT: &str = "....*\n....*\n..*..\n.....\n*....";
+
+// everything a game is written out as, to clients, spectators and the page
+fn written(game: &Game) -> Vec<String> {
+    let view = GameView::of(game);
+    let mut written = vec![
+        view.board_json().to_string(),
+        view.counters_json().to_string(),
+        view.events_json().to_string(),
+        BoardView::of(game.board()).to_string(),
+    ];
+    written.extend(view.changes_json().map(|changes| changes.to_string()));
+    written
+}
+
+// no cell named and nothing but closed on the board
+fn assert_hidden(game: &Game, mine: CellPosition) {
+    let (row, col) = (mine.row_index, mine.col_index);
+    for out in written(game) {
+        assert!(
+            !out.contains(&format!("{{\"row\":{row},\"col\":{col}}}")),
+            "{out}"
+        );
+        assert!(!out.contains(&format!("[{row},{col},")), "{out}");
+    }
+    let view = GameView::of(game);
+    assert_eq!(view.board().get(mine), Some(CellView::Closed));
+    let line = &view.board().lines()[row];
+    assert_eq!(line.split(' ').nth(col), Some("#"), "{line}");
+}
+
+#[test]
+fn mines_the_player_hasnt_found_are_never_written_out() {
+    let board: Board = LAYOUT.parse().expect("The layout should parse");
+    let mut game = Game::new(board, 2).with_peeks(1);
+    let hidden = [at(1, 4), at(4, 0)];
+
+    let moves = [
+        Command::Clear(at(0, 0)),
+        Command::Flag(at(0, 4)),
+        Command::Question(at(3, 4)),
+        Command::Peek(at(4, 4)),
+        // a mine set off is there for anyone to see
+        Command::Clear(at(2, 2)),
+        Command::Undo,
+        Command::Clear(at(3, 3)),
+    ];
+    for command in moves {
+        game.apply(0, command).expect("The move should be allowed");
+        for mine in hidden {
+            assert_hidden(&game, mine);
+        }
+    }
+
+    // and not even once the game is lost
+    game.apply(0, Command::Clear(at(2, 2)))
+        .expect("The mine should go off");
+    game.apply(0, Command::Flag(at(0, 4)))
+        .expect("The flag should come off");
+    game.apply(0, Command::Clear(at(0, 4)))
+        .expect("The mine should go off");
+    assert_eq!(game.state(), GameState::Lost);
+    for mine in hidden {
+        assert_hidden(&game, mine);
+    }
+}
+
+#[test]
+fn a_view_keeps_what_was_shown_when_it_was_made() {
+    let mut game = game_on(LAYOUT);
+    game.apply(0, Command::Clear(at(0, 0)))
+        .expect("The corner should be safe");
+    let view = GameView::of(&game);
+
+    assert_eq!(
+        game.apply(0, Command::Clear(at(4, 0))).ok(),
+        Some(GameState::Lost)
+    );
+    assert_eq!(view.state(), GameState::Playing);
+    assert_eq!(view.board().get(at(4, 0)), Some(CellView::Closed));
+    assert_eq!(view.counters().open, game.board().count_open());
+    assert_eq!((view.board().height(), view.board().width()), (5, 5));
+}
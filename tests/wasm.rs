@@ -0,0 +1,40 @@
+// the module's exports, driven as a page would: through their buffers, by
+// pointer
+use minesweeper::json::{self, Value};
+use minesweeper::wasm::{wasm_apply, wasm_input, wasm_new_game, wasm_output};
+
+fn send(command: &str) -> String {
+    let input = wasm_input(command.len());
+    // SAFETY: wasm_input gave room for exactly this many bytes
+    unsafe { std::ptr::copy_nonoverlapping(command.as_ptr(), input, command.len()) };
+    let len = wasm_apply(command.len());
+    // SAFETY: the answer is `len` bytes at wasm_output until the next command
+    let output = unsafe { std::slice::from_raw_parts(wasm_output(), len) };
+    String::from_utf8(output.to_vec()).expect("The answer should be UTF-8")
+}
+
+fn field(answer: &str, name: &str) -> Option<Value> {
+    json::parse(answer)
+        .expect("The answer should be JSON")
+        .get(name)
+        .cloned()
+}
+
+#[test]
+fn the_exports_play_a_game_through_their_buffers() {
+    assert!(send(r#"{"command": "c 4 4"}"#).contains("no game has been started"));
+
+    wasm_new_game(42);
+    let answer = send(r#"{"command": "c 4 4", "now_ms": 0}"#);
+    assert_eq!(field(&answer, "state"), Some(Value::from("playing")));
+    let answer = send(r#"{"command": "f 0 0", "now_ms": 1500}"#);
+    assert_eq!(
+        field(&answer, "elapsed_ms").and_then(|v| v.as_u64()),
+        Some(1500)
+    );
+    assert_eq!(
+        field(&answer, "mines_left").and_then(|v| v.as_u64()),
+        Some(9)
+    );
+    assert!(field(&send("nonsense"), "error").is_some());
+}
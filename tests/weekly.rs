@@ -0,0 +1,82 @@
+// the weekly challenge's twist, picked by the ISO week, and the rules and
+// board each week is played by
+use minesweeper::board::{MineDistribution, Mines, Symmetry};
+use minesweeper::calendar::{civil_from_days, days_from_civil, iso_week};
+use minesweeper::dump::debug_dump;
+use minesweeper::game::WinCondition;
+use minesweeper::weekly::{modifier, new_game, seed, tag, MODIFIERS};
+
+#[test]
+fn days_and_dates_convert_both_ways() {
+    assert_eq!(days_from_civil(1970, 1, 1), 0);
+    assert_eq!(days_from_civil(2026, 10, 14), 20_740);
+    for days in [-800_000, -1, 0, 59, 11_016, 20_740, 1_000_000] {
+        let (year, month, day) = civil_from_days(days);
+        assert_eq!(days_from_civil(year, month, day), days, "{days}");
+    }
+}
+
+// around new year the week can belong to the year before or after
+#[test]
+fn a_day_falls_in_its_iso_week() {
+    let week_of = |year, month, day| iso_week(days_from_civil(year, month, day));
+    assert_eq!(week_of(1970, 1, 1), (1970, 1));
+    assert_eq!(week_of(2026, 10, 14), (2026, 42));
+    assert_eq!(week_of(2021, 1, 3), (2020, 53));
+    assert_eq!(week_of(2021, 1, 4), (2021, 1));
+    assert_eq!(week_of(2024, 12, 30), (2025, 1));
+    assert_eq!(week_of(2026, 12, 31), (2026, 53));
+    assert_eq!(week_of(2027, 1, 1), (2026, 53));
+}
+
+#[test]
+fn each_week_has_its_twist_from_the_table() {
+    let names: Vec<&str> = (1..=10).map(|week| modifier(week).name).collect();
+    let table = [
+        "lives", "rocks", "mirror", "clusters", "moving", "flags", "sparse", "peeks",
+    ];
+    assert_eq!(names[..8], table);
+    assert_eq!(names[8..], ["lives", "rocks"]);
+    assert_eq!(modifier(42).name, "rocks");
+    assert_eq!(modifier(53).name, "moving");
+    assert_eq!(tag(2026, 7), "2026-W07");
+}
+
+#[test]
+fn every_twist_has_rules_that_build() {
+    for modifier in &MODIFIERS {
+        let rules = modifier.rules();
+        assert!(rules.height() * rules.width() > 0, "{}", modifier.name);
+    }
+
+    let lives = modifier(1).rules();
+    assert_eq!((lives.height(), lives.width(), lives.lives()), (16, 16, 2));
+    assert_eq!(lives.layout().mines, Mines::Density(99.0 / 480.0));
+    assert_eq!(modifier(2).rules().layout().rocks, 12);
+    assert_eq!(modifier(3).rules().layout().symmetry, Symmetry::Horizontal);
+    assert_eq!(
+        modifier(4).rules().layout().distribution,
+        MineDistribution::Clustered
+    );
+    assert!(modifier(5).rules().moving_mines(0).is_some());
+    assert_eq!(modifier(6).rules().win(), WinCondition::FlagAll);
+    assert_eq!(
+        modifier(7).rules().layout().distribution,
+        MineDistribution::Sparse
+    );
+    let peeks = modifier(8).rules();
+    assert_eq!((peeks.peeks(), peeks.shields()), (3, true));
+}
+
+// the same board for everyone that week, and another the next
+#[test]
+fn a_week_is_played_by_its_twist_on_its_own_board() {
+    let board = |year, week| debug_dump(&new_game(year, week), "weekly");
+    assert_eq!(new_game(2026, 42).ruleset().key(), "rocks=12");
+    assert_eq!(board(2026, 42), board(2026, 42));
+    assert_ne!(board(2026, 42), board(2026, 50));
+    assert_ne!(seed(2026, 42), seed(2027, 42));
+
+    assert_eq!(new_game(2026, 1).ruleset().key(), "lives=2");
+    assert_eq!(new_game(2026, 6).win_condition(), WinCondition::FlagAll);
+}
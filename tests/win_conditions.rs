@@ -0,0 +1,73 @@
+// both ways of winning, played on the same board made by hand
+mod common;
+
+use common::{at, game_on};
+use minesweeper::command::Command;
+use minesweeper::game::{Game, GameEvent, GameOutcome, GameState, WinCondition};
+
+// two mines in the top corners, and everything else safe
+const LAYOUT: &str = "*...*\n.....\n.....";
+
+fn game(win_condition: WinCondition) -> Game {
+    game_on(LAYOUT).with_win_condition(win_condition)
+}
+
+fn play(game: &mut Game, command: Command) -> GameState {
+    game.apply(0, command).expect("The move should be allowed")
+}
+
+#[test]
+fn flagging_every_mine_wins_as_soon_as_the_last_flag_is_placed() {
+    let mut game = game(WinCondition::FlagAll);
+
+    assert_eq!(play(&mut game, Command::Flag(at(0, 0))), GameState::Playing);
+    assert_eq!(play(&mut game, Command::Flag(at(0, 4))), GameState::Won);
+    assert!(game.events().contains(&GameEvent::GameWon));
+    assert_eq!(game.board().count_open(), 0);
+
+    let Some(GameOutcome::Won(stats)) = game.outcome() else {
+        panic!("The game should have been won");
+    };
+    assert_eq!(stats.won_by, Some(WinCondition::FlagAll));
+    assert_eq!(
+        game.outcome().map(|outcome| outcome.to_string()),
+        Some(String::from("Game won: every mine flagged"))
+    );
+}
+
+#[test]
+fn a_wrong_flag_keeps_a_flag_win_from_counting() {
+    let mut game = game(WinCondition::FlagAll);
+
+    play(&mut game, Command::Flag(at(0, 0)));
+    play(&mut game, Command::Flag(at(1, 1)));
+    assert_eq!(play(&mut game, Command::Flag(at(0, 4))), GameState::Playing);
+
+    // taking the wrong flag off leaves only the right ones
+    assert_eq!(play(&mut game, Command::Flag(at(1, 1))), GameState::Won);
+}
+
+#[test]
+fn flags_alone_dont_win_by_the_usual_rules() {
+    let mut game = game(WinCondition::OpenAll);
+
+    play(&mut game, Command::Flag(at(0, 0)));
+    assert_eq!(play(&mut game, Command::Flag(at(0, 4))), GameState::Playing);
+
+    assert_eq!(play(&mut game, Command::Clear(at(2, 0))), GameState::Won);
+    let Some(GameOutcome::Won(stats)) = game.outcome() else {
+        panic!("The game should have been won");
+    };
+    assert_eq!(stats.won_by, None);
+}
+
+#[test]
+fn opening_every_safe_cell_still_wins_a_flag_game() {
+    let mut game = game(WinCondition::FlagAll);
+
+    assert_eq!(play(&mut game, Command::Clear(at(2, 0))), GameState::Won);
+    assert_eq!(
+        game.outcome().map(|outcome| outcome.to_string()),
+        Some(String::from("Game won: every safe cell open"))
+    );
+}
@@ -0,0 +1,93 @@
+// a move that wins says so itself, whichever kind of move it is, and the
+// game loop stops on it without asking for another
+mod common;
+
+use common::{at, game_on, Home};
+use minesweeper::command::Command;
+use minesweeper::game::{Game, GameEvent, GameState};
+
+// one mine, in the corner, with a 1 beside it
+const LAYOUT: &str = "*..\n...";
+
+fn play(game: &mut Game, command: Command) -> GameState {
+    game.apply(0, command).expect("The move should be allowed")
+}
+
+#[test]
+fn the_last_clear_returns_won() {
+    let mut game = game_on(LAYOUT);
+
+    assert_eq!(
+        play(&mut game, Command::Clear(at(0, 1))),
+        GameState::Playing
+    );
+    assert_eq!(
+        play(&mut game, Command::Clear(at(1, 0))),
+        GameState::Playing
+    );
+    assert_eq!(
+        play(&mut game, Command::Clear(at(1, 1))),
+        GameState::Playing
+    );
+    // the cascade from here opens the rest
+    assert_eq!(play(&mut game, Command::Clear(at(0, 2))), GameState::Won);
+    assert_eq!(game.state(), GameState::Won);
+    let won = game.events().iter().filter(|e| **e == GameEvent::GameWon);
+    assert_eq!(won.count(), 1);
+}
+
+#[test]
+fn a_chord_that_opens_the_rest_returns_won() {
+    let mut game = game_on("*.\n..\n*.");
+
+    game.apply(0, Command::Clear(at(1, 0)))
+        .expect("The cell should open");
+    game.apply(0, Command::Flag(at(0, 0)))
+        .expect("The flag should go on");
+    game.apply(0, Command::Flag(at(2, 0)))
+        .expect("The flag should go on");
+    // (1,0) is a 2 with both of its mines flagged
+    assert_eq!(play(&mut game, Command::Chord(at(1, 0))), GameState::Won);
+}
+
+#[test]
+fn a_flag_after_the_win_doesn_t_undo_it() {
+    let mut game = game_on(LAYOUT);
+
+    play(&mut game, Command::Clear(at(1, 0)));
+    assert_eq!(play(&mut game, Command::Clear(at(1, 2))), GameState::Won);
+    assert!(game.apply(0, Command::Flag(at(0, 0))).is_err());
+    assert_eq!(game.state(), GameState::Won);
+}
+
+#[test]
+fn the_binary_stops_on_the_winning_move() {
+    let home = Home::new("winning_move");
+    let args = ["--seed", "42", "--preset", "beginner", "--plain"];
+    let layout = [
+        "*........",
+        ".*...*...",
+        ".........",
+        "....*....",
+        "..**.....",
+        "..*......",
+        ".........",
+        "*..*.....",
+        "..*......",
+    ];
+    let mut script = String::new();
+    for (row, cells) in layout.iter().enumerate() {
+        for (col, _) in cells.match_indices('.') {
+            script += &format!("c {row} {col}\n");
+        }
+    }
+    let run = home.run(&args, &script);
+
+    let after_win = run
+        .stdout
+        .split_once("Game won")
+        .map(|(_, after)| after)
+        .expect("The game should be won");
+    assert!(!after_win.contains("Enter row index"), "{after_win}");
+    assert_eq!(run.code, Some(0));
+}